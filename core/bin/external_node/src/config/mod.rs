@@ -1,17 +1,37 @@
-use std::{env, time::Duration};
+use std::{
+    collections::HashMap,
+    env, fmt, fs,
+    num::{NonZeroU32, NonZeroUsize},
+    ops,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
 
 use anyhow::Context;
-use serde::Deserialize;
+use prometheus_exporter::PrometheusExporterConfig;
+use serde::{de::DeserializeOwned, Deserialize};
+use tokio::sync::watch;
 use url::Url;
 use zksync_basic_types::{Address, L1ChainId, L2ChainId};
-use zksync_config::{configs::chain::L1BatchCommitDataGeneratorMode, ObjectStoreConfig};
+use zksync_concurrency::time;
+use zksync_config::{
+    configs::{
+        chain::{L1BatchCommitDataGeneratorMode, StateKeeperConfig},
+        database::{MerkleTreeMode, RocksdbCompactionStyle},
+    },
+    ObjectStoreConfig, SnapshotsCreatorConfig,
+};
 use zksync_core::{
     api_server::{
         tx_sender::TxSenderConfig,
         web3::{state::InternalApiConfig, Namespace},
     },
     consensus,
+    state_keeper::StateKeeperRocksdbOptions,
+    sync_layer::{MainNodeClientConfig, MainNodeRetryConfig},
     temp_config_store::decode_yaml,
+    utils::clamp_polling_interval,
 };
 use zksync_types::{api::BridgeAddresses, fee_model::FeeParams};
 use zksync_web3_decl::{
@@ -20,6 +40,8 @@ use zksync_web3_decl::{
     namespaces::{EthNamespaceClient, ZksNamespaceClient},
 };
 
+use crate::helpers::RpcRetryPolicy;
+
 pub(crate) mod observability;
 #[cfg(test)]
 mod tests;
@@ -42,6 +64,22 @@ pub struct RemoteENConfig {
 }
 
 impl RemoteENConfig {
+    #[cfg(test)]
+    pub(crate) fn mock() -> Self {
+        Self {
+            bridgehub_proxy_addr: None,
+            diamond_proxy_addr: Address::repeat_byte(1),
+            l1_erc20_bridge_proxy_addr: Address::repeat_byte(2),
+            l2_erc20_bridge_addr: Address::repeat_byte(3),
+            l1_weth_bridge_proxy_addr: None,
+            l2_weth_bridge_addr: None,
+            l2_testnet_paymaster_addr: None,
+            l2_chain_id: L2ChainId::from(270),
+            l1_chain_id: L1ChainId(9),
+            max_pubdata_per_batch: 100_000,
+        }
+    }
+
     pub async fn fetch(client: &HttpClient) -> anyhow::Result<Self> {
         let bridges = client
             .get_bridge_contracts()
@@ -89,6 +127,30 @@ impl RemoteENConfig {
             max_pubdata_per_batch,
         })
     }
+
+    /// Checks that the chain IDs configured for the node match the ones reported by the main node.
+    /// A mismatch means that the node is connected to a wrong network.
+    pub fn check_chain_ids(
+        &self,
+        l2_chain_id: L2ChainId,
+        l1_chain_id: L1ChainId,
+    ) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            l2_chain_id == self.l2_chain_id,
+            "Configured L2 chain ID doesn't match the one from the main node. Make sure your configuration is correct \
+             and you are connected to the right main node. Main node L2 chain ID: {:?}, local config value: {:?}",
+            self.l2_chain_id,
+            l2_chain_id
+        );
+        anyhow::ensure!(
+            l1_chain_id == self.l1_chain_id,
+            "Configured L1 chain ID doesn't match the one from the main node. Make sure your configuration is correct \
+             and you are connected to the right main node. Main node L1 chain ID: {}, local config value: {}",
+            self.l1_chain_id.0,
+            l1_chain_id.0
+        );
+        Ok(())
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -97,6 +159,18 @@ pub enum BlockFetcher {
     Consensus,
 }
 
+/// Node behavior when a reorg (i.e., a divergence of the local storage with the main node) is detected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReorgHandlingMode {
+    /// Roll back the node storage to the last correct L1 batch on node startup.
+    #[default]
+    Rollback,
+    /// Only detect reorgs: mark the reorg detector as failed and exit without touching the node storage.
+    /// The operator is expected to roll back the storage manually using the block reverter CLI.
+    Observe,
+}
+
 /// This part of the external node config is completely optional to provide.
 /// It can tweak limits of the API, delay intervals of certain components, etc.
 /// If any of the fields are not provided, the default values will be used.
@@ -106,6 +180,9 @@ pub struct OptionalENConfig {
     /// Max possible limit of filters to be in the API state at once.
     #[serde(default = "OptionalENConfig::default_filters_limit")]
     pub filters_limit: usize,
+    /// Max possible limit of filters installed by a single WebSocket connection. If not set, a single connection
+    /// may install filters up to `filters_limit`.
+    pub filters_limit_per_connection: Option<usize>,
     /// Max possible limit of subscriptions to be in the API state at once.
     #[serde(default = "OptionalENConfig::default_subscriptions_limit")]
     pub subscriptions_limit: usize,
@@ -126,9 +203,14 @@ pub struct OptionalENConfig {
     /// Maximum number of requests in a single batch JSON RPC request. Default is 500.
     #[serde(default = "OptionalENConfig::default_max_batch_request_size")]
     pub max_batch_request_size: usize,
-    /// Maximum response body size in MiBs. Default is 10 MiB.
+    /// Maximum response body size in MiBs. Applies both to responses of the node API servers and to (decompressed)
+    /// responses received from the main node. Default is 10 MiB.
     #[serde(default = "OptionalENConfig::default_max_response_body_size_mb")]
     pub max_response_body_size_mb: usize,
+    /// Maximum request body size in MiBs. Requests exceeding this limit are rejected before they are parsed.
+    /// Default is 10 MiB.
+    #[serde(default = "OptionalENConfig::default_max_request_body_size_mb")]
+    pub max_request_body_size_mb: usize,
 
     // Other API config settings
     /// Interval between polling DB for pubsub (in ms).
@@ -144,6 +226,10 @@ pub struct OptionalENConfig {
     /// This option can be tweaked down if the API server is running out of memory.
     #[serde(default = "OptionalENConfig::default_vm_concurrency_limit")]
     pub vm_concurrency_limit: usize,
+    /// Max number of concurrent VM executions for tracing calls (e.g., `debug_traceCall`), which are much more expensive
+    /// than ordinary calls. These executions also count towards `vm_concurrency_limit`. If not specified, tracing calls
+    /// are only limited by `vm_concurrency_limit`.
+    pub trace_call_concurrency_limit: Option<NonZeroUsize>,
     /// Smart contract bytecode cache size for the API server. Default value is 128 MiB.
     #[serde(default = "OptionalENConfig::default_factory_deps_cache_size_mb")]
     factory_deps_cache_size_mb: usize,
@@ -166,6 +252,12 @@ pub struct OptionalENConfig {
     /// different node.
     #[serde(default)]
     pub filters_disabled: bool,
+    /// Fraction of transactions (from 0.0 to 1.0) for which call traces are saved if the `debug` namespace is enabled.
+    /// Transactions are sampled deterministically based on their hash. `debug_traceTransaction` re-executes
+    /// transactions that were not sampled in the VM sandbox, and `debug_traceBlock*` methods skip such transactions.
+    /// Default is 1.0 (all transactions are traced).
+    #[serde(default = "OptionalENConfig::default_call_traces_sampling_rate")]
+    call_traces_sampling_rate: f64,
 
     // Health checks
     /// Time limit in milliseconds to mark a health check as slow and log the corresponding warning.
@@ -174,6 +266,16 @@ pub struct OptionalENConfig {
     /// Time limit in milliseconds to abort a health check and return "not ready" status for the corresponding component.
     /// If not specified, the default value in the health check crate will be used.
     healthcheck_hard_time_limit_ms: Option<u64>,
+    /// HTTP status code returned by the healthcheck endpoint while the node is initializing, i.e., while some
+    /// of its components haven't completed their first successful operation yet. Must not be a 2xx code,
+    /// so that load balancers don't route traffic to the node prematurely. Default is 503 (Service Unavailable).
+    #[serde(default = "OptionalENConfig::default_healthcheck_initializing_status_code")]
+    pub healthcheck_initializing_status_code: u16,
+    /// Names of health check components (e.g., `consistency_checker`) excluded from the aggregated node health.
+    /// The status of excluded components is still reported by the healthcheck endpoint. The names are validated
+    /// against the components registered by the node.
+    #[serde(default)]
+    pub healthcheck_excluded_components: Vec<String>,
 
     // Gas estimation config
     /// The factor by which to scale the gasLimit
@@ -199,12 +301,21 @@ pub struct OptionalENConfig {
     // Merkle tree config
     #[serde(default = "OptionalENConfig::default_metadata_calculator_delay")]
     metadata_calculator_delay: u64,
+    /// Whether to adapt the delay between Merkle tree updates to the node sync lag. If enabled, the tree runs
+    /// without delays while the node is catching up with the main node, and uses `metadata_calculator_delay`
+    /// once the node is caught up. Disabled by default.
+    #[serde(default)]
+    pub metadata_calculator_adaptive_delay: bool,
     /// Maximum number of L1 batches to be processed by the Merkle tree at a time.
     #[serde(
         alias = "max_blocks_per_tree_batch",
         default = "OptionalENConfig::default_max_l1_batches_per_tree_iter"
     )]
     pub max_l1_batches_per_tree_iter: usize,
+    /// Mode of the Merkle tree. In the `full` mode, the tree API (e.g., `zks_getProof`) can serve Merkle proofs;
+    /// in the default `lightweight` mode, proofs are not available.
+    #[serde(default = "OptionalENConfig::default_merkle_tree_mode")]
+    pub merkle_tree_mode: MerkleTreeMode,
     /// Chunk size for multi-get operations. Can speed up loading data for the Merkle tree on some environments,
     /// but the effects vary wildly depending on the setup (e.g., the filesystem used).
     #[serde(default = "OptionalENConfig::default_merkle_tree_multi_get_chunk_size")]
@@ -220,6 +331,25 @@ pub struct OptionalENConfig {
     /// Timeout to wait for the Merkle tree database to run compaction on stalled writes.
     #[serde(default = "OptionalENConfig::default_merkle_tree_stalled_writes_timeout_sec")]
     merkle_tree_stalled_writes_timeout_sec: u64,
+    /// Lag of the full Merkle tree (in L1 batches) relative to Postgres after which the tree health is considered
+    /// affected since the tree cannot serve proofs for recent L1 batches. Not applied while the node is catching up
+    /// with the main node.
+    #[serde(default = "OptionalENConfig::default_merkle_tree_max_l1_batch_lag_for_proofs")]
+    pub merkle_tree_max_l1_batch_lag_for_proofs: u32,
+    /// Interval in L1 batches between Merkle tree checkpoints. Checkpoints are stored next to the tree
+    /// and speed up rebuilding the tree with `--rebuild-tree`. If not set, checkpoints are not created.
+    pub merkle_tree_checkpoint_interval: Option<NonZeroU32>,
+    /// If set, the commitment generator checks the input of each L1 batch commitment against data produced
+    /// by the VM when executing the batch (e.g., the state diffs hash published by the bootloader) before persisting
+    /// the commitment, halting the node on a mismatch. This allows catching commitment generation bugs independently
+    /// of L1, but increases CPU usage of the generator. Disabled by default.
+    #[serde(default)]
+    pub commitment_generator_local_verification: bool,
+    /// Whether to run the background migration of fee addresses from L1 batches to miniblocks. The migration
+    /// finishes on its own; it can be disabled once it has completed, in which case the node checks on startup
+    /// that the storage is fully migrated and refuses to start otherwise. Enabled by default.
+    #[serde(default = "OptionalENConfig::default_fee_address_migration_enabled")]
+    pub fee_address_migration_enabled: bool,
 
     // Postgres config (new parameters)
     /// Threshold in milliseconds for the DB connection lifetime to denote it as long-living and log its details.
@@ -228,16 +358,171 @@ pub struct OptionalENConfig {
     database_slow_query_threshold_ms: Option<u64>,
 
     // Other config settings
-    /// Port on which the Prometheus exporter server is listening.
+    /// Port on which the Prometheus exporter server is listening. Mutually exclusive with `prometheus_pushgateway_url`.
     pub prometheus_port: Option<u16>,
+    /// Full URL of the Prometheus push gateway endpoint (including the job path) to push metrics to. If set,
+    /// metrics are pushed instead of being served on `prometheus_port`, which is useful if the node cannot expose
+    /// a scrape port.
+    prometheus_pushgateway_url: Option<String>,
+    /// Interval in milliseconds between pushing metrics to the Prometheus push gateway. Setting this value
+    /// enables push mode and requires `prometheus_pushgateway_url` to be set. If not specified, metrics
+    /// are pushed every 10 seconds.
+    prometheus_push_interval_ms: Option<u64>,
     /// Number of keys that is processed by enum_index migration in State Keeper each L1 batch.
+    /// Must be positive and not exceed 100,000; larger chunks lead to long-running Postgres queries and RocksDB writes.
     #[serde(default = "OptionalENConfig::default_enum_index_migration_chunk_size")]
-    pub enum_index_migration_chunk_size: usize,
+    enum_index_migration_chunk_size: usize,
     /// Capacity of the queue for asynchronous miniblock sealing. Once this many miniblocks are queued,
     /// sealing will block until some of the miniblocks from the queue are processed.
     /// 0 means that sealing is synchronous; this is mostly useful for performance comparison, testing etc.
     #[serde(default = "OptionalENConfig::default_miniblock_seal_queue_capacity")]
     pub miniblock_seal_queue_capacity: usize,
+    /// Interval in seconds between manual compactions of the state keeper RocksDB. Compaction removes tombstones
+    /// accumulated in the DB, which can degrade read performance over time. Compaction is skipped if the miniblock
+    /// seal queue is not empty. If not specified, manual compaction is disabled.
+    state_keeper_db_compaction_interval_sec: Option<u64>,
+    /// Size of a single write buffer (memtable) of the state keeper RocksDB in megabytes. Larger buffers
+    /// reduce write amplification at the cost of RAM usage. If not specified, the RocksDB default is used.
+    state_keeper_db_write_buffer_size_mb: Option<usize>,
+    /// Maximum number of concurrent background jobs (flushes and compactions) for the state keeper RocksDB.
+    /// If not specified, the RocksDB default is used.
+    pub state_keeper_db_max_background_jobs: Option<NonZeroU32>,
+    /// Compaction style for the state keeper RocksDB (`level` or `universal`). If not specified,
+    /// the RocksDB default (level compaction) is used.
+    pub state_keeper_db_compaction_style: Option<RocksdbCompactionStyle>,
+    /// If set, the state keeper reads storage directly from Postgres instead of caching it in RocksDB
+    /// at `state_cache_path`. This is significantly slower, but removes the need to maintain RocksDB; can be useful
+    /// for small or ephemeral nodes (e.g., in CI). Disabled by default.
+    #[serde(default)]
+    pub state_keeper_db_disabled: bool,
+    /// Total memory budget in megabytes shared by the Merkle tree and state keeper RocksDB instances. If set,
+    /// the instances use a shared block cache instead of individually sized ones, and their memtables are capped,
+    /// so that total RocksDB memory usage is bounded regardless of per-instance settings. If not specified,
+    /// memory usage is determined by per-instance settings only.
+    rocksdb_memory_budget_mb: Option<usize>,
+    /// Whether to collect RocksDB statistics (e.g., IO metrics) for the Merkle tree and state keeper RocksDB
+    /// instances. Statistics have a non-negligible performance overhead, so they are disabled by default.
+    #[serde(default)]
+    pub rocksdb_statistics_enabled: bool,
+    /// Whether to periodically scrape Postgres metrics (e.g., table sizes). On large databases, the scraping query
+    /// itself may be expensive, so it may make sense to disable scraping. Enabled by default.
+    #[serde(default = "OptionalENConfig::default_postgres_metrics_scraping_enabled")]
+    postgres_metrics_scraping_enabled: bool,
+    /// Interval in seconds between Postgres metrics scrapes. Must be positive if scraping is enabled. Default is 60 seconds.
+    #[serde(default = "OptionalENConfig::default_postgres_metrics_scraping_interval_sec")]
+    postgres_metrics_scraping_interval_sec: u64,
+    /// Maximum number of L1 batches for which the batch status updater fetches details from the main node concurrently
+    /// when catching up (e.g., after the node has started far behind). Must be positive. Default is 1, i.e.,
+    /// the catch-up is serial.
+    #[serde(default = "OptionalENConfig::default_batch_status_updater_backfill_concurrency")]
+    batch_status_updater_backfill_concurrency: usize,
+    /// Additional delay after an L1 batch is executed on L1 before the node reports it as executed, and its miniblocks
+    /// as finalized (e.g., for the `finalized` block tag in the Web3 API). Provides an extra safety margin for clients
+    /// relying on finality; doesn't influence committed / proven batch statuses. In seconds. Default is 0 (no delay).
+    #[serde(default)]
+    l1_batch_finality_delay_sec: u64,
+    /// Maximum number of L1 batches committed on L1, but not yet executed, after which the batch status updater
+    /// reports degraded health. If not set, finality lag doesn't influence the node health.
+    pub batch_status_updater_max_finality_lag: Option<NonZeroU32>,
+    /// Maximum interval between attempts to load the state hash of the previous L1 batch when the state keeper
+    /// opens a new batch. The interval starts from 100ms and doubles while the hash is unavailable (e.g., because
+    /// the Merkle tree lags behind). In milliseconds. Must be positive. Default is 1,000ms.
+    #[serde(default = "OptionalENConfig::default_state_hash_max_poll_interval_ms")]
+    state_hash_max_poll_interval_ms: u64,
+    /// If set, the state keeper falls back to the root hash from the local Merkle tree if the state hash
+    /// of the previous L1 batch is not yet persisted in Postgres. If both sources have the hash, they are checked
+    /// to match. Disabled by default.
+    #[serde(default)]
+    pub state_keeper_tree_state_hash_fallback: bool,
+    /// Maximum time to wait for node components to stop on shutdown. Shutdown finishes earlier if all components
+    /// stop before the timeout. Large nodes (e.g., with big RocksDB instances) may need a larger value.
+    /// In milliseconds. Default is 30,000ms.
+    #[serde(default = "OptionalENConfig::default_shutdown_timeout_ms")]
+    shutdown_timeout_ms: u64,
+    /// Interval between runs of the snapshot creator. If specified, the node periodically creates application-level
+    /// snapshots of its storage, which other nodes can recover from. Snapshots are written to the object store
+    /// configured with `EN_SNAPSHOTS_OBJECT_STORE_*` variables. In seconds. If not specified, snapshots are not created.
+    snapshots_creator_interval_sec: Option<u64>,
+    /// Interval between checks whether contracts used in the API sandbox (e.g., for `eth_call` and `eth_estimateGas`)
+    /// have changed on disk. Changed contracts are applied without restarting the node. In seconds. If not specified,
+    /// contracts are only loaded on node startup.
+    api_contracts_reload_interval_sec: Option<u64>,
+    /// Whether to request gzip-compressed responses when fetching miniblocks from the main node. Can significantly
+    /// reduce bandwidth when syncing blocks with many transactions, at the cost of CPU time spent on compression.
+    /// Disabled by default.
+    #[serde(default)]
+    pub main_node_response_compression: bool,
+    /// Timeout for a single JSON-RPC request to the main node, including establishing a connection. In seconds.
+    /// Must be positive. Default is 30 seconds.
+    #[serde(default = "OptionalENConfig::default_main_node_request_timeout_sec")]
+    main_node_request_timeout_sec: u64,
+    /// Timeout for establishing a TCP connection to the main node. In seconds. Must be positive. Default is 10 seconds.
+    #[serde(default = "OptionalENConfig::default_main_node_connect_timeout_sec")]
+    main_node_connect_timeout_sec: u64,
+    /// Idle time after which TCP keepalive probes are sent on connections to the main node, which allows detecting
+    /// connections silently dropped by load balancers. In seconds. If set to 0, keepalive is disabled.
+    /// Default is 30 seconds.
+    #[serde(default = "OptionalENConfig::default_main_node_tcp_keepalive_sec")]
+    main_node_tcp_keepalive_sec: u64,
+    /// Maximum number of retries for main node requests failed with a transient error (e.g., a timeout).
+    /// Only idempotent reads are retried: requests by the state keeper I/O, fee params fetcher, reorg detector and
+    /// transaction proxy lookups. Transaction submissions are never retried, and the block fetcher relies on its own
+    /// retry logic. Default is 0 (no retries).
+    #[serde(default)]
+    main_node_request_max_retries: usize,
+    /// Delay before the first retry of a failed main node request. The delay is doubled for each subsequent retry
+    /// and randomly jittered. In milliseconds. Must be positive. Default is 100ms.
+    #[serde(default = "OptionalENConfig::default_main_node_request_retry_base_delay_ms")]
+    main_node_request_retry_base_delay_ms: u64,
+    /// Interval between polls of the latest miniblock on the main node, which is used to determine the sync status
+    /// of the node (e.g., reported by `eth_syncing`). In milliseconds. Must be positive. Default is 500ms.
+    #[serde(default = "OptionalENConfig::default_sync_state_polling_interval_ms")]
+    sync_state_polling_interval_ms: u64,
+    /// Timeout for a single poll of the latest miniblock on the main node. In milliseconds. Must be positive.
+    /// Default is 5,000ms.
+    #[serde(default = "OptionalENConfig::default_sync_state_polling_timeout_ms")]
+    sync_state_polling_timeout_ms: u64,
+    /// Number of consecutive failed or timed-out polls of the latest miniblock on the main node after which
+    /// the `sync_state` health check is marked as affected until the next successful poll. Must be positive.
+    /// Default is 3.
+    #[serde(default = "OptionalENConfig::default_sync_state_polling_max_consecutive_failures")]
+    sync_state_polling_max_consecutive_failures: u32,
+    /// If set, the `sync_state` health check is marked as degraded if the node is synced, but the latest
+    /// miniblock on the main node hasn't changed for this duration. In seconds. Must be positive. Disabled by default.
+    main_node_idle_timeout_sec: Option<u64>,
+    /// If set, the node terminates if the main node has been unreachable for longer than `main_node_idle_timeout_sec`,
+    /// which likely indicates a wedged connection rather than a quiet network. Requires `main_node_idle_timeout_sec`
+    /// to be set.
+    #[serde(default)]
+    abort_on_main_node_idle: bool,
+    /// Maximum lag (in L1 batches) of the database replica used by the API servers behind the primary database.
+    /// If the lag exceeds this value, the `database_replica` health check is marked as degraded. Only used if
+    /// the replica is configured via `DATABASE_REPLICA_URL`. Default is 1.
+    #[serde(default = "OptionalENConfig::default_database_replica_max_l1_batch_lag")]
+    pub database_replica_max_l1_batch_lag: u32,
+    /// If set, a fatal error in the consistency checker (e.g., L1 data divergence) doesn't terminate the node.
+    /// Instead, the node stops synchronization (the state keeper and fetcher), marks the consistency checker
+    /// health as failed, and keeps serving historical data via the API. Disabled by default.
+    #[serde(default)]
+    pub read_only_on_consistency_failure: bool,
+    /// Number of the latest committed L1 batches that the consistency checker rechecks against L1 on node startup
+    /// (batches processed by the checker previously are not rechecked). Must be positive. Default is 10.
+    #[serde(default = "OptionalENConfig::default_consistency_checker_max_batches_to_recheck")]
+    consistency_checker_max_batches_to_recheck: u32,
+    /// Node behavior when a reorg is detected: either roll back the storage automatically (`rollback`, the default),
+    /// or only report the reorg and exit, leaving the rollback to the operator (`observe`).
+    #[serde(default)]
+    pub reorg_handling_mode: ReorgHandlingMode,
+    /// Interval in milliseconds between reorg detector checks against the main node. Must be positive.
+    /// Default is 5 seconds.
+    #[serde(default = "OptionalENConfig::default_reorg_detector_poll_interval_ms")]
+    reorg_detector_poll_interval_ms: u64,
+    /// Maximum number of miniblocks the fetcher requests from the main node at once, i.e., the maximum
+    /// fetch request size. Each miniblock is fetched with a separate RPC call. The effective request size
+    /// is adjusted automatically: it's halved on request timeouts and gradually restored after successful
+    /// requests. Must be positive. Default is 30.
+    #[serde(default = "OptionalENConfig::default_fetcher_max_request_size")]
+    fetcher_max_request_size: usize,
     /// Polling period for mempool cache update - how often the mempool cache is updated from the database.
     /// In milliseconds. Default is 50 milliseconds.
     #[serde(default = "OptionalENConfig::default_mempool_cache_update_interval")]
@@ -245,6 +530,11 @@ pub struct OptionalENConfig {
     /// Maximum number of transactions to be stored in the mempool cache. Default is 10000.
     #[serde(default = "OptionalENConfig::default_mempool_cache_size")]
     pub mempool_cache_size: usize,
+    /// Floor for intervals of polling loops (e.g., fetching fee params or L1 batch statuses from the main node,
+    /// or updating the mempool cache). Configured intervals less than the floor are clamped to it. In milliseconds.
+    /// Default is 50.
+    #[serde(default = "OptionalENConfig::default_min_polling_interval_ms")]
+    min_polling_interval_ms: u64,
     /// Address of the L1 diamond proxy contract used by the consistency checker to match with the origin of logs emitted
     /// by commit transactions. If not set, it will not be verified.
     // This is intentionally not a part of `RemoteENConfig` because fetching this info from the main node would defeat
@@ -292,6 +582,14 @@ impl OptionalENConfig {
         1.2
     }
 
+    const fn default_call_traces_sampling_rate() -> f64 {
+        1.0
+    }
+
+    const fn default_healthcheck_initializing_status_code() -> u16 {
+        503
+    }
+
     const fn default_max_nonce_ahead() -> u32 {
         50
     }
@@ -323,6 +621,10 @@ impl OptionalENConfig {
         128
     }
 
+    const fn default_merkle_tree_mode() -> MerkleTreeMode {
+        MerkleTreeMode::Lightweight
+    }
+
     const fn default_merkle_tree_multi_get_chunk_size() -> usize {
         500
     }
@@ -339,6 +641,10 @@ impl OptionalENConfig {
         30
     }
 
+    const fn default_merkle_tree_max_l1_batch_lag_for_proofs() -> u32 {
+        5
+    }
+
     const fn default_fee_history_limit() -> u64 {
         1_024
     }
@@ -351,6 +657,10 @@ impl OptionalENConfig {
         10
     }
 
+    const fn default_max_request_body_size_mb() -> usize {
+        10
+    }
+
     const fn default_enum_index_migration_chunk_size() -> usize {
         5000
     }
@@ -359,6 +669,74 @@ impl OptionalENConfig {
         10
     }
 
+    const fn default_postgres_metrics_scraping_enabled() -> bool {
+        true
+    }
+
+    const fn default_postgres_metrics_scraping_interval_sec() -> u64 {
+        60
+    }
+
+    const fn default_fetcher_max_request_size() -> usize {
+        30
+    }
+
+    const fn default_batch_status_updater_backfill_concurrency() -> usize {
+        1
+    }
+
+    const fn default_consistency_checker_max_batches_to_recheck() -> u32 {
+        10
+    }
+
+    const fn default_reorg_detector_poll_interval_ms() -> u64 {
+        5_000
+    }
+
+    const fn default_state_hash_max_poll_interval_ms() -> u64 {
+        1_000
+    }
+
+    const fn default_shutdown_timeout_ms() -> u64 {
+        30_000
+    }
+
+    const fn default_min_polling_interval_ms() -> u64 {
+        50
+    }
+
+    const fn default_main_node_request_timeout_sec() -> u64 {
+        30
+    }
+
+    const fn default_main_node_connect_timeout_sec() -> u64 {
+        10
+    }
+
+    const fn default_main_node_tcp_keepalive_sec() -> u64 {
+        30
+    }
+
+    const fn default_main_node_request_retry_base_delay_ms() -> u64 {
+        100
+    }
+
+    const fn default_sync_state_polling_interval_ms() -> u64 {
+        500
+    }
+
+    const fn default_sync_state_polling_timeout_ms() -> u64 {
+        5_000
+    }
+
+    const fn default_sync_state_polling_max_consecutive_failures() -> u32 {
+        3
+    }
+
+    const fn default_database_replica_max_l1_batch_lag() -> u32 {
+        1
+    }
+
     const fn default_mempool_cache_update_interval() -> u64 {
         50
     }
@@ -371,8 +749,22 @@ impl OptionalENConfig {
         L1BatchCommitDataGeneratorMode::Rollup
     }
 
+    const fn default_fee_address_migration_enabled() -> bool {
+        true
+    }
+
     pub fn polling_interval(&self) -> Duration {
-        Duration::from_millis(self.polling_interval)
+        self.clamp_polling_interval(Duration::from_millis(self.polling_interval))
+    }
+
+    /// Returns the validated fraction of transactions for which call traces are saved.
+    pub fn call_traces_sampling_rate(&self) -> anyhow::Result<f64> {
+        let rate = self.call_traces_sampling_rate;
+        anyhow::ensure!(
+            (0.0..=1.0).contains(&rate),
+            "call_traces_sampling_rate must be in 0.0..=1.0 range, got {rate}"
+        );
+        Ok(rate)
     }
 
     pub fn metadata_calculator_delay(&self) -> Duration {
@@ -404,9 +796,128 @@ impl OptionalENConfig {
         self.merkle_tree_memtable_capacity_mb * BYTES_IN_MEGABYTE
     }
 
-    /// Returns the timeout to wait for the Merkle tree database to run compaction on stalled writes.
-    pub fn merkle_tree_stalled_writes_timeout(&self) -> Duration {
-        Duration::from_secs(self.merkle_tree_stalled_writes_timeout_sec)
+    /// Returns the validated timeout to wait for the Merkle tree database to run compaction on stalled writes.
+    pub fn merkle_tree_stalled_writes_timeout(&self) -> anyhow::Result<Duration> {
+        const ALLOWED_RANGE_SEC: ops::RangeInclusive<u64> = 5..=1_800;
+
+        let timeout_sec = self.merkle_tree_stalled_writes_timeout_sec;
+        anyhow::ensure!(
+            ALLOWED_RANGE_SEC.contains(&timeout_sec),
+            "merkle_tree_stalled_writes_timeout_sec must be in {ALLOWED_RANGE_SEC:?} range, got {timeout_sec}; \
+             too short timeouts lead to spurious tree errors under load, while too long ones can mask disk issues. \
+             Use the `rocksdb_write_stalled` metric to tune the timeout"
+        );
+        Ok(Duration::from_secs(timeout_sec))
+    }
+
+    /// Returns the validated number of keys processed by the enum index migration per chunk.
+    pub fn enum_index_migration_chunk_size(&self) -> anyhow::Result<usize> {
+        const MAX_CHUNK_SIZE: usize = StateKeeperConfig::MAX_ENUM_INDEX_MIGRATION_CHUNK_SIZE;
+
+        let chunk_size = self.enum_index_migration_chunk_size;
+        anyhow::ensure!(
+            (1..=MAX_CHUNK_SIZE).contains(&chunk_size),
+            "enum_index_migration_chunk_size must be in 1..={MAX_CHUNK_SIZE} range, got {chunk_size}"
+        );
+        Ok(chunk_size)
+    }
+
+    /// Returns the validated batch status updater backfill concurrency.
+    pub fn batch_status_updater_backfill_concurrency(&self) -> anyhow::Result<NonZeroUsize> {
+        NonZeroUsize::new(self.batch_status_updater_backfill_concurrency)
+            .context("batch_status_updater_backfill_concurrency must be positive")
+    }
+
+    pub fn reorg_detector_poll_interval(&self) -> anyhow::Result<Duration> {
+        anyhow::ensure!(
+            self.reorg_detector_poll_interval_ms > 0,
+            "reorg_detector_poll_interval_ms must be positive"
+        );
+        let interval = Duration::from_millis(self.reorg_detector_poll_interval_ms);
+        Ok(self.clamp_polling_interval(interval))
+    }
+
+    pub fn consistency_checker_max_batches_to_recheck(&self) -> anyhow::Result<u32> {
+        anyhow::ensure!(
+            self.consistency_checker_max_batches_to_recheck > 0,
+            "consistency_checker_max_batches_to_recheck must be positive"
+        );
+        Ok(self.consistency_checker_max_batches_to_recheck)
+    }
+
+    pub fn l1_batch_finality_delay(&self) -> Duration {
+        Duration::from_secs(self.l1_batch_finality_delay_sec)
+    }
+
+    pub fn state_hash_max_poll_interval(&self) -> anyhow::Result<Duration> {
+        anyhow::ensure!(
+            self.state_hash_max_poll_interval_ms > 0,
+            "state_hash_max_poll_interval_ms must be positive"
+        );
+        let interval = Duration::from_millis(self.state_hash_max_poll_interval_ms);
+        Ok(self.clamp_polling_interval(interval))
+    }
+
+    pub fn shutdown_timeout(&self) -> Duration {
+        Duration::from_millis(self.shutdown_timeout_ms)
+    }
+
+    pub fn snapshots_creator_interval(&self) -> anyhow::Result<Option<Duration>> {
+        if let Some(interval) = self.snapshots_creator_interval_sec {
+            anyhow::ensure!(
+                interval > 0,
+                "snapshots_creator_interval_sec must be positive"
+            );
+        }
+        Ok(self.snapshots_creator_interval_sec.map(Duration::from_secs))
+    }
+
+    /// Returns the validated Prometheus exporter config, or `None` if metrics exporting is disabled.
+    pub fn prometheus_exporter_config(&self) -> anyhow::Result<Option<PrometheusExporterConfig>> {
+        const DEFAULT_PUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+        let Some(gateway_url) = &self.prometheus_pushgateway_url else {
+            anyhow::ensure!(
+                self.prometheus_push_interval_ms.is_none(),
+                "prometheus_push_interval_ms is set, but prometheus_pushgateway_url is not; \
+                 push mode requires a gateway URL"
+            );
+            return Ok(self.prometheus_port.map(PrometheusExporterConfig::pull));
+        };
+
+        anyhow::ensure!(
+            self.prometheus_port.is_none(),
+            "prometheus_port and prometheus_pushgateway_url cannot be set simultaneously"
+        );
+        Url::parse(gateway_url).context("prometheus_pushgateway_url is not a valid URL")?;
+        let interval = match self.prometheus_push_interval_ms {
+            Some(interval) => {
+                anyhow::ensure!(interval > 0, "prometheus_push_interval_ms must be positive");
+                Duration::from_millis(interval)
+            }
+            None => DEFAULT_PUSH_INTERVAL,
+        };
+        Ok(Some(PrometheusExporterConfig::push(
+            gateway_url.clone(),
+            interval,
+        )))
+    }
+
+    pub fn api_contracts_reload_interval(&self) -> anyhow::Result<Option<Duration>> {
+        if let Some(interval) = self.api_contracts_reload_interval_sec {
+            anyhow::ensure!(
+                interval > 0,
+                "api_contracts_reload_interval_sec must be positive"
+            );
+        }
+        Ok(self
+            .api_contracts_reload_interval_sec
+            .map(Duration::from_secs))
+    }
+
+    pub fn fetcher_max_request_size(&self) -> anyhow::Result<NonZeroUsize> {
+        NonZeroUsize::new(self.fetcher_max_request_size)
+            .context("fetcher_max_request_size must be positive")
     }
 
     pub fn long_connection_threshold(&self) -> Option<Duration> {
@@ -429,6 +940,10 @@ impl OptionalENConfig {
         self.max_response_body_size_mb * BYTES_IN_MEGABYTE
     }
 
+    pub fn max_request_body_size(&self) -> usize {
+        self.max_request_body_size_mb * BYTES_IN_MEGABYTE
+    }
+
     pub fn healthcheck_slow_time_limit(&self) -> Option<Duration> {
         self.healthcheck_slow_time_limit_ms
             .map(Duration::from_millis)
@@ -440,7 +955,132 @@ impl OptionalENConfig {
     }
 
     pub fn mempool_cache_update_interval(&self) -> Duration {
-        Duration::from_millis(self.mempool_cache_update_interval)
+        self.clamp_polling_interval(Duration::from_millis(self.mempool_cache_update_interval))
+    }
+
+    pub fn state_keeper_db_compaction_interval(&self) -> anyhow::Result<Option<Duration>> {
+        if let Some(interval) = self.state_keeper_db_compaction_interval_sec {
+            anyhow::ensure!(
+                interval > 0,
+                "state_keeper_db_compaction_interval_sec must be positive"
+            );
+        }
+        Ok(self
+            .state_keeper_db_compaction_interval_sec
+            .map(|interval| self.clamp_polling_interval(Duration::from_secs(interval))))
+    }
+
+    /// Returns tuning options for the state keeper RocksDB.
+    pub fn state_keeper_db_options(&self) -> StateKeeperRocksdbOptions {
+        StateKeeperRocksdbOptions {
+            write_buffer_size: self
+                .state_keeper_db_write_buffer_size_mb
+                .map(|size_mb| size_mb * BYTES_IN_MEGABYTE),
+            max_background_jobs: self.state_keeper_db_max_background_jobs,
+            compaction_style: self.state_keeper_db_compaction_style,
+            memory_budget: None,
+            collect_statistics: self.rocksdb_statistics_enabled,
+        }
+    }
+
+    /// Returns the total RocksDB memory budget in bytes, if it's configured.
+    pub fn rocksdb_memory_budget(&self) -> anyhow::Result<Option<usize>> {
+        let Some(budget_mb) = self.rocksdb_memory_budget_mb else {
+            return Ok(None);
+        };
+        anyhow::ensure!(
+            budget_mb > 0,
+            "RocksDB memory budget is misconfigured to be 0; please update it to positive value"
+        );
+        Ok(Some(budget_mb * BYTES_IN_MEGABYTE))
+    }
+
+    pub fn min_polling_interval(&self) -> Duration {
+        Duration::from_millis(self.min_polling_interval_ms)
+    }
+
+    fn clamp_polling_interval(&self, interval: Duration) -> Duration {
+        clamp_polling_interval(interval, self.min_polling_interval())
+    }
+
+    /// Returns the validated configuration of the JSON-RPC client connecting to the main node.
+    pub fn main_node_client_config(&self) -> anyhow::Result<MainNodeClientConfig> {
+        anyhow::ensure!(
+            self.main_node_request_timeout_sec > 0,
+            "`main_node_request_timeout_sec` must be positive"
+        );
+        anyhow::ensure!(
+            self.main_node_connect_timeout_sec > 0,
+            "`main_node_connect_timeout_sec` must be positive"
+        );
+        anyhow::ensure!(
+            self.main_node_request_retry_base_delay_ms > 0,
+            "`main_node_request_retry_base_delay_ms` must be positive"
+        );
+        Ok(MainNodeClientConfig {
+            request_timeout: Duration::from_secs(self.main_node_request_timeout_sec),
+            connect_timeout: Duration::from_secs(self.main_node_connect_timeout_sec),
+            tcp_keepalive: (self.main_node_tcp_keepalive_sec > 0)
+                .then(|| Duration::from_secs(self.main_node_tcp_keepalive_sec)),
+            compress_responses: self.main_node_response_compression,
+            max_response_body_size: self.max_response_body_size(),
+            retries: MainNodeRetryConfig {
+                max_retries: self.main_node_request_max_retries,
+                base_delay: Duration::from_millis(self.main_node_request_retry_base_delay_ms),
+            },
+        })
+    }
+
+    /// Returns the validated configuration of polling the main node for its latest miniblock.
+    pub fn sync_state_polling_config(&self) -> anyhow::Result<consensus::SyncStatePollingConfig> {
+        anyhow::ensure!(
+            self.sync_state_polling_interval_ms > 0,
+            "`sync_state_polling_interval_ms` must be positive"
+        );
+        anyhow::ensure!(
+            self.sync_state_polling_timeout_ms > 0,
+            "`sync_state_polling_timeout_ms` must be positive"
+        );
+        let max_consecutive_failures =
+            NonZeroU32::new(self.sync_state_polling_max_consecutive_failures)
+                .context("`sync_state_polling_max_consecutive_failures` must be positive")?;
+        let idle_timeout = if let Some(timeout_sec) = self.main_node_idle_timeout_sec {
+            anyhow::ensure!(
+                timeout_sec > 0,
+                "`main_node_idle_timeout_sec` must be positive"
+            );
+            Some(time::Duration::seconds(timeout_sec as i64))
+        } else {
+            anyhow::ensure!(
+                !self.abort_on_main_node_idle,
+                "`abort_on_main_node_idle` requires `main_node_idle_timeout_sec` to be set"
+            );
+            None
+        };
+        let interval =
+            self.clamp_polling_interval(Duration::from_millis(self.sync_state_polling_interval_ms));
+        Ok(consensus::SyncStatePollingConfig {
+            interval: time::Duration::milliseconds(interval.as_millis() as i64),
+            timeout: time::Duration::milliseconds(self.sync_state_polling_timeout_ms as i64),
+            max_consecutive_failures,
+            idle_timeout,
+            abort_on_idle: self.abort_on_main_node_idle,
+        })
+    }
+
+    /// Returns the validated interval between Postgres metrics scrapes, or `None` if scraping is disabled.
+    pub fn postgres_metrics_scraping_interval(&self) -> anyhow::Result<Option<Duration>> {
+        if !self.postgres_metrics_scraping_enabled {
+            return Ok(None);
+        }
+        let interval = self.postgres_metrics_scraping_interval_sec;
+        anyhow::ensure!(
+            interval > 0,
+            "postgres_metrics_scraping_interval_sec must be positive if Postgres metrics scraping is enabled"
+        );
+        Ok(Some(
+            self.clamp_polling_interval(Duration::from_secs(interval)),
+        ))
     }
 }
 
@@ -478,6 +1118,51 @@ impl RequiredENConfig {
         let url = Url::parse(url_str).context("URL can not be parsed")?;
         format_url_with_port(&url)
     }
+
+    fn rocksdb_paths(&self) -> [(&'static str, &str); 2] {
+        [
+            ("state_cache_path", &self.state_cache_path),
+            ("merkle_tree_path", &self.merkle_tree_path),
+        ]
+    }
+
+    /// Checks that RocksDB directories used by the node are distinct and writable. This should be called
+    /// before opening any RocksDB instance: instances sharing a directory corrupt each other.
+    pub fn validate_rocksdb_paths(&self) -> anyhow::Result<()> {
+        let mut canonical_paths = vec![];
+        for (name, path) in self.rocksdb_paths() {
+            // Directories are created first, so that paths can be canonicalized (which resolves symlinks
+            // and relative components).
+            ensure_writable_dir(Path::new(path))
+                .with_context(|| format!("`{name}` ({path}) is not a writable directory"))?;
+            let canonical_path = fs::canonicalize(path)
+                .with_context(|| format!("cannot canonicalize `{name}` ({path})"))?;
+            canonical_paths.push((name, canonical_path));
+        }
+        ensure_distinct_paths(&canonical_paths)
+    }
+}
+
+/// Checks that canonical `paths` are distinct and not nested in each other.
+fn ensure_distinct_paths(paths: &[(&str, PathBuf)]) -> anyhow::Result<()> {
+    for (i, (name, path)) in paths.iter().enumerate() {
+        for (other_name, other_path) in &paths[i + 1..] {
+            anyhow::ensure!(
+                !path.starts_with(other_path) && !other_path.starts_with(path),
+                "`{name}` ({path:?}) and `{other_name}` ({other_path:?}) must point to distinct, non-nested \
+                 directories; RocksDB instances sharing a directory corrupt each other"
+            );
+        }
+    }
+    Ok(())
+}
+
+fn ensure_writable_dir(path: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(path).context("cannot create directory")?;
+    let probe_path = path.join(".write_probe");
+    fs::write(&probe_path, []).context("cannot write to directory")?;
+    fs::remove_file(&probe_path).context("cannot remove probe file")?;
+    Ok(())
 }
 
 /// Configuration for Postgres database.
@@ -487,35 +1172,138 @@ impl RequiredENConfig {
 #[derive(Debug, Clone, Deserialize, PartialEq)]
 pub struct PostgresConfig {
     pub database_url: String,
+    /// URL of a read replica of the node database. If set, the replica is used by the API servers, so that
+    /// API read traffic doesn't compete for connections with components writing to the primary database.
+    pub database_replica_url: Option<String>,
     pub max_connections: u32,
+    /// Size of the connection pool for the read replica. If not set, `max_connections` is used. Only used
+    /// if the replica is configured.
+    pub replica_max_connections: Option<u32>,
 }
 
 impl PostgresConfig {
-    pub fn from_env() -> anyhow::Result<Self> {
+    fn from_vars(vars: &ConfigVars) -> anyhow::Result<Self> {
         Ok(Self {
-            database_url: env::var("DATABASE_URL")
-                .context("DATABASE_URL env variable is not set")?,
-            max_connections: env::var("DATABASE_POOL_SIZE")
-                .context("DATABASE_POOL_SIZE env variable is not set")?
-                .parse()
-                .context("Unable to parse DATABASE_POOL_SIZE env variable")?,
+            database_url: vars
+                .get("DATABASE_URL")
+                .context("DATABASE_URL variable is not set")?
+                .to_owned(),
+            database_replica_url: vars.get("DATABASE_REPLICA_URL").map(str::to_owned),
+            max_connections: vars.parse("DATABASE_POOL_SIZE")?,
+            replica_max_connections: vars
+                .get("DATABASE_REPLICA_POOL_SIZE")
+                .map(|_| vars.parse("DATABASE_REPLICA_POOL_SIZE"))
+                .transpose()?,
+        })
+    }
+
+    /// Returns the URL of the database used by the API servers: the read replica if it's configured,
+    /// or the primary database otherwise.
+    pub fn api_database_url(&self) -> &str {
+        self.database_replica_url
+            .as_deref()
+            .unwrap_or(&self.database_url)
+    }
+
+    /// Returns the size of the connection pool for the read replica.
+    pub fn replica_max_connections(&self) -> u32 {
+        self.replica_max_connections.unwrap_or(self.max_connections)
+    }
+}
+
+/// Variables used to load the EN config. Variables are named and formatted in the same way as environment variables
+/// (e.g., `EN_HTTP_PORT`), so that the config is resolved identically regardless of the variable source.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct ConfigVars(HashMap<String, String>);
+
+impl ConfigVars {
+    /// Loads variables from the process environment, optionally merging them on top of variables
+    /// from the YAML file at `config_path`. Environment variables take precedence over the file.
+    pub(crate) fn new(config_path: Option<&Path>) -> anyhow::Result<Self> {
+        let vars = if let Some(path) = config_path {
+            let yaml = fs::read_to_string(path)
+                .with_context(|| format!("failed reading config file `{}`", path.display()))?;
+            Self::from_yaml(&yaml)
+                .with_context(|| format!("failed parsing config file `{}`", path.display()))?
+        } else {
+            Self::default()
+        };
+        Ok(vars.with_overrides(env::vars()))
+    }
+
+    /// Parses variables from YAML containing a flat mapping of variable names to values, e.g. `EN_HTTP_PORT: 3060`.
+    /// Sequences are joined with commas, in the same way list values are specified in environment variables.
+    fn from_yaml(yaml: &str) -> anyhow::Result<Self> {
+        let raw_vars: HashMap<String, serde_yaml::Value> = serde_yaml::from_str(yaml)?;
+        let vars = raw_vars.into_iter().map(|(name, value)| {
+            let value = Self::format_value(&value)
+                .with_context(|| format!("unsupported value for variable `{name}`"))?;
+            Ok((name, value))
+        });
+        vars.collect::<anyhow::Result<_>>().map(Self)
+    }
+
+    fn format_value(value: &serde_yaml::Value) -> anyhow::Result<String> {
+        Ok(match value {
+            serde_yaml::Value::Bool(value) => value.to_string(),
+            serde_yaml::Value::Number(value) => value.to_string(),
+            serde_yaml::Value::String(value) => value.clone(),
+            serde_yaml::Value::Sequence(items) => {
+                let items: Vec<_> = items
+                    .iter()
+                    .map(Self::format_value)
+                    .collect::<anyhow::Result<_>>()?;
+                items.join(",")
+            }
+            _ => anyhow::bail!("only scalars and sequences of scalars are supported"),
         })
     }
+
+    #[must_use]
+    fn with_overrides(mut self, overrides: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.0.extend(overrides);
+        self
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+
+    fn parse<T>(&self, name: &str) -> anyhow::Result<T>
+    where
+        T: FromStr,
+        T::Err: fmt::Debug,
+    {
+        let value = self
+            .get(name)
+            .with_context(|| format!("{name} variable is not set"))?;
+        value
+            .parse()
+            .map_err(|err| anyhow::anyhow!("unable to parse {name} variable: {err:?}"))
+    }
+
+    fn deserialize_prefixed<T: DeserializeOwned>(&self, prefix: &str) -> envy::Result<T> {
+        let vars = self
+            .0
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()));
+        envy::prefixed(prefix).from_iter(vars)
+    }
 }
 
-pub(crate) fn read_consensus_secrets() -> anyhow::Result<Option<consensus::Secrets>> {
-    let Ok(path) = std::env::var("EN_CONSENSUS_SECRETS_PATH") else {
+fn read_consensus_secrets(vars: &ConfigVars) -> anyhow::Result<Option<consensus::Secrets>> {
+    let Some(path) = vars.get("EN_CONSENSUS_SECRETS_PATH") else {
         return Ok(None);
     };
-    let cfg = std::fs::read_to_string(&path).context(path)?;
+    let cfg = std::fs::read_to_string(path).context(path.to_owned())?;
     Ok(Some(decode_yaml(&cfg).context("failed decoding YAML")?))
 }
 
-pub(crate) fn read_consensus_config() -> anyhow::Result<Option<consensus::Config>> {
-    let Ok(path) = std::env::var("EN_CONSENSUS_CONFIG_PATH") else {
+fn read_consensus_config(vars: &ConfigVars) -> anyhow::Result<Option<consensus::Config>> {
+    let Some(path) = vars.get("EN_CONSENSUS_CONFIG_PATH") else {
         return Ok(None);
     };
-    let cfg = std::fs::read_to_string(&path).context(path)?;
+    let cfg = std::fs::read_to_string(path).context(path.to_owned())?;
     Ok(Some(decode_yaml(&cfg).context("failed decoding YAML")?))
 }
 
@@ -526,15 +1314,36 @@ pub struct SnapshotsRecoveryConfig {
     pub snapshots_object_store: ObjectStoreConfig,
 }
 
-pub(crate) fn read_snapshots_recovery_config() -> anyhow::Result<SnapshotsRecoveryConfig> {
-    let snapshots_object_store = envy::prefixed("EN_SNAPSHOTS_OBJECT_STORE_")
-        .from_env::<ObjectStoreConfig>()
-        .context("failed loading snapshot object store config from env variables")?;
+fn read_snapshots_recovery_config(vars: &ConfigVars) -> anyhow::Result<SnapshotsRecoveryConfig> {
+    let snapshots_object_store = vars
+        .deserialize_prefixed::<ObjectStoreConfig>("EN_SNAPSHOTS_OBJECT_STORE_")
+        .context("failed loading snapshot object store config")?;
     Ok(SnapshotsRecoveryConfig {
         snapshots_object_store,
     })
 }
 
+/// Configuration for creating snapshots. Loaded only if the snapshot creator is enabled
+/// (i.e., `EN_SNAPSHOTS_CREATOR_INTERVAL_SEC` is set).
+#[derive(Debug, Clone)]
+pub struct SnapshotsCreationConfig {
+    pub creator: SnapshotsCreatorConfig,
+    pub snapshots_object_store: ObjectStoreConfig,
+}
+
+fn read_snapshots_creation_config(vars: &ConfigVars) -> anyhow::Result<SnapshotsCreationConfig> {
+    let creator = vars
+        .deserialize_prefixed::<SnapshotsCreatorConfig>("EN_SNAPSHOTS_CREATOR_")
+        .context("failed loading snapshot creator config")?;
+    let snapshots_object_store = vars
+        .deserialize_prefixed::<ObjectStoreConfig>("EN_SNAPSHOTS_OBJECT_STORE_")
+        .context("failed loading snapshot object store config")?;
+    Ok(SnapshotsCreationConfig {
+        creator,
+        snapshots_object_store,
+    })
+}
+
 /// External Node Config contains all the configuration required for the EN operation.
 /// It is split into three parts: required, optional and remote for easier navigation.
 #[derive(Debug, Clone)]
@@ -544,53 +1353,58 @@ pub struct ExternalNodeConfig {
     pub optional: OptionalENConfig,
     pub remote: RemoteENConfig,
     pub consensus: Option<consensus::Config>,
+    /// Variables the config was loaded from. Used to load optional configs (e.g., consensus secrets
+    /// or the snapshot object store) only when they are needed.
+    vars: ConfigVars,
 }
 
 impl ExternalNodeConfig {
-    /// Loads config from the environment variables and
-    /// fetches contracts addresses from the main node.
-    pub async fn collect() -> anyhow::Result<Self> {
-        let required = envy::prefixed("EN_")
-            .from_env::<RequiredENConfig>()
+    /// Loads config from the provided variables (see [`ConfigVars::new()`]) and fetches contracts addresses
+    /// from the main node. Transient errors reaching the main node or the L1 node are retried according
+    /// to `retry_policy`. Returns `Ok(None)` if a stop signal is received while waiting for a retry.
+    pub(crate) async fn collect(
+        vars: ConfigVars,
+        retry_policy: RpcRetryPolicy,
+        stop_receiver: &mut watch::Receiver<bool>,
+    ) -> anyhow::Result<Option<Self>> {
+        let required = vars
+            .deserialize_prefixed::<RequiredENConfig>("EN_")
             .context("could not load external node config")?;
 
-        let optional = envy::prefixed("EN_")
-            .from_env::<OptionalENConfig>()
+        let optional = vars
+            .deserialize_prefixed::<OptionalENConfig>("EN_")
             .context("could not load external node config")?;
 
         let client = HttpClientBuilder::default()
             .build(required.main_node_url()?)
-            .expect("Unable to build HTTP client for main node");
-        let remote = RemoteENConfig::fetch(&client)
+            .context("Unable to build HTTP client for main node")?;
+        let remote = retry_policy
+            .retry_until_stopped("fetch_remote_config", stop_receiver, || {
+                RemoteENConfig::fetch(&client)
+            })
             .await
             .context("Unable to fetch required config values from the main node")?;
+        let Some(remote) = remote else {
+            return Ok(None);
+        };
         // We can query them from main node, but it's better to set them explicitly
         // as well to avoid connecting to wrong environment variables unintentionally.
-        let eth_chain_id = HttpClientBuilder::default()
+        let eth_client = HttpClientBuilder::default()
             .build(required.eth_client_url()?)
-            .expect("Unable to build HTTP client for L1 client")
-            .chain_id()
+            .context("Unable to build HTTP client for L1 client")?;
+        let eth_chain_id = retry_policy
+            .retry_until_stopped("eth_chain_id", stop_receiver, || async {
+                Ok(eth_client.chain_id().rpc_context("chain_id").await?)
+            })
             .await
             .context("Unable to check L1 chain ID through the configured L1 client")?;
+        let Some(eth_chain_id) = eth_chain_id else {
+            return Ok(None);
+        };
 
-        let l2_chain_id: L2ChainId = env_var("EN_L2_CHAIN_ID");
-        let l1_chain_id: u64 = env_var("EN_L1_CHAIN_ID");
-        if l2_chain_id != remote.l2_chain_id {
-            anyhow::bail!(
-                "Configured L2 chain id doesn't match the one from main node.
-                Make sure your configuration is correct and you are corrected to the right main node.
-                Main node L2 chain id: {:?}. Local config value: {:?}",
-                remote.l2_chain_id, l2_chain_id
-            );
-        }
-        if l1_chain_id != remote.l1_chain_id.0 {
-            anyhow::bail!(
-                "Configured L1 chain id doesn't match the one from main node.
-                Make sure your configuration is correct and you are corrected to the right main node.
-                Main node L1 chain id: {}. Local config value: {}",
-                remote.l1_chain_id.0, l1_chain_id
-            );
-        }
+        let l2_chain_id: L2ChainId = vars.parse("EN_L2_CHAIN_ID")?;
+        let l1_chain_id: u64 = vars.parse("EN_L1_CHAIN_ID")?;
+        remote.check_chain_ids(l2_chain_id, L1ChainId(l1_chain_id))?;
         if l1_chain_id != eth_chain_id.as_u64() {
             anyhow::bail!(
                 "Configured L1 chain id doesn't match the one from eth node.
@@ -601,32 +1415,108 @@ impl ExternalNodeConfig {
             );
         }
 
-        let postgres = PostgresConfig::from_env()?;
+        let postgres = PostgresConfig::from_vars(&vars)?;
 
-        Ok(Self {
+        Ok(Some(Self {
             remote,
             postgres,
             required,
             optional,
-            consensus: read_consensus_config().context("read_consensus_config()")?,
-        })
+            consensus: read_consensus_config(&vars).context("read_consensus_config()")?,
+            vars,
+        }))
     }
-}
 
-fn env_var<T>(name: &str) -> T
-where
-    T: std::str::FromStr,
-    T::Err: std::fmt::Debug,
-{
-    env::var(name)
-        .unwrap_or_else(|_| panic!("{} env variable is not set", name))
-        .parse()
-        .unwrap_or_else(|_| panic!("unable to parse {} env variable", name))
-}
+    /// Reads consensus secrets from the file specified by `EN_CONSENSUS_SECRETS_PATH`, if any.
+    pub(crate) fn consensus_secrets(&self) -> anyhow::Result<Option<consensus::Secrets>> {
+        read_consensus_secrets(&self.vars)
+    }
 
-impl From<ExternalNodeConfig> for InternalApiConfig {
-    fn from(config: ExternalNodeConfig) -> Self {
+    /// Loads the configuration for snapshot recovery (`EN_SNAPSHOTS_OBJECT_STORE_*` variables).
+    pub(crate) fn snapshots_recovery_config(&self) -> anyhow::Result<SnapshotsRecoveryConfig> {
+        read_snapshots_recovery_config(&self.vars)
+    }
+
+    /// Loads the configuration for creating snapshots (`EN_SNAPSHOTS_CREATOR_*` and `EN_SNAPSHOTS_OBJECT_STORE_*` variables).
+    pub(crate) fn snapshots_creation_config(&self) -> anyhow::Result<SnapshotsCreationConfig> {
+        read_snapshots_creation_config(&self.vars)
+    }
+
+    /// Creates a mock configuration with default optional params.
+    #[cfg(test)]
+    pub(crate) fn mock() -> Self {
+        let optional = envy::prefixed("EN_")
+            .from_iter::<_, OptionalENConfig>([])
+            .expect("default optional config is valid");
         Self {
+            required: RequiredENConfig {
+                http_port: 3060,
+                ws_port: 3061,
+                healthcheck_port: 3081,
+                eth_client_url: "http://localhost:8545/".to_owned(),
+                main_node_url: "http://localhost:3050/".to_owned(),
+                state_cache_path: "./db/state_keeper".to_owned(),
+                merkle_tree_path: "./db/lightweight".to_owned(),
+            },
+            postgres: PostgresConfig {
+                database_url: "postgres://postgres@localhost/en".to_owned(),
+                database_replica_url: None,
+                max_connections: 10,
+                replica_max_connections: None,
+            },
+            optional,
+            remote: RemoteENConfig::mock(),
+            consensus: None,
+            vars: ConfigVars::default(),
+        }
+    }
+
+    /// Checks that the consensus config and secrets are present. Should be called if consensus is explicitly enabled,
+    /// so that the node doesn't silently fall back to centralized syncing.
+    pub(crate) fn validate_consensus(
+        &self,
+        secrets: Option<&consensus::Secrets>,
+    ) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.consensus.is_some(),
+            "consensus config (`config.consensus`) is missing; set `EN_CONSENSUS_CONFIG_PATH` to the config file"
+        );
+        anyhow::ensure!(
+            secrets.is_some(),
+            "consensus secrets are missing; set `EN_CONSENSUS_SECRETS_PATH` to the secrets file"
+        );
+        Ok(())
+    }
+
+    /// Returns the components run by the node with this configuration. Reported via the `zks_getNodeInfo` RPC method.
+    pub fn components(&self) -> Vec<&'static str> {
+        let mut components = vec!["core", "tree", "http_api", "ws_api"];
+        if !self.optional.state_keeper_db_disabled {
+            components.push("state_keeper_cache");
+        }
+        if self.consensus.is_some() {
+            components.push("consensus");
+        }
+        if self.postgres.database_replica_url.is_some() {
+            components.push("database_replica");
+        }
+        if self.optional.prometheus_port.is_some()
+            || self.optional.prometheus_pushgateway_url.is_some()
+        {
+            components.push("prometheus_exporter");
+        }
+        if self.optional.snapshots_creator_interval_sec.is_some() {
+            components.push("snapshots_creator");
+        }
+        components
+    }
+}
+
+impl TryFrom<ExternalNodeConfig> for InternalApiConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(config: ExternalNodeConfig) -> anyhow::Result<Self> {
+        Ok(Self {
             l1_chain_id: config.remote.l1_chain_id,
             l2_chain_id: config.remote.l2_chain_id,
             max_tx_size: config.optional.max_tx_size,
@@ -648,7 +1538,11 @@ impl From<ExternalNodeConfig> for InternalApiConfig {
             filters_disabled: config.optional.filters_disabled,
             mempool_cache_update_interval: config.optional.mempool_cache_update_interval(),
             mempool_cache_size: config.optional.mempool_cache_size,
-        }
+            node_version: None,
+            node_components: config.components().into_iter().map(str::to_owned).collect(),
+            consensus_enabled: config.consensus.is_some(),
+            call_traces_sampling_rate: config.optional.call_traces_sampling_rate()?,
+        })
     }
 }
 
@@ -1,32 +1,39 @@
 use zksync_config::configs::ObservabilityConfig;
 
-pub fn observability_config_from_env() -> anyhow::Result<ObservabilityConfig> {
+use super::ConfigVars;
+
+pub(crate) fn observability_config_from_vars(
+    vars: &ConfigVars,
+) -> anyhow::Result<ObservabilityConfig> {
     // The logic in this method mimics the historical logic of loading observability options
     // This is left intact, since some of the existing deployments may rely on the this behavior.
-    let sentry_url = if let Ok(sentry_url) = std::env::var("MISC_SENTRY_URL") {
+    let sentry_url = if let Some(sentry_url) = vars.get("MISC_SENTRY_URL") {
         if sentry_url == "unset" {
             None
         } else {
-            Some(sentry_url)
+            Some(sentry_url.to_owned())
         }
     } else {
         None
     };
-    let sentry_environment = std::env::var("EN_SENTRY_ENVIRONMENT").ok().or_else(|| {
-        let l1_network = std::env::var("CHAIN_ETH_NETWORK").ok();
-        let l2_network = std::env::var("CHAIN_ETH_ZKSYNC_NETWORK").ok();
-        match (l1_network, l2_network) {
-            (Some(l1_network), Some(l2_network)) => {
-                Some(format!("{} - {}", l1_network, l2_network))
+    let sentry_environment = vars
+        .get("EN_SENTRY_ENVIRONMENT")
+        .map(str::to_owned)
+        .or_else(|| {
+            let l1_network = vars.get("CHAIN_ETH_NETWORK");
+            let l2_network = vars.get("CHAIN_ETH_ZKSYNC_NETWORK");
+            match (l1_network, l2_network) {
+                (Some(l1_network), Some(l2_network)) => {
+                    Some(format!("{} - {}", l1_network, l2_network))
+                }
+                _ => None,
             }
-            _ => None,
-        }
-    });
-    let log_format = if let Ok(log_format) = std::env::var("MISC_LOG_FORMAT") {
+        });
+    let log_format = if let Some(log_format) = vars.get("MISC_LOG_FORMAT") {
         if log_format != "plain" && log_format != "json" {
             anyhow::bail!("MISC_LOG_FORMAT has an unexpected value {}", log_format);
         }
-        log_format
+        log_format.to_owned()
     } else {
         "plain".to_string()
     };
@@ -1,11 +1,14 @@
 //! Tests for EN configuration.
 
+use zksync_config::configs::object_store::ObjectStoreMode;
+
 use super::*;
 
 #[test]
 fn parsing_optional_config_from_empty_env() {
     let config: OptionalENConfig = envy::prefixed("EN_").from_iter([]).unwrap();
     assert_eq!(config.filters_limit, 10_000);
+    assert_eq!(config.filters_limit_per_connection, None);
     assert_eq!(config.subscriptions_limit, 10_000);
     assert_eq!(config.fee_history_limit, 1_024);
     assert_eq!(config.polling_interval(), Duration::from_millis(200));
@@ -17,18 +20,82 @@ fn parsing_optional_config_from_empty_env() {
     assert_eq!(config.max_nonce_ahead, 50);
     assert_eq!(config.estimate_gas_scale_factor, 1.2);
     assert_eq!(config.vm_concurrency_limit, 2_048);
+    assert_eq!(config.trace_call_concurrency_limit, None);
     assert_eq!(config.factory_deps_cache_size(), 128 * BYTES_IN_MEGABYTE);
     assert_eq!(config.latest_values_cache_size(), 128 * BYTES_IN_MEGABYTE);
+    assert_eq!(config.merkle_tree_mode, MerkleTreeMode::Lightweight);
     assert_eq!(config.merkle_tree_multi_get_chunk_size, 500);
     assert_eq!(
         config.merkle_tree_block_cache_size(),
         128 * BYTES_IN_MEGABYTE
     );
     assert_eq!(config.max_response_body_size(), 10 * BYTES_IN_MEGABYTE);
+    assert_eq!(config.max_request_body_size(), 10 * BYTES_IN_MEGABYTE);
+    assert_eq!(
+        config.merkle_tree_stalled_writes_timeout().unwrap(),
+        Duration::from_secs(30)
+    );
     assert_eq!(
         config.l1_batch_commit_data_generator_mode,
         L1BatchCommitDataGeneratorMode::Rollup
     );
+    assert_eq!(config.call_traces_sampling_rate().unwrap(), 1.0);
+    assert_eq!(config.healthcheck_initializing_status_code, 503);
+    assert!(config.healthcheck_excluded_components.is_empty());
+    assert_eq!(config.state_keeper_db_compaction_interval().unwrap(), None);
+    assert_eq!(
+        config.state_keeper_db_options(),
+        StateKeeperRocksdbOptions::default()
+    );
+    assert_eq!(config.rocksdb_memory_budget().unwrap(), None);
+    assert!(!config.rocksdb_statistics_enabled);
+    assert_eq!(config.merkle_tree_checkpoint_interval, None);
+    assert!(!config.commitment_generator_local_verification);
+    assert!(config.fee_address_migration_enabled);
+    assert!(!config.read_only_on_consistency_failure);
+    assert_eq!(
+        config.consistency_checker_max_batches_to_recheck().unwrap(),
+        10
+    );
+    assert_eq!(config.reorg_handling_mode, ReorgHandlingMode::Rollback);
+    assert_eq!(
+        config.reorg_detector_poll_interval().unwrap(),
+        Duration::from_secs(5)
+    );
+    assert_eq!(config.database_replica_max_l1_batch_lag, 1);
+    assert_eq!(config.min_polling_interval(), Duration::from_millis(50));
+    assert_eq!(
+        config.mempool_cache_update_interval(),
+        Duration::from_millis(50)
+    );
+    assert_eq!(config.l1_batch_finality_delay(), Duration::ZERO);
+    assert_eq!(config.batch_status_updater_max_finality_lag, None);
+    assert_eq!(config.api_contracts_reload_interval().unwrap(), None);
+    assert_eq!(
+        config.state_hash_max_poll_interval().unwrap(),
+        Duration::from_secs(1)
+    );
+    assert!(!config.state_keeper_tree_state_hash_fallback);
+    assert_eq!(config.shutdown_timeout(), Duration::from_secs(30));
+    assert_eq!(config.snapshots_creator_interval().unwrap(), None);
+    assert!(config.prometheus_exporter_config().unwrap().is_none());
+    let client_config = config.main_node_client_config().unwrap();
+    assert_eq!(client_config.request_timeout, Duration::from_secs(30));
+    assert_eq!(client_config.connect_timeout, Duration::from_secs(10));
+    assert_eq!(client_config.tcp_keepalive, Some(Duration::from_secs(30)));
+    assert!(!client_config.compress_responses);
+    assert_eq!(client_config.retries.max_retries, 0);
+    assert_eq!(client_config.retries.base_delay, Duration::from_millis(100));
+    let polling_config = config.sync_state_polling_config().unwrap();
+    assert_eq!(polling_config.interval, time::Duration::milliseconds(500));
+    assert_eq!(polling_config.timeout, time::Duration::seconds(5));
+    assert_eq!(polling_config.max_consecutive_failures.get(), 3);
+    assert_eq!(polling_config.idle_timeout, None);
+    assert!(!polling_config.abort_on_idle);
+    assert_eq!(
+        config.postgres_metrics_scraping_interval().unwrap(),
+        Some(Duration::from_secs(60))
+    );
 }
 
 #[test]
@@ -36,6 +103,7 @@ fn parsing_optional_config_from_env() {
     let env_vars = [
         ("EN_FILTERS_DISABLED", "true"),
         ("EN_FILTERS_LIMIT", "5000"),
+        ("EN_FILTERS_LIMIT_PER_CONNECTION", "100"),
         ("EN_SUBSCRIPTIONS_LIMIT", "20000"),
         ("EN_FEE_HISTORY_LIMIT", "1000"),
         ("EN_PUBSUB_POLLING_INTERVAL", "500"),
@@ -44,12 +112,55 @@ fn parsing_optional_config_from_env() {
         ("EN_MAX_NONCE_AHEAD", "100"),
         ("EN_ESTIMATE_GAS_SCALE_FACTOR", "1.5"),
         ("EN_VM_CONCURRENCY_LIMIT", "1000"),
+        ("EN_TRACE_CALL_CONCURRENCY_LIMIT", "10"),
         ("EN_FACTORY_DEPS_CACHE_SIZE_MB", "64"),
         ("EN_LATEST_VALUES_CACHE_SIZE_MB", "50"),
+        ("EN_MERKLE_TREE_MODE", "full"),
         ("EN_MERKLE_TREE_MULTI_GET_CHUNK_SIZE", "1000"),
         ("EN_MERKLE_TREE_BLOCK_CACHE_SIZE_MB", "32"),
         ("EN_MAX_RESPONSE_BODY_SIZE_MB", "1"),
+        ("EN_MAX_REQUEST_BODY_SIZE_MB", "2"),
         ("EN_L1_BATCH_COMMIT_DATA_GENERATOR_MODE", "Validium"),
+        ("EN_CALL_TRACES_SAMPLING_RATE", "0.25"),
+        ("EN_HEALTHCHECK_INITIALIZING_STATUS_CODE", "425"),
+        ("EN_STATE_KEEPER_DB_COMPACTION_INTERVAL_SEC", "3600"),
+        ("EN_STATE_KEEPER_DB_WRITE_BUFFER_SIZE_MB", "32"),
+        ("EN_STATE_KEEPER_DB_MAX_BACKGROUND_JOBS", "4"),
+        ("EN_STATE_KEEPER_DB_COMPACTION_STYLE", "universal"),
+        ("EN_ROCKSDB_MEMORY_BUDGET_MB", "2048"),
+        ("EN_ROCKSDB_STATISTICS_ENABLED", "true"),
+        ("EN_MERKLE_TREE_CHECKPOINT_INTERVAL", "100"),
+        ("EN_COMMITMENT_GENERATOR_LOCAL_VERIFICATION", "true"),
+        ("EN_FEE_ADDRESS_MIGRATION_ENABLED", "false"),
+        ("EN_POSTGRES_METRICS_SCRAPING_INTERVAL_SEC", "300"),
+        ("EN_READ_ONLY_ON_CONSISTENCY_FAILURE", "true"),
+        ("EN_CONSISTENCY_CHECKER_MAX_BATCHES_TO_RECHECK", "3"),
+        ("EN_REORG_HANDLING_MODE", "observe"),
+        ("EN_REORG_DETECTOR_POLL_INTERVAL_MS", "1500"),
+        ("EN_DATABASE_REPLICA_MAX_L1_BATCH_LAG", "3"),
+        ("EN_MIN_POLLING_INTERVAL_MS", "250"),
+        ("EN_L1_BATCH_FINALITY_DELAY_SEC", "600"),
+        ("EN_BATCH_STATUS_UPDATER_MAX_FINALITY_LAG", "20"),
+        ("EN_API_CONTRACTS_RELOAD_INTERVAL_SEC", "60"),
+        ("EN_STATE_HASH_MAX_POLL_INTERVAL_MS", "500"),
+        ("EN_STATE_KEEPER_TREE_STATE_HASH_FALLBACK", "true"),
+        ("EN_SHUTDOWN_TIMEOUT_MS", "5000"),
+        ("EN_SNAPSHOTS_CREATOR_INTERVAL_SEC", "3600"),
+        ("EN_MAIN_NODE_REQUEST_TIMEOUT_SEC", "10"),
+        ("EN_MAIN_NODE_CONNECT_TIMEOUT_SEC", "3"),
+        ("EN_MAIN_NODE_TCP_KEEPALIVE_SEC", "0"),
+        ("EN_MAIN_NODE_RESPONSE_COMPRESSION", "true"),
+        ("EN_MAIN_NODE_REQUEST_MAX_RETRIES", "3"),
+        ("EN_MAIN_NODE_REQUEST_RETRY_BASE_DELAY_MS", "250"),
+        ("EN_SYNC_STATE_POLLING_INTERVAL_MS", "1000"),
+        ("EN_SYNC_STATE_POLLING_TIMEOUT_MS", "2000"),
+        ("EN_SYNC_STATE_POLLING_MAX_CONSECUTIVE_FAILURES", "5"),
+        ("EN_MAIN_NODE_IDLE_TIMEOUT_SEC", "300"),
+        ("EN_ABORT_ON_MAIN_NODE_IDLE", "true"),
+        (
+            "EN_HEALTHCHECK_EXCLUDED_COMPONENTS",
+            "consistency_checker,reorg_detector",
+        ),
     ];
     let env_vars = env_vars
         .into_iter()
@@ -58,6 +169,7 @@ fn parsing_optional_config_from_env() {
     let config: OptionalENConfig = envy::prefixed("EN_").from_iter(env_vars).unwrap();
     assert!(config.filters_disabled);
     assert_eq!(config.filters_limit, 5_000);
+    assert_eq!(config.filters_limit_per_connection, Some(100));
     assert_eq!(config.subscriptions_limit, 20_000);
     assert_eq!(config.fee_history_limit, 1_000);
     assert_eq!(config.polling_interval(), Duration::from_millis(500));
@@ -69,16 +181,674 @@ fn parsing_optional_config_from_env() {
     assert_eq!(config.max_nonce_ahead, 100);
     assert_eq!(config.estimate_gas_scale_factor, 1.5);
     assert_eq!(config.vm_concurrency_limit, 1_000);
+    assert_eq!(config.trace_call_concurrency_limit, NonZeroUsize::new(10));
     assert_eq!(config.factory_deps_cache_size(), 64 * BYTES_IN_MEGABYTE);
     assert_eq!(config.latest_values_cache_size(), 50 * BYTES_IN_MEGABYTE);
+    assert_eq!(config.merkle_tree_mode, MerkleTreeMode::Full);
     assert_eq!(config.merkle_tree_multi_get_chunk_size, 1_000);
     assert_eq!(
         config.merkle_tree_block_cache_size(),
         32 * BYTES_IN_MEGABYTE
     );
     assert_eq!(config.max_response_body_size(), BYTES_IN_MEGABYTE);
+    assert_eq!(config.max_request_body_size(), 2 * BYTES_IN_MEGABYTE);
     assert_eq!(
         config.l1_batch_commit_data_generator_mode,
         L1BatchCommitDataGeneratorMode::Validium
     );
+    assert_eq!(config.call_traces_sampling_rate().unwrap(), 0.25);
+    assert_eq!(config.healthcheck_initializing_status_code, 425);
+    assert_eq!(
+        config.state_keeper_db_compaction_interval().unwrap(),
+        Some(Duration::from_secs(3600))
+    );
+    assert_eq!(
+        config.state_keeper_db_options(),
+        StateKeeperRocksdbOptions {
+            write_buffer_size: Some(32 * BYTES_IN_MEGABYTE),
+            max_background_jobs: NonZeroU32::new(4),
+            compaction_style: Some(RocksdbCompactionStyle::Universal),
+            memory_budget: None,
+            collect_statistics: true,
+        }
+    );
+    assert_eq!(
+        config.rocksdb_memory_budget().unwrap(),
+        Some(2_048 * BYTES_IN_MEGABYTE)
+    );
+    assert_eq!(config.merkle_tree_checkpoint_interval, NonZeroU32::new(100));
+    assert!(config.commitment_generator_local_verification);
+    assert!(!config.fee_address_migration_enabled);
+    assert_eq!(
+        config.postgres_metrics_scraping_interval().unwrap(),
+        Some(Duration::from_secs(300))
+    );
+    assert!(config.read_only_on_consistency_failure);
+    assert_eq!(
+        config.consistency_checker_max_batches_to_recheck().unwrap(),
+        3
+    );
+    assert_eq!(config.reorg_handling_mode, ReorgHandlingMode::Observe);
+    assert_eq!(
+        config.reorg_detector_poll_interval().unwrap(),
+        Duration::from_millis(1_500)
+    );
+    assert_eq!(config.database_replica_max_l1_batch_lag, 3);
+    assert_eq!(config.min_polling_interval(), Duration::from_millis(250));
+    assert_eq!(config.l1_batch_finality_delay(), Duration::from_secs(600));
+    assert_eq!(
+        config.batch_status_updater_max_finality_lag,
+        NonZeroU32::new(20)
+    );
+    assert_eq!(
+        config.api_contracts_reload_interval().unwrap(),
+        Some(Duration::from_secs(60))
+    );
+    assert_eq!(
+        config.state_hash_max_poll_interval().unwrap(),
+        Duration::from_millis(500)
+    );
+    assert!(config.state_keeper_tree_state_hash_fallback);
+    assert_eq!(config.shutdown_timeout(), Duration::from_secs(5));
+    assert_eq!(
+        config.snapshots_creator_interval().unwrap(),
+        Some(Duration::from_secs(3_600))
+    );
+    let client_config = config.main_node_client_config().unwrap();
+    assert_eq!(client_config.request_timeout, Duration::from_secs(10));
+    assert_eq!(client_config.connect_timeout, Duration::from_secs(3));
+    assert_eq!(client_config.tcp_keepalive, None);
+    assert!(client_config.compress_responses);
+    assert_eq!(client_config.retries.max_retries, 3);
+    assert_eq!(client_config.retries.base_delay, Duration::from_millis(250));
+    let polling_config = config.sync_state_polling_config().unwrap();
+    assert_eq!(polling_config.interval, time::Duration::seconds(1));
+    assert_eq!(polling_config.timeout, time::Duration::seconds(2));
+    assert_eq!(polling_config.max_consecutive_failures.get(), 5);
+    assert_eq!(
+        polling_config.idle_timeout,
+        Some(time::Duration::minutes(5))
+    );
+    assert!(polling_config.abort_on_idle);
+    assert_eq!(
+        config.healthcheck_excluded_components,
+        ["consistency_checker", "reorg_detector"]
+    );
+}
+
+#[test]
+fn clamping_intervals_to_min_polling_interval() {
+    let env_vars = [
+        ("EN_MIN_POLLING_INTERVAL_MS", "300"),
+        ("EN_PUBSUB_POLLING_INTERVAL", "10"),
+        ("EN_MEMPOOL_CACHE_UPDATE_INTERVAL", "1"),
+        ("EN_REORG_DETECTOR_POLL_INTERVAL_MS", "100"),
+        ("EN_STATE_HASH_MAX_POLL_INTERVAL_MS", "5000"),
+        ("EN_SYNC_STATE_POLLING_INTERVAL_MS", "20"),
+    ];
+    let env_vars = env_vars
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value.to_owned()));
+    let config: OptionalENConfig = envy::prefixed("EN_").from_iter(env_vars).unwrap();
+
+    let min_interval = Duration::from_millis(300);
+    assert_eq!(config.polling_interval(), min_interval);
+    assert_eq!(config.mempool_cache_update_interval(), min_interval);
+    assert_eq!(config.reorg_detector_poll_interval().unwrap(), min_interval);
+    // Intervals exceeding the floor are left as is.
+    assert_eq!(
+        config.state_hash_max_poll_interval().unwrap(),
+        Duration::from_secs(5)
+    );
+    let polling_config = config.sync_state_polling_config().unwrap();
+    assert_eq!(polling_config.interval, time::Duration::milliseconds(300));
+
+    // Intervals specified in seconds are clamped as well.
+    let env_vars = [
+        ("EN_MIN_POLLING_INTERVAL_MS", "2000"),
+        ("EN_STATE_KEEPER_DB_COMPACTION_INTERVAL_SEC", "1"),
+        ("EN_POSTGRES_METRICS_SCRAPING_INTERVAL_SEC", "1"),
+    ];
+    let env_vars = env_vars
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value.to_owned()));
+    let config: OptionalENConfig = envy::prefixed("EN_").from_iter(env_vars).unwrap();
+
+    let min_interval = Duration::from_secs(2);
+    assert_eq!(
+        config.state_keeper_db_compaction_interval().unwrap(),
+        Some(min_interval)
+    );
+    assert_eq!(
+        config.postgres_metrics_scraping_interval().unwrap(),
+        Some(min_interval)
+    );
+}
+
+#[test]
+fn parsing_invalid_call_traces_sampling_rate() {
+    let env_vars = [("EN_CALL_TRACES_SAMPLING_RATE".to_owned(), "1.5".to_owned())];
+    let config: OptionalENConfig = envy::prefixed("EN_").from_iter(env_vars).unwrap();
+    let err = config.call_traces_sampling_rate().unwrap_err().to_string();
+    assert!(err.contains("call_traces_sampling_rate"), "{err}");
+
+    let mut en_config = ExternalNodeConfig::mock();
+    en_config.optional = config;
+    let err = InternalApiConfig::try_from(en_config)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("call_traces_sampling_rate"), "{err}");
+}
+
+#[test]
+fn rejecting_fifo_state_keeper_db_compaction_style() {
+    let env_vars = [(
+        "EN_STATE_KEEPER_DB_COMPACTION_STYLE".to_owned(),
+        "fifo".to_owned(),
+    )];
+    let err = envy::prefixed("EN_")
+        .from_iter::<_, OptionalENConfig>(env_vars)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("fifo"), "{err}");
+}
+
+#[test]
+fn parsing_invalid_merkle_tree_stalled_writes_timeout() {
+    for timeout_sec in ["0", "1", "3600"] {
+        let env_vars = [(
+            "EN_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC".to_owned(),
+            timeout_sec.to_owned(),
+        )];
+        let config: OptionalENConfig = envy::prefixed("EN_").from_iter(env_vars).unwrap();
+        let err = config
+            .merkle_tree_stalled_writes_timeout()
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("merkle_tree_stalled_writes_timeout_sec"),
+            "{err}"
+        );
+    }
+}
+
+#[test]
+fn rejecting_zero_sync_state_polling_max_consecutive_failures() {
+    let env_vars = [(
+        "EN_SYNC_STATE_POLLING_MAX_CONSECUTIVE_FAILURES".to_owned(),
+        "0".to_owned(),
+    )];
+    let config: OptionalENConfig = envy::prefixed("EN_").from_iter(env_vars).unwrap();
+    let err = config.sync_state_polling_config().unwrap_err().to_string();
+    assert!(
+        err.contains("sync_state_polling_max_consecutive_failures"),
+        "{err}"
+    );
+}
+
+#[test]
+fn parsing_postgres_metrics_scraping_interval() {
+    let env_vars = [(
+        "EN_POSTGRES_METRICS_SCRAPING_ENABLED".to_owned(),
+        "false".to_owned(),
+    )];
+    let config: OptionalENConfig = envy::prefixed("EN_").from_iter(env_vars).unwrap();
+    assert_eq!(config.postgres_metrics_scraping_interval().unwrap(), None);
+
+    let env_vars = [(
+        "EN_POSTGRES_METRICS_SCRAPING_INTERVAL_SEC".to_owned(),
+        "0".to_owned(),
+    )];
+    let config: OptionalENConfig = envy::prefixed("EN_").from_iter(env_vars).unwrap();
+    let err = config
+        .postgres_metrics_scraping_interval()
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("postgres_metrics_scraping_interval_sec"),
+        "{err}"
+    );
+
+    // The interval is not validated if scraping is disabled.
+    let env_vars = [
+        ("EN_POSTGRES_METRICS_SCRAPING_ENABLED", "false"),
+        ("EN_POSTGRES_METRICS_SCRAPING_INTERVAL_SEC", "0"),
+    ];
+    let env_vars = env_vars
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value.to_owned()));
+    let config: OptionalENConfig = envy::prefixed("EN_").from_iter(env_vars).unwrap();
+    assert_eq!(config.postgres_metrics_scraping_interval().unwrap(), None);
+}
+
+#[test]
+fn rejecting_zero_state_keeper_db_compaction_interval() {
+    let env_vars = [(
+        "EN_STATE_KEEPER_DB_COMPACTION_INTERVAL_SEC".to_owned(),
+        "0".to_owned(),
+    )];
+    let config: OptionalENConfig = envy::prefixed("EN_").from_iter(env_vars).unwrap();
+    let err = config
+        .state_keeper_db_compaction_interval()
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("state_keeper_db_compaction_interval_sec"),
+        "{err}"
+    );
+}
+
+#[test]
+fn rejecting_zero_trace_call_concurrency_limit() {
+    let env_vars = [("EN_TRACE_CALL_CONCURRENCY_LIMIT".to_owned(), "0".to_owned())];
+    let err = envy::prefixed("EN_")
+        .from_iter::<_, OptionalENConfig>(env_vars)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("nonzero"), "{err}");
+}
+
+#[test]
+fn rejecting_zero_consistency_checker_max_batches_to_recheck() {
+    let env_vars = [(
+        "EN_CONSISTENCY_CHECKER_MAX_BATCHES_TO_RECHECK".to_owned(),
+        "0".to_owned(),
+    )];
+    let config: OptionalENConfig = envy::prefixed("EN_").from_iter(env_vars).unwrap();
+    let err = config
+        .consistency_checker_max_batches_to_recheck()
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("consistency_checker_max_batches_to_recheck"),
+        "{err}"
+    );
+}
+
+#[test]
+fn parsing_enum_index_migration_chunk_size() {
+    let config: OptionalENConfig = envy::prefixed("EN_").from_iter([]).unwrap();
+    assert_eq!(config.enum_index_migration_chunk_size().unwrap(), 5_000);
+
+    for invalid_value in ["0", "1000000"] {
+        let env_vars = [(
+            "EN_ENUM_INDEX_MIGRATION_CHUNK_SIZE".to_owned(),
+            invalid_value.to_owned(),
+        )];
+        let config: OptionalENConfig = envy::prefixed("EN_").from_iter(env_vars).unwrap();
+        let err = config
+            .enum_index_migration_chunk_size()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("enum_index_migration_chunk_size"), "{err}");
+    }
+}
+
+const CONFIG_YAML: &str = r#"
+EN_HTTP_PORT: 3060
+EN_WS_PORT: 3061
+EN_HEALTHCHECK_PORT: 3081
+EN_ETH_CLIENT_URL: http://127.0.0.1:8545/
+EN_MAIN_NODE_URL: https://mainnet.era.zksync.io/
+EN_STATE_CACHE_PATH: /db/state_keeper
+EN_MERKLE_TREE_PATH: /db/tree
+EN_FILTERS_DISABLED: true
+EN_ESTIMATE_GAS_SCALE_FACTOR: 1.5
+EN_HEALTHCHECK_EXCLUDED_COMPONENTS: [consistency_checker, reorg_detector]
+DATABASE_URL: postgres://postgres@localhost/en
+DATABASE_POOL_SIZE: 50
+"#;
+
+const CONFIG_ENV_VARS: [(&str, &str); 12] = [
+    ("EN_HTTP_PORT", "3060"),
+    ("EN_WS_PORT", "3061"),
+    ("EN_HEALTHCHECK_PORT", "3081"),
+    ("EN_ETH_CLIENT_URL", "http://127.0.0.1:8545/"),
+    ("EN_MAIN_NODE_URL", "https://mainnet.era.zksync.io/"),
+    ("EN_STATE_CACHE_PATH", "/db/state_keeper"),
+    ("EN_MERKLE_TREE_PATH", "/db/tree"),
+    ("EN_FILTERS_DISABLED", "true"),
+    ("EN_ESTIMATE_GAS_SCALE_FACTOR", "1.5"),
+    (
+        "EN_HEALTHCHECK_EXCLUDED_COMPONENTS",
+        "consistency_checker,reorg_detector",
+    ),
+    ("DATABASE_URL", "postgres://postgres@localhost/en"),
+    ("DATABASE_POOL_SIZE", "50"),
+];
+
+fn to_owned_vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+    vars.iter()
+        .map(|&(name, value)| (name.to_owned(), value.to_owned()))
+        .collect()
+}
+
+#[test]
+fn loading_config_from_yaml_and_env_vars() {
+    let file_vars = ConfigVars::from_yaml(CONFIG_YAML).unwrap();
+    let env_vars = ConfigVars::default().with_overrides(to_owned_vars(&CONFIG_ENV_VARS));
+    assert_eq!(file_vars, env_vars);
+
+    let required: RequiredENConfig = file_vars.deserialize_prefixed("EN_").unwrap();
+    assert_eq!(
+        required,
+        env_vars
+            .deserialize_prefixed::<RequiredENConfig>("EN_")
+            .unwrap()
+    );
+    assert_eq!(required.http_port, 3060);
+    assert_eq!(required.merkle_tree_path, "/db/tree");
+
+    let optional: OptionalENConfig = file_vars.deserialize_prefixed("EN_").unwrap();
+    assert_eq!(
+        optional,
+        env_vars
+            .deserialize_prefixed::<OptionalENConfig>("EN_")
+            .unwrap()
+    );
+    assert!(optional.filters_disabled);
+    assert_eq!(optional.estimate_gas_scale_factor, 1.5);
+    assert_eq!(
+        optional.healthcheck_excluded_components,
+        ["consistency_checker", "reorg_detector"]
+    );
+
+    let postgres = PostgresConfig::from_vars(&file_vars).unwrap();
+    assert_eq!(postgres, PostgresConfig::from_vars(&env_vars).unwrap());
+    assert_eq!(postgres.max_connections, 50);
+}
+
+#[test]
+fn env_vars_override_config_file() {
+    let vars = ConfigVars::from_yaml(CONFIG_YAML)
+        .unwrap()
+        .with_overrides(to_owned_vars(&[
+            ("EN_HTTP_PORT", "4060"),
+            ("DATABASE_POOL_SIZE", "10"),
+        ]));
+
+    let required: RequiredENConfig = vars.deserialize_prefixed("EN_").unwrap();
+    assert_eq!(required.http_port, 4060);
+    assert_eq!(required.ws_port, 3061);
+    let postgres = PostgresConfig::from_vars(&vars).unwrap();
+    assert_eq!(postgres.max_connections, 10);
+    assert_eq!(postgres.database_url, "postgres://postgres@localhost/en");
+}
+
+#[test]
+fn loading_auxiliary_configs_from_config_file() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let secrets_path = temp_dir.path().join("secrets.yaml");
+    fs::write(&secrets_path, "{}").unwrap();
+    let config_yaml = format!(
+        "{CONFIG_YAML}\
+         EN_CONSENSUS_SECRETS_PATH: {secrets_path}\n\
+         EN_SNAPSHOTS_CREATOR_CONCURRENT_QUERIES_COUNT: 5\n\
+         EN_SNAPSHOTS_OBJECT_STORE_MODE: FileBacked\n\
+         EN_SNAPSHOTS_OBJECT_STORE_FILE_BACKED_BASE_PATH: /db/snapshots\n\
+         MISC_LOG_FORMAT: json\n\
+         MISC_SENTRY_URL: https://sentry.example.com/\n",
+        secrets_path = secrets_path.display()
+    );
+    let config_path = temp_dir.path().join("config.yaml");
+    fs::write(&config_path, config_yaml).unwrap();
+
+    let vars = ConfigVars::new(Some(&config_path)).unwrap();
+    let observability_config = observability::observability_config_from_vars(&vars).unwrap();
+    assert_eq!(observability_config.log_format, "json");
+    assert_eq!(
+        observability_config.sentry_url.as_deref(),
+        Some("https://sentry.example.com/")
+    );
+
+    let mut config = ExternalNodeConfig::mock();
+    config.vars = vars;
+    let secrets = config.consensus_secrets().unwrap();
+    assert!(secrets.is_some());
+
+    let expected_object_store_mode = ObjectStoreMode::FileBacked {
+        file_backed_base_path: "/db/snapshots".to_owned(),
+    };
+    let recovery_config = config.snapshots_recovery_config().unwrap();
+    assert_eq!(
+        recovery_config.snapshots_object_store.mode,
+        expected_object_store_mode
+    );
+    let creation_config = config.snapshots_creation_config().unwrap();
+    assert_eq!(creation_config.creator.concurrent_queries_count, 5);
+    assert_eq!(
+        creation_config.snapshots_object_store.mode,
+        expected_object_store_mode
+    );
+}
+
+#[test]
+fn validating_rocksdb_paths() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let root = temp_dir.path().to_str().unwrap();
+    let validate = |state_cache_path: &str, merkle_tree_path: &str| {
+        let vars = ConfigVars::from_yaml(CONFIG_YAML)
+            .unwrap()
+            .with_overrides(to_owned_vars(&[
+                ("EN_STATE_CACHE_PATH", &format!("{root}{state_cache_path}")),
+                ("EN_MERKLE_TREE_PATH", &format!("{root}{merkle_tree_path}")),
+            ]));
+        let required: RequiredENConfig = vars.deserialize_prefixed("EN_").unwrap();
+        required.validate_rocksdb_paths()
+    };
+
+    validate("/db/state_keeper", "/db/tree").unwrap();
+    fs::create_dir_all(temp_dir.path().join("links")).unwrap();
+    std::os::unix::fs::symlink(
+        temp_dir.path().join("db/state_keeper"),
+        temp_dir.path().join("links/state_keeper"),
+    )
+    .unwrap();
+
+    let invalid_paths = [
+        ("/db/state_keeper", "/db/state_keeper"),
+        ("/db/state_keeper", "/db/./state_keeper/"),
+        ("/db/state_keeper", "/db/tree/../state_keeper"),
+        ("/db/state_keeper", "/links/state_keeper"),
+        ("/db", "/db/tree"),
+        ("/db/state_keeper/tree", "/db/state_keeper"),
+    ];
+    for (state_cache_path, merkle_tree_path) in invalid_paths {
+        let err = validate(state_cache_path, merkle_tree_path)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("distinct"), "{err}");
+    }
+}
+
+#[test]
+fn api_uses_database_replica_if_configured() {
+    let vars = ConfigVars::from_yaml(CONFIG_YAML).unwrap();
+    let postgres = PostgresConfig::from_vars(&vars).unwrap();
+    assert_eq!(postgres.database_replica_url, None);
+    assert_eq!(
+        postgres.api_database_url(),
+        "postgres://postgres@localhost/en"
+    );
+
+    let vars = vars.with_overrides(to_owned_vars(&[(
+        "DATABASE_REPLICA_URL",
+        "postgres://postgres@replica/en",
+    )]));
+    let postgres = PostgresConfig::from_vars(&vars).unwrap();
+    assert_eq!(postgres.database_url, "postgres://postgres@localhost/en");
+    assert_eq!(
+        postgres.api_database_url(),
+        "postgres://postgres@replica/en"
+    );
+    // The replica pool has the same size as the primary one by default.
+    assert_eq!(postgres.replica_max_connections(), postgres.max_connections);
+
+    let vars = vars.with_overrides(to_owned_vars(&[("DATABASE_REPLICA_POOL_SIZE", "20")]));
+    let postgres = PostgresConfig::from_vars(&vars).unwrap();
+    assert_eq!(postgres.max_connections, 50);
+    assert_eq!(postgres.replica_max_connections(), 20);
+}
+
+#[test]
+fn parsing_prometheus_exporter_config() {
+    fn parse(env_vars: &[(&str, &str)]) -> OptionalENConfig {
+        let env_vars = env_vars
+            .iter()
+            .map(|&(name, value)| (name.to_owned(), value.to_owned()));
+        envy::prefixed("EN_").from_iter(env_vars).unwrap()
+    }
+
+    let config = parse(&[("EN_PROMETHEUS_PORT", "3322")]);
+    let exporter_config = config.prometheus_exporter_config().unwrap().unwrap();
+    let exporter_config = format!("{exporter_config:?}");
+    assert!(
+        exporter_config.contains("Pull { port: 3322 }"),
+        "{exporter_config}"
+    );
+
+    let config = parse(&[(
+        "EN_PROMETHEUS_PUSHGATEWAY_URL",
+        "http://gateway:9091/metrics/job/en",
+    )]);
+    let exporter_config = config.prometheus_exporter_config().unwrap().unwrap();
+    let exporter_config = format!("{exporter_config:?}");
+    assert!(exporter_config.contains("Push"), "{exporter_config}");
+    assert!(
+        exporter_config.contains("http://gateway:9091/metrics/job/en"),
+        "{exporter_config}"
+    );
+    assert!(exporter_config.contains("10s"), "{exporter_config}");
+
+    let config = parse(&[
+        (
+            "EN_PROMETHEUS_PUSHGATEWAY_URL",
+            "http://gateway:9091/metrics/job/en",
+        ),
+        ("EN_PROMETHEUS_PUSH_INTERVAL_MS", "500"),
+    ]);
+    let exporter_config = config.prometheus_exporter_config().unwrap().unwrap();
+    let exporter_config = format!("{exporter_config:?}");
+    assert!(exporter_config.contains("500ms"), "{exporter_config}");
+
+    let config = parse(&[("EN_PROMETHEUS_PUSH_INTERVAL_MS", "500")]);
+    let err = config.prometheus_exporter_config().unwrap_err().to_string();
+    assert!(err.contains("prometheus_pushgateway_url"), "{err}");
+
+    let config = parse(&[
+        ("EN_PROMETHEUS_PORT", "3322"),
+        (
+            "EN_PROMETHEUS_PUSHGATEWAY_URL",
+            "http://gateway:9091/metrics/job/en",
+        ),
+    ]);
+    let err = config.prometheus_exporter_config().unwrap_err().to_string();
+    assert!(err.contains("cannot be set simultaneously"), "{err}");
+
+    let config = parse(&[
+        (
+            "EN_PROMETHEUS_PUSHGATEWAY_URL",
+            "http://gateway:9091/metrics/job/en",
+        ),
+        ("EN_PROMETHEUS_PUSH_INTERVAL_MS", "0"),
+    ]);
+    let err = config.prometheus_exporter_config().unwrap_err().to_string();
+    assert!(err.contains("prometheus_push_interval_ms"), "{err}");
+}
+
+#[test]
+fn rejecting_unsupported_config_file_values() {
+    let err = ConfigVars::from_yaml("EN_HTTP_PORT:\n  nested: 3060").unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("EN_HTTP_PORT"), "{err}");
+}
+
+#[test]
+fn reporting_node_components() {
+    let vars = ConfigVars::from_yaml(CONFIG_YAML).unwrap();
+    let mut config = ExternalNodeConfig {
+        required: vars.deserialize_prefixed("EN_").unwrap(),
+        postgres: PostgresConfig::from_vars(&vars).unwrap(),
+        optional: vars.deserialize_prefixed("EN_").unwrap(),
+        remote: RemoteENConfig::mock(),
+        consensus: None,
+        vars,
+    };
+    config.optional.state_keeper_db_disabled = true;
+    config.optional.prometheus_port = Some(3322);
+
+    let components = config.components();
+    assert!(components.contains(&"prometheus_exporter"));
+    assert!(!components.contains(&"state_keeper_cache"));
+    assert!(!components.contains(&"consensus"));
+    assert!(!components.contains(&"database_replica"));
+
+    let api_config = InternalApiConfig::try_from(config).unwrap();
+    assert_eq!(api_config.node_components, components);
+    assert!(!api_config.consensus_enabled);
+    assert_eq!(api_config.l2_chain_id, L2ChainId::from(270));
+}
+
+#[test]
+fn validating_consensus_without_config() {
+    let config = ExternalNodeConfig::mock();
+    assert!(config.consensus.is_none());
+    let err = config.validate_consensus(None).unwrap_err().to_string();
+    assert!(err.contains("config.consensus"), "{err}");
+    assert!(err.contains("EN_CONSENSUS_CONFIG_PATH"), "{err}");
+}
+
+const CONSENSUS_CONFIG_YAML: &str = r#"
+server_addr: '127.0.0.1:3055'
+public_addr: '127.0.0.1:3055'
+validators:
+- 'validator:public:bn254:8b0ff0ad1a250e64b0209277148ccee3b64534d8fa60cf25ba0bcc8b65d4d89309cdae79197c2db873d351401093fa0542a5a2071c1a247f2e1abe56d08cbabb'
+max_payload_size: 5000000
+gossip_dynamic_inbound_limit: 0
+"#;
+
+#[test]
+fn validating_consensus_without_secrets() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let consensus_config_path = temp_dir.path().join("consensus_config.yaml");
+    fs::write(&consensus_config_path, CONSENSUS_CONFIG_YAML).unwrap();
+    let vars = ConfigVars::from_yaml(&format!(
+        "EN_CONSENSUS_CONFIG_PATH: {}\n",
+        consensus_config_path.display()
+    ))
+    .unwrap();
+
+    let mut config = ExternalNodeConfig::mock();
+    config.consensus = read_consensus_config(&vars).unwrap();
+    assert!(config.consensus.is_some());
+    config.vars = vars;
+    let secrets = config.consensus_secrets().unwrap();
+    assert!(secrets.is_none());
+
+    let err = config
+        .validate_consensus(secrets.as_ref())
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("consensus secrets"), "{err}");
+    assert!(err.contains("EN_CONSENSUS_SECRETS_PATH"), "{err}");
+}
+
+#[test]
+fn checking_chain_ids() {
+    let remote = RemoteENConfig::mock();
+    remote
+        .check_chain_ids(L2ChainId::from(270), L1ChainId(9))
+        .unwrap();
+
+    let err = remote
+        .check_chain_ids(L2ChainId::from(271), L1ChainId(9))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("L2 chain ID"), "{err}");
+
+    let err = remote
+        .check_chain_ids(L2ChainId::from(270), L1ChainId(5))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("L1 chain ID"), "{err}");
 }
@@ -0,0 +1,123 @@
+//! Operator confirmation for destructive EN operations (e.g., rolling back L1 batches).
+
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use zksync_basic_types::L1BatchNumber;
+
+/// Source of operator input used to confirm destructive operations. Abstracted so that confirmation logic
+/// can be tested without a real terminal.
+pub(crate) trait ConfirmationPrompt {
+    /// Shows the `message` to the operator and reads their response. Returns `None` if the response
+    /// cannot be read interactively (e.g., stdin is not attached to a terminal).
+    fn read_response(&mut self, message: &str) -> anyhow::Result<Option<String>>;
+}
+
+/// Prompt reading operator responses from stdin if it is attached to a terminal.
+#[derive(Debug)]
+pub(crate) struct TerminalPrompt;
+
+impl ConfirmationPrompt for TerminalPrompt {
+    fn read_response(&mut self, message: &str) -> anyhow::Result<Option<String>> {
+        let stdin = io::stdin();
+        if !stdin.is_terminal() {
+            return Ok(None);
+        }
+
+        let mut stderr = io::stderr();
+        write!(stderr, "{message}: ")?;
+        stderr.flush()?;
+        let mut response = String::new();
+        stdin.lock().read_line(&mut response)?;
+        Ok(Some(response.trim().to_owned()))
+    }
+}
+
+/// Asks the operator to confirm a rollback to `target_l1_batch` by typing the target batch number.
+/// The confirmation is skipped if `skip_confirmation` is set (e.g., via the `--yes` command-line flag).
+pub(crate) fn confirm_rollback(
+    prompt: &mut dyn ConfirmationPrompt,
+    skip_confirmation: bool,
+    operation: &str,
+    target_l1_batch: L1BatchNumber,
+) -> anyhow::Result<()> {
+    if skip_confirmation {
+        tracing::info!("Skipping confirmation for {operation} to L1 batch #{target_l1_batch}");
+        return Ok(());
+    }
+
+    let message = format!(
+        "{operation} will irreversibly remove all data after L1 batch #{target_l1_batch}. \
+         Type the target L1 batch number to confirm"
+    );
+    let Some(response) = prompt.read_response(&message)? else {
+        anyhow::bail!(
+            "Cannot confirm {operation} since stdin is not a terminal; \
+             pass `--yes` to proceed without confirmation"
+        );
+    };
+    anyhow::ensure!(
+        response == target_l1_batch.0.to_string(),
+        "{operation} aborted: confirmation `{response}` doesn't match the target L1 batch #{target_l1_batch}"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct MockPrompt {
+        response: Option<&'static str>,
+        call_count: usize,
+    }
+
+    impl MockPrompt {
+        fn new(response: Option<&'static str>) -> Self {
+            Self {
+                response,
+                call_count: 0,
+            }
+        }
+    }
+
+    impl ConfirmationPrompt for MockPrompt {
+        fn read_response(&mut self, message: &str) -> anyhow::Result<Option<String>> {
+            assert!(message.contains("#42"), "{message}");
+            self.call_count += 1;
+            Ok(self.response.map(str::to_owned))
+        }
+    }
+
+    #[test]
+    fn skipping_confirmation() {
+        let mut prompt = MockPrompt::new(None);
+        confirm_rollback(&mut prompt, true, "Rollback", L1BatchNumber(42)).unwrap();
+        assert_eq!(prompt.call_count, 0);
+    }
+
+    #[test]
+    fn confirming_rollback() {
+        let mut prompt = MockPrompt::new(Some("42"));
+        confirm_rollback(&mut prompt, false, "Rollback", L1BatchNumber(42)).unwrap();
+        assert_eq!(prompt.call_count, 1);
+    }
+
+    #[test]
+    fn aborting_rollback_on_wrong_confirmation() {
+        let mut prompt = MockPrompt::new(Some("43"));
+        let err = confirm_rollback(&mut prompt, false, "Rollback", L1BatchNumber(42))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("aborted"), "{err}");
+    }
+
+    #[test]
+    fn aborting_rollback_without_terminal() {
+        let mut prompt = MockPrompt::new(None);
+        let err = confirm_rollback(&mut prompt, false, "Rollback", L1BatchNumber(42))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("--yes"), "{err}");
+    }
+}
@@ -1,7 +1,14 @@
 //! Miscellaneous helpers for the EN.
 
+use std::{future::Future, time::Duration};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_core::sync_layer::MainNodeClient;
 use zksync_health_check::{async_trait, CheckHealth, Health, HealthStatus};
-use zksync_web3_decl::{jsonrpsee::http_client::HttpClient, namespaces::EthNamespaceClient};
+use zksync_web3_decl::{
+    error::EnrichedClientError, jsonrpsee::http_client::HttpClient, namespaces::EthNamespaceClient,
+};
 
 /// Main node health check.
 #[derive(Debug)]
@@ -30,3 +37,306 @@ impl CheckHealth for MainNodeHealthCheck {
         HealthStatus::Ready.into()
     }
 }
+
+/// Policy for retrying calls to remote JSON-RPC nodes (the main node and the L1 node) during node startup.
+/// Only [transient](EnrichedClientError::is_transient()) client errors (e.g., failing to resolve the node host)
+/// are retried; other errors are returned immediately.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RpcRetryPolicy {
+    max_retries: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for RpcRetryPolicy {
+    /// Returns the default policy, which survives a network outage on the order of a few minutes.
+    fn default() -> Self {
+        Self {
+            max_retries: 20,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(15),
+        }
+    }
+}
+
+impl RpcRetryPolicy {
+    /// Creates a policy with the specified number of retries and backoff intervals. The backoff
+    /// is doubled after each failed attempt until it reaches `max_backoff`.
+    #[cfg(test)]
+    pub(crate) fn new(
+        max_retries: usize,
+        initial_backoff: Duration,
+        max_backoff: Duration,
+    ) -> Self {
+        Self {
+            max_retries,
+            initial_backoff,
+            max_backoff: max_backoff.max(initial_backoff),
+        }
+    }
+
+    /// Runs the provided RPC operation, retrying it on transient client errors. Waiting between attempts
+    /// is interrupted once a stop signal is sent via `stop_receiver`; in this case, returns `Ok(None)`.
+    pub(crate) async fn retry_until_stopped<T, Fut>(
+        &self,
+        operation_name: &str,
+        stop_receiver: &mut watch::Receiver<bool>,
+        mut operation: impl FnMut() -> Fut,
+    ) -> anyhow::Result<Option<T>>
+    where
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let mut backoff = self.initial_backoff;
+        let mut retries = 0;
+        loop {
+            let err = match operation().await {
+                Ok(output) => return Ok(Some(output)),
+                Err(err) if retries < self.max_retries && is_transient_client_error(&err) => err,
+                Err(err) => return Err(err),
+            };
+            retries += 1;
+            tracing::warn!(
+                "RPC operation `{operation_name}` failed with a transient error; retrying in {backoff:?} \
+                 (retry {retries}/{max_retries}): {err:#}",
+                max_retries = self.max_retries
+            );
+            let stop = stop_receiver.wait_for(|stop| *stop);
+            if tokio::time::timeout(backoff, stop).await.is_ok() {
+                tracing::info!(
+                    "Stop signal received, interrupting RPC operation `{operation_name}`"
+                );
+                return Ok(None);
+            }
+            backoff = (backoff * 2).min(self.max_backoff);
+        }
+    }
+}
+
+/// Builds a main node client and checks that the main node is reachable. Errors building the client
+/// (e.g., a malformed main node URL) are returned immediately, while transient errors reaching the main node
+/// (e.g., failing to resolve its host) are retried according to `retry_policy`. Returns `Ok(None)` if a stop signal
+/// is received while waiting for a retry.
+pub(crate) async fn connect_to_main_node<C: MainNodeClient>(
+    build_client: impl FnOnce() -> anyhow::Result<C>,
+    retry_policy: RpcRetryPolicy,
+    stop_receiver: &mut watch::Receiver<bool>,
+) -> anyhow::Result<Option<C>> {
+    let client = build_client().context("Failed creating JSON-RPC client for main node")?;
+    let connected = retry_policy
+        .retry_until_stopped("fetch_l2_block_number", stop_receiver, || async {
+            client.fetch_l2_block_number().await?;
+            anyhow::Ok(())
+        })
+        .await
+        .context("Failed reaching main node")?;
+    Ok(connected.map(|()| client))
+}
+
+fn is_transient_client_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<EnrichedClientError>()
+            .map_or(false, EnrichedClientError::is_transient)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use zksync_config::GenesisConfig;
+    use zksync_types::{
+        api::{self, en},
+        Address, MiniblockNumber, ProtocolVersionId, H256,
+    };
+    use zksync_web3_decl::{error::EnrichedClientResult, jsonrpsee::core::ClientError};
+
+    use super::*;
+
+    const MAX_RETRIES: usize = 9;
+
+    fn test_retry_policy() -> RpcRetryPolicy {
+        RpcRetryPolicy::new(
+            MAX_RETRIES,
+            Duration::from_millis(1),
+            Duration::from_millis(5),
+        )
+    }
+
+    /// Client failing the first `failure_count` calls with the specified error.
+    #[derive(Debug)]
+    struct FlakyClient {
+        failure_count: usize,
+        transient: bool,
+        call_count: Arc<AtomicUsize>,
+    }
+
+    impl FlakyClient {
+        fn new(failure_count: usize, transient: bool) -> Self {
+            Self {
+                failure_count,
+                transient,
+                call_count: Arc::default(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl MainNodeClient for FlakyClient {
+        async fn fetch_system_contract_by_hash(
+            &self,
+            _hash: H256,
+        ) -> EnrichedClientResult<Option<Vec<u8>>> {
+            unreachable!()
+        }
+
+        async fn fetch_genesis_contract_bytecode(
+            &self,
+            _address: Address,
+        ) -> EnrichedClientResult<Option<Vec<u8>>> {
+            unreachable!()
+        }
+
+        async fn fetch_protocol_version(
+            &self,
+            _protocol_version: ProtocolVersionId,
+        ) -> EnrichedClientResult<Option<api::ProtocolVersion>> {
+            unreachable!()
+        }
+
+        async fn fetch_l2_block_number(&self) -> EnrichedClientResult<MiniblockNumber> {
+            let call_idx = self.call_count.fetch_add(1, Ordering::SeqCst);
+            if call_idx < self.failure_count {
+                let err = if self.transient {
+                    EnrichedClientError::new(ClientError::RequestTimeout, "get_block_number")
+                } else {
+                    EnrichedClientError::custom("unknown method", "get_block_number")
+                };
+                return Err(err);
+            }
+            Ok(MiniblockNumber(42))
+        }
+
+        async fn fetch_l2_block(
+            &self,
+            _number: MiniblockNumber,
+            _with_transactions: bool,
+        ) -> EnrichedClientResult<Option<en::SyncBlock>> {
+            unreachable!()
+        }
+
+        async fn fetch_consensus_genesis(
+            &self,
+        ) -> EnrichedClientResult<Option<en::ConsensusGenesis>> {
+            unreachable!()
+        }
+
+        async fn fetch_genesis_config(&self) -> EnrichedClientResult<GenesisConfig> {
+            unreachable!()
+        }
+    }
+
+    #[tokio::test]
+    async fn connecting_to_main_node_after_transient_error() {
+        let client = FlakyClient::new(1, true);
+        let call_count = client.call_count.clone();
+        let (_stop_sender, mut stop_receiver) = watch::channel(false);
+        let client = connect_to_main_node(|| Ok(client), test_retry_policy(), &mut stop_receiver)
+            .await
+            .unwrap()
+            .expect("connection was interrupted");
+        assert_eq!(call_count.load(Ordering::SeqCst), 2);
+        // Check that the returned client is usable.
+        let block_number = client.fetch_l2_block_number().await.unwrap();
+        assert_eq!(block_number, MiniblockNumber(42));
+    }
+
+    #[tokio::test]
+    async fn connecting_to_main_node_with_persistent_transient_errors() {
+        let client = FlakyClient::new(usize::MAX, true);
+        let call_count = client.call_count.clone();
+        let (_stop_sender, mut stop_receiver) = watch::channel(false);
+        connect_to_main_node(|| Ok(client), test_retry_policy(), &mut stop_receiver)
+            .await
+            .unwrap_err();
+        assert_eq!(call_count.load(Ordering::SeqCst), MAX_RETRIES + 1);
+    }
+
+    #[tokio::test]
+    async fn connecting_to_main_node_fails_fast_on_non_transient_errors() {
+        let client = FlakyClient::new(1, false);
+        let call_count = client.call_count.clone();
+        let (_stop_sender, mut stop_receiver) = watch::channel(false);
+        connect_to_main_node(|| Ok(client), test_retry_policy(), &mut stop_receiver)
+            .await
+            .unwrap_err();
+        assert_eq!(call_count.load(Ordering::SeqCst), 1);
+
+        let err = connect_to_main_node(
+            || <dyn MainNodeClient>::json_rpc("not a URL"),
+            test_retry_policy(),
+            &mut stop_receiver,
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("Failed creating JSON-RPC client"),
+            "{err:#}"
+        );
+    }
+
+    #[tokio::test]
+    async fn retrying_rpc_operations_with_contextual_errors() {
+        let attempts = &AtomicUsize::new(0);
+        let (_stop_sender, mut stop_receiver) = watch::channel(false);
+        let output = test_retry_policy()
+            .retry_until_stopped("test", &mut stop_receiver, || async move {
+                if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                    let err = EnrichedClientError::new(ClientError::RequestTimeout, "chain_id");
+                    return Err(anyhow::Error::from(err).context("Unable to fetch chain ID"));
+                }
+                Ok(42)
+            })
+            .await
+            .unwrap();
+        assert_eq!(output, Some(42));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        let err = test_retry_policy()
+            .retry_until_stopped("test", &mut stop_receiver, || async move {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                anyhow::Result::<()>::Err(anyhow::anyhow!("invalid chain ID"))
+            })
+            .await
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("invalid chain ID"), "{err:#}");
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn connecting_to_main_node_is_interrupted_by_stop_signal() {
+        let client = FlakyClient::new(usize::MAX, true);
+        let call_count = client.call_count.clone();
+        let retry_policy = RpcRetryPolicy::new(
+            MAX_RETRIES,
+            Duration::from_secs(60),
+            Duration::from_secs(60),
+        );
+        let (stop_sender, mut stop_receiver) = watch::channel(false);
+        let connection_task = tokio::spawn(async move {
+            connect_to_main_node(|| Ok(client), retry_policy, &mut stop_receiver).await
+        });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        stop_sender.send_replace(true);
+        let connection_result = tokio::time::timeout(Duration::from_secs(5), connection_task)
+            .await
+            .expect("connection was not interrupted")
+            .unwrap();
+        assert!(connection_result.unwrap().is_none());
+        assert_eq!(call_count.load(Ordering::SeqCst), 1);
+    }
+}
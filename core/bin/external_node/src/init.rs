@@ -1,15 +1,21 @@
 //! EN initialization logic.
 
 use anyhow::Context as _;
-use zksync_basic_types::{L1BatchNumber, L2ChainId};
-use zksync_core::sync_layer::genesis::perform_genesis_if_needed;
+use zksync_basic_types::L1BatchNumber;
+use zksync_core::{
+    block_reverter::{BlockReverter, BlockReverterFlags},
+    sync_layer::genesis::perform_genesis_if_needed,
+};
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_health_check::AppHealthCheck;
 use zksync_object_store::ObjectStoreFactory;
 use zksync_snapshots_applier::SnapshotsApplierConfig;
 use zksync_web3_decl::jsonrpsee::http_client::HttpClient;
 
-use crate::config::read_snapshots_recovery_config;
+use crate::{
+    config::{ExternalNodeConfig, ReorgHandlingMode},
+    confirmation::{confirm_rollback, ConfirmationPrompt},
+};
 
 #[derive(Debug)]
 enum InitDecision {
@@ -23,7 +29,7 @@ pub(crate) async fn ensure_storage_initialized(
     pool: &ConnectionPool<Core>,
     main_node_client: &HttpClient,
     app_health: &AppHealthCheck,
-    l2_chain_id: L2ChainId,
+    config: &ExternalNodeConfig,
     consider_snapshot_recovery: bool,
 ) -> anyhow::Result<()> {
     let mut storage = pool.connection_tagged("en").await?;
@@ -67,8 +73,7 @@ pub(crate) async fn ensure_storage_initialized(
     tracing::info!("Chosen node initialization strategy: {decision:?}");
     match decision {
         InitDecision::Genesis => {
-            let mut storage = pool.connection_tagged("en").await?;
-            perform_genesis_if_needed(&mut storage, l2_chain_id, main_node_client)
+            perform_genesis_if_needed(pool, config.remote.l2_chain_id, main_node_client)
                 .await
                 .context("performing genesis failed")?;
         }
@@ -81,7 +86,7 @@ pub(crate) async fn ensure_storage_initialized(
             );
 
             tracing::warn!("Proceeding with snapshot recovery. This is an experimental feature; use at your own risk");
-            let recovery_config = read_snapshots_recovery_config()?;
+            let recovery_config = config.snapshots_recovery_config()?;
             let blob_store = ObjectStoreFactory::new(recovery_config.snapshots_object_store)
                 .create_store()
                 .await;
@@ -97,3 +102,388 @@ pub(crate) async fn ensure_storage_initialized(
     }
     Ok(())
 }
+
+/// Reverts the pending L1 batch, i.e., rolls back the node storage to the last sealed L1 batch.
+/// If there are no sealed L1 batches in Postgres (e.g., the node was just recovered from a snapshot),
+/// there's nothing to revert; in this case, returns `Ok(false)` without touching the storage.
+pub(crate) async fn revert_pending_l1_batch(
+    pool: &ConnectionPool<Core>,
+    reverter: &BlockReverter,
+    prompt: &mut dyn ConfirmationPrompt,
+    skip_confirmation: bool,
+) -> anyhow::Result<bool> {
+    let mut storage = pool.connection_tagged("en").await?;
+    let sealed_l1_batch_number = storage
+        .blocks_dal()
+        .get_sealed_l1_batch_number()
+        .await
+        .context("Failed getting sealed L1 batch number")?;
+    drop(storage);
+
+    let Some(sealed_l1_batch_number) = sealed_l1_batch_number else {
+        tracing::info!(
+            "There are no L1 batches in Postgres (e.g., the node was just recovered from a snapshot); \
+             skipping reverting the pending L1 batch since there's nothing to revert"
+        );
+        return Ok(false);
+    };
+
+    estimate_rollback(reverter, sealed_l1_batch_number).await;
+    confirm_rollback(
+        prompt,
+        skip_confirmation,
+        "Reverting the pending L1 batch",
+        sealed_l1_batch_number,
+    )?;
+    tracing::info!("Rolling back to l1 batch number {sealed_l1_batch_number}");
+    reverter
+        .rollback_db(sealed_l1_batch_number, BlockReverterFlags::all())
+        .await;
+    tracing::info!("Rollback successfully completed");
+    Ok(true)
+}
+
+/// Reverts the node storage to the specified L1 batch, removing all data after it. Errors if the target L1 batch
+/// is ahead of the last sealed L1 batch in Postgres.
+/// Before asking for confirmation, logs the amount of data that would be removed.
+pub(crate) async fn revert_to_l1_batch(
+    pool: &ConnectionPool<Core>,
+    reverter: &BlockReverter,
+    target_l1_batch: L1BatchNumber,
+    prompt: &mut dyn ConfirmationPrompt,
+    skip_confirmation: bool,
+) -> anyhow::Result<()> {
+    let mut storage = pool.connection_tagged("en").await?;
+    let sealed_l1_batch_number = storage
+        .blocks_dal()
+        .get_sealed_l1_batch_number()
+        .await
+        .context("Failed getting sealed L1 batch number")?;
+    let snapshot_recovery = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .context("Failed getting snapshot recovery info")?;
+    drop(storage);
+
+    let Some(sealed_l1_batch_number) = sealed_l1_batch_number else {
+        anyhow::bail!(
+            "Cannot revert to L1 batch #{target_l1_batch}: there are no L1 batches in Postgres"
+        );
+    };
+    anyhow::ensure!(
+        target_l1_batch <= sealed_l1_batch_number,
+        "Cannot revert to L1 batch #{target_l1_batch}: it is ahead of the last sealed L1 batch #{sealed_l1_batch_number}"
+    );
+    // Data preceding the snapshot is not present in Postgres, so the node cannot be rolled back past it.
+    if let Some(snapshot_recovery) = snapshot_recovery {
+        let snapshot_l1_batch = snapshot_recovery.l1_batch_number;
+        anyhow::ensure!(
+            target_l1_batch >= snapshot_l1_batch,
+            "Cannot revert to L1 batch #{target_l1_batch}: the node was recovered from a snapshot \
+             at L1 batch #{snapshot_l1_batch}, and earlier L1 batches are not present in Postgres"
+        );
+    }
+
+    estimate_rollback(reverter, target_l1_batch).await;
+    confirm_rollback(
+        prompt,
+        skip_confirmation,
+        "Reverting to the specified L1 batch",
+        target_l1_batch,
+    )?;
+    tracing::info!("Rolling back to l1 batch number {target_l1_batch}");
+    reverter
+        .rollback_db(target_l1_batch, BlockReverterFlags::all())
+        .await;
+    tracing::info!("Rollback successfully completed");
+    Ok(())
+}
+
+/// Logs the amount of data removed by a rollback. The estimate is informational, so errors estimating it
+/// (e.g., the Merkle tree being locked by another process) don't prevent the rollback.
+async fn estimate_rollback(reverter: &BlockReverter, last_l1_batch_to_keep: L1BatchNumber) {
+    if let Err(err) = reverter.estimate_rollback(last_l1_batch_to_keep).await {
+        tracing::warn!("Failed estimating rollback to L1 batch #{last_l1_batch_to_keep}: {err:#}");
+    }
+}
+
+/// Handles a reorg detected on node startup. In the rollback mode, rolls back the node storage to `last_correct_l1_batch`.
+/// In the observer mode, returns an error without touching the storage; the operator is expected to perform
+/// the rollback manually.
+pub(crate) async fn handle_detected_reorg(
+    mode: ReorgHandlingMode,
+    reverter: &BlockReverter,
+    last_correct_l1_batch: L1BatchNumber,
+) -> anyhow::Result<()> {
+    match mode {
+        ReorgHandlingMode::Rollback => {
+            tracing::info!("Rolling back to l1 batch number {last_correct_l1_batch}");
+            reverter
+                .rollback_db(last_correct_l1_batch, BlockReverterFlags::all())
+                .await;
+            tracing::info!("Rollback successfully completed");
+            Ok(())
+        }
+        ReorgHandlingMode::Observe => {
+            tracing::error!(
+                "Reorg detected; last correct L1 batch is #{last_correct_l1_batch}. The node storage is left untouched \
+                 since reorg handling mode is `observe`"
+            );
+            anyhow::bail!(
+                "reorg detected; roll back the node storage to L1 batch #{last_correct_l1_batch} manually \
+                 using `block_reverter rollback-db`, or restart the node with `rollback` reorg handling mode"
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+    use zksync_core::block_reverter::{L1ExecutedBatchesRevert, NodeRole};
+    use zksync_state::RocksdbStorage;
+    use zksync_types::{
+        snapshots::SnapshotRecoveryStatus, MiniblockNumber, ProtocolVersionId, H256,
+    };
+
+    use super::*;
+    use crate::tests::seal_l1_batches;
+
+    #[derive(Debug)]
+    struct UnreachablePrompt;
+
+    impl ConfirmationPrompt for UnreachablePrompt {
+        fn read_response(&mut self, message: &str) -> anyhow::Result<Option<String>> {
+            panic!("Unexpected confirmation prompt: {message}");
+        }
+    }
+
+    /// Prompt answering with the specified response.
+    #[derive(Debug)]
+    struct AnsweringPrompt(String);
+
+    impl ConfirmationPrompt for AnsweringPrompt {
+        fn read_response(&mut self, _message: &str) -> anyhow::Result<Option<String>> {
+            Ok(Some(self.0.clone()))
+        }
+    }
+
+    fn create_reverter(pool: ConnectionPool<Core>, temp_dir: &TempDir) -> BlockReverter {
+        let path = |name: &str| temp_dir.path().join(name).to_str().unwrap().to_owned();
+        BlockReverter::new(
+            NodeRole::External,
+            path("state_keeper_cache"),
+            path("merkle_tree"),
+            None,
+            pool,
+            L1ExecutedBatchesRevert::Allowed,
+        )
+    }
+
+    #[tokio::test]
+    async fn reverting_to_l1_batch_ahead_of_sealed_l1_batch_errors() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        seal_l1_batches(&mut storage, 2).await;
+        let temp_dir = TempDir::new().unwrap();
+        let reverter = create_reverter(pool.clone(), &temp_dir);
+
+        let err = revert_to_l1_batch(
+            &pool,
+            &reverter,
+            L1BatchNumber(3),
+            &mut UnreachablePrompt,
+            false,
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+        assert!(
+            err.contains("ahead of the last sealed L1 batch #2"),
+            "{err}"
+        );
+
+        let sealed_l1_batch = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .unwrap();
+        assert_eq!(sealed_l1_batch, Some(L1BatchNumber(2)));
+    }
+
+    #[tokio::test]
+    async fn reverting_to_l1_batch_before_snapshot_errors() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        seal_l1_batches(&mut storage, 3).await;
+        let snapshot_recovery = SnapshotRecoveryStatus {
+            l1_batch_number: L1BatchNumber(2),
+            l1_batch_root_hash: H256::zero(),
+            l1_batch_timestamp: 2,
+            miniblock_number: MiniblockNumber(2),
+            miniblock_hash: H256::from_low_u64_be(2),
+            miniblock_timestamp: 2,
+            protocol_version: ProtocolVersionId::latest(),
+            storage_logs_chunks_processed: vec![true],
+        };
+        storage
+            .snapshot_recovery_dal()
+            .insert_initial_recovery_status(&snapshot_recovery)
+            .await
+            .unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let reverter = create_reverter(pool.clone(), &temp_dir);
+
+        let err = revert_to_l1_batch(
+            &pool,
+            &reverter,
+            L1BatchNumber(1),
+            &mut UnreachablePrompt,
+            false,
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("snapshot at L1 batch #2"), "{err}");
+
+        let sealed_l1_batch = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .unwrap();
+        assert_eq!(sealed_l1_batch, Some(L1BatchNumber(3)));
+    }
+
+    #[tokio::test]
+    async fn reverting_to_l1_batch() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        seal_l1_batches(&mut storage, 3).await;
+        let temp_dir = TempDir::new().unwrap();
+        // The state keeper cache must exist for the rollback; the Merkle tree is skipped if it's absent.
+        RocksdbStorage::builder(&temp_dir.path().join("state_keeper_cache"))
+            .await
+            .unwrap();
+        let reverter = create_reverter(pool.clone(), &temp_dir);
+
+        revert_to_l1_batch(
+            &pool,
+            &reverter,
+            L1BatchNumber(1),
+            &mut AnsweringPrompt("1".to_owned()),
+            false,
+        )
+        .await
+        .unwrap();
+
+        let sealed_l1_batch = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .unwrap();
+        assert_eq!(sealed_l1_batch, Some(L1BatchNumber(1)));
+        let sealed_miniblock = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .unwrap();
+        assert_eq!(sealed_miniblock, Some(MiniblockNumber(1)));
+    }
+
+    #[tokio::test]
+    async fn reverting_to_l1_batch_requires_matching_confirmation() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        seal_l1_batches(&mut storage, 3).await;
+        let temp_dir = TempDir::new().unwrap();
+        let reverter = create_reverter(pool.clone(), &temp_dir);
+
+        revert_to_l1_batch(
+            &pool,
+            &reverter,
+            L1BatchNumber(1),
+            &mut AnsweringPrompt("2".to_owned()),
+            false,
+        )
+        .await
+        .unwrap_err();
+
+        let sealed_l1_batch = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .unwrap();
+        assert_eq!(sealed_l1_batch, Some(L1BatchNumber(3)));
+    }
+
+    #[tokio::test]
+    async fn reverting_pending_l1_batch_is_noop_without_l1_batches() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        // RocksDB paths are never accessed since there's nothing to revert.
+        let reverter = BlockReverter::new(
+            NodeRole::External,
+            "state_keeper_cache".to_owned(),
+            "merkle_tree".to_owned(),
+            None,
+            pool.clone(),
+            L1ExecutedBatchesRevert::Allowed,
+        );
+
+        let reverted = revert_pending_l1_batch(&pool, &reverter, &mut UnreachablePrompt, false)
+            .await
+            .unwrap();
+        assert!(!reverted);
+    }
+
+    #[tokio::test]
+    async fn reverting_to_l1_batch_errors_without_l1_batches() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        // RocksDB paths are never accessed since the target L1 batch is checked first.
+        let reverter = BlockReverter::new(
+            NodeRole::External,
+            "state_keeper_cache".to_owned(),
+            "merkle_tree".to_owned(),
+            None,
+            pool.clone(),
+            L1ExecutedBatchesRevert::Allowed,
+        );
+
+        let err = revert_to_l1_batch(
+            &pool,
+            &reverter,
+            L1BatchNumber(1),
+            &mut UnreachablePrompt,
+            false,
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("#1"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn observing_reorg_leaves_storage_untouched() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let temp_dir = TempDir::new().unwrap();
+        let state_keeper_cache_path = temp_dir.path().join("state_keeper_cache");
+        let merkle_tree_path = temp_dir.path().join("merkle_tree");
+        // Rolling back would open RocksDB instances at these paths, thus creating them.
+        let reverter = BlockReverter::new(
+            NodeRole::External,
+            state_keeper_cache_path.to_str().unwrap().to_owned(),
+            merkle_tree_path.to_str().unwrap().to_owned(),
+            None,
+            pool.clone(),
+            L1ExecutedBatchesRevert::Allowed,
+        );
+
+        let err = handle_detected_reorg(ReorgHandlingMode::Observe, &reverter, L1BatchNumber(1))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("#1"), "{err}");
+        assert!(!state_keeper_cache_path.exists());
+        assert!(!merkle_tree_path.exists());
+    }
+}
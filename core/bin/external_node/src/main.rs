@@ -1,21 +1,27 @@
-use std::{future, sync::Arc, time::Duration};
+use std::{collections::BTreeMap, future, path::PathBuf, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
-use clap::Parser;
+use clap::{ArgGroup, Parser};
 use metrics::EN_METRICS;
-use prometheus_exporter::PrometheusExporterConfig;
-use tokio::{sync::watch, task};
-use zksync_basic_types::L2ChainId;
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    sync::watch,
+    task,
+};
+use zksync_basic_types::{L1BatchNumber, L2ChainId};
 use zksync_concurrency::{ctx, limiter, scope, time};
-use zksync_config::configs::{chain::L1BatchCommitDataGeneratorMode, database::MerkleTreeMode};
+use zksync_config::configs::chain::L1BatchCommitDataGeneratorMode;
 use zksync_core::{
     api_server::{
-        execution_sandbox::VmConcurrencyLimiter,
+        execution_sandbox::{VmConcurrencyBarrier, VmConcurrencyLimiter},
         healthcheck::HealthCheckHandle,
-        tx_sender::{proxy::TxProxy, ApiContracts, TxSenderBuilder},
-        web3::{ApiBuilder, Namespace},
+        tree::TreeApiClient,
+        tx_sender::{
+            contracts_reloader::ApiContractsReloader, proxy::TxProxy, ApiContracts, TxSenderBuilder,
+        },
+        web3::{state::InternalApiConfig, ApiBuilder, Namespace},
     },
-    block_reverter::{BlockReverter, BlockReverterFlags, L1ExecutedBatchesRevert, NodeRole},
+    block_reverter::{BlockReverter, L1ExecutedBatchesRevert, NodeRole},
     commitment_generator::CommitmentGenerator,
     consensus,
     consistency_checker::ConsistencyChecker,
@@ -24,39 +30,57 @@ use zksync_core::{
         ValidiumModeL1BatchCommitDataGenerator,
     },
     l1_gas_price::MainNodeFeeParamsFetcher,
-    metadata_calculator::{MetadataCalculator, MetadataCalculatorConfig},
+    metadata_calculator::{LazyAsyncTreeReader, MetadataCalculator, MetadataCalculatorConfig},
     reorg_detector,
     reorg_detector::ReorgDetector,
     setup_sigint_handler,
     state_keeper::{
-        seal_criteria::NoopSealer, AsyncRocksdbCache, BatchExecutor, MainBatchExecutor,
-        OutputHandler, StateKeeperPersistence, ZkSyncStateKeeper,
+        ensure_miniblocks_migrated, seal_criteria::NoopSealer, AsyncRocksdbCache, BatchExecutor,
+        MainBatchExecutor, OutputHandler, PostgresStorageFactory, ReadStorageFactory,
+        SealQueueLoad, StateKeeperPersistence, StateKeeperRocksdbOptions, ZkSyncStateKeeper,
     },
     sync_layer::{
         batch_status_updater::BatchStatusUpdater, external_io::ExternalIO, ActionQueue,
-        MainNodeClient, SyncState,
+        MainNodeClient, MainNodeClientConfig, MainNodeRetryConfig, SyncState,
     },
-    utils::ensure_l1_batch_commit_data_generation_mode,
+    utils::{clamp_polling_interval, ensure_l1_batch_commit_data_generation_mode},
 };
 use zksync_dal::{metrics::PostgresMetrics, ConnectionPool, Core, CoreDal};
-use zksync_db_connection::healthcheck::ConnectionPoolHealthCheck;
+use zksync_db_connection::{
+    healthcheck::ConnectionPoolHealthCheck, retry::retry_on_connection_error,
+};
 use zksync_eth_client::clients::QueryClient;
 use zksync_health_check::{AppHealthCheck, HealthStatus, ReactiveHealthCheck};
+use zksync_object_store::ObjectStoreFactory;
 use zksync_state::PostgresStorageCaches;
-use zksync_storage::RocksDB;
-use zksync_utils::wait_for_tasks::ManagedTasks;
+use zksync_storage::{RocksDB, RocksDBMemoryBudget};
+use zksync_utils::wait_for_tasks::{ManagedTasks, NamedTask, RestartPolicy, TaskPolicy};
 use zksync_web3_decl::jsonrpsee::http_client::HttpClient;
 
 use crate::{
-    config::{observability::observability_config_from_env, ExternalNodeConfig},
-    helpers::MainNodeHealthCheck,
-    init::ensure_storage_initialized,
+    config::{
+        observability::observability_config_from_vars, ConfigVars, ExternalNodeConfig,
+        OptionalENConfig,
+    },
+    confirmation::TerminalPrompt,
+    helpers::{connect_to_main_node, MainNodeHealthCheck, RpcRetryPolicy},
+    init::{
+        ensure_storage_initialized, handle_detected_reorg, revert_pending_l1_batch,
+        revert_to_l1_batch,
+    },
+    replica_lag::ReplicaLagChecker,
+    snapshots::PeriodicSnapshotsCreator,
 };
 
 mod config;
+mod confirmation;
 mod helpers;
 mod init;
 mod metrics;
+mod replica_lag;
+mod snapshots;
+#[cfg(test)]
+mod tests;
 mod version_sync_task;
 
 const RELEASE_MANIFEST: &str = include_str!("../../../../.github/release-please/manifest.json");
@@ -66,44 +90,75 @@ const RELEASE_MANIFEST: &str = include_str!("../../../../.github/release-please/
 async fn build_state_keeper(
     action_queue: ActionQueue,
     state_keeper_db_path: String,
+    rocksdb_memory_budget: Option<RocksDBMemoryBudget>,
     config: &ExternalNodeConfig,
     connection_pool: ConnectionPool<Core>,
     output_handler: OutputHandler,
+    seal_queue_load: SealQueueLoad,
+    state_hash_tree_reader: Option<LazyAsyncTreeReader>,
     stop_receiver: watch::Receiver<bool>,
     chain_id: L2ChainId,
-    task_handles: &mut Vec<task::JoinHandle<anyhow::Result<()>>>,
+    task_handles: &mut Vec<NamedTask>,
 ) -> anyhow::Result<ZkSyncStateKeeper> {
     // We only need call traces on the external node if the `debug_` namespace is enabled.
     let save_call_traces = config.optional.api_namespaces().contains(&Namespace::Debug);
 
-    let (storage_factory, task) = AsyncRocksdbCache::new(
-        connection_pool.clone(),
-        state_keeper_db_path,
-        config.optional.enum_index_migration_chunk_size,
+    let storage_factory: Arc<dyn ReadStorageFactory> = if config.optional.state_keeper_db_disabled {
+        tracing::warn!(
+            "State keeper RocksDB cache is disabled; storage will be read directly from Postgres, which is slow"
+        );
+        Arc::new(PostgresStorageFactory::new(connection_pool.clone()))
+    } else {
+        let state_keeper_db_options = StateKeeperRocksdbOptions {
+            memory_budget: rocksdb_memory_budget,
+            ..config.optional.state_keeper_db_options()
+        };
+        let (storage_factory, task) = AsyncRocksdbCache::new(
+            connection_pool.clone(),
+            state_keeper_db_path,
+            state_keeper_db_options,
+            config
+                .optional
+                .enum_index_migration_chunk_size()
+                .context("invalid state keeper config")?,
+        );
+        let mut stop_receiver_clone = stop_receiver.clone();
+        task_handles.push(NamedTask::spawn(
+            "state_keeper_rocksdb_catchup",
+            async move {
+                let result = task.run(stop_receiver_clone.clone()).await;
+                stop_receiver_clone.changed().await?;
+                result
+            },
+        ));
+        if let Some(interval) = config.optional.state_keeper_db_compaction_interval()? {
+            let compaction_task = storage_factory.compaction_task(interval, seal_queue_load);
+            task_handles.push(NamedTask::spawn(
+                "state_keeper_rocksdb_compaction",
+                compaction_task.run(stop_receiver.clone()),
+            ));
+        }
+        Arc::new(storage_factory)
+    };
+    let call_traces_sampling_rate = config.optional.call_traces_sampling_rate()?;
+    let batch_executor_base: Box<dyn BatchExecutor> = Box::new(
+        MainBatchExecutor::new(storage_factory, save_call_traces, true)
+            .with_call_traces_sampling_rate(call_traces_sampling_rate),
     );
-    let mut stop_receiver_clone = stop_receiver.clone();
-    task_handles.push(tokio::task::spawn(async move {
-        let result = task.run(stop_receiver_clone.clone()).await;
-        stop_receiver_clone.changed().await?;
-        result
-    }));
-    let batch_executor_base: Box<dyn BatchExecutor> = Box::new(MainBatchExecutor::new(
-        Arc::new(storage_factory),
-        save_call_traces,
-        true,
-    ));
 
     let main_node_url = config.required.main_node_url()?;
-    let main_node_client = <dyn MainNodeClient>::json_rpc(&main_node_url)
-        .context("Failed creating JSON-RPC client for main node")?;
-    let io = ExternalIO::new(
-        connection_pool,
-        action_queue,
-        Box::new(main_node_client),
-        chain_id,
+    let main_node_client = <dyn MainNodeClient>::json_rpc_with_config(
+        &main_node_url,
+        &config.optional.main_node_client_config()?,
     )
-    .await
-    .context("Failed initializing I/O for external node state keeper")?;
+    .context("Failed creating JSON-RPC client for main node")?;
+    let mut io = ExternalIO::new(connection_pool, action_queue, main_node_client, chain_id)
+        .await
+        .context("Failed initializing I/O for external node state keeper")?
+        .with_state_hash_max_poll_interval(config.optional.state_hash_max_poll_interval()?);
+    if let Some(tree_reader) = state_hash_tree_reader {
+        io = io.with_tree_reader(tree_reader);
+    }
 
     Ok(ZkSyncStateKeeper::new(
         stop_receiver,
@@ -114,12 +169,353 @@ async fn build_state_keeper(
     ))
 }
 
+/// Stop signal for tasks synchronizing the node with the main node (the state keeper, fetcher etc.).
+/// In the read-only mode, these tasks are stopped on a consistency checker failure without stopping the rest
+/// of the node.
+#[derive(Debug)]
+struct SyncStopSignal {
+    receiver: watch::Receiver<bool>,
+    halt_sender: Option<watch::Sender<bool>>,
+}
+
+impl SyncStopSignal {
+    fn new(
+        stop_receiver: &watch::Receiver<bool>,
+        read_only_on_consistency_failure: bool,
+        task_handles: &mut Vec<NamedTask>,
+    ) -> Self {
+        if !read_only_on_consistency_failure {
+            return Self {
+                receiver: stop_receiver.clone(),
+                halt_sender: None,
+            };
+        }
+
+        let (halt_sender, halt_receiver) = watch::channel(false);
+        let (sync_stop_sender, sync_stop_receiver) = watch::channel(false);
+        task_handles.push(NamedTask::spawn(
+            "sync_stop_signal",
+            combine_stop_signals(stop_receiver.clone(), halt_receiver, sync_stop_sender),
+        ));
+        Self {
+            receiver: sync_stop_receiver,
+            halt_sender: Some(halt_sender),
+        }
+    }
+
+    /// Converts synchronization tasks into tasks managed by the node. In the read-only mode, synchronization tasks
+    /// are allowed to finish without shutting down the node.
+    fn managed_tasks(&self, sync_tasks: Vec<NamedTask>) -> impl Iterator<Item = NamedTask> {
+        let read_only = self.halt_sender.is_some();
+        sync_tasks.into_iter().map(move |task| {
+            if read_only {
+                task.allowed_to_finish()
+            } else {
+                task
+            }
+        })
+    }
+}
+
+/// Sends the stop signal for synchronization tasks either on the node stop signal, or on the halt signal
+/// from the consistency checker. The sender is kept alive until the node is stopped, so that synchronization tasks
+/// don't observe a closed channel.
+async fn combine_stop_signals(
+    mut stop_receiver: watch::Receiver<bool>,
+    mut halt_receiver: watch::Receiver<bool>,
+    sync_stop_sender: watch::Sender<bool>,
+) -> anyhow::Result<()> {
+    tokio::select! {
+        _ = stop_receiver.wait_for(|stop| *stop) => {}
+        Ok(_) = halt_receiver.wait_for(|halt| *halt) => {
+            tracing::warn!("Node synchronization is halted; the node continues running in the read-only mode");
+        }
+    }
+    sync_stop_sender.send_replace(true);
+    stop_receiver.wait_for(|stop| *stop).await.ok();
+    Ok(())
+}
+
+fn metadata_calculator_config(
+    config: &ExternalNodeConfig,
+    memory_budget: Option<RocksDBMemoryBudget>,
+) -> anyhow::Result<MetadataCalculatorConfig> {
+    Ok(MetadataCalculatorConfig {
+        db_path: config.required.merkle_tree_path.clone(),
+        mode: config.optional.merkle_tree_mode,
+        delay_interval: config.optional.metadata_calculator_delay(),
+        max_l1_batches_per_iter: config.optional.max_l1_batches_per_tree_iter,
+        max_l1_batch_lag_for_proofs: config.optional.merkle_tree_max_l1_batch_lag_for_proofs,
+        multi_get_chunk_size: config.optional.merkle_tree_multi_get_chunk_size,
+        block_cache_capacity: config.optional.merkle_tree_block_cache_size(),
+        memtable_capacity: config.optional.merkle_tree_memtable_capacity(),
+        memory_budget,
+        stalled_writes_timeout: config
+            .optional
+            .merkle_tree_stalled_writes_timeout()
+            .context("invalid Merkle tree config")?,
+        checkpoint_interval: config.optional.merkle_tree_checkpoint_interval,
+        collect_rocksdb_statistics: config.optional.rocksdb_statistics_enabled,
+    })
+}
+
+/// Rebuilds the Merkle tree from the storage logs in Postgres. Used by the `--rebuild-tree` maintenance command.
+async fn rebuild_merkle_tree(
+    config: &ExternalNodeConfig,
+    connection_pool: &ConnectionPool<Core>,
+) -> anyhow::Result<()> {
+    let metadata_calculator_config = metadata_calculator_config(config, None)?;
+    let metadata_calculator = MetadataCalculator::new(metadata_calculator_config, None)
+        .await
+        .context("failed initializing metadata calculator")?;
+    // The rebuild is not interrupted gracefully; if the process is terminated, the existing tree is left intact.
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let l1_batch = metadata_calculator
+        .rebuild_tree(connection_pool, &stop_receiver)
+        .await
+        .context("failed rebuilding Merkle tree")?;
+    if let Some(l1_batch) = l1_batch {
+        tracing::info!("Merkle tree was successfully rebuilt for L1 batch #{l1_batch}");
+    }
+    Ok(())
+}
+
+/// Starts the HTTP and WS JSON-RPC servers together with auxiliary API tasks (caches, transaction proxy etc.).
+/// Returns the VM concurrency barrier shared by both servers.
+#[allow(clippy::too_many_arguments)]
+async fn run_api(
+    config: &ExternalNodeConfig,
+    api_config: InternalApiConfig,
+    api_pool: ConnectionPool<Core>,
+    proxy_cache_updater_pool: ConnectionPool<Core>,
+    main_node_client: HttpClient,
+    fee_params_fetcher: Arc<MainNodeFeeParamsFetcher>,
+    sync_state: SyncState,
+    tree_reader: Arc<dyn TreeApiClient>,
+    vm_concurrency_limiter: Arc<VmConcurrencyLimiter>,
+    vm_barrier: VmConcurrencyBarrier,
+    task_handles: &mut Vec<NamedTask>,
+    app_health: &AppHealthCheck,
+    stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    let (tx_sender, cache_update_handle, proxy_cache_updater_handle) = {
+        let main_node_retries = config.optional.main_node_client_config()?.retries;
+        let tx_proxy = TxProxy::new(main_node_client).with_retries(main_node_retries);
+        let proxy_cache_updater_handle = tokio::spawn(
+            tx_proxy
+                .run_account_nonce_sweeper(proxy_cache_updater_pool.clone(), stop_receiver.clone()),
+        );
+
+        let tx_sender_builder =
+            TxSenderBuilder::new(config.clone().into(), api_pool.clone(), Arc::new(tx_proxy));
+
+        if config.optional.transactions_per_sec_limit.is_some() {
+            tracing::warn!("`transactions_per_sec_limit` option is deprecated and ignored");
+        };
+
+        let mut storage_caches = PostgresStorageCaches::new(
+            config.optional.factory_deps_cache_size() as u64,
+            config.optional.initial_writes_cache_size() as u64,
+        );
+        let latest_values_cache_size = config.optional.latest_values_cache_size() as u64;
+        let cache_update_handle = (latest_values_cache_size > 0).then(|| {
+            task::spawn(
+                storage_caches
+                    .configure_storage_values_cache(latest_values_cache_size, api_pool.clone())
+                    .run(stop_receiver.clone()),
+            )
+        });
+
+        let tx_sender = tx_sender_builder
+            .build(
+                fee_params_fetcher,
+                vm_concurrency_limiter,
+                ApiContracts::load_from_disk(),
+                storage_caches,
+            )
+            .await;
+        (tx_sender, cache_update_handle, proxy_cache_updater_handle)
+    };
+
+    if let Some(interval) = config.optional.api_contracts_reload_interval()? {
+        let reloader = ApiContractsReloader::new(tx_sender.clone(), interval);
+        task_handles.push(NamedTask::spawn(
+            "api_contracts_reloader",
+            reloader.run(stop_receiver.clone()),
+        ));
+    }
+
+    let http_server_handles = ApiBuilder::jsonrpsee_backend(api_config.clone(), api_pool.clone())
+        .http(config.required.http_port)
+        .with_filter_limit(config.optional.filters_limit)
+        .with_batch_request_size_limit(config.optional.max_batch_request_size)
+        .with_response_body_size_limit(config.optional.max_response_body_size())
+        .with_request_body_size_limit(config.optional.max_request_body_size())
+        .with_tx_sender(tx_sender.clone())
+        .with_vm_barrier(vm_barrier.clone())
+        .with_sync_state(sync_state.clone())
+        .with_tree_api(tree_reader.clone())
+        .enable_api_namespaces(config.optional.api_namespaces())
+        .build()
+        .context("failed to build HTTP JSON-RPC server")?
+        .run(stop_receiver.clone())
+        .await
+        .context("Failed initializing HTTP JSON-RPC server")?;
+
+    let mut ws_api_builder = ApiBuilder::jsonrpsee_backend(api_config, api_pool)
+        .ws(config.required.ws_port)
+        .with_filter_limit(config.optional.filters_limit)
+        .with_subscriptions_limit(config.optional.subscriptions_limit)
+        .with_batch_request_size_limit(config.optional.max_batch_request_size)
+        .with_response_body_size_limit(config.optional.max_response_body_size())
+        .with_request_body_size_limit(config.optional.max_request_body_size())
+        .with_polling_interval(config.optional.polling_interval())
+        .with_tx_sender(tx_sender)
+        .with_vm_barrier(vm_barrier)
+        .with_sync_state(sync_state)
+        .with_tree_api(tree_reader)
+        .enable_api_namespaces(config.optional.api_namespaces());
+    if let Some(limit) = config.optional.filters_limit_per_connection {
+        ws_api_builder = ws_api_builder.with_filter_limit_per_connection(limit);
+    }
+    let ws_server_handles = ws_api_builder
+        .build()
+        .context("failed to build WS JSON-RPC server")?
+        .run(stop_receiver.clone())
+        .await
+        .context("Failed initializing WS JSON-RPC server")?;
+
+    app_health.insert_component(ws_server_handles.health_check);
+    app_health.insert_component(http_server_handles.health_check);
+
+    task_handles.extend(
+        http_server_handles
+            .tasks
+            .into_iter()
+            .map(|handle| NamedTask::new("http_api", handle)),
+    );
+    task_handles.extend(
+        ws_server_handles
+            .tasks
+            .into_iter()
+            .map(|handle| NamedTask::new("ws_api", handle)),
+    );
+    task_handles.extend(
+        cache_update_handle.map(|handle| NamedTask::new("storage_values_cache_updater", handle)),
+    );
+    task_handles.push(NamedTask::new(
+        "proxy_cache_updater",
+        proxy_cache_updater_handle,
+    ));
+    Ok(())
+}
+
+/// Creates a VM concurrency limiter shared by the HTTP and WS API servers, together with its barrier.
+fn create_vm_concurrency_limiter(
+    config: &OptionalENConfig,
+) -> (Arc<VmConcurrencyLimiter>, VmConcurrencyBarrier) {
+    let (mut vm_concurrency_limiter, vm_barrier) =
+        VmConcurrencyLimiter::new(config.vm_concurrency_limit);
+    if let Some(limit) = config.trace_call_concurrency_limit {
+        vm_concurrency_limiter = vm_concurrency_limiter.with_trace_concurrency_limit(limit);
+    }
+    (Arc::new(vm_concurrency_limiter), vm_barrier)
+}
+
+/// Returns names of the spawned tasks mapped to the number of tasks with each name (e.g., an API server
+/// may be represented by several tasks).
+fn spawned_task_names(task_handles: &[NamedTask]) -> BTreeMap<&'static str, usize> {
+    let mut names = BTreeMap::new();
+    for task in task_handles {
+        *names.entry(task.name()).or_default() += 1;
+    }
+    names
+}
+
+/// Logs names of all spawned tasks, so that the task topology of the node is visible at a glance.
+fn log_spawned_tasks(task_handles: &[NamedTask]) {
+    tracing::info!(
+        task_count = task_handles.len(),
+        tasks = ?spawned_task_names(task_handles),
+        "Spawned node tasks"
+    );
+}
+
+/// Runs API servers of a node in the standby mode. Until the node is promoted (i.e., `promotion_receiver`
+/// is set to `true`), API servers are not started, and the node reports the standby health status. After promotion,
+/// the servers are started in the same way as for a regular node.
+#[allow(clippy::too_many_arguments)]
+async fn run_api_after_promotion(
+    config: ExternalNodeConfig,
+    api_config: InternalApiConfig,
+    api_pool: ConnectionPool<Core>,
+    proxy_cache_updater_pool: ConnectionPool<Core>,
+    main_node_client: HttpClient,
+    fee_params_fetcher: Arc<MainNodeFeeParamsFetcher>,
+    sync_state: SyncState,
+    tree_reader: Arc<dyn TreeApiClient>,
+    app_health: Arc<AppHealthCheck>,
+    mut promotion_receiver: watch::Receiver<bool>,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    let (standby_health_check, standby_health_updater) = ReactiveHealthCheck::new("standby");
+    standby_health_updater.update(HealthStatus::Standby.into());
+    app_health.insert_component(standby_health_check);
+
+    tracing::info!("Node is in the standby mode; API servers will be started after promotion");
+    while !*promotion_receiver.borrow() {
+        tokio::select! {
+            res = promotion_receiver.changed() => {
+                if res.is_err() {
+                    tracing::warn!("Promotion signal sender was dropped; the node will stay in the standby mode");
+                    stop_receiver.changed().await.ok();
+                    return Ok(());
+                }
+            }
+            _ = stop_receiver.changed() => return Ok(()),
+        }
+    }
+    if *stop_receiver.borrow() {
+        return Ok(());
+    }
+
+    tracing::info!("Node is promoted from the standby mode; starting API servers");
+    let (vm_concurrency_limiter, vm_barrier) = create_vm_concurrency_limiter(&config.optional);
+    let mut api_tasks = vec![];
+    run_api(
+        &config,
+        api_config,
+        api_pool,
+        proxy_cache_updater_pool,
+        main_node_client,
+        fee_params_fetcher,
+        sync_state,
+        tree_reader,
+        vm_concurrency_limiter,
+        vm_barrier,
+        &mut api_tasks,
+        &app_health,
+        stop_receiver,
+    )
+    .await?;
+    standby_health_updater.update(HealthStatus::Ready.into());
+
+    let mut api_tasks = ManagedTasks::new(api_tasks).allow_tasks_to_finish();
+    api_tasks.wait_single().await;
+    api_tasks.complete(config.optional.shutdown_timeout()).await;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn init_tasks(
     config: &ExternalNodeConfig,
+    consensus_secrets: Option<consensus::Secrets>,
     connection_pool: ConnectionPool<Core>,
     main_node_client: HttpClient,
-    task_handles: &mut Vec<task::JoinHandle<anyhow::Result<()>>>,
-    app_health: &AppHealthCheck,
+    reorg_detector: ReorgDetector,
+    promotion_receiver: Option<watch::Receiver<bool>>,
+    task_handles: &mut Vec<NamedTask>,
+    app_health: &Arc<AppHealthCheck>,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     let release_manifest: serde_json::Value = serde_json::from_str(RELEASE_MANIFEST)
@@ -130,59 +526,132 @@ async fn init_tasks(
 
     let version = semver::Version::parse(release_manifest_version)
         .expect("version in manifest is a correct semver format; qed");
+    let mut api_config = InternalApiConfig::try_from(config.clone())?;
+    api_config.node_version = Some(version.to_string());
     // Create components.
-    let fee_params_fetcher = Arc::new(MainNodeFeeParamsFetcher::new(main_node_client.clone()));
+    let fee_params_fetcher = Arc::new(
+        MainNodeFeeParamsFetcher::new(main_node_client.clone())
+            .with_retries(config.optional.main_node_client_config()?.retries)
+            .with_min_polling_interval(config.optional.min_polling_interval()),
+    );
 
     let sync_state = SyncState::default();
     app_health.insert_custom_component(Arc::new(sync_state.clone()));
     let (action_queue_sender, action_queue) = ActionQueue::new();
 
+    // Tasks synchronizing the node with the main node. In the read-only mode, they are stopped on a consistency checker
+    // failure without stopping the rest of the node.
+    let mut sync_tasks = vec![];
+    let sync_stop_signal = SyncStopSignal::new(
+        &stop_receiver,
+        config.optional.read_only_on_consistency_failure,
+        task_handles,
+    );
+    let sync_stop_receiver = sync_stop_signal.receiver.clone();
+
     let (persistence, miniblock_sealer) = StateKeeperPersistence::new(
         connection_pool.clone(),
         config.remote.l2_erc20_bridge_addr,
         config.optional.miniblock_seal_queue_capacity,
     );
-    task_handles.push(tokio::spawn(miniblock_sealer.run()));
+    sync_tasks.push(NamedTask::spawn("miniblock_sealer", miniblock_sealer.run()));
     let pool = connection_pool.clone();
-    task_handles.push(tokio::spawn(async move {
+    let min_polling_interval = config.optional.min_polling_interval();
+    task_handles.push(NamedTask::spawn("protocol_version_metrics", async move {
+        let pool = &pool;
         loop {
-            let protocol_version = pool
-                .connection()
-                .await
-                .unwrap()
-                .protocol_versions_dal()
-                .last_used_version_id()
+            // The connection may be lost during a Postgres failover; retry instead of taking the whole node down.
+            let protocol_version =
+                retry_on_connection_error("last_used_version_id", || async move {
+                    let mut storage = pool.connection_tagged("en").await?;
+                    let version = storage
+                        .protocol_versions_dal()
+                        .last_used_version_id()
+                        .await?;
+                    anyhow::Ok(version)
+                })
                 .await
+                .context("failed getting last used protocol version")?
                 .map(|version| version as u16);
 
             EN_METRICS.version[&(format!("{}", version), protocol_version)].set(1);
 
-            tokio::time::sleep(Duration::from_secs(10)).await;
+            tokio::time::sleep(clamp_polling_interval(
+                Duration::from_secs(10),
+                min_polling_interval,
+            ))
+            .await;
         }
     }));
 
+    // Memory budget shared by the state keeper RocksDB cache and the Merkle tree.
+    let rocksdb_memory_budget = config
+        .optional
+        .rocksdb_memory_budget()
+        .context("invalid RocksDB config")?
+        .map(|capacity| {
+            let instance_count = if config.optional.state_keeper_db_disabled {
+                1
+            } else {
+                2
+            };
+            RocksDBMemoryBudget::new(capacity, instance_count)
+        });
+
+    let metadata_calculator_config =
+        metadata_calculator_config(config, rocksdb_memory_budget.clone())?;
+    let mut metadata_calculator = MetadataCalculator::new(metadata_calculator_config, None)
+        .await
+        .context("failed initializing metadata calculator")?;
+    if config.optional.metadata_calculator_adaptive_delay {
+        metadata_calculator = metadata_calculator.with_sync_state(sync_state.clone());
+    }
+    app_health.insert_component(metadata_calculator.tree_health_check());
+
+    let seal_queue_load = persistence.seal_queue_load();
     let output_handler = OutputHandler::new(Box::new(persistence.with_tx_insertion()))
         .with_handler(Box::new(sync_state.clone()));
+    let state_hash_tree_reader = config
+        .optional
+        .state_keeper_tree_state_hash_fallback
+        .then(|| metadata_calculator.tree_reader());
     let state_keeper = build_state_keeper(
         action_queue,
         config.required.state_cache_path.clone(),
+        rocksdb_memory_budget,
         config,
         connection_pool.clone(),
         output_handler,
-        stop_receiver.clone(),
+        seal_queue_load,
+        state_hash_tree_reader,
+        sync_stop_receiver.clone(),
         config.remote.l2_chain_id,
-        task_handles,
+        &mut sync_tasks,
     )
     .await?;
 
-    task_handles.push(tokio::spawn({
+    // The fetcher retries transient errors itself, adjusting its request window based on them,
+    // so the client used by it must not retry requests (retries are disabled by default).
+    let fetcher_client_config = MainNodeClientConfig {
+        retries: MainNodeRetryConfig::default(),
+        ..config.optional.main_node_client_config()?
+    };
+    let fetcher_client = <dyn MainNodeClient>::json_rpc_with_config(
+        &config.required.main_node_url()?,
+        &fetcher_client_config,
+    )
+    .context("Failed creating JSON-RPC client for main node")?;
+    let fetcher_max_request_size = config.optional.fetcher_max_request_size()?;
+    let sync_state_polling = config.optional.sync_state_polling_config()?;
+    sync_tasks.push(NamedTask::spawn("consensus_fetcher", {
         let ctx = ctx::root();
         let cfg = config.consensus.clone();
-        let mut stop_receiver = stop_receiver.clone();
+        let secrets = consensus_secrets;
+        let mut stop_receiver = sync_stop_receiver.clone();
         let fetcher = consensus::Fetcher {
             store: consensus::Store(connection_pool.clone()),
             sync_state: sync_state.clone(),
-            client: Box::new(main_node_client.clone()),
+            client: fetcher_client,
             limiter: limiter::Limiter::new(
                 &ctx,
                 limiter::Rate {
@@ -190,6 +659,8 @@ async fn init_tasks(
                     refresh: time::Duration::milliseconds(30),
                 },
             ),
+            max_request_size: fetcher_max_request_size,
+            sync_state_polling,
         };
         let actions = action_queue_sender;
         async move {
@@ -197,9 +668,7 @@ async fn init_tasks(
                 s.spawn_bg(async {
                     let res = match cfg {
                         Some(cfg) => {
-                            let secrets = config::read_consensus_secrets()
-                                .context("config::read_consensus_secrets()")?
-                                .context("consensus secrets missing")?;
+                            let secrets = secrets.context("consensus secrets missing")?;
                             fetcher.run_p2p(ctx, actions, cfg.p2p(&secrets)?).await
                         }
                         None => fetcher.run_centralized(ctx, actions).await,
@@ -215,9 +684,8 @@ async fn init_tasks(
         }
     }));
 
-    let reorg_detector = ReorgDetector::new(main_node_client.clone(), connection_pool.clone());
     app_health.insert_component(reorg_detector.health_check().clone());
-    task_handles.push(tokio::spawn({
+    task_handles.push(NamedTask::spawn("reorg_detector", {
         let stop = stop_receiver.clone();
         async move {
             reorg_detector
@@ -228,21 +696,45 @@ async fn init_tasks(
     }));
 
     let singleton_pool_builder = ConnectionPool::<Core>::singleton(&config.postgres.database_url);
-
-    let metadata_calculator_config = MetadataCalculatorConfig {
-        db_path: config.required.merkle_tree_path.clone(),
-        mode: MerkleTreeMode::Lightweight,
-        delay_interval: config.optional.metadata_calculator_delay(),
-        max_l1_batches_per_iter: config.optional.max_l1_batches_per_tree_iter,
-        multi_get_chunk_size: config.optional.merkle_tree_multi_get_chunk_size,
-        block_cache_capacity: config.optional.merkle_tree_block_cache_size(),
-        memtable_capacity: config.optional.merkle_tree_memtable_capacity(),
-        stalled_writes_timeout: config.optional.merkle_tree_stalled_writes_timeout(),
-    };
-    let metadata_calculator = MetadataCalculator::new(metadata_calculator_config, None)
-        .await
-        .context("failed initializing metadata calculator")?;
-    app_health.insert_component(metadata_calculator.tree_health_check());
+    // Pools are built concurrently, so that startup isn't slowed down much if Postgres latency is high.
+    let (
+        consistency_checker_pool,
+        batch_status_updater_pool,
+        tree_pool,
+        commitment_generator_pool,
+        proxy_cache_updater_pool,
+    ) = tokio::try_join!(
+        async {
+            singleton_pool_builder
+                .build()
+                .await
+                .context("failed to build connection pool for ConsistencyChecker")
+        },
+        async {
+            singleton_pool_builder
+                .build()
+                .await
+                .context("failed to build a connection pool for BatchStatusUpdater")
+        },
+        async {
+            singleton_pool_builder
+                .build()
+                .await
+                .context("failed to build a tree_pool")
+        },
+        async {
+            singleton_pool_builder
+                .build()
+                .await
+                .context("failed to build a commitment_generator_pool")
+        },
+        async {
+            singleton_pool_builder
+                .build()
+                .await
+                .context("failed to build a proxy_cache_updater_pool")
+        },
+    )?;
 
     let remote_diamond_proxy_addr = config.remote.diamond_proxy_addr;
     let diamond_proxy_addr = if let Some(addr) = config.optional.contracts_diamond_proxy_addr {
@@ -282,190 +774,200 @@ async fn init_tasks(
         }
     };
 
-    let consistency_checker = ConsistencyChecker::new(
+    let mut consistency_checker = ConsistencyChecker::new(
         Box::new(eth_client),
-        10, // TODO (BFT-97): Make it a part of a proper EN config
-        singleton_pool_builder
-            .build()
-            .await
-            .context("failed to build connection pool for ConsistencyChecker")?,
+        config
+            .optional
+            .consistency_checker_max_batches_to_recheck()
+            .context("invalid consistency checker config")?,
+        consistency_checker_pool,
         l1_batch_commit_data_generator,
     )
     .context("cannot initialize consistency checker")?
     .with_diamond_proxy_addr(diamond_proxy_addr);
+    if let Some(halt_sender) = sync_stop_signal.halt_sender.clone() {
+        consistency_checker = consistency_checker.with_read_only_fallback(halt_sender);
+    }
 
     app_health.insert_component(consistency_checker.health_check().clone());
     let consistency_checker_handle = tokio::spawn(consistency_checker.run(stop_receiver.clone()));
 
-    let batch_status_updater = BatchStatusUpdater::new(
-        main_node_client.clone(),
-        singleton_pool_builder
-            .build()
-            .await
-            .context("failed to build a connection pool for BatchStatusUpdater")?,
-    );
+    let mut batch_status_updater =
+        BatchStatusUpdater::new(main_node_client.clone(), batch_status_updater_pool)
+            .with_backfill_concurrency(
+                config
+                    .optional
+                    .batch_status_updater_backfill_concurrency()
+                    .context("invalid batch status updater config")?,
+            )
+            .with_finality_delay(config.optional.l1_batch_finality_delay())
+            .with_min_polling_interval(config.optional.min_polling_interval());
+    if let Some(max_lag) = config.optional.batch_status_updater_max_finality_lag {
+        batch_status_updater = batch_status_updater.with_max_finality_lag(max_lag);
+    }
     app_health.insert_component(batch_status_updater.health_check());
 
     // Run the components.
     let tree_stop_receiver = stop_receiver.clone();
-    let tree_pool = singleton_pool_builder
-        .build()
-        .await
-        .context("failed to build a tree_pool")?;
     let tree_reader = Arc::new(metadata_calculator.tree_reader());
     let tree_handle = task::spawn(metadata_calculator.run(tree_pool, tree_stop_receiver));
 
-    let commitment_generator_pool = singleton_pool_builder
-        .build()
-        .await
-        .context("failed to build a commitment_generator_pool")?;
-    let commitment_generator = CommitmentGenerator::new(commitment_generator_pool);
+    let commitment_generator = CommitmentGenerator::new(commitment_generator_pool)
+        .with_local_verification(config.optional.commitment_generator_local_verification);
     app_health.insert_component(commitment_generator.health_check());
     let commitment_generator_handle = tokio::spawn(commitment_generator.run(stop_receiver.clone()));
 
-    let updater_handle = task::spawn(batch_status_updater.run(stop_receiver.clone()));
-    let fee_address_migration_handle =
-        task::spawn(state_keeper.run_fee_address_migration(connection_pool.clone()));
-    let sk_handle = task::spawn(state_keeper.run());
-    let fee_params_fetcher_handle =
-        tokio::spawn(fee_params_fetcher.clone().run(stop_receiver.clone()));
-
-    let (tx_sender, vm_barrier, cache_update_handle, proxy_cache_updater_handle) = {
-        let tx_proxy = TxProxy::new(main_node_client);
-        let proxy_cache_updater_pool = singleton_pool_builder
-            .build()
+    let batch_status_updater = Arc::new(batch_status_updater);
+    let updater_task = NamedTask::spawn_with_policy(
+        "batch_status_updater",
+        TaskPolicy::Restart(RestartPolicy::default()),
+        stop_receiver.clone(),
+        move |stop_receiver| batch_status_updater.clone().run(stop_receiver),
+    );
+    let fee_address_migration_task = if config.optional.fee_address_migration_enabled {
+        let task = state_keeper.run_fee_address_migration(connection_pool.clone());
+        Some(NamedTask::spawn("fee_address_migration", task).allowed_to_finish())
+    } else {
+        ensure_miniblocks_migrated(&connection_pool)
             .await
-            .context("failed to build a tree_pool")?;
-        let proxy_cache_updater_handle = tokio::spawn(
-            tx_proxy
-                .run_account_nonce_sweeper(proxy_cache_updater_pool.clone(), stop_receiver.clone()),
+            .context("fee address migration is disabled, but it is incomplete")?;
+        tracing::info!(
+            "Fee address migration is disabled; storage is checked to be fully migrated"
         );
+        None
+    };
+    sync_tasks.push(NamedTask::spawn("state_keeper", state_keeper.run()));
+    let fee_params_fetcher_task = NamedTask::spawn_with_policy(
+        "fee_params_fetcher",
+        TaskPolicy::Restart(RestartPolicy::default()),
+        stop_receiver.clone(),
+        {
+            let fee_params_fetcher = fee_params_fetcher.clone();
+            move |stop_receiver| fee_params_fetcher.clone().run(stop_receiver)
+        },
+    );
 
-        let tx_sender_builder = TxSenderBuilder::new(
-            config.clone().into(),
+    // API servers only read from the database, so they may use a read replica. Components writing to the database
+    // or relying on reading their own writes must use `connection_pool`, which always points to the primary database.
+    let api_database_url = config.postgres.api_database_url();
+    let api_pool = if api_database_url == config.postgres.database_url {
+        connection_pool.clone()
+    } else {
+        tracing::info!("Using the database replica for API servers");
+        let api_pool = ConnectionPool::<Core>::builder(
+            api_database_url,
+            config.postgres.replica_max_connections(),
+        )
+        .build()
+        .await
+        .context("failed to build API connection pool")?;
+        let replica_lag_checker = ReplicaLagChecker::new(
             connection_pool.clone(),
-            Arc::new(tx_proxy),
+            api_pool.clone(),
+            config.optional.database_replica_max_l1_batch_lag,
         );
+        app_health.insert_component(replica_lag_checker.health_check());
+        task_handles.push(NamedTask::spawn(
+            "replica_lag_checker",
+            replica_lag_checker.run(stop_receiver.clone()),
+        ));
+        api_pool
+    };
 
-        if config.optional.transactions_per_sec_limit.is_some() {
-            tracing::warn!("`transactions_per_sec_limit` option is deprecated and ignored");
-        };
-
-        let max_concurrency = config.optional.vm_concurrency_limit;
-        let (vm_concurrency_limiter, vm_barrier) = VmConcurrencyLimiter::new(max_concurrency);
-        let mut storage_caches = PostgresStorageCaches::new(
-            config.optional.factory_deps_cache_size() as u64,
-            config.optional.initial_writes_cache_size() as u64,
+    if let Some(interval) = config.optional.snapshots_creator_interval()? {
+        let snapshots_config = config.snapshots_creation_config()?;
+        let blob_store = ObjectStoreFactory::new(snapshots_config.snapshots_object_store)
+            .create_store()
+            .await;
+        // Storage logs are read from the API pool, which may point to a read replica.
+        let snapshots_creator = PeriodicSnapshotsCreator::new(
+            blob_store,
+            connection_pool.clone(),
+            api_pool.clone(),
+            snapshots_config.creator,
+            interval,
         );
-        let latest_values_cache_size = config.optional.latest_values_cache_size() as u64;
-        let cache_update_handle = (latest_values_cache_size > 0).then(|| {
-            task::spawn(
-                storage_caches
-                    .configure_storage_values_cache(
-                        latest_values_cache_size,
-                        connection_pool.clone(),
-                    )
-                    .run(stop_receiver.clone()),
-            )
-        });
+        app_health.insert_component(snapshots_creator.health_check());
+        task_handles.push(NamedTask::spawn(
+            "snapshots_creator",
+            snapshots_creator.run(stop_receiver.clone()),
+        ));
+    }
 
-        let tx_sender = tx_sender_builder
-            .build(
+    if let Some(promotion_receiver) = promotion_receiver {
+        task_handles.push(NamedTask::spawn(
+            "standby_api",
+            run_api_after_promotion(
+                config.clone(),
+                api_config,
+                api_pool,
+                proxy_cache_updater_pool,
+                main_node_client,
                 fee_params_fetcher,
-                Arc::new(vm_concurrency_limiter),
-                ApiContracts::load_from_disk(), // TODO (BFT-138): Allow to dynamically reload API contracts
-                storage_caches,
-            )
-            .await;
-        (
-            tx_sender,
+                sync_state,
+                tree_reader,
+                app_health.clone(),
+                promotion_receiver,
+                stop_receiver.clone(),
+            ),
+        ));
+    } else {
+        let (vm_concurrency_limiter, vm_barrier) = create_vm_concurrency_limiter(&config.optional);
+        run_api(
+            config,
+            api_config,
+            api_pool,
+            proxy_cache_updater_pool,
+            main_node_client,
+            fee_params_fetcher,
+            sync_state,
+            tree_reader,
+            vm_concurrency_limiter,
             vm_barrier,
-            cache_update_handle,
-            proxy_cache_updater_handle,
+            task_handles,
+            app_health,
+            stop_receiver.clone(),
         )
-    };
-
-    let http_server_handles =
-        ApiBuilder::jsonrpsee_backend(config.clone().into(), connection_pool.clone())
-            .http(config.required.http_port)
-            .with_filter_limit(config.optional.filters_limit)
-            .with_batch_request_size_limit(config.optional.max_batch_request_size)
-            .with_response_body_size_limit(config.optional.max_response_body_size())
-            .with_tx_sender(tx_sender.clone())
-            .with_vm_barrier(vm_barrier.clone())
-            .with_sync_state(sync_state.clone())
-            .with_tree_api(tree_reader.clone())
-            .enable_api_namespaces(config.optional.api_namespaces())
-            .build()
-            .context("failed to build HTTP JSON-RPC server")?
-            .run(stop_receiver.clone())
-            .await
-            .context("Failed initializing HTTP JSON-RPC server")?;
-
-    let ws_server_handles =
-        ApiBuilder::jsonrpsee_backend(config.clone().into(), connection_pool.clone())
-            .ws(config.required.ws_port)
-            .with_filter_limit(config.optional.filters_limit)
-            .with_subscriptions_limit(config.optional.subscriptions_limit)
-            .with_batch_request_size_limit(config.optional.max_batch_request_size)
-            .with_response_body_size_limit(config.optional.max_response_body_size())
-            .with_polling_interval(config.optional.polling_interval())
-            .with_tx_sender(tx_sender)
-            .with_vm_barrier(vm_barrier)
-            .with_sync_state(sync_state)
-            .with_tree_api(tree_reader)
-            .enable_api_namespaces(config.optional.api_namespaces())
-            .build()
-            .context("failed to build WS JSON-RPC server")?
-            .run(stop_receiver.clone())
-            .await
-            .context("Failed initializing WS JSON-RPC server")?;
-
-    app_health.insert_component(ws_server_handles.health_check);
-    app_health.insert_component(http_server_handles.health_check);
+        .await?;
+    }
 
-    if let Some(port) = config.optional.prometheus_port {
+    if let Some(exporter_config) = config.optional.prometheus_exporter_config()? {
         let (prometheus_health_check, prometheus_health_updater) =
             ReactiveHealthCheck::new("prometheus_exporter");
         app_health.insert_component(prometheus_health_check);
-        task_handles.push(tokio::spawn(async move {
+        task_handles.push(NamedTask::spawn("prometheus_exporter", async move {
             prometheus_health_updater.update(HealthStatus::Ready.into());
-            let result = PrometheusExporterConfig::pull(port)
-                .run(stop_receiver)
-                .await;
+            let result = exporter_config.run(stop_receiver).await;
             drop(prometheus_health_updater);
             result
         }));
     }
 
-    task_handles.extend(http_server_handles.tasks);
-    task_handles.extend(ws_server_handles.tasks);
-    task_handles.extend(cache_update_handle);
-    task_handles.push(proxy_cache_updater_handle);
     task_handles.extend([
-        sk_handle,
-        fee_address_migration_handle,
-        updater_handle,
-        tree_handle,
-        consistency_checker_handle,
-        fee_params_fetcher_handle,
-        commitment_generator_handle,
+        updater_task,
+        NamedTask::new("metadata_calculator", tree_handle),
+        NamedTask::new("consistency_checker", consistency_checker_handle),
+        fee_params_fetcher_task,
+        NamedTask::new("commitment_generator", commitment_generator_handle),
     ]);
+    task_handles.extend(fee_address_migration_task);
+    task_handles.extend(sync_stop_signal.managed_tasks(sync_tasks));
 
     Ok(())
 }
 
 async fn shutdown_components(
-    stop_sender: watch::Sender<bool>,
+    stop_sender: &watch::Sender<bool>,
     tasks: ManagedTasks,
     healthcheck_handle: HealthCheckHandle,
+    timeout: Duration,
 ) -> anyhow::Result<()> {
     stop_sender.send(true).ok();
     task::spawn_blocking(RocksDB::await_rocksdb_termination)
         .await
         .context("error waiting for RocksDB instances to drop")?;
-    // Increase timeout because of complicated graceful shutdown procedure for API servers.
-    tasks.complete(Duration::from_secs(30)).await;
+    // Returns as soon as all tasks are finished, so the timeout only matters for tasks that are slow to stop.
+    tasks.complete(timeout).await;
     healthcheck_handle.stop().await;
     Ok(())
 }
@@ -473,10 +975,34 @@ async fn shutdown_components(
 /// External node for zkSync Era.
 #[derive(Debug, Parser)]
 #[command(author = "Matter Labs", version)]
+#[command(group(
+    // Maintenance operations are mutually exclusive; combining them would lead to surprising sequential changes
+    // to the node storage.
+    ArgGroup::new("maintenance")
+        .args(["revert_pending_l1_batch", "revert_to_l1_batch", "rebuild_tree"])
+        .multiple(false)
+))]
 struct Cli {
+    /// Path to a YAML file with config variables (e.g., `EN_HTTP_PORT: 3060`). Variables set in the environment
+    /// take precedence over the ones in the file.
+    #[arg(long)]
+    config_path: Option<PathBuf>,
     /// Revert the pending L1 batch and exit.
     #[arg(long)]
     revert_pending_l1_batch: bool,
+    /// Revert the node storage to the specified L1 batch, removing all data after it, and exit. The L1 batch
+    /// must not be ahead of the last sealed L1 batch.
+    #[arg(long, value_name = "N")]
+    revert_to_l1_batch: Option<u32>,
+    /// Rebuild the Merkle tree from the storage logs in Postgres and exit. The tree is rebuilt for the latest
+    /// L1 batch with metadata, and its root hash is verified against the one in Postgres. The existing tree
+    /// at `merkle_tree_path` is replaced only after the rebuilt tree is verified.
+    #[arg(long)]
+    rebuild_tree: bool,
+    /// Skip interactive confirmation for destructive operations (e.g., `--revert-pending-l1-batch` or
+    /// `--revert-to-l1-batch`). Required to run such operations if stdin is not attached to a terminal.
+    #[arg(long, alias = "non-interactive")]
+    yes: bool,
     /// Enables consensus-based syncing instead of JSON-RPC based one. This is an experimental and incomplete feature;
     /// do not use unless you know what you're doing.
     #[arg(long)]
@@ -488,6 +1014,11 @@ struct Cli {
     /// This is an experimental and incomplete feature; do not use unless you know what you're doing.
     #[arg(long)]
     enable_snapshots_recovery: bool,
+    /// Runs the node in the warm standby mode. The node fully syncs with the main node, but doesn't start API servers
+    /// and reports the `standby` health status until it's promoted by sending the `SIGUSR1` signal to the process.
+    /// After promotion, the node serves API requests as usual. Cannot be combined with maintenance operations.
+    #[arg(long, conflicts_with = "maintenance")]
+    standby: bool,
 }
 
 #[tokio::main]
@@ -495,8 +1026,10 @@ async fn main() -> anyhow::Result<()> {
     // Initial setup.
     let opt = Cli::parse();
 
-    let observability_config =
-        observability_config_from_env().context("ObservabilityConfig::from_env()")?;
+    let config_vars =
+        ConfigVars::new(opt.config_path.as_deref()).context("failed loading config variables")?;
+    let observability_config = observability_config_from_vars(&config_vars)
+        .context("failed loading observability config")?;
     let log_format: vlog::LogFormat = observability_config
         .log_format
         .parse()
@@ -518,12 +1051,56 @@ async fn main() -> anyhow::Result<()> {
         tracing::info!("No sentry URL was provided");
     }
 
-    let mut config = ExternalNodeConfig::collect()
-        .await
-        .context("Failed to load external node config")?;
-    if !opt.enable_consensus {
+    let sigint_receiver = setup_sigint_handler();
+    // Container orchestrators (e.g., Kubernetes) stop the node with SIGTERM rather than SIGINT, so we handle it as well
+    // to shut down gracefully (in particular, to let RocksDB instances finish their background work).
+    let mut sigterm_receiver =
+        signal(SignalKind::terminate()).context("failed setting up SIGTERM handler")?;
+    let (stop_sender, mut stop_receiver) = watch::channel(false);
+    let stop_sender = Arc::new(stop_sender);
+    // Termination signals are converted to the stop signal, so that they interrupt node initialization as well.
+    tokio::spawn({
+        let stop_sender = stop_sender.clone();
+        async move {
+            tokio::select! {
+                _ = sigint_receiver => {
+                    tracing::info!("Received SIGINT, shutting down");
+                },
+                _ = sigterm_receiver.recv() => {
+                    tracing::info!("Received SIGTERM, shutting down");
+                },
+            }
+            stop_sender.send_replace(true);
+        }
+    });
+
+    let config = ExternalNodeConfig::collect(
+        config_vars,
+        RpcRetryPolicy::default(),
+        &mut stop_receiver.clone(),
+    )
+    .await
+    .context("Failed to load external node config")?;
+    let Some(mut config) = config else {
+        tracing::info!("Stop signal received during node startup, exiting");
+        return Ok(());
+    };
+    let consensus_secrets = if opt.enable_consensus {
+        let secrets = config
+            .consensus_secrets()
+            .context("failed reading consensus secrets")?;
+        config
+            .validate_consensus(secrets.as_ref())
+            .context("`--enable-consensus` is set, but consensus cannot be enabled")?;
+        secrets
+    } else {
         config.consensus = None;
-    }
+        None
+    };
+    config
+        .required
+        .validate_rocksdb_paths()
+        .context("invalid RocksDB paths")?;
     if let Some(threshold) = config.optional.slow_query_threshold() {
         ConnectionPool::<Core>::global_config().set_slow_query_threshold(threshold)?;
     }
@@ -544,16 +1121,29 @@ async fn main() -> anyhow::Result<()> {
         .main_node_url()
         .expect("Main node URL is incorrect");
     tracing::info!("Main node URL is: {main_node_url}");
-    let main_node_client = <dyn MainNodeClient>::json_rpc(&main_node_url)
-        .context("Failed creating JSON-RPC client for main node")?;
+    let main_node_client_config = config.optional.main_node_client_config()?;
+
+    let main_node_client = connect_to_main_node(
+        || <dyn MainNodeClient>::json_rpc_http_client(&main_node_url, &main_node_client_config),
+        RpcRetryPolicy::default(),
+        &mut stop_receiver.clone(),
+    )
+    .await?;
+    let Some(main_node_client) = main_node_client else {
+        tracing::info!("Stop signal received during node startup, exiting");
+        return Ok(());
+    };
 
     tracing::warn!("The external node is in the alpha phase, and should be used with caution.");
     tracing::info!("Started the external node");
 
-    let app_health = Arc::new(AppHealthCheck::new(
-        config.optional.healthcheck_slow_time_limit(),
-        config.optional.healthcheck_hard_time_limit(),
-    ));
+    let app_health = Arc::new(
+        AppHealthCheck::new(
+            config.optional.healthcheck_slow_time_limit(),
+            config.optional.healthcheck_hard_time_limit(),
+        )
+        .with_excluded_components(config.optional.healthcheck_excluded_components.clone()),
+    );
     app_health.insert_custom_component(Arc::new(MainNodeHealthCheck::from(
         main_node_client.clone(),
     )));
@@ -562,42 +1152,45 @@ async fn main() -> anyhow::Result<()> {
     )));
 
     // Start the health check server early into the node lifecycle so that its health can be monitored from the very start.
-    let healthcheck_handle = HealthCheckHandle::spawn_server(
+    let healthcheck_handle = HealthCheckHandle::spawn_server_with_initializing_status(
         ([0, 0, 0, 0], config.required.healthcheck_port).into(),
         app_health.clone(),
-    );
-    // Start scraping Postgres metrics before store initialization as well.
-    let metrics_pool = connection_pool.clone();
+        config.optional.healthcheck_initializing_status_code,
+    )
+    .context("failed starting healthcheck server")?;
     let version_sync_task_pool = connection_pool.clone();
     let version_sync_task_main_node_client = main_node_client.clone();
-    let mut task_handles = vec![
-        tokio::spawn(async move {
-            PostgresMetrics::run_scraping(metrics_pool, Duration::from_secs(60)).await;
-            Ok(())
-        }),
-        tokio::spawn(async move {
-            version_sync_task::sync_versions(
-                version_sync_task_pool,
-                version_sync_task_main_node_client,
-            )
-            .await?;
-            future::pending::<()>().await;
-            // ^ Since this is run as a task, we don't want it to exit on success (this would shut down the node).
+    let mut task_handles = vec![NamedTask::spawn("version_sync", async move {
+        version_sync_task::sync_versions(
+            version_sync_task_pool,
+            version_sync_task_main_node_client,
+        )
+        .await?;
+        future::pending::<()>().await;
+        // ^ Since this is run as a task, we don't want it to exit on success (this would shut down the node).
+        Ok(())
+    })];
+
+    // Start scraping Postgres metrics before store initialization as well.
+    if let Some(scraping_interval) = config.optional.postgres_metrics_scraping_interval()? {
+        let metrics_pool = connection_pool.clone();
+        task_handles.push(NamedTask::spawn("postgres_metrics", async move {
+            PostgresMetrics::run_scraping(metrics_pool, scraping_interval).await;
             Ok(())
-        }),
-    ];
+        }));
+    } else {
+        tracing::info!("Postgres metrics scraping is disabled");
+    }
 
     // Make sure that the node storage is initialized either via genesis or snapshot recovery.
     ensure_storage_initialized(
         &connection_pool,
         &main_node_client,
         &app_health,
-        config.remote.l2_chain_id,
+        &config,
         opt.enable_snapshots_recovery,
     )
     .await?;
-    let sigint_receiver = setup_sigint_handler();
-
     // Revert the storage if needed.
     let reverter = BlockReverter::new(
         NodeRole::External,
@@ -608,65 +1201,98 @@ async fn main() -> anyhow::Result<()> {
         L1ExecutedBatchesRevert::Allowed,
     );
 
-    let mut reorg_detector = ReorgDetector::new(main_node_client.clone(), connection_pool.clone());
+    let mut reorg_detector = ReorgDetector::new(main_node_client.clone(), connection_pool.clone())
+        .with_retries(main_node_client_config.retries)
+        .with_sleep_interval(config.optional.reorg_detector_poll_interval()?);
     // We're checking for the reorg in the beginning because we expect that if reorg is detected during
     // the node lifecycle, the node will exit the same way as it does with any other critical error,
     // and would restart. Then, on the 2nd launch reorg would be detected here, then processed and the node
-    // will be able to operate normally afterwards.
+    // will be able to operate normally afterwards. The same detector is run as a task afterwards, so that
+    // the detected reorg is reported in its health details.
     match reorg_detector.check_consistency().await {
         Ok(()) => {}
         Err(reorg_detector::Error::ReorgDetected(last_correct_l1_batch)) => {
-            tracing::info!("Rolling back to l1 batch number {last_correct_l1_batch}");
-            reverter
-                .rollback_db(last_correct_l1_batch, BlockReverterFlags::all())
-                .await;
-            tracing::info!("Rollback successfully completed");
+            handle_detected_reorg(
+                config.optional.reorg_handling_mode,
+                &reverter,
+                last_correct_l1_batch,
+            )
+            .await?;
         }
         Err(err) => return Err(err).context("reorg_detector.check_consistency()"),
     }
     if opt.revert_pending_l1_batch {
         tracing::info!("Rolling pending L1 batch back..");
-        let mut connection = connection_pool.connection().await?;
-        let sealed_l1_batch_number = connection
-            .blocks_dal()
-            .get_sealed_l1_batch_number()
-            .await
-            .context("Failed getting sealed L1 batch number")?
-            .context(
-                "Cannot roll back pending L1 batch since there are no L1 batches in Postgres",
-            )?;
-        drop(connection);
-
-        tracing::info!("Rolling back to l1 batch number {sealed_l1_batch_number}");
-        reverter
-            .rollback_db(sealed_l1_batch_number, BlockReverterFlags::all())
-            .await;
-        tracing::info!("Rollback successfully completed");
+        revert_pending_l1_batch(&connection_pool, &reverter, &mut TerminalPrompt, opt.yes).await?;
+    }
+    if let Some(target_l1_batch) = opt.revert_to_l1_batch {
+        tracing::info!("Rolling back to L1 batch #{target_l1_batch}..");
+        revert_to_l1_batch(
+            &connection_pool,
+            &reverter,
+            L1BatchNumber(target_l1_batch),
+            &mut TerminalPrompt,
+            opt.yes,
+        )
+        .await?;
+        healthcheck_handle.stop().await;
+        return Ok(());
+    }
+    if opt.rebuild_tree {
+        rebuild_merkle_tree(&config, &connection_pool).await?;
+        healthcheck_handle.stop().await;
+        return Ok(());
     }
 
-    let (stop_sender, stop_receiver) = watch::channel(false);
+    let promotion_receiver = if opt.standby {
+        let (promotion_sender, promotion_receiver) = watch::channel(false);
+        let mut promotion_signal =
+            signal(SignalKind::user_defined1()).context("failed setting up SIGUSR1 handler")?;
+        let promotion_task = NamedTask::spawn("standby_promotion", async move {
+            promotion_signal.recv().await;
+            tracing::info!("Received SIGUSR1, promoting the node from the standby mode");
+            promotion_sender.send_replace(true);
+            Ok(())
+        });
+        task_handles.push(promotion_task.allowed_to_finish());
+        Some(promotion_receiver)
+    } else {
+        None
+    };
+
     init_tasks(
         &config,
+        consensus_secrets,
         connection_pool.clone(),
         main_node_client.clone(),
+        reorg_detector,
+        promotion_receiver,
         &mut task_handles,
         &app_health,
         stop_receiver.clone(),
     )
     .await
     .context("init_tasks")?;
+    log_spawned_tasks(&task_handles);
+    app_health
+        .validate_excluded_components()
+        .context("invalid `healthcheck_excluded_components` config")?;
 
     let mut tasks = ManagedTasks::new(task_handles);
     tokio::select! {
         _ = tasks.wait_single() => {},
-        _ = sigint_receiver => {
-            tracing::info!("Stop signal received, shutting down");
-        },
+        _ = stop_receiver.wait_for(|stop| *stop) => {},
     };
 
     // Reaching this point means that either some actor exited unexpectedly or we received a stop signal.
     // Broadcast the stop signal to all actors and exit.
-    shutdown_components(stop_sender, tasks, healthcheck_handle).await?;
+    shutdown_components(
+        &stop_sender,
+        tasks,
+        healthcheck_handle,
+        config.optional.shutdown_timeout(),
+    )
+    .await?;
     tracing::info!("Stopped");
     Ok(())
 }
@@ -5,6 +5,8 @@ use vise::{Gauge, LabeledFamily, Metrics};
 pub(crate) struct EnMetrics {
     #[metrics(labels = ["server_version", "protocol_version"])]
     pub version: LabeledFamily<(String, Option<u16>), Gauge<u64>, 2>,
+    /// Lag of the database replica used by the API servers behind the primary database, in L1 batches.
+    pub database_replica_lag: Gauge<u64>,
 }
 
 #[vise::register]
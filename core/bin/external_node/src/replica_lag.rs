@@ -0,0 +1,169 @@
+//! Monitoring of the lag of the database replica used by the API servers.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_basic_types::L1BatchNumber;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+
+use crate::metrics::EN_METRICS;
+
+#[derive(Debug, Serialize)]
+struct ReplicaLagHealthDetails {
+    primary_l1_batch: Option<L1BatchNumber>,
+    replica_l1_batch: Option<L1BatchNumber>,
+    l1_batch_lag: u32,
+}
+
+/// Periodically compares the latest L1 batch in the database replica used by the API servers with the one
+/// in the primary database. If the replica lags behind the primary by more than the configured threshold,
+/// the checker health is marked as degraded, so that operators can decide whether to switch API reads to the primary.
+#[derive(Debug)]
+pub(crate) struct ReplicaLagChecker {
+    primary_pool: ConnectionPool<Core>,
+    replica_pool: ConnectionPool<Core>,
+    max_l1_batch_lag: u32,
+    check_interval: Duration,
+    health_updater: HealthUpdater,
+}
+
+impl ReplicaLagChecker {
+    const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+    pub fn new(
+        primary_pool: ConnectionPool<Core>,
+        replica_pool: ConnectionPool<Core>,
+        max_l1_batch_lag: u32,
+    ) -> Self {
+        Self {
+            primary_pool,
+            replica_pool,
+            max_l1_batch_lag,
+            check_interval: Self::DEFAULT_CHECK_INTERVAL,
+            health_updater: ReactiveHealthCheck::new("database_replica").1,
+        }
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    async fn sealed_l1_batch_number(
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<Option<L1BatchNumber>> {
+        let mut storage = pool.connection_tagged("en").await?;
+        storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .context("get_sealed_l1_batch_number()")
+    }
+
+    /// Performs a single lag check and returns the lag of the replica in L1 batches.
+    async fn check(&self) -> anyhow::Result<u32> {
+        // Query the replica first, so that the replica cannot appear ahead of the primary.
+        let replica_l1_batch = Self::sealed_l1_batch_number(&self.replica_pool).await?;
+        let primary_l1_batch = Self::sealed_l1_batch_number(&self.primary_pool).await?;
+        let l1_batch_lag = match (primary_l1_batch, replica_l1_batch) {
+            (Some(primary), Some(replica)) => primary.0.saturating_sub(replica.0),
+            (Some(primary), None) => primary.0 + 1,
+            (None, _) => 0,
+        };
+        EN_METRICS.database_replica_lag.set(l1_batch_lag.into());
+
+        let status = if l1_batch_lag > self.max_l1_batch_lag {
+            tracing::warn!(
+                "Database replica used by API servers lags behind the primary database by {l1_batch_lag} L1 batches \
+                 (primary: {primary_l1_batch:?}, replica: {replica_l1_batch:?}); API clients may observe stale data"
+            );
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Ready
+        };
+        let details = ReplicaLagHealthDetails {
+            primary_l1_batch,
+            replica_l1_batch,
+            l1_batch_lag,
+        };
+        self.health_updater
+            .update(Health::from(status).with_details(details));
+        Ok(l1_batch_lag)
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow() {
+            if let Err(err) = self.check().await {
+                tracing::warn!("Failed checking database replica lag: {err:#}");
+            }
+            if tokio::time::timeout(self.check_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, replica lag checker is shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops;
+
+    use zksync_health_check::CheckHealth;
+    use zksync_types::{block::L1BatchHeader, ProtocolVersion, ProtocolVersionId};
+
+    use super::*;
+
+    async fn seal_l1_batches(pool: &ConnectionPool<Core>, numbers: ops::Range<u32>) {
+        let mut storage = pool.connection().await.unwrap();
+        if numbers.start == 0 {
+            storage
+                .protocol_versions_dal()
+                .save_protocol_version_with_tx(ProtocolVersion::default())
+                .await;
+        }
+        for number in numbers {
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                number.into(),
+                Default::default(),
+                ProtocolVersionId::latest(),
+            );
+            storage
+                .blocks_dal()
+                .insert_mock_l1_batch(&header)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn replica_lag_is_reported() {
+        let primary_pool = ConnectionPool::<Core>::test_pool().await;
+        let replica_pool = ConnectionPool::<Core>::test_pool().await;
+        seal_l1_batches(&primary_pool, 0..5).await;
+        seal_l1_batches(&replica_pool, 0..4).await;
+
+        let checker = ReplicaLagChecker::new(primary_pool.clone(), replica_pool.clone(), 2);
+        let health_check = checker.health_check();
+        assert_eq!(checker.check().await.unwrap(), 1);
+        assert_eq!(
+            health_check.check_health().await.status(),
+            HealthStatus::Ready
+        );
+        assert_eq!(EN_METRICS.database_replica_lag.get(), 1);
+
+        // Simulate the primary database progressing while the replica is stuck.
+        seal_l1_batches(&primary_pool, 5..8).await;
+        assert_eq!(checker.check().await.unwrap(), 4);
+        assert_eq!(
+            health_check.check_health().await.status(),
+            HealthStatus::Degraded
+        );
+    }
+}
@@ -0,0 +1,256 @@
+//! Periodic creation of application-level snapshots of the node storage.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use serde::Serialize;
+use snapshots_creator::{SnapshotCreator, MIN_CHUNK_COUNT};
+use tokio::sync::watch;
+use zksync_basic_types::L1BatchNumber;
+use zksync_config::SnapshotsCreatorConfig;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_object_store::ObjectStore;
+
+#[derive(Debug, Serialize)]
+struct SnapshotsCreatorHealthDetails {
+    l1_batch_number: L1BatchNumber,
+    is_complete: bool,
+    storage_logs_chunk_count: usize,
+    processed_storage_logs_chunk_count: usize,
+}
+
+/// Periodically runs [`SnapshotCreator`] to create snapshots of the node storage, so that other nodes can recover
+/// from them without relying on snapshots produced by the main node. Progress of the latest snapshot is reported
+/// via the health check.
+///
+/// Snapshots are only created for L1 batches executed on L1, so that they never contain data that could be reverted.
+/// Snapshot creation is resumable, so a snapshot interrupted by the node shutdown is finished on the next run.
+#[derive(Debug)]
+pub(crate) struct PeriodicSnapshotsCreator {
+    blob_store: Arc<dyn ObjectStore>,
+    master_pool: ConnectionPool<Core>,
+    replica_pool: ConnectionPool<Core>,
+    config: SnapshotsCreatorConfig,
+    interval: Duration,
+    health_updater: HealthUpdater,
+}
+
+impl PeriodicSnapshotsCreator {
+    pub fn new(
+        blob_store: Arc<dyn ObjectStore>,
+        master_pool: ConnectionPool<Core>,
+        replica_pool: ConnectionPool<Core>,
+        config: SnapshotsCreatorConfig,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            blob_store,
+            master_pool,
+            replica_pool,
+            config,
+            interval,
+            health_updater: ReactiveHealthCheck::new("snapshots_creator").1,
+        }
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    async fn update_health(&self) -> anyhow::Result<()> {
+        let mut storage = self.master_pool.connection_tagged("en").await?;
+        let latest_snapshot = storage
+            .snapshots_dal()
+            .get_newest_snapshot_metadata()
+            .await
+            .context("get_newest_snapshot_metadata()")?;
+        drop(storage);
+
+        let mut health = Health::from(HealthStatus::Ready);
+        if let Some(snapshot) = latest_snapshot {
+            let paths = &snapshot.storage_logs_filepaths;
+            health = health.with_details(SnapshotsCreatorHealthDetails {
+                l1_batch_number: snapshot.l1_batch_number,
+                is_complete: snapshot.is_complete(),
+                storage_logs_chunk_count: paths.len(),
+                processed_storage_logs_chunk_count: paths.iter().flatten().count(),
+            });
+        }
+        self.health_updater.update(health);
+        Ok(())
+    }
+
+    /// Creates a snapshot for the last L1 batch executed on L1 (or finishes a pending snapshot).
+    async fn create_snapshot(&self) -> anyhow::Result<()> {
+        let mut storage = self.master_pool.connection_tagged("en").await?;
+        let last_executed_l1_batch = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await
+            .context("get_number_of_last_l1_batch_executed_on_eth()")?;
+        drop(storage);
+
+        let Some(last_executed_l1_batch) = last_executed_l1_batch else {
+            tracing::info!("No L1 batches are executed on L1 yet; skipping snapshot creation");
+            return Ok(());
+        };
+        SnapshotCreator::new(
+            self.blob_store.clone(),
+            self.master_pool.clone(),
+            self.replica_pool.clone(),
+        )
+        .with_max_l1_batch_number(last_executed_l1_batch)
+        .run(self.config.clone(), MIN_CHUNK_COUNT)
+        .await
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        // Report the latest snapshot (which may be pending) before the first run, which can take a long time.
+        if let Err(err) = self.update_health().await {
+            tracing::warn!("Failed updating snapshots creator health: {err:#}");
+        }
+
+        while !*stop_receiver.borrow() {
+            tokio::select! {
+                result = self.create_snapshot() => {
+                    if let Err(err) = result {
+                        tracing::warn!("Failed creating storage snapshot: {err:#}");
+                    }
+                }
+                _ = stop_receiver.changed() => break,
+            }
+            if let Err(err) = self.update_health().await {
+                tracing::warn!("Failed updating snapshots creator health: {err:#}");
+            }
+
+            if tokio::time::timeout(self.interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, snapshots creator is shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use zksync_health_check::CheckHealth;
+    use zksync_object_store::ObjectStoreFactory;
+    use zksync_types::{aggregated_operations::AggregatedActionType, H256};
+
+    use super::*;
+    use crate::tests::seal_l1_batches;
+
+    const TEST_CONFIG: SnapshotsCreatorConfig = SnapshotsCreatorConfig {
+        storage_logs_chunk_size: 1_000_000,
+        concurrent_queries_count: 1,
+    };
+
+    async fn mark_l1_batches_as_executed(pool: &ConnectionPool<Core>, l1_batches: &[u32]) {
+        let mut storage = pool.connection().await.unwrap();
+        for &number in l1_batches {
+            storage
+                .eth_sender_dal()
+                .insert_bogus_confirmed_eth_tx(
+                    L1BatchNumber(number),
+                    AggregatedActionType::Execute,
+                    H256::from_low_u64_be(number.into()),
+                    Utc::now(),
+                )
+                .await
+                .unwrap();
+        }
+    }
+
+    async fn create_snapshots_creator(pool: &ConnectionPool<Core>) -> PeriodicSnapshotsCreator {
+        let blob_store = ObjectStoreFactory::mock().create_store().await;
+        PeriodicSnapshotsCreator::new(
+            blob_store,
+            pool.clone(),
+            pool.clone(),
+            TEST_CONFIG,
+            Duration::from_millis(10),
+        )
+    }
+
+    async fn complete_snapshots(pool: &ConnectionPool<Core>) -> Vec<L1BatchNumber> {
+        let mut storage = pool.connection().await.unwrap();
+        storage
+            .snapshots_dal()
+            .get_all_complete_snapshots()
+            .await
+            .unwrap()
+            .snapshots_l1_batch_numbers
+    }
+
+    #[tokio::test]
+    async fn snapshots_are_capped_by_last_executed_l1_batch() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        seal_l1_batches(&mut storage, 5).await;
+        drop(storage);
+        let creator = create_snapshots_creator(&pool).await;
+
+        creator.create_snapshot().await.unwrap();
+        assert_eq!(complete_snapshots(&pool).await, []);
+
+        mark_l1_batches_as_executed(&pool, &[1, 2]).await;
+        creator.create_snapshot().await.unwrap();
+        assert_eq!(complete_snapshots(&pool).await, [L1BatchNumber(2)]);
+
+        // Even if all L1 batches are executed, the snapshot is not created for the last sealed L1 batch.
+        mark_l1_batches_as_executed(&pool, &[3, 4, 5]).await;
+        creator.create_snapshot().await.unwrap();
+        assert_eq!(
+            complete_snapshots(&pool).await,
+            [L1BatchNumber(4), L1BatchNumber(2)]
+        );
+    }
+
+    #[tokio::test]
+    async fn snapshots_creator_health() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        seal_l1_batches(&mut storage, 5).await;
+        drop(storage);
+        mark_l1_batches_as_executed(&pool, &[1, 2, 3]).await;
+        let creator = create_snapshots_creator(&pool).await;
+        let health_check = creator.health_check();
+        assert_eq!(
+            health_check.check_health().await.status(),
+            HealthStatus::NotReady
+        );
+
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let creator_task = tokio::spawn(creator.run(stop_receiver));
+        let health = tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                let health = health_check.check_health().await;
+                let details = serde_json::to_value(&health).unwrap()["details"].clone();
+                if details["is_complete"] == true {
+                    return details;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("snapshot was not created");
+        assert_eq!(health["l1_batch_number"], 3);
+        assert_eq!(
+            health["storage_logs_chunk_count"],
+            health["processed_storage_logs_chunk_count"]
+        );
+
+        stop_sender.send_replace(true);
+        creator_task.await.unwrap().unwrap();
+        assert_eq!(
+            health_check.check_health().await.status(),
+            HealthStatus::ShutDown
+        );
+    }
+}
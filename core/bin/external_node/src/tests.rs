@@ -0,0 +1,621 @@
+//! High-level tests for the external node wiring.
+
+use std::{
+    collections::HashMap,
+    fmt, iter,
+    net::{Ipv4Addr, SocketAddr},
+    sync::Mutex,
+};
+
+use tempfile::TempDir;
+use tracing_subscriber::{layer::SubscriberExt, Layer};
+use zksync_contracts::BaseSystemContractsHashes;
+use zksync_core::api_server::tree::TreeApiHttpClient;
+use zksync_dal::Connection;
+use zksync_types::{
+    block::{L1BatchHeader, MiniblockHeader},
+    fee_model::BatchFeeInput,
+    AccountTreeId, Address, MiniblockNumber, ProtocolVersion, ProtocolVersionId, StorageKey,
+    StorageLog, H256,
+};
+use zksync_web3_decl::{
+    jsonrpsee::{http_client::HttpClientBuilder, ws_client::WsClientBuilder},
+    namespaces::{EthNamespaceClient, ZksNamespaceClient},
+};
+
+use super::*;
+
+const TEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Creates a mock config with API servers bound to random ports. Bound addresses can be read via
+/// [`wait_for_api_servers()`].
+fn mock_config() -> ExternalNodeConfig {
+    let mut config = ExternalNodeConfig::mock();
+    config.required.http_port = 0;
+    config.required.ws_port = 0;
+    config
+}
+
+/// Creates a client for the main node. The main node is never reached in tests; the client is only used
+/// to proxy transactions.
+fn mock_main_node_client() -> HttpClient {
+    HttpClientBuilder::default()
+        .build("http://127.0.0.1:1/")
+        .unwrap()
+}
+
+/// Seals L1 batches #0..=#`last_l1_batch`, each consisting of a single miniblock with the same number.
+pub(crate) async fn seal_l1_batches(storage: &mut Connection<'_, Core>, last_l1_batch: u32) {
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion::default())
+        .await;
+    for number in 0..=last_l1_batch {
+        seal_l1_batch(storage, number).await;
+        storage
+            .blocks_dal()
+            .set_l1_batch_hash(L1BatchNumber(number), H256::from_low_u64_be(number.into()))
+            .await
+            .unwrap();
+    }
+}
+
+/// Seals an L1 batch consisting of a single miniblock with the same number. Unlike [`seal_l1_batches()`],
+/// doesn't set the L1 batch state hash, so that it can be computed by the Merkle tree.
+async fn seal_l1_batch(storage: &mut Connection<'_, Core>, number: u32) {
+    let miniblock = MiniblockHeader {
+        number: MiniblockNumber(number),
+        timestamp: number.into(),
+        hash: H256::from_low_u64_be(number.into()),
+        l1_tx_count: 0,
+        l2_tx_count: 0,
+        fee_account_address: Address::zero(),
+        base_fee_per_gas: 100,
+        batch_fee_input: BatchFeeInput::l1_pegged(100, 100),
+        gas_per_pubdata_limit: 50_000,
+        base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+        protocol_version: Some(ProtocolVersionId::latest()),
+        virtual_blocks: 1,
+    };
+    storage
+        .blocks_dal()
+        .insert_miniblock(&miniblock)
+        .await
+        .unwrap();
+
+    let l1_batch_number = L1BatchNumber(number);
+    let l1_batch = L1BatchHeader::new(
+        l1_batch_number,
+        number.into(),
+        BaseSystemContractsHashes::default(),
+        ProtocolVersionId::latest(),
+    );
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&l1_batch)
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .mark_miniblocks_as_executed_in_l1_batch(l1_batch_number)
+        .await
+        .unwrap();
+}
+
+/// Dependencies shared by tests running the node API via [`run_api()`].
+struct ApiTestFixture {
+    config: ExternalNodeConfig,
+    /// Pool for the primary database.
+    pool: ConnectionPool<Core>,
+    /// Pool used by the API servers. Points to the primary database unless overridden.
+    api_pool: ConnectionPool<Core>,
+    tree_reader: Arc<dyn TreeApiClient>,
+}
+
+impl ApiTestFixture {
+    async fn new() -> Self {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        Self {
+            config: mock_config(),
+            api_pool: pool.clone(),
+            pool,
+            // The tree API is never reached in tests using this reader.
+            tree_reader: Arc::new(TreeApiHttpClient::new("http://127.0.0.1:1")),
+        }
+    }
+
+    /// Runs API servers and returns the VM concurrency limiter shared by them.
+    async fn run_api(
+        &self,
+        task_handles: &mut Vec<NamedTask>,
+        app_health: &AppHealthCheck,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> Arc<VmConcurrencyLimiter> {
+        let api_config = InternalApiConfig::try_from(self.config.clone()).unwrap();
+        let main_node_client = mock_main_node_client();
+        let fee_params_fetcher = Arc::new(MainNodeFeeParamsFetcher::new(main_node_client.clone()));
+        let (vm_concurrency_limiter, vm_barrier) =
+            create_vm_concurrency_limiter(&self.config.optional);
+        run_api(
+            &self.config,
+            api_config,
+            self.api_pool.clone(),
+            self.pool.clone(),
+            main_node_client,
+            fee_params_fetcher,
+            SyncState::default(),
+            self.tree_reader.clone(),
+            vm_concurrency_limiter.clone(),
+            vm_barrier,
+            task_handles,
+            app_health,
+            stop_receiver,
+        )
+        .await
+        .unwrap();
+        vm_concurrency_limiter
+    }
+
+    fn spawn_api_after_promotion(
+        &self,
+        app_health: Arc<AppHealthCheck>,
+        promotion_receiver: watch::Receiver<bool>,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> tokio::task::JoinHandle<anyhow::Result<()>> {
+        let api_config = InternalApiConfig::try_from(self.config.clone()).unwrap();
+        let main_node_client = mock_main_node_client();
+        let fee_params_fetcher = Arc::new(MainNodeFeeParamsFetcher::new(main_node_client.clone()));
+        tokio::spawn(run_api_after_promotion(
+            self.config.clone(),
+            api_config,
+            self.api_pool.clone(),
+            self.pool.clone(),
+            main_node_client,
+            fee_params_fetcher,
+            SyncState::default(),
+            self.tree_reader.clone(),
+            app_health,
+            promotion_receiver,
+            stop_receiver,
+        ))
+    }
+}
+
+/// `tracing` layer recording fields of all logged events.
+#[derive(Debug, Clone, Default)]
+struct RecordingLayer {
+    events: Arc<Mutex<Vec<HashMap<&'static str, String>>>>,
+}
+
+impl<S: tracing::Subscriber> Layer<S> for RecordingLayer {
+    fn on_event(
+        &self,
+        event: &tracing::Event<'_>,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        struct FieldsVisitor(HashMap<&'static str, String>);
+
+        impl tracing::field::Visit for FieldsVisitor {
+            fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+                self.0.insert(field.name(), value.to_string());
+            }
+
+            fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn fmt::Debug) {
+                self.0.insert(field.name(), format!("{value:?}"));
+            }
+        }
+
+        let mut visitor = FieldsVisitor(HashMap::new());
+        event.record(&mut visitor);
+        self.events.lock().unwrap().push(visitor.0);
+    }
+}
+
+async fn wait_for_component_status(app_health: &AppHealthCheck, component: &str, status: &str) {
+    loop {
+        let health = serde_json::to_value(app_health.check_health().await).unwrap();
+        if health["components"][component]["status"] == status {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+/// Waits until HTTP and WS API servers are ready and returns their local addresses.
+async fn wait_for_api_servers(app_health: &AppHealthCheck) -> (SocketAddr, SocketAddr) {
+    loop {
+        let health = serde_json::to_value(app_health.check_health().await).unwrap();
+        let components = &health["components"];
+        if components["http_api"]["status"] == "ready" && components["ws_api"]["status"] == "ready"
+        {
+            let local_addr = |component: &str| {
+                let details = &components[component]["details"];
+                let addr: SocketAddr = serde_json::from_value(details["local_addr"].clone())
+                    .expect("no local address in API server health details");
+                SocketAddr::from((Ipv4Addr::LOCALHOST, addr.port()))
+            };
+            return (local_addr("http_api"), local_addr("ws_api"));
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+#[test]
+fn parsing_cli_with_maintenance_operation() {
+    let cli = Cli::try_parse_from(["external_node", "--revert-to-l1-batch", "5", "--yes"]).unwrap();
+    assert_eq!(cli.revert_to_l1_batch, Some(5));
+    assert!(!cli.revert_pending_l1_batch && !cli.rebuild_tree);
+}
+
+#[test]
+fn rejecting_conflicting_maintenance_flags() {
+    let conflicting_args: &[&[&str]] = &[
+        &["--revert-pending-l1-batch", "--revert-to-l1-batch", "5"],
+        &["--revert-pending-l1-batch", "--rebuild-tree"],
+        &["--revert-to-l1-batch", "5", "--rebuild-tree"],
+        &["--rebuild-tree", "--standby"],
+    ];
+    for &args in conflicting_args {
+        let err = Cli::try_parse_from(iter::once("external_node").chain(args.iter().copied()))
+            .unwrap_err();
+        assert_eq!(
+            err.kind(),
+            clap::error::ErrorKind::ArgumentConflict,
+            "{args:?}"
+        );
+        let err = err.to_string();
+        assert!(err.contains("cannot be used with"), "{args:?}: {err}");
+    }
+}
+
+#[tokio::test]
+async fn running_api_with_http_and_ws_servers() {
+    let fixture = ApiTestFixture::new().await;
+    let config = &fixture.config;
+    let app_health = AppHealthCheck::new(None, None);
+    let mut task_handles = vec![];
+    let (stop_sender, stop_receiver) = watch::channel(false);
+
+    let vm_concurrency_limiter = fixture
+        .run_api(&mut task_handles, &app_health, stop_receiver)
+        .await;
+
+    let task_names: Vec<_> = task_handles.iter().map(NamedTask::name).collect();
+    assert!(task_names.contains(&"http_api"), "{task_names:?}");
+    assert!(task_names.contains(&"ws_api"), "{task_names:?}");
+    assert!(
+        task_names.contains(&"proxy_cache_updater"),
+        "{task_names:?}"
+    );
+
+    let (http_addr, ws_addr) =
+        tokio::time::timeout(TEST_TIMEOUT, wait_for_api_servers(&app_health))
+            .await
+            .expect("timed out waiting for API servers to become ready");
+
+    let http_client = HttpClientBuilder::default()
+        .build(format!("http://{http_addr}/"))
+        .unwrap();
+    let chain_id = http_client.chain_id().await.unwrap();
+    assert_eq!(chain_id.as_u64(), config.remote.l2_chain_id.as_u64());
+
+    let ws_client = WsClientBuilder::default()
+        .build(format!("ws://{ws_addr}/"))
+        .await
+        .unwrap();
+    let chain_id = ws_client.chain_id().await.unwrap();
+    assert_eq!(chain_id.as_u64(), config.remote.l2_chain_id.as_u64());
+    drop(ws_client);
+
+    // Emulate a VM execution in progress; both servers must wait for it on shutdown since they share the VM barrier.
+    let vm_permit = vm_concurrency_limiter.acquire().await.unwrap();
+    stop_sender.send_replace(true);
+    tokio::time::timeout(TEST_TIMEOUT, async {
+        loop {
+            let health = serde_json::to_value(app_health.check_health().await).unwrap();
+            let components = &health["components"];
+            if components["http_api"]["status"] == "shut_down"
+                && components["ws_api"]["status"] == "shut_down"
+            {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("timed out waiting for API servers to stop serving requests");
+    // The barrier is closed by the servers on shutdown, so no new VM executions can start.
+    assert!(vm_concurrency_limiter.acquire().await.is_none());
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    for task in &task_handles {
+        if matches!(task.name(), "http_api" | "ws_api") {
+            assert!(
+                !task.is_finished(),
+                "{} stopped before VM barrier was released",
+                task.name()
+            );
+        }
+    }
+
+    drop(vm_permit);
+    ManagedTasks::new(task_handles).complete(TEST_TIMEOUT).await;
+}
+
+#[tokio::test]
+async fn api_reads_from_replica_pool() {
+    let mut fixture = ApiTestFixture::new().await;
+    // Emulate a replica by a separate database. Miniblocks are only present in the replica, so if the API servers
+    // used the primary database, they would return a different block number.
+    fixture.api_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = fixture.api_pool.connection().await.unwrap();
+    seal_l1_batches(&mut storage, 2).await;
+    drop(storage);
+
+    let app_health = AppHealthCheck::new(None, None);
+    let mut task_handles = vec![];
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    fixture
+        .run_api(&mut task_handles, &app_health, stop_receiver)
+        .await;
+    let (http_addr, _) = tokio::time::timeout(TEST_TIMEOUT, wait_for_api_servers(&app_health))
+        .await
+        .expect("timed out waiting for API servers to become ready");
+
+    let http_client = HttpClientBuilder::default()
+        .build(format!("http://{http_addr}/"))
+        .unwrap();
+    let block_number = http_client.get_block_number().await.unwrap();
+    assert_eq!(block_number.as_u64(), 2);
+
+    stop_sender.send_replace(true);
+    ManagedTasks::new(task_handles).complete(TEST_TIMEOUT).await;
+}
+
+/// Checks the task topology dumped after the node initialization. Only API tasks spawned by `run_api()`
+/// are covered; the rest of `init_tasks()` requires L1 and main node connections, so it cannot run in this test.
+#[tokio::test]
+async fn spawned_api_tasks_match_enabled_api_components() {
+    let fixture = ApiTestFixture::new().await;
+    let app_health = AppHealthCheck::new(None, None);
+    let mut task_handles = vec![];
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    fixture
+        .run_api(&mut task_handles, &app_health, stop_receiver)
+        .await;
+
+    let recording_layer = RecordingLayer::default();
+    let subscriber = tracing_subscriber::registry().with(recording_layer.clone());
+    tracing::subscriber::with_default(subscriber, || log_spawned_tasks(&task_handles));
+    let events = recording_layer.events.lock().unwrap().clone();
+    let [event] = events.as_slice() else {
+        panic!("unexpected logged events: {events:?}");
+    };
+    assert_eq!(event["message"], "Spawned node tasks");
+    assert_eq!(event["task_count"], task_handles.len().to_string());
+
+    // The latest values cache is enabled by default, while API contracts reloading is not.
+    let logged_tasks = &event["tasks"];
+    let expected_task_names = [
+        "http_api",
+        "proxy_cache_updater",
+        "storage_values_cache_updater",
+        "ws_api",
+    ];
+    let logged_task_names: Vec<_> = logged_tasks
+        .trim_matches(|ch| ch == '{' || ch == '}')
+        .split(", ")
+        .map(|entry| entry.split(':').next().unwrap().trim_matches('"'))
+        .collect();
+    assert_eq!(logged_task_names, expected_task_names, "{logged_tasks}");
+    // All API server components advertised by the node must be backed by tasks.
+    for component in fixture.config.components() {
+        if component.ends_with("_api") {
+            assert!(logged_task_names.contains(&component), "{logged_tasks}");
+        }
+    }
+
+    stop_sender.send_replace(true);
+    ManagedTasks::new(task_handles).complete(TEST_TIMEOUT).await;
+}
+
+#[tokio::test]
+async fn api_is_started_only_after_standby_promotion() {
+    let fixture = ApiTestFixture::new().await;
+    let config = &fixture.config;
+    let app_health = Arc::new(AppHealthCheck::new(None, None));
+    let (promotion_sender, promotion_receiver) = watch::channel(false);
+    let (stop_sender, stop_receiver) = watch::channel(false);
+
+    let api_task =
+        fixture.spawn_api_after_promotion(app_health.clone(), promotion_receiver, stop_receiver);
+
+    tokio::time::timeout(
+        TEST_TIMEOUT,
+        wait_for_component_status(&app_health, "standby", "standby"),
+    )
+    .await
+    .expect("timed out waiting for standby health status");
+    let health = app_health.check_health().await;
+    assert!(!health.is_healthy());
+    let health = serde_json::to_value(health).unwrap();
+    assert_eq!(health["status"], "standby");
+    let components = health["components"].as_object().unwrap();
+    assert!(!components.contains_key("http_api"), "{components:?}");
+    assert!(!components.contains_key("ws_api"), "{components:?}");
+
+    promotion_sender.send_replace(true);
+    let (http_addr, _) = tokio::time::timeout(TEST_TIMEOUT, wait_for_api_servers(&app_health))
+        .await
+        .expect("timed out waiting for API servers to become ready");
+    tokio::time::timeout(
+        TEST_TIMEOUT,
+        wait_for_component_status(&app_health, "standby", "ready"),
+    )
+    .await
+    .expect("timed out waiting for node promotion");
+    let http_client = HttpClientBuilder::default()
+        .build(format!("http://{http_addr}/"))
+        .unwrap();
+    let chain_id = http_client.chain_id().await.unwrap();
+    assert_eq!(chain_id.as_u64(), config.remote.l2_chain_id.as_u64());
+
+    stop_sender.send_replace(true);
+    tokio::time::timeout(TEST_TIMEOUT, api_task)
+        .await
+        .expect("timed out waiting for API task to stop")
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn serving_merkle_proofs_in_full_tree_mode() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion::default())
+        .await;
+    seal_l1_batch(&mut storage, 0).await;
+    seal_l1_batch(&mut storage, 1).await;
+    let address = Address::repeat_byte(1);
+    let storage_key = StorageKey::new(AccountTreeId::new(address), H256::repeat_byte(2));
+    let storage_log = StorageLog::new_write_log(storage_key, H256::repeat_byte(3));
+    storage
+        .storage_logs_dal()
+        .insert_storage_logs(MiniblockNumber(1), &[(H256::zero(), vec![storage_log])])
+        .await
+        .unwrap();
+    storage
+        .storage_logs_dedup_dal()
+        .insert_initial_writes(L1BatchNumber(1), &[storage_key])
+        .await
+        .unwrap();
+    drop(storage);
+
+    let temp_dir = TempDir::new().unwrap();
+    let mut config = mock_config();
+    config.optional = envy::prefixed("EN_")
+        .from_iter([("EN_MERKLE_TREE_MODE".to_owned(), "full".to_owned())])
+        .unwrap();
+    config.required.merkle_tree_path = temp_dir.path().to_str().unwrap().to_owned();
+    let metadata_calculator_config = metadata_calculator_config(&config, None).unwrap();
+    let metadata_calculator = MetadataCalculator::new(metadata_calculator_config, None)
+        .await
+        .unwrap();
+    let tree_reader = metadata_calculator.tree_reader();
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let calculator_task =
+        tokio::spawn(metadata_calculator.run(pool.clone(), stop_receiver.clone()));
+
+    tokio::time::timeout(TEST_TIMEOUT, async {
+        loop {
+            if let Ok(info) = tree_reader.get_info().await {
+                if info.next_l1_batch_number > L1BatchNumber(1) {
+                    return;
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("timed out waiting for Merkle tree to process L1 batches");
+
+    let fixture = ApiTestFixture {
+        config,
+        pool,
+        tree_reader: Arc::new(tree_reader),
+    };
+    let app_health = AppHealthCheck::new(None, None);
+    let mut task_handles = vec![];
+    fixture
+        .run_api(&mut task_handles, &app_health, stop_receiver)
+        .await;
+    let (http_addr, _) = tokio::time::timeout(TEST_TIMEOUT, wait_for_api_servers(&app_health))
+        .await
+        .expect("timed out waiting for API servers to become ready");
+
+    let http_client = HttpClientBuilder::default()
+        .build(format!("http://{http_addr}/"))
+        .unwrap();
+    let proof = http_client
+        .get_proof(address, vec![H256::repeat_byte(2)], L1BatchNumber(1))
+        .await
+        .unwrap()
+        .expect("no proof for sealed L1 batch");
+    assert_eq!(proof.address, address);
+    assert_eq!(proof.storage_proof.len(), 1);
+    let storage_proof = &proof.storage_proof[0];
+    assert_eq!(storage_proof.key, H256::repeat_byte(2));
+    assert_eq!(storage_proof.value, H256::repeat_byte(3));
+    assert!(!storage_proof.proof.is_empty());
+
+    stop_sender.send_replace(true);
+    ManagedTasks::new(task_handles).complete(TEST_TIMEOUT).await;
+    calculator_task.await.unwrap().unwrap();
+}
+
+/// Spawns a task emulating a synchronization task: it runs until the sync stop signal.
+fn spawn_sync_task(name: &'static str, sync_stop_signal: &SyncStopSignal) -> NamedTask {
+    let mut stop_receiver = sync_stop_signal.receiver.clone();
+    NamedTask::spawn(name, async move {
+        stop_receiver.wait_for(|stop| *stop).await?;
+        Ok(())
+    })
+}
+
+#[tokio::test]
+async fn consistency_failure_stops_only_sync_tasks_in_read_only_mode() {
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let mut task_handles = vec![NamedTask::spawn("api", {
+        let mut stop_receiver = stop_receiver.clone();
+        async move {
+            stop_receiver.wait_for(|stop| *stop).await?;
+            Ok(())
+        }
+    })];
+    let sync_stop_signal = SyncStopSignal::new(&stop_receiver, true, &mut task_handles);
+    let sync_tasks = vec![
+        spawn_sync_task("state_keeper", &sync_stop_signal),
+        spawn_sync_task("consensus_fetcher", &sync_stop_signal),
+    ];
+    task_handles.extend(sync_stop_signal.managed_tasks(sync_tasks));
+    let mut tasks = ManagedTasks::new(task_handles);
+
+    // Emulate a consistency checker failure.
+    let halt_sender = sync_stop_signal.halt_sender.as_ref().unwrap();
+    halt_sender.send_replace(true);
+    let mut sync_stop_receiver = sync_stop_signal.receiver.clone();
+    tokio::time::timeout(TEST_TIMEOUT, sync_stop_receiver.wait_for(|stop| *stop))
+        .await
+        .expect("timed out waiting for sync stop signal")
+        .unwrap();
+    assert!(!*stop_receiver.borrow());
+
+    // Sync tasks have finished, but this must not trigger the node shutdown.
+    let wait_result = tokio::time::timeout(Duration::from_millis(500), tasks.wait_single()).await;
+    assert!(
+        wait_result.is_err(),
+        "node was shut down on consistency failure"
+    );
+
+    stop_sender.send_replace(true);
+    tokio::time::timeout(TEST_TIMEOUT, tasks.wait_single())
+        .await
+        .expect("timed out waiting for node to stop");
+    tasks.complete(TEST_TIMEOUT).await;
+}
+
+#[tokio::test]
+async fn sync_tasks_use_node_stop_signal_without_read_only_mode() {
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let mut task_handles = vec![];
+    let sync_stop_signal = SyncStopSignal::new(&stop_receiver, false, &mut task_handles);
+    assert!(sync_stop_signal.halt_sender.is_none());
+    assert!(task_handles.is_empty());
+    assert!(sync_stop_signal.receiver.same_channel(&stop_receiver));
+
+    let sync_task = spawn_sync_task("state_keeper", &sync_stop_signal);
+    let tasks: Vec<_> = sync_stop_signal.managed_tasks(vec![sync_task]).collect();
+    assert_eq!(tasks[0].policy(), TaskPolicy::Fatal);
+}
@@ -58,15 +58,40 @@ impl SnapshotProgress {
 
 /// Creator of a single storage snapshot.
 #[derive(Debug)]
-pub(crate) struct SnapshotCreator {
-    pub blob_store: Arc<dyn ObjectStore>,
-    pub master_pool: ConnectionPool<Core>,
-    pub replica_pool: ConnectionPool<Core>,
+pub struct SnapshotCreator {
+    pub(crate) blob_store: Arc<dyn ObjectStore>,
+    pub(crate) master_pool: ConnectionPool<Core>,
+    pub(crate) replica_pool: ConnectionPool<Core>,
+    pub(crate) max_l1_batch_number: Option<L1BatchNumber>,
     #[cfg(test)]
-    pub event_listener: Box<dyn HandleEvent>,
+    pub(crate) event_listener: Box<dyn HandleEvent>,
 }
 
 impl SnapshotCreator {
+    /// Creates a snapshot creator. Snapshot metadata is written via `master_pool`, while storage logs
+    /// and factory deps are read via `replica_pool`.
+    pub fn new(
+        blob_store: Arc<dyn ObjectStore>,
+        master_pool: ConnectionPool<Core>,
+        replica_pool: ConnectionPool<Core>,
+    ) -> Self {
+        Self {
+            blob_store,
+            master_pool,
+            replica_pool,
+            max_l1_batch_number: None,
+            #[cfg(test)]
+            event_listener: Box::new(()),
+        }
+    }
+
+    /// Limits the L1 batch number of newly created snapshots. By default, a snapshot is created
+    /// for the L1 batch preceding the last sealed one.
+    pub fn with_max_l1_batch_number(mut self, max_l1_batch_number: L1BatchNumber) -> Self {
+        self.max_l1_batch_number = Some(max_l1_batch_number);
+        self
+    }
+
     async fn connect_to_replica(&self) -> anyhow::Result<Connection<'_, Core>> {
         self.replica_pool
             .connection_tagged("snapshots_creator")
@@ -191,6 +216,7 @@ impl SnapshotCreator {
     async fn initialize_snapshot_progress(
         config: &SnapshotsCreatorConfig,
         min_chunk_count: u64,
+        max_l1_batch_number: Option<L1BatchNumber>,
         latest_snapshot: Option<&SnapshotMetadata>,
         conn: &mut Connection<'_, Core>,
     ) -> anyhow::Result<Option<SnapshotProgress>> {
@@ -201,13 +227,16 @@ impl SnapshotCreator {
             sealed_l1_batch_number != L1BatchNumber(0),
             "Cannot create snapshot when only the genesis L1 batch is present in Postgres"
         );
-        let l1_batch_number = sealed_l1_batch_number - 1;
+        let mut l1_batch_number = sealed_l1_batch_number - 1;
+        if let Some(max_l1_batch_number) = max_l1_batch_number {
+            l1_batch_number = l1_batch_number.min(max_l1_batch_number);
+        }
 
         let latest_snapshot_l1_batch_number =
             latest_snapshot.map(|snapshot| snapshot.l1_batch_number);
-        if latest_snapshot_l1_batch_number == Some(l1_batch_number) {
+        if latest_snapshot_l1_batch_number >= Some(l1_batch_number) {
             tracing::info!(
-                "Snapshot at expected L1 batch #{l1_batch_number} is already created; exiting"
+                "Snapshot at expected L1 batch #{l1_batch_number} (or a newer one) is already created; exiting"
             );
             return Ok(None);
         }
@@ -254,6 +283,7 @@ impl SnapshotCreator {
             Self::initialize_snapshot_progress(
                 config,
                 min_chunk_count,
+                self.max_l1_batch_number,
                 latest_snapshot.as_ref(),
                 &mut self.connect_to_replica().await?,
             )
@@ -0,0 +1,21 @@
+//! Snapshot creator library. Used by the standalone snapshot creator binary, and can be embedded into other
+//! components (e.g., the external node) to create snapshots of their storage.
+//!
+//! # Assumptions
+//!
+//! The snapshot creator is fault-tolerant; if it stops in the middle of creating a snapshot,
+//! this snapshot will be continued from roughly the same point after the restart. If this is
+//! undesired, remove the `snapshots` table record corresponding to the pending snapshot.
+//!
+//! It is assumed that the snapshot creator is run as a singleton process (no more than 1 instance
+//! at a time).
+
+pub use self::creator::SnapshotCreator;
+
+mod creator;
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Minimum number of storage log chunks to produce.
+pub const MIN_CHUNK_COUNT: u64 = 10;
@@ -1,16 +1,9 @@
 //! Snapshot creator utility. Intended to run on a schedule, with each run creating a new snapshot.
-//!
-//! # Assumptions
-//!
-//! The snapshot creator is fault-tolerant; if it stops in the middle of creating a snapshot,
-//! this snapshot will be continued from roughly the same point after the restart. If this is
-//! undesired, remove the `snapshots` table record corresponding to the pending snapshot.
-//!
-//! It is assumed that the snapshot creator is run as a singleton process (no more than 1 instance
-//! at a time).
+//! See the library docs for the assumptions the snapshot creator makes.
 
 use anyhow::Context as _;
 use prometheus_exporter::PrometheusExporterConfig;
+use snapshots_creator::{SnapshotCreator, MIN_CHUNK_COUNT};
 use tokio::{sync::watch, task::JoinHandle};
 use zksync_config::{
     configs::{ObservabilityConfig, PrometheusConfig},
@@ -20,13 +13,6 @@ use zksync_dal::{ConnectionPool, Core};
 use zksync_env_config::{object_store::SnapshotsObjectStoreConfig, FromEnv};
 use zksync_object_store::ObjectStoreFactory;
 
-use crate::creator::SnapshotCreator;
-
-mod creator;
-mod metrics;
-#[cfg(test)]
-mod tests;
-
 async fn maybe_enable_prometheus_metrics(
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<Option<JoinHandle<anyhow::Result<()>>>> {
@@ -46,9 +32,6 @@ async fn maybe_enable_prometheus_metrics(
     }
 }
 
-/// Minimum number of storage log chunks to produce.
-const MIN_CHUNK_COUNT: u64 = 10;
-
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let (stop_sender, stop_receiver) = watch::channel(false);
@@ -92,13 +75,7 @@ async fn main() -> anyhow::Result<()> {
         .build()
         .await?;
 
-    let creator = SnapshotCreator {
-        blob_store,
-        master_pool,
-        replica_pool,
-        #[cfg(test)]
-        event_listener: Box::new(()),
-    };
+    let creator = SnapshotCreator::new(blob_store, master_pool, replica_pool);
     creator.run(creator_config, MIN_CHUNK_COUNT).await?;
 
     tracing::info!("Finished running snapshot creator!");
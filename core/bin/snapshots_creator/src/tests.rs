@@ -10,8 +10,9 @@ use std::{
 };
 
 use rand::{thread_rng, Rng};
-use zksync_dal::{Connection, CoreDal};
-use zksync_object_store::ObjectStore;
+use zksync_config::SnapshotsCreatorConfig;
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_types::{
     block::{L1BatchHeader, MiniblockHeader},
     snapshots::{
@@ -66,6 +67,7 @@ impl SnapshotCreator {
             blob_store,
             master_pool: pool.clone(),
             replica_pool: pool,
+            max_l1_batch_number: None,
             event_listener: Box::new(()),
         }
     }
@@ -288,6 +290,41 @@ async fn persisting_snapshot_metadata() {
     }
 }
 
+#[tokio::test]
+async fn capping_snapshot_l1_batch_number() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store = object_store_factory.create_store().await;
+    let mut conn = pool.connection().await.unwrap();
+    prepare_postgres(&mut rng, &mut conn, 10).await;
+
+    SnapshotCreator::for_tests(object_store.clone(), pool.clone())
+        .with_max_l1_batch_number(L1BatchNumber(5))
+        .run(TEST_CONFIG, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let snapshots = conn
+        .snapshots_dal()
+        .get_all_complete_snapshots()
+        .await
+        .unwrap();
+    assert_eq!(snapshots.snapshots_l1_batch_numbers, [L1BatchNumber(5)]);
+
+    // A snapshot older than the latest one should not be created.
+    SnapshotCreator::for_tests(object_store, pool.clone())
+        .with_max_l1_batch_number(L1BatchNumber(3))
+        .run(TEST_CONFIG, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let snapshots = conn
+        .snapshots_dal()
+        .get_all_complete_snapshots()
+        .await
+        .unwrap();
+    assert_eq!(snapshots.snapshots_l1_batch_numbers, [L1BatchNumber(5)]);
+}
+
 #[tokio::test]
 async fn persisting_snapshot_factory_deps() {
     let pool = ConnectionPool::<Core>::test_pool().await;
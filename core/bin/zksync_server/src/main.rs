@@ -11,16 +11,18 @@ use zksync_config::{
         },
         fri_prover_group::FriProverGroupConfig,
         house_keeper::HouseKeeperConfig,
-        FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig, ObservabilityConfig,
-        PrometheusConfig, ProofDataHandlerConfig, WitnessGeneratorConfig,
+        AdminConfig, FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig,
+        ObservabilityConfig, PrometheusConfig, ProofDataHandlerConfig, WitnessGeneratorConfig,
     },
     ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
     GasAdjusterConfig, GenesisConfig, ObjectStoreConfig, PostgresConfig,
 };
+use zksync_contracts::BaseSystemContracts;
 use zksync_core::{
     genesis, genesis_init, initialize_components, is_genesis_needed, setup_sigint_handler,
+    state_keeper::run_vm_self_test,
     temp_config_store::{decode_yaml, Secrets, TempConfigStore},
-    Component, Components,
+    validate_components, Component, Components,
 };
 use zksync_env_config::FromEnv;
 use zksync_storage::RocksDB;
@@ -44,6 +46,9 @@ struct Cli {
     /// Rebuild tree.
     #[arg(long)]
     rebuild_tree: bool,
+    /// Check that the base system contracts loaded from disk can be used to execute a transaction, and exit.
+    #[arg(long)]
+    self_test: bool,
     /// Comma-separated list of components to launch.
     #[arg(
         long,
@@ -77,6 +82,7 @@ impl FromStr for ComponentsToRun {
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let opt = Cli::parse();
+    validate_components(&opt.components.0).context("invalid `--components`")?;
     let sigint_receiver = setup_sigint_handler();
 
     let observability_config =
@@ -139,6 +145,7 @@ async fn main() -> anyhow::Result<()> {
             gas_adjuster_config: GasAdjusterConfig::from_env().ok(),
             object_store_config: ObjectStoreConfig::from_env().ok(),
             consensus_config: config::read_consensus_config().context("read_consensus_config()")?,
+            admin_config: AdminConfig::from_env().ok(),
         },
     };
     let secrets: Secrets = match opt.secrets_path {
@@ -149,13 +156,28 @@ async fn main() -> anyhow::Result<()> {
         }
         None => Secrets {
             consensus: config::read_consensus_secrets().context("read_consensus_secrets()")?,
+            admin_token: std::env::var("ADMIN_TOKEN").ok(),
         },
     };
 
+    if opt.self_test {
+        let chain_id = configs
+            .network_config
+            .as_ref()
+            .context("NetworkConfig")?
+            .zksync_network_id;
+        return run_vm_self_test(BaseSystemContracts::load_from_disk(), chain_id)
+            .await
+            .context("VM self-test failed");
+    }
+
     let postgres_config = configs.postgres_config.clone().context("PostgresConfig")?;
 
     if opt.genesis || is_genesis_needed(&postgres_config).await {
         let genesis = GenesisConfig::from_env().context("Genesis config")?;
+        genesis.validate().context("invalid genesis config")?;
+        genesis::verify_genesis_commitment(&genesis)
+            .context("genesis commitment doesn't match genesis config")?;
         genesis_init(genesis, &postgres_config)
             .await
             .context("genesis_init")?;
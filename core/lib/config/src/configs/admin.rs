@@ -0,0 +1,20 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use serde::Deserialize;
+
+/// Configuration of the admin server exposing runtime controls to node operators.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct AdminConfig {
+    /// Port to bind the admin server to.
+    pub port: u16,
+    /// IP address to bind the admin server to. If not specified, the server is bound to the loopback interface,
+    /// so that it's not exposed outside the host.
+    pub bind_ip: Option<IpAddr>,
+}
+
+impl AdminConfig {
+    pub fn bind_addr(&self) -> SocketAddr {
+        let ip = self.bind_ip.unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        SocketAddr::new(ip, self.port)
+    }
+}
@@ -1,4 +1,8 @@
-use std::{net::SocketAddr, num::NonZeroU32, time::Duration};
+use std::{
+    net::SocketAddr,
+    num::{NonZeroU32, NonZeroUsize},
+    time::Duration,
+};
 
 use serde::Deserialize;
 use zksync_basic_types::H256;
@@ -44,6 +48,9 @@ pub struct Web3JsonRpcConfig {
     pub filters_disabled: bool,
     /// Max possible limit of filters to be in the state at once.
     pub filters_limit: Option<u32>,
+    /// Max possible limit of filters installed by a single WebSocket connection. Must not exceed `filters_limit`
+    /// to have effect. If not set, a single connection may install filters up to the global limit.
+    pub filters_limit_per_connection: Option<u32>,
     /// Max possible limit of subscriptions to be in the state at once.
     pub subscriptions_limit: Option<u32>,
     /// Interval between polling db for pubsub (in ms).
@@ -78,6 +85,9 @@ pub struct Web3JsonRpcConfig {
     /// This option can be tweaked down if the API server is running out of memory.
     /// If not set, the VM concurrency limit will be efficiently disabled.
     pub vm_concurrency_limit: Option<usize>,
+    /// Max number of concurrent VM executions for tracing calls (e.g., `debug_traceCall`). These executions
+    /// also count towards `vm_concurrency_limit`. If not set, tracing calls are only limited by `vm_concurrency_limit`.
+    pub trace_call_concurrency_limit: Option<NonZeroUsize>,
     /// Smart contract cache size in MiBs. The default value is 128 MiB.
     pub factory_deps_cache_size_mb: Option<usize>,
     /// Initial writes cache size in MiBs. The default value is 32 MiB.
@@ -91,6 +101,9 @@ pub struct Web3JsonRpcConfig {
     pub max_batch_request_size: Option<usize>,
     /// Maximum response body size in MiBs. Default is 10 MiB.
     pub max_response_body_size_mb: Option<usize>,
+    /// Maximum request body size in MiBs. Requests exceeding this limit are rejected before they are parsed.
+    /// Default is 10 MiB.
+    pub max_request_body_size_mb: Option<usize>,
     /// Maximum number of requests per minute for the WebSocket server.
     /// The value is per active connection.
     /// Note: For HTTP, rate limiting is expected to be configured on the infra level.
@@ -117,6 +130,7 @@ impl Web3JsonRpcConfig {
             req_entities_limit: Some(10000),
             filters_disabled: false,
             filters_limit: Some(10000),
+            filters_limit_per_connection: None,
             subscriptions_limit: Some(10000),
             pubsub_polling_interval: Some(200),
             max_nonce_ahead: 50,
@@ -129,12 +143,14 @@ impl Web3JsonRpcConfig {
             max_tx_size: 1000000,
             vm_execution_cache_misses_limit: Default::default(),
             vm_concurrency_limit: Default::default(),
+            trace_call_concurrency_limit: Default::default(),
             factory_deps_cache_size_mb: Default::default(),
             initial_writes_cache_size_mb: Default::default(),
             latest_values_cache_size_mb: Default::default(),
             fee_history_limit: Default::default(),
             max_batch_request_size: Default::default(),
             max_response_body_size_mb: Default::default(),
+            max_request_body_size_mb: Default::default(),
             websocket_requests_per_minute_limit: Default::default(),
             mempool_cache_update_interval: Default::default(),
             mempool_cache_size: Default::default(),
@@ -158,6 +174,11 @@ impl Web3JsonRpcConfig {
         self.filters_limit.unwrap_or(10000) as usize
     }
 
+    pub fn filters_limit_per_connection(&self) -> Option<usize> {
+        self.filters_limit_per_connection
+            .map(|limit| limit as usize)
+    }
+
     pub fn subscriptions_limit(&self) -> usize {
         self.subscriptions_limit.unwrap_or(10000) as usize
     }
@@ -209,6 +230,10 @@ impl Web3JsonRpcConfig {
         self.max_response_body_size_mb.unwrap_or(10) * super::BYTES_IN_MEGABYTE
     }
 
+    pub fn max_request_body_size(&self) -> usize {
+        self.max_request_body_size_mb.unwrap_or(10) * super::BYTES_IN_MEGABYTE
+    }
+
     pub fn websocket_requests_per_minute_limit(&self) -> NonZeroU32 {
         // The default limit is chosen to be reasonably permissive.
         self.websocket_requests_per_minute_limit
@@ -40,10 +40,12 @@ impl NetworkConfig {
 ///  - `V2`, the second model that was used in zkSync Era. There the pubdata price might be independent from the L1 gas price. Also,
 ///  The fair L2 gas price is expected to both the proving/computation price for the operator and the costs that come from
 ///  processing the batch on L1.
+///  - `V3`, an experimental model. It can be specified in configs, but is not supported by the fee input providers yet.
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
 pub enum FeeModelVersion {
     V1,
     V2,
+    V3,
 }
 
 impl Default for FeeModelVersion {
@@ -148,12 +150,20 @@ pub struct StateKeeperConfig {
     /// Max number of computational gas that validation step is allowed to take.
     pub validation_computational_gas_limit: u32,
     pub save_call_traces: bool,
+    /// Whether call traces (if saved) should be persisted via a dedicated connection pool, rather than in the same
+    /// DB transaction as other miniblock data. Traces are persisted before the corresponding miniblock is committed.
+    #[serde(default)]
+    pub separate_call_traces_pool: bool,
 
     pub virtual_blocks_interval: u32,
     pub virtual_blocks_per_miniblock: u32,
 
     /// Number of keys that is processed by enum_index migration in State Keeper each L1 batch.
+    /// If not specified, 1,000 keys are processed per chunk.
     pub enum_index_migration_chunk_size: Option<usize>,
+    /// Safety cap on the number of miniblocks in a single L1 batch; once reached, the batch is sealed.
+    /// If not specified, 10,000 miniblocks per batch are allowed.
+    pub max_miniblocks_per_batch: Option<usize>,
 
     // Base system contract hash, required only for genesis file, it's temporary solution
     // #PLA-811
@@ -192,17 +202,41 @@ impl StateKeeperConfig {
             fee_model_version: FeeModelVersion::V2,
             validation_computational_gas_limit: 300000,
             save_call_traces: true,
+            separate_call_traces_pool: false,
             virtual_blocks_interval: 1,
             virtual_blocks_per_miniblock: 1,
             enum_index_migration_chunk_size: None,
+            max_miniblocks_per_batch: None,
             bootloader_hash: None,
             default_aa_hash: None,
             l1_batch_commit_data_generator_mode: L1BatchCommitDataGeneratorMode::Rollup,
         }
     }
 
-    pub fn enum_index_migration_chunk_size(&self) -> usize {
-        self.enum_index_migration_chunk_size.unwrap_or(1_000)
+    /// Maximum allowed number of keys processed by the enum index migration per chunk. Larger chunks
+    /// lead to long-running Postgres queries and RocksDB writes.
+    pub const MAX_ENUM_INDEX_MIGRATION_CHUNK_SIZE: usize = 100_000;
+    const DEFAULT_ENUM_INDEX_MIGRATION_CHUNK_SIZE: usize = 1_000;
+
+    /// Returns the validated number of keys processed by the enum index migration per chunk.
+    pub fn enum_index_migration_chunk_size(&self) -> anyhow::Result<usize> {
+        let Some(chunk_size) = self.enum_index_migration_chunk_size else {
+            return Ok(Self::DEFAULT_ENUM_INDEX_MIGRATION_CHUNK_SIZE);
+        };
+        anyhow::ensure!(
+            (1..=Self::MAX_ENUM_INDEX_MIGRATION_CHUNK_SIZE).contains(&chunk_size),
+            "enum_index_migration_chunk_size must be in 1..={} range, got {chunk_size}",
+            Self::MAX_ENUM_INDEX_MIGRATION_CHUNK_SIZE
+        );
+        Ok(chunk_size)
+    }
+
+    const DEFAULT_MAX_MINIBLOCKS_PER_BATCH: usize = 10_000;
+
+    /// Returns the maximum number of miniblocks in a single L1 batch.
+    pub fn max_miniblocks_per_batch(&self) -> usize {
+        self.max_miniblocks_per_batch
+            .unwrap_or(Self::DEFAULT_MAX_MINIBLOCKS_PER_BATCH)
     }
 }
 
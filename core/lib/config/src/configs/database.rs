@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{num::NonZeroU32, time::Duration};
 
 use anyhow::Context as _;
 use serde::{Deserialize, Serialize};
@@ -18,6 +18,18 @@ pub enum MerkleTreeMode {
     Lightweight,
 }
 
+/// Compaction style for a RocksDB instance. FIFO compaction is intentionally not supported since it drops
+/// the oldest data once the DB exceeds a size limit, which would lead to data loss for the state keeper
+/// and Merkle tree DBs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RocksdbCompactionStyle {
+    /// Level-style compaction (the default one). Optimized for read amplification.
+    Level,
+    /// Universal compaction. Optimized for write amplification at the cost of read and space amplification.
+    Universal,
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct MerkleTreeConfig {
     /// Path to the RocksDB data directory for Merkle tree.
@@ -44,6 +56,14 @@ pub struct MerkleTreeConfig {
     /// Maximum number of L1 batches to be processed by the Merkle tree at a time.
     #[serde(default = "MerkleTreeConfig::default_max_l1_batches_per_iter")]
     pub max_l1_batches_per_iter: usize,
+    /// Lag of the full Merkle tree (in L1 batches) relative to Postgres after which the tree health is considered
+    /// affected since the tree cannot serve proofs for recent L1 batches. The default value is 5.
+    #[serde(default = "MerkleTreeConfig::default_max_l1_batch_lag_for_proofs")]
+    pub max_l1_batch_lag_for_proofs: u32,
+    /// Whether to collect RocksDB statistics (e.g., IO metrics) for the Merkle tree. Statistics have
+    /// a non-negligible performance overhead, so they are disabled by default.
+    #[serde(default)]
+    pub statistics_enabled: bool,
 }
 
 impl Default for MerkleTreeConfig {
@@ -56,6 +76,8 @@ impl Default for MerkleTreeConfig {
             memtable_capacity_mb: Self::default_memtable_capacity_mb(),
             stalled_writes_timeout_sec: Self::default_stalled_writes_timeout_sec(),
             max_l1_batches_per_iter: Self::default_max_l1_batches_per_iter(),
+            max_l1_batch_lag_for_proofs: Self::default_max_l1_batch_lag_for_proofs(),
+            statistics_enabled: false,
         }
     }
 }
@@ -85,6 +107,10 @@ impl MerkleTreeConfig {
         20
     }
 
+    pub const fn default_max_l1_batch_lag_for_proofs() -> u32 {
+        5
+    }
+
     /// Returns the size of block cache size for Merkle tree in bytes.
     pub fn block_cache_size(&self) -> usize {
         self.block_cache_size_mb * super::BYTES_IN_MEGABYTE
@@ -107,6 +133,22 @@ pub struct DBConfig {
     /// Path to the RocksDB data directory that serves state cache.
     #[serde(default = "DBConfig::default_state_keeper_db_path")]
     pub state_keeper_db_path: String,
+    /// Size of a single write buffer (memtable) of the state keeper RocksDB, in MB. Larger values can speed up
+    /// write-heavy workloads (e.g., catching up with Postgres) at the cost of memory usage. If not set,
+    /// the default RocksDB value is used.
+    #[serde(default)]
+    pub state_keeper_db_write_buffer_size_mb: Option<usize>,
+    /// Maximum number of concurrent background jobs (compactions and flushes) of the state keeper RocksDB.
+    /// If not set, the number is chosen based on the number of CPUs.
+    #[serde(default)]
+    pub state_keeper_db_max_background_jobs: Option<NonZeroU32>,
+    /// Compaction style of the state keeper RocksDB. If not set, level-style compaction is used.
+    #[serde(default)]
+    pub state_keeper_db_compaction_style: Option<RocksdbCompactionStyle>,
+    /// Whether to collect RocksDB statistics (e.g., IO metrics) for the state keeper RocksDB. Disabled by default
+    /// because of the performance overhead.
+    #[serde(default)]
+    pub state_keeper_db_statistics_enabled: bool,
     /// Merkle tree configuration.
     #[serde(skip)]
     // ^ Filled in separately in `Self::from_env()`. We cannot use `serde(flatten)` because it
@@ -118,6 +160,12 @@ impl DBConfig {
     fn default_state_keeper_db_path() -> String {
         "./db/state_keeper".to_owned()
     }
+
+    /// Returns the write buffer size of the state keeper RocksDB in bytes.
+    pub fn state_keeper_db_write_buffer_size(&self) -> Option<usize> {
+        self.state_keeper_db_write_buffer_size_mb
+            .map(|size| size * super::BYTES_IN_MEGABYTE)
+    }
 }
 
 /// Collection of different database URLs and general PostgreSQL options.
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
-use zksync_basic_types::{Address, L1ChainId, L2ChainId, H256};
+use zksync_basic_types::{
+    protocol_version::ProtocolVersionId, Address, L1ChainId, L2ChainId, H256,
+};
 
 /// This config represents the genesis state of the chain.
 /// Each chain has this config immutable and we update it only during the protocol upgrade
@@ -18,3 +20,27 @@ pub struct GenesisConfig {
     pub recursion_leaf_level_vk_hash: H256,
     pub recursion_scheduler_level_vk_hash: H256,
 }
+
+impl GenesisConfig {
+    /// Checks that the config doesn't contain obvious misconfigurations, such as zero hashes
+    /// or an unknown protocol version.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let hashes = [
+            ("genesis_root_hash", self.genesis_root_hash),
+            ("genesis_commitment", self.genesis_commitment),
+            ("bootloader_hash", self.bootloader_hash),
+            ("default_aa_hash", self.default_aa_hash),
+        ];
+        for (name, hash) in hashes {
+            anyhow::ensure!(!hash.is_zero(), "`{name}` in genesis config is zero");
+        }
+        ProtocolVersionId::try_from(self.protocol_version).map_err(|_| {
+            anyhow::anyhow!(
+                "`protocol_version` {} in genesis config is unknown; latest known version is {}",
+                self.protocol_version,
+                ProtocolVersionId::next() as u16
+            )
+        })?;
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 // Public re-exports
 pub use self::{
+    admin::AdminConfig,
     alerts::AlertsConfig,
     api::ApiConfig,
     contract_verifier::ContractVerifierConfig,
@@ -22,6 +23,7 @@ pub use self::{
     witness_generator::WitnessGeneratorConfig,
 };
 
+pub mod admin;
 pub mod alerts;
 pub mod api;
 pub mod chain;
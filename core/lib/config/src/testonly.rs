@@ -1,3 +1,5 @@
+use std::net::IpAddr;
+
 use rand::{distributions::Distribution, Rng};
 use zksync_basic_types::{
     basic_fri_types::CircuitIdRoundTuple, network::Network, L1ChainId, L2ChainId,
@@ -29,9 +31,10 @@ impl Sample for Network {
 impl Distribution<configs::chain::FeeModelVersion> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::chain::FeeModelVersion {
         type T = configs::chain::FeeModelVersion;
-        match rng.gen_range(0..2) {
+        match rng.gen_range(0..3) {
             0 => T::V1,
-            _ => T::V2,
+            1 => T::V2,
+            _ => T::V3,
         }
     }
 }
@@ -66,6 +69,7 @@ impl Distribution<configs::api::Web3JsonRpcConfig> for EncodeDist {
             req_entities_limit: self.sample(rng),
             filters_disabled: self.sample(rng),
             filters_limit: self.sample(rng),
+            filters_limit_per_connection: self.sample(rng),
             subscriptions_limit: self.sample(rng),
             pubsub_polling_interval: self.sample(rng),
             max_nonce_ahead: self.sample(rng),
@@ -78,12 +82,14 @@ impl Distribution<configs::api::Web3JsonRpcConfig> for EncodeDist {
             max_tx_size: self.sample(rng),
             vm_execution_cache_misses_limit: self.sample(rng),
             vm_concurrency_limit: self.sample(rng),
+            trace_call_concurrency_limit: self.sample(rng),
             factory_deps_cache_size_mb: self.sample(rng),
             initial_writes_cache_size_mb: self.sample(rng),
             latest_values_cache_size_mb: self.sample(rng),
             fee_history_limit: self.sample(rng),
             max_batch_request_size: self.sample(rng),
             max_response_body_size_mb: self.sample(rng),
+            max_request_body_size_mb: self.sample(rng),
             websocket_requests_per_minute_limit: self.sample(rng),
             tree_api_url: self.sample(rng),
             mempool_cache_update_interval: self.sample(rng),
@@ -177,9 +183,11 @@ impl Distribution<configs::chain::StateKeeperConfig> for EncodeDist {
             fee_model_version: self.sample(rng),
             validation_computational_gas_limit: self.sample(rng),
             save_call_traces: self.sample(rng),
+            separate_call_traces_pool: self.sample(rng),
             virtual_blocks_interval: self.sample(rng),
             virtual_blocks_per_miniblock: self.sample(rng),
             enum_index_migration_chunk_size: self.sample(rng),
+            max_miniblocks_per_batch: self.sample(rng),
             bootloader_hash: rng.gen(),
             default_aa_hash: rng.gen(),
             l1_batch_commit_data_generator_mode: self.sample(rng),
@@ -282,6 +290,16 @@ impl Distribution<configs::database::MerkleTreeMode> for EncodeDist {
     }
 }
 
+impl Distribution<configs::database::RocksdbCompactionStyle> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::database::RocksdbCompactionStyle {
+        type T = configs::database::RocksdbCompactionStyle;
+        match rng.gen_range(0..2) {
+            0 => T::Level,
+            _ => T::Universal,
+        }
+    }
+}
+
 impl Distribution<configs::database::MerkleTreeConfig> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::database::MerkleTreeConfig {
         configs::database::MerkleTreeConfig {
@@ -292,6 +310,8 @@ impl Distribution<configs::database::MerkleTreeConfig> for EncodeDist {
             memtable_capacity_mb: self.sample(rng),
             stalled_writes_timeout_sec: self.sample(rng),
             max_l1_batches_per_iter: self.sample(rng),
+            max_l1_batch_lag_for_proofs: self.sample(rng),
+            statistics_enabled: self.sample(rng),
         }
     }
 }
@@ -300,6 +320,10 @@ impl Distribution<configs::database::DBConfig> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::database::DBConfig {
         configs::database::DBConfig {
             state_keeper_db_path: self.sample(rng),
+            state_keeper_db_write_buffer_size_mb: self.sample(rng),
+            state_keeper_db_max_background_jobs: self.sample(rng),
+            state_keeper_db_compaction_style: self.sample(rng),
+            state_keeper_db_statistics_enabled: self.sample(rng),
             merkle_tree: self.sample(rng),
         }
     }
@@ -635,6 +659,15 @@ impl Distribution<configs::ObjectStoreConfig> for EncodeDist {
     }
 }
 
+impl Distribution<configs::AdminConfig> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::AdminConfig {
+        configs::AdminConfig {
+            port: self.sample(rng),
+            bind_ip: self.sample_opt(|| IpAddr::from(rng.gen::<[u8; 4]>())),
+        }
+    }
+}
+
 impl Distribution<configs::ProofDataHandlerConfig> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::ProofDataHandlerConfig {
         configs::ProofDataHandlerConfig {
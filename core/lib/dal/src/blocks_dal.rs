@@ -2073,6 +2073,42 @@ impl BlocksDal<'_, '_> {
         Ok(Some((v as u16).try_into()?))
     }
 
+    /// Returns protocol versions for L1 batches in the specified range, ordered by the batch number.
+    /// L1 batches missing from the storage are omitted.
+    pub async fn get_batch_protocol_version_ids(
+        &mut self,
+        l1_batch_numbers: ops::RangeInclusive<L1BatchNumber>,
+    ) -> anyhow::Result<Vec<(L1BatchNumber, Option<ProtocolVersionId>)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                number,
+                protocol_version
+            FROM
+                l1_batches
+            WHERE
+                number BETWEEN $1 AND $2
+            ORDER BY
+                number
+            "#,
+            i64::from(l1_batch_numbers.start().0),
+            i64::from(l1_batch_numbers.end().0)
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let number = L1BatchNumber(row.number as u32);
+                let version = row
+                    .protocol_version
+                    .map(|version| (version as u16).try_into())
+                    .transpose()?;
+                Ok((number, version))
+            })
+            .collect()
+    }
+
     pub async fn get_miniblock_protocol_version_id(
         &mut self,
         miniblock_number: MiniblockNumber,
@@ -71,9 +71,26 @@ impl EventsWeb3Dal<'_, '_> {
         &mut self,
         filter: GetLogsFilter,
         limit: usize,
+    ) -> Result<Vec<Log>, SqlxError> {
+        self.get_logs_after(&filter, None, limit).await
+    }
+
+    /// Returns logs for given filter that follow the specified `(miniblock_number, event_index_in_block)` position.
+    /// Logs are ordered by the position, so this method can be used to paginate through logs matching the filter.
+    pub async fn get_logs_after(
+        &mut self,
+        filter: &GetLogsFilter,
+        after: Option<(MiniblockNumber, u32)>,
+        limit: usize,
     ) -> Result<Vec<Log>, SqlxError> {
         {
-            let (where_sql, arg_index) = self.build_get_logs_where_clause(&filter);
+            let (mut where_sql, arg_index) = self.build_get_logs_where_clause(filter);
+            if let Some((miniblock_number, event_index)) = after {
+                where_sql += &format!(
+                    " AND ((miniblock_number, event_index_in_block) > ({}, {}))",
+                    miniblock_number.0, event_index
+                );
+            }
 
             let query = format!(
                 r#"
@@ -114,7 +131,8 @@ impl EventsWeb3Dal<'_, '_> {
             let db_logs: Vec<StorageWeb3Log> = query
                 .instrument("get_logs")
                 .report_latency()
-                .with_arg("filter", &filter)
+                .with_arg("filter", filter)
+                .with_arg("after", &after)
                 .with_arg("limit", &limit)
                 .fetch_all(self.storage)
                 .await?;
@@ -303,8 +303,8 @@ impl ProtocolVersionsDal<'_, '_> {
         Some((id as u16).try_into().unwrap())
     }
 
-    pub async fn last_used_version_id(&mut self) -> Option<ProtocolVersionId> {
-        let id = sqlx::query!(
+    pub async fn last_used_version_id(&mut self) -> sqlx::Result<Option<ProtocolVersionId>> {
+        let row = sqlx::query!(
             r#"
             SELECT
                 protocol_version
@@ -317,11 +317,12 @@ impl ProtocolVersionsDal<'_, '_> {
             "#
         )
         .fetch_optional(self.storage.conn())
-        .await
-        .unwrap()?
-        .protocol_version?;
+        .await?;
 
-        Some((id as u16).try_into().unwrap())
+        let Some(id) = row.and_then(|row| row.protocol_version) else {
+            return Ok(None);
+        };
+        Ok(Some((id as u16).try_into().unwrap()))
     }
 
     pub async fn all_version_ids(&mut self) -> Vec<ProtocolVersionId> {
@@ -658,6 +658,32 @@ impl StorageLogsDal<'_, '_> {
         Ok(row.count.unwrap_or(0) as u64)
     }
 
+    /// Returns the number of rows in the `storage_logs` table with a miniblock number strictly greater
+    /// than the specified one, i.e., the number of storage logs that would be removed by
+    /// [`Self::rollback_storage_logs()`].
+    pub async fn get_storage_logs_row_count_after(
+        &mut self,
+        after_miniblock: MiniblockNumber,
+    ) -> sqlx::Result<u64> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS COUNT
+            FROM
+                storage_logs
+            WHERE
+                miniblock_number > $1
+            "#,
+            i64::from(after_miniblock.0)
+        )
+        .instrument("get_storage_logs_row_count_after")
+        .with_arg("miniblock_number", &after_miniblock)
+        .report_latency()
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.count.unwrap_or(0) as u64)
+    }
+
     /// Gets a starting tree entry for each of the supplied `key_ranges` for the specified
     /// `miniblock_number`. This method is used during Merkle tree recovery.
     pub async fn get_chunk_starts_for_miniblock(
@@ -884,6 +910,13 @@ mod tests {
         assert_eq!(prev_values[&prev_keys[1]], None);
         assert_eq!(prev_values[&prev_keys[2]], None);
 
+        let removed_log_count = conn
+            .storage_logs_dal()
+            .get_storage_logs_row_count_after(MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(removed_log_count, 3);
+
         #[allow(deprecated)]
         {
             conn.storage_logs_dal()
@@ -902,6 +935,12 @@ mod tests {
             .rollback_storage_logs(MiniblockNumber(1))
             .await
             .unwrap();
+        let removed_log_count = conn
+            .storage_logs_dal()
+            .get_storage_logs_row_count_after(MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(removed_log_count, 0);
 
         let value = conn.storage_web3_dal().get_value(&key).await.unwrap();
         assert_eq!(value, H256::repeat_byte(3));
@@ -22,7 +22,7 @@ use zksync_utils::u256_to_big_decimal;
 
 use crate::{
     models::storage_transaction::{CallTrace, StorageTransaction},
-    Core,
+    Core, CoreDal,
 };
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
@@ -497,6 +497,39 @@ impl TransactionsDal<'_, '_> {
         miniblock_number: MiniblockNumber,
         transactions: &[TransactionExecutionResult],
         block_base_fee_per_gas: U256,
+    ) {
+        self.mark_txs_as_executed_in_miniblock_inner(
+            miniblock_number,
+            transactions,
+            block_base_fee_per_gas,
+            true,
+        )
+        .await;
+    }
+
+    /// Same as [`Self::mark_txs_as_executed_in_miniblock()`], but doesn't persist call traces
+    /// of the transactions. Call traces can be persisted separately using [`Self::insert_call_traces()`].
+    pub async fn mark_txs_as_executed_in_miniblock_without_call_traces(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+        transactions: &[TransactionExecutionResult],
+        block_base_fee_per_gas: U256,
+    ) {
+        self.mark_txs_as_executed_in_miniblock_inner(
+            miniblock_number,
+            transactions,
+            block_base_fee_per_gas,
+            false,
+        )
+        .await;
+    }
+
+    async fn mark_txs_as_executed_in_miniblock_inner(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+        transactions: &[TransactionExecutionResult],
+        block_base_fee_per_gas: U256,
+        with_call_traces: bool,
     ) {
         {
             let mut transaction = self.storage.start_transaction().await.unwrap();
@@ -535,8 +568,6 @@ impl TransactionsDal<'_, '_> {
             let mut l2_gas_per_pubdata_limit = Vec::with_capacity(transactions.len());
             let mut l2_refunded_gas = Vec::with_capacity(transactions.len());
 
-            let mut call_traces_tx_hashes = Vec::with_capacity(transactions.len());
-            let mut bytea_call_traces = Vec::with_capacity(transactions.len());
             transactions
                 .iter()
                 .enumerate()
@@ -559,11 +590,6 @@ impl TransactionsDal<'_, '_> {
                         TxExecutionStatus::Failure => Some("Bootloader-based tx failed".to_owned()),
                     };
 
-                    if let Some(call_trace) = tx_res.call_trace() {
-                        bytea_call_traces.push(bincode::serialize(&call_trace).unwrap());
-                        call_traces_tx_hashes.push(hash.0.to_vec());
-                    }
-
                     match &transaction.common_data {
                         ExecuteTransactionCommon::L1(common_data) => {
                             l1_hashes.push(hash.0.to_vec());
@@ -797,6 +823,29 @@ impl TransactionsDal<'_, '_> {
                 .unwrap();
             }
 
+            if with_call_traces {
+                transaction
+                    .transactions_dal()
+                    .insert_call_traces(transactions)
+                    .await;
+            }
+            transaction.commit().await.unwrap();
+        }
+    }
+
+    /// Persists call traces for the provided executed transactions. Transactions must already be present in the storage.
+    /// Existing call traces for the transactions are overwritten.
+    pub async fn insert_call_traces(&mut self, transactions: &[TransactionExecutionResult]) {
+        {
+            let mut call_traces_tx_hashes = Vec::with_capacity(transactions.len());
+            let mut bytea_call_traces = Vec::with_capacity(transactions.len());
+            for tx_res in transactions {
+                if let Some(call_trace) = tx_res.call_trace() {
+                    bytea_call_traces.push(bincode::serialize(&call_trace).unwrap());
+                    call_traces_tx_hashes.push(tx_res.hash.0.to_vec());
+                }
+            }
+
             if !bytea_call_traces.is_empty() {
                 sqlx::query!(
                     r#"
@@ -807,17 +856,20 @@ impl TransactionsDal<'_, '_> {
                         u.call_trace
                     FROM
                         UNNEST($1::bytea[], $2::bytea[]) AS u (tx_hash, call_trace)
+                    ON CONFLICT (tx_hash) DO
+                    UPDATE
+                    SET
+                        call_trace = excluded.call_trace
                     "#,
                     &call_traces_tx_hashes,
                     &bytea_call_traces
                 )
                 .instrument("insert_call_tracer")
                 .report_latency()
-                .execute(&mut transaction)
+                .execute(self.storage)
                 .await
                 .unwrap();
             }
-            transaction.commit().await.unwrap();
         }
     }
 
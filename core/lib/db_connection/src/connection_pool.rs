@@ -415,13 +415,17 @@ impl<DB: DbMarker> ConnectionPool<DB> {
             Err(err) => {
                 Self::report_connection_error(&err);
                 let tags_display = ConnectionTags::display(tags);
+                // The original error is retained so that callers can classify it (e.g., using `retry::is_connection_error()`).
+                let err = anyhow::Error::from(err);
                 if let Some(traced_connections) = &self.traced_connections {
-                    anyhow::bail!(
-                        "Run out of retries getting a DB connection ({tags_display}), last error: {err}\n\
+                    Err(err.context(format!(
+                        "Run out of retries getting a DB connection ({tags_display})\n\
                          Active connections: {traced_connections:#?}"
-                    );
+                    )))
                 } else {
-                    anyhow::bail!("Run out of retries getting a DB connection ({tags_display}), last error: {err}");
+                    Err(err.context(format!(
+                        "Run out of retries getting a DB connection ({tags_display})"
+                    )))
                 }
             }
         }
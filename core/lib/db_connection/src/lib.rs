@@ -3,6 +3,7 @@ pub mod connection_pool;
 pub mod healthcheck;
 pub mod instrument;
 pub mod metrics;
+pub mod retry;
 #[macro_use]
 pub mod macro_utils;
 pub mod utils;
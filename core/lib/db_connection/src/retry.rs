@@ -0,0 +1,270 @@
+//! Helpers allowing long-running tasks to survive transient DB connection failures (e.g., a Postgres failover).
+
+use std::{future::Future, time::Duration};
+
+use tokio::sync::watch;
+
+/// Checks whether the provided error is caused by a broken or unavailable DB connection. Such errors
+/// are expected during a Postgres failover or restart; once the DB is back, the connection pool will establish
+/// new connections transparently, so an operation failing with such an error can be retried.
+pub fn is_connection_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<sqlx::Error>()
+            .map_or(false, is_sqlx_connection_error)
+    })
+}
+
+fn is_sqlx_connection_error(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::PoolTimedOut => true,
+        sqlx::Error::Database(db_err) => db_err.code().map_or(false, |code| {
+            // Class 08 is "connection exception"; 57P01..57P03 correspond to the server shutting down
+            // or not accepting connections yet.
+            code.starts_with("08") || matches!(&*code, "57P01" | "57P02" | "57P03")
+        }),
+        _ => false,
+    }
+}
+
+/// Policy for retrying DB operations on connection errors. The policy can also be used for other operations
+/// with transient errors (e.g., calls to a remote node) via [`Self::retry_if()`].
+#[derive(Debug, Clone, Copy)]
+pub struct ConnectionRetryPolicy {
+    max_retries: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for ConnectionRetryPolicy {
+    /// Returns the default policy, which is lenient enough to survive a typical Postgres failover
+    /// (i.e., a downtime on the order of a minute).
+    fn default() -> Self {
+        Self {
+            max_retries: 10,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl ConnectionRetryPolicy {
+    /// Creates a policy with the specified number of retries and backoff intervals. The backoff
+    /// is doubled after each failed attempt until it reaches `max_backoff`.
+    pub fn new(max_retries: usize, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        Self {
+            max_retries,
+            initial_backoff,
+            max_backoff: max_backoff.max(initial_backoff),
+        }
+    }
+
+    /// Runs the provided DB operation, retrying it if it fails with a [connection error](is_connection_error()).
+    /// Other errors are returned immediately, as well as the connection error after the retries are exhausted.
+    ///
+    /// The operation should acquire its DB connection(s) itself, so that a broken connection is not reused
+    /// between attempts.
+    pub async fn retry<T, Fut>(
+        &self,
+        operation_name: &str,
+        operation: impl FnMut() -> Fut,
+    ) -> anyhow::Result<T>
+    where
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        self.retry_if(operation_name, is_connection_error, operation)
+            .await
+    }
+
+    /// Runs the provided operation, retrying it if it fails with an error for which `is_retriable` returns `true`.
+    pub async fn retry_if<T, Fut>(
+        &self,
+        operation_name: &str,
+        is_retriable: impl Fn(&anyhow::Error) -> bool,
+        operation: impl FnMut() -> Fut,
+    ) -> anyhow::Result<T>
+    where
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let output = self
+            .retry_inner(operation_name, is_retriable, None, operation)
+            .await?;
+        Ok(output.expect("operation cannot be interrupted without a stop signal"))
+    }
+
+    /// Same as [`Self::retry_if()`], but interrupts waiting between attempts once a stop signal is sent
+    /// via `stop_receiver`. Returns `Ok(None)` if the operation was interrupted.
+    pub async fn retry_until_stopped<T, Fut>(
+        &self,
+        operation_name: &str,
+        is_retriable: impl Fn(&anyhow::Error) -> bool,
+        stop_receiver: &mut watch::Receiver<bool>,
+        operation: impl FnMut() -> Fut,
+    ) -> anyhow::Result<Option<T>>
+    where
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        self.retry_inner(operation_name, is_retriable, Some(stop_receiver), operation)
+            .await
+    }
+
+    async fn retry_inner<T, Fut>(
+        &self,
+        operation_name: &str,
+        is_retriable: impl Fn(&anyhow::Error) -> bool,
+        mut stop_receiver: Option<&mut watch::Receiver<bool>>,
+        mut operation: impl FnMut() -> Fut,
+    ) -> anyhow::Result<Option<T>>
+    where
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let mut backoff = self.initial_backoff;
+        let mut retries = 0;
+        loop {
+            let err = match operation().await {
+                Ok(output) => return Ok(Some(output)),
+                Err(err) if retries < self.max_retries && is_retriable(&err) => err,
+                Err(err) => return Err(err),
+            };
+            retries += 1;
+            tracing::warn!(
+                "Operation `{operation_name}` failed with a retriable error; retrying in {backoff:?} \
+                 (retry {retries}/{max_retries}): {err:#}",
+                max_retries = self.max_retries
+            );
+            if let Some(stop_receiver) = stop_receiver.as_deref_mut() {
+                let stop = stop_receiver.wait_for(|stop| *stop);
+                if tokio::time::timeout(backoff, stop).await.is_ok() {
+                    tracing::info!(
+                        "Stop signal received, interrupting operation `{operation_name}`"
+                    );
+                    return Ok(None);
+                }
+            } else {
+                tokio::time::sleep(backoff).await;
+            }
+            backoff = (backoff * 2).min(self.max_backoff);
+        }
+    }
+}
+
+/// Runs the provided DB operation retrying it on connection errors with the [default policy](ConnectionRetryPolicy::default()).
+pub async fn retry_on_connection_error<T, Fut>(
+    operation_name: &str,
+    operation: impl FnMut() -> Fut,
+) -> anyhow::Result<T>
+where
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    ConnectionRetryPolicy::default()
+        .retry(operation_name, operation)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use anyhow::Context as _;
+
+    use super::*;
+
+    fn connection_reset() -> anyhow::Error {
+        let err = io::Error::new(io::ErrorKind::ConnectionReset, "connection reset by peer");
+        anyhow::Error::from(sqlx::Error::Io(err)).context("failed getting data")
+    }
+
+    #[test]
+    fn classifying_connection_errors() {
+        assert!(is_connection_error(&connection_reset()));
+        assert!(is_connection_error(
+            &anyhow::Error::from(sqlx::Error::PoolTimedOut).context("acquire_connection_retried()")
+        ));
+        assert!(!is_connection_error(&anyhow::Error::from(
+            sqlx::Error::RowNotFound
+        )));
+        assert!(!is_connection_error(&anyhow::anyhow!("logical error")));
+    }
+
+    #[tokio::test]
+    async fn recovering_from_connection_reset() {
+        let policy =
+            ConnectionRetryPolicy::new(3, Duration::from_millis(1), Duration::from_millis(5));
+        let attempts = &AtomicUsize::new(0);
+        let output = policy
+            .retry("test", move || async move {
+                if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                    Err(connection_reset())
+                } else {
+                    Ok(42)
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(output, 42);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn not_retrying_non_connection_errors() {
+        let policy =
+            ConnectionRetryPolicy::new(3, Duration::from_millis(1), Duration::from_millis(5));
+        let attempts = &AtomicUsize::new(0);
+        let err = policy
+            .retry("test", move || async move {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(sqlx::Error::RowNotFound).context("logical error")
+            })
+            .await
+            .unwrap_err();
+        assert!(!is_connection_error(&err));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn exhausting_retries() {
+        let policy =
+            ConnectionRetryPolicy::new(2, Duration::from_millis(1), Duration::from_millis(5));
+        let attempts = &AtomicUsize::new(0);
+        let err = policy
+            .retry("test", move || async move {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(connection_reset())
+            })
+            .await
+            .unwrap_err();
+        assert!(is_connection_error(&err));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn interrupting_retries_on_stop_signal() {
+        let policy =
+            ConnectionRetryPolicy::new(5, Duration::from_secs(30), Duration::from_secs(30));
+        let (stop_sender, mut stop_receiver) = watch::channel(false);
+        let attempts = &AtomicUsize::new(0);
+        let retry_future = policy.retry_until_stopped(
+            "test",
+            is_connection_error,
+            &mut stop_receiver,
+            move || async move {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(connection_reset())
+            },
+        );
+        let stop_future = async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            stop_sender.send_replace(true);
+        };
+        let (output, ()) = tokio::time::timeout(Duration::from_secs(5), async {
+            tokio::join!(retry_future, stop_future)
+        })
+        .await
+        .expect("retries were not interrupted by the stop signal");
+        assert_eq!(output.unwrap(), None);
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,50 @@
+use zksync_config::configs::AdminConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for AdminConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("admin", "ADMIN_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            ADMIN_PORT="3322"
+            ADMIN_BIND_IP="0.0.0.0"
+        "#;
+        lock.set_env(config);
+
+        let actual = AdminConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            AdminConfig {
+                port: 3322,
+                bind_ip: Some(Ipv4Addr::UNSPECIFIED.into()),
+            }
+        );
+        assert_eq!(actual.bind_addr(), "0.0.0.0:3322".parse().unwrap());
+    }
+
+    #[test]
+    fn from_env_with_default_bind_ip() {
+        let mut lock = MUTEX.lock();
+        lock.set_env(r#"ADMIN_PORT="3322""#);
+        lock.remove_env(&["ADMIN_BIND_IP"]);
+
+        let actual = AdminConfig::from_env().unwrap();
+        assert_eq!(actual.bind_ip, None);
+        assert_eq!(actual.bind_addr(), "127.0.0.1:3322".parse().unwrap());
+    }
+}
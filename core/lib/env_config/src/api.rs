@@ -48,7 +48,7 @@ impl FromEnv for MerkleTreeApiConfig {
 
 #[cfg(test)]
 mod tests {
-    use std::num::NonZeroU32;
+    use std::num::{NonZeroU32, NonZeroUsize};
 
     use super::*;
     use crate::test_utils::{hash, EnvMutex};
@@ -65,6 +65,7 @@ mod tests {
                 req_entities_limit: Some(10000),
                 filters_disabled: false,
                 filters_limit: Some(10000),
+                filters_limit_per_connection: Some(1000),
                 subscriptions_limit: Some(10000),
                 pubsub_polling_interval: Some(200),
                 max_nonce_ahead: 5,
@@ -80,12 +81,14 @@ mod tests {
                 max_tx_size: 1000000,
                 vm_execution_cache_misses_limit: None,
                 vm_concurrency_limit: Some(512),
+                trace_call_concurrency_limit: NonZeroUsize::new(16),
                 factory_deps_cache_size_mb: Some(128),
                 initial_writes_cache_size_mb: Some(32),
                 latest_values_cache_size_mb: Some(256),
                 fee_history_limit: Some(100),
                 max_batch_request_size: Some(200),
                 max_response_body_size_mb: Some(10),
+                max_request_body_size_mb: Some(5),
                 websocket_requests_per_minute_limit: Some(NonZeroU32::new(10).unwrap()),
                 tree_api_url: None,
                 mempool_cache_update_interval: Some(50),
@@ -120,6 +123,7 @@ mod tests {
             API_WEB3_JSON_RPC_REQ_ENTITIES_LIMIT=10000
             API_WEB3_JSON_RPC_FILTERS_DISABLED=false
             API_WEB3_JSON_RPC_FILTERS_LIMIT=10000
+            API_WEB3_JSON_RPC_FILTERS_LIMIT_PER_CONNECTION=1000
             API_WEB3_JSON_RPC_SUBSCRIPTIONS_LIMIT=10000
             API_WEB3_JSON_RPC_PUBSUB_POLLING_INTERVAL=200
             API_WEB3_JSON_RPC_MAX_NONCE_AHEAD=5
@@ -131,6 +135,7 @@ mod tests {
             API_WEB3_JSON_RPC_L1_TO_L2_TRANSACTIONS_COMPATIBILITY_MODE=true
             API_WEB3_JSON_RPC_MAX_TX_SIZE=1000000
             API_WEB3_JSON_RPC_VM_CONCURRENCY_LIMIT=512
+            API_WEB3_JSON_RPC_TRACE_CALL_CONCURRENCY_LIMIT=16
             API_WEB3_JSON_RPC_FACTORY_DEPS_CACHE_SIZE_MB=128
             API_WEB3_JSON_RPC_INITIAL_WRITES_CACHE_SIZE_MB=32
             API_WEB3_JSON_RPC_LATEST_VALUES_CACHE_SIZE_MB=256
@@ -142,6 +147,7 @@ mod tests {
             API_CONTRACT_VERIFICATION_PORT="3070"
             API_CONTRACT_VERIFICATION_URL="http://127.0.0.1:3070"
             API_WEB3_JSON_RPC_MAX_RESPONSE_BODY_SIZE_MB=10
+            API_WEB3_JSON_RPC_MAX_REQUEST_BODY_SIZE_MB=5
             API_PROMETHEUS_LISTENER_PORT="3312"
             API_PROMETHEUS_PUSHGATEWAY_URL="http://127.0.0.1:9091"
             API_PROMETHEUS_PUSH_INTERVAL_MS=100
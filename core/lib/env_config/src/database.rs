@@ -61,9 +61,9 @@ impl FromEnv for PostgresConfig {
 
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
+    use std::{num::NonZeroU32, time::Duration};
 
-    use zksync_config::configs::database::MerkleTreeMode;
+    use zksync_config::configs::database::{MerkleTreeMode, RocksdbCompactionStyle};
 
     use super::*;
     use crate::test_utils::EnvMutex;
@@ -75,23 +75,44 @@ mod tests {
         let mut lock = MUTEX.lock();
         let config = r#"
             DATABASE_STATE_KEEPER_DB_PATH="/db/state_keeper"
+            DATABASE_STATE_KEEPER_DB_WRITE_BUFFER_SIZE_MB=64
+            DATABASE_STATE_KEEPER_DB_MAX_BACKGROUND_JOBS=4
+            DATABASE_STATE_KEEPER_DB_COMPACTION_STYLE=universal
+            DATABASE_STATE_KEEPER_DB_STATISTICS_ENABLED=true
             DATABASE_MERKLE_TREE_PATH="/db/tree"
             DATABASE_MERKLE_TREE_MODE=lightweight
             DATABASE_MERKLE_TREE_MULTI_GET_CHUNK_SIZE=250
             DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB=512
             DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC=60
             DATABASE_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER=50
+            DATABASE_MERKLE_TREE_MAX_L1_BATCH_LAG_FOR_PROOFS=10
+            DATABASE_MERKLE_TREE_STATISTICS_ENABLED=true
         "#;
         lock.set_env(config);
 
         let db_config = DBConfig::from_env().unwrap();
         assert_eq!(db_config.state_keeper_db_path, "/db/state_keeper");
+        assert_eq!(
+            db_config.state_keeper_db_write_buffer_size(),
+            Some(64 * 1_024 * 1_024)
+        );
+        assert_eq!(
+            db_config.state_keeper_db_max_background_jobs,
+            NonZeroU32::new(4)
+        );
+        assert_eq!(
+            db_config.state_keeper_db_compaction_style,
+            Some(RocksdbCompactionStyle::Universal)
+        );
+        assert!(db_config.state_keeper_db_statistics_enabled);
         assert_eq!(db_config.merkle_tree.path, "/db/tree");
         assert_eq!(db_config.merkle_tree.mode, MerkleTreeMode::Lightweight);
         assert_eq!(db_config.merkle_tree.multi_get_chunk_size, 250);
         assert_eq!(db_config.merkle_tree.max_l1_batches_per_iter, 50);
+        assert_eq!(db_config.merkle_tree.max_l1_batch_lag_for_proofs, 10);
         assert_eq!(db_config.merkle_tree.memtable_capacity_mb, 512);
         assert_eq!(db_config.merkle_tree.stalled_writes_timeout_sec, 60);
+        assert!(db_config.merkle_tree.statistics_enabled);
     }
 
     #[test]
@@ -99,6 +120,10 @@ mod tests {
         let mut lock = MUTEX.lock();
         lock.remove_env(&[
             "DATABASE_STATE_KEEPER_DB_PATH",
+            "DATABASE_STATE_KEEPER_DB_WRITE_BUFFER_SIZE_MB",
+            "DATABASE_STATE_KEEPER_DB_MAX_BACKGROUND_JOBS",
+            "DATABASE_STATE_KEEPER_DB_COMPACTION_STYLE",
+            "DATABASE_STATE_KEEPER_DB_STATISTICS_ENABLED",
             "DATABASE_MERKLE_TREE_BACKUP_PATH",
             "DATABASE_MERKLE_TREE_PATH",
             "DATABASE_MERKLE_TREE_MODE",
@@ -107,14 +132,21 @@ mod tests {
             "DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB",
             "DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC",
             "DATABASE_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER",
+            "DATABASE_MERKLE_TREE_MAX_L1_BATCH_LAG_FOR_PROOFS",
+            "DATABASE_MERKLE_TREE_STATISTICS_ENABLED",
         ]);
 
         let db_config = DBConfig::from_env().unwrap();
         assert_eq!(db_config.state_keeper_db_path, "./db/state_keeper");
+        assert_eq!(db_config.state_keeper_db_write_buffer_size(), None);
+        assert_eq!(db_config.state_keeper_db_max_background_jobs, None);
+        assert_eq!(db_config.state_keeper_db_compaction_style, None);
+        assert!(!db_config.state_keeper_db_statistics_enabled);
         assert_eq!(db_config.merkle_tree.path, "./db/lightweight-new");
         assert_eq!(db_config.merkle_tree.mode, MerkleTreeMode::Full);
         assert_eq!(db_config.merkle_tree.multi_get_chunk_size, 500);
         assert_eq!(db_config.merkle_tree.max_l1_batches_per_iter, 20);
+        assert_eq!(db_config.merkle_tree.max_l1_batch_lag_for_proofs, 5);
         assert_eq!(db_config.merkle_tree.block_cache_size_mb, 128);
         assert_eq!(db_config.merkle_tree.memtable_capacity_mb, 256);
         assert_eq!(db_config.merkle_tree.stalled_writes_timeout_sec, 30);
@@ -137,6 +169,14 @@ mod tests {
         assert_eq!(db_config.merkle_tree.max_l1_batches_per_iter, 50);
     }
 
+    #[test]
+    fn fifo_compaction_style_is_rejected() {
+        let mut lock = MUTEX.lock();
+        lock.set_env("DATABASE_STATE_KEEPER_DB_COMPACTION_STYLE=fifo");
+        let err = DBConfig::from_env().unwrap_err();
+        assert!(format!("{err:#}").contains("fifo"), "{err:#}");
+    }
+
     #[test]
     fn postgres_from_env() {
         let mut lock = MUTEX.lock();
@@ -1,6 +1,7 @@
 use anyhow::Context as _;
 use serde::de::DeserializeOwned;
 
+mod admin;
 mod alerts;
 mod api;
 mod chain;
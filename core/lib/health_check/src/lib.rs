@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt,
     sync::{Arc, Mutex},
     thread,
@@ -26,15 +26,28 @@ mod tests;
 pub enum HealthStatus {
     /// Component is initializing and is not ready yet.
     NotReady,
+    /// Component has started, but hasn't completed its first successful operation yet. Unlike [`Self::NotReady`],
+    /// this status is reported by the component itself, signalling that it is actively starting up.
+    Initializing,
     /// Component is ready for operations.
     Ready,
+    /// Component is operational, but its performance is degraded (e.g., it lags behind a soft threshold).
+    /// The component is still considered healthy and can serve requests.
+    Degraded,
     /// Component is affected by some non-fatal issue. The component is still considered healthy.
     Affected,
+    /// Component is operational, but intentionally doesn't serve requests until it's promoted
+    /// (e.g., API servers of a warm standby node). The component is not considered healthy, so that
+    /// load balancers don't route traffic to it.
+    Standby,
     /// Component has received a termination request and is in the process of shutting down.
     /// Components that shut down instantly may skip this status and proceed directly to [`Self::ShutDown`].
     ShuttingDown,
     /// Component is shut down.
     ShutDown,
+    /// Component has stopped operating because of a fatal error, but the process keeps running
+    /// (e.g., to serve requests in a read-only mode).
+    Failed,
     /// Component has been abnormally interrupted by a panic.
     Panicked,
 }
@@ -42,21 +55,57 @@ pub enum HealthStatus {
 impl HealthStatus {
     /// Checks whether a component is healthy according to this status.
     pub fn is_healthy(self) -> bool {
-        matches!(self, Self::Ready | Self::Affected)
+        matches!(self, Self::Ready | Self::Degraded | Self::Affected)
+    }
+
+    /// Returns the severity level corresponding to this status.
+    pub fn severity(self) -> HealthSeverity {
+        match self {
+            Self::Ready => HealthSeverity::Ok,
+            Self::Degraded => HealthSeverity::Minor,
+            Self::Affected => HealthSeverity::Major,
+            Self::NotReady
+            | Self::Initializing
+            | Self::Standby
+            | Self::ShuttingDown
+            | Self::ShutDown => HealthSeverity::Unavailable,
+            Self::Failed | Self::Panicked => HealthSeverity::Critical,
+        }
     }
 
     fn priority_for_aggregation(self) -> usize {
         match self {
             Self::Ready => 0,
-            Self::Affected => 1,
-            Self::ShuttingDown => 2,
-            Self::ShutDown => 3,
-            Self::NotReady => 4,
-            Self::Panicked => 5,
+            Self::Degraded => 1,
+            Self::Affected => 2,
+            Self::Standby => 3,
+            Self::Initializing => 4,
+            Self::ShuttingDown => 5,
+            Self::ShutDown => 6,
+            Self::NotReady => 7,
+            Self::Failed => 8,
+            Self::Panicked => 9,
         }
     }
 }
 
+/// Coarse-grained severity of a [`HealthStatus`], ordered from the least to the most severe.
+/// Allows distinguishing components that are merely slowed down from components that are failing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthSeverity {
+    /// Component is fully operational.
+    Ok,
+    /// Component is operational, but degraded; it can still serve requests.
+    Minor,
+    /// Component is affected by an issue; requests should preferably be routed elsewhere.
+    Major,
+    /// Component is not available (not ready yet, initializing, or shutting down).
+    Unavailable,
+    /// Component has failed abnormally.
+    Critical,
+}
+
 /// Health of a single component.
 #[derive(Debug, Clone, Serialize, PartialEq)]
 pub struct Health {
@@ -90,10 +139,32 @@ impl From<HealthStatus> for Health {
     }
 }
 
+/// Information about the latest health check of a component.
+#[derive(Debug, Clone, Copy, Serialize)]
+struct HealthCheckInfo {
+    /// Time the check took to complete (or to time out), in milliseconds.
+    duration_ms: u64,
+    /// Whether the check took longer than the slow time limit.
+    is_slow: bool,
+    /// Whether the check took longer than the hard time limit and was aborted.
+    timed_out: bool,
+}
+
+/// Health of a single component reported in [`AppHealth`]. Serialized as the component [`Health`]
+/// extended with the `check` field containing [`HealthCheckInfo`].
+#[derive(Debug, Clone, Serialize)]
+struct ComponentHealth {
+    #[serde(flatten)]
+    health: Health,
+    check: HealthCheckInfo,
+}
+
 /// Application health check aggregating health from multiple components.
 #[derive(Debug)]
 pub struct AppHealthCheck {
     components: Mutex<Vec<Arc<dyn CheckHealth>>>,
+    /// Names of components excluded from the aggregated health status.
+    excluded_components: HashSet<String>,
     slow_time_limit: Duration,
     hard_time_limit: Duration,
 }
@@ -114,11 +185,42 @@ impl AppHealthCheck {
         tracing::debug!("Created app health with time limits: slow={slow_time_limit:?}, hard={hard_time_limit:?}");
         Self {
             components: Mutex::default(),
+            excluded_components: HashSet::new(),
             slow_time_limit,
             hard_time_limit,
         }
     }
 
+    /// Excludes the specified components from the aggregated health status. The health of excluded components
+    /// is still checked and reported in [`AppHealth`], but it doesn't influence whether the application is healthy.
+    #[must_use]
+    pub fn with_excluded_components(mut self, names: impl IntoIterator<Item = String>) -> Self {
+        self.excluded_components.extend(names);
+        self
+    }
+
+    /// Checks that all [excluded components](Self::with_excluded_components()) are registered. Should be called
+    /// after all components are inserted.
+    pub fn validate_excluded_components(&self) -> anyhow::Result<()> {
+        let guard = self
+            .components
+            .lock()
+            .expect("`AppHealthCheck` is poisoned");
+        let mut unknown_components: Vec<_> = self
+            .excluded_components
+            .iter()
+            .filter(|&name| !guard.iter().any(|check| check.name() == name))
+            .collect();
+        unknown_components.sort_unstable();
+        anyhow::ensure!(
+            unknown_components.is_empty(),
+            "Components {unknown_components:?} excluded from health aggregation are not registered; \
+             registered components: {:?}",
+            guard.iter().map(|check| check.name()).collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
     /// Inserts health check for a component.
     pub fn insert_component(&self, health_check: ReactiveHealthCheck) {
         self.insert_custom_component(Arc::new(health_check));
@@ -159,13 +261,18 @@ impl AppHealthCheck {
         let components: HashMap<_, _> = future::join_all(check_futures).await.into_iter().collect();
 
         let aggregated_status = components
-            .values()
-            .map(|health| health.status)
+            .iter()
+            .filter(|(&name, _)| !self.excluded_components.contains(name))
+            .map(|(_, component)| component.health.status)
             .max_by_key(|status| status.priority_for_aggregation())
             .unwrap_or(HealthStatus::Ready);
         let inner = aggregated_status.into();
 
-        let health = AppHealth { inner, components };
+        let health = AppHealth {
+            inner,
+            severity: aggregated_status.severity(),
+            components,
+        };
         if !health.inner.status.is_healthy() {
             // Only log non-ready application health so that logs are not spammed without a reason.
             tracing::debug!("Aggregated application health: {health:?}");
@@ -177,7 +284,7 @@ impl AppHealthCheck {
         check: &dyn CheckHealth,
         slow_time_limit: Duration,
         hard_time_limit: Duration,
-    ) -> (&'static str, Health) {
+    ) -> (&'static str, ComponentHealth) {
         struct DropGuard {
             check_name: &'static str,
             started_at: tokio::time::Instant,
@@ -218,24 +325,32 @@ impl AppHealthCheck {
         let result = tokio::time::timeout_at(timeout_at, check.check_health()).await;
         drop_guard.is_armed = false;
         let elapsed = started_at.elapsed();
-        match result {
+        let is_slow = elapsed > slow_time_limit;
+        let timed_out = result.is_err();
+        let health = match result {
             Ok(output) => {
-                if elapsed > slow_time_limit {
+                if is_slow {
                     tracing::info!(
                         "Health check `{check_name}` took >{slow_time_limit:?} to complete: {elapsed:?}"
                     );
                     METRICS.observe_abnormal_check(check_name, CheckResult::Slow, elapsed);
                 }
-                (check_name, output)
+                output
             }
             Err(_) => {
                 tracing::warn!(
                     "Health check `{check_name}` timed out, taking >{hard_time_limit:?} to complete; marking as not ready"
                 );
                 METRICS.observe_abnormal_check(check_name, CheckResult::TimedOut, elapsed);
-                (check_name, HealthStatus::NotReady.into())
+                HealthStatus::NotReady.into()
             }
-        }
+        };
+        let check = HealthCheckInfo {
+            duration_ms: elapsed.as_millis().try_into().unwrap_or(u64::MAX),
+            is_slow,
+            timed_out,
+        };
+        (check_name, ComponentHealth { health, check })
     }
 }
 
@@ -244,13 +359,33 @@ impl AppHealthCheck {
 pub struct AppHealth {
     #[serde(flatten)]
     inner: Health,
-    components: HashMap<&'static str, Health>,
+    /// Worst severity among all components.
+    severity: HealthSeverity,
+    components: HashMap<&'static str, ComponentHealth>,
 }
 
 impl AppHealth {
     pub fn is_healthy(&self) -> bool {
         self.inner.status.is_healthy()
     }
+
+    /// Checks whether the application is starting up, i.e., all its components are either healthy
+    /// or [initializing](HealthStatus::Initializing), and at least one of them is initializing.
+    pub fn is_initializing(&self) -> bool {
+        self.inner.status == HealthStatus::Initializing
+    }
+
+    /// Checks whether the application is alive, i.e., none of its components has [failed](HealthSeverity::Critical).
+    /// Unlike [`Self::is_healthy()`], this holds for an application that is starting up, catching up
+    /// or shutting down.
+    pub fn is_alive(&self) -> bool {
+        self.severity < HealthSeverity::Critical
+    }
+
+    /// Returns the aggregated severity of the application health.
+    pub fn severity(&self) -> HealthSeverity {
+        self.severity
+    }
 }
 
 /// Interface to be used for health checks.
@@ -90,11 +90,11 @@ async fn aggregating_health_checks() {
     assert!(!app_health.is_healthy());
     assert_matches!(app_health.inner.status(), HealthStatus::NotReady);
     assert_matches!(
-        app_health.components["first"].status,
+        app_health.components["first"].health.status,
         HealthStatus::NotReady
     );
     assert_matches!(
-        app_health.components["second"].status,
+        app_health.components["second"].health.status,
         HealthStatus::NotReady
     );
 
@@ -103,9 +103,12 @@ async fn aggregating_health_checks() {
     let app_health = checks.check_health().await;
     assert!(!app_health.is_healthy());
     assert_matches!(app_health.inner.status(), HealthStatus::NotReady);
-    assert_matches!(app_health.components["first"].status, HealthStatus::Ready);
     assert_matches!(
-        app_health.components["second"].status,
+        app_health.components["first"].health.status,
+        HealthStatus::Ready
+    );
+    assert_matches!(
+        app_health.components["second"].health.status,
         HealthStatus::NotReady
     );
 
@@ -114,9 +117,12 @@ async fn aggregating_health_checks() {
     let app_health = checks.check_health().await;
     assert!(app_health.is_healthy());
     assert_matches!(app_health.inner.status(), HealthStatus::Affected);
-    assert_matches!(app_health.components["first"].status, HealthStatus::Ready);
     assert_matches!(
-        app_health.components["second"].status,
+        app_health.components["first"].health.status,
+        HealthStatus::Ready
+    );
+    assert_matches!(
+        app_health.components["second"].health.status,
         HealthStatus::Affected
     );
 
@@ -126,11 +132,242 @@ async fn aggregating_health_checks() {
     assert!(!app_health.is_healthy());
     assert_matches!(app_health.inner.status(), HealthStatus::ShutDown);
     assert_matches!(
-        app_health.components["first"].status,
+        app_health.components["first"].health.status,
         HealthStatus::ShutDown
     );
     assert_matches!(
-        app_health.components["second"].status,
+        app_health.components["second"].health.status,
         HealthStatus::Affected
     );
 }
+
+#[tokio::test]
+async fn aggregating_health_checks_with_severity() {
+    let (first_check, first_updater) = ReactiveHealthCheck::new("first");
+    let (second_check, second_updater) = ReactiveHealthCheck::new("second");
+    let checks = AppHealthCheck {
+        components: Mutex::new(vec![Arc::new(first_check), Arc::new(second_check)]),
+        ..AppHealthCheck::default()
+    };
+
+    first_updater.update(HealthStatus::Ready.into());
+    second_updater.update(HealthStatus::Ready.into());
+    let app_health = checks.check_health().await;
+    assert!(app_health.is_healthy());
+    assert_eq!(app_health.severity(), HealthSeverity::Ok);
+
+    second_updater.update(HealthStatus::Degraded.into());
+    let app_health = checks.check_health().await;
+    assert!(app_health.is_healthy());
+    assert_matches!(app_health.inner.status(), HealthStatus::Degraded);
+    assert_eq!(app_health.severity(), HealthSeverity::Minor);
+
+    first_updater.update(HealthStatus::Affected.into());
+    let app_health = checks.check_health().await;
+    assert!(app_health.is_healthy());
+    assert_matches!(app_health.inner.status(), HealthStatus::Affected);
+    assert_eq!(app_health.severity(), HealthSeverity::Major);
+
+    let serialized = serde_json::to_value(&app_health).unwrap();
+    assert_eq!(serialized["status"], "affected");
+    assert_eq!(serialized["severity"], "major");
+    assert_eq!(serialized["components"]["second"]["status"], "degraded");
+
+    second_updater.update(HealthStatus::Panicked.into());
+    let app_health = checks.check_health().await;
+    assert!(!app_health.is_healthy());
+    assert_matches!(app_health.inner.status(), HealthStatus::Panicked);
+    assert_eq!(app_health.severity(), HealthSeverity::Critical);
+}
+
+#[tokio::test]
+async fn aggregating_health_checks_during_initialization() {
+    let (first_check, first_updater) = ReactiveHealthCheck::new("first");
+    let (second_check, second_updater) = ReactiveHealthCheck::new("second");
+    let checks = AppHealthCheck {
+        components: Mutex::new(vec![Arc::new(first_check), Arc::new(second_check)]),
+        ..AppHealthCheck::default()
+    };
+
+    first_updater.update(HealthStatus::Initializing.into());
+    let app_health = checks.check_health().await;
+    // A component that hasn't started yet takes precedence over an initializing one.
+    assert!(!app_health.is_healthy());
+    assert!(!app_health.is_initializing());
+    assert_matches!(app_health.inner.status(), HealthStatus::NotReady);
+
+    second_updater.update(HealthStatus::Ready.into());
+    let app_health = checks.check_health().await;
+    assert!(!app_health.is_healthy());
+    assert!(app_health.is_initializing());
+    assert_matches!(app_health.inner.status(), HealthStatus::Initializing);
+    assert_eq!(app_health.severity(), HealthSeverity::Unavailable);
+
+    first_updater.update(HealthStatus::Ready.into());
+    let app_health = checks.check_health().await;
+    assert!(app_health.is_healthy());
+    assert!(!app_health.is_initializing());
+    assert_matches!(app_health.inner.status(), HealthStatus::Ready);
+    assert_matches!(
+        app_health.components["first"].health.status,
+        HealthStatus::Ready
+    );
+
+    // A component degraded after running is distinguished from one that is starting up.
+    first_updater.update(HealthStatus::Degraded.into());
+    let app_health = checks.check_health().await;
+    assert!(app_health.is_healthy());
+    assert!(!app_health.is_initializing());
+    assert_matches!(app_health.inner.status(), HealthStatus::Degraded);
+}
+
+#[tokio::test]
+async fn aggregating_health_checks_with_excluded_component() {
+    let (first_check, first_updater) = ReactiveHealthCheck::new("first");
+    let (second_check, second_updater) = ReactiveHealthCheck::new("second");
+    let checks = AppHealthCheck::default().with_excluded_components(["second".to_owned()]);
+    checks.insert_component(first_check);
+    checks.insert_component(second_check);
+    checks.validate_excluded_components().unwrap();
+
+    first_updater.update(HealthStatus::Ready.into());
+    second_updater.update(HealthStatus::Panicked.into());
+    let app_health = checks.check_health().await;
+    assert!(app_health.is_healthy());
+    assert_matches!(app_health.inner.status(), HealthStatus::Ready);
+    // The status of the excluded component is still reported.
+    assert_matches!(
+        app_health.components["second"].health.status,
+        HealthStatus::Panicked
+    );
+
+    first_updater.update(HealthStatus::Affected.into());
+    let app_health = checks.check_health().await;
+    assert!(app_health.is_healthy());
+    assert_matches!(app_health.inner.status(), HealthStatus::Affected);
+}
+
+#[test]
+fn validating_excluded_components() {
+    let (check, _updater) = ReactiveHealthCheck::new("first");
+    let checks = AppHealthCheck::default().with_excluded_components(["unknown".to_owned()]);
+    checks.insert_component(check);
+    let err = checks
+        .validate_excluded_components()
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("unknown"), "{err}");
+}
+
+#[tokio::test]
+async fn aggregating_health_checks_in_standby() {
+    let (first_check, first_updater) = ReactiveHealthCheck::new("first");
+    let (second_check, second_updater) = ReactiveHealthCheck::new("second");
+    let checks = AppHealthCheck::default();
+    checks.insert_component(first_check);
+    checks.insert_component(second_check);
+
+    first_updater.update(HealthStatus::Affected.into());
+    second_updater.update(HealthStatus::Standby.into());
+    let app_health = checks.check_health().await;
+    // A standby application must not be considered healthy, so that it doesn't receive traffic.
+    assert!(!app_health.is_healthy());
+    assert!(!app_health.is_initializing());
+    assert_matches!(app_health.inner.status(), HealthStatus::Standby);
+    assert_eq!(app_health.severity(), HealthSeverity::Unavailable);
+    let serialized = serde_json::to_value(&app_health).unwrap();
+    assert_eq!(serialized["status"], "standby");
+
+    second_updater.update(HealthStatus::Ready.into());
+    let app_health = checks.check_health().await;
+    assert!(app_health.is_healthy());
+    assert_matches!(app_health.inner.status(), HealthStatus::Affected);
+}
+
+#[tokio::test]
+async fn checking_liveness() {
+    let (first_check, first_updater) = ReactiveHealthCheck::new("first");
+    let (second_check, second_updater) = ReactiveHealthCheck::new("second");
+    let checks = AppHealthCheck::default();
+    checks.insert_component(first_check);
+    checks.insert_component(second_check);
+
+    first_updater.update(HealthStatus::Ready.into());
+    let app_health = checks.check_health().await;
+    // A component that isn't ready yet (e.g., one catching up) doesn't make the application dead.
+    assert!(!app_health.is_healthy());
+    assert!(app_health.is_alive());
+
+    second_updater.update(HealthStatus::ShuttingDown.into());
+    let app_health = checks.check_health().await;
+    assert!(!app_health.is_healthy());
+    assert!(app_health.is_alive());
+
+    second_updater.update(HealthStatus::Failed.into());
+    let app_health = checks.check_health().await;
+    assert!(!app_health.is_alive());
+    assert_eq!(app_health.severity(), HealthSeverity::Critical);
+}
+
+#[derive(Debug)]
+struct SlowHealthCheck {
+    name: &'static str,
+    latency: Duration,
+}
+
+#[async_trait]
+impl CheckHealth for SlowHealthCheck {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn check_health(&self) -> Health {
+        tokio::time::sleep(self.latency).await;
+        HealthStatus::Ready.into()
+    }
+}
+
+#[tokio::test]
+async fn reporting_health_check_latency() {
+    let checks = AppHealthCheck::new(
+        Some(Duration::from_millis(50)),
+        Some(Duration::from_millis(500)),
+    );
+    let (fast_check, fast_updater) = ReactiveHealthCheck::new("fast");
+    fast_updater.update(HealthStatus::Ready.into());
+    checks.insert_component(fast_check);
+    checks.insert_custom_component(Arc::new(SlowHealthCheck {
+        name: "slow",
+        latency: Duration::from_millis(100),
+    }));
+    checks.insert_custom_component(Arc::new(SlowHealthCheck {
+        name: "hanging",
+        latency: Duration::from_secs(3_600),
+    }));
+
+    let app_health = checks.check_health().await;
+    // The timed out component is reported as not ready, same as before adding check info.
+    assert_matches!(app_health.inner.status(), HealthStatus::NotReady);
+    let serialized = serde_json::to_value(&app_health).unwrap();
+    assert_eq!(serialized["status"], "not_ready");
+    let components = &serialized["components"];
+
+    assert_eq!(components["fast"]["status"], "ready");
+    assert_eq!(components["fast"]["check"]["is_slow"], false);
+    assert_eq!(components["fast"]["check"]["timed_out"], false);
+
+    assert_eq!(components["slow"]["status"], "ready");
+    assert!(components["slow"]["check"]["duration_ms"].as_u64().unwrap() >= 100);
+    assert_eq!(components["slow"]["check"]["is_slow"], true);
+    assert_eq!(components["slow"]["check"]["timed_out"], false);
+
+    assert_eq!(components["hanging"]["status"], "not_ready");
+    assert!(
+        components["hanging"]["check"]["duration_ms"]
+            .as_u64()
+            .unwrap()
+            >= 500
+    );
+    assert_eq!(components["hanging"]["check"]["is_slow"], true);
+    assert_eq!(components["hanging"]["check"]["timed_out"], true);
+}
@@ -1,8 +1,11 @@
 //! Tying the Merkle tree implementation to the problem domain.
 
+use std::path::Path;
+
 use rayon::{ThreadPool, ThreadPoolBuilder};
 use zksync_crypto::hasher::blake2::Blake2Hasher;
 use zksync_prover_interface::inputs::{PrepareBasicCircuitsJob, StorageLogMetadata};
+use zksync_storage::rocksdb;
 use zksync_types::{
     writes::{InitialStorageWrite, RepeatedStorageWrite},
     L1BatchNumber, StorageKey,
@@ -430,6 +433,31 @@ impl ZkSyncTreeReader {
         self.0.latest_root().leaf_count()
     }
 
+    /// Returns the number of leaves in the tree after processing the specified L1 batch, or `None` if the L1 batch
+    /// is not processed by the tree yet.
+    pub fn l1_batch_leaf_count(&self, l1_batch_number: L1BatchNumber) -> Option<u64> {
+        let version = u64::from(l1_batch_number.0);
+        self.0.root(version).as_ref().map(Root::leaf_count)
+    }
+
+    /// Returns the root hash of the tree after processing the specified L1 batch, or `None` if the L1 batch
+    /// is not processed by the tree yet.
+    pub fn l1_batch_root_hash(&self, l1_batch_number: L1BatchNumber) -> Option<ValueHash> {
+        let version = u64::from(l1_batch_number.0);
+        self.0.root_hash(version)
+    }
+
+    /// Creates a consistent checkpoint of the tree RocksDB at the specified `path`, which must not exist.
+    /// The checkpoint contains all changes flushed to RocksDB, i.e., it corresponds to the tree state
+    /// as of [`Self::next_l1_batch_number()`] - 1.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB I/O errors.
+    pub fn create_checkpoint(&self, path: &Path) -> Result<(), rocksdb::Error> {
+        self.0.db.create_checkpoint(path)
+    }
+
     /// Reads entries together with Merkle proofs with the specified keys from the tree. The entries are returned
     /// in the same order as requested.
     ///
@@ -133,6 +133,15 @@ impl RocksDBWrapper {
         })
     }
 
+    /// Creates a consistent checkpoint of the underlying RocksDB at the specified `path`, which must not exist.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB I/O errors.
+    pub fn create_checkpoint(&self, path: &Path) -> Result<(), rocksdb::Error> {
+        self.db.create_checkpoint(path)
+    }
+
     /// Returns the wrapped RocksDB instance.
     pub fn into_inner(self) -> RocksDB<MerkleTreeColumnFamily> {
         self.db
@@ -6,3 +6,34 @@ use crate::vm_latest::constants::{TX_MEMORY_OVERHEAD_GAS, TX_SLOT_OVERHEAD_GAS};
 pub(crate) fn derive_overhead(encoded_len: usize) -> u32 {
     TX_SLOT_OVERHEAD_GAS.max(TX_MEMORY_OVERHEAD_GAS * (encoded_len as u32))
 }
+
+/// Returns the combined overhead gas for a transaction occupying a single bootloader slot and `encoded_len_bytes`
+/// of the bootloader memory, i.e. `TX_SLOT_OVERHEAD_GAS + encoded_len_bytes * TX_MEMORY_OVERHEAD_GAS`.
+/// The computation saturates at `u32::MAX`.
+///
+/// Note that the bootloader charges only the larger of the two overheads (see [`derive_overhead()`]),
+/// so the returned value is an upper bound for the overhead charged during execution.
+pub fn tx_overhead_gas(encoded_len_bytes: usize) -> u32 {
+    let encoded_len_bytes = u32::try_from(encoded_len_bytes).unwrap_or(u32::MAX);
+    TX_MEMORY_OVERHEAD_GAS
+        .saturating_mul(encoded_len_bytes)
+        .saturating_add(TX_SLOT_OVERHEAD_GAS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computing_tx_overhead_gas() {
+        assert_eq!(tx_overhead_gas(0), 10_000);
+        assert_eq!(tx_overhead_gas(1), 10_010);
+        assert_eq!(tx_overhead_gas(1_000), 20_000);
+        assert_eq!(tx_overhead_gas(100_000), 1_010_000);
+        assert_eq!(tx_overhead_gas(usize::MAX), u32::MAX);
+
+        for encoded_len in [0, 1, 1_000, 100_000] {
+            assert!(tx_overhead_gas(encoded_len) >= derive_overhead(encoded_len));
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::{repr::ProtoRepr, required};
+
+use crate::proto::admin as proto;
+
+impl ProtoRepr for proto::Admin {
+    type Type = configs::AdminConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            port: required(&self.port)
+                .and_then(|x| Ok((*x).try_into()?))
+                .context("port")?,
+            bind_ip: self
+                .bind_ip
+                .as_ref()
+                .map(|x| x.parse())
+                .transpose()
+                .context("bind_ip")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            port: Some(this.port.into()),
+            bind_ip: this.bind_ip.map(|ip| ip.to_string()),
+        }
+    }
+}
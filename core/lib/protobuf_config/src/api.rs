@@ -46,6 +46,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
             req_entities_limit: self.req_entities_limit,
             filters_disabled: self.filters_disabled.unwrap_or(false),
             filters_limit: self.filters_limit,
+            filters_limit_per_connection: self.filters_limit_per_connection,
             subscriptions_limit: self.subscriptions_limit,
             pubsub_polling_interval: self.pubsub_polling_interval,
             max_nonce_ahead: *required(&self.max_nonce_ahead).context("max_nonce_ahead")?,
@@ -88,6 +89,11 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("vm_concurrency_limit")?,
+            trace_call_concurrency_limit: self
+                .trace_call_concurrency_limit
+                .map(|x| anyhow::Ok(usize::try_from(x)?.try_into()?))
+                .transpose()
+                .context("trace_call_concurrency_limit")?,
             factory_deps_cache_size_mb: self
                 .factory_deps_cache_size_mb
                 .map(|x| x.try_into())
@@ -114,6 +120,11 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("max_response_body_size_mb")?,
+            max_request_body_size_mb: self
+                .max_request_body_size_mb
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_request_body_size_mb")?,
             websocket_requests_per_minute_limit: self
                 .websocket_requests_per_minute_limit
                 .map(|x| x.try_into())
@@ -139,6 +150,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
             mempool_cache_update_interval: this.mempool_cache_update_interval,
             mempool_cache_size: this.mempool_cache_size.map(|x| x.try_into().unwrap()),
             filters_limit: this.filters_limit,
+            filters_limit_per_connection: this.filters_limit_per_connection,
             subscriptions_limit: this.subscriptions_limit,
             pubsub_polling_interval: this.pubsub_polling_interval,
             max_nonce_ahead: Some(this.max_nonce_ahead),
@@ -159,6 +171,9 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .vm_execution_cache_misses_limit
                 .map(|x| x.try_into().unwrap()),
             vm_concurrency_limit: this.vm_concurrency_limit.map(|x| x.try_into().unwrap()),
+            trace_call_concurrency_limit: this
+                .trace_call_concurrency_limit
+                .map(|x| x.get().try_into().unwrap()),
             factory_deps_cache_size_mb: this
                 .factory_deps_cache_size_mb
                 .map(|x| x.try_into().unwrap()),
@@ -173,6 +188,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
             max_response_body_size_mb: this
                 .max_response_body_size_mb
                 .map(|x| x.try_into().unwrap()),
+            max_request_body_size_mb: this.max_request_body_size_mb.map(|x| x.try_into().unwrap()),
             websocket_requests_per_minute_limit: this
                 .websocket_requests_per_minute_limit
                 .map(|x| x.into()),
@@ -39,6 +39,7 @@ impl proto::FeeModelVersion {
         match n {
             From::V1 => Self::V1,
             From::V2 => Self::V2,
+            From::V3 => Self::V3,
         }
     }
 
@@ -47,6 +48,7 @@ impl proto::FeeModelVersion {
         match self {
             Self::V1 => To::V1,
             Self::V2 => To::V2,
+            Self::V3 => To::V3,
         }
     }
 }
@@ -147,6 +149,7 @@ impl ProtoRepr for proto::StateKeeper {
             validation_computational_gas_limit: *required(&self.validation_computational_gas_limit)
                 .context("validation_computational_gas_limit")?,
             save_call_traces: *required(&self.save_call_traces).context("save_call_traces")?,
+            separate_call_traces_pool: self.separate_call_traces_pool.unwrap_or(false),
             virtual_blocks_interval: *required(&self.virtual_blocks_interval)
                 .context("virtual_blocks_interval")?,
             virtual_blocks_per_miniblock: *required(&self.virtual_blocks_per_miniblock)
@@ -156,6 +159,11 @@ impl ProtoRepr for proto::StateKeeper {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("enum_index_migration_chunk_size")?,
+            max_miniblocks_per_batch: self
+                .max_miniblocks_per_batch
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_miniblocks_per_batch")?,
             bootloader_hash: self
                 .bootloader_hash
                 .as_ref()
@@ -203,12 +211,17 @@ impl ProtoRepr for proto::StateKeeper {
             fee_model_version: Some(proto::FeeModelVersion::new(&this.fee_model_version).into()),
             validation_computational_gas_limit: Some(this.validation_computational_gas_limit),
             save_call_traces: Some(this.save_call_traces),
+            separate_call_traces_pool: Some(this.separate_call_traces_pool),
             virtual_blocks_interval: Some(this.virtual_blocks_interval),
             virtual_blocks_per_miniblock: Some(this.virtual_blocks_per_miniblock),
             enum_index_migration_chunk_size: this
                 .enum_index_migration_chunk_size
                 .as_ref()
                 .map(|x| (*x).try_into().unwrap()),
+            max_miniblocks_per_batch: this
+                .max_miniblocks_per_batch
+                .as_ref()
+                .map(|x| (*x).try_into().unwrap()),
             bootloader_hash: this.bootloader_hash.map(|a| a.as_bytes().into()),
             default_aa_hash: this.default_aa_hash.map(|a| a.as_bytes().into()),
             l1_batch_commit_data_generator_mode: Some(
@@ -240,14 +253,17 @@ impl ProtoRepr for proto::Mempool {
     type Type = configs::chain::MempoolConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
         Ok(Self::Type {
-            sync_interval_ms: *required(&self.sync_interval_ms).context("sync_interval_ms")?,
+            sync_interval_ms: *required(&self.sync_interval_ms)
+                .context("mempool.sync_interval_ms")?,
             sync_batch_size: required(&self.sync_batch_size)
                 .and_then(|x| Ok((*x).try_into()?))
-                .context("sync_batch_size")?,
-            capacity: *required(&self.capacity).context("capacity")?,
-            stuck_tx_timeout: *required(&self.stuck_tx_timeout).context("stuck_tx_timeout")?,
-            remove_stuck_txs: *required(&self.remove_stuck_txs).context("remove_stuck_txs")?,
-            delay_interval: *required(&self.delay_interval).context("delay_interval")?,
+                .context("mempool.sync_batch_size")?,
+            capacity: *required(&self.capacity).context("mempool.capacity")?,
+            stuck_tx_timeout: *required(&self.stuck_tx_timeout)
+                .context("mempool.stuck_tx_timeout")?,
+            remove_stuck_txs: *required(&self.remove_stuck_txs)
+                .context("mempool.remove_stuck_txs")?,
+            delay_interval: *required(&self.delay_interval).context("mempool.delay_interval")?,
         })
     }
 
@@ -25,6 +25,24 @@ impl proto::MerkleTreeMode {
     }
 }
 
+impl proto::RocksdbCompactionStyle {
+    fn new(x: &configs::database::RocksdbCompactionStyle) -> Self {
+        use configs::database::RocksdbCompactionStyle as From;
+        match x {
+            From::Level => Self::Level,
+            From::Universal => Self::Universal,
+        }
+    }
+
+    fn parse(&self) -> configs::database::RocksdbCompactionStyle {
+        use configs::database::RocksdbCompactionStyle as To;
+        match self {
+            Self::Level => To::Level,
+            Self::Universal => To::Universal,
+        }
+    }
+}
+
 impl ProtoRepr for proto::MerkleTree {
     type Type = configs::database::MerkleTreeConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
@@ -48,6 +66,10 @@ impl ProtoRepr for proto::MerkleTree {
             max_l1_batches_per_iter: required(&self.max_l1_batches_per_iter)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("max_l1_batches_per_iter")?,
+            max_l1_batch_lag_for_proofs: self.max_l1_batch_lag_for_proofs.unwrap_or(
+                configs::database::MerkleTreeConfig::default_max_l1_batch_lag_for_proofs(),
+            ),
+            statistics_enabled: self.statistics_enabled.unwrap_or(false),
         })
     }
 
@@ -60,6 +82,8 @@ impl ProtoRepr for proto::MerkleTree {
             memtable_capacity_mb: Some(this.memtable_capacity_mb.try_into().unwrap()),
             stalled_writes_timeout_sec: Some(this.stalled_writes_timeout_sec),
             max_l1_batches_per_iter: Some(this.max_l1_batches_per_iter.try_into().unwrap()),
+            max_l1_batch_lag_for_proofs: Some(this.max_l1_batch_lag_for_proofs),
+            statistics_enabled: Some(this.statistics_enabled),
         }
     }
 }
@@ -71,6 +95,26 @@ impl ProtoRepr for proto::Db {
             state_keeper_db_path: required(&self.state_keeper_db_path)
                 .context("state_keeper_db_path")?
                 .clone(),
+            state_keeper_db_write_buffer_size_mb: self
+                .state_keeper_db_write_buffer_size_mb
+                .map(|x| x.try_into())
+                .transpose()
+                .context("state_keeper_db_write_buffer_size_mb")?,
+            state_keeper_db_max_background_jobs: self
+                .state_keeper_db_max_background_jobs
+                .map(|x| x.try_into())
+                .transpose()
+                .context("state_keeper_db_max_background_jobs")?,
+            state_keeper_db_compaction_style: self
+                .state_keeper_db_compaction_style
+                .map(|x| {
+                    Ok::<_, anyhow::Error>(proto::RocksdbCompactionStyle::try_from(x)?.parse())
+                })
+                .transpose()
+                .context("state_keeper_db_compaction_style")?,
+            state_keeper_db_statistics_enabled: self
+                .state_keeper_db_statistics_enabled
+                .unwrap_or(false),
             merkle_tree: read_required_repr(&self.merkle_tree).context("merkle_tree")?,
         })
     }
@@ -79,6 +123,16 @@ impl ProtoRepr for proto::Db {
         Self {
             state_keeper_db_path: Some(this.state_keeper_db_path.clone()),
             merkle_tree: Some(ProtoRepr::build(&this.merkle_tree)),
+            state_keeper_db_write_buffer_size_mb: this
+                .state_keeper_db_write_buffer_size_mb
+                .map(|x| x.try_into().unwrap()),
+            state_keeper_db_max_background_jobs: this
+                .state_keeper_db_max_background_jobs
+                .map(|x| x.into()),
+            state_keeper_db_compaction_style: this
+                .state_keeper_db_compaction_style
+                .map(|x| proto::RocksdbCompactionStyle::new(&x).into()),
+            state_keeper_db_statistics_enabled: Some(this.state_keeper_db_statistics_enabled),
         }
     }
 }
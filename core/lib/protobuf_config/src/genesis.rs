@@ -0,0 +1,68 @@
+use anyhow::Context as _;
+use zksync_basic_types::{L1ChainId, L2ChainId};
+use zksync_config::configs;
+use zksync_protobuf::{repr::ProtoRepr, required};
+
+use crate::{parse_h160, parse_h256, proto::genesis as proto};
+
+impl ProtoRepr for proto::Genesis {
+    type Type = configs::GenesisConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            protocol_version: required(&self.protocol_version)
+                .and_then(|x| Ok((*x).try_into()?))
+                .context("protocol_version")?,
+            genesis_root_hash: required(&self.genesis_root_hash)
+                .and_then(|x| parse_h256(x))
+                .context("genesis_root_hash")?,
+            rollup_last_leaf_index: *required(&self.rollup_last_leaf_index)
+                .context("rollup_last_leaf_index")?,
+            genesis_commitment: required(&self.genesis_commitment)
+                .and_then(|x| parse_h256(x))
+                .context("genesis_commitment")?,
+            bootloader_hash: required(&self.bootloader_hash)
+                .and_then(|x| parse_h256(x))
+                .context("bootloader_hash")?,
+            default_aa_hash: required(&self.default_aa_hash)
+                .and_then(|x| parse_h256(x))
+                .context("default_aa_hash")?,
+            fee_account: required(&self.fee_account)
+                .and_then(|x| parse_h160(x))
+                .context("fee_account")?,
+            l1_chain_id: required(&self.l1_chain_id)
+                .map(|x| L1ChainId(*x))
+                .context("l1_chain_id")?,
+            l2_chain_id: required(&self.l2_chain_id)
+                .and_then(|x| L2ChainId::try_from(*x).map_err(anyhow::Error::msg))
+                .context("l2_chain_id")?,
+            recursion_node_level_vk_hash: required(&self.recursion_node_level_vk_hash)
+                .and_then(|x| parse_h256(x))
+                .context("recursion_node_level_vk_hash")?,
+            recursion_leaf_level_vk_hash: required(&self.recursion_leaf_level_vk_hash)
+                .and_then(|x| parse_h256(x))
+                .context("recursion_leaf_level_vk_hash")?,
+            recursion_scheduler_level_vk_hash: required(&self.recursion_scheduler_level_vk_hash)
+                .and_then(|x| parse_h256(x))
+                .context("recursion_scheduler_level_vk_hash")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            protocol_version: Some(this.protocol_version.into()),
+            genesis_root_hash: Some(this.genesis_root_hash.as_bytes().into()),
+            rollup_last_leaf_index: Some(this.rollup_last_leaf_index),
+            genesis_commitment: Some(this.genesis_commitment.as_bytes().into()),
+            bootloader_hash: Some(this.bootloader_hash.as_bytes().into()),
+            default_aa_hash: Some(this.default_aa_hash.as_bytes().into()),
+            fee_account: Some(this.fee_account.as_bytes().into()),
+            l1_chain_id: Some(this.l1_chain_id.0),
+            l2_chain_id: Some(this.l2_chain_id.as_u64()),
+            recursion_node_level_vk_hash: Some(this.recursion_node_level_vk_hash.as_bytes().into()),
+            recursion_leaf_level_vk_hash: Some(this.recursion_leaf_level_vk_hash.as_bytes().into()),
+            recursion_scheduler_level_vk_hash: Some(
+                this.recursion_scheduler_level_vk_hash.as_bytes().into(),
+            ),
+        }
+    }
+}
@@ -4,6 +4,7 @@
 //! * protobuf text format
 //! * protobuf json format
 
+mod admin;
 mod alerts;
 mod api;
 mod chain;
@@ -19,6 +20,7 @@ mod fri_prover_gateway;
 mod fri_prover_group;
 mod fri_witness_generator;
 mod fri_witness_vector_generator;
+mod genesis;
 mod house_keeper;
 mod object_store;
 mod observability;
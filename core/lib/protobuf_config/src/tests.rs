@@ -1,4 +1,13 @@
-use zksync_protobuf::testonly::{test_encode_all_formats, ReprConv};
+use rand::distributions::Distribution;
+use zksync_config::configs::{
+    api::Web3JsonRpcConfig,
+    chain::{FeeModelVersion, MempoolConfig, StateKeeperConfig},
+};
+use zksync_consensus_utils::EncodeDist;
+use zksync_protobuf::{
+    repr::ProtoRepr,
+    testonly::{test_encode_all_formats, ReprConv},
+};
 
 use crate::proto;
 
@@ -6,6 +15,7 @@ use crate::proto;
 #[test]
 fn test_encoding() {
     let rng = &mut rand::thread_rng();
+    test_encode_all_formats::<ReprConv<proto::admin::Admin>>(rng);
     test_encode_all_formats::<ReprConv<proto::alerts::Alerts>>(rng);
     test_encode_all_formats::<ReprConv<proto::api::Web3JsonRpc>>(rng);
     test_encode_all_formats::<ReprConv<proto::api::ContractVerificationApi>>(rng);
@@ -36,6 +46,7 @@ fn test_encoding() {
     test_encode_all_formats::<
         ReprConv<proto::fri_witness_vector_generator::FriWitnessVectorGenerator>,
     >(rng);
+    test_encode_all_formats::<ReprConv<proto::genesis::Genesis>>(rng);
     test_encode_all_formats::<ReprConv<proto::house_keeper::HouseKeeper>>(rng);
     test_encode_all_formats::<ReprConv<proto::object_store::ObjectStore>>(rng);
     test_encode_all_formats::<ReprConv<proto::proof_data_handler::ProofDataHandler>>(rng);
@@ -43,3 +54,107 @@ fn test_encoding() {
     test_encode_all_formats::<ReprConv<proto::witness_generator::WitnessGenerator>>(rng);
     test_encode_all_formats::<ReprConv<proto::observability::Observability>>(rng);
 }
+
+/// Checks that all `StateKeeperConfig` fields are mapped by `proto::StateKeeper`. Unlike `test_encoding()`,
+/// this test uses many samples, so that all enum variants (e.g., fee model versions and commit data generator modes)
+/// and both set and unset optional fields are covered. Fee account address and base system contract hashes
+/// are round-tripped as well.
+#[test]
+fn state_keeper_config_roundtrip() {
+    let rng = &mut rand::thread_rng();
+    for required_only in [false, true] {
+        let dist = EncodeDist {
+            required_only,
+            decimal_fractions: false,
+        };
+        for _ in 0..100 {
+            let config: StateKeeperConfig = dist.sample(rng);
+            let restored = proto::chain::StateKeeper::build(&config).read().unwrap();
+            assert_eq!(restored, config);
+        }
+    }
+}
+
+#[test]
+fn fee_model_version_roundtrip() {
+    let rng = &mut rand::thread_rng();
+    let mut config: StateKeeperConfig = EncodeDist {
+        required_only: false,
+        decimal_fractions: false,
+    }
+    .sample(rng);
+    for version in [
+        FeeModelVersion::V1,
+        FeeModelVersion::V2,
+        FeeModelVersion::V3,
+    ] {
+        config.fee_model_version = version;
+        let restored = proto::chain::StateKeeper::build(&config).read().unwrap();
+        assert_eq!(restored.fee_model_version, version);
+    }
+}
+
+#[test]
+fn unknown_fee_model_version() {
+    let rng = &mut rand::thread_rng();
+    let config: StateKeeperConfig = EncodeDist {
+        required_only: false,
+        decimal_fractions: false,
+    }
+    .sample(rng);
+    let mut proto = proto::chain::StateKeeper::build(&config);
+    proto.fee_model_version = Some(42);
+    let err = format!("{:#}", proto.read().unwrap_err());
+    assert!(err.starts_with("fee_model_version"), "{err}");
+}
+
+#[test]
+fn zero_trace_call_concurrency_limit_is_rejected() {
+    let rng = &mut rand::thread_rng();
+    let config: Web3JsonRpcConfig = EncodeDist {
+        required_only: false,
+        decimal_fractions: false,
+    }
+    .sample(rng);
+    let mut proto = proto::api::Web3JsonRpc::build(&config);
+    proto.trace_call_concurrency_limit = Some(0);
+    let err = format!("{:#}", proto.read().unwrap_err());
+    assert!(err.starts_with("trace_call_concurrency_limit"), "{err}");
+}
+
+/// Checks that errors for missing mempool config fields name the full path to the field.
+#[test]
+fn missing_mempool_config_fields() {
+    type ClearField = fn(&mut proto::chain::Mempool);
+    let cases: [(&str, ClearField); 6] = [
+        ("mempool.sync_interval_ms", |proto| {
+            proto.sync_interval_ms = None
+        }),
+        ("mempool.sync_batch_size", |proto| {
+            proto.sync_batch_size = None
+        }),
+        ("mempool.capacity", |proto| proto.capacity = None),
+        ("mempool.stuck_tx_timeout", |proto| {
+            proto.stuck_tx_timeout = None
+        }),
+        ("mempool.remove_stuck_txs", |proto| {
+            proto.remove_stuck_txs = None
+        }),
+        ("mempool.delay_interval", |proto| {
+            proto.delay_interval = None
+        }),
+    ];
+
+    let rng = &mut rand::thread_rng();
+    let config: MempoolConfig = EncodeDist {
+        required_only: false,
+        decimal_fractions: false,
+    }
+    .sample(rng);
+    for (path, clear_field) in cases {
+        let mut proto = proto::chain::Mempool::build(&config);
+        clear_field(&mut proto);
+        let err = format!("{:#}", proto.read().unwrap_err());
+        assert!(err.starts_with(path), "{path}: {err}");
+    }
+}
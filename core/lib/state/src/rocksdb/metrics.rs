@@ -2,7 +2,9 @@
 
 use std::time::Duration;
 
-use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit};
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit,
+};
 
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "server_state_keeper_secondary_storage")]
@@ -14,6 +16,10 @@ pub(super) struct RocksdbStorageMetrics {
     pub lag: Gauge<u64>,
     /// Estimated number of entries in the secondary storage.
     pub size: Gauge<u64>,
+    /// Estimated progress of the enum index migration as a fraction of the processed key space (from 0 to 1).
+    pub enum_index_migration_progress: Gauge<f64>,
+    /// Number of keys processed by the enum index migration.
+    pub enum_index_migration_keys: Counter,
 }
 
 #[vise::register]
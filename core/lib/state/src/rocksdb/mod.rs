@@ -31,7 +31,7 @@ use anyhow::Context as _;
 use itertools::{Either, Itertools};
 use tokio::sync::watch;
 use zksync_dal::{Connection, Core, CoreDal};
-use zksync_storage::{db::NamedColumnFamily, RocksDB};
+use zksync_storage::{db::NamedColumnFamily, RocksDB, RocksDBOptions};
 use zksync_types::{L1BatchNumber, StorageKey, StorageValue, H256, U256};
 use zksync_utils::{h256_to_u256, u256_to_h256};
 
@@ -224,15 +224,29 @@ impl RocksdbStorage {
     ///
     /// Propagates RocksDB I/O errors.
     pub async fn builder(path: &Path) -> anyhow::Result<RocksdbStorageBuilder> {
-        Self::new(path.to_path_buf())
+        Self::builder_with_options(path, RocksDBOptions::default()).await
+    }
+
+    /// Creates a new storage builder with the provided RocksDB `path` and custom RocksDB `options`
+    /// (e.g., to tune write throughput vs memory usage).
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB I/O errors.
+    pub async fn builder_with_options(
+        path: &Path,
+        options: RocksDBOptions,
+    ) -> anyhow::Result<RocksdbStorageBuilder> {
+        Self::new(path.to_path_buf(), options)
             .await
             .map(RocksdbStorageBuilder)
     }
 
-    async fn new(path: PathBuf) -> anyhow::Result<Self> {
+    async fn new(path: PathBuf, options: RocksDBOptions) -> anyhow::Result<Self> {
         tokio::task::spawn_blocking(move || {
             Ok(Self {
-                db: RocksDB::new(&path).context("failed initializing state keeper RocksDB")?,
+                db: RocksDB::with_options(&path, options)
+                    .context("failed initializing state keeper RocksDB")?,
                 pending_patch: InMemoryStorage::default(),
                 enum_index_migration_chunk_size: 100,
                 #[cfg(test)]
@@ -415,7 +429,7 @@ impl RocksdbStorage {
         let key_count = keys.len();
 
         let db = self.db.clone();
-        tokio::task::spawn_blocking(move || {
+        let next_cursor = tokio::task::spawn_blocking(move || {
             let mut write_batch = db.new_write_batch();
             for (key, value) in keys.iter().zip(values) {
                 let index = enum_indices_and_batches[key].1;
@@ -430,13 +444,14 @@ impl RocksdbStorage {
                 .last()
                 .and_then(|last_key| h256_to_u256(*last_key).checked_add(U256::one()))
                 .map(u256_to_h256);
-            match (next_key, keys.len()) {
+            let next_cursor = match (next_key, keys.len()) {
                 (Some(next_key), keys_len) if keys_len == enum_index_migration_chunk_size => {
                     write_batch.put_cf(
                         StateKeeperColumnFamily::State,
                         Self::ENUM_INDEX_MIGRATION_CURSOR,
                         next_key.as_bytes(),
                     );
+                    Some(next_key)
                 }
                 _ => {
                     write_batch.put_cf(
@@ -445,21 +460,38 @@ impl RocksdbStorage {
                         &[],
                     );
                     tracing::info!("RocksDB enum index migration finished");
+                    None
                 }
-            }
+            };
             db.write(write_batch)
-                .context("failed saving enum indices to RocksDB")
+                .context("failed saving enum indices to RocksDB")?;
+            anyhow::Ok(next_cursor)
         })
         .await
         .context("panicked while saving enum indices to RocksDB")??;
 
+        let progress = Self::enum_index_migration_progress(next_cursor);
+        METRICS.enum_index_migration_keys.inc_by(key_count as u64);
+        METRICS.enum_index_migration_progress.set(progress);
         tracing::info!(
-            "RocksDB enum index migration chunk took {:?}, migrated {key_count} keys",
-            started_at.elapsed()
+            "RocksDB enum index migration chunk took {:?}, migrated {key_count} keys; estimated progress: {:.1}%",
+            started_at.elapsed(),
+            progress * 100.0
         );
         Ok(())
     }
 
+    /// Estimates the enum index migration progress (a fraction from 0 to 1) based on the migration cursor.
+    /// Since keys are hashes, they are distributed uniformly across the key space, so the fraction
+    /// of the key space preceding the cursor is a reasonable estimate.
+    fn enum_index_migration_progress(cursor: Option<H256>) -> f64 {
+        let Some(cursor) = cursor else {
+            return 1.0; // The migration is finished
+        };
+        let cursor_prefix: [u8; 8] = cursor.as_bytes()[..8].try_into().unwrap();
+        u64::from_be_bytes(cursor_prefix) as f64 / u64::MAX as f64
+    }
+
     fn read_value_inner(&self, key: &StorageKey) -> Option<StorageValue> {
         Self::read_state_value(&self.db, key.hashed_key()).map(|state_value| state_value.value)
     }
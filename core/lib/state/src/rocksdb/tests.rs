@@ -41,7 +41,9 @@ impl Default for RocksdbStorageEventListener {
 #[tokio::test]
 async fn rocksdb_storage_basics() {
     let dir = TempDir::new().expect("cannot create temporary dir for state keeper");
-    let mut storage = RocksdbStorage::new(dir.path().into()).await.unwrap();
+    let mut storage = RocksdbStorage::new(dir.path().into(), RocksDBOptions::default())
+        .await
+        .unwrap();
     let mut storage_logs: HashMap<_, _> = gen_storage_logs(0..20)
         .into_iter()
         .map(|log| (log.key, log.value))
@@ -303,6 +305,11 @@ async fn rocksdb_enum_index_migration() {
         let expected_index = enum_indices[&key.hashed_key()];
         assert_eq!(storage.get_enumeration_index(key), Some(expected_index));
     }
+    // Check that the migration progress is estimated based on the migration cursor.
+    let cursor = storage.enum_migration_start_from().await;
+    assert!(cursor.unwrap() > ordered_keys_to_migrate[9].hashed_key());
+    let progress = RocksdbStorage::enum_index_migration_progress(cursor);
+    assert!(progress > 0.0 && progress < 1.0, "{progress}");
     let non_migrated_state_value =
         RocksdbStorage::read_state_value(&storage.db, ordered_keys_to_migrate[10].hashed_key())
             .unwrap();
@@ -323,6 +330,10 @@ async fn rocksdb_enum_index_migration() {
     storage.save_missing_enum_indices(&mut conn).await.unwrap();
     let start_from = storage.enum_migration_start_from().await;
     assert!(start_from.is_none());
+    assert_eq!(
+        RocksdbStorage::enum_index_migration_progress(start_from),
+        1.0
+    );
 }
 
 #[test_casing(4, [RocksdbStorage::DESIRED_LOG_CHUNK_SIZE, 20, 5, 1])]
@@ -334,7 +345,9 @@ async fn low_level_snapshot_recovery(log_chunk_size: u64) {
         prepare_postgres_for_snapshot_recovery(&mut conn).await;
 
     let dir = TempDir::new().expect("cannot create temporary dir for state keeper");
-    let mut storage = RocksdbStorage::new(dir.path().into()).await.unwrap();
+    let mut storage = RocksdbStorage::new(dir.path().into(), RocksDBOptions::default())
+        .await
+        .unwrap();
     let (_stop_sender, stop_receiver) = watch::channel(false);
     let next_l1_batch = storage
         .ensure_ready(&mut conn, log_chunk_size, &stop_receiver)
@@ -460,7 +473,9 @@ async fn recovery_fault_tolerance() {
     let log_chunk_size = storage_logs.len() as u64 / 5;
 
     let dir = TempDir::new().expect("cannot create temporary dir for state keeper");
-    let mut storage = RocksdbStorage::new(dir.path().into()).await.unwrap();
+    let mut storage = RocksdbStorage::new(dir.path().into(), RocksDBOptions::default())
+        .await
+        .unwrap();
     let (stop_sender, stop_receiver) = watch::channel(false);
     let mut synced_chunk_count = 0_u64;
     storage.listener.on_logs_chunk_recovered = Box::new(move |chunk_id| {
@@ -480,7 +495,9 @@ async fn recovery_fault_tolerance() {
 
     // Resume recovery and check that no chunks are recovered twice.
     let (_stop_sender, stop_receiver) = watch::channel(false);
-    let mut storage = RocksdbStorage::new(dir.path().into()).await.unwrap();
+    let mut storage = RocksdbStorage::new(dir.path().into(), RocksDBOptions::default())
+        .await
+        .unwrap();
     storage.listener.on_logs_chunk_recovered = Box::new(|chunk_id| {
         assert!(chunk_id >= 2);
     });
@@ -12,11 +12,12 @@ use std::{
 };
 
 use rocksdb::{
-    properties, BlockBasedOptions, Cache, ColumnFamily, ColumnFamilyDescriptor, DBPinnableSlice,
-    Direction, IteratorMode, Options, PrefixRange, ReadOptions, WriteOptions, DB,
+    checkpoint::Checkpoint, properties, BlockBasedOptions, Cache, ColumnFamily,
+    ColumnFamilyDescriptor, DBCompactionStyle, DBPinnableSlice, Direction, IteratorMode, Options,
+    PrefixRange, ReadOptions, WriteOptions, DB,
 };
 
-use crate::metrics::{RocksdbLabels, RocksdbSizeMetrics, METRICS};
+use crate::metrics::{DbLabel, RocksdbLabels, RocksdbSizeMetrics, METRICS};
 
 /// Number of active RocksDB instances used to determine if it's safe to exit current process.
 /// Not properly dropped RocksDB instances can lead to DB corruption.
@@ -85,15 +86,160 @@ impl fmt::Debug for RocksDBCaches {
 }
 
 impl RocksDBCaches {
-    fn new(capacity: Option<usize>) -> Self {
-        let shared = capacity.map(Cache::new_lru_cache);
+    fn new(options: &RocksDBOptions) -> Self {
+        let shared = if let Some(budget) = &options.memory_budget {
+            Some(budget.inner.block_cache.clone())
+        } else {
+            options.block_cache_capacity.map(Cache::new_lru_cache)
+        };
         Self { shared }
     }
 }
 
+/// Memory budget shared among several RocksDB instances, so that their total memory usage is bounded
+/// regardless of per-instance settings.
+///
+/// Half of the budget is allocated to the LRU block cache shared among all instances using the budget;
+/// index and filter blocks are accounted in this cache as well. The remaining memory is split evenly among
+/// instances and is used to cap the total size of memtables in each instance.
+///
+/// The budget is cheaply cloneable; clones refer to the same budget.
+#[derive(Debug, Clone)]
+pub struct RocksDBMemoryBudget {
+    inner: Arc<MemoryBudgetInner>,
+}
+
+struct MemoryBudgetInner {
+    capacity: usize,
+    block_cache_capacity: usize,
+    write_buffer_capacity_per_instance: usize,
+    block_cache: Cache,
+}
+
+impl fmt::Debug for MemoryBudgetInner {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("MemoryBudgetInner")
+            .field("capacity", &self.capacity)
+            .field("block_cache_capacity", &self.block_cache_capacity)
+            .field(
+                "write_buffer_capacity_per_instance",
+                &self.write_buffer_capacity_per_instance,
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+/// Budgets are compared by identity; clones of the same budget are equal.
+impl PartialEq for RocksDBMemoryBudget {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for RocksDBMemoryBudget {}
+
+impl RocksDBMemoryBudget {
+    /// Creates a budget with the specified total byte `capacity` that will be shared among
+    /// `instance_count` RocksDB instances.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `instance_count` is zero.
+    pub fn new(capacity: usize, instance_count: usize) -> Self {
+        assert!(
+            instance_count > 0,
+            "RocksDB instance count must be positive"
+        );
+        let block_cache_capacity = capacity / 2;
+        let write_buffer_capacity_per_instance = (capacity - block_cache_capacity) / instance_count;
+        Self {
+            inner: Arc::new(MemoryBudgetInner {
+                capacity,
+                block_cache_capacity,
+                write_buffer_capacity_per_instance,
+                block_cache: Cache::new_lru_cache(block_cache_capacity),
+            }),
+        }
+    }
+
+    /// Returns the total byte capacity of this budget.
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity
+    }
+
+    /// Returns the byte capacity of the block cache shared among instances.
+    pub fn block_cache_capacity(&self) -> usize {
+        self.inner.block_cache_capacity
+    }
+
+    /// Returns the maximum total byte size of memtables for a single instance.
+    pub fn write_buffer_capacity_per_instance(&self) -> usize {
+        self.inner.write_buffer_capacity_per_instance
+    }
+}
+
+/// Cumulative IO statistics for a RocksDB instance since it was opened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RocksDBIoStats {
+    /// Number of bytes read by point lookups.
+    pub bytes_read: u64,
+    /// Number of bytes written by write operations.
+    pub bytes_written: u64,
+    /// Number of bytes read by compactions.
+    pub compaction_bytes_read: u64,
+    /// Number of bytes written by compactions.
+    pub compaction_bytes_written: u64,
+    /// Number of bytes written by memtable flushes.
+    pub flush_bytes_written: u64,
+    /// Total duration of write stalls.
+    pub write_stall_duration: Duration,
+}
+
+impl RocksDBIoStats {
+    /// Parses stats from the RocksDB statistics dump. Ticker values in the dump have the
+    /// `rocksdb.bytes.read COUNT : 123` format.
+    fn parse(raw_stats: &str) -> Self {
+        let mut stats = Self::default();
+        for line in raw_stats.lines() {
+            let mut parts = line.split_whitespace();
+            let (Some(name), Some("COUNT"), Some(":"), Some(value)) =
+                (parts.next(), parts.next(), parts.next(), parts.next())
+            else {
+                continue;
+            };
+            let Ok(value) = value.parse::<u64>() else {
+                continue;
+            };
+            match name {
+                "rocksdb.bytes.read" => stats.bytes_read = value,
+                "rocksdb.bytes.written" => stats.bytes_written = value,
+                "rocksdb.compact.read.bytes" => stats.compaction_bytes_read = value,
+                "rocksdb.compact.write.bytes" => stats.compaction_bytes_written = value,
+                "rocksdb.flush.write.bytes" => stats.flush_bytes_written = value,
+                "rocksdb.stall.micros" => stats.write_stall_duration = Duration::from_micros(value),
+                _ => { /* other tickers are not reported */ }
+            }
+        }
+        stats
+    }
+}
+
+/// DB-wide options retained to access statistics collected for the DB.
+struct RocksDBStatistics(Options);
+
+impl fmt::Debug for RocksDBStatistics {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("RocksDBStatistics")
+            .finish_non_exhaustive()
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct RocksDBInner {
     db: DB,
+    db_statistics: RocksDBStatistics,
     db_name: &'static str,
     cf_names: HashSet<&'static str>,
     _registry_entry: RegistryEntry,
@@ -104,6 +250,16 @@ pub(crate) struct RocksDBInner {
 
 impl RocksDBInner {
     pub(crate) fn collect_metrics(&self, metrics: &RocksdbSizeMetrics) {
+        if let Some(io_stats) = self.io_stats() {
+            let db_label = DbLabel::from(self.db_name);
+            metrics.bytes_read[&db_label].inc_by(io_stats.bytes_read);
+            metrics.bytes_written[&db_label].inc_by(io_stats.bytes_written);
+            metrics.compaction_bytes_read[&db_label].inc_by(io_stats.compaction_bytes_read);
+            metrics.compaction_bytes_written[&db_label].inc_by(io_stats.compaction_bytes_written);
+            metrics.flush_bytes_written[&db_label].inc_by(io_stats.flush_bytes_written);
+            metrics.write_stall_duration[&db_label].set(io_stats.write_stall_duration);
+        }
+
         for &cf_name in &self.cf_names {
             let cf = self.db.cf_handle(cf_name).unwrap();
             // ^ `unwrap()` is safe (CF existence is checked during DB initialization)
@@ -160,6 +316,11 @@ impl RocksDBInner {
         }
     }
 
+    fn io_stats(&self) -> Option<RocksDBIoStats> {
+        let raw_stats = self.db_statistics.0.get_statistics()?;
+        Some(RocksDBIoStats::parse(&raw_stats))
+    }
+
     fn int_property(&self, cf: &ColumnFamily, name: &CStr) -> Option<u64> {
         let property = self.db.property_int_value_cf(cf, name);
         let property = property.unwrap_or_else(|err| {
@@ -262,7 +423,7 @@ impl StalledWritesRetries {
 }
 
 /// [`RocksDB`] options.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct RocksDBOptions {
     /// Byte capacity of the block cache (the main RocksDB cache for reads). If not set, default RocksDB
     /// cache options will be used.
@@ -277,6 +438,31 @@ pub struct RocksDBOptions {
     pub stalled_writes_retries: StalledWritesRetries,
     /// Number of open files that can be used by the DB. Default is None, for no limit.
     pub max_open_files: Option<NonZeroU32>,
+    /// Byte size of a single memtable (aka write buffer) for all CFs. Takes precedence over the memtable size
+    /// derived from `large_memtable_capacity`. If not set, the default RocksDB value is used.
+    pub write_buffer_size: Option<usize>,
+    /// Maximum number of concurrent background jobs (compactions and flushes). If not set, the number
+    /// is chosen based on the number of CPUs.
+    pub max_background_jobs: Option<NonZeroU32>,
+    /// Compaction style for all CFs. If not set, level-style compaction is used.
+    pub compaction_style: Option<CompactionStyle>,
+    /// Memory budget shared with other RocksDB instances. If set, the block cache from the budget is used
+    /// instead of the one configured with `block_cache_capacity`, and the total size of memtables
+    /// in the DB is capped according to the budget.
+    pub memory_budget: Option<RocksDBMemoryBudget>,
+    /// Whether to collect RocksDB statistics (e.g., IO metrics reported for the DB). Statistics have
+    /// a non-negligible overhead on reads and writes, so they are disabled by default.
+    pub collect_statistics: bool,
+}
+
+/// Compaction style for RocksDB column families. FIFO compaction is not supported since it drops the oldest data
+/// once the DB exceeds a size limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompactionStyle {
+    /// Level-style compaction (the default one).
+    Level,
+    /// Universal compaction.
+    Universal,
 }
 
 impl Default for RocksDBOptions {
@@ -286,6 +472,11 @@ impl Default for RocksDBOptions {
             large_memtable_capacity: None,
             stalled_writes_retries: StalledWritesRetries::new(Duration::from_secs(10)),
             max_open_files: None,
+            write_buffer_size: None,
+            max_background_jobs: None,
+            compaction_style: None,
+            memory_budget: None,
+            collect_statistics: false,
         }
     }
 }
@@ -307,7 +498,7 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
     }
 
     pub fn with_options(path: &Path, options: RocksDBOptions) -> Result<Self, rocksdb::Error> {
-        let caches = RocksDBCaches::new(options.block_cache_capacity);
+        let caches = RocksDBCaches::new(&options);
         let mut db_options = Self::rocksdb_options(None, None);
         let max_open_files = if let Some(non_zero) = options.max_open_files {
             i32::try_from(non_zero.get()).unwrap_or(i32::MAX)
@@ -315,6 +506,18 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
             -1
         };
         db_options.set_max_open_files(max_open_files);
+        if let Some(max_background_jobs) = options.max_background_jobs {
+            db_options.set_max_background_jobs(
+                i32::try_from(max_background_jobs.get()).unwrap_or(i32::MAX),
+            );
+        }
+        if let Some(budget) = &options.memory_budget {
+            db_options.set_db_write_buffer_size(budget.write_buffer_capacity_per_instance());
+        }
+        if options.collect_statistics {
+            // Collect statistics so that IO metrics can be reported for the DB.
+            db_options.enable_statistics();
+        }
         let existing_cfs = DB::list_cf(&db_options, path).unwrap_or_else(|err| {
             tracing::warn!(
                 "Failed getting column families for RocksDB `{}` at `{}`, assuming CFs are empty; {err}",
@@ -359,14 +562,29 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
             if let Some(cache) = &caches.shared {
                 block_based_options.set_block_cache(cache);
             }
+            if options.memory_budget.is_some() {
+                // Account index and filter blocks in the shared cache, so that they are bounded by the budget.
+                block_based_options.set_cache_index_and_filter_blocks(true);
+            }
             let memtable_capacity = options.large_memtable_capacity.filter(|_| requires_tuning);
-            let cf_options = Self::rocksdb_options(memtable_capacity, Some(block_based_options));
+            let mut cf_options =
+                Self::rocksdb_options(memtable_capacity, Some(block_based_options));
+            if let Some(write_buffer_size) = options.write_buffer_size {
+                cf_options.set_write_buffer_size(write_buffer_size);
+            }
+            if let Some(compaction_style) = options.compaction_style {
+                cf_options.set_compaction_style(match compaction_style {
+                    CompactionStyle::Level => DBCompactionStyle::Level,
+                    CompactionStyle::Universal => DBCompactionStyle::Universal,
+                });
+            }
             ColumnFamilyDescriptor::new(cf_name, cf_options)
         });
 
         let db = DB::open_cf_descriptors(&db_options, path, cfs)?;
         let inner = Arc::new(RocksDBInner {
             db,
+            db_statistics: RocksDBStatistics(db_options),
             db_name: CF::DB_NAME,
             cf_names,
             _registry_entry: RegistryEntry::new(),
@@ -432,6 +650,31 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
             .unwrap_or(0)
     }
 
+    /// Returns cumulative IO statistics for this DB, or `None` if statistics are unavailable.
+    pub fn io_stats(&self) -> Option<RocksDBIoStats> {
+        self.inner.io_stats()
+    }
+
+    /// Triggers manual compaction of all column families in the DB. This is a blocking operation
+    /// that may take significant time for large DBs.
+    pub fn compact(&self) {
+        for &cf in CF::ALL {
+            let cf = self.column_family(cf);
+            self.inner
+                .db
+                .compact_range_cf(cf, None::<&[u8]>, None::<&[u8]>);
+        }
+    }
+
+    /// Creates a consistent checkpoint of the DB at the specified `path`, which must not exist.
+    /// Immutable DB files are hard-linked if `path` is on the same filesystem as the DB, so checkpoints
+    /// are cheap. The checkpoint can be opened as a separate DB instance.
+    ///
+    /// This method is blocking and should be wrapped in `spawn_blocking(_)` if run in the async context.
+    pub fn create_checkpoint(&self, path: &Path) -> Result<(), rocksdb::Error> {
+        Checkpoint::new(&self.inner.db)?.create_checkpoint(path)
+    }
+
     pub fn multi_get<K, I>(&self, keys: I) -> Vec<Result<Option<Vec<u8>>, rocksdb::Error>>
     where
         K: AsRef<[u8]>,
@@ -605,6 +848,8 @@ impl Drop for RegistryEntry {
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
+
     use tempfile::TempDir;
 
     use super::*;
@@ -742,4 +987,168 @@ mod tests {
             .unwrap();
         assert_eq!(value, b"value2");
     }
+
+    #[test]
+    fn parsing_io_stats() {
+        let raw_stats = "\
+            rocksdb.block.cache.miss COUNT : 3\n\
+            rocksdb.bytes.written COUNT : 1024\n\
+            rocksdb.bytes.read COUNT : 512\n\
+            rocksdb.compact.read.bytes COUNT : 2048\n\
+            rocksdb.compact.write.bytes COUNT : 4096\n\
+            rocksdb.flush.write.bytes COUNT : 8192\n\
+            rocksdb.stall.micros COUNT : 1500\n\
+            rocksdb.db.get.micros P50 : 1.000000 P95 : 2.000000 COUNT : 10 SUM : 15\n";
+        let stats = RocksDBIoStats::parse(raw_stats);
+        assert_eq!(
+            stats,
+            RocksDBIoStats {
+                bytes_read: 512,
+                bytes_written: 1_024,
+                compaction_bytes_read: 2_048,
+                compaction_bytes_written: 4_096,
+                flush_bytes_written: 8_192,
+                write_stall_duration: Duration::from_micros(1_500),
+            }
+        );
+    }
+
+    #[test]
+    fn collecting_io_stats() {
+        let temp_dir = TempDir::new().unwrap();
+        let options = RocksDBOptions {
+            collect_statistics: true,
+            ..RocksDBOptions::default()
+        };
+        let db = RocksDB::<JunkColumnFamily>::with_options(temp_dir.path(), options)
+            .unwrap()
+            .with_sync_writes();
+        let mut batch = db.new_write_batch();
+        batch.put_cf(JunkColumnFamily, b"test", b"value");
+        db.write(batch).unwrap();
+        let value = db.get_cf(JunkColumnFamily, b"test").unwrap();
+        assert_eq!(value.unwrap(), b"value");
+
+        let stats = db.io_stats().expect("no stats");
+        assert!(stats.bytes_written > 0, "{stats:?}");
+        assert!(stats.bytes_read > 0, "{stats:?}");
+    }
+
+    #[test]
+    fn io_stats_are_not_collected_by_default() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = RocksDB::<JunkColumnFamily>::new(temp_dir.path()).unwrap();
+        let mut batch = db.new_write_batch();
+        batch.put_cf(JunkColumnFamily, b"test", b"value");
+        db.write(batch).unwrap();
+
+        assert!(db.io_stats().is_none());
+    }
+
+    #[test]
+    fn memory_budget_is_shared_among_instances() {
+        let budget = RocksDBMemoryBudget::new(64 << 20, 2);
+        assert_eq!(budget.block_cache_capacity(), 32 << 20);
+        assert_eq!(budget.write_buffer_capacity_per_instance(), 16 << 20);
+
+        let options = RocksDBOptions {
+            block_cache_capacity: Some(128 << 20), // should be overridden by the budget
+            memory_budget: Some(budget.clone()),
+            ..RocksDBOptions::default()
+        };
+        let temp_dirs = [TempDir::new().unwrap(), TempDir::new().unwrap()];
+        let dbs: Vec<_> = temp_dirs
+            .iter()
+            .map(|dir| {
+                RocksDB::<JunkColumnFamily>::with_options(dir.path(), options.clone())
+                    .unwrap()
+                    .with_sync_writes()
+            })
+            .collect();
+
+        for (db, dir) in dbs.iter().zip(&temp_dirs) {
+            let cf = db.column_family(JunkColumnFamily);
+            let cache_capacity = db.inner.int_property(cf, properties::BLOCK_CACHE_CAPACITY);
+            assert_eq!(cache_capacity, Some(32 << 20));
+            let persisted_options = read_options_file(dir.path());
+            assert!(
+                persisted_options.contains("db_write_buffer_size=16777216"),
+                "{persisted_options}"
+            );
+        }
+
+        // Populate the block cache using the first instance and check that the second instance observes it.
+        let mut batch = dbs[0].new_write_batch();
+        batch.put_cf(JunkColumnFamily, b"test", b"value");
+        dbs[0].write(batch).unwrap();
+        dbs[0].compact();
+        let value = dbs[0].get_cf(JunkColumnFamily, b"test").unwrap();
+        assert_eq!(value.unwrap(), b"value");
+
+        let cf = dbs[1].column_family(JunkColumnFamily);
+        let cache_usage = dbs[1].inner.int_property(cf, properties::BLOCK_CACHE_USAGE);
+        assert!(cache_usage.unwrap() > 0, "{cache_usage:?}");
+    }
+
+    fn read_options_file(db_path: &Path) -> String {
+        // RocksDB persists effective options in an `OPTIONS-*` file on opening the DB.
+        let options_file = fs::read_dir(db_path)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| {
+                let file_name = path.file_name().unwrap().to_str().unwrap();
+                file_name.starts_with("OPTIONS-")
+            })
+            .expect("no options file");
+        fs::read_to_string(options_file).unwrap()
+    }
+
+    #[test]
+    fn creating_checkpoint() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = RocksDB::<JunkColumnFamily>::new(&temp_dir.path().join("db"))
+            .unwrap()
+            .with_sync_writes();
+        let mut batch = db.new_write_batch();
+        batch.put_cf(JunkColumnFamily, b"test", b"value");
+        db.write(batch).unwrap();
+
+        let checkpoint_path = temp_dir.path().join("checkpoint");
+        db.create_checkpoint(&checkpoint_path).unwrap();
+        // Changes after the checkpoint must not be visible in it.
+        let mut batch = db.new_write_batch();
+        batch.put_cf(JunkColumnFamily, b"test", b"new_value");
+        db.write(batch).unwrap();
+        drop(db);
+
+        let checkpoint = RocksDB::<JunkColumnFamily>::new(&checkpoint_path).unwrap();
+        let value = checkpoint.get_cf(JunkColumnFamily, b"test").unwrap();
+        assert_eq!(value.unwrap(), b"value");
+    }
+
+    #[test]
+    fn tuning_options_are_applied() {
+        let temp_dir = TempDir::new().unwrap();
+        let options = RocksDBOptions {
+            write_buffer_size: Some(32 << 20),
+            max_background_jobs: NonZeroU32::new(3),
+            compaction_style: Some(CompactionStyle::Universal),
+            ..RocksDBOptions::default()
+        };
+        let _db = RocksDB::<JunkColumnFamily>::with_options(temp_dir.path(), options).unwrap();
+
+        let persisted_options = read_options_file(temp_dir.path());
+        assert!(
+            persisted_options.contains("max_background_jobs=3"),
+            "{persisted_options}"
+        );
+        assert!(
+            persisted_options.contains("write_buffer_size=33554432"),
+            "{persisted_options}"
+        );
+        assert!(
+            persisted_options.contains("compaction_style=kCompactionStyleUniversal"),
+            "{persisted_options}"
+        );
+    }
 }
@@ -1,5 +1,8 @@
 pub mod db;
 mod metrics;
 
-pub use db::{RocksDB, RocksDBOptions, StalledWritesRetries};
+pub use db::{
+    CompactionStyle, RocksDB, RocksDBIoStats, RocksDBMemoryBudget, RocksDBOptions,
+    StalledWritesRetries,
+};
 pub use rocksdb;
@@ -12,7 +12,7 @@ use vise::{Buckets, Collector, Counter, EncodeLabelSet, Family, Gauge, Histogram
 use crate::db::RocksDBInner;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet)]
-struct DbLabel {
+pub(crate) struct DbLabel {
     db: &'static str,
 }
 
@@ -102,6 +102,25 @@ pub(crate) struct RocksdbSizeMetrics {
     pub block_cache_size: Family<RocksdbLabels, Gauge<u64>>,
     /// Total size of index and Bloom filters in the column family of a RocksDB instance.
     pub index_and_filters_size: Family<RocksdbLabels, Gauge<u64>>,
+
+    /// Number of bytes read by point lookups from a RocksDB instance since it was opened.
+    #[metrics(unit = Unit::Bytes)]
+    pub bytes_read: Family<DbLabel, Counter>,
+    /// Number of bytes written to a RocksDB instance since it was opened.
+    #[metrics(unit = Unit::Bytes)]
+    pub bytes_written: Family<DbLabel, Counter>,
+    /// Number of bytes read by compactions of a RocksDB instance since it was opened.
+    #[metrics(unit = Unit::Bytes)]
+    pub compaction_bytes_read: Family<DbLabel, Counter>,
+    /// Number of bytes written by compactions of a RocksDB instance since it was opened.
+    #[metrics(unit = Unit::Bytes)]
+    pub compaction_bytes_written: Family<DbLabel, Counter>,
+    /// Number of bytes written by memtable flushes of a RocksDB instance since it was opened.
+    #[metrics(unit = Unit::Bytes)]
+    pub flush_bytes_written: Family<DbLabel, Counter>,
+    /// Total duration of write stalls for a RocksDB instance since it was opened.
+    #[metrics(unit = Unit::Seconds)]
+    pub write_stall_duration: Family<DbLabel, Gauge<Duration>>,
 }
 
 /// Weak refs to DB instances registered using [`RocksdbSizeMetrics::register()`].
@@ -138,3 +157,19 @@ impl RocksdbSizeMetrics {
         metrics
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reporting_stalled_writes() {
+        const DB_NAME: &str = "stalled_writes_test";
+
+        let stalled_writes = &METRICS.write_stalled[&DB_NAME.into()];
+        let initial_count = stalled_writes.get();
+        METRICS.observe_stalled_write(DB_NAME);
+        METRICS.observe_stalled_write(DB_NAME);
+        assert_eq!(stalled_writes.get(), initial_count + 2);
+    }
+}
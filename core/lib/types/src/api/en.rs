@@ -47,3 +47,18 @@ pub struct SyncBlock {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConsensusGenesis(pub serde_json::Value);
+
+/// Synchronization status of an external node, as returned by the `en_syncStatus` method.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    /// Latest miniblock sealed by the node, or `None` if the node hasn't initialized its state yet.
+    pub local_miniblock: Option<MiniblockNumber>,
+    /// Latest miniblock on the main node, or `None` if the node hasn't observed the main node yet.
+    pub main_node_miniblock: Option<MiniblockNumber>,
+    /// Lag of the node behind the main node in miniblocks, or `None` if either of the miniblocks above is unknown.
+    /// If the node is ahead of the main node, the lag is 0.
+    pub lag: Option<u32>,
+    /// Whether the node is considered synced with the main node (i.e., the lag is within a small threshold).
+    pub is_synced: bool,
+}
@@ -3,7 +3,7 @@ use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use strum::Display;
 use zksync_basic_types::{
     web3::types::{Bytes, H160, H256, H64, U256, U64},
-    L1BatchNumber,
+    L1BatchNumber, L1ChainId, L2ChainId,
 };
 use zksync_contracts::BaseSystemContractsHashes;
 
@@ -639,6 +639,31 @@ impl From<Call> for DebugCall {
     }
 }
 
+/// Versions used by the node serving the request. Allows clients to detect protocol upgrade boundaries
+/// without querying the main node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeVersionInfo {
+    /// ID of the protocol version used by the latest miniblock stored by the node.
+    pub last_used_protocol_version: Option<u16>,
+    /// Version of the node binary, if known.
+    pub node_version: Option<String>,
+}
+
+/// Information about a node instance returned by `zks_getNodeInfo`. Allows to inventory a fleet of nodes via RPC.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeInfo {
+    /// Components run by the node, such as `tree` or `http_api`. Empty if the node doesn't report its components.
+    pub components: Vec<String>,
+    /// Version of the node binary, if known.
+    pub node_version: Option<String>,
+    pub l1_chain_id: L1ChainId,
+    pub l2_chain_id: L2ChainId,
+    /// Whether the node runs consensus.
+    pub consensus_enabled: bool,
+}
+
 #[derive(Default, Serialize, Deserialize, Clone, Debug)]
 pub struct ProtocolVersion {
     /// Protocol version ID
@@ -211,8 +211,12 @@ impl Default for FeeModelConfig {
 }
 
 impl FeeModelConfig {
-    pub fn from_state_keeper_config(state_keeper_config: &StateKeeperConfig) -> Self {
-        match state_keeper_config.fee_model_version {
+    /// Creates a fee model config from the state keeper config. Errors if the specified fee model version
+    /// is not supported.
+    pub fn from_state_keeper_config(
+        state_keeper_config: &StateKeeperConfig,
+    ) -> anyhow::Result<Self> {
+        Ok(match state_keeper_config.fee_model_version {
             FeeModelVersion::V1 => Self::V1(FeeModelConfigV1 {
                 minimal_l2_gas_price: state_keeper_config.minimal_l2_gas_price,
             }),
@@ -224,7 +228,8 @@ impl FeeModelConfig {
                 max_gas_per_batch: state_keeper_config.max_gas_per_batch,
                 max_pubdata_per_batch: state_keeper_config.max_pubdata_per_batch,
             }),
-        }
+            FeeModelVersion::V3 => anyhow::bail!("fee model V3 is not supported yet"),
+        })
     }
 }
 
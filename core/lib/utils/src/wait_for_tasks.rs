@@ -1,10 +1,212 @@
-use std::time::Duration;
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
 
 use futures::future;
-use tokio::task::JoinHandle;
+use tokio::{sync::watch, task::JoinHandle};
 
 use crate::panic_extractor::try_extract_panic_message;
 
+/// Policy of restarting a [restartable](TaskPolicy::Restart) task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestartPolicy {
+    max_restarts: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    reset_after: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+            reset_after: Duration::from_secs(300),
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Creates a policy with the specified number of restarts and backoff intervals. The backoff
+    /// is doubled after each restart until it reaches `max_backoff`.
+    pub fn new(max_restarts: usize, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        Self {
+            max_restarts,
+            initial_backoff,
+            max_backoff: max_backoff.max(initial_backoff),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the duration of a task run after which the task is considered to be running stably. If a task fails
+    /// after running at least this long, the restart counter and backoff are reset, so that occasional failures
+    /// during a long node lifetime don't add up to exhausting restarts.
+    pub fn with_reset_after(mut self, reset_after: Duration) -> Self {
+        self.reset_after = reset_after;
+        self
+    }
+}
+
+/// Policy defining how [`ManagedTasks`] handle termination of a task.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TaskPolicy {
+    /// Any termination of the task (a successful one, with an error, or a panic) leads to node shutdown.
+    #[default]
+    Fatal,
+    /// The task may finish successfully without triggering shutdown. Errors and panics are still fatal.
+    AllowedToFinish,
+    /// Errors returned by the task are handled by restarting it with an exponential backoff. Once restarts
+    /// are exhausted, the error is escalated to shutdown. Panics and successful completion are fatal.
+    Restart(RestartPolicy),
+}
+
+/// Tokio task tagged with a name (e.g., the component the task belongs to). The name is used in logs
+/// to identify the task that has terminated.
+#[derive(Debug)]
+pub struct NamedTask {
+    name: &'static str,
+    policy: TaskPolicy,
+    handle: JoinHandle<anyhow::Result<()>>,
+}
+
+impl NamedTask {
+    /// Name used for tasks that were not explicitly named.
+    const UNNAMED: &'static str = "(unnamed)";
+
+    /// Tags the specified task with a name.
+    pub fn new(name: &'static str, handle: JoinHandle<anyhow::Result<()>>) -> Self {
+        Self {
+            name,
+            policy: TaskPolicy::Fatal,
+            handle,
+        }
+    }
+
+    /// Spawns a Tokio task with the specified name.
+    pub fn spawn<F>(name: &'static str, task: F) -> Self
+    where
+        F: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        Self::new(name, tokio::spawn(task))
+    }
+
+    /// Spawns a Tokio task with the specified name and termination policy. `task_factory` is called
+    /// with a clone of `stop_receiver` to create the task future; it is called again each time
+    /// a [restartable](TaskPolicy::Restart) task needs to be restarted. Waiting before a restart is interrupted
+    /// if a stop signal is received.
+    pub fn spawn_with_policy<F, Fut>(
+        name: &'static str,
+        policy: TaskPolicy,
+        stop_receiver: watch::Receiver<bool>,
+        mut task_factory: F,
+    ) -> Self
+    where
+        F: FnMut(watch::Receiver<bool>) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        let handle = match policy {
+            TaskPolicy::Fatal | TaskPolicy::AllowedToFinish => {
+                tokio::spawn(task_factory(stop_receiver))
+            }
+            TaskPolicy::Restart(restart_policy) => tokio::spawn(run_with_restarts(
+                name,
+                restart_policy,
+                stop_receiver,
+                task_factory,
+            )),
+        };
+        Self {
+            name,
+            policy,
+            handle,
+        }
+    }
+
+    /// Allows this task to finish successfully without triggering node shutdown, i.e., changes its policy
+    /// to [`TaskPolicy::AllowedToFinish`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the task is [restartable](TaskPolicy::Restart).
+    pub fn allowed_to_finish(mut self) -> Self {
+        assert!(
+            !matches!(self.policy, TaskPolicy::Restart(_)),
+            "Restartable task `{}` cannot be allowed to finish",
+            self.name
+        );
+        self.policy = TaskPolicy::AllowedToFinish;
+        self
+    }
+
+    /// Returns the name of this task.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the termination policy of this task.
+    pub fn policy(&self) -> TaskPolicy {
+        self.policy
+    }
+
+    /// Checks whether this task has finished.
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+}
+
+/// Runs a task produced by `task_factory`, restarting it on errors according to the `policy`.
+async fn run_with_restarts<F, Fut>(
+    name: &'static str,
+    policy: RestartPolicy,
+    mut stop_receiver: watch::Receiver<bool>,
+    mut task_factory: F,
+) -> anyhow::Result<()>
+where
+    F: FnMut(watch::Receiver<bool>) -> Fut,
+    Fut: Future<Output = anyhow::Result<()>>,
+{
+    let mut backoff = policy.initial_backoff;
+    let mut restarts = 0;
+    loop {
+        let started_at = Instant::now();
+        let result = task_factory(stop_receiver.clone()).await;
+        if result.is_err() && started_at.elapsed() >= policy.reset_after {
+            tracing::info!(
+                "Actor `{name}` failed after running stably for {:?}; resetting its restart counter",
+                started_at.elapsed()
+            );
+            restarts = 0;
+            backoff = policy.initial_backoff;
+        }
+        let err = match result {
+            Ok(()) => return Ok(()),
+            Err(err) if restarts < policy.max_restarts => err,
+            Err(err) => return Err(err.context(format!("exhausted {restarts} restart(s)"))),
+        };
+        restarts += 1;
+        tracing::warn!(
+            "Actor `{name}` failed; restarting it in {backoff:?} (restart {restarts}/{max_restarts}): {err:#}",
+            max_restarts = policy.max_restarts
+        );
+        tokio::select! {
+            () = tokio::time::sleep(backoff) => {}
+            _ = stop_receiver.wait_for(|stop| *stop) => {
+                tracing::info!("Stop signal received, actor `{name}` is not restarted");
+                return Ok(());
+            }
+        }
+        backoff = (backoff * 2).min(policy.max_backoff);
+    }
+}
+
+impl From<JoinHandle<anyhow::Result<()>>> for NamedTask {
+    fn from(handle: JoinHandle<anyhow::Result<()>>) -> Self {
+        Self::new(Self::UNNAMED, handle)
+    }
+}
+
 /// Container for fallible Tokio tasks with ability to track their shutdown.
 ///
 /// The intended usage is [first waiting for a single task](Self::wait_single()) (perhaps in a `select!`
@@ -13,15 +215,15 @@ use crate::panic_extractor::try_extract_panic_message;
 #[must_use = "Tasks should be `complete()`d"]
 #[derive(Debug)]
 pub struct ManagedTasks {
-    task_handles: Vec<JoinHandle<anyhow::Result<()>>>,
+    tasks: Vec<NamedTask>,
     tasks_allowed_to_finish: bool,
 }
 
 impl ManagedTasks {
-    /// Wraps the specified list of Tokio tasks.
-    pub fn new(task_handles: Vec<JoinHandle<anyhow::Result<()>>>) -> Self {
+    /// Wraps the specified list of Tokio tasks. Tasks can be either [named](NamedTask) or plain [`JoinHandle`]s.
+    pub fn new<T: Into<NamedTask>>(tasks: Vec<T>) -> Self {
         Self {
-            task_handles,
+            tasks: tasks.into_iter().map(Into::into).collect(),
             tasks_allowed_to_finish: false,
         }
     }
@@ -33,33 +235,52 @@ impl ManagedTasks {
         self
     }
 
-    /// Waits until a single managed task terminates, no matter the outcome.
+    /// Waits until a single managed task terminates in a way requiring shutdown. Tasks [allowed to finish](TaskPolicy::AllowedToFinish)
+    /// that have finished successfully are removed without returning; if all tasks have finished, this method returns.
     pub async fn wait_single(&mut self) {
-        let (result, completed_index, _) = future::select_all(&mut self.task_handles).await;
-        // Remove the completed task so that it doesn't panic when polling tasks in `Self::complete()`.
-        self.task_handles.swap_remove(completed_index);
-
-        match result {
-            Ok(Ok(())) => {
-                if self.tasks_allowed_to_finish {
-                    tracing::info!("One of the actors finished its run. Finishing execution.");
-                } else {
-                    let err =
-                        "One of the actors finished its run, while it wasn't expected to do it";
+        loop {
+            if self.tasks.is_empty() {
+                tracing::info!("All actors have finished their run. Finishing execution.");
+                return;
+            }
+
+            let handles = self.tasks.iter_mut().map(|task| &mut task.handle);
+            let (result, completed_index, _) = future::select_all(handles).await;
+            // Remove the completed task so that it doesn't panic when polling tasks in `Self::complete()`.
+            let task = self.tasks.swap_remove(completed_index);
+            let name = task.name;
+
+            match result {
+                Ok(Ok(())) if task.policy == TaskPolicy::AllowedToFinish => {
+                    tracing::info!("Actor `{name}` finished its run");
+                    continue;
+                }
+                Ok(Ok(())) => {
+                    if self.tasks_allowed_to_finish {
+                        tracing::info!("Actor `{name}` finished its run. Finishing execution.");
+                    } else {
+                        let err = format!(
+                            "Shutting down because actor `{name}` finished its run, while it wasn't expected to do it"
+                        );
+                        tracing::error!("{err}");
+                        vlog::capture_message(&err, vlog::AlertLevel::Warning);
+                    }
+                }
+                Ok(Err(err)) => {
+                    let err = format!(
+                        "Shutting down because actor `{name}` unexpectedly finished with error: {err:#}"
+                    );
                     tracing::error!("{err}");
-                    vlog::capture_message(err, vlog::AlertLevel::Warning);
+                    vlog::capture_message(&err, vlog::AlertLevel::Warning);
+                }
+                Err(error) => {
+                    let panic_message = try_extract_panic_message(error);
+                    tracing::info!(
+                        "Shutting down because actor `{name}` panicked: {panic_message}"
+                    );
                 }
             }
-            Ok(Err(err)) => {
-                let err =
-                    format!("One of the tokio actors unexpectedly finished with error: {err:#}");
-                tracing::error!("{err}");
-                vlog::capture_message(&err, vlog::AlertLevel::Warning);
-            }
-            Err(error) => {
-                let panic_message = try_extract_panic_message(error);
-                tracing::info!("One of the tokio actors panicked: {panic_message}");
-            }
+            return;
         }
     }
 
@@ -74,13 +295,14 @@ impl ManagedTasks {
     }
 
     async fn complete_inner(self) {
-        let futures = self.task_handles.into_iter().map(|fut| async move {
-            match fut.await {
+        let futures = self.tasks.into_iter().map(|task| async move {
+            let name = task.name;
+            match task.handle.await {
                 Ok(Ok(())) => { /* do nothing */ }
                 Ok(Err(err)) => {
-                    tracing::error!("One of actors returned an error during shutdown: {err:?}");
+                    tracing::error!("Actor `{name}` returned an error during shutdown: {err:?}");
                 }
-                Err(err) => tracing::error!("One of actors panicked during shutdown: {err}"),
+                Err(err) => tracing::error!("Actor `{name}` panicked during shutdown: {err}"),
             }
         });
         future::join_all(futures).await;
@@ -89,12 +311,16 @@ impl ManagedTasks {
 
 #[cfg(test)]
 mod tests {
-    use std::sync::{
-        atomic::{AtomicBool, AtomicUsize, Ordering},
-        Arc,
+    use std::{
+        io,
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
     };
 
     use tokio::sync::watch;
+    use tracing_subscriber::fmt::MakeWriter;
 
     use super::*;
 
@@ -165,4 +391,209 @@ mod tests {
             assert!(is_finished.load(Ordering::Relaxed));
         }
     }
+
+    /// Log writer capturing logs into a shared buffer.
+    #[derive(Debug, Clone, Default)]
+    struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl LogBuffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl io::Write for LogBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl MakeWriter<'_> for LogBuffer {
+        type Writer = Self;
+
+        fn make_writer(&self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn logging_name_of_terminated_task() {
+        let logs = LogBuffer::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(logs.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let (shutdown_sender, mut shutdown_receiver) = watch::channel(false);
+        let mut tasks = ManagedTasks::new(vec![
+            NamedTask::spawn("long_running", async move {
+                shutdown_receiver.changed().await.ok();
+                Ok(())
+            }),
+            NamedTask::spawn("failing", async {
+                tokio::task::yield_now().await;
+                Err(anyhow::anyhow!("oops"))
+            }),
+        ]);
+        tasks.wait_single().await;
+        shutdown_sender.send_replace(true);
+        tasks.complete(Duration::from_secs(1)).await;
+
+        let logs = logs.contents();
+        assert!(
+            logs.contains("actor `failing` unexpectedly finished with error: oops"),
+            "{logs}"
+        );
+        assert!(!logs.contains("long_running"), "{logs}");
+    }
+
+    #[tokio::test]
+    async fn restarting_failed_task() {
+        let (shutdown_sender, shutdown_receiver) = watch::channel(false);
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let attempts_for_task = attempts.clone();
+        let policy = RestartPolicy::new(3, Duration::from_millis(1), Duration::from_millis(5));
+        let mut tasks = ManagedTasks::new(vec![NamedTask::spawn_with_policy(
+            "restartable",
+            TaskPolicy::Restart(policy),
+            shutdown_receiver,
+            move |mut shutdown_receiver| {
+                let attempt = attempts_for_task.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if attempt < 2 {
+                        anyhow::bail!("transient error");
+                    }
+                    shutdown_receiver.changed().await.ok();
+                    Ok(())
+                }
+            },
+        )]);
+
+        tokio::select! {
+            () = tasks.wait_single() => {
+                panic!("Restartable task should not lead to shutdown");
+            }
+            () = tokio::time::sleep(Duration::from_millis(100)) => {
+                // Emulate shutdown after a delay.
+            }
+        }
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        shutdown_sender.send_replace(true);
+        tasks.complete(Duration::from_secs(1)).await;
+    }
+
+    #[tokio::test]
+    async fn escalating_task_error_after_exhausting_restarts() {
+        let (_shutdown_sender, shutdown_receiver) = watch::channel(false);
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let attempts_for_task = attempts.clone();
+        let policy = RestartPolicy::new(2, Duration::from_millis(1), Duration::from_millis(5));
+        let mut tasks = ManagedTasks::new(vec![NamedTask::spawn_with_policy(
+            "restartable",
+            TaskPolicy::Restart(policy),
+            shutdown_receiver,
+            move |_| {
+                attempts_for_task.fetch_add(1, Ordering::SeqCst);
+                async { Err(anyhow::anyhow!("persistent error")) }
+            },
+        )]);
+        tasks.wait_single().await;
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        tasks.complete(Duration::from_secs(1)).await;
+    }
+
+    #[tokio::test]
+    async fn resetting_restarts_after_stable_run() {
+        let (_shutdown_sender, shutdown_receiver) = watch::channel(false);
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let attempts_for_task = attempts.clone();
+        let policy = RestartPolicy::new(2, Duration::from_millis(1), Duration::from_millis(5))
+            .with_reset_after(Duration::from_millis(20));
+        let mut tasks = ManagedTasks::new(vec![NamedTask::spawn_with_policy(
+            "restartable",
+            TaskPolicy::Restart(policy),
+            shutdown_receiver,
+            move |_| {
+                let attempt = attempts_for_task.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    // Every other run is stable, so restarts are never exhausted.
+                    if attempt % 2 == 1 {
+                        tokio::time::sleep(Duration::from_millis(30)).await;
+                    }
+                    anyhow::bail!("error")
+                }
+            },
+        )]);
+
+        tokio::select! {
+            () = tasks.wait_single() => {
+                panic!("Restarts should be reset after stable runs");
+            }
+            () = tokio::time::sleep(Duration::from_millis(200)) => {}
+        }
+        assert!(attempts.load(Ordering::SeqCst) > 3);
+    }
+
+    #[tokio::test]
+    async fn stop_signal_interrupts_restart_backoff() {
+        let (shutdown_sender, shutdown_receiver) = watch::channel(false);
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let attempts_for_task = attempts.clone();
+        let policy = RestartPolicy::new(2, Duration::from_secs(60), Duration::from_secs(60));
+        let mut tasks = ManagedTasks::new(vec![NamedTask::spawn_with_policy(
+            "restartable",
+            TaskPolicy::Restart(policy),
+            shutdown_receiver,
+            move |_| {
+                attempts_for_task.fetch_add(1, Ordering::SeqCst);
+                async { Err(anyhow::anyhow!("error")) }
+            },
+        )]);
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        shutdown_sender.send_replace(true);
+        tokio::time::timeout(Duration::from_secs(5), tasks.wait_single())
+            .await
+            .expect("restart backoff was not interrupted");
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+        tasks.complete(Duration::from_secs(1)).await;
+    }
+
+    #[tokio::test]
+    async fn task_allowed_to_finish_does_not_lead_to_shutdown() {
+        let (shutdown_sender, mut shutdown_receiver) = watch::channel(false);
+        let mut tasks = ManagedTasks::new(vec![
+            NamedTask::spawn_with_policy(
+                "finishing",
+                TaskPolicy::AllowedToFinish,
+                shutdown_receiver.clone(),
+                |_| async {
+                    tokio::task::yield_now().await;
+                    Ok(())
+                },
+            ),
+            NamedTask::spawn("long_running", async move {
+                shutdown_receiver.changed().await.ok();
+                Ok(())
+            }),
+        ]);
+
+        tokio::select! {
+            () = tasks.wait_single() => {
+                panic!("Task allowed to finish should not lead to shutdown");
+            }
+            () = tokio::time::sleep(Duration::from_millis(50)) => {
+                // Emulate shutdown after a delay.
+            }
+        }
+        assert_eq!(tasks.tasks.len(), 1);
+        shutdown_sender.send_replace(true);
+        tasks.complete(Duration::from_secs(1)).await;
+    }
 }
@@ -1,7 +1,7 @@
 //! This module contains the observability subsystem.
 //! It is responsible for providing a centralized interface for consistent observability configuration.
 
-use std::{backtrace::Backtrace, borrow::Cow, panic::PanicInfo, str::FromStr};
+use std::{backtrace::Backtrace, borrow::Cow, panic::PanicInfo, str::FromStr, sync::OnceLock};
 
 // Temporary re-export of `sentry::capture_message` aiming to simplify the transition from `vlog` to using
 // crates directly.
@@ -19,17 +19,21 @@ pub use sentry::{capture_message, Level as AlertLevel};
 use sentry::{types::Dsn, ClientInitGuard};
 use tracing_opentelemetry::OpenTelemetryLayer;
 use tracing_subscriber::{
-    filter::Filtered,
+    filter::{Filtered, ParseError},
     fmt,
     layer::{Layered, SubscriberExt},
     registry::LookupSpan,
+    reload,
     util::SubscriberInitExt,
-    EnvFilter, Layer,
+    EnvFilter, Layer, Registry,
 };
 
 type TracingLayer<Inner> =
     Layered<Filtered<OpenTelemetryLayer<Inner, Tracer>, EnvFilter, Inner>, Inner>;
 
+/// Handle for the log filter of the global subscriber; set in [`ObservabilityBuilder::build()`].
+static LOG_FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
 /// Specifies the format of the logs in stdout.
 #[derive(Debug, Clone, Copy, Default)]
 pub enum LogFormat {
@@ -82,6 +86,49 @@ impl std::fmt::Display for OpenTelemetryLevelFormatError {
 
 impl std::error::Error for OpenTelemetryLevelFormatError {}
 
+/// Error returned by [`set_log_filter()`].
+#[derive(Debug)]
+pub enum LogFilterError {
+    /// Supplied filter directives are invalid.
+    Parse(ParseError),
+    /// Observability subsystem was not initialized via [`ObservabilityBuilder::build()`].
+    NotInitialized,
+    /// Filter could not be replaced.
+    Reload(reload::Error),
+}
+
+impl std::fmt::Display for LogFilterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "invalid log filter directives: {err}"),
+            Self::NotInitialized => write!(f, "observability subsystem is not initialized"),
+            Self::Reload(err) => write!(f, "failed reloading log filter: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for LogFilterError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(err) => Some(err),
+            Self::NotInitialized => None,
+            Self::Reload(err) => Some(err),
+        }
+    }
+}
+
+/// Replaces the log filter (initially read from the `RUST_LOG` env variable) with the specified directives,
+/// e.g. `zksync_core=debug,info`. This doesn't affect the OpenTelemetry filter.
+pub fn set_log_filter(directives: &str) -> Result<(), LogFilterError> {
+    let filter = EnvFilter::try_new(directives).map_err(LogFilterError::Parse)?;
+    let handle = LOG_FILTER_HANDLE
+        .get()
+        .ok_or(LogFilterError::NotInitialized)?;
+    handle.reload(filter).map_err(LogFilterError::Reload)?;
+    tracing::info!("Replaced log filter with `{directives}`");
+    Ok(())
+}
+
 impl FromStr for OpenTelemetryLevel {
     type Err = OpenTelemetryLevelFormatError;
 
@@ -233,11 +280,15 @@ impl ObservabilityBuilder {
 
     /// Initializes the observability subsystem.
     pub fn build(self) -> ObservabilityGuard {
-        // Initialize logs.
+        // Initialize logs. The log filter is reloadable, so that it can be changed at runtime via [`set_log_filter()`].
+        let (log_filter, log_filter_handle) = reload::Layer::new(EnvFilter::from_default_env());
+        if LOG_FILTER_HANDLE.set(log_filter_handle).is_err() {
+            panic!("observability subsystem is already initialized");
+        }
         match self.log_format {
             LogFormat::Plain => {
                 let subscriber = tracing_subscriber::registry()
-                    .with(tracing_subscriber::EnvFilter::from_default_env())
+                    .with(log_filter)
                     .with(fmt::Layer::default());
                 if let Some(opts) = self.opentelemetry_options {
                     let subscriber = Self::add_opentelemetry_layer(
@@ -253,15 +304,13 @@ impl ObservabilityBuilder {
             }
             LogFormat::Json => {
                 let timer = tracing_subscriber::fmt::time::UtcTime::rfc_3339();
-                let subscriber = tracing_subscriber::registry()
-                    .with(tracing_subscriber::EnvFilter::from_default_env())
-                    .with(
-                        fmt::Layer::default()
-                            .with_file(true)
-                            .with_line_number(true)
-                            .with_timer(timer)
-                            .json(),
-                    );
+                let subscriber = tracing_subscriber::registry().with(log_filter).with(
+                    fmt::Layer::default()
+                        .with_file(true)
+                        .with_line_number(true)
+                        .with_timer(timer)
+                        .json(),
+                );
                 if let Some(opts) = self.opentelemetry_options {
                     let subscriber = Self::add_opentelemetry_layer(
                         opts.opentelemetry_level,
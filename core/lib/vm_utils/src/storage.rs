@@ -1,4 +1,7 @@
-use std::time::{Duration, Instant};
+use std::{
+    ops,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context;
 use multivm::{
@@ -121,6 +124,30 @@ impl L1BatchParamsProvider {
         Self::wait_for_l1_batch_params_unchecked(storage, number).await
     }
 
+    /// Returns state root hash of an L1 batch with the specified number, or `None` if the hash is not computed yet.
+    /// Unlike [`Self::wait_for_l1_batch_params()`], this method doesn't wait for the hash.
+    pub async fn load_l1_batch_state_hash(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        number: L1BatchNumber,
+    ) -> anyhow::Result<Option<H256>> {
+        if let Some(snapshot) = &self.snapshot {
+            if number == snapshot.l1_batch_number {
+                return Ok(Some(snapshot.l1_batch_root_hash));
+            }
+            anyhow::ensure!(
+                number > snapshot.l1_batch_number,
+                "Cannot load a hash of a pruned L1 batch #{number} (first retained batch: {})",
+                snapshot.l1_batch_number + 1
+            );
+        }
+        storage
+            .blocks_dal()
+            .get_l1_batch_state_root(number)
+            .await
+            .map_err(Into::into)
+    }
+
     async fn wait_for_l1_batch_params_unchecked(
         storage: &mut Connection<'_, Core>,
         number: L1BatchNumber,
@@ -169,6 +196,44 @@ impl L1BatchParamsProvider {
             .map_err(Into::into)
     }
 
+    /// Bulk version of [`Self::load_l1_batch_protocol_version()`] loading protocol versions for a range of L1 batches
+    /// in a single query. The returned vector has an entry for each batch in the range; the entry is `None`
+    /// if the batch or its protocol version is missing from the storage.
+    pub async fn load_l1_batch_protocol_versions(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        l1_batch_numbers: ops::RangeInclusive<L1BatchNumber>,
+    ) -> anyhow::Result<Vec<Option<ProtocolVersionId>>> {
+        let (start, end) = (*l1_batch_numbers.start(), *l1_batch_numbers.end());
+        if start > end {
+            return Ok(vec![]);
+        }
+        let mut versions = vec![None; (end.0 - start.0) as usize + 1];
+        let mut first_stored_batch = start;
+        if let Some(snapshot) = &self.snapshot {
+            anyhow::ensure!(
+                start >= snapshot.l1_batch_number,
+                "Requested protocol versions for pruned L1 batches starting from #{start}; first retained batch is #{}",
+                snapshot.l1_batch_number + 1
+            );
+            if start == snapshot.l1_batch_number {
+                versions[0] = Some(snapshot.protocol_version);
+                first_stored_batch += 1;
+            }
+        }
+
+        if first_stored_batch <= end {
+            let stored_versions = storage
+                .blocks_dal()
+                .get_batch_protocol_version_ids(first_stored_batch..=end)
+                .await?;
+            for (number, version) in stored_versions {
+                versions[(number.0 - start.0) as usize] = version;
+            }
+        }
+        Ok(versions)
+    }
+
     /// Returns a header of the first miniblock in the specified L1 batch regardless of whether the batch is sealed or not.
     pub async fn load_first_miniblock_in_batch(
         &self,
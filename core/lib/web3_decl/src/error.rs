@@ -35,8 +35,12 @@ pub enum Web3Error {
     TooManyTopics,
     #[error("Filter not found")]
     FilterNotFound,
+    #[error("Too many filters installed by the connection; the limit is {0}")]
+    TooManyFilters(usize),
     #[error("Query returned more than {0} results. Try with this block range [{1:#x}, {2:#x}].")]
     LogsLimitExceeded(usize, u32, u32),
+    #[error("Response size exceeds the limit of {0} bytes. Try with a smaller block range.")]
+    ResponseTooLarge(usize),
     #[error("invalid filter: if blockHash is supplied fromBlock and toBlock must not be")]
     InvalidFilterBlockHash,
     #[error("Not implemented")]
@@ -44,6 +48,14 @@ pub enum Web3Error {
 
     #[error("Tree API is not available")]
     TreeApiUnavailable,
+    #[error(
+        "Call trace for this transaction is not available since it was not sampled by the node"
+    )]
+    CallTraceNotSampled,
+    #[error(
+        "Merkle proofs are not available because the Merkle tree runs in the lightweight mode"
+    )]
+    TreeProofsUnavailable,
     #[error("Internal error")]
     InternalError(#[from] anyhow::Error),
 }
@@ -35,4 +35,9 @@ pub trait EnNamespace {
     /// Get genesis configuration
     #[method(name = "genesisConfig")]
     async fn genesis_config(&self) -> RpcResult<GenesisConfig>;
+
+    /// Returns the synchronization status of the node relative to the main node, or `None` if the node
+    /// doesn't sync from the main node (i.e., it *is* the main node).
+    #[method(name = "syncStatus")]
+    async fn sync_status(&self) -> RpcResult<Option<en::SyncStatus>>;
 }
@@ -3,8 +3,8 @@ use std::collections::HashMap;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
-        TransactionDetails,
+        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, NodeInfo, NodeVersionInfo,
+        Proof, ProtocolVersion, TransactionDetails,
     },
     fee::Fee,
     fee_model::FeeParams,
@@ -111,6 +111,12 @@ pub trait ZksNamespace {
         version_id: Option<u16>,
     ) -> RpcResult<Option<ProtocolVersion>>;
 
+    #[method(name = "getNodeVersionInfo")]
+    async fn get_node_version_info(&self) -> RpcResult<NodeVersionInfo>;
+
+    #[method(name = "getNodeInfo")]
+    async fn get_node_info(&self) -> RpcResult<NodeInfo>;
+
     #[method(name = "getProof")]
     async fn get_proof(
         &self,
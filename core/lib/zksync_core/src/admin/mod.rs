@@ -0,0 +1,352 @@
+//! Admin HTTP server exposing runtime controls to node operators.
+
+use std::{net::SocketAddr, sync::Arc};
+
+use anyhow::Context as _;
+use axum::{
+    extract::State,
+    http::{header::AUTHORIZATION, Request, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{watch, Mutex};
+use tower_http::validate_request::{ValidateRequest, ValidateRequestHeaderLayer};
+use zksync_config::configs::AdminConfig;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_protobuf::ProtoFmt;
+use zksync_types::L1BatchNumber;
+
+use crate::{
+    block_reverter::{BlockReverter, BlockReverterFlags, RollbackEstimate},
+    temp_config_store::TempConfigStore,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Placeholder for redacted config values.
+const REDACTED: &str = "<redacted>";
+/// Config params are redacted if their name contains any of these substrings.
+const SENSITIVE_PARAM_MARKERS: &[&str] =
+    &["url", "credential", "secret", "password", "token", "key"];
+
+/// Exports node configuration as JSON. String values that may contain credentials are redacted: all values
+/// of params with sensitive names (URLs, credentials, keys etc.), and all values that look like URLs.
+pub fn export_config(configs: &TempConfigStore) -> anyhow::Result<serde_json::Value> {
+    let mut exported_config =
+        zksync_protobuf::serde::serialize(configs, serde_json::value::Serializer)
+            .context("failed serializing config")?;
+    redact_config_values(&mut exported_config, false);
+    Ok(exported_config)
+}
+
+fn redact_config_values(value: &mut serde_json::Value, is_sensitive: bool) {
+    match value {
+        serde_json::Value::String(s) if is_sensitive || s.contains("://") => {
+            *s = REDACTED.to_owned();
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                redact_config_values(item, is_sensitive);
+            }
+        }
+        serde_json::Value::Object(params) => {
+            for (name, value) in params {
+                let name = name.to_lowercase();
+                let is_sensitive = is_sensitive
+                    || SENSITIVE_PARAM_MARKERS
+                        .iter()
+                        .any(|marker| name.contains(marker));
+                redact_config_values(value, is_sensitive);
+            }
+        }
+        _ => { /* Other values cannot contain credentials */ }
+    }
+}
+
+/// Compares byte strings in time independent of their contents, so that the comparison doesn't leak
+/// how long the matching prefix is. The length of compared strings is not considered secret.
+fn constant_time_eq(lhs: &[u8], rhs: &[u8]) -> bool {
+    if lhs.len() != rhs.len() {
+        return false;
+    }
+    let diff = lhs
+        .iter()
+        .zip(rhs)
+        .fold(0_u8, |acc, (&lhs, &rhs)| acc | (lhs ^ rhs));
+    std::hint::black_box(diff) == 0
+}
+
+/// Validates the `Authorization: Bearer <token>` header. Unlike [`ValidateRequestHeaderLayer::bearer()`],
+/// the supplied header is compared with the expected one in constant time.
+#[derive(Debug, Clone)]
+struct BearerAuth {
+    expected_header: Arc<[u8]>,
+}
+
+impl BearerAuth {
+    fn new(token: &str) -> Self {
+        Self {
+            expected_header: format!("Bearer {token}").into_bytes().into(),
+        }
+    }
+}
+
+impl<B> ValidateRequest<B> for BearerAuth {
+    type ResponseBody = axum::body::BoxBody;
+
+    fn validate(&mut self, request: &mut Request<B>) -> Result<(), Response> {
+        let is_authorized = request
+            .headers()
+            .get(AUTHORIZATION)
+            .map_or(false, |header| {
+                constant_time_eq(header.as_bytes(), &self.expected_header)
+            });
+        if is_authorized {
+            Ok(())
+        } else {
+            Err(StatusCode::UNAUTHORIZED.into_response())
+        }
+    }
+}
+
+/// Error returned by admin actions.
+#[derive(Debug, thiserror::Error)]
+enum AdminError {
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("{0}")]
+    Unsupported(&'static str),
+    #[error("internal error: {0:#}")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl IntoResponse for AdminError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            Self::BadRequest(_) => StatusCode::BAD_REQUEST,
+            Self::Unsupported(_) => StatusCode::CONFLICT,
+            Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, self.to_string()).into_response()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct DrainMode {
+    enabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct RollbackRequest {
+    l1_batch_number: L1BatchNumber,
+    /// If set, only estimates the amount of data that would be rolled back.
+    #[serde(default)]
+    dry_run: bool,
+}
+
+/// Rollback capabilities of the admin server.
+#[derive(Debug)]
+struct ForceRollback {
+    reverter: Mutex<BlockReverter>,
+    pool: ConnectionPool<Core>,
+    flags: BlockReverterFlags,
+}
+
+impl ForceRollback {
+    async fn execute(&self, request: RollbackRequest) -> Result<RollbackEstimate, AdminError> {
+        let last_l1_batch_to_keep = request.l1_batch_number;
+        let mut storage = self.pool.connection_tagged("admin").await?;
+        let sealed_l1_batch_number = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .context("get_sealed_l1_batch_number()")?;
+        if sealed_l1_batch_number.map_or(true, |number| number <= last_l1_batch_to_keep) {
+            return Err(AdminError::BadRequest(format!(
+                "nothing to roll back: last sealed L1 batch is {sealed_l1_batch_number:?}"
+            )));
+        }
+        let last_committed_l1_batch = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_committed_on_eth()
+            .await
+            .context("get_number_of_last_l1_batch_committed_on_eth()")?;
+        drop(storage);
+        if let Some(last_committed_l1_batch) = last_committed_l1_batch {
+            if last_committed_l1_batch > last_l1_batch_to_keep {
+                return Err(AdminError::BadRequest(format!(
+                    "L1 batch #{last_committed_l1_batch} is committed on L1; use the block reverter \
+                     to roll back committed batches"
+                )));
+            }
+        }
+
+        // Holding the lock prevents concurrent rollbacks.
+        let reverter = self.reverter.lock().await;
+        let estimate = reverter.estimate_rollback(last_l1_batch_to_keep).await?;
+        if !request.dry_run {
+            tracing::warn!(
+                "Rolling back to L1 batch #{last_l1_batch_to_keep} as requested via admin server"
+            );
+            reverter
+                .rollback_db(last_l1_batch_to_keep, self.flags)
+                .await;
+            tracing::info!("Rolled back to L1 batch #{last_l1_batch_to_keep}");
+        }
+        Ok(estimate)
+    }
+}
+
+#[derive(Debug)]
+struct AdminState {
+    exported_config: serde_json::Value,
+    drain_mode: Option<watch::Sender<bool>>,
+    force_rollback: Option<ForceRollback>,
+}
+
+impl AdminState {
+    fn drain_mode(&self) -> Result<&watch::Sender<bool>, AdminError> {
+        self.drain_mode.as_ref().ok_or(AdminError::Unsupported(
+            "drain mode is not supported by the node",
+        ))
+    }
+
+    async fn exported_config(State(this): State<Arc<Self>>) -> Json<serde_json::Value> {
+        Json(this.exported_config.clone())
+    }
+
+    async fn get_drain_mode(State(this): State<Arc<Self>>) -> Result<Json<DrainMode>, AdminError> {
+        let enabled = *this.drain_mode()?.borrow();
+        Ok(Json(DrainMode { enabled }))
+    }
+
+    async fn set_drain_mode(
+        State(this): State<Arc<Self>>,
+        Json(mode): Json<DrainMode>,
+    ) -> Result<Json<DrainMode>, AdminError> {
+        this.drain_mode()?.send_replace(mode.enabled);
+        tracing::info!("Set drain mode to {mode:?}");
+        Ok(Json(mode))
+    }
+
+    async fn set_log_filter(directives: String) -> Result<StatusCode, AdminError> {
+        vlog::set_log_filter(directives.trim()).map_err(|err| match err {
+            vlog::LogFilterError::Parse(_) => AdminError::BadRequest(err.to_string()),
+            _ => AdminError::Internal(err.into()),
+        })?;
+        Ok(StatusCode::NO_CONTENT)
+    }
+
+    async fn force_rollback(
+        State(this): State<Arc<Self>>,
+        Json(request): Json<RollbackRequest>,
+    ) -> Result<Json<RollbackEstimate>, AdminError> {
+        let force_rollback = this.force_rollback.as_ref().ok_or(AdminError::Unsupported(
+            "rollback is only supported if the node runs no components other than `admin`",
+        ))?;
+        force_rollback.execute(request).await.map(Json)
+    }
+}
+
+/// Admin server consolidating operator actions. All actions require the bearer token from secrets
+/// to be supplied in the `Authorization` header.
+///
+/// Supported actions are:
+///
+/// - `GET /config`: exports the node config with sensitive values redacted.
+/// - `GET /drain`, `PUT /drain`: gets or sets drain mode, in which API servers reject new transactions.
+/// - `PUT /log_filter`: replaces log filter directives (in the `RUST_LOG` format) supplied as the request body.
+/// - `POST /rollback`: rolls back node state to the specified L1 batch. Only batches not committed on L1
+///   can be rolled back, and only if the node runs no components other than the admin server.
+#[derive(Debug)]
+pub struct AdminServer {
+    bind_addr: SocketAddr,
+    token: String,
+    state: AdminState,
+}
+
+impl AdminServer {
+    /// Creates a server protected by the specified bearer token.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token is empty (or consists only of whitespace).
+    pub fn new(
+        config: &AdminConfig,
+        token: String,
+        exported_config: serde_json::Value,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(!token.trim().is_empty(), "admin token must not be empty");
+        Ok(Self {
+            bind_addr: config.bind_addr(),
+            token,
+            state: AdminState {
+                exported_config,
+                drain_mode: None,
+                force_rollback: None,
+            },
+        })
+    }
+
+    /// Enables controlling drain mode. The provided sender should be connected to `TxSender`s
+    /// of the node API servers.
+    pub fn with_drain_mode(mut self, drain_mode: watch::Sender<bool>) -> Self {
+        self.state.drain_mode = Some(drain_mode);
+        self
+    }
+
+    /// Enables forced rollbacks. The caller is responsible for ensuring that no other node component
+    /// modifies the node state while the admin server is running.
+    pub fn with_block_reverter(
+        mut self,
+        reverter: BlockReverter,
+        pool: ConnectionPool<Core>,
+        flags: BlockReverterFlags,
+    ) -> Self {
+        self.state.force_rollback = Some(ForceRollback {
+            reverter: Mutex::new(reverter),
+            pool,
+            flags,
+        });
+        self
+    }
+
+    fn router(self) -> Router {
+        Router::new()
+            .route("/config", get(AdminState::exported_config))
+            .route(
+                "/drain",
+                get(AdminState::get_drain_mode).put(AdminState::set_drain_mode),
+            )
+            .route("/log_filter", put(AdminState::set_log_filter))
+            .route("/rollback", post(AdminState::force_rollback))
+            .route_layer(ValidateRequestHeaderLayer::custom(BearerAuth::new(
+                &self.token,
+            )))
+            .with_state(Arc::new(self.state))
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::info!("Starting admin server on {}", self.bind_addr);
+        let bind_addr = self.bind_addr;
+        let app = self.router();
+        axum::Server::try_bind(&bind_addr)
+            .with_context(|| format!("failed binding admin server to {bind_addr}"))?
+            .serve(app.into_make_service())
+            .with_graceful_shutdown(async move {
+                if stop_receiver.changed().await.is_err() {
+                    tracing::warn!(
+                        "Stop signal sender for admin server was dropped without sending a signal"
+                    );
+                }
+                tracing::info!("Stop signal received, admin server is shutting down");
+            })
+            .await
+            .context("Admin server failed")?;
+        tracing::info!("Admin server shut down");
+        Ok(())
+    }
+}
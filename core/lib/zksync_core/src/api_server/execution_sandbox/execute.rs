@@ -2,7 +2,7 @@
 
 use anyhow::Context as _;
 use multivm::{
-    interface::{TxExecutionMode, VmExecutionResultAndLogs, VmInterface},
+    interface::{TxExecutionMode, VmExecutionMode, VmExecutionResultAndLogs, VmInterface},
     tracers::StorageInvocations,
     vm_latest::constants::ETH_CALL_GAS_LIMIT,
     MultiVMTracer,
@@ -38,6 +38,18 @@ impl TxExecutionArgs {
         }
     }
 
+    /// Arguments for re-executing a transaction that was already included into a miniblock. Unlike [`Self::for_validation()`],
+    /// the nonce is not enforced since it is already set correctly by replaying the preceding transactions.
+    fn for_replay() -> Self {
+        Self {
+            execution_mode: TxExecutionMode::VerifyExecute,
+            enforced_nonce: None,
+            added_balance: U256::zero(),
+            enforced_base_fee: None,
+            missed_storage_invocation_limit: usize::MAX,
+        }
+    }
+
     fn for_eth_call(
         enforced_base_fee: u64,
         vm_execution_cache_misses_limit: Option<usize>,
@@ -164,6 +176,60 @@ impl TransactionExecutor {
         })
     }
 
+    /// Re-executes a transaction included into a miniblock, e.g. to collect its call trace. `block_args` must point
+    /// to the miniblock preceding the one the transaction was included into, and `preceding_txs` must contain
+    /// the transactions from the transaction's miniblock that precede it; they are executed before the traced transaction
+    /// so that it observes the same storage state.
+    #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(skip_all)]
+    pub async fn replay_tx_in_sandbox(
+        &self,
+        vm_permit: VmPermit,
+        shared_args: TxSharedArgs,
+        connection_pool: ConnectionPool<Core>,
+        preceding_txs: Vec<Transaction>,
+        tx: Transaction,
+        block_args: BlockArgs,
+        custom_tracers: Vec<ApiTracer>,
+    ) -> anyhow::Result<VmExecutionResultAndLogs> {
+        #[cfg(test)]
+        if let Self::Mock(mock_executor) = self {
+            return mock_executor
+                .execute_tx(&tx, &block_args)
+                .map(|output| output.vm);
+        }
+
+        let execution_args = TxExecutionArgs::for_replay();
+        tokio::task::spawn_blocking(move || {
+            let span = span!(Level::DEBUG, "replay_in_sandbox").entered();
+            let result = apply::apply_vm_in_sandbox(
+                vm_permit,
+                shared_args,
+                false,
+                &execution_args,
+                &connection_pool,
+                tx,
+                block_args,
+                |vm, tx| {
+                    for preceding_tx in preceding_txs {
+                        vm.push_transaction(preceding_tx);
+                        vm.execute(VmExecutionMode::OneTx);
+                    }
+                    let custom_tracers: Vec<_> = custom_tracers
+                        .into_iter()
+                        .map(|tracer| tracer.into_boxed())
+                        .collect();
+                    vm.push_transaction(tx);
+                    vm.inspect(custom_tracers.into(), VmExecutionMode::OneTx)
+                },
+            );
+            span.exit();
+            result
+        })
+        .await
+        .context("transaction replay panicked")?
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn execute_tx_eth_call(
         &self,
@@ -1,4 +1,4 @@
-use std::{sync::Arc, time::Duration};
+use std::{num::NonZeroUsize, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use tokio::runtime::Handle;
@@ -41,6 +41,8 @@ pub struct VmPermit {
     /// A handle to the runtime that is used to query the VM storage.
     rt_handle: Handle,
     _permit: Arc<tokio::sync::OwnedSemaphorePermit>,
+    /// Additional permit for tracing calls; see [`VmConcurrencyLimiter::acquire_for_trace()`].
+    _trace_permit: Option<Arc<tokio::sync::OwnedSemaphorePermit>>,
 }
 
 impl VmPermit {
@@ -101,6 +103,9 @@ impl VmConcurrencyBarrier {
 pub struct VmConcurrencyLimiter {
     /// Semaphore that limits the number of concurrent VM executions.
     limiter: Arc<tokio::sync::Semaphore>,
+    /// Semaphore that additionally limits the number of concurrent VM executions for tracing calls
+    /// (e.g., `debug_traceCall`), which are much more expensive than ordinary calls.
+    trace_limiter: Option<Arc<tokio::sync::Semaphore>>,
     rt_handle: Handle,
 }
 
@@ -114,6 +119,7 @@ impl VmConcurrencyLimiter {
 
         let this = Self {
             limiter: Arc::clone(&limiter),
+            trace_limiter: None,
             rt_handle: Handle::current(),
         };
         let barrier = VmConcurrencyBarrier {
@@ -123,6 +129,18 @@ impl VmConcurrencyLimiter {
         (this, barrier)
     }
 
+    /// Sets a separate limit on the number of concurrent VM executions for tracing calls. These executions
+    /// still count towards the general limit, but cannot occupy more than `max_concurrency` permits, so that
+    /// a burst of tracing calls cannot starve ordinary calls.
+    #[must_use]
+    pub fn with_trace_concurrency_limit(mut self, max_concurrency: NonZeroUsize) -> Self {
+        tracing::info!(
+            "Limiting the number of concurrent VM executions for tracing calls to {max_concurrency}"
+        );
+        self.trace_limiter = Some(Arc::new(tokio::sync::Semaphore::new(max_concurrency.get())));
+        self
+    }
+
     /// Waits until there is a free slot in the concurrency limiter.
     /// Returns a permit that should be dropped when the VM execution is finished.
     pub async fn acquire(&self) -> Option<VmPermit> {
@@ -144,8 +162,30 @@ impl VmConcurrencyLimiter {
         Some(VmPermit {
             rt_handle: self.rt_handle.clone(),
             _permit: Arc::new(permit),
+            _trace_permit: None,
         })
     }
+
+    /// Same as [`Self::acquire()`], but for tracing calls. If a trace concurrency limit is set, waits until
+    /// there is a free slot in the trace limiter before acquiring a general permit.
+    pub async fn acquire_for_trace(&self) -> Option<VmPermit> {
+        let trace_permit = if let Some(trace_limiter) = &self.trace_limiter {
+            SANDBOX_METRICS
+                .trace_execution_permits
+                .observe(trace_limiter.available_permits());
+            let latency =
+                SANDBOX_METRICS.sandbox[&SandboxStage::TraceConcurrencyLimiterAcquire].start();
+            let permit = Arc::clone(trace_limiter).acquire_owned().await.ok()?;
+            latency.observe();
+            Some(Arc::new(permit))
+        } else {
+            None
+        };
+
+        let mut permit = self.acquire().await?;
+        permit._trace_permit = trace_permit;
+        Some(permit)
+    }
 }
 
 async fn get_pending_state(
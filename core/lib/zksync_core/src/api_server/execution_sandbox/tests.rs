@@ -196,3 +196,35 @@ async fn test_instantiating_vm(pool: ConnectionPool<Core>, block_args: BlockArgs
     .expect("VM instantiation panicked")
     .expect("VM instantiation errored");
 }
+
+#[tokio::test]
+async fn trace_concurrency_is_limited_independently() {
+    let (vm_concurrency_limiter, _) = VmConcurrencyLimiter::new(10);
+    let vm_concurrency_limiter =
+        vm_concurrency_limiter.with_trace_concurrency_limit(NonZeroUsize::new(2).unwrap());
+
+    let mut trace_permits = vec![];
+    for _ in 0..2 {
+        trace_permits.push(vm_concurrency_limiter.acquire_for_trace().await.unwrap());
+    }
+    let trace_permit_future = vm_concurrency_limiter.acquire_for_trace();
+    tokio::time::timeout(Duration::from_millis(50), trace_permit_future)
+        .await
+        .unwrap_err();
+
+    // Ordinary calls can still use the remaining permits.
+    let mut permits = vec![];
+    for _ in 0..8 {
+        permits.push(vm_concurrency_limiter.acquire().await.unwrap());
+    }
+    assert_eq!(vm_concurrency_limiter.limiter.available_permits(), 0);
+
+    // Trace permits count towards the general limit and are released together with VM permits.
+    drop(trace_permits);
+    assert_eq!(vm_concurrency_limiter.limiter.available_permits(), 2);
+    let trace_permit_future = vm_concurrency_limiter.acquire_for_trace();
+    tokio::time::timeout(Duration::from_secs(1), trace_permit_future)
+        .await
+        .unwrap()
+        .unwrap();
+}
@@ -75,6 +75,7 @@ static STORAGE_METRICS: vise::Global<RuntimeContextStorageMetrics> = vise::Globa
 #[metrics(label = "stage", rename_all = "snake_case")]
 pub(super) enum SandboxStage {
     VmConcurrencyLimiterAcquire,
+    TraceConcurrencyLimiterAcquire,
     Initialization,
     ValidateInSandbox,
     Validation,
@@ -103,6 +104,10 @@ pub(in crate::api_server) struct SandboxMetrics {
     pub(super) sandbox: Family<SandboxStage, Histogram<Duration>>,
     #[metrics(buckets = Buckets::linear(0.0..=2_000.0, 200.0))]
     pub(super) sandbox_execution_permits: Histogram<usize>,
+    /// Number of available permits in the trace concurrency limiter observed when acquiring a permit.
+    /// Values close to 0 mean that the limiter is saturated.
+    #[metrics(buckets = Buckets::linear(0.0..=100.0, 10.0))]
+    pub(super) trace_execution_permits: Histogram<usize>,
     #[metrics(buckets = Buckets::LATENCIES)]
     pub submit_tx: Family<SubmitTxStage, Histogram<Duration>>,
     #[metrics(buckets = Buckets::linear(0.0..=30.0, 3.0))]
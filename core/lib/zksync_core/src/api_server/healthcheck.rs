@@ -1,15 +1,36 @@
 use std::{net::SocketAddr, sync::Arc, time::Duration};
 
+use anyhow::Context as _;
 use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
 use tokio::sync::watch;
 use zksync_health_check::{AppHealth, AppHealthCheck};
 
-async fn check_health(
-    app_health_check: State<Arc<AppHealthCheck>>,
-) -> (StatusCode, Json<AppHealth>) {
-    let response = app_health_check.check_health().await;
+#[derive(Debug, Clone)]
+struct HealthCheckState {
+    app_health_check: Arc<AppHealthCheck>,
+    /// Status code returned while the application is initializing.
+    initializing_status: StatusCode,
+}
+
+/// Readiness probe: succeeds only if the application is healthy, i.e., it should receive traffic.
+async fn check_readiness(state: State<HealthCheckState>) -> (StatusCode, Json<AppHealth>) {
+    let response = state.app_health_check.check_health().await;
     let response_code = if response.is_healthy() {
         StatusCode::OK
+    } else if response.is_initializing() {
+        state.initializing_status
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (response_code, Json(response))
+}
+
+/// Liveness probe: fails only if the application has [failed](AppHealth::is_alive()), so that an application
+/// that is starting up or catching up isn't restarted.
+async fn check_liveness(state: State<HealthCheckState>) -> (StatusCode, Json<AppHealth>) {
+    let response = state.app_health_check.check_health().await;
+    let response_code = if response.is_alive() {
+        StatusCode::OK
     } else {
         StatusCode::SERVICE_UNAVAILABLE
     };
@@ -18,16 +39,20 @@ async fn check_health(
 
 async fn run_server(
     bind_address: &SocketAddr,
-    app_health_check: Arc<AppHealthCheck>,
+    state: HealthCheckState,
     mut stop_receiver: watch::Receiver<bool>,
 ) {
     tracing::debug!(
-        "Starting healthcheck server with checks {app_health_check:?} on {bind_address}"
+        "Starting healthcheck server with checks {:?} on {bind_address}",
+        state.app_health_check
     );
 
     let app = Router::new()
-        .route("/health", get(check_health))
-        .with_state(app_health_check);
+        // `/health` is retained for backward compatibility; it's equivalent to `/ready`.
+        .route("/health", get(check_readiness))
+        .route("/ready", get(check_readiness))
+        .route("/live", get(check_liveness))
+        .with_state(state);
 
     axum::Server::bind(bind_address)
         .serve(app.into_make_service())
@@ -50,9 +75,38 @@ pub struct HealthCheckHandle {
 
 impl HealthCheckHandle {
     pub fn spawn_server(addr: SocketAddr, app_health_check: Arc<AppHealthCheck>) -> Self {
+        let state = HealthCheckState {
+            app_health_check,
+            initializing_status: StatusCode::SERVICE_UNAVAILABLE,
+        };
+        Self::spawn_server_with_state(addr, state)
+    }
+
+    /// Spawns a server that responds with the specified HTTP status code while the application
+    /// is [initializing](AppHealth::is_initializing()). This allows load balancers to distinguish a starting node
+    /// from a failing one.
+    pub fn spawn_server_with_initializing_status(
+        addr: SocketAddr,
+        app_health_check: Arc<AppHealthCheck>,
+        initializing_status: u16,
+    ) -> anyhow::Result<Self> {
+        let initializing_status = StatusCode::from_u16(initializing_status)
+            .with_context(|| format!("invalid HTTP status code: {initializing_status}"))?;
+        anyhow::ensure!(
+            !initializing_status.is_success(),
+            "HTTP status code returned during initialization must not indicate success, got {initializing_status}"
+        );
+        let state = HealthCheckState {
+            app_health_check,
+            initializing_status,
+        };
+        Ok(Self::spawn_server_with_state(addr, state))
+    }
+
+    fn spawn_server_with_state(addr: SocketAddr, state: HealthCheckState) -> Self {
         let (stop_sender, stop_receiver) = watch::channel(false);
         let server = tokio::spawn(async move {
-            run_server(&addr, app_health_check, stop_receiver).await;
+            run_server(&addr, state, stop_receiver).await;
         });
 
         Self {
@@ -76,3 +130,43 @@ impl HealthCheckHandle {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use zksync_health_check::{HealthStatus, ReactiveHealthCheck};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn readiness_and_liveness_while_catching_up() {
+        let app_health_check = Arc::new(AppHealthCheck::default());
+        let (api_check, api_updater) = ReactiveHealthCheck::new("api");
+        let (sync_check, sync_updater) = ReactiveHealthCheck::new("sync_state");
+        app_health_check.insert_component(api_check);
+        app_health_check.insert_component(sync_check);
+        let state = HealthCheckState {
+            app_health_check,
+            initializing_status: StatusCode::SERVICE_UNAVAILABLE,
+        };
+
+        // The node is catching up, so it shouldn't receive traffic, but it mustn't be restarted either.
+        api_updater.update(HealthStatus::Ready.into());
+        let (readiness, _) = check_readiness(State(state.clone())).await;
+        assert_eq!(readiness, StatusCode::SERVICE_UNAVAILABLE);
+        let (liveness, _) = check_liveness(State(state.clone())).await;
+        assert_eq!(liveness, StatusCode::OK);
+
+        sync_updater.update(HealthStatus::Ready.into());
+        let (readiness, _) = check_readiness(State(state.clone())).await;
+        assert_eq!(readiness, StatusCode::OK);
+        let (liveness, _) = check_liveness(State(state.clone())).await;
+        assert_eq!(liveness, StatusCode::OK);
+
+        sync_updater.update(HealthStatus::Failed.into());
+        let (readiness, _) = check_readiness(State(state.clone())).await;
+        assert_eq!(readiness, StatusCode::SERVICE_UNAVAILABLE);
+        let (liveness, Json(health)) = check_liveness(State(state)).await;
+        assert_eq!(liveness, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(!health.is_alive());
+    }
+}
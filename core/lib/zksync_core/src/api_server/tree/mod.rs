@@ -12,6 +12,7 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use tokio::sync::watch;
+use zksync_config::configs::database::MerkleTreeMode;
 use zksync_health_check::{CheckHealth, Health, HealthStatus};
 use zksync_merkle_tree::NoVersionError;
 use zksync_types::{L1BatchNumber, H256, U256};
@@ -65,6 +66,7 @@ impl TreeEntryWithProof {
 #[derive(Debug)]
 enum TreeApiServerError {
     NoTreeVersion(NoVersionError),
+    ProofsUnavailable,
 }
 
 // Contains the same fields as `NoVersionError` and is serializable.
@@ -117,6 +119,15 @@ impl IntoResponse for TreeApiServerError {
                 };
                 (StatusCode::NOT_FOUND, headers, Json(body)).into_response()
             }
+            Self::ProofsUnavailable => {
+                let body = Problem {
+                    r#type: "/errors#proofs-unavailable",
+                    title: "Proofs not available",
+                    detail: TreeApiError::ProofsUnavailable.to_string(),
+                    data: serde_json::Map::new(),
+                };
+                (StatusCode::NOT_IMPLEMENTED, headers, Json(body)).into_response()
+            }
         }
     }
 }
@@ -129,6 +140,9 @@ pub enum TreeApiError {
     NoVersion(NoVersionError),
     #[error("tree API is temporarily not available because the Merkle tree isn't initialized; repeat request later")]
     NotReady,
+    /// Proofs were requested from a tree running in the [lightweight mode](MerkleTreeMode::Lightweight).
+    #[error("Merkle proofs are not available in lightweight mode; the tree must run in the full mode to serve proofs")]
+    ProofsUnavailable,
     /// Catch-all variant for internal errors.
     #[error("internal error")]
     Internal(#[from] anyhow::Error),
@@ -168,7 +182,7 @@ impl TreeApiClient for LazyAsyncTreeReader {
             reader
                 .get_proofs_inner(l1_batch_number, hashed_keys)
                 .await
-                .map_err(TreeApiError::NoVersion)
+                .map_err(Into::into)
         } else {
             Err(TreeApiError::NotReady)
         }
@@ -183,6 +197,15 @@ pub struct TreeApiHttpClient {
     proofs_url: String,
 }
 
+impl From<TreeApiServerError> for TreeApiError {
+    fn from(err: TreeApiServerError) -> Self {
+        match err {
+            TreeApiServerError::NoTreeVersion(err) => Self::NoVersion(err),
+            TreeApiServerError::ProofsUnavailable => Self::ProofsUnavailable,
+        }
+    }
+}
+
 impl TreeApiHttpClient {
     pub fn new(url_base: &str) -> Self {
         Self {
@@ -256,6 +279,9 @@ impl TreeApiClient for TreeApiHttpClient {
                 .context("failed parsing error response")?;
             return Err(TreeApiError::NoVersion(problem_data.into()));
         }
+        if response.status() == StatusCode::NOT_IMPLEMENTED && is_problem {
+            return Err(TreeApiError::ProofsUnavailable);
+        }
 
         let response = response.error_for_status().with_context(|| {
             format!("requesting proofs for L1 batch #{l1_batch_number} returned non-OK response")
@@ -279,11 +305,15 @@ impl AsyncTreeReader {
         &self,
         l1_batch_number: L1BatchNumber,
         hashed_keys: Vec<U256>,
-    ) -> Result<Vec<TreeEntryWithProof>, NoVersionError> {
+    ) -> Result<Vec<TreeEntryWithProof>, TreeApiServerError> {
+        if self.mode() == MerkleTreeMode::Lightweight {
+            return Err(TreeApiServerError::ProofsUnavailable);
+        }
         let proofs = self
             .clone()
             .entries_with_proofs(l1_batch_number, hashed_keys)
-            .await?;
+            .await
+            .map_err(TreeApiServerError::NoTreeVersion)?;
         Ok(proofs.into_iter().map(TreeEntryWithProof::new).collect())
     }
 
@@ -294,8 +324,7 @@ impl AsyncTreeReader {
         let latency = API_METRICS.latency[&MerkleTreeApiMethod::GetProofs].start();
         let entries = this
             .get_proofs_inner(request.l1_batch_number, request.hashed_keys)
-            .await
-            .map_err(TreeApiServerError::NoTreeVersion)?;
+            .await?;
         let response = TreeProofsResponse { entries };
         latency.observe();
         Ok(Json(response))
@@ -8,7 +8,7 @@ use zksync_dal::{ConnectionPool, Core};
 
 use super::*;
 use crate::metadata_calculator::tests::{
-    gen_storage_logs, reset_db_state, run_calculator, setup_calculator,
+    create_tree_reader, gen_storage_logs, reset_db_state, run_calculator, setup_calculator,
 };
 
 #[tokio::test]
@@ -102,3 +102,39 @@ async fn local_merkle_tree_client() {
     assert_eq!(err.version_count, 6);
     assert_eq!(err.missing_version, 10);
 }
+
+#[tokio::test]
+async fn proofs_are_unavailable_in_lightweight_mode() {
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (tree_reader, _) = create_tree_reader(temp_dir.path(), gen_storage_logs(0..20, 2)).await;
+    let hashed_keys = vec![U256::zero()];
+
+    let tree_info = tree_reader.get_info().await.unwrap();
+    assert_eq!(tree_info.mode, MerkleTreeMode::Lightweight);
+    assert_eq!(tree_info.next_l1_batch_number, L1BatchNumber(2));
+    let err = tree_reader
+        .get_proofs(L1BatchNumber(1), hashed_keys.clone())
+        .await
+        .unwrap_err();
+    assert_matches!(err, TreeApiError::ProofsUnavailable);
+
+    let api_addr = (Ipv4Addr::LOCALHOST, 0).into();
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let api_server = tree_reader
+        .wait()
+        .await
+        .create_api_server(&api_addr, stop_receiver)
+        .unwrap();
+    let local_addr = *api_server.local_addr();
+    let api_server_task = tokio::spawn(api_server.run());
+    let api_client = TreeApiHttpClient::new(&format!("http://{local_addr}"));
+
+    let err = api_client
+        .get_proofs(L1BatchNumber(1), hashed_keys)
+        .await
+        .unwrap_err();
+    assert_matches!(err, TreeApiError::ProofsUnavailable);
+
+    stop_sender.send_replace(true);
+    api_server_task.await.unwrap().unwrap();
+}
@@ -0,0 +1,151 @@
+//! Periodic reloading of contracts used in the API sandbox.
+
+use std::{fmt, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use vise::{Counter, Metrics};
+
+use super::{ApiContracts, TxSender};
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_contracts")]
+struct ApiContractsMetrics {
+    /// Number of times API contracts were replaced with changed ones.
+    reloads: Counter,
+    /// Number of failed attempts to load API contracts.
+    load_errors: Counter,
+}
+
+#[vise::register]
+static METRICS: vise::Global<ApiContractsMetrics> = vise::Global::new();
+
+type ContractsLoader = Arc<dyn Fn() -> ApiContracts + Send + Sync>;
+
+/// Periodically reloads [`ApiContracts`] from disk and swaps them into a [`TxSender`] if they have changed.
+/// This allows updating contracts used for `eth_call` / `eth_estimateGas` without restarting the node.
+pub struct ApiContractsReloader {
+    tx_sender: TxSender,
+    poll_interval: Duration,
+    loader: ContractsLoader,
+}
+
+impl fmt::Debug for ApiContractsReloader {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ApiContractsReloader")
+            .field("poll_interval", &self.poll_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ApiContractsReloader {
+    pub fn new(tx_sender: TxSender, poll_interval: Duration) -> Self {
+        Self {
+            tx_sender,
+            poll_interval,
+            loader: Arc::new(ApiContracts::load_from_disk),
+        }
+    }
+
+    #[cfg(test)]
+    fn with_loader(mut self, loader: impl Fn() -> ApiContracts + Send + Sync + 'static) -> Self {
+        self.loader = Arc::new(loader);
+        self
+    }
+
+    /// Loads contracts and replaces the current ones if they differ. Returns whether the contracts were replaced.
+    async fn reload(&self) -> anyhow::Result<bool> {
+        let loader = self.loader.clone();
+        // Loading contracts panics on errors, so it's isolated in a separate task.
+        let new_contracts = tokio::task::spawn_blocking(move || loader())
+            .await
+            .context("failed loading API contracts")?;
+
+        let current_contracts = self.tx_sender.0.api_contracts.load();
+        if current_contracts.has_same_hashes(&new_contracts) {
+            return Ok(false);
+        }
+        self.tx_sender.replace_api_contracts(new_contracts);
+        Ok(true)
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow() {
+            if tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+
+            match self.reload().await {
+                Ok(true) => {
+                    METRICS.reloads.inc();
+                    tracing::info!("Reloaded API contracts");
+                }
+                Ok(false) => { /* contracts haven't changed */ }
+                Err(err) => {
+                    METRICS.load_errors.inc();
+                    tracing::warn!("Failed reloading API contracts: {err:#}");
+                }
+            }
+        }
+        tracing::info!("Stop signal received, API contracts reloader is shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_dal::{ConnectionPool, Core};
+    use zksync_types::{L2ChainId, H256};
+
+    use super::*;
+    use crate::api_server::{
+        execution_sandbox::testonly::MockTransactionExecutor,
+        tx_sender::tests::create_test_tx_sender,
+    };
+
+    fn modified_contracts() -> ApiContracts {
+        let mut contracts = ApiContracts::load_from_disk();
+        contracts.eth_call.post_1_4_2.bootloader.hash = H256::repeat_byte(0xff);
+        contracts
+    }
+
+    #[tokio::test]
+    async fn reloading_api_contracts() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let tx_executor = MockTransactionExecutor::default().into();
+        let (tx_sender, _) = create_test_tx_sender(pool, L2ChainId::default(), tx_executor).await;
+
+        let reloader = ApiContractsReloader::new(tx_sender.clone(), Duration::from_millis(10));
+        assert!(!reloader.reload().await.unwrap());
+
+        let reloader = reloader.with_loader(modified_contracts);
+        assert!(reloader.reload().await.unwrap());
+        let current_contracts = tx_sender.0.api_contracts.load();
+        assert_eq!(
+            current_contracts.eth_call.post_1_4_2.bootloader.hash,
+            H256::repeat_byte(0xff)
+        );
+        // Contracts haven't changed since the last reload.
+        assert!(!reloader.reload().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn failed_reload_keeps_current_contracts() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let tx_executor = MockTransactionExecutor::default().into();
+        let (tx_sender, _) = create_test_tx_sender(pool, L2ChainId::default(), tx_executor).await;
+        let initial_contracts = tx_sender.0.api_contracts.load_full();
+
+        let reloader = ApiContractsReloader::new(tx_sender.clone(), Duration::from_millis(10))
+            .with_loader(|| panic!("contracts are missing"));
+        reloader.reload().await.unwrap_err();
+        assert!(Arc::ptr_eq(
+            &tx_sender.0.api_contracts.load_full(),
+            &initial_contracts
+        ));
+    }
+}
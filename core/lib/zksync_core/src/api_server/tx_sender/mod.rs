@@ -3,13 +3,15 @@
 use std::{cmp, sync::Arc, time::Instant};
 
 use anyhow::Context as _;
+use arc_swap::ArcSwap;
 use multivm::{
     interface::VmExecutionResultAndLogs,
     utils::{adjust_pubdata_price_for_tx, derive_base_fee_and_gas_per_pubdata, derive_overhead},
     vm_latest::constants::BLOCK_GAS_LIMIT,
 };
+use tokio::sync::watch;
 use zksync_config::configs::{api::Web3JsonRpcConfig, chain::StateKeeperConfig};
-use zksync_contracts::BaseSystemContracts;
+use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes};
 use zksync_dal::{
     transactions_dal::L2TxSubmissionResult, Connection, ConnectionPool, Core, CoreDal,
 };
@@ -21,7 +23,7 @@ use zksync_types::{
     l1::is_l1_tx_type,
     l2::{error::TxCheckError::TxDuplication, L2Tx},
     utils::storage_key_for_eth_balance,
-    AccountTreeId, Address, ExecuteTransactionCommon, L2ChainId, MiniblockNumber, Nonce,
+    AccountTreeId, Address, Execute, ExecuteTransactionCommon, L2ChainId, MiniblockNumber, Nonce,
     PackedEthSignature, ProtocolVersionId, Transaction, VmVersion, H160, H256, MAX_L2_TX_GAS_LIMIT,
     MAX_NEW_FACTORY_DEPS, U256,
 };
@@ -43,6 +45,7 @@ use crate::{
     utils::pending_protocol_version,
 };
 
+pub mod contracts_reloader;
 pub mod master_pool_sink;
 pub mod proxy;
 mod result;
@@ -97,6 +100,18 @@ impl MultiVMBaseSystemContracts {
             | ProtocolVersionId::Version23 => self.post_1_4_2,
         }
     }
+
+    fn hashes(&self) -> [BaseSystemContractsHashes; 7] {
+        [
+            self.pre_virtual_blocks.hashes(),
+            self.post_virtual_blocks.hashes(),
+            self.post_virtual_blocks_finish_upgrade_fix.hashes(),
+            self.post_boojum.hashes(),
+            self.post_allowlist_removal.hashes(),
+            self.post_1_4_1.hashes(),
+            self.post_1_4_2.hashes(),
+        ]
+    }
 }
 
 /// Smart contracts to be used in the API sandbox requests, e.g. for estimating gas and
@@ -141,6 +156,12 @@ impl ApiContracts {
             },
         }
     }
+
+    /// Checks whether these contracts are identical to `other` by comparing their hashes.
+    pub(crate) fn has_same_hashes(&self, other: &Self) -> bool {
+        self.estimate_gas.hashes() == other.estimate_gas.hashes()
+            && self.eth_call.hashes() == other.eth_call.hashes()
+    }
 }
 
 /// Builder for the `TxSender`.
@@ -154,6 +175,8 @@ pub struct TxSenderBuilder {
     tx_sink: Arc<dyn TxSink>,
     /// Batch sealer used to check whether transaction can be executed by the sequencer.
     sealer: Option<Arc<dyn ConditionalSealer>>,
+    /// Drain mode flag; if set, new transactions are rejected.
+    drain_mode: Option<watch::Receiver<bool>>,
 }
 
 impl TxSenderBuilder {
@@ -167,6 +190,7 @@ impl TxSenderBuilder {
             replica_connection_pool,
             tx_sink,
             sealer: None,
+            drain_mode: None,
         }
     }
 
@@ -175,6 +199,12 @@ impl TxSenderBuilder {
         self
     }
 
+    /// Makes the sender reject new transactions while the provided flag is set.
+    pub fn with_drain_mode(mut self, drain_mode: watch::Receiver<bool>) -> Self {
+        self.drain_mode = Some(drain_mode);
+        self
+    }
+
     pub async fn build(
         self,
         batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
@@ -190,10 +220,11 @@ impl TxSenderBuilder {
             tx_sink: self.tx_sink,
             replica_connection_pool: self.replica_connection_pool,
             batch_fee_input_provider,
-            api_contracts,
+            api_contracts: ArcSwap::from_pointee(api_contracts),
             vm_concurrency_limiter,
             storage_caches,
             sealer,
+            drain_mode: self.drain_mode,
             executor: TransactionExecutor::Real,
         }))
     }
@@ -245,13 +276,16 @@ pub struct TxSenderInner {
     pub replica_connection_pool: ConnectionPool<Core>,
     // Used to keep track of gas prices for the fee ticker.
     pub batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
-    pub(super) api_contracts: ApiContracts,
+    /// Contracts used in the API sandbox. Can be replaced while the server is running (see [`TxSender::replace_api_contracts()`]);
+    /// the replacement doesn't block readers.
+    pub(super) api_contracts: ArcSwap<ApiContracts>,
     /// Used to limit the amount of VMs that can be executed simultaneously.
     pub(super) vm_concurrency_limiter: Arc<VmConcurrencyLimiter>,
     // Caches used in VM execution.
     storage_caches: PostgresStorageCaches,
     /// Batch sealer used to check whether transaction can be executed by the sequencer.
     sealer: Arc<dyn ConditionalSealer>,
+    drain_mode: Option<watch::Receiver<bool>>,
     pub(super) executor: TransactionExecutor,
 }
 
@@ -265,6 +299,13 @@ impl std::fmt::Debug for TxSender {
 }
 
 impl TxSender {
+    fn is_draining(&self) -> bool {
+        self.0
+            .drain_mode
+            .as_ref()
+            .map_or(false, |drain_mode| *drain_mode.borrow())
+    }
+
     pub(crate) fn vm_concurrency_limiter(&self) -> Arc<VmConcurrencyLimiter> {
         Arc::clone(&self.0.vm_concurrency_limiter)
     }
@@ -273,6 +314,12 @@ impl TxSender {
         self.0.storage_caches.clone()
     }
 
+    /// Atomically replaces contracts used in the API sandbox (e.g., for `eth_call` and `eth_estimateGas`).
+    /// Requests that are already being processed continue using the previous contracts.
+    pub fn replace_api_contracts(&self, contracts: ApiContracts) {
+        self.0.api_contracts.store(Arc::new(contracts));
+    }
+
     async fn acquire_replica_connection(&self) -> anyhow::Result<Connection<'_, Core>> {
         self.0
             .replica_connection_pool
@@ -283,6 +330,10 @@ impl TxSender {
 
     #[tracing::instrument(skip(self, tx))]
     pub async fn submit_tx(&self, tx: L2Tx) -> Result<L2TxSubmissionResult, SubmitTxError> {
+        if self.is_draining() {
+            return Err(SubmitTxError::Draining);
+        }
+
         let stage_latency = SANDBOX_METRICS.submit_tx[&SubmitTxStage::Validate].start();
         self.validate_tx(&tx).await?;
         stage_latency.observe();
@@ -385,7 +436,7 @@ impl TxSender {
         TxSharedArgs {
             operator_account: AccountTreeId::new(self.0.sender_config.fee_account_addr),
             fee_input: self.0.batch_fee_input_provider.get_batch_fee_input().await,
-            base_system_contracts: self.0.api_contracts.eth_call.clone(),
+            base_system_contracts: self.0.api_contracts.load().eth_call.clone(),
             caches: self.storage_caches(),
             validation_computational_gas_limit: self
                 .0
@@ -395,6 +446,19 @@ impl TxSender {
         }
     }
 
+    /// Checks that the number of factory deps in a transaction doesn't exceed the limit imposed by the VM.
+    /// Used to reject transactions / calls early, without spinning up the VM.
+    fn validate_factory_deps(execute: &Execute) -> Result<(), SubmitTxError> {
+        let factory_deps_count = execute.factory_deps_length();
+        if factory_deps_count > MAX_NEW_FACTORY_DEPS {
+            return Err(SubmitTxError::TooManyFactoryDependencies(
+                factory_deps_count,
+                MAX_NEW_FACTORY_DEPS,
+            ));
+        }
+        Ok(())
+    }
+
     async fn validate_tx(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
         let max_gas = U256::from(u32::MAX);
         if tx.common_data.fee.gas_limit > max_gas
@@ -431,12 +495,7 @@ impl TxSender {
             );
             return Err(SubmitTxError::MaxPriorityFeeGreaterThanMaxFee);
         }
-        if tx.execute.factory_deps_length() > MAX_NEW_FACTORY_DEPS {
-            return Err(SubmitTxError::TooManyFactoryDependencies(
-                tx.execute.factory_deps_length(),
-                MAX_NEW_FACTORY_DEPS,
-            ));
-        }
+        Self::validate_factory_deps(&tx.execute)?;
 
         let intrinsic_consts = get_intrinsic_constants();
         assert!(
@@ -623,7 +682,7 @@ impl TxSender {
             fee_input,
             // We want to bypass the computation gas limit check for gas estimation
             validation_computational_gas_limit: BLOCK_GAS_LIMIT,
-            base_system_contracts: self.0.api_contracts.estimate_gas.clone(),
+            base_system_contracts: self.0.api_contracts.load().estimate_gas.clone(),
             caches: self.storage_caches(),
             chain_id: config.chain_id,
         }
@@ -635,6 +694,7 @@ impl TxSender {
         estimated_fee_scale_factor: f64,
         acceptable_overestimation: u32,
     ) -> Result<Fee, SubmitTxError> {
+        Self::validate_factory_deps(&tx.execute)?;
         let estimation_started_at = Instant::now();
 
         let mut connection = self.acquire_replica_connection().await?;
@@ -869,6 +929,7 @@ impl TxSender {
         block_args: BlockArgs,
         tx: L2Tx,
     ) -> Result<Vec<u8>, SubmitTxError> {
+        Self::validate_factory_deps(&tx.execute)?;
         let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
         let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
 
@@ -20,7 +20,10 @@ use zksync_web3_decl::{
 };
 
 use super::{tx_sink::TxSink, SubmitTxError};
-use crate::metrics::{TxStage, APP_METRICS};
+use crate::{
+    metrics::{TxStage, APP_METRICS},
+    sync_layer::MainNodeRetryConfig,
+};
 
 #[derive(Debug, Clone, Default)]
 pub(crate) struct TxCache {
@@ -110,6 +113,7 @@ impl TxCache {
 pub struct TxProxy {
     tx_cache: TxCache,
     client: HttpClient,
+    retries: MainNodeRetryConfig,
 }
 
 impl TxProxy {
@@ -117,9 +121,18 @@ impl TxProxy {
         Self {
             client,
             tx_cache: TxCache::default(),
+            retries: MainNodeRetryConfig::default(),
         }
     }
 
+    /// Retries requests to the main node failed with transient errors according to the specified policy.
+    /// Only reads are retried; transaction submissions are never retried.
+    #[must_use]
+    pub fn with_retries(mut self, retries: MainNodeRetryConfig) -> Self {
+        self.retries = retries;
+        self
+    }
+
     async fn submit_tx_impl(&self, tx: &L2Tx) -> EnrichedClientResult<H256> {
         let input_data = tx.common_data.input_data().expect("raw tx is absent");
         let raw_tx = zksync_types::Bytes(input_data.to_vec());
@@ -164,6 +177,15 @@ impl TxProxy {
     }
 
     async fn request_tx(&self, id: TransactionId) -> EnrichedClientResult<Option<Transaction>> {
+        self.retries
+            .retry("request_tx", || self.request_tx_once(id))
+            .await
+    }
+
+    async fn request_tx_once(
+        &self,
+        id: TransactionId,
+    ) -> EnrichedClientResult<Option<Transaction>> {
         match id {
             TransactionId::Block(BlockId::Hash(block), index) => {
                 self.client
@@ -195,10 +217,13 @@ impl TxProxy {
         &self,
         hash: H256,
     ) -> EnrichedClientResult<Option<TransactionDetails>> {
-        self.client
-            .get_transaction_details(hash)
-            .rpc_context("get_transaction_details")
-            .with_arg("hash", &hash)
+        self.retries
+            .retry("request_tx_details", || {
+                self.client
+                    .get_transaction_details(hash)
+                    .rpc_context("get_transaction_details")
+                    .with_arg("hash", &hash)
+            })
             .await
     }
 
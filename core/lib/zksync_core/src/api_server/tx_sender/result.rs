@@ -28,6 +28,8 @@ pub enum SubmitTxError {
     RateLimitExceeded,
     #[error("server shutting down")]
     ServerShuttingDown,
+    #[error("server is in drain mode and doesn't accept new transactions")]
+    Draining,
     #[error("failed to include transaction in the system. reason: {0}")]
     BootloaderFailure(String),
     #[error("failed to validate the transaction. reason: {0}")]
@@ -90,6 +92,7 @@ impl SubmitTxError {
             Self::Unexecutable(_) => "unexecutable",
             Self::RateLimitExceeded => "rate-limit-exceeded",
             Self::ServerShuttingDown => "shutting-down",
+            Self::Draining => "draining",
             Self::BootloaderFailure(_) => "bootloader-failure",
             Self::ValidationFailed(_) => "validation-failed",
             Self::FailedToChargeFee(_) => "failed-too-charge-fee",
@@ -1,12 +1,16 @@
 //! Tests for the transaction sender.
 
+use assert_matches::assert_matches;
 use zksync_types::{get_nonce_key, L1BatchNumber, StorageLog};
 
 use super::*;
 use crate::{
     api_server::execution_sandbox::{testonly::MockTransactionExecutor, VmConcurrencyBarrier},
     genesis::{insert_genesis_batch, GenesisParams},
-    utils::testonly::{create_miniblock, prepare_recovery_snapshot, MockBatchFeeParamsProvider},
+    utils::testonly::{
+        create_l2_transaction, create_miniblock, prepare_recovery_snapshot,
+        MockBatchFeeParamsProvider,
+    },
 };
 
 pub(crate) async fn create_test_tx_sender(
@@ -28,6 +32,7 @@ pub(crate) async fn create_test_tx_sender(
         pool,
         batch_fee_model_input_provider,
         storage_caches,
+        watch::channel(false).1,
     )
     .await;
 
@@ -139,3 +144,59 @@ async fn getting_nonce_for_account_after_snapshot_recovery() {
     let nonce = tx_sender.get_expected_nonce(missing_address).await.unwrap();
     assert_eq!(nonce, Nonce(0));
 }
+
+#[tokio::test]
+async fn rejecting_call_with_too_many_factory_deps() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    let block_args = BlockArgs::pending(&mut storage).await.unwrap();
+    drop(storage);
+
+    // The default mock executor panics if called, so the call must be rejected before VM execution.
+    let tx_executor = MockTransactionExecutor::default().into();
+    let (tx_sender, _) = create_test_tx_sender(pool, L2ChainId::default(), tx_executor).await;
+
+    let mut tx = create_l2_transaction(10, 100);
+    tx.execute.factory_deps = Some(vec![vec![0; 32]; MAX_NEW_FACTORY_DEPS + 1]);
+
+    let err = tx_sender
+        .eth_call(block_args, tx.clone())
+        .await
+        .unwrap_err();
+    assert_matches!(
+        err,
+        SubmitTxError::TooManyFactoryDependencies(count, MAX_NEW_FACTORY_DEPS)
+            if count == MAX_NEW_FACTORY_DEPS + 1
+    );
+
+    let err = tx_sender
+        .get_txs_fee_in_wei(tx.into(), 1.0, 1_000)
+        .await
+        .unwrap_err();
+    assert_matches!(err, SubmitTxError::TooManyFactoryDependencies(..));
+}
+
+#[tokio::test]
+async fn rejecting_transactions_in_drain_mode() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    drop(storage);
+
+    // The default mock executor panics if called, so the transaction must be rejected before VM execution.
+    let tx_executor = MockTransactionExecutor::default().into();
+    let (mut tx_sender, _) = create_test_tx_sender(pool, L2ChainId::default(), tx_executor).await;
+    let (drain_sender, drain_receiver) = watch::channel(false);
+    Arc::get_mut(&mut tx_sender.0).unwrap().drain_mode = Some(drain_receiver);
+    drain_sender.send_replace(true);
+
+    let tx = create_l2_transaction(10, 100);
+    let err = tx_sender.submit_tx(tx).await.unwrap_err();
+    assert_matches!(err, SubmitTxError::Draining);
+    assert_eq!(err.prom_error_code(), "draining");
+}
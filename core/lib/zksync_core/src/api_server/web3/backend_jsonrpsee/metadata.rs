@@ -1,6 +1,14 @@
 //! Method metadata.
 
-use std::{cell::RefCell, mem, sync::Arc, time::Instant};
+use std::{
+    cell::RefCell,
+    mem,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
 
 use thread_local::ThreadLocal;
 use zksync_types::api;
@@ -11,29 +19,47 @@ use zksync_web3_decl::{
 
 #[cfg(test)]
 use super::testonly::RecordedMethodCalls;
-use crate::api_server::web3::metrics::API_METRICS;
+use crate::api_server::web3::metrics::{CallResult, API_METRICS};
+
+/// Unique identifier of a client connection (a WebSocket session) to the API server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct ConnectionId(u64);
+
+impl ConnectionId {
+    /// Allocates a new connection ID that is unique for the process lifetime.
+    pub fn next() -> Self {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        Self(COUNTER.fetch_add(1, Ordering::Relaxed))
+    }
+}
 
 /// Metadata assigned to a JSON-RPC method call.
 #[derive(Debug, Clone)]
 pub(crate) struct MethodMetadata {
     pub name: &'static str,
     pub started_at: Instant,
+    /// Connection the call was received from. Only set for WebSocket connections.
+    pub connection_id: Option<ConnectionId>,
     /// Block ID requested by the call.
     pub block_id: Option<api::BlockId>,
     /// Difference between the latest block number and the requested block ID.
     pub block_diff: Option<u32>,
     /// Did this call return an app-level error?
     pub has_app_error: bool,
+    /// Was the app-level error caused by the server rather than by the client?
+    pub has_server_error: bool,
 }
 
 impl MethodMetadata {
-    fn new(name: &'static str) -> Self {
+    fn new(name: &'static str, connection_id: Option<ConnectionId>) -> Self {
         Self {
             name,
             started_at: Instant::now(),
+            connection_id,
             block_id: None,
             block_diff: None,
             has_app_error: false,
+            has_server_error: false,
         }
     }
 }
@@ -88,10 +114,22 @@ impl MethodTracer {
         }
     }
 
-    pub(super) fn new_call(self: &Arc<Self>, name: &'static str) -> MethodCall {
+    /// Returns the connection the current JSON-RPC method call was received from. Returns `None` for HTTP calls
+    /// or if called outside of JSON-RPC method handlers.
+    pub fn connection_id(&self) -> Option<ConnectionId> {
+        let cell = self.inner.get_or_default();
+        let metadata = cell.borrow();
+        metadata.as_ref()?.connection_id
+    }
+
+    pub(super) fn new_call(
+        self: &Arc<Self>,
+        name: &'static str,
+        connection_id: Option<ConnectionId>,
+    ) -> MethodCall {
         MethodCall {
             tracer: self.clone(),
-            meta: MethodMetadata::new(name),
+            meta: MethodMetadata::new(name, connection_id),
             is_completed: false,
         }
     }
@@ -101,6 +139,7 @@ impl MethodTracer {
         if let Some(metadata) = &mut *cell.borrow_mut() {
             API_METRICS.observe_web3_error(metadata.name, err);
             metadata.has_app_error = true;
+            metadata.has_server_error = CallResult::for_web3_error(err) == CallResult::ServerError;
         }
     }
 }
@@ -156,6 +195,7 @@ impl MethodCall {
             }
         }
         API_METRICS.observe_latency(meta);
+        API_METRICS.observe_call_result(meta, response.success_or_error);
         #[cfg(test)]
         self.tracer.recorder.observe_response(meta, response);
     }
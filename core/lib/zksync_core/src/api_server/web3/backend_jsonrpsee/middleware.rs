@@ -26,7 +26,7 @@ use zksync_web3_decl::jsonrpsee::{
     MethodResponse,
 };
 
-use super::metadata::{MethodCall, MethodTracer};
+use super::metadata::{ConnectionId, MethodCall, MethodTracer};
 use crate::api_server::web3::metrics::API_METRICS;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
@@ -50,6 +50,9 @@ struct LimitMiddlewareMetrics {
 #[vise::register]
 static METRICS: vise::Global<LimitMiddlewareMetrics> = vise::Global::new();
 
+/// Method name used in metadata (and thus in metric labels) for methods not registered on the server.
+const UNKNOWN_METHOD_NAME: &str = "other";
+
 /// A rate-limiting middleware.
 ///
 /// `jsonrpsee` will allocate the instance of this struct once per session.
@@ -114,18 +117,23 @@ pub(crate) struct MetadataMiddleware<S> {
     inner: S,
     registered_method_names: Arc<HashSet<&'static str>>,
     method_tracer: Arc<MethodTracer>,
+    connection_id: Option<ConnectionId>,
 }
 
 impl<S> MetadataMiddleware<S> {
+    /// Creates the middleware. `connection_id` should be set for the WebSocket transport, in which case
+    /// the middleware is instantiated once per session.
     pub fn new(
         inner: S,
         registered_method_names: Arc<HashSet<&'static str>>,
         method_tracer: Arc<MethodTracer>,
+        connection_id: Option<ConnectionId>,
     ) -> Self {
         Self {
             inner,
             registered_method_names,
             method_tracer,
+            connection_id,
         }
     }
 }
@@ -138,15 +146,16 @@ where
 
     fn call(&self, request: Request<'a>) -> Self::Future {
         // "Normalize" the method name by searching it in the set of all registered methods. This extends the lifetime
-        // of the name to `'static` and maps unknown methods to "", so that method name metric labels don't have unlimited cardinality.
+        // of the name to `'static` and maps unknown methods to "other", so that method name metric labels don't have
+        // unlimited cardinality.
         let method_name = self
             .registered_method_names
             .get(request.method_name())
             .copied()
-            .unwrap_or("");
+            .unwrap_or(UNKNOWN_METHOD_NAME);
 
         WithMethodCall {
-            call: self.method_tracer.new_call(method_name),
+            call: self.method_tracer.new_call(method_name, self.connection_id),
             inner: self.inner.call(request),
         }
     }
@@ -294,7 +303,7 @@ mod tests {
             };
 
             WithMethodCall {
-                call: method_tracer.new_call("test"),
+                call: method_tracer.new_call("test", None),
                 inner,
             }
         });
@@ -2,33 +2,81 @@
 //! Consists mostly of boilerplate code implementing the `jsonrpsee` server traits for the corresponding
 //! namespace structures defined in `zksync_core`.
 
+use zksync_dal::SqlxError;
 use zksync_web3_decl::{
     error::Web3Error,
     jsonrpsee::types::{error::ErrorCode, ErrorObjectOwned},
 };
 
 pub(crate) use self::{
-    metadata::{MethodMetadata, MethodTracer},
+    metadata::{ConnectionId, MethodMetadata, MethodTracer},
     middleware::{LimitMiddleware, MetadataMiddleware, ShutdownMiddleware, TrafficTracker},
 };
 use crate::api_server::tx_sender::SubmitTxError;
 
+/// "Limit exceeded" error code as per EIP-1474.
+const LIMIT_EXCEEDED_CODE: i32 = -32005;
+
 mod metadata;
 mod middleware;
 pub mod namespaces;
 #[cfg(test)]
 pub(crate) mod testonly;
 
+/// Internal error caused by the server being overloaded rather than by a bug. Such errors are reported to clients
+/// with a retryable error code, so that clients can back off and retry the request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TransientError {
+    /// Timed out acquiring a DB connection from the pool.
+    PoolExhausted,
+    /// Internal operation (e.g., VM execution) timed out.
+    Timeout,
+}
+
+impl TransientError {
+    fn new(err: &anyhow::Error) -> Option<Self> {
+        err.chain().find_map(|cause| {
+            if matches!(
+                cause.downcast_ref::<SqlxError>(),
+                Some(SqlxError::PoolTimedOut)
+            ) {
+                Some(Self::PoolExhausted)
+            } else if cause.is::<tokio::time::error::Elapsed>() {
+                Some(Self::Timeout)
+            } else {
+                None
+            }
+        })
+    }
+
+    fn message(self) -> &'static str {
+        match self {
+            Self::PoolExhausted => {
+                "Server is busy: no database connections available; try again later"
+            }
+            Self::Timeout => "Server is busy: request processing timed out; try again later",
+        }
+    }
+}
+
 impl MethodTracer {
     pub(crate) fn map_err(&self, err: Web3Error) -> ErrorObjectOwned {
         self.observe_error(&err);
 
+        let transient_error = match &err {
+            Web3Error::InternalError(err) => TransientError::new(err),
+            _ => None,
+        };
+
         let data = match &err {
             Web3Error::SubmitTransactionError(_, data) => Some(format!("0x{}", hex::encode(data))),
             Web3Error::ProxyError(_) => Some("0x".to_owned()),
             _ => None,
         };
         let code = match err {
+            Web3Error::InternalError(_) if transient_error.is_some() => {
+                ErrorCode::ServerIsBusy.code()
+            }
             Web3Error::NotImplemented => ErrorCode::MethodNotFound.code(),
             Web3Error::InternalError(_) => ErrorCode::InternalError.code(),
             Web3Error::NoBlock
@@ -41,11 +89,16 @@ impl MethodTracer {
             Web3Error::SubmitTransactionError(_, _)
             | Web3Error::SerializationError(_)
             | Web3Error::ProxyError(_) => 3,
-            Web3Error::TreeApiUnavailable => 6,
+            Web3Error::TreeApiUnavailable
+            | Web3Error::TreeProofsUnavailable
+            | Web3Error::CallTraceNotSampled => 6,
+            Web3Error::TooManyFilters(_) | Web3Error::ResponseTooLarge(_) => LIMIT_EXCEEDED_CODE,
         };
         let message = match err {
             // Do not expose internal error details to the client.
-            Web3Error::InternalError(_) => "Internal error".to_owned(),
+            Web3Error::InternalError(_) => transient_error
+                .map_or("Internal error", TransientError::message)
+                .to_owned(),
             Web3Error::ProxyError(err) => err.as_ref().to_string(),
             Web3Error::SubmitTransactionError(message, _) => message,
             _ => err.to_string(),
@@ -64,3 +117,42 @@ impl From<SubmitTxError> for Web3Error {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{future, time::Duration};
+
+    use anyhow::Context as _;
+
+    use super::*;
+
+    #[test]
+    fn mapping_pool_timeout() {
+        let err =
+            anyhow::Error::from(SqlxError::PoolTimedOut).context("acquire_connection_retried()");
+        let err = MethodTracer::default().map_err(Web3Error::InternalError(err));
+        assert_eq!(err.code(), ErrorCode::ServerIsBusy.code());
+        assert!(err.message().contains("try again"), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn mapping_vm_timeout() {
+        let elapsed = tokio::time::timeout(Duration::ZERO, future::pending::<()>())
+            .await
+            .unwrap_err();
+        let err = Err::<(), _>(elapsed)
+            .context("VM execution timed out")
+            .unwrap_err();
+        let err = MethodTracer::default().map_err(Web3Error::InternalError(err));
+        assert_eq!(err.code(), ErrorCode::ServerIsBusy.code());
+        assert!(err.message().contains("timed out"), "{err:?}");
+    }
+
+    #[test]
+    fn mapping_other_internal_errors() {
+        let err = anyhow::anyhow!("oops").context("failed doing something");
+        let err = MethodTracer::default().map_err(Web3Error::InternalError(err));
+        assert_eq!(err.code(), ErrorCode::InternalError.code());
+        assert_eq!(err.message(), "Internal error");
+    }
+}
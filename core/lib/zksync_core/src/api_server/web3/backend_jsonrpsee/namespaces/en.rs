@@ -39,4 +39,8 @@ impl EnNamespaceServer for EnNamespace {
             .await
             .map_err(|err| self.current_method().map_err(err))
     }
+
+    async fn sync_status(&self) -> RpcResult<Option<en::SyncStatus>> {
+        Ok(self.sync_status_impl())
+    }
 }
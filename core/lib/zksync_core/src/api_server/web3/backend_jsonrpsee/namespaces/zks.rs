@@ -2,8 +2,8 @@ use std::collections::HashMap;
 
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
-        TransactionDetails,
+        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, NodeInfo, NodeVersionInfo,
+        Proof, ProtocolVersion, TransactionDetails,
     },
     fee::Fee,
     fee_model::FeeParams,
@@ -157,6 +157,16 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn get_node_version_info(&self) -> RpcResult<NodeVersionInfo> {
+        self.get_node_version_info_impl()
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_node_info(&self) -> RpcResult<NodeInfo> {
+        Ok(self.get_node_info_impl())
+    }
+
     async fn get_proof(
         &self,
         address: Address,
@@ -7,7 +7,10 @@ use vise::{
     Metrics, Unit,
 };
 use zksync_types::api;
-use zksync_web3_decl::error::Web3Error;
+use zksync_web3_decl::{
+    error::Web3Error,
+    jsonrpsee::{helpers::MethodResponseResult, types::error::ErrorCode},
+};
 
 use super::{backend_jsonrpsee::MethodMetadata, ApiTransport, TypedFilter};
 
@@ -98,9 +101,13 @@ enum Web3ErrorKind {
     Proxy,
     TooManyTopics,
     FilterNotFound,
+    TooManyFilters,
     LogsLimitExceeded,
+    ResponseTooLarge,
     InvalidFilterBlockHash,
     TreeApiUnavailable,
+    CallTraceNotSampled,
+    TreeProofsUnavailable,
     Internal,
 }
 
@@ -114,14 +121,56 @@ impl Web3ErrorKind {
             Web3Error::SerializationError(_) => Self::TransactionSerialization,
             Web3Error::TooManyTopics => Self::TooManyTopics,
             Web3Error::FilterNotFound => Self::FilterNotFound,
+            Web3Error::TooManyFilters(_) => Self::TooManyFilters,
             Web3Error::LogsLimitExceeded(..) => Self::LogsLimitExceeded,
+            Web3Error::ResponseTooLarge(_) => Self::ResponseTooLarge,
             Web3Error::InvalidFilterBlockHash => Self::InvalidFilterBlockHash,
             Web3Error::TreeApiUnavailable => Self::TreeApiUnavailable,
+            Web3Error::CallTraceNotSampled => Self::CallTraceNotSampled,
+            Web3Error::TreeProofsUnavailable => Self::TreeProofsUnavailable,
             Web3Error::InternalError(_) | Web3Error::NotImplemented => Self::Internal,
         }
     }
 }
 
+/// Result of a Web3 call distinguishing between errors caused by the client (e.g., invalid call params)
+/// and by the server (e.g., an internal error or unavailable main node).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub(super) enum CallResult {
+    Success,
+    ClientError,
+    ServerError,
+}
+
+impl CallResult {
+    pub fn for_web3_error(err: &Web3Error) -> Self {
+        match err {
+            Web3Error::InternalError(_)
+            | Web3Error::ProxyError(_)
+            | Web3Error::TreeApiUnavailable
+            | Web3Error::TreeProofsUnavailable => Self::ServerError,
+            _ => Self::ClientError,
+        }
+    }
+
+    fn for_protocol_error(error_code: i32) -> Self {
+        if error_code == ErrorCode::InternalError.code()
+            || error_code == ErrorCode::ServerIsBusy.code()
+        {
+            Self::ServerError
+        } else {
+            Self::ClientError
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet)]
+pub(super) struct CallResultLabels {
+    pub method: &'static str,
+    pub result: CallResult,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
 #[metrics(rename_all = "snake_case")]
 enum ProtocolErrorOrigin {
@@ -169,8 +218,11 @@ pub(in crate::api_server) struct ApiMetrics {
     /// Number of application errors grouped by error kind and method name. Only collected for errors that were successfully routed
     /// to a method (i.e., this method is defined).
     web3_errors: Family<Web3ErrorLabels, Counter>,
-    /// Number of protocol errors grouped by error code and method name. Method name is not set for "method not found" errors.
+    /// Number of protocol errors grouped by error code and method name. Method name is `other` for "method not found" errors.
     web3_rpc_errors: Family<ProtocolErrorLabels, Counter>,
+    /// Number of finished Web3 calls grouped by method name and result. Method name is `other` for methods
+    /// not registered on the server, so that the label has bounded cardinality.
+    pub(super) web3_call_results: Family<CallResultLabels, Counter>,
     /// Number of transaction submission errors for a specific submission error reason.
     #[metrics(labels = ["reason"])]
     pub submit_tx_error: LabeledFamily<&'static str, Counter>,
@@ -193,6 +245,26 @@ impl ApiMetrics {
         }
     }
 
+    /// Observes the result of a finished RPC call.
+    pub fn observe_call_result(&self, meta: &MethodMetadata, response: MethodResponseResult) {
+        let result = match response {
+            MethodResponseResult::Success => CallResult::Success,
+            MethodResponseResult::Failed(_) if meta.has_app_error => {
+                if meta.has_server_error {
+                    CallResult::ServerError
+                } else {
+                    CallResult::ClientError
+                }
+            }
+            MethodResponseResult::Failed(error_code) => CallResult::for_protocol_error(error_code),
+        };
+        let labels = CallResultLabels {
+            method: meta.name,
+            result,
+        };
+        self.web3_call_results[&labels].inc();
+    }
+
     /// Observes latency of a dropped RPC call.
     pub fn observe_dropped_call(&self, meta: &MethodMetadata) {
         let latency = meta.started_at.elapsed();
@@ -10,7 +10,7 @@ use tokio::{
 };
 use tower_http::{cors::CorsLayer, metrics::InFlightRequestsLayer};
 use zksync_dal::{ConnectionPool, Core};
-use zksync_health_check::{HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_types::MiniblockNumber;
 use zksync_web3_decl::{
     jsonrpsee::{
@@ -26,7 +26,8 @@ use zksync_web3_decl::{
 
 use self::{
     backend_jsonrpsee::{
-        LimitMiddleware, MetadataMiddleware, MethodTracer, ShutdownMiddleware, TrafficTracker,
+        ConnectionId, LimitMiddleware, MetadataMiddleware, MethodTracer, ShutdownMiddleware,
+        TrafficTracker,
     },
     mempool_cache::MempoolCache,
     metrics::API_METRICS,
@@ -106,6 +107,59 @@ impl Namespace {
         Self::En,
         Self::Pubsub,
     ];
+
+    const ALL: &'static [Self] = &[
+        Self::Eth,
+        Self::Net,
+        Self::Web3,
+        Self::Debug,
+        Self::Zks,
+        Self::En,
+        Self::Pubsub,
+        Self::Snapshots,
+    ];
+
+    /// Returns the prefix of the methods in this namespace.
+    fn method_prefix(&self) -> &'static str {
+        match self {
+            // Pubsub methods (`eth_subscribe` / `eth_unsubscribe`) live in the `eth_` namespace.
+            Self::Eth | Self::Pubsub => "eth_",
+            Self::Net => "net_",
+            Self::Web3 => "web3_",
+            Self::Debug => "debug_",
+            Self::Zks => "zks_",
+            Self::En => "en_",
+            Self::Snapshots => "snapshots_",
+        }
+    }
+}
+
+/// Checks that extra RPC methods don't intrude into the built-in namespaces, regardless of whether
+/// these namespaces are enabled on a particular server.
+fn validate_extra_methods(methods: &RpcModule<()>) -> anyhow::Result<()> {
+    for method_name in methods.method_names() {
+        let reserved_namespace = Namespace::ALL
+            .iter()
+            .find(|namespace| method_name.starts_with(namespace.method_prefix()));
+        if let Some(namespace) = reserved_namespace {
+            anyhow::bail!(
+                "Extra RPC method `{method_name}` uses prefix `{}` reserved for the built-in {namespace:?} namespace",
+                namespace.method_prefix()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Converts the request body size limit to the type used by `jsonrpsee`. Unlike a plain cast, oversized limits
+/// are reported as an error rather than being silently truncated.
+fn request_body_size_limit(limit: usize) -> anyhow::Result<u32> {
+    u32::try_from(limit).with_context(|| {
+        format!(
+            "request body size limit {limit} exceeds the maximum supported value {}",
+            u32::MAX
+        )
+    })
 }
 
 /// Handles to the initialized API server.
@@ -123,11 +177,14 @@ struct OptionalApiParams {
     vm_barrier: Option<VmConcurrencyBarrier>,
     sync_state: Option<SyncState>,
     filters_limit: Option<usize>,
+    filters_limit_per_connection: Option<usize>,
     subscriptions_limit: Option<usize>,
     batch_request_size_limit: Option<usize>,
     response_body_size_limit: Option<usize>,
+    request_body_size_limit: Option<usize>,
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
     tree_api: Option<Arc<dyn TreeApiClient>>,
+    extra_methods: Option<RpcModule<()>>,
     pub_sub_events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
 }
 
@@ -214,6 +271,13 @@ impl ApiBuilder {
         self
     }
 
+    /// Limits the number of filters that can be installed by a single connection. Only applies to the WebSocket
+    /// transport, since HTTP requests cannot be reliably attributed to a client.
+    pub fn with_filter_limit_per_connection(mut self, limit: usize) -> Self {
+        self.optional.filters_limit_per_connection = Some(limit);
+        self
+    }
+
     pub fn with_subscriptions_limit(mut self, subscriptions_limit: usize) -> Self {
         self.optional.subscriptions_limit = Some(subscriptions_limit);
         self
@@ -229,6 +293,13 @@ impl ApiBuilder {
         self
     }
 
+    /// Limits the size of a request body (for WebSocket, the size of a single message). Oversized requests
+    /// are rejected before they are parsed, with the HTTP server responding with 413 Payload Too Large.
+    pub fn with_request_body_size_limit(mut self, request_body_size_limit: usize) -> Self {
+        self.optional.request_body_size_limit = Some(request_body_size_limit);
+        self
+    }
+
     pub fn with_websocket_requests_per_minute_limit(
         mut self,
         websocket_requests_per_minute_limit: NonZeroU32,
@@ -259,6 +330,14 @@ impl ApiBuilder {
         self
     }
 
+    /// Registers extra RPC methods served alongside the built-in namespaces, e.g. custom read-only methods
+    /// for bespoke infrastructure. Methods must not use prefixes of the built-in namespaces (`eth_`, `zks_` etc.),
+    /// even if the corresponding namespace is disabled; this is checked when building the server.
+    pub fn with_extra_methods(mut self, methods: RpcModule<()>) -> Self {
+        self.optional.extra_methods = Some(methods);
+        self
+    }
+
     #[cfg(test)]
     fn with_pub_sub_events(mut self, sender: mpsc::UnboundedSender<PubSubEvent>) -> Self {
         self.optional.pub_sub_events_sender = Some(sender);
@@ -280,6 +359,12 @@ impl ApiBuilder {
             ApiTransport::WebSocket(_) => "ws_api",
         };
         let (_, health_updater) = ReactiveHealthCheck::new(health_check_name);
+        if let Some(extra_methods) = &self.optional.extra_methods {
+            validate_extra_methods(extra_methods)?;
+        }
+        if let Some(limit) = self.optional.request_body_size_limit {
+            request_body_size_limit(limit)?;
+        }
 
         Ok(ApiServer {
             pool: self.pool,
@@ -322,6 +407,7 @@ impl ApiServer {
             } else {
                 Some(Arc::new(Mutex::new(Filters::new(
                     self.optional.filters_limit,
+                    self.optional.filters_limit_per_connection,
                 ))))
             };
 
@@ -336,16 +422,18 @@ impl ApiServer {
             mempool_cache,
             last_sealed_miniblock,
             tree_api: self.optional.tree_api,
+            response_body_size_limit: self.optional.response_body_size_limit,
         })
     }
 
     async fn build_rpc_module(
-        self,
+        mut self,
         pub_sub: Option<EthSubscribe>,
         last_sealed_miniblock: SealedMiniblockNumber,
         mempool_cache: MempoolCache,
     ) -> anyhow::Result<RpcModule<()>> {
         let namespaces = self.namespaces.clone();
+        let extra_methods = self.optional.extra_methods.take();
         let zksync_network_id = self.config.l2_chain_id;
         let rpc_state = self
             .build_rpc_state(last_sealed_miniblock, mempool_cache)
@@ -386,6 +474,11 @@ impl ApiServer {
             rpc.merge(SnapshotsNamespace::new(rpc_state).into_rpc())
                 .expect("Can't merge snapshots namespace");
         }
+        if let Some(extra_methods) = extra_methods {
+            // Collisions with built-in methods are ruled out by `validate_extra_methods()` on server build.
+            rpc.merge(extra_methods)
+                .context("cannot merge extra RPC methods")?;
+        }
         Ok(rpc)
     }
 
@@ -402,6 +495,13 @@ impl ApiServer {
         } else if self.optional.filters_limit.is_none() {
             tracing::warn!("Filters limit is not set - unlimited filters are allowed");
         }
+        if matches!(&self.transport, ApiTransport::Http(_))
+            && self.optional.filters_limit_per_connection.is_some()
+        {
+            tracing::warn!(
+                "`filters_limit_per_connection` is ignored for HTTP transport, use WebSocket instead"
+            );
+        }
 
         if self.namespaces.contains(&Namespace::Pubsub)
             && matches!(&self.transport, ApiTransport::Http(_))
@@ -548,6 +648,11 @@ impl ApiServer {
             .optional
             .response_body_size_limit
             .map_or(u32::MAX, |limit| limit as u32);
+        let request_body_size_limit = self
+            .optional
+            .request_body_size_limit
+            .map(request_body_size_limit)
+            .transpose()?;
         let websocket_requests_per_minute_limit = self.optional.websocket_requests_per_minute_limit;
         let subscriptions_limit = self.optional.subscriptions_limit;
         let vm_barrier = self.optional.vm_barrier.clone();
@@ -598,7 +703,14 @@ impl ApiServer {
                 ShutdownMiddleware::new(svc, traffic_tracker_for_middleware.clone())
             })
             .layer_fn(move |svc| {
-                MetadataMiddleware::new(svc, registered_method_names.clone(), method_tracer.clone())
+                // For WS, the middleware is instantiated once per session, so we can allocate connection IDs here.
+                let connection_id = (!is_http).then(ConnectionId::next);
+                MetadataMiddleware::new(
+                    svc,
+                    registered_method_names.clone(),
+                    method_tracer.clone(),
+                    connection_id,
+                )
             })
             .option_layer((!is_http).then(|| {
                 tower::layer::layer_fn(move |svc| {
@@ -612,6 +724,12 @@ impl ApiServer {
             .max_response_body_size(response_body_size_limit)
             .set_batch_request_config(batch_request_config)
             .set_rpc_middleware(rpc_middleware);
+        // If the limit is not set, we use the `jsonrpsee` default (10 MiB) rather than removing the limit altogether.
+        let server_builder = if let Some(limit) = request_body_size_limit {
+            server_builder.max_request_body_size(limit)
+        } else {
+            server_builder
+        };
 
         let (local_addr, server_handle) = if is_http {
             // HTTP-specific settings
@@ -635,7 +753,9 @@ impl ApiServer {
         })?;
         tracing::info!("Initialized {transport_str} API on {local_addr:?}");
         local_addr_sender.send(local_addr).ok();
-        health_updater.update(HealthStatus::Ready.into());
+        let health = Health::from(HealthStatus::Ready)
+            .with_details(serde_json::json!({ "local_addr": local_addr }));
+        health_updater.update(health);
 
         // We want to be able to immediately stop the server task if the server stops on its own for whatever reason.
         // Hence, we monitor `stop_receiver` on a separate Tokio task.
@@ -12,26 +12,27 @@ use zksync_types::{
     l2::L2Tx,
     transaction_request::CallRequest,
     vm_trace::Call,
-    AccountTreeId, H256,
+    AccountTreeId, MiniblockNumber, H256,
 };
 use zksync_web3_decl::error::Web3Error;
 
-use crate::api_server::{
-    execution_sandbox::{ApiTracer, TxSharedArgs},
-    tx_sender::{ApiContracts, TxSenderConfig},
-    web3::{backend_jsonrpsee::MethodTracer, state::RpcState},
+use crate::{
+    api_server::{
+        execution_sandbox::{ApiTracer, TxSharedArgs},
+        tx_sender::TxSenderConfig,
+        web3::{backend_jsonrpsee::MethodTracer, state::RpcState},
+    },
+    utils::is_sampled_for_call_traces,
 };
 
 #[derive(Debug, Clone)]
 pub(crate) struct DebugNamespace {
     batch_fee_input: BatchFeeInput,
     state: RpcState,
-    api_contracts: ApiContracts,
 }
 
 impl DebugNamespace {
     pub async fn new(state: RpcState) -> Self {
-        let api_contracts = ApiContracts::load_from_disk();
         Self {
             // For now, the same scaling is used for both the L1 gas price and the pubdata price
             batch_fee_input: state
@@ -44,7 +45,6 @@ impl DebugNamespace {
                 )
                 .await,
             state,
-            api_contracts,
         }
     }
 
@@ -116,6 +116,18 @@ impl DebugNamespace {
             .get_call_trace(tx_hash)
             .await
             .context("get_call_trace")?;
+        drop(connection);
+
+        let call_trace = match call_trace {
+            None if !is_sampled_for_call_traces(
+                tx_hash,
+                self.state.api_config.call_traces_sampling_rate,
+            ) =>
+            {
+                self.replay_transaction(tx_hash, only_top_call).await?
+            }
+            call_trace => call_trace,
+        };
         Ok(call_trace.map(|call_trace| {
             let mut result: DebugCall = call_trace.into();
             if only_top_call {
@@ -125,6 +137,104 @@ impl DebugNamespace {
         }))
     }
 
+    /// Re-executes a transaction that was not sampled for call traces in order to restore its trace.
+    /// Returns `None` if the transaction is unknown or is not included into a miniblock yet.
+    ///
+    /// The transaction is executed on top of the state at the end of the previous miniblock after the transactions
+    /// preceding it in its miniblock. The block context and fee input are taken from the previous miniblock,
+    /// so gas-related values in the trace may slightly differ from the original execution.
+    async fn replay_transaction(
+        &self,
+        tx_hash: H256,
+        only_top_call: bool,
+    ) -> Result<Option<Call>, Web3Error> {
+        let mut connection = self.state.connection_pool.connection_tagged("api").await?;
+        let api_tx = connection
+            .transactions_web3_dal()
+            .get_transaction_by_hash(tx_hash, self.state.api_config.l2_chain_id)
+            .await
+            .context("get_transaction_by_hash")?;
+        let Some(block_number) = api_tx.and_then(|tx| tx.block_number) else {
+            return Ok(None);
+        };
+        let miniblock_number = MiniblockNumber(block_number.as_u32());
+        let Some(prev_miniblock_number) = miniblock_number.0.checked_sub(1) else {
+            return Err(Web3Error::CallTraceNotSampled);
+        };
+
+        let mut preceding_txs = connection
+            .transactions_web3_dal()
+            .get_raw_miniblock_transactions(miniblock_number)
+            .await
+            .context("get_raw_miniblock_transactions")?;
+        let tx_position = preceding_txs
+            .iter()
+            .position(|tx| tx.hash() == tx_hash)
+            .with_context(|| {
+                format!("transaction {tx_hash:?} is missing from miniblock #{miniblock_number}")
+            })?;
+        let tx = preceding_txs.remove(tx_position);
+        preceding_txs.truncate(tx_position);
+
+        let block_id = BlockId::Number(BlockNumber::Number(prev_miniblock_number.into()));
+        let block_args = self
+            .state
+            .resolve_block_args(&mut connection, block_id)
+            .await?;
+        drop(connection);
+
+        let vm_permit = self
+            .state
+            .tx_sender
+            .vm_concurrency_limiter()
+            .acquire_for_trace()
+            .await;
+        let vm_permit = vm_permit.context("cannot acquire VM permit")?;
+
+        let call_tracer_result = Arc::new(OnceCell::default());
+        let custom_tracers = if only_top_call {
+            vec![]
+        } else {
+            vec![ApiTracer::CallTracer(call_tracer_result.clone())]
+        };
+        let executor = &self.state.tx_sender.0.executor;
+        let result = executor
+            .replay_tx_in_sandbox(
+                vm_permit,
+                self.shared_args(),
+                self.state.connection_pool.clone(),
+                preceding_txs,
+                tx.clone(),
+                block_args,
+                custom_tracers,
+            )
+            .await?;
+
+        let (output, revert_reason) = match result.result {
+            ExecutionResult::Success { output, .. } => (output, None),
+            ExecutionResult::Revert { output } => (vec![], Some(output.to_string())),
+            ExecutionResult::Halt { reason } => {
+                let err =
+                    anyhow::anyhow!("transaction {tx_hash:?} halted on re-execution: {reason}");
+                return Err(err.into());
+            }
+        };
+        // The tracer is dropped together with the VM, so this is the only remaining copy of the `Arc`.
+        let trace = Arc::try_unwrap(call_tracer_result)
+            .unwrap()
+            .take()
+            .unwrap_or_default();
+        Ok(Some(Call::new_high_level(
+            tx.gas_limit().as_u32(),
+            result.statistics.gas_used,
+            tx.execute.value,
+            tx.execute.calldata,
+            output,
+            revert_reason,
+            trace,
+        )))
+    }
+
     #[tracing::instrument(skip(self, request, block_id))]
     pub async fn debug_trace_call_impl(
         &self,
@@ -158,7 +268,7 @@ impl DebugNamespace {
             .state
             .tx_sender
             .vm_concurrency_limiter()
-            .acquire()
+            .acquire_for_trace()
             .await;
         let vm_permit = vm_permit.context("cannot acquire VM permit")?;
 
@@ -216,7 +326,7 @@ impl DebugNamespace {
         TxSharedArgs {
             operator_account: AccountTreeId::default(),
             fee_input: self.batch_fee_input,
-            base_system_contracts: self.api_contracts.eth_call.clone(),
+            base_system_contracts: self.state.tx_sender.0.api_contracts.load().eth_call.clone(),
             caches: self.state.tx_sender.storage_caches().clone(),
             validation_computational_gas_limit: BLOCK_GAS_LIMIT,
             chain_id: sender_config.chain_id,
@@ -39,6 +39,12 @@ impl EnNamespace {
         &self.state.current_method
     }
 
+    #[tracing::instrument(skip(self))]
+    pub fn sync_status_impl(&self) -> Option<en::SyncStatus> {
+        // If there is no sync state, the node is the main node.
+        Some(self.state.sync_state.as_ref()?.status())
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn sync_l2_block_impl(
         &self,
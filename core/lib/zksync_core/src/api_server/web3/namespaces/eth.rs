@@ -1,5 +1,7 @@
+use std::io;
+
 use anyhow::Context as _;
-use zksync_dal::CoreDal;
+use zksync_dal::{Connection, Core, CoreDal};
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
     api::{
@@ -27,6 +29,23 @@ use crate::api_server::web3::{
 
 pub const EVENT_TOPIC_NUMBER_LIMIT: usize = 4;
 pub const PROTOCOL_VERSION: &str = "zks/1";
+/// Number of logs loaded from Postgres at once when serving `eth_getLogs` and related methods.
+const LOGS_CHUNK_SIZE: usize = 1_000;
+
+/// [`io::Write`] implementation that only counts the number of written bytes.
+#[derive(Debug, Default)]
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
 
 #[derive(Debug)]
 pub(crate) struct EthNamespace {
@@ -506,10 +525,11 @@ impl EthNamespace {
         let next_block_number = last_block_number + 1;
         drop(storage);
 
-        Ok(installed_filters
+        let connection_id = self.current_method().connection_id();
+        installed_filters
             .lock()
             .await
-            .add(TypedFilter::Blocks(next_block_number)))
+            .add(TypedFilter::Blocks(next_block_number), connection_id)
     }
 
     #[tracing::instrument(skip(self, filter))]
@@ -527,10 +547,11 @@ impl EthNamespace {
 
         self.state.resolve_filter_block_hash(&mut filter).await?;
         let from_block = self.state.get_filter_from_block(&filter).await?;
-        Ok(installed_filters
+        let connection_id = self.current_method().connection_id();
+        installed_filters
             .lock()
             .await
-            .add(TypedFilter::Events(filter, from_block)))
+            .add(TypedFilter::Events(filter, from_block), connection_id)
     }
 
     #[tracing::instrument(skip(self))]
@@ -540,12 +561,9 @@ impl EthNamespace {
             .installed_filters
             .as_ref()
             .ok_or(Web3Error::NotImplemented)?;
-        Ok(installed_filters
-            .lock()
-            .await
-            .add(TypedFilter::PendingTransactions(
-                chrono::Utc::now().naive_utc(),
-            )))
+        let connection_id = self.current_method().connection_id();
+        let filter = TypedFilter::PendingTransactions(chrono::Utc::now().naive_utc());
+        installed_filters.lock().await.add(filter, connection_id)
     }
 
     #[tracing::instrument(skip(self))]
@@ -796,16 +814,57 @@ impl EthNamespace {
                     }
                 }
 
-                let logs = storage
-                    .events_web3_dal()
-                    .get_logs(get_logs_filter, i32::MAX as usize)
-                    .await
-                    .context("get_logs")?;
+                let logs = self.load_logs(&mut storage, &get_logs_filter).await?;
                 *from_block = to_block + 1;
                 FilterChanges::Logs(logs)
             }
         })
     }
+
+    /// Loads logs matching the filter in chunks. If the response body size limit is set, the serialized size
+    /// of loaded logs is tracked, and loading is aborted as soon as the limit is exceeded, so that the node
+    /// doesn't need to materialize an arbitrarily large response.
+    async fn load_logs(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        filter: &GetLogsFilter,
+    ) -> Result<Vec<Log>, Web3Error> {
+        let size_limit = self.state.response_body_size_limit;
+        let mut logs = vec![];
+        let mut serialized_size = ByteCounter::default();
+        let mut cursor = None;
+        loop {
+            let chunk = storage
+                .events_web3_dal()
+                .get_logs_after(filter, cursor, LOGS_CHUNK_SIZE)
+                .await
+                .context("get_logs_after")?;
+            let is_last_chunk = chunk.len() < LOGS_CHUNK_SIZE;
+
+            if let Some(size_limit) = size_limit {
+                for log in &chunk {
+                    serde_json::to_writer(&mut serialized_size, log)
+                        .context("failed serializing log")?;
+                    serialized_size.0 += 1; // separator in the JSON array
+                    if serialized_size.0 > size_limit {
+                        return Err(Web3Error::ResponseTooLarge(size_limit));
+                    }
+                }
+            }
+            if let Some(last_log) = chunk.last() {
+                let miniblock_number = last_log.block_number.context("no block number")?;
+                let log_index = last_log.log_index.context("no log index")?;
+                cursor = Some((
+                    MiniblockNumber(miniblock_number.as_u32()),
+                    log_index.as_u32(),
+                ));
+            }
+            logs.extend(chunk);
+            if is_last_chunk {
+                return Ok(logs);
+            }
+        }
+    }
 }
 
 // Bogus methods.
@@ -6,8 +6,8 @@ use zksync_mini_merkle_tree::MiniMerkleTree;
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, GetLogsFilter, L1BatchDetails, L2ToL1LogProof, Proof,
-        ProtocolVersion, StorageProof, TransactionDetails,
+        BlockDetails, BridgeAddresses, GetLogsFilter, L1BatchDetails, L2ToL1LogProof, NodeInfo,
+        NodeVersionInfo, Proof, ProtocolVersion, StorageProof, TransactionDetails,
     },
     fee::Fee,
     fee_model::FeeParams,
@@ -489,6 +489,32 @@ impl ZksNamespace {
         Ok(protocol_version)
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_node_version_info_impl(&self) -> Result<NodeVersionInfo, Web3Error> {
+        let mut storage = self.connection().await?;
+        let last_used_protocol_version = storage
+            .protocol_versions_dal()
+            .last_used_version_id()
+            .await
+            .context("last_used_version_id")?;
+        Ok(NodeVersionInfo {
+            last_used_protocol_version: last_used_protocol_version.map(|version| version as u16),
+            node_version: self.state.api_config.node_version.clone(),
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn get_node_info_impl(&self) -> NodeInfo {
+        let api_config = &self.state.api_config;
+        NodeInfo {
+            components: api_config.node_components.clone(),
+            node_version: api_config.node_version.clone(),
+            l1_chain_id: api_config.l1_chain_id,
+            l2_chain_id: api_config.l2_chain_id,
+            consensus_enabled: api_config.consensus_enabled,
+        }
+    }
+
     #[tracing::instrument(skip_all)]
     pub async fn get_proofs_impl(
         &self,
@@ -510,6 +536,7 @@ impl ZksNamespace {
         let proofs = match proofs_result {
             Ok(proofs) => proofs,
             Err(TreeApiError::NotReady) => return Err(Web3Error::TreeApiUnavailable),
+            Err(TreeApiError::ProofsUnavailable) => return Err(Web3Error::TreeProofsUnavailable),
             Err(TreeApiError::NoVersion(err)) => {
                 return if err.missing_version > err.version_count {
                     Ok(None)
@@ -1,4 +1,5 @@
 use std::{
+    collections::HashMap,
     future::Future,
     sync::{
         atomic::{AtomicU32, Ordering},
@@ -20,7 +21,7 @@ use zksync_types::{
 use zksync_web3_decl::{error::Web3Error, types::Filter};
 
 use super::{
-    backend_jsonrpsee::MethodTracer,
+    backend_jsonrpsee::{ConnectionId, MethodTracer},
     mempool_cache::MempoolCache,
     metrics::{FilterType, FILTER_METRICS},
     TypedFilter,
@@ -32,6 +33,7 @@ use crate::{
         tx_sender::{tx_sink::TxSink, TxSender},
     },
     sync_layer::SyncState,
+    Component,
 };
 
 #[derive(Debug)]
@@ -94,6 +96,15 @@ pub struct InternalApiConfig {
     pub filters_disabled: bool,
     pub mempool_cache_update_interval: Duration,
     pub mempool_cache_size: usize,
+    /// Version of the node binary reported via the API, if known.
+    pub node_version: Option<String>,
+    /// Components run by the node reported via the API.
+    pub node_components: Vec<String>,
+    /// Whether the node runs consensus; reported via the API.
+    pub consensus_enabled: bool,
+    /// Fraction of transactions (from 0.0 to 1.0) for which call traces are saved by the node. Unsampled transactions
+    /// are re-executed in the sandbox by `debug_traceTransaction`.
+    pub call_traces_sampling_rate: f64,
 }
 
 impl InternalApiConfig {
@@ -123,8 +134,22 @@ impl InternalApiConfig {
             filters_disabled: web3_config.filters_disabled,
             mempool_cache_update_interval: web3_config.mempool_cache_update_interval(),
             mempool_cache_size: web3_config.mempool_cache_size(),
+            node_version: None,
+            node_components: Vec::new(),
+            consensus_enabled: false,
+            call_traces_sampling_rate: 1.0,
         }
     }
+
+    /// Reports the specified main node components via the API.
+    pub fn with_components(mut self, components: &[Component]) -> Self {
+        self.node_components = components
+            .iter()
+            .map(|component| component.name().to_owned())
+            .collect();
+        self.consensus_enabled = components.contains(&Component::Consensus);
+        self
+    }
 }
 
 /// Thread-safe updatable information about the last sealed miniblock number.
@@ -217,6 +242,8 @@ pub(crate) struct RpcState {
     pub(super) start_info: BlockStartInfo,
     pub(super) mempool_cache: MempoolCache,
     pub(super) last_sealed_miniblock: SealedMiniblockNumber,
+    /// Response body size limit enforced when loading potentially large responses (e.g., logs).
+    pub(super) response_body_size_limit: Option<usize>,
 }
 
 impl RpcState {
@@ -395,11 +422,17 @@ impl RpcState {
 
 /// Contains mapping from index to `Filter`s with optional location.
 #[derive(Debug)]
-pub(crate) struct Filters(LruCache<U256, InstalledFilter>);
+pub(crate) struct Filters {
+    state: LruCache<U256, InstalledFilter>,
+    per_connection_limit: Option<usize>,
+    /// Number of installed filters for each connection. Only contains connections with at least one filter.
+    per_connection_counts: HashMap<ConnectionId, usize>,
+}
 
 #[derive(Debug)]
 struct InstalledFilter {
     pub filter: TypedFilter,
+    connection_id: Option<ConnectionId>,
     _guard: GaugeGuard,
     created_at: Instant,
     last_request: Instant,
@@ -407,10 +440,11 @@ struct InstalledFilter {
 }
 
 impl InstalledFilter {
-    pub fn new(filter: TypedFilter) -> Self {
+    pub fn new(filter: TypedFilter, connection_id: Option<ConnectionId>) -> Self {
         let guard = FILTER_METRICS.filter_count[&FilterType::from(&filter)].inc_guard(1);
         Self {
             filter,
+            connection_id,
             _guard: guard,
             created_at: Instant::now(),
             last_request: Instant::now(),
@@ -440,34 +474,69 @@ impl Drop for InstalledFilter {
 }
 
 impl Filters {
-    /// Instantiates `Filters` with given max capacity.
-    pub fn new(max_cap: Option<usize>) -> Self {
+    /// Instantiates `Filters` with given max capacity and max number of filters per connection.
+    pub fn new(max_cap: Option<usize>, per_connection_limit: Option<usize>) -> Self {
         let state = match max_cap {
             Some(max_cap) => {
                 LruCache::new(max_cap.try_into().expect("Filter capacity should not be 0"))
             }
             None => LruCache::unbounded(),
         };
-        Self(state)
+        Self {
+            state,
+            per_connection_limit,
+            per_connection_counts: HashMap::new(),
+        }
     }
 
-    /// Adds filter to the state and returns its key.
-    pub fn add(&mut self, filter: TypedFilter) -> U256 {
+    /// Adds filter to the state and returns its key. Fails if the connection has reached its limit
+    /// on installed filters.
+    pub fn add(
+        &mut self,
+        filter: TypedFilter,
+        connection_id: Option<ConnectionId>,
+    ) -> Result<U256, Web3Error> {
+        if let (Some(connection_id), Some(limit)) = (connection_id, self.per_connection_limit) {
+            let count = self.per_connection_counts.get(&connection_id).copied();
+            if count.unwrap_or(0) >= limit {
+                return Err(Web3Error::TooManyFilters(limit));
+            }
+        }
+
         let idx = loop {
             let val = H256::random().to_fixed_bytes().into();
-            if !self.0.contains(&val) {
+            if !self.state.contains(&val) {
                 break val;
             }
         };
 
-        self.0.push(idx, InstalledFilter::new(filter));
+        if let Some(connection_id) = connection_id {
+            *self.per_connection_counts.entry(connection_id).or_default() += 1;
+        }
+        let evicted = self
+            .state
+            .push(idx, InstalledFilter::new(filter, connection_id));
+        if let Some((_, evicted_filter)) = evicted {
+            self.on_filter_removed(&evicted_filter);
+        }
+        Ok(idx)
+    }
 
-        idx
+    fn on_filter_removed(&mut self, filter: &InstalledFilter) {
+        let Some(connection_id) = filter.connection_id else {
+            return;
+        };
+        if let Some(count) = self.per_connection_counts.get_mut(&connection_id) {
+            *count -= 1;
+            if *count == 0 {
+                self.per_connection_counts.remove(&connection_id);
+            }
+        }
     }
 
     /// Retrieves filter from the state.
     pub fn get_and_update_stats(&mut self, index: U256) -> Option<TypedFilter> {
-        let installed_filter = self.0.get_mut(&index)?;
+        let installed_filter = self.state.get_mut(&index)?;
 
         installed_filter.update_stats();
 
@@ -476,53 +545,98 @@ impl Filters {
 
     /// Updates filter in the state.
     pub fn update(&mut self, index: U256, new_filter: TypedFilter) {
-        if let Some(installed_filter) = self.0.get_mut(&index) {
+        if let Some(installed_filter) = self.state.get_mut(&index) {
             installed_filter.filter = new_filter;
         }
     }
 
     /// Removes filter from the map.
     pub fn remove(&mut self, index: U256) -> bool {
-        self.0.pop(&index).is_some()
+        let Some(removed_filter) = self.state.pop(&index) else {
+            return false;
+        };
+        self.on_filter_removed(&removed_filter);
+        true
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use assert_matches::assert_matches;
     use chrono::NaiveDateTime;
 
     #[test]
     fn test_filters_functionality() {
         use super::*;
 
-        let mut filters = Filters::new(Some(2));
+        let mut filters = Filters::new(Some(2), None);
 
         let filter1 = TypedFilter::Events(Filter::default(), MiniblockNumber::default());
         let filter2 = TypedFilter::Blocks(MiniblockNumber::default());
         let filter3 = TypedFilter::PendingTransactions(NaiveDateTime::default());
 
-        let idx1 = filters.add(filter1.clone());
-        let idx2 = filters.add(filter2);
-        let idx3 = filters.add(filter3);
+        let idx1 = filters.add(filter1.clone(), None).unwrap();
+        let idx2 = filters.add(filter2, None).unwrap();
+        let idx3 = filters.add(filter3, None).unwrap();
 
-        assert_eq!(filters.0.len(), 2);
-        assert!(!filters.0.contains(&idx1));
-        assert!(filters.0.contains(&idx2));
-        assert!(filters.0.contains(&idx3));
+        assert_eq!(filters.state.len(), 2);
+        assert!(!filters.state.contains(&idx1));
+        assert!(filters.state.contains(&idx2));
+        assert!(filters.state.contains(&idx3));
 
         filters.get_and_update_stats(idx2);
 
-        let idx1 = filters.add(filter1);
-        assert_eq!(filters.0.len(), 2);
-        assert!(filters.0.contains(&idx1));
-        assert!(filters.0.contains(&idx2));
-        assert!(!filters.0.contains(&idx3));
+        let idx1 = filters.add(filter1, None).unwrap();
+        assert_eq!(filters.state.len(), 2);
+        assert!(filters.state.contains(&idx1));
+        assert!(filters.state.contains(&idx2));
+        assert!(!filters.state.contains(&idx3));
 
         filters.remove(idx1);
 
-        assert_eq!(filters.0.len(), 1);
-        assert!(!filters.0.contains(&idx1));
-        assert!(filters.0.contains(&idx2));
-        assert!(!filters.0.contains(&idx3));
+        assert_eq!(filters.state.len(), 1);
+        assert!(!filters.state.contains(&idx1));
+        assert!(filters.state.contains(&idx2));
+        assert!(!filters.state.contains(&idx3));
+    }
+
+    #[test]
+    fn connection_cannot_exhaust_filters() {
+        use super::*;
+
+        let mut filters = Filters::new(Some(4), Some(2));
+        let greedy_connection = ConnectionId::next();
+        let other_connection = ConnectionId::next();
+        let filter = TypedFilter::Blocks(MiniblockNumber::default());
+
+        let greedy_idx1 = filters
+            .add(filter.clone(), Some(greedy_connection))
+            .unwrap();
+        let greedy_idx2 = filters
+            .add(filter.clone(), Some(greedy_connection))
+            .unwrap();
+        let err = filters
+            .add(filter.clone(), Some(greedy_connection))
+            .unwrap_err();
+        assert_matches!(err, Web3Error::TooManyFilters(2));
+        assert_eq!(filters.state.len(), 2);
+
+        // The rest of the global capacity is available to other connections.
+        let other_idx1 = filters.add(filter.clone(), Some(other_connection)).unwrap();
+        let other_idx2 = filters.add(filter.clone(), Some(other_connection)).unwrap();
+        assert_eq!(filters.state.len(), 4);
+        for idx in [greedy_idx1, greedy_idx2, other_idx1, other_idx2] {
+            assert!(filters.state.contains(&idx));
+        }
+
+        // Removing a filter frees a slot for the connection.
+        assert!(filters.remove(greedy_idx1));
+        filters
+            .add(filter.clone(), Some(greedy_connection))
+            .unwrap();
+        // Evicting filters by the global LRU limit frees slots as well.
+        filters.add(filter.clone(), None).unwrap();
+        assert!(!filters.state.contains(&greedy_idx2));
+        filters.add(filter, Some(greedy_connection)).unwrap();
     }
 }
@@ -1,9 +1,13 @@
 //! Tests for the `debug` Web3 namespace.
 
-use zksync_types::{tx::TransactionExecutionResult, vm_trace::Call, BOOTLOADER_ADDRESS};
+use multivm::interface::ExecutionResult;
+use zksync_types::{
+    tx::TransactionExecutionResult, vm_trace::Call, Transaction, BOOTLOADER_ADDRESS,
+};
 use zksync_web3_decl::namespaces::DebugNamespaceClient;
 
 use super::*;
+use crate::{api_server::execution_sandbox::BlockArgs, utils::is_sampled_for_call_traces};
 
 fn execute_l2_transaction_with_traces(index_in_block: u8) -> TransactionExecutionResult {
     let first_call_trace = Call {
@@ -203,6 +207,88 @@ async fn tracing_transaction() {
     test_http_server(TraceTransactionTest).await;
 }
 
+#[derive(Debug)]
+struct TraceTransactionWithSamplingTest;
+
+impl TraceTransactionWithSamplingTest {
+    const SAMPLING_RATE: f64 = 0.5;
+}
+
+#[async_trait]
+impl HttpTest for TraceTransactionWithSamplingTest {
+    fn call_traces_sampling_rate(&self) -> f64 {
+        Self::SAMPLING_RATE
+    }
+
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        // Unsampled transactions must be re-executed on top of the state before their miniblock.
+        let replay_response = |_: &Transaction, block_args: &BlockArgs| {
+            assert_eq!(block_args.resolved_block_number(), MiniblockNumber(0));
+            ExecutionResult::Success {
+                output: b"replayed".to_vec(),
+            }
+        };
+        let mut tx_executor = MockTransactionExecutor::default();
+        tx_executor.set_call_responses(replay_response);
+        tx_executor.set_tx_responses(replay_response);
+        tx_executor
+    }
+
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        // Emulate the state keeper saving call traces only for sampled transactions.
+        let (mut sampled_tx, mut unsampled_tx) = (None, None);
+        while sampled_tx.is_none() || unsampled_tx.is_none() {
+            let tx_result = execute_l2_transaction_with_traces(0);
+            if is_sampled_for_call_traces(tx_result.hash, Self::SAMPLING_RATE) {
+                sampled_tx.get_or_insert(tx_result);
+            } else {
+                unsampled_tx.get_or_insert(TransactionExecutionResult {
+                    call_traces: vec![],
+                    ..tx_result
+                });
+            }
+        }
+        let tx_results = [sampled_tx.unwrap(), unsampled_tx.unwrap()];
+        let mut storage = pool.connection().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &tx_results).await?;
+        drop(storage);
+
+        let expected_calls: Vec<_> = tx_results[0]
+            .call_traces
+            .iter()
+            .map(|call| api::DebugCall::from(call.clone()))
+            .collect();
+        let result = client
+            .trace_transaction(tx_results[0].hash, None)
+            .await?
+            .context("no transaction traces")?;
+        assert_eq!(result.calls, expected_calls);
+
+        let result = client
+            .trace_transaction(tx_results[1].hash, None)
+            .await?
+            .context("no traces for unsampled transaction")?;
+        assert_eq!(result.from, Address::zero());
+        assert_eq!(result.to, BOOTLOADER_ADDRESS);
+        assert_eq!(result.gas, tx_results[1].transaction.gas_limit());
+        assert_eq!(result.output.0, b"replayed");
+        assert!(result.calls.is_empty(), "{result:?}");
+
+        // Unknown transactions must not be re-executed.
+        let unknown_tx_hash = std::iter::repeat_with(H256::random)
+            .find(|&hash| !is_sampled_for_call_traces(hash, Self::SAMPLING_RATE))
+            .unwrap();
+        let result = client.trace_transaction(unknown_tx_hash, None).await?;
+        assert!(result.is_none(), "{result:?}");
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn tracing_transaction_with_sampling() {
+    test_http_server(TraceTransactionWithSamplingTest).await;
+}
+
 #[derive(Debug)]
 struct TraceBlockTestWithSnapshotRecovery;
 
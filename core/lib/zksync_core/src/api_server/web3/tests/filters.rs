@@ -267,6 +267,77 @@ async fn log_filter_changes_with_block_boundaries() {
     test_http_server(LogFilterChangesWithBlockBoundariesTest).await;
 }
 
+#[derive(Debug)]
+struct OversizedLogsTest;
+
+impl OversizedLogsTest {
+    const RESPONSE_SIZE_LIMIT: usize = 64 * 1_024;
+    const EVENT_COUNT: u32 = 2_500;
+}
+
+#[async_trait]
+impl HttpTest for OversizedLogsTest {
+    fn response_body_size_limit(&self) -> Option<usize> {
+        Some(Self::RESPONSE_SIZE_LIMIT)
+    }
+
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        let mut storage = pool.connection().await?;
+        let (_, small_events) = store_events(&mut storage, 1, 0).await?;
+        // Store all events in a single miniblock, so that the query isn't rejected because of the logs limit.
+        let new_miniblock = create_miniblock(2);
+        storage
+            .blocks_dal()
+            .insert_miniblock(&new_miniblock)
+            .await?;
+        let tx_location = IncludedTxLocation {
+            tx_hash: H256::repeat_byte(1),
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::repeat_byte(2),
+        };
+        let events: Vec<_> = (0..Self::EVENT_COUNT)
+            .map(|i| VmEvent {
+                location: (L1BatchNumber(2), i),
+                address: Address::repeat_byte(23),
+                indexed_topics: vec![H256::repeat_byte(42)],
+                value: vec![0xff; 64],
+            })
+            .collect();
+        storage
+            .events_dal()
+            .save_events(
+                new_miniblock.number,
+                &[(tx_location, events.iter().collect())],
+            )
+            .await;
+        drop(storage);
+
+        // Queries fitting into the limit must be served as usual.
+        let small_filter = Filter {
+            from_block: Some(api::BlockNumber::Number(1.into())),
+            to_block: Some(api::BlockNumber::Number(1.into())),
+            ..Filter::default()
+        };
+        let logs = client.get_logs(small_filter).await?;
+        let small_events: Vec<_> = small_events.iter().collect();
+        assert_logs_match(&logs, &small_events);
+
+        let oversized_filter = Filter {
+            from_block: Some(api::BlockNumber::Number(2.into())),
+            to_block: Some(api::BlockNumber::Number(2.into())),
+            ..Filter::default()
+        };
+        let err = client.get_logs(oversized_filter).await.unwrap_err();
+        assert_matches!(err, RpcError::Call(err) if err.code() == -32005 && err.message().contains("limit"));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn oversized_logs_query_is_aborted() {
+    test_http_server(OversizedLogsTest).await;
+}
+
 fn assert_not_implemented<T: Debug>(result: Result<T, Error>) {
     assert_matches!(result, Err(Error::Call(e)) => {
         assert_eq!(e.code(), ErrorCode::MethodNotFound.code());
@@ -37,11 +37,19 @@ use zksync_types::{
 };
 use zksync_utils::u256_to_h256;
 use zksync_web3_decl::{
-    jsonrpsee::{http_client::HttpClient, types::error::ErrorCode},
+    error::Web3Error,
+    jsonrpsee::{
+        http_client::HttpClient,
+        rpc_params,
+        types::{error::ErrorCode, ErrorObjectOwned},
+    },
     namespaces::{EnNamespaceClient, EthNamespaceClient, ZksNamespaceClient},
 };
 
-use super::{metrics::ApiTransportLabel, *};
+use super::{
+    metrics::{ApiTransportLabel, CallResult, CallResultLabels, API_METRICS},
+    *,
+};
 use crate::{
     api_server::{
         execution_sandbox::testonly::MockTransactionExecutor,
@@ -121,6 +129,9 @@ pub(crate) async fn spawn_http_server(
         None,
         tx_executor,
         method_tracer,
+        None,
+        None,
+        None,
         stop_receiver,
     )
     .await
@@ -140,6 +151,9 @@ async fn spawn_ws_server(
         websocket_requests_per_minute_limit,
         MockTransactionExecutor::default(),
         Arc::default(),
+        None,
+        None,
+        None,
         stop_receiver,
     )
     .await
@@ -152,6 +166,9 @@ async fn spawn_server(
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
     tx_executor: MockTransactionExecutor,
     method_tracer: Arc<MethodTracer>,
+    extra_methods: Option<RpcModule<()>>,
+    request_body_size_limit: Option<usize>,
+    response_body_size_limit: Option<usize>,
     stop_receiver: watch::Receiver<bool>,
 ) -> (ApiServerHandles, mpsc::UnboundedReceiver<PubSubEvent>) {
     let (tx_sender, vm_barrier) =
@@ -161,7 +178,7 @@ async fn spawn_server(
     let mut namespaces = Namespace::DEFAULT.to_vec();
     namespaces.extend([Namespace::Debug, Namespace::Snapshots]);
 
-    let server_builder = match transport {
+    let mut server_builder = match transport {
         ApiTransportLabel::Http => ApiBuilder::jsonrpsee_backend(api_config, pool).http(0),
         ApiTransportLabel::Ws => {
             let mut builder = ApiBuilder::jsonrpsee_backend(api_config, pool)
@@ -174,6 +191,15 @@ async fn spawn_server(
             builder
         }
     };
+    if let Some(extra_methods) = extra_methods {
+        server_builder = server_builder.with_extra_methods(extra_methods);
+    }
+    if let Some(limit) = request_body_size_limit {
+        server_builder = server_builder.with_request_body_size_limit(limit);
+    }
+    if let Some(limit) = response_body_size_limit {
+        server_builder = server_builder.with_response_body_size_limit(limit);
+    }
     let server_handles = server_builder
         .with_polling_interval(POLL_INTERVAL)
         .with_tx_sender(tx_sender)
@@ -204,12 +230,42 @@ trait HttpTest: Send + Sync {
         Arc::default()
     }
 
+    /// Extra RPC methods registered on the server.
+    fn extra_methods(&self) -> Option<RpcModule<()>> {
+        None
+    }
+
+    /// Request body size limit for the server.
+    fn request_body_size_limit(&self) -> Option<usize> {
+        None
+    }
+
+    /// Response body size limit for the server.
+    fn response_body_size_limit(&self) -> Option<usize> {
+        None
+    }
+
     async fn test(&self, client: &HttpClient, pool: &ConnectionPool<Core>) -> anyhow::Result<()>;
 
     /// Overrides the `filters_disabled` configuration parameter for HTTP server startup
     fn filters_disabled(&self) -> bool {
         false
     }
+
+    /// Overrides the `node_version` configuration parameter for HTTP server startup
+    fn node_version(&self) -> Option<String> {
+        None
+    }
+
+    /// Overrides the `call_traces_sampling_rate` configuration parameter for HTTP server startup
+    fn call_traces_sampling_rate(&self) -> f64 {
+        1.0
+    }
+
+    /// Overrides the `node_components` configuration parameter for HTTP server startup
+    fn node_components(&self) -> Vec<String> {
+        vec![]
+    }
 }
 
 /// Storage initialization strategy.
@@ -286,11 +342,19 @@ async fn test_http_server(test: impl HttpTest) {
     let web3_config = Web3JsonRpcConfig::for_tests();
     let mut api_config = InternalApiConfig::new(&network_config, &web3_config, &contracts_config);
     api_config.filters_disabled = test.filters_disabled();
-    let mut server_handles = spawn_http_server(
+    api_config.node_version = test.node_version();
+    api_config.node_components = test.node_components();
+    api_config.call_traces_sampling_rate = test.call_traces_sampling_rate();
+    let (mut server_handles, _) = spawn_server(
+        ApiTransportLabel::Http,
         api_config,
         pool.clone(),
+        None,
         test.transaction_executor(),
         test.method_tracer(),
+        test.extra_methods(),
+        test.request_body_size_limit(),
+        test.response_body_size_limit(),
         stop_receiver,
     )
     .await;
@@ -476,6 +540,107 @@ async fn http_server_basics() {
     test_http_server(HttpServerBasicsTest).await;
 }
 
+fn custom_methods() -> RpcModule<()> {
+    let mut methods = RpcModule::new(());
+    methods
+        .register_method("custom_answer", |_params, _ctx| {
+            Ok::<_, ErrorObjectOwned>(42_u64)
+        })
+        .unwrap();
+    methods
+}
+
+#[derive(Debug)]
+struct ExtraMethodsTest;
+
+#[async_trait]
+impl HttpTest for ExtraMethodsTest {
+    fn extra_methods(&self) -> Option<RpcModule<()>> {
+        Some(custom_methods())
+    }
+
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        let answer: u64 = client.request("custom_answer", rpc_params![]).await?;
+        assert_eq!(answer, 42);
+
+        // Built-in methods must still be available.
+        let block_number = client.get_block_number().await?;
+        assert_eq!(block_number, U64::from(0));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn extra_methods_are_served() {
+    test_http_server(ExtraMethodsTest).await;
+}
+
+#[derive(Debug)]
+struct RequestBodySizeLimitTest;
+
+impl RequestBodySizeLimitTest {
+    const LIMIT: usize = 1_024;
+}
+
+#[async_trait]
+impl HttpTest for RequestBodySizeLimitTest {
+    fn request_body_size_limit(&self) -> Option<usize> {
+        Some(Self::LIMIT)
+    }
+
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        // Requests fitting into the limit must be served as usual.
+        let block_number = client.get_block_number().await?;
+        assert_eq!(block_number, U64::from(0));
+
+        let call_request = serde_json::json!({
+            "to": Address::repeat_byte(1),
+            "data": format!("0x{}", "00".repeat(Self::LIMIT)),
+        });
+        let err = client
+            .request::<serde_json::Value, _>("eth_call", rpc_params![call_request, "latest"])
+            .await
+            .unwrap_err();
+        // The server responds with 413 Payload Too Large, which is surfaced as a transport error by the client.
+        assert_matches!(err, ClientError::Transport(_));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn oversized_request_body_is_rejected() {
+    test_http_server(RequestBodySizeLimitTest).await;
+}
+
+#[test]
+fn extra_methods_cannot_use_builtin_namespaces() {
+    validate_extra_methods(&custom_methods()).unwrap();
+
+    // `snapshots_` namespace is not enabled by default, but it still cannot be used.
+    for method_name in ["eth_blockNumber", "eth_customMethod", "snapshots_custom"] {
+        let mut methods = RpcModule::new(());
+        methods
+            .register_method(method_name, |_params, _ctx| Ok::<_, ErrorObjectOwned>(()))
+            .unwrap();
+        let err = validate_extra_methods(&methods).unwrap_err().to_string();
+        assert!(err.contains(method_name), "{err}");
+    }
+}
+
+#[test]
+fn oversized_request_body_size_limit_is_rejected() {
+    assert_eq!(request_body_size_limit(1_024).unwrap(), 1_024);
+    assert_eq!(
+        request_body_size_limit(u32::MAX as usize).unwrap(),
+        u32::MAX
+    );
+
+    let err = request_body_size_limit(u32::MAX as usize + 1)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("request body size limit"), "{err}");
+}
+
 #[derive(Debug)]
 struct BlockMethodsWithSnapshotRecovery;
 
@@ -1023,6 +1188,7 @@ impl HttpTest for RpcCallsTracingTest {
             calls[0].response.as_error_code(),
             Some(ErrorCode::MethodNotFound.code())
         );
+        assert_eq!(calls[0].metadata.name, "other");
         assert!(!calls[0].metadata.has_app_error);
 
         client
@@ -1081,6 +1247,103 @@ async fn tracing_rpc_calls() {
     test_http_server(RpcCallsTracingTest::default()).await;
 }
 
+#[derive(Debug)]
+struct RpcCallMetricsTest;
+
+impl RpcCallMetricsTest {
+    fn call_count(method: &'static str, result: CallResult) -> u64 {
+        API_METRICS.web3_call_results[&CallResultLabels { method, result }].get()
+    }
+
+    /// Returns the number of observations in the `api_web3_call` latency histogram for `method`,
+    /// summed across all other labels.
+    fn latency_observation_count(method: &str) -> u64 {
+        let registry = vise::MetricsCollection::default().collect();
+        let mut buffer = String::new();
+        registry
+            .encode(&mut buffer, vise::Format::OpenMetrics)
+            .unwrap();
+        let method_label = format!("method=\"{method}\"");
+        buffer
+            .lines()
+            .filter_map(|line| {
+                let (name, value) = line.split_once(' ')?;
+                let (name, labels) = name.split_once('{')?;
+                let is_latency_count =
+                    name == "api_web3_call_count" || name == "api_web3_call_seconds_count";
+                let has_method = labels
+                    .trim_end_matches('}')
+                    .split(',')
+                    .any(|label| label == method_label);
+                (is_latency_count && has_method).then(|| value.parse::<u64>().unwrap())
+            })
+            .sum()
+    }
+}
+
+#[async_trait]
+impl HttpTest for RpcCallMetricsTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        // Metrics are global, so they can be concurrently updated by other tests; hence, we only check that
+        // the relevant counters increase.
+        let successful_calls = Self::call_count("eth_chainId", CallResult::Success);
+        let latency_observations = Self::latency_observation_count("eth_chainId");
+        client.chain_id().await?;
+        assert!(Self::call_count("eth_chainId", CallResult::Success) > successful_calls);
+        assert!(Self::latency_observation_count("eth_chainId") > latency_observations);
+
+        let failed_calls = Self::call_count("eth_getFilterLogs", CallResult::ClientError);
+        client
+            .request::<serde_json::Value, _>(
+                "eth_getFilterLogs",
+                jsonrpsee::rpc_params![U256::from(1)],
+            )
+            .await
+            .unwrap_err();
+        assert!(Self::call_count("eth_getFilterLogs", CallResult::ClientError) > failed_calls);
+
+        let unknown_calls = Self::call_count("other", CallResult::ClientError);
+        client
+            .request::<serde_json::Value, _>("eth_unknownMethod", jsonrpsee::rpc_params![])
+            .await
+            .unwrap_err();
+        assert!(Self::call_count("other", CallResult::ClientError) > unknown_calls);
+        // Unknown methods must not be recorded with an empty method label, which was used previously.
+        assert_eq!(Self::latency_observation_count(""), 0);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn rpc_call_metrics() {
+    test_http_server(RpcCallMetricsTest).await;
+}
+
+#[test]
+fn classifying_web3_errors() {
+    let internal_err = Web3Error::InternalError(anyhow::anyhow!("oops"));
+    assert_eq!(
+        CallResult::for_web3_error(&internal_err),
+        CallResult::ServerError
+    );
+    assert_eq!(
+        CallResult::for_web3_error(&Web3Error::TreeApiUnavailable),
+        CallResult::ServerError
+    );
+    assert_eq!(
+        CallResult::for_web3_error(&Web3Error::TreeProofsUnavailable),
+        CallResult::ServerError
+    );
+    assert_eq!(
+        CallResult::for_web3_error(&Web3Error::FilterNotFound),
+        CallResult::ClientError
+    );
+    assert_eq!(
+        CallResult::for_web3_error(&Web3Error::NoBlock),
+        CallResult::ClientError
+    );
+}
+
 #[derive(Debug, Default)]
 struct GenesisConfigTest;
 
@@ -1098,3 +1361,84 @@ impl HttpTest for GenesisConfigTest {
 async fn tracing_genesis_config() {
     test_http_server(GenesisConfigTest).await;
 }
+
+#[derive(Debug)]
+struct SyncStatusOnMainNodeTest;
+
+#[async_trait]
+impl HttpTest for SyncStatusOnMainNodeTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        // The test server has no sync state, i.e., it acts as the main node.
+        let status = client.sync_status().await?;
+        assert_eq!(status, None);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_sync_status_on_main_node() {
+    test_http_server(SyncStatusOnMainNodeTest).await;
+}
+
+#[derive(Debug)]
+struct NodeVersionInfoTest;
+
+#[async_trait]
+impl HttpTest for NodeVersionInfoTest {
+    fn node_version(&self) -> Option<String> {
+        Some("1.2.3".to_owned())
+    }
+
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        let last_used_version = pool
+            .connection()
+            .await?
+            .protocol_versions_dal()
+            .last_used_version_id()
+            .await?
+            .context("no protocol versions used after genesis")?;
+
+        let version_info = client.get_node_version_info().await?;
+        assert_eq!(
+            version_info.last_used_protocol_version,
+            Some(last_used_version as u16)
+        );
+        assert_eq!(version_info.node_version.as_deref(), Some("1.2.3"));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_node_version_info() {
+    test_http_server(NodeVersionInfoTest).await;
+}
+
+#[derive(Debug)]
+struct NodeInfoTest;
+
+#[async_trait]
+impl HttpTest for NodeInfoTest {
+    fn node_version(&self) -> Option<String> {
+        Some("1.2.3".to_owned())
+    }
+
+    fn node_components(&self) -> Vec<String> {
+        vec!["core".to_owned(), "http_api".to_owned()]
+    }
+
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        let network_config = NetworkConfig::for_tests();
+        let node_info = client.get_node_info().await?;
+        assert_eq!(node_info.components, ["core", "http_api"]);
+        assert_eq!(node_info.node_version.as_deref(), Some("1.2.3"));
+        assert_eq!(node_info.l1_chain_id, network_config.network.chain_id());
+        assert_eq!(node_info.l2_chain_id, network_config.zksync_network_id);
+        assert!(!node_info.consensus_enabled);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_node_info() {
+    test_http_server(NodeInfoTest).await;
+}
@@ -1,5 +1,6 @@
 use std::{path::Path, time::Duration};
 
+use anyhow::Context as _;
 use bitflags::bitflags;
 use serde::Serialize;
 use tokio::time::sleep;
@@ -22,6 +23,9 @@ use zksync_types::{
     L1BatchNumber, PackedEthSignature, H160, H256, U256,
 };
 
+#[cfg(test)]
+mod tests;
+
 bitflags! {
     pub struct BlockReverterFlags: u32 {
         const POSTGRES = 0b_0001;
@@ -158,6 +162,84 @@ impl BlockReverter {
         }
     }
 
+    /// Estimates the amount of data that [`Self::rollback_db()`] would remove when rolling back
+    /// to `last_l1_batch_to_keep`. Unlike the rollback, this method only reads from Postgres and RocksDB.
+    pub async fn estimate_rollback(
+        &self,
+        last_l1_batch_to_keep: L1BatchNumber,
+    ) -> anyhow::Result<RollbackEstimate> {
+        let mut storage = self.connection_pool.connection().await?;
+        let sealed_l1_batch_number = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .context("there are no L1 batches in Postgres")?;
+        let (_, last_miniblock_to_keep) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(last_l1_batch_to_keep)
+            .await?
+            .with_context(|| {
+                format!("L1 batch #{last_l1_batch_to_keep} doesn't contain miniblocks")
+            })?;
+        let sealed_miniblock_number = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await?
+            .context("there are no miniblocks in Postgres")?;
+        let storage_log_count = storage
+            .storage_logs_dal()
+            .get_storage_logs_row_count_after(last_miniblock_to_keep)
+            .await?;
+        drop(storage);
+
+        let merkle_tree_path = Path::new(&self.merkle_tree_path);
+        let tree = if merkle_tree_path.exists() {
+            Some(Self::estimate_tree_rollback(
+                last_l1_batch_to_keep,
+                merkle_tree_path,
+            )?)
+        } else {
+            None
+        };
+
+        let estimate = RollbackEstimate {
+            l1_batch_count: sealed_l1_batch_number
+                .0
+                .saturating_sub(last_l1_batch_to_keep.0),
+            miniblock_count: sealed_miniblock_number
+                .0
+                .saturating_sub(last_miniblock_to_keep.0),
+            storage_log_count,
+            tree,
+        };
+        tracing::info!(
+            "Rolling back to L1 batch #{last_l1_batch_to_keep} would remove: {estimate:?}"
+        );
+        Ok(estimate)
+    }
+
+    fn estimate_tree_rollback(
+        last_l1_batch_to_keep: L1BatchNumber,
+        path: &Path,
+    ) -> anyhow::Result<TreeRollbackEstimate> {
+        let db = RocksDB::new(path).context("failed initializing RocksDB for Merkle tree")?;
+        let reader = ZkSyncTree::new_lightweight(db.into()).reader();
+        let next_l1_batch_number = reader.next_l1_batch_number();
+        if next_l1_batch_number <= last_l1_batch_to_keep {
+            return Ok(TreeRollbackEstimate::default());
+        }
+
+        let leaf_count_to_keep = reader
+            .l1_batch_leaf_count(last_l1_batch_to_keep)
+            .with_context(|| {
+                format!("Merkle tree doesn't contain L1 batch #{last_l1_batch_to_keep}")
+            })?;
+        Ok(TreeRollbackEstimate {
+            l1_batch_count: next_l1_batch_number.0 - last_l1_batch_to_keep.0 - 1,
+            leaf_count: reader.leaf_count() - leaf_count_to_keep,
+        })
+    }
+
     async fn rollback_rocks_dbs(
         &self,
         last_l1_batch_to_keep: L1BatchNumber,
@@ -470,6 +552,32 @@ impl BlockReverter {
     }
 }
 
+/// Amount of data that would be removed by [`BlockReverter::rollback_db()`], as returned
+/// by [`BlockReverter::estimate_rollback()`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RollbackEstimate {
+    /// Number of L1 batches removed from Postgres.
+    pub l1_batch_count: u32,
+    /// Number of miniblocks removed from Postgres, including miniblocks of the pending L1 batch.
+    pub miniblock_count: u32,
+    /// Number of storage logs removed from Postgres.
+    pub storage_log_count: u64,
+    /// Changes to the Merkle tree, or `None` if the tree is not present on the node.
+    pub tree: Option<TreeRollbackEstimate>,
+}
+
+/// Part of [`RollbackEstimate`] related to the Merkle tree.
+///
+/// Since tree truncation doesn't remove node data (it's likely to be reused), the estimate
+/// is expressed in removed tree versions and leaves rather than in raw nodes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TreeRollbackEstimate {
+    /// Number of tree versions (i.e., L1 batches) removed from the tree.
+    pub l1_batch_count: u32,
+    /// Number of leaves removed from the tree.
+    pub leaf_count: u64,
+}
+
 #[derive(Debug, Serialize)]
 pub struct SuggestedRollbackValues {
     pub last_executed_l1_batch_number: L1BatchNumber,
@@ -0,0 +1,165 @@
+//! Tests for the block reverter.
+
+use tempfile::TempDir;
+use zksync_dal::Connection;
+use zksync_merkle_tree::TreeInstruction;
+use zksync_types::{AccountTreeId, Address, MiniblockNumber, StorageKey, StorageLog};
+
+use super::*;
+use crate::{
+    genesis::{insert_genesis_batch, GenesisParams},
+    utils::testonly::{create_l1_batch, create_miniblock},
+};
+
+const LOGS_PER_MINIBLOCK: u64 = 2;
+
+fn storage_key(index: u64) -> StorageKey {
+    StorageKey::new(
+        AccountTreeId::new(Address::repeat_byte(1)),
+        H256::from_low_u64_be(index),
+    )
+}
+
+async fn store_miniblock_with_logs(storage: &mut Connection<'_, Core>, number: u32) {
+    storage
+        .blocks_dal()
+        .insert_miniblock(&create_miniblock(number))
+        .await
+        .unwrap();
+    let logs: Vec<_> = (0..LOGS_PER_MINIBLOCK)
+        .map(|i| {
+            let key = storage_key(u64::from(number) * LOGS_PER_MINIBLOCK + i);
+            StorageLog::new_write_log(key, H256::repeat_byte(0xff))
+        })
+        .collect();
+    storage
+        .storage_logs_dal()
+        .insert_storage_logs(MiniblockNumber(number), &[(H256::zero(), logs)])
+        .await
+        .unwrap();
+}
+
+async fn seal_l1_batch(storage: &mut Connection<'_, Core>, number: u32) {
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&create_l1_batch(number))
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(number))
+        .await
+        .unwrap();
+}
+
+/// Creates L1 batches #1..=#3 with a single miniblock each, and a pending miniblock #4.
+async fn prepare_postgres(pool: &ConnectionPool<Core>) {
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    for number in 1..=3 {
+        store_miniblock_with_logs(&mut storage, number).await;
+        seal_l1_batch(&mut storage, number).await;
+    }
+    store_miniblock_with_logs(&mut storage, 4).await;
+}
+
+/// Creates a Merkle tree with L1 batches #0..=#3, each inserting `LOGS_PER_MINIBLOCK` new leaves.
+fn prepare_merkle_tree(path: &Path) {
+    let db = RocksDB::new(path).unwrap();
+    let mut tree = ZkSyncTree::new_lightweight(db.into());
+    for l1_batch_number in 0..=3 {
+        let instructions: Vec<_> = (0..LOGS_PER_MINIBLOCK)
+            .map(|i| {
+                let index = l1_batch_number * LOGS_PER_MINIBLOCK + i;
+                TreeInstruction::write(storage_key(index), index + 1, H256::repeat_byte(0xff))
+            })
+            .collect();
+        tree.process_l1_batch(&instructions);
+    }
+    tree.save();
+}
+
+fn create_reverter(pool: ConnectionPool<Core>, temp_dir: &TempDir) -> BlockReverter {
+    let path = |name: &str| temp_dir.path().join(name).to_str().unwrap().to_owned();
+    BlockReverter::new(
+        NodeRole::External,
+        path("state_keeper_cache"),
+        path("merkle_tree"),
+        None,
+        pool,
+        L1ExecutedBatchesRevert::Allowed,
+    )
+}
+
+#[tokio::test]
+async fn estimating_rollback_without_tree() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_postgres(&pool).await;
+    let temp_dir = TempDir::new().unwrap();
+    let reverter = create_reverter(pool, &temp_dir);
+
+    let estimate = reverter.estimate_rollback(L1BatchNumber(1)).await.unwrap();
+    assert_eq!(
+        estimate,
+        RollbackEstimate {
+            l1_batch_count: 2,
+            miniblock_count: 3,
+            storage_log_count: 3 * LOGS_PER_MINIBLOCK,
+            tree: None,
+        }
+    );
+    // The estimate must not create RocksDB instances.
+    assert!(!temp_dir.path().join("merkle_tree").exists());
+    assert!(!temp_dir.path().join("state_keeper_cache").exists());
+
+    let estimate = reverter.estimate_rollback(L1BatchNumber(3)).await.unwrap();
+    assert_eq!(
+        estimate,
+        RollbackEstimate {
+            l1_batch_count: 0,
+            miniblock_count: 1,
+            storage_log_count: LOGS_PER_MINIBLOCK,
+            tree: None,
+        }
+    );
+}
+
+#[tokio::test]
+async fn estimating_rollback_with_tree() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_postgres(&pool).await;
+    let temp_dir = TempDir::new().unwrap();
+    prepare_merkle_tree(&temp_dir.path().join("merkle_tree"));
+    let reverter = create_reverter(pool, &temp_dir);
+
+    let estimate = reverter.estimate_rollback(L1BatchNumber(1)).await.unwrap();
+    assert_eq!(
+        estimate.tree,
+        Some(TreeRollbackEstimate {
+            l1_batch_count: 2,
+            leaf_count: 2 * LOGS_PER_MINIBLOCK,
+        })
+    );
+}
+
+#[tokio::test]
+async fn estimating_rollback_errors_for_unknown_l1_batch() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().unwrap();
+    let reverter = create_reverter(pool.clone(), &temp_dir);
+
+    let err = reverter
+        .estimate_rollback(L1BatchNumber(1))
+        .await
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("no L1 batches"), "{err:#}");
+
+    prepare_postgres(&pool).await;
+    let err = reverter
+        .estimate_rollback(L1BatchNumber(10))
+        .await
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("#10"), "{err:#}");
+}
@@ -7,6 +7,7 @@ use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Histogram, Metrics
 pub(super) enum CommitmentStage {
     PrepareInput,
     Calculate,
+    Verify,
     SaveResults,
 }
 
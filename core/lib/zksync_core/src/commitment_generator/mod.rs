@@ -9,15 +9,22 @@ use zksync_commitment_utils::{bootloader_initial_content_commitment, events_queu
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_l1_contract_interface::i_executor::commit::kzg::pubdata_to_blob_commitments;
+use zksync_system_constants::STATE_DIFF_HASH_KEY;
 use zksync_types::{
-    commitment::{AuxCommitments, CommitmentCommonInput, CommitmentInput, L1BatchCommitment},
+    commitment::{
+        serialize_commitments, AuxCommitments, CommitmentCommonInput, CommitmentInput,
+        L1BatchCommitment,
+    },
     event::convert_vm_events_to_log_queries,
+    web3::signing::keccak256,
     writes::{InitialStorageWrite, RepeatedStorageWrite, StateDiffRecord},
     L1BatchNumber, ProtocolVersionId, StorageKey, H256,
 };
-use zksync_utils::h256_to_u256;
+use zksync_utils::{h256_to_u256, u256_to_h256};
 
 mod metrics;
+#[cfg(test)]
+mod tests;
 
 const SLEEP_INTERVAL: Duration = Duration::from_millis(100);
 
@@ -25,6 +32,7 @@ const SLEEP_INTERVAL: Duration = Duration::from_millis(100);
 pub struct CommitmentGenerator {
     connection_pool: ConnectionPool<Core>,
     health_updater: HealthUpdater,
+    verify_locally: bool,
 }
 
 impl CommitmentGenerator {
@@ -32,9 +40,25 @@ impl CommitmentGenerator {
         Self {
             connection_pool,
             health_updater: ReactiveHealthCheck::new("commitment_generator").1,
+            verify_locally: false,
         }
     }
 
+    /// Enables local verification of generated commitments. With verification enabled, the commitment input
+    /// for each L1 batch is checked against data produced independently by the VM when executing the batch
+    /// before the commitment is computed and persisted:
+    ///
+    /// - The hash of state diffs must match the one published by the bootloader in system logs.
+    /// - The events queue persisted by the state keeper must match the one reconstructed from VM events.
+    ///
+    /// If any check fails, the generator halts with the mismatch details. This catches bugs in preparing
+    /// the commitment input independently of L1, but is CPU-heavy since it requires hashing all state diffs
+    /// of each batch once more.
+    pub fn with_local_verification(mut self, verify_locally: bool) -> Self {
+        self.verify_locally = verify_locally;
+        self
+    }
+
     pub fn health_check(&self) -> ReactiveHealthCheck {
         self.health_updater.subscribe()
     }
@@ -68,6 +92,14 @@ impl CommitmentGenerator {
 
         if events_queue_from_db != events_queue_calculated {
             tracing::error!("Events queue mismatch for L1 batch #{l1_batch_number}");
+            if self.verify_locally {
+                anyhow::bail!(
+                    "Events queue persisted for L1 batch #{l1_batch_number} ({} events) differs from \
+                     the one reconstructed from VM events ({} events)",
+                    events_queue_from_db.len(),
+                    events_queue_calculated.len()
+                );
+            }
         }
 
         let initial_bootloader_contents = connection
@@ -258,6 +290,44 @@ impl CommitmentGenerator {
         Ok(input)
     }
 
+    /// Checks the state diffs in the prepared commitment `input` against the hash published by the bootloader
+    /// in system logs. Pre-Boojum L1 batches and L1 batches without system logs (e.g., the genesis batch)
+    /// have no such reference and are not checked.
+    fn verify_input(l1_batch_number: L1BatchNumber, input: &CommitmentInput) -> anyhow::Result<()> {
+        let CommitmentInput::PostBoojum {
+            system_logs,
+            state_diffs,
+            ..
+        } = input
+        else {
+            return Ok(());
+        };
+        if system_logs.is_empty() {
+            return Ok(());
+        }
+
+        let state_diff_hash_key = u256_to_h256(STATE_DIFF_HASH_KEY.into());
+        let expected_hash = system_logs
+            .iter()
+            .find_map(|log| (log.0.key == state_diff_hash_key).then_some(log.0.value))
+            .with_context(|| {
+                format!("state diff hash is missing in system logs for L1 batch #{l1_batch_number}")
+            })?;
+        let state_diffs_hash = H256(keccak256(&serialize_commitments(state_diffs)));
+        if state_diffs_hash != expected_hash {
+            // State diffs are too large to be included into the error, but they are valuable for debugging.
+            tracing::error!(
+                "Mismatched state diffs prepared for L1 batch #{l1_batch_number}: {state_diffs:?}"
+            );
+            anyhow::bail!(
+                "Hash of {} state diffs prepared for L1 batch #{l1_batch_number} ({state_diffs_hash:?}) differs \
+                 from the one published by the bootloader in system logs ({expected_hash:?})",
+                state_diffs.len()
+            );
+        }
+        Ok(())
+    }
+
     async fn step(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<()> {
         let latency =
             METRICS.generate_commitment_latency_stage[&CommitmentStage::PrepareInput].start();
@@ -265,6 +335,16 @@ impl CommitmentGenerator {
         let latency = latency.observe();
         tracing::debug!("Prepared commitment input for L1 batch #{l1_batch_number} in {latency:?}");
 
+        if self.verify_locally {
+            let latency =
+                METRICS.generate_commitment_latency_stage[&CommitmentStage::Verify].start();
+            Self::verify_input(l1_batch_number, &input)?;
+            let latency = latency.observe();
+            tracing::debug!(
+                "Verified commitment input for L1 batch #{l1_batch_number} in {latency:?}"
+            );
+        }
+
         let latency =
             METRICS.generate_commitment_latency_stage[&CommitmentStage::Calculate].start();
         let commitment = L1BatchCommitment::new(input);
@@ -0,0 +1,195 @@
+//! Tests for `CommitmentGenerator`.
+
+use std::iter;
+
+use zksync_dal::Connection;
+use zksync_mini_merkle_tree::MiniMerkleTree;
+use zksync_system_constants::{L1_MESSENGER_ADDRESS, L2_TO_L1_LOGS_TREE_ROOT_KEY};
+use zksync_types::{
+    block::L1BatchHeader,
+    l2_to_l1_log::{l2_to_l1_logs_tree_size, L2ToL1Log, SystemL2ToL1Log, UserL2ToL1Log},
+    AccountTreeId, Address, MiniblockNumber, StorageLog,
+};
+
+use super::*;
+use crate::{
+    genesis::{insert_genesis_batch, GenesisParams},
+    utils::testonly::{create_l1_batch, create_l1_batch_metadata, create_miniblock},
+};
+
+fn system_log(key: u32, value: H256) -> SystemL2ToL1Log {
+    SystemL2ToL1Log(L2ToL1Log {
+        shard_id: 0,
+        is_service: true,
+        tx_number_in_block: 0,
+        sender: L1_MESSENGER_ADDRESS,
+        key: u256_to_h256(key.into()),
+        value,
+    })
+}
+
+/// Creates an L1 batch header with system logs emulating the ones published by the bootloader
+/// for an L1 batch without user L2-to-L1 logs and with the specified initial writes.
+fn create_l1_batch_with_system_logs(
+    number: L1BatchNumber,
+    initial_writes: &[(StorageKey, H256)],
+) -> L1BatchHeader {
+    let mut header = create_l1_batch(number.0);
+    header.pubdata_input = Some(vec![]);
+    let protocol_version = header.protocol_version.unwrap();
+
+    let mut state_diffs: Vec<_> = initial_writes
+        .iter()
+        .map(|(key, value)| StateDiffRecord {
+            address: *key.address(),
+            key: h256_to_u256(*key.key()),
+            derived_key: StorageKey::raw_hashed_key(key.address(), key.key()),
+            enumeration_index: 0,
+            initial_value: U256::zero(),
+            final_value: h256_to_u256(*value),
+        })
+        .collect();
+    state_diffs.sort_unstable_by_key(|rec| (rec.address, rec.key));
+    let state_diffs_hash = H256(keccak256(&serialize_commitments(&state_diffs)));
+    let l2_to_l1_logs_root = MiniMerkleTree::new(
+        iter::empty::<[u8; UserL2ToL1Log::SERIALIZED_SIZE]>(),
+        Some(l2_to_l1_logs_tree_size(protocol_version)),
+    )
+    .merkle_root();
+
+    header.system_logs = vec![
+        system_log(L2_TO_L1_LOGS_TREE_ROOT_KEY, l2_to_l1_logs_root),
+        system_log(STATE_DIFF_HASH_KEY, state_diffs_hash),
+    ];
+    header
+}
+
+async fn write_initial_values(
+    storage: &mut Connection<'_, Core>,
+    l1_batch_number: L1BatchNumber,
+    initial_writes: &[(StorageKey, H256)],
+) {
+    let logs: Vec<_> = initial_writes
+        .iter()
+        .map(|&(key, value)| StorageLog::new_write_log(key, value))
+        .collect();
+    storage
+        .storage_logs_dal()
+        .insert_storage_logs(MiniblockNumber(l1_batch_number.0), &[(H256::zero(), logs)])
+        .await
+        .unwrap();
+    let keys: Vec<_> = initial_writes.iter().map(|(key, _)| *key).collect();
+    storage
+        .storage_logs_dedup_dal()
+        .insert_initial_writes(l1_batch_number, &keys)
+        .await
+        .unwrap();
+}
+
+async fn seal_l1_batch(
+    storage: &mut Connection<'_, Core>,
+    header: &L1BatchHeader,
+    initial_writes: &[(StorageKey, H256)],
+) {
+    let number = header.number;
+    storage
+        .blocks_dal()
+        .insert_miniblock(&create_miniblock(number.0))
+        .await
+        .unwrap();
+    write_initial_values(storage, number, initial_writes).await;
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(header)
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .mark_miniblocks_as_executed_in_l1_batch(number)
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .save_l1_batch_tree_data(number, &create_l1_batch_metadata(number.0).tree_data())
+        .await
+        .unwrap();
+}
+
+fn test_initial_writes() -> Vec<(StorageKey, H256)> {
+    (1..=3)
+        .map(|i| {
+            let key = StorageKey::new(
+                AccountTreeId::new(Address::repeat_byte(i)),
+                H256::from_low_u64_be(i.into()),
+            );
+            (key, H256::from_low_u64_be(u64::from(i) + 100))
+        })
+        .collect()
+}
+
+async fn prepare_storage(pool: &ConnectionPool<Core>) {
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    let initial_writes = test_initial_writes();
+    let header = create_l1_batch_with_system_logs(L1BatchNumber(1), &initial_writes);
+    seal_l1_batch(&mut storage, &header, &initial_writes).await;
+}
+
+#[tokio::test]
+async fn generating_commitment_with_local_verification() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_storage(&pool).await;
+
+    let generator = CommitmentGenerator::new(pool.clone()).with_local_verification(true);
+    generator.step(L1BatchNumber(1)).await.unwrap();
+
+    let next_l1_batch = pool
+        .connection()
+        .await
+        .unwrap()
+        .blocks_dal()
+        .get_next_l1_batch_ready_for_commitment_generation()
+        .await
+        .unwrap();
+    assert_eq!(next_l1_batch, None);
+}
+
+#[tokio::test]
+async fn local_verification_detects_corrupted_input_data() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_storage(&pool).await;
+
+    // Corrupt input data in Postgres so that it's inconsistent with the state diffs published by the bootloader.
+    let mut storage = pool.connection().await.unwrap();
+    let extra_key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(0xff)), H256::zero());
+    write_initial_values(
+        &mut storage,
+        L1BatchNumber(1),
+        &[(extra_key, H256::repeat_byte(1))],
+    )
+    .await;
+    drop(storage);
+
+    let generator = CommitmentGenerator::new(pool.clone()).with_local_verification(true);
+    let err = generator
+        .step(L1BatchNumber(1))
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("4 state diffs"), "{err}");
+    assert!(err.contains("L1 batch #1"), "{err}");
+    assert!(err.contains("published by the bootloader"), "{err}");
+
+    // No commitment must be persisted for the batch.
+    let next_l1_batch = pool
+        .connection()
+        .await
+        .unwrap()
+        .blocks_dal()
+        .get_next_l1_batch_ready_for_commitment_generation()
+        .await
+        .unwrap();
+    assert_eq!(next_l1_batch, Some(L1BatchNumber(1)));
+}
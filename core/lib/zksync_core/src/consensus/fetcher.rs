@@ -1,19 +1,94 @@
+use std::num::{NonZeroU32, NonZeroUsize};
+
 use anyhow::Context as _;
-use zksync_concurrency::{ctx, error::Wrap as _, limiter, scope, time};
+use zksync_concurrency::{ctx, error::Wrap as _, limiter, scope, sync, time};
 use zksync_consensus_executor as executor;
 use zksync_consensus_roles::validator;
 use zksync_consensus_storage::BlockStore;
 use zksync_types::MiniblockNumber;
+use zksync_web3_decl::jsonrpsee::core::ClientError;
 
 use crate::{
     consensus::{storage, Store},
     sync_layer::{
-        fetcher::FetchedBlock, sync_action::ActionQueueSender, MainNodeClient, SyncState,
+        fetcher::FetchedBlock, metrics::FETCHER_METRICS, sync_action::ActionQueueSender,
+        MainNodeClient, SyncState,
     },
 };
 
 pub type P2PConfig = executor::Config;
 
+/// Window of miniblocks requested from the main node at once, i.e., the effective fetch request size.
+/// The window size is adjusted AIMD-style: it is incremented after each successful request and halved after
+/// each request timeout, so that the fetcher backs off if the main node is overloaded.
+#[derive(Debug)]
+pub(super) struct RequestWindow {
+    size: usize,
+    max_size: usize,
+    in_flight: usize,
+}
+
+impl RequestWindow {
+    pub(super) fn new(max_size: NonZeroUsize) -> Self {
+        let max_size = max_size.get();
+        FETCHER_METRICS.request_size.set(max_size);
+        Self {
+            size: max_size,
+            max_size,
+            in_flight: 0,
+        }
+    }
+
+    pub(super) fn size(&self) -> usize {
+        self.size
+    }
+
+    fn has_capacity(&self) -> bool {
+        self.in_flight < self.size
+    }
+
+    pub(super) fn on_success(&mut self) {
+        self.size = (self.size + 1).min(self.max_size);
+        FETCHER_METRICS.request_size.set(self.size);
+    }
+
+    pub(super) fn on_timeout(&mut self) {
+        self.size = (self.size / 2).max(1);
+        FETCHER_METRICS.request_size.set(self.size);
+    }
+}
+
+/// Configuration of polling the main node for its latest miniblock, which is used to determine the sync status
+/// of the node (e.g., in the `eth_syncing` Web3 method).
+#[derive(Debug, Clone, Copy)]
+pub struct SyncStatePollingConfig {
+    /// Interval between successful polls.
+    pub interval: time::Duration,
+    /// Timeout for a single poll.
+    pub timeout: time::Duration,
+    /// Number of consecutive failed or timed-out polls after which the main node is marked as unreachable
+    /// in the [`SyncState`] until the next successful poll.
+    pub max_consecutive_failures: NonZeroU32,
+    /// If set, the main node is marked as idle in the [`SyncState`] if the node is synced, but the latest
+    /// main node miniblock hasn't changed for this duration.
+    pub idle_timeout: Option<time::Duration>,
+    /// Whether to abort the fetcher if the main node has been unreachable for longer than [`Self::idle_timeout`].
+    /// Unlike a quiet network, this likely means that the connection is wedged.
+    pub abort_on_idle: bool,
+}
+
+impl Default for SyncStatePollingConfig {
+    fn default() -> Self {
+        Self {
+            interval: time::Duration::milliseconds(500),
+            timeout: time::Duration::seconds(5),
+            max_consecutive_failures: NonZeroU32::new(3).unwrap(),
+            idle_timeout: None,
+            abort_on_idle: false,
+        }
+    }
+}
+
 /// Miniblock fetcher.
 pub struct Fetcher {
     pub store: Store,
@@ -21,6 +96,12 @@ pub struct Fetcher {
     pub client: Box<dyn MainNodeClient>,
     /// Rate limiter for `client.fetch_l2_block` requests.
     pub limiter: limiter::Limiter,
+    /// Maximum number of miniblocks requested from the main node at once (each via a separate
+    /// `client.fetch_l2_block` call). The effective request size is adjusted based on main node responses;
+    /// see [`RequestWindow`].
+    pub max_request_size: NonZeroUsize,
+    /// Configuration of polling the main node for its latest miniblock.
+    pub sync_state_polling: SyncStatePollingConfig,
 }
 
 impl Fetcher {
@@ -120,20 +201,64 @@ impl Fetcher {
 
     /// Periodically fetches the head of the main node
     /// and updates `SyncState` accordingly.
-    async fn fetch_state_loop(&self, ctx: &ctx::Ctx) -> ctx::Result<()> {
-        const DELAY_INTERVAL: time::Duration = time::Duration::milliseconds(500);
+    ///
+    /// If [`SyncStatePollingConfig::idle_timeout`] is set, also acts as a watchdog: marks the main node as idle
+    /// if its head doesn't change for a long time, and optionally aborts if the main node is unreachable
+    /// for a long time.
+    pub(super) async fn fetch_state_loop(&self, ctx: &ctx::Ctx) -> ctx::Result<()> {
         const RETRY_INTERVAL: time::Duration = time::Duration::seconds(5);
+
+        let polling = self.sync_state_polling;
+        let mut last_head = None;
+        let mut head_changed_at = ctx.now();
+        let mut last_success_at = ctx.now();
         loop {
-            match ctx.wait(self.client.fetch_l2_block_number()).await? {
-                Ok(head) => {
+            let res = ctx
+                .with_timeout(polling.timeout)
+                .wait(self.client.fetch_l2_block_number())
+                .await;
+            match res {
+                Ok(Ok(head)) => {
                     self.sync_state.set_main_node_block(head);
-                    ctx.sleep(DELAY_INTERVAL).await?;
+                    last_success_at = ctx.now();
+                    if last_head != Some(head) {
+                        last_head = Some(head);
+                        head_changed_at = last_success_at;
+                    }
+                    if let Some(idle_timeout) = polling.idle_timeout {
+                        let is_idle = last_success_at - head_changed_at > idle_timeout
+                            && self.sync_state.is_synced();
+                        self.sync_state.set_main_node_idle(is_idle);
+                    }
+                    ctx.sleep(polling.interval).await?;
+                    continue;
                 }
-                Err(err) => {
+                Ok(Err(err)) => {
                     tracing::warn!("main_node_client.fetch_l2_block_number(): {err}");
-                    ctx.sleep(RETRY_INTERVAL).await?;
+                }
+                Err(canceled) => {
+                    if !ctx.is_active() {
+                        return Err(canceled.into());
+                    }
+                    tracing::warn!(
+                        "main_node_client.fetch_l2_block_number() timed out after {:?}",
+                        polling.timeout
+                    );
                 }
             }
+            self.sync_state
+                .register_main_node_failure(polling.max_consecutive_failures);
+            if let Some(idle_timeout) = polling.idle_timeout {
+                let unreachable_for = ctx.now() - last_success_at;
+                if polling.abort_on_idle && unreachable_for > idle_timeout {
+                    return Err(anyhow::anyhow!(
+                        "main node has been unreachable for {unreachable_for:?}, which exceeds idle timeout \
+                         {idle_timeout:?}; aborting"
+                    )
+                    .into());
+                }
+            }
+            ctx.sleep(RETRY_INTERVAL).await?;
         }
     }
 
@@ -147,17 +272,34 @@ impl Fetcher {
         Ok(zksync_protobuf::serde::deserialize(&genesis.0).context("deserialize(genesis)")?)
     }
 
-    /// Fetches (with retries) the given block from the main node.
-    async fn fetch_block(&self, ctx: &ctx::Ctx, n: MiniblockNumber) -> ctx::Result<FetchedBlock> {
+    /// Fetches (with retries) the given block from the main node. Outcomes of requests are reported
+    /// to the request `window`.
+    async fn fetch_block(
+        &self,
+        ctx: &ctx::Ctx,
+        n: MiniblockNumber,
+        window: &sync::watch::Sender<RequestWindow>,
+    ) -> ctx::Result<FetchedBlock> {
         // TODO: consider removing sleep in favor to just relying on the rate limiter.
         const RETRY_INTERVAL: time::Duration = time::Duration::seconds(5);
         loop {
             self.limiter.acquire(ctx, 1).await?;
             let res = ctx.wait(self.client.fetch_l2_block(n, true)).await?;
             match res {
-                Ok(Some(block)) => return Ok(block.try_into()?),
+                Ok(Some(block)) => {
+                    window.send_modify(RequestWindow::on_success);
+                    return Ok(block.try_into()?);
+                }
                 Ok(None) => {}
-                Err(err) if err.is_transient() => {}
+                Err(err) if matches!(err.as_ref(), ClientError::RequestTimeout) => {
+                    tracing::debug!(
+                        "Timed out fetching miniblock #{n}, shrinking request window: {err}"
+                    );
+                    window.send_modify(RequestWindow::on_timeout);
+                }
+                Err(err) if err.is_transient() => {
+                    tracing::debug!("Transient error fetching miniblock #{n}: {err}");
+                }
                 Err(err) => {
                     return Err(anyhow::format_err!("client.fetch_l2_block({}): {err}", n).into());
                 }
@@ -173,23 +315,41 @@ impl Fetcher {
         cursor: &mut storage::Cursor,
         end: Option<validator::BlockNumber>,
     ) -> ctx::Result<()> {
-        const MAX_CONCURRENT_REQUESTS: usize = 30;
         let first = cursor.next();
         let mut next = cursor.next();
+        let window = sync::watch::channel(RequestWindow::new(self.max_request_size)).0;
+        let window = &window;
         scope::run!(ctx, |ctx, s| async {
-            let (send, mut recv) = ctx::channel::bounded(MAX_CONCURRENT_REQUESTS);
+            let (send, mut recv) = ctx::channel::bounded(self.max_request_size.get());
             s.spawn(async {
                 let send = send;
+                let mut window_receiver = window.subscribe();
                 while end.map_or(true, |end| next < end) {
                     let n = MiniblockNumber(next.0.try_into().unwrap());
                     self.sync_state.wait_for_main_node_block(ctx, n).await?;
-                    send.send(ctx, s.spawn(self.fetch_block(ctx, n))).await?;
+                    sync::wait_for(ctx, &mut window_receiver, RequestWindow::has_capacity).await?;
+                    window.send_modify(|window| window.in_flight += 1);
+                    let block = s.spawn(async move {
+                        let res = self.fetch_block(ctx, n, window).await;
+                        window.send_modify(|window| window.in_flight -= 1);
+                        res
+                    });
+                    send.send(ctx, block).await?;
                     next = next.next();
                 }
                 Ok(())
             });
             while end.map_or(true, |end| cursor.next() < end) {
                 let block = recv.recv(ctx).await?.join(ctx).await?;
+                if let Some(missing) = cursor.detect_gap(block.number) {
+                    // Re-fetch the missing range rather than applying blocks out of order.
+                    for number in missing.start.0..missing.end.0 {
+                        let missing_block = self
+                            .fetch_block(ctx, MiniblockNumber(number), window)
+                            .await?;
+                        cursor.advance(missing_block).await?;
+                    }
+                }
                 cursor.advance(block).await?;
             }
             Ok(())
@@ -1,5 +1,7 @@
 //! Storage implementation based on DAL.
 
+use std::{ops, time::Instant};
+
 use anyhow::Context as _;
 use zksync_concurrency::{ctx, error::Wrap as _, sync, time};
 use zksync_consensus_bft::PayloadManager;
@@ -156,6 +158,14 @@ impl Cursor {
         validator::BlockNumber(self.inner.next_miniblock.0.into())
     }
 
+    /// Returns the range of missing miniblocks if the `received` miniblock skips ahead of the next expected one.
+    pub(super) fn detect_gap(
+        &self,
+        received: MiniblockNumber,
+    ) -> Option<ops::Range<MiniblockNumber>> {
+        self.inner.detect_gap(received)
+    }
+
     /// Advances the cursor by converting the block into actions and pushing them
     /// to the actions queue.
     /// Does nothing and returns Ok() if the block has been already processed.
@@ -371,6 +381,7 @@ impl PersistentBlockStore for BlockStore {
                     .try_into()
                     .context("Integer overflow converting block number")?,
             );
+            let received_at = Instant::now();
             let payload = Payload::decode(&block.payload).context("Payload::decode()")?;
             let block = FetchedBlock {
                 number,
@@ -387,7 +398,7 @@ impl PersistentBlockStore for BlockStore {
                 transactions: payload
                     .transactions
                     .into_iter()
-                    .map(FetchedTransaction::new)
+                    .map(|tx| FetchedTransaction::new(tx).with_block_received_at(received_at))
                     .collect(),
             };
             cursor.advance(block).await.context("cursor.advance()")?;
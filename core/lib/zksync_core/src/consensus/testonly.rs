@@ -1,6 +1,6 @@
 //! Utilities for testing the consensus module.
 
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, num::NonZeroUsize, sync::Arc};
 
 use anyhow::Context as _;
 use rand::Rng;
@@ -20,7 +20,7 @@ use zksync_web3_decl::{
 
 use crate::{
     api_server::web3::{state::InternalApiConfig, tests::spawn_http_server},
-    consensus::{fetcher::P2PConfig, Fetcher, Store},
+    consensus::{fetcher::P2PConfig, Fetcher, Store, SyncStatePollingConfig},
     genesis::{mock_genesis_config, GenesisParams},
     state_keeper::{
         io::{IoCursor, L1BatchParams, MiniblockParams},
@@ -301,13 +301,15 @@ impl StateKeeper {
     pub async fn run_centralized_fetcher(
         self,
         ctx: &ctx::Ctx,
-        client: HttpClient,
+        client: impl MainNodeClient,
     ) -> anyhow::Result<()> {
         Fetcher {
             store: self.store,
             client: Box::new(client),
             sync_state: SyncState::default(),
             limiter: unbounded_limiter(ctx),
+            max_request_size: NonZeroUsize::new(30).unwrap(),
+            sync_state_polling: SyncStatePollingConfig::default(),
         }
         .run_centralized(ctx, self.actions_sender)
         .await
@@ -325,6 +327,8 @@ impl StateKeeper {
             client: Box::new(client),
             sync_state: SyncState::default(),
             limiter: unbounded_limiter(ctx),
+            max_request_size: NonZeroUsize::new(30).unwrap(),
+            sync_state_polling: SyncStatePollingConfig::default(),
         }
         .run_p2p(ctx, self.actions_sender, cfg)
         .await
@@ -401,7 +405,7 @@ impl StateKeeperRunner {
                     ZkSyncStateKeeper::new(
                         stop_recv,
                         Box::new(io),
-                        Box::new(MockBatchExecutor),
+                        Box::new(MockBatchExecutor::default()),
                         OutputHandler::new(Box::new(persistence.with_tx_insertion())),
                         Arc::new(NoopSealer),
                     )
@@ -1,8 +1,18 @@
+use std::{
+    num::NonZeroUsize,
+    ops,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
 use anyhow::Context as _;
 use rand::{distributions::Distribution, Rng};
 use test_casing::test_casing;
 use tracing::Instrument as _;
-use zksync_concurrency::{ctx, scope};
+use zksync_concurrency::{ctx, limiter, scope, time};
+use zksync_config::GenesisConfig;
 use zksync_consensus_executor as executor;
 use zksync_consensus_network as network;
 use zksync_consensus_network::testonly::{new_configs, new_fullnode};
@@ -10,11 +20,19 @@ use zksync_consensus_roles::validator::testonly::Setup;
 use zksync_consensus_storage as storage;
 use zksync_consensus_storage::PersistentBlockStore as _;
 use zksync_consensus_utils::EncodeDist;
+use zksync_health_check::{CheckHealth, HealthStatus};
 use zksync_protobuf::testonly::{test_encode_all_formats, FmtConv};
-use zksync_types::{L1BatchNumber, MiniblockNumber};
+use zksync_types::{
+    api, snapshots::SnapshotRecoveryStatus, Address, L1BatchNumber, MiniblockNumber,
+    ProtocolVersionId, H256,
+};
+use zksync_web3_decl::{error::EnrichedClientResult, jsonrpsee::http_client::HttpClient};
 
 use super::*;
-use crate::utils::testonly::Snapshot;
+use crate::{
+    sync_layer::{MainNodeClient, SyncState},
+    utils::testonly::Snapshot,
+};
 
 async fn new_store(from_snapshot: bool) -> Store {
     match from_snapshot {
@@ -439,6 +457,266 @@ async fn test_centralized_fetcher(from_snapshot: bool) {
     .unwrap();
 }
 
+/// Main node client injecting a gap into the fetched miniblocks: the first request for `gap.start` returns
+/// the miniblock `gap.end` instead.
+#[derive(Debug)]
+struct GapInjectingClient {
+    inner: HttpClient,
+    gap: ops::Range<MiniblockNumber>,
+    gap_injected: AtomicBool,
+    requested_blocks: Arc<Mutex<Vec<MiniblockNumber>>>,
+}
+
+impl GapInjectingClient {
+    fn new(inner: HttpClient, gap: ops::Range<MiniblockNumber>) -> Self {
+        Self {
+            inner,
+            gap,
+            gap_injected: AtomicBool::new(false),
+            requested_blocks: Arc::default(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl MainNodeClient for GapInjectingClient {
+    async fn fetch_system_contract_by_hash(
+        &self,
+        hash: H256,
+    ) -> EnrichedClientResult<Option<Vec<u8>>> {
+        self.inner.fetch_system_contract_by_hash(hash).await
+    }
+
+    async fn fetch_genesis_contract_bytecode(
+        &self,
+        address: Address,
+    ) -> EnrichedClientResult<Option<Vec<u8>>> {
+        self.inner.fetch_genesis_contract_bytecode(address).await
+    }
+
+    async fn fetch_protocol_version(
+        &self,
+        protocol_version: ProtocolVersionId,
+    ) -> EnrichedClientResult<Option<api::ProtocolVersion>> {
+        self.inner.fetch_protocol_version(protocol_version).await
+    }
+
+    async fn fetch_l2_block_number(&self) -> EnrichedClientResult<MiniblockNumber> {
+        self.inner.fetch_l2_block_number().await
+    }
+
+    async fn fetch_l2_block(
+        &self,
+        number: MiniblockNumber,
+        with_transactions: bool,
+    ) -> EnrichedClientResult<Option<api::en::SyncBlock>> {
+        self.requested_blocks.lock().unwrap().push(number);
+        let number = if number == self.gap.start && !self.gap_injected.swap(true, Ordering::SeqCst)
+        {
+            self.gap.end
+        } else {
+            number
+        };
+        self.inner.fetch_l2_block(number, with_transactions).await
+    }
+
+    async fn fetch_consensus_genesis(
+        &self,
+    ) -> EnrichedClientResult<Option<api::en::ConsensusGenesis>> {
+        self.inner.fetch_consensus_genesis().await
+    }
+
+    async fn fetch_genesis_config(&self) -> EnrichedClientResult<GenesisConfig> {
+        self.inner.fetch_genesis_config().await
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_centralized_fetcher_with_gap() {
+    zksync_concurrency::testonly::abort_on_panic();
+    let ctx = &ctx::test_root(&ctx::RealClock);
+    let rng = &mut ctx.rng();
+
+    let requested_blocks = scope::run!(ctx, |ctx, s| async {
+        tracing::info!("Spawn a validator.");
+        let validator_store = new_store(false).await;
+        let (mut validator, runner) =
+            testonly::StateKeeper::new(ctx, validator_store.clone()).await?;
+        s.spawn_bg(runner.run(ctx).instrument(tracing::info_span!("validator")));
+        validator.seal_batch().await;
+        validator.push_random_blocks(rng, 10).await;
+        validator_store
+            .wait_for_payload(ctx, validator.last_block())
+            .await?;
+
+        tracing::info!("Spawn a node fetching blocks with an injected gap.");
+        let node_store = new_store(false).await;
+        let (node, runner) = testonly::StateKeeper::new(ctx, node_store.clone()).await?;
+        let gap_start = MiniblockNumber(u32::try_from(node.last_block().0).unwrap() + 2);
+        let client =
+            GapInjectingClient::new(validator.connect(ctx).await?, gap_start..gap_start + 3);
+        let requested_blocks = client.requested_blocks.clone();
+        s.spawn_bg(runner.run(ctx).instrument(tracing::info_span!("fetcher")));
+        s.spawn_bg(node.run_centralized_fetcher(ctx, client));
+
+        let want = validator_store
+            .wait_for_payload(ctx, validator.last_block())
+            .await?;
+        let got = node_store
+            .wait_for_payload(ctx, validator.last_block())
+            .await?;
+        assert_eq!(want, got);
+        Ok((gap_start, requested_blocks))
+    })
+    .await
+    .unwrap();
+
+    // Each missing miniblock must be requested both by the fetching pipeline and when re-fetching the gap.
+    let (gap_start, requested_blocks) = requested_blocks;
+    let requested_blocks = requested_blocks.lock().unwrap();
+    for number in gap_start.0..gap_start.0 + 3 {
+        let request_count = requested_blocks
+            .iter()
+            .filter(|&&requested| requested == MiniblockNumber(number))
+            .count();
+        assert!(request_count >= 2, "{requested_blocks:?}");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unreachable_main_node_is_reflected_in_sync_state_health() {
+    zksync_concurrency::testonly::abort_on_panic();
+    let ctx = &ctx::test_root(&ctx::AffineClock::new(10.));
+    let sync_state = SyncState::default();
+    sync_state.set_local_block(MiniblockNumber(0));
+    sync_state.set_main_node_block(MiniblockNumber(0));
+    assert_eq!(
+        sync_state.check_health().await.status(),
+        HealthStatus::Ready
+    );
+
+    // The mock client has no miniblocks, so fetching the latest miniblock number fails.
+    let fetcher = Fetcher {
+        store: new_store(false).await,
+        sync_state: sync_state.clone(),
+        client: Box::new(testonly::MockMainNodeClient::default()),
+        limiter: limiter::Limiter::new(
+            ctx,
+            limiter::Rate {
+                burst: 1,
+                refresh: time::Duration::ZERO,
+            },
+        ),
+        max_request_size: NonZeroUsize::new(1).unwrap(),
+        sync_state_polling: SyncStatePollingConfig::default(),
+    };
+    scope::run!(ctx, |ctx, s| async {
+        s.spawn_bg(fetcher.fetch_state_loop(ctx));
+        while sync_state.check_health().await.status() != HealthStatus::Affected {
+            ctx.sleep(time::Duration::milliseconds(10)).await?;
+        }
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    // A successful poll resets the sync status.
+    sync_state.set_main_node_block(MiniblockNumber(1));
+    assert_eq!(
+        sync_state.check_health().await.status(),
+        HealthStatus::Ready
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn idle_main_node_is_reflected_in_sync_state_health() {
+    zksync_concurrency::testonly::abort_on_panic();
+    let ctx = &ctx::test_root(&ctx::RealClock);
+    let sync_state = SyncState::default();
+    sync_state.set_local_block(MiniblockNumber(0));
+
+    // The mock client always reports miniblock #0 as the latest one, i.e., the network is quiet.
+    let snapshot = SnapshotRecoveryStatus {
+        l1_batch_number: L1BatchNumber(0),
+        l1_batch_root_hash: H256::zero(),
+        l1_batch_timestamp: 0,
+        miniblock_number: MiniblockNumber(0),
+        miniblock_hash: H256::zero(),
+        miniblock_timestamp: 0,
+        protocol_version: ProtocolVersionId::latest(),
+        storage_logs_chunks_processed: vec![],
+    };
+    let fetcher = Fetcher {
+        store: new_store(false).await,
+        sync_state: sync_state.clone(),
+        client: Box::new(testonly::MockMainNodeClient::for_snapshot_recovery(
+            &snapshot,
+        )),
+        limiter: limiter::Limiter::new(
+            ctx,
+            limiter::Rate {
+                burst: 1,
+                refresh: time::Duration::ZERO,
+            },
+        ),
+        max_request_size: NonZeroUsize::new(1).unwrap(),
+        sync_state_polling: SyncStatePollingConfig {
+            interval: time::Duration::milliseconds(10),
+            idle_timeout: Some(time::Duration::milliseconds(100)),
+            ..SyncStatePollingConfig::default()
+        },
+    };
+    scope::run!(ctx, |ctx, s| async {
+        s.spawn_bg(fetcher.fetch_state_loop(ctx));
+        while sync_state.check_health().await.status() != HealthStatus::Ready {
+            ctx.sleep(time::Duration::milliseconds(10)).await?;
+        }
+        // Prolonged idleness must trip the watchdog.
+        while sync_state.check_health().await.status() != HealthStatus::Degraded {
+            ctx.sleep(time::Duration::milliseconds(10)).await?;
+        }
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    let health = serde_json::to_value(sync_state.check_health().await).unwrap();
+    assert_eq!(health["details"]["main_node_idle"], true);
+    assert_eq!(health["details"]["main_node_unreachable"], false);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fetcher_aborts_if_main_node_is_unreachable_for_too_long() {
+    zksync_concurrency::testonly::abort_on_panic();
+    let ctx = &ctx::test_root(&ctx::AffineClock::new(10.));
+
+    // The mock client has no miniblocks, so fetching the latest miniblock number fails.
+    let fetcher = Fetcher {
+        store: new_store(false).await,
+        sync_state: SyncState::default(),
+        client: Box::new(testonly::MockMainNodeClient::default()),
+        limiter: limiter::Limiter::new(
+            ctx,
+            limiter::Rate {
+                burst: 1,
+                refresh: time::Duration::ZERO,
+            },
+        ),
+        max_request_size: NonZeroUsize::new(1).unwrap(),
+        sync_state_polling: SyncStatePollingConfig {
+            idle_timeout: Some(time::Duration::seconds(1)),
+            abort_on_idle: true,
+            ..SyncStatePollingConfig::default()
+        },
+    };
+    let err = fetcher.fetch_state_loop(ctx).await.unwrap_err();
+    let ctx::Error::Internal(err) = err else {
+        panic!("unexpected error: {err:?}");
+    };
+    let err = err.to_string();
+    assert!(err.contains("unreachable"), "{err}");
+}
+
 impl Distribution<Config> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Config {
         Config {
@@ -471,3 +749,23 @@ fn test_schema_encoding() {
     let rng = &mut ctx.rng();
     test_encode_all_formats::<FmtConv<config::Config>>(rng);
 }
+
+#[test]
+fn request_window_shrinks_on_timeouts_and_grows_on_successes() {
+    let mut window = fetcher::RequestWindow::new(NonZeroUsize::new(8).unwrap());
+    assert_eq!(window.size(), 8);
+
+    window.on_timeout();
+    assert_eq!(window.size(), 4);
+    for _ in 0..5 {
+        window.on_timeout();
+    }
+    assert_eq!(window.size(), 1);
+
+    window.on_success();
+    assert_eq!(window.size(), 2);
+    for _ in 0..10 {
+        window.on_success();
+    }
+    assert_eq!(window.size(), 8);
+}
@@ -1,4 +1,4 @@
-use std::{borrow::Cow, collections::HashSet, fmt, sync::Arc, time::Duration};
+use std::{borrow::Cow, collections::HashSet, fmt, slice, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use serde::Serialize;
@@ -12,7 +12,7 @@ use zksync_types::{
     commitment::L1BatchWithMetadata,
     ethabi::Token,
     pubdata_da::PubdataDA,
-    web3::{self, contract::Error as Web3ContractError, ethabi},
+    web3::{self, contract::Error as Web3ContractError, ethabi, signing::keccak256},
     Address, L1BatchNumber, ProtocolVersionId, H256, U256,
 };
 
@@ -48,6 +48,25 @@ impl CheckError {
     }
 }
 
+/// Mismatch between the L1 batch commitment reproduced from local data and the one published in the commit transaction on L1.
+#[derive(Debug, Clone, PartialEq, Serialize, thiserror::Error)]
+#[error(
+    "locally reproduced commitment for L1 batch #{l1_batch_number} differs from the reference in commit tx {commit_tx_hash:?}; expected hash: {expected_commitment_hash:?}, actual hash: {actual_commitment_hash:?}"
+)]
+struct CommitmentMismatch {
+    l1_batch_number: L1BatchNumber,
+    /// Hash of the L1 commit transaction the batch was checked against.
+    commit_tx_hash: H256,
+    /// Hash of the ABI-encoded commitment obtained from L1.
+    expected_commitment_hash: H256,
+    /// Hash of the ABI-encoded commitment reproduced from local data.
+    actual_commitment_hash: H256,
+}
+
+fn commitment_hash(commitment: &ethabi::Token) -> H256 {
+    H256(keccak256(&ethabi::encode(slice::from_ref(commitment))))
+}
+
 /// Handler of life cycle events emitted by [`ConsistencyChecker`].
 trait HandleConsistencyCheckerEvent: fmt::Debug + Send + Sync {
     fn initialize(&mut self);
@@ -57,6 +76,10 @@ trait HandleConsistencyCheckerEvent: fmt::Debug + Send + Sync {
     fn update_checked_batch(&mut self, last_checked_batch: L1BatchNumber);
 
     fn report_inconsistent_batch(&mut self, number: L1BatchNumber, err: &anyhow::Error);
+
+    /// Reports a fatal error after which the checker stops. Only called if the checker is configured
+    /// to keep the node running in the read-only mode.
+    fn report_fatal_error(&mut self, err: &anyhow::Error);
 }
 
 /// Health details reported by [`ConsistencyChecker`].
@@ -68,11 +91,19 @@ struct ConsistencyCheckerDetails {
     last_checked_batch: Option<L1BatchNumber>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     inconsistent_batches: Vec<L1BatchNumber>,
+    /// Details for inconsistent batches caused by a commitment mismatch.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    commitment_mismatches: Vec<CommitmentMismatch>,
+    /// Error that has stopped the checker (only reported in the read-only mode).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fatal_error: Option<String>,
 }
 
 impl ConsistencyCheckerDetails {
     fn health(&self) -> Health {
-        let status = if self.inconsistent_batches.is_empty() {
+        let status = if self.fatal_error.is_some() {
+            HealthStatus::Failed
+        } else if self.inconsistent_batches.is_empty() {
             HealthStatus::Ready
         } else {
             HealthStatus::Affected
@@ -118,10 +149,28 @@ impl HandleConsistencyCheckerEvent for ConsistencyCheckerHealthUpdater {
     }
 
     fn report_inconsistent_batch(&mut self, number: L1BatchNumber, err: &anyhow::Error) {
-        tracing::warn!("L1 batch #{number} is inconsistent with L1: {err:?}");
+        if let Some(mismatch) = err.downcast_ref::<CommitmentMismatch>() {
+            tracing::warn!(
+                l1_batch_number = %mismatch.l1_batch_number,
+                commit_tx_hash = ?mismatch.commit_tx_hash,
+                expected_commitment_hash = ?mismatch.expected_commitment_hash,
+                actual_commitment_hash = ?mismatch.actual_commitment_hash,
+                "L1 batch #{number} commitment is inconsistent with L1"
+            );
+            self.current_details
+                .commitment_mismatches
+                .push(mismatch.clone());
+        } else {
+            tracing::warn!("L1 batch #{number} is inconsistent with L1: {err:?}");
+        }
         self.current_details.inconsistent_batches.push(number);
         self.inner.update(self.current_details.health());
     }
+
+    fn report_fatal_error(&mut self, err: &anyhow::Error) {
+        self.current_details.fatal_error = Some(format!("{err:#}"));
+        self.inner.update(self.current_details.health());
+    }
 }
 
 /// Consistency checker behavior when L1 commit data divergence is detected.
@@ -234,11 +283,20 @@ impl LocalL1BatchCommitData {
         let local_token = self
             .l1_batch_commit_data_generator
             .l1_commit_batch(&self.l1_batch, &da);
-        anyhow::ensure!(
-            local_token == *reference,
-            "Locally reproduced commitment differs from the reference obtained from L1; \
-             local: {local_token:?}, reference: {reference:?}"
-        );
+        if local_token != *reference {
+            // Full commitments are too large to be included into the error, but they are valuable for debugging.
+            tracing::debug!(
+                "Locally reproduced commitment for L1 batch #{}: {local_token:?}, reference obtained from L1: {reference:?}",
+                self.l1_batch.header.number
+            );
+            return Err(CommitmentMismatch {
+                l1_batch_number: self.l1_batch.header.number,
+                commit_tx_hash: self.commit_tx_hash,
+                expected_commitment_hash: commitment_hash(reference),
+                actual_commitment_hash: commitment_hash(&local_token),
+            }
+            .into());
+        }
         Ok(())
     }
 }
@@ -309,6 +367,8 @@ pub struct ConsistencyChecker {
     pool: ConnectionPool<Core>,
     health_check: ReactiveHealthCheck,
     l1_batch_commit_data_generator: Arc<dyn L1BatchCommitDataGenerator>,
+    /// Sender of the signal halting node synchronization; set in the read-only fallback mode.
+    halt_sender: Option<watch::Sender<bool>>,
 }
 
 impl ConsistencyChecker {
@@ -332,6 +392,7 @@ impl ConsistencyChecker {
             pool,
             health_check,
             l1_batch_commit_data_generator,
+            halt_sender: None,
         })
     }
 
@@ -340,6 +401,15 @@ impl ConsistencyChecker {
         self
     }
 
+    /// Switches the checker to the read-only fallback mode. In this mode, a fatal error doesn't terminate the checker
+    /// (and thus the node). Instead, the checker sends `true` via `halt_sender`, marks its health as failed and idles
+    /// until the node is stopped. Components synchronizing the node (the state keeper, fetcher etc.) are expected
+    /// to stop on the halt signal, while the API server continues serving historical data.
+    pub fn with_read_only_fallback(mut self, halt_sender: watch::Sender<bool>) -> Self {
+        self.halt_sender = Some(halt_sender);
+        self
+    }
+
     /// Returns health check associated with this checker.
     pub fn health_check(&self) -> &ReactiveHealthCheck {
         &self.health_check
@@ -523,6 +593,22 @@ impl ConsistencyChecker {
     }
 
     pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let result = self.run_inner(&mut stop_receiver).await;
+        let (Err(err), Some(halt_sender)) = (&result, &self.halt_sender) else {
+            return result;
+        };
+
+        tracing::error!(
+            "Consistency checker failed; halting node synchronization and switching to read-only mode: {err:#}"
+        );
+        halt_sender.send_replace(true);
+        self.event_handler.report_fatal_error(err);
+        // Keep the failed health status until the node is stopped.
+        stop_receiver.wait_for(|stop| *stop).await.ok();
+        Ok(())
+    }
+
+    async fn run_inner(&mut self, stop_receiver: &mut watch::Receiver<bool>) -> anyhow::Result<()> {
         tracing::info!(
             "Starting consistency checker with diamond proxy contract: {:?}, sleep interval: {:?}, \
              max historic L1 batches to check: {}",
@@ -544,8 +630,7 @@ impl ConsistencyChecker {
 
         // It doesn't make sense to start the checker until we have at least one L1 batch with metadata.
         let earliest_l1_batch_number =
-            wait_for_l1_batch_with_metadata(&self.pool, self.sleep_interval, &mut stop_receiver)
-                .await?;
+            wait_for_l1_batch_with_metadata(&self.pool, self.sleep_interval, stop_receiver).await?;
 
         let Some(earliest_l1_batch_number) = earliest_l1_batch_number else {
             return Ok(()); // Stop signal received
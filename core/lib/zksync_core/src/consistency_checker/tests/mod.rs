@@ -5,16 +5,19 @@ use assert_matches::assert_matches;
 use once_cell::sync::Lazy;
 use test_casing::{test_casing, Product};
 use tokio::sync::mpsc;
-use zksync_config::GenesisConfig;
+use zksync_config::{configs, GenesisConfig};
 use zksync_dal::Connection;
 use zksync_eth_client::{clients::MockEthereum, Options};
+use zksync_health_check::CheckHealth;
 use zksync_types::{
-    aggregated_operations::AggregatedActionType, commitment::L1BatchWithMetadata, Log,
-    ProtocolVersion, ProtocolVersionId, H256,
+    aggregated_operations::AggregatedActionType, api::BlockNumber, commitment::L1BatchWithMetadata,
+    Log, ProtocolVersion, ProtocolVersionId, H256,
 };
+use zksync_web3_decl::{jsonrpsee::http_client::HttpClient, namespaces::EthNamespaceClient};
 
 use super::*;
 use crate::{
+    api_server::web3::{state::InternalApiConfig, tests::spawn_http_server},
     eth_sender::l1_batch_commit_data_generator::{
         RollupModeL1BatchCommitDataGenerator, ValidiumModeL1BatchCommitDataGenerator,
     },
@@ -93,6 +96,7 @@ pub(crate) fn create_mock_checker(
         pool,
         l1_batch_commit_data_generator,
         health_check,
+        halt_sender: None,
     }
 }
 
@@ -121,6 +125,10 @@ impl HandleConsistencyCheckerEvent for mpsc::UnboundedSender<L1BatchNumber> {
     fn report_inconsistent_batch(&mut self, _number: L1BatchNumber, _err: &anyhow::Error) {
         // Do nothing
     }
+
+    fn report_fatal_error(&mut self, _err: &anyhow::Error) {
+        // Do nothing
+    }
 }
 
 #[test_casing(2, [DeploymentMode::Rollup, DeploymentMode::Validium])]
@@ -822,3 +830,169 @@ async fn checker_detects_incorrect_tx_data_after_snapshot_recovery(
     )
     .await;
 }
+
+/// Postgres and L1 state with a single L1 batch, for which the commitment on L1 differs from the locally computed one.
+struct CommitmentMismatchFixture {
+    client: MockEthereum,
+    l1_batch_commit_data_generator: Arc<dyn L1BatchCommitDataGenerator>,
+    l1_batch: L1BatchWithMetadata,
+    expected_mismatch: CommitmentMismatch,
+}
+
+impl CommitmentMismatchFixture {
+    async fn new(pool: &ConnectionPool<Core>) -> Self {
+        let l1_batch_commit_data_generator: Arc<dyn L1BatchCommitDataGenerator> =
+            Arc::new(RollupModeL1BatchCommitDataGenerator {});
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+
+        let l1_batch = create_l1_batch_with_metadata(1);
+        let client = create_mock_ethereum();
+        let commit_tx_hash = IncorrectDataKind::MismatchedCommitDataTimestamp
+            .apply(&client, &l1_batch, l1_batch_commit_data_generator.clone())
+            .await;
+        let commit_tx_hash_by_l1_batch = HashMap::from([(l1_batch.header.number, commit_tx_hash)]);
+        let save_actions = [
+            SaveAction::InsertBatch(&l1_batch),
+            SaveAction::SaveMetadata(&l1_batch),
+            SaveAction::InsertCommitTx(l1_batch.header.number),
+        ];
+        for save_action in save_actions {
+            save_action
+                .apply(&mut storage, &commit_tx_hash_by_l1_batch)
+                .await;
+        }
+
+        let mut bogus_l1_batch = l1_batch.clone();
+        bogus_l1_batch.header.timestamp += 1;
+        let expected_commitment =
+            l1_batch_commit_data_generator.l1_commit_batch(&bogus_l1_batch, &PubdataDA::Calldata);
+        let actual_commitment =
+            l1_batch_commit_data_generator.l1_commit_batch(&l1_batch, &PubdataDA::Calldata);
+        let expected_mismatch = CommitmentMismatch {
+            l1_batch_number: l1_batch.header.number,
+            commit_tx_hash,
+            expected_commitment_hash: commitment_hash(&expected_commitment),
+            actual_commitment_hash: commitment_hash(&actual_commitment),
+        };
+
+        Self {
+            client,
+            l1_batch_commit_data_generator,
+            l1_batch,
+            expected_mismatch,
+        }
+    }
+
+    fn create_checker(self, pool: ConnectionPool<Core>) -> ConsistencyChecker {
+        create_mock_checker(self.client, pool, self.l1_batch_commit_data_generator)
+    }
+}
+
+#[tokio::test]
+async fn checker_reports_commitment_mismatch_details() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let fixture = CommitmentMismatchFixture::new(&pool).await;
+    let l1_batch = fixture.l1_batch.clone();
+    let expected_mismatch = fixture.expected_mismatch.clone();
+
+    let mut checker = fixture.create_checker(pool);
+    checker.l1_data_mismatch_behavior = L1DataMismatchBehavior::Log;
+    let health_check = checker.health_check().clone();
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let checker_task = tokio::spawn(checker.run(stop_receiver));
+
+    let health = tokio::time::timeout(Duration::from_secs(30), async {
+        loop {
+            let health = health_check.check_health().await;
+            if matches!(health.status(), HealthStatus::Affected) {
+                return health;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("Timed out waiting for checker to detect mismatch");
+    let details = serde_json::to_value(&health).unwrap()["details"].clone();
+    assert_eq!(
+        details["inconsistent_batches"],
+        serde_json::json!([l1_batch.header.number])
+    );
+    assert_eq!(
+        details["commitment_mismatches"],
+        serde_json::json!([expected_mismatch])
+    );
+
+    stop_sender.send_replace(true);
+    checker_task.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn checker_switches_to_read_only_mode_on_failure() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let fixture = CommitmentMismatchFixture::new(&pool).await;
+    let expected_mismatch = fixture.expected_mismatch.clone();
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let (halt_sender, mut halt_receiver) = watch::channel(false);
+    let checker = fixture
+        .create_checker(pool.clone())
+        .with_read_only_fallback(halt_sender);
+    let health_check = checker.health_check().clone();
+    let checker_task = tokio::spawn(checker.run(stop_receiver.clone()));
+
+    let api_config = InternalApiConfig::new(
+        &configs::chain::NetworkConfig::for_tests(),
+        &configs::api::Web3JsonRpcConfig::for_tests(),
+        &configs::contracts::ContractsConfig::for_tests(),
+    );
+    let mut server_handles = spawn_http_server(
+        api_config,
+        pool,
+        Default::default(),
+        Arc::default(),
+        stop_receiver,
+    )
+    .await;
+
+    tokio::time::timeout(
+        Duration::from_secs(30),
+        halt_receiver.wait_for(|halt| *halt),
+    )
+    .await
+    .expect("Timed out waiting for checker to halt synchronization")
+    .unwrap();
+
+    let health = health_check.check_health().await;
+    assert_matches!(health.status(), HealthStatus::Failed);
+    let details = serde_json::to_value(&health).unwrap()["details"].clone();
+    let fatal_error = details["fatal_error"].as_str().unwrap();
+    assert!(
+        fatal_error.contains("inconsistent with L1"),
+        "{fatal_error}"
+    );
+    assert_eq!(
+        details["commitment_mismatches"],
+        serde_json::json!([expected_mismatch])
+    );
+    // The checker must not terminate until the node is stopped.
+    assert!(!checker_task.is_finished());
+
+    // The API server should still serve historical data.
+    let local_addr = server_handles.wait_until_ready().await;
+    let client = <HttpClient>::builder()
+        .build(format!("http://{local_addr}/"))
+        .unwrap();
+    let genesis_block = client
+        .get_block_by_number(BlockNumber::Number(0.into()), false)
+        .await
+        .unwrap()
+        .expect("no genesis block");
+    assert_eq!(genesis_block.number, 0.into());
+
+    stop_sender.send_replace(true);
+    checker_task.await.unwrap().unwrap();
+    server_handles.shutdown().await;
+}
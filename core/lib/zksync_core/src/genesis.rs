@@ -168,6 +168,42 @@ pub fn mock_genesis_config() -> GenesisConfig {
     }
 }
 
+/// Computes the commitment of the genesis L1 batch from the genesis root hash, rollup last leaf index,
+/// base system contract hashes and protocol version specified in the `config`. Other config fields
+/// (e.g., VK hashes) don't influence the commitment.
+pub fn compute_genesis_commitment(config: &GenesisConfig) -> Result<H256, GenesisError> {
+    Ok(genesis_batch_commitment(config)?.hash().commitment)
+}
+
+fn genesis_batch_commitment(config: &GenesisConfig) -> Result<L1BatchCommitment, GenesisError> {
+    let protocol_version = ProtocolVersionId::try_from(config.protocol_version)
+        .map_err(|_| GenesisError::ProtocolVersion(config.protocol_version))?;
+    let base_system_contract_hashes = BaseSystemContractsHashes {
+        bootloader: config.bootloader_hash,
+        default_aa: config.default_aa_hash,
+    };
+    let commitment_input = CommitmentInput::for_genesis_batch(
+        config.genesis_root_hash,
+        config.rollup_last_leaf_index,
+        base_system_contract_hashes,
+        protocol_version,
+    );
+    Ok(L1BatchCommitment::new(commitment_input))
+}
+
+/// Checks that the genesis commitment in the `config` matches the one [computed](compute_genesis_commitment())
+/// from other config fields.
+pub fn verify_genesis_commitment(config: &GenesisConfig) -> Result<(), GenesisError> {
+    let computed = compute_genesis_commitment(config)?;
+    if config.genesis_commitment != computed {
+        return Err(GenesisError::Commitment(
+            config.genesis_commitment,
+            computed,
+        ));
+    }
+    Ok(())
+}
+
 pub struct GenesisBatchParams {
     pub root_hash: H256,
     pub commitment: H256,
@@ -209,17 +245,13 @@ pub async fn insert_genesis_batch(
     let genesis_root_hash = metadata.root_hash;
     let rollup_last_leaf_index = metadata.leaf_count + 1;
 
-    let base_system_contract_hashes = BaseSystemContractsHashes {
-        bootloader: genesis_params.config.bootloader_hash,
-        default_aa: genesis_params.config.default_aa_hash,
-    };
-    let commitment_input = CommitmentInput::for_genesis_batch(
+    // The commitment is computed from the actual tree data rather than the values in the config;
+    // the latter are checked by the caller.
+    let block_commitment = genesis_batch_commitment(&GenesisConfig {
         genesis_root_hash,
         rollup_last_leaf_index,
-        base_system_contract_hashes,
-        genesis_params.protocol_version(),
-    );
-    let block_commitment = L1BatchCommitment::new(commitment_input);
+        ..genesis_params.config.clone()
+    })?;
 
     save_genesis_l1_batch_metadata(
         &mut transaction,
@@ -582,6 +614,7 @@ pub async fn save_set_chain_id_tx(
 
 #[cfg(test)]
 mod tests {
+    use assert_matches::assert_matches;
     use zksync_config::GenesisConfig;
     use zksync_dal::{ConnectionPool, Core, CoreDal};
 
@@ -610,6 +643,71 @@ mod tests {
         assert!(!conn.blocks_dal().is_genesis_needed().await.unwrap());
     }
 
+    #[test]
+    fn validating_genesis_config() {
+        let config = GenesisConfig {
+            genesis_root_hash: H256::repeat_byte(1),
+            genesis_commitment: H256::repeat_byte(2),
+            ..mock_genesis_config()
+        };
+        config.validate().unwrap();
+
+        let err = GenesisConfig {
+            bootloader_hash: H256::zero(),
+            ..config.clone()
+        }
+        .validate()
+        .unwrap_err();
+        assert!(err.to_string().contains("bootloader_hash"), "{err}");
+
+        // `mock_genesis_config()` doesn't set the genesis root hash.
+        let err = mock_genesis_config().validate().unwrap_err();
+        assert!(err.to_string().contains("genesis_root_hash"), "{err}");
+
+        let err = GenesisConfig {
+            protocol_version: ProtocolVersionId::next() as u16 + 1,
+            ..config
+        }
+        .validate()
+        .unwrap_err();
+        assert!(err.to_string().contains("protocol_version"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn verifying_genesis_commitment() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        conn.blocks_dal().delete_genesis().await.unwrap();
+
+        let params = GenesisParams::mock();
+        let batch_params = insert_genesis_batch(&mut conn, &params).await.unwrap();
+        let config = GenesisConfig {
+            genesis_root_hash: batch_params.root_hash,
+            rollup_last_leaf_index: batch_params.rollup_last_leaf_index,
+            genesis_commitment: batch_params.commitment,
+            ..params.config().clone()
+        };
+        assert_eq!(
+            compute_genesis_commitment(&config).unwrap(),
+            batch_params.commitment
+        );
+        verify_genesis_commitment(&config).unwrap();
+
+        let err = verify_genesis_commitment(&GenesisConfig {
+            rollup_last_leaf_index: batch_params.rollup_last_leaf_index + 1,
+            ..config.clone()
+        })
+        .unwrap_err();
+        assert_matches!(err, GenesisError::Commitment(stored, _) if stored == batch_params.commitment);
+
+        let err = verify_genesis_commitment(&GenesisConfig {
+            protocol_version: u16::MAX,
+            ..config
+        })
+        .unwrap_err();
+        assert_matches!(err, GenesisError::ProtocolVersion(u16::MAX));
+    }
+
     #[tokio::test]
     async fn running_genesis_with_big_chain_id() {
         let pool = ConnectionPool::<Core>::test_pool().await;
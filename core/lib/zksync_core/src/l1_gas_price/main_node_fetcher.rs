@@ -9,7 +9,10 @@ use zksync_web3_decl::{
     error::ClientRpcContext, jsonrpsee::http_client::HttpClient, namespaces::ZksNamespaceClient,
 };
 
-use crate::fee_model::BatchFeeModelInputProvider;
+use crate::{
+    fee_model::BatchFeeModelInputProvider, sync_layer::MainNodeRetryConfig,
+    utils::clamp_polling_interval,
+};
 
 const SLEEP_INTERVAL: Duration = Duration::from_secs(5);
 
@@ -22,6 +25,8 @@ const SLEEP_INTERVAL: Duration = Duration::from_secs(5);
 #[derive(Debug)]
 pub struct MainNodeFeeParamsFetcher {
     client: HttpClient,
+    retries: MainNodeRetryConfig,
+    sleep_interval: Duration,
     main_node_fee_params: RwLock<FeeParams>,
 }
 
@@ -29,10 +34,25 @@ impl MainNodeFeeParamsFetcher {
     pub fn new(client: HttpClient) -> Self {
         Self {
             client,
+            retries: MainNodeRetryConfig::default(),
+            sleep_interval: SLEEP_INTERVAL,
             main_node_fee_params: RwLock::new(FeeParams::sensible_v1_default()),
         }
     }
 
+    /// Retries requests to the main node failed with transient errors according to the specified policy.
+    /// If retries are exhausted, the fetcher waits for the next polling iteration.
+    pub fn with_retries(mut self, retries: MainNodeRetryConfig) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Ensures that the fetcher doesn't poll the main node more frequently than `min_interval`.
+    pub fn with_min_polling_interval(mut self, min_interval: Duration) -> Self {
+        self.sleep_interval = clamp_polling_interval(self.sleep_interval, min_interval);
+        self
+    }
+
     pub async fn run(self: Arc<Self>, stop_receiver: Receiver<bool>) -> anyhow::Result<()> {
         loop {
             if *stop_receiver.borrow() {
@@ -41,22 +61,23 @@ impl MainNodeFeeParamsFetcher {
             }
 
             let fetch_result = self
-                .client
-                .get_fee_params()
-                .rpc_context("get_fee_params")
+                .retries
+                .retry("get_fee_params", || {
+                    self.client.get_fee_params().rpc_context("get_fee_params")
+                })
                 .await;
             let main_node_fee_params = match fetch_result {
                 Ok(price) => price,
                 Err(err) => {
                     tracing::warn!("Unable to get the gas price: {}", err);
                     // A delay to avoid spamming the main node with requests.
-                    tokio::time::sleep(SLEEP_INTERVAL).await;
+                    tokio::time::sleep(self.sleep_interval).await;
                     continue;
                 }
             };
             *self.main_node_fee_params.write().unwrap() = main_node_fee_params;
 
-            tokio::time::sleep(SLEEP_INTERVAL).await;
+            tokio::time::sleep(self.sleep_interval).await;
         }
         Ok(())
     }
@@ -2,6 +2,7 @@
 
 use std::{
     net::Ipv4Addr,
+    path::Path,
     str::FromStr,
     sync::Arc,
     time::{Duration, Instant},
@@ -47,6 +48,7 @@ use zksync_state::PostgresStorageCaches;
 use zksync_types::{fee_model::FeeModelConfig, L2ChainId};
 
 use crate::{
+    admin::AdminServer,
     api_server::{
         contract_verification,
         execution_sandbox::{VmConcurrencyBarrier, VmConcurrencyLimiter},
@@ -56,6 +58,7 @@ use crate::{
         web3::{self, state::InternalApiConfig, Namespace},
     },
     basic_witness_input_producer::BasicWitnessInputProducer,
+    block_reverter::{BlockReverter, BlockReverterFlags, L1ExecutedBatchesRevert, NodeRole},
     commitment_generator::CommitmentGenerator,
     eth_sender::{
         l1_batch_commit_data_generator::{
@@ -91,6 +94,7 @@ use crate::{
     utils::ensure_l1_batch_commit_data_generation_mode,
 };
 
+pub mod admin;
 pub mod api_server;
 pub mod basic_witness_input_producer;
 pub mod block_reverter;
@@ -160,7 +164,7 @@ pub fn setup_sigint_handler() -> oneshot::Receiver<()> {
     sigint_receiver
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, strum::EnumIter)]
 pub enum Component {
     /// Public Web3 API running on HTTP server.
     HttpApi,
@@ -190,6 +194,31 @@ pub enum Component {
     Consensus,
     /// Component generating commitment for L1 batches.
     CommitmentGenerator,
+    /// Authenticated HTTP server exposing runtime controls to node operators.
+    Admin,
+}
+
+impl Component {
+    /// Returns the name of this component as accepted by [`Components`] parsing.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::HttpApi => "http_api",
+            Self::WsApi => "ws_api",
+            Self::ContractVerificationApi => "contract_verification_api",
+            Self::Tree => "tree",
+            Self::TreeApi => "tree_api",
+            Self::EthWatcher => "eth_watcher",
+            Self::EthTxAggregator => "eth_tx_aggregator",
+            Self::EthTxManager => "eth_tx_manager",
+            Self::StateKeeper => "state_keeper",
+            Self::BasicWitnessInputProducer => "basic_witness_input_producer",
+            Self::Housekeeper => "housekeeper",
+            Self::ProofDataHandler => "proof_data_handler",
+            Self::Consensus => "consensus",
+            Self::CommitmentGenerator => "commitment_generator",
+            Self::Admin => "admin",
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -226,11 +255,22 @@ impl FromStr for Components {
             "proof_data_handler" => Ok(Components(vec![Component::ProofDataHandler])),
             "consensus" => Ok(Components(vec![Component::Consensus])),
             "commitment_generator" => Ok(Components(vec![Component::CommitmentGenerator])),
+            "admin" => Ok(Components(vec![Component::Admin])),
             other => Err(format!("{} is not a valid component name", other)),
         }
     }
 }
 
+/// Checks that all dependencies of the specified components are satisfied. This check should be performed
+/// before any components are initialized, so that misconfigurations are detected early.
+pub fn validate_components(components: &[Component]) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        !components.contains(&Component::TreeApi) || components.contains(&Component::Tree),
+        "Merkle tree API (`tree_api` component) requires the `tree` component to be enabled"
+    );
+    Ok(())
+}
+
 pub async fn initialize_components(
     configs: &TempConfigStore,
     components: &[Component],
@@ -241,6 +281,7 @@ pub async fn initialize_components(
     HealthCheckHandle,
 )> {
     tracing::info!("Starting the components: {components:?}");
+    validate_components(components)?;
 
     let db_config = configs.db_config.clone().context("db_config")?;
     let postgres_config = configs.postgres_config.clone().context("postgres_config")?;
@@ -329,6 +370,8 @@ pub async fn initialize_components(
     );
 
     let (stop_sender, stop_receiver) = watch::channel(false);
+    // Drain mode is controlled by the admin server; if it's not run, the mode is never enabled.
+    let (drain_sender, drain_receiver) = watch::channel(false);
 
     // Prometheus exporter and circuit breaker checker should run for every component configuration.
     let prom_config = configs
@@ -372,7 +415,8 @@ pub async fn initialize_components(
             &network_config,
             &api_config.web3_json_rpc,
             &contracts_config,
-        );
+        )
+        .with_components(components);
 
         // Lazily initialize storage caches only when they are needed (e.g., skip their initialization
         // if we only run the explorer APIs). This is required because the cache update task will
@@ -399,7 +443,7 @@ pub async fn initialize_components(
                 .context("gas_adjuster.get_or_init()")?;
             let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
                 bounded_gas_adjuster,
-                FeeModelConfig::from_state_keeper_config(&state_keeper_config),
+                FeeModelConfig::from_state_keeper_config(&state_keeper_config)?,
             ));
             run_http_api(
                 &mut task_futures,
@@ -412,6 +456,7 @@ pub async fn initialize_components(
                 connection_pool.clone(),
                 replica_connection_pool.clone(),
                 stop_receiver.clone(),
+                drain_receiver.clone(),
                 batch_fee_input_provider,
                 state_keeper_config.save_call_traces,
                 storage_caches.clone().unwrap(),
@@ -447,7 +492,7 @@ pub async fn initialize_components(
                 .context("gas_adjuster.get_or_init()")?;
             let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
                 bounded_gas_adjuster,
-                FeeModelConfig::from_state_keeper_config(&state_keeper_config),
+                FeeModelConfig::from_state_keeper_config(&state_keeper_config)?,
             ));
             run_ws_api(
                 &mut task_futures,
@@ -461,6 +506,7 @@ pub async fn initialize_components(
                 connection_pool.clone(),
                 replica_connection_pool.clone(),
                 stop_receiver.clone(),
+                drain_receiver.clone(),
                 storage_caches,
             )
             .await
@@ -508,7 +554,7 @@ pub async fn initialize_components(
             .context("state_keeper_config")?;
         let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
             bounded_gas_adjuster,
-            FeeModelConfig::from_state_keeper_config(&state_keeper_config),
+            FeeModelConfig::from_state_keeper_config(&state_keeper_config)?,
         ));
         add_state_keeper_to_task_futures(
             &mut task_futures,
@@ -763,6 +809,37 @@ pub async fn initialize_components(
         ));
     }
 
+    if components.contains(&Component::Admin) {
+        let admin_config = configs.admin_config.clone().context("admin_config")?;
+        let admin_token = secrets
+            .admin_token
+            .clone()
+            .context("admin token must be specified in secrets to run the admin server")?;
+        let exported_config = admin::export_config(configs)?;
+        let mut admin_server = AdminServer::new(&admin_config, admin_token, exported_config)
+            .context("failed creating admin server")?
+            .with_drain_mode(drain_sender);
+        // Rollbacks are only safe if no other component can modify the node state concurrently.
+        if matches!(components, [Component::Admin]) {
+            let mut flags = BlockReverterFlags::POSTGRES | BlockReverterFlags::TREE;
+            if Path::new(&db_config.state_keeper_db_path).exists() {
+                flags |= BlockReverterFlags::SK_CACHE;
+            }
+            // The admin server refuses to roll back batches committed on L1, which is stricter than this check.
+            let block_reverter = BlockReverter::new(
+                NodeRole::Main,
+                db_config.state_keeper_db_path.clone(),
+                db_config.merkle_tree.path.clone(),
+                None,
+                connection_pool.clone(),
+                L1ExecutedBatchesRevert::Allowed,
+            );
+            admin_server =
+                admin_server.with_block_reverter(block_reverter, connection_pool.clone(), flags);
+        }
+        task_futures.push(tokio::spawn(admin_server.run(stop_receiver.clone())));
+    }
+
     // Run healthcheck server for all components.
     let db_health_check = ConnectionPoolHealthCheck::new(replica_connection_pool);
     app_health.insert_custom_component(Arc::new(db_health_check));
@@ -807,11 +884,18 @@ async fn add_state_keeper_to_task_futures(
         .build()
         .await
         .context("failed to build miniblock_sealer_pool")?;
-    let (persistence, miniblock_sealer) = StateKeeperPersistence::new(
+    let (persistence, mut miniblock_sealer) = StateKeeperPersistence::new(
         miniblock_sealer_pool,
         contracts_config.l2_erc20_bridge_addr,
         state_keeper_config.miniblock_seal_queue_capacity,
     );
+    if state_keeper_config.save_call_traces && state_keeper_config.separate_call_traces_pool {
+        let call_traces_pool = pool_builder
+            .build()
+            .await
+            .context("failed to build call_traces_pool")?;
+        miniblock_sealer = miniblock_sealer.with_call_traces_pool(call_traces_pool);
+    }
     task_futures.push(tokio::spawn(miniblock_sealer.run()));
 
     let (state_keeper, async_catchup_task) = create_state_keeper(
@@ -825,7 +909,7 @@ async fn add_state_keeper_to_task_futures(
         OutputHandler::new(Box::new(persistence)),
         stop_receiver.clone(),
     )
-    .await;
+    .await?;
 
     let mut stop_receiver_clone = stop_receiver.clone();
     task_futures.push(tokio::task::spawn(async move {
@@ -833,9 +917,14 @@ async fn add_state_keeper_to_task_futures(
         stop_receiver_clone.changed().await?;
         result
     }));
-    task_futures.push(tokio::spawn(
-        state_keeper.run_fee_address_migration(state_keeper_pool),
-    ));
+    let fee_address_migration = state_keeper.run_fee_address_migration(state_keeper_pool);
+    let mut stop_receiver_clone = stop_receiver.clone();
+    task_futures.push(tokio::spawn(async move {
+        fee_address_migration.await?;
+        // We don't want the task to exit on success, since this would shut down the node.
+        stop_receiver_clone.changed().await?;
+        Ok(())
+    }));
     task_futures.push(tokio::spawn(state_keeper.run()));
 
     let mempool_fetcher_pool = pool_builder
@@ -862,10 +951,6 @@ async fn add_trees_to_task_futures(
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     if !components.contains(&Component::Tree) {
-        anyhow::ensure!(
-            !components.contains(&Component::TreeApi),
-            "Merkle tree API cannot be started without a tree component"
-        );
         return Ok(());
     }
 
@@ -1149,6 +1234,7 @@ fn build_storage_caches(
     Ok(storage_caches)
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn build_tx_sender(
     tx_sender_config: &TxSenderConfig,
     web3_json_config: &Web3JsonRpcConfig,
@@ -1157,6 +1243,7 @@ async fn build_tx_sender(
     master_pool: ConnectionPool<Core>,
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     storage_caches: PostgresStorageCaches,
+    drain_mode: watch::Receiver<bool>,
 ) -> (TxSender, VmConcurrencyBarrier) {
     let sequencer_sealer = SequencerSealer::new(state_keeper_config.clone());
     let master_pool_sink = MasterPoolSink::new(master_pool);
@@ -1165,10 +1252,14 @@ async fn build_tx_sender(
         replica_pool.clone(),
         Arc::new(master_pool_sink),
     )
-    .with_sealer(Arc::new(sequencer_sealer));
+    .with_sealer(Arc::new(sequencer_sealer))
+    .with_drain_mode(drain_mode);
 
     let max_concurrency = web3_json_config.vm_concurrency_limit();
-    let (vm_concurrency_limiter, vm_barrier) = VmConcurrencyLimiter::new(max_concurrency);
+    let (mut vm_concurrency_limiter, vm_barrier) = VmConcurrencyLimiter::new(max_concurrency);
+    if let Some(limit) = web3_json_config.trace_call_concurrency_limit {
+        vm_concurrency_limiter = vm_concurrency_limiter.with_trace_concurrency_limit(limit);
+    }
 
     let batch_fee_input_provider =
         ApiFeeInputProvider::new(batch_fee_model_input_provider, replica_pool);
@@ -1196,6 +1287,7 @@ async fn run_http_api(
     master_connection_pool: ConnectionPool<Core>,
     replica_connection_pool: ConnectionPool<Core>,
     stop_receiver: watch::Receiver<bool>,
+    drain_mode: watch::Receiver<bool>,
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     with_debug_namespace: bool,
     storage_caches: PostgresStorageCaches,
@@ -1208,6 +1300,7 @@ async fn run_http_api(
         master_connection_pool,
         batch_fee_model_input_provider,
         storage_caches,
+        drain_mode,
     )
     .await;
 
@@ -1229,6 +1322,7 @@ async fn run_http_api(
             .with_filter_limit(api_config.web3_json_rpc.filters_limit())
             .with_batch_request_size_limit(api_config.web3_json_rpc.max_batch_request_size())
             .with_response_body_size_limit(api_config.web3_json_rpc.max_response_body_size())
+            .with_request_body_size_limit(api_config.web3_json_rpc.max_request_body_size())
             .with_tx_sender(tx_sender)
             .with_vm_barrier(vm_barrier)
             .enable_api_namespaces(namespaces);
@@ -1261,6 +1355,7 @@ async fn run_ws_api(
     master_connection_pool: ConnectionPool<Core>,
     replica_connection_pool: ConnectionPool<Core>,
     stop_receiver: watch::Receiver<bool>,
+    drain_mode: watch::Receiver<bool>,
     storage_caches: PostgresStorageCaches,
 ) -> anyhow::Result<()> {
     let (tx_sender, vm_barrier) = build_tx_sender(
@@ -1271,6 +1366,7 @@ async fn run_ws_api(
         master_connection_pool,
         batch_fee_model_input_provider,
         storage_caches,
+        drain_mode,
     )
     .await;
     let last_miniblock_pool = ConnectionPool::<Core>::singleton(postgres_config.replica_url()?)
@@ -1289,6 +1385,7 @@ async fn run_ws_api(
             .with_subscriptions_limit(api_config.web3_json_rpc.subscriptions_limit())
             .with_batch_request_size_limit(api_config.web3_json_rpc.max_batch_request_size())
             .with_response_body_size_limit(api_config.web3_json_rpc.max_response_body_size())
+            .with_request_body_size_limit(api_config.web3_json_rpc.max_request_body_size())
             .with_websocket_requests_per_minute_limit(
                 api_config
                     .web3_json_rpc
@@ -1303,6 +1400,9 @@ async fn run_ws_api(
         api_builder = api_builder.with_tree_api(tree_api.clone());
         app_health.insert_custom_component(tree_api);
     }
+    if let Some(limit) = api_config.web3_json_rpc.filters_limit_per_connection() {
+        api_builder = api_builder.with_filter_limit_per_connection(limit);
+    }
 
     let server_handles = api_builder
         .build()
@@ -1352,3 +1452,70 @@ async fn circuit_breakers_for_components(
     }
     Ok(circuit_breakers)
 }
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn validating_components() {
+        let valid_components = [
+            vec![],
+            vec![Component::Tree],
+            vec![Component::Tree, Component::TreeApi],
+            vec![Component::TreeApi, Component::Tree],
+            vec![Component::HttpApi, Component::StateKeeper, Component::Tree],
+        ];
+        for components in valid_components {
+            validate_components(&components)
+                .unwrap_or_else(|err| panic!("{components:?} should be valid: {err}"));
+        }
+
+        let invalid_components = [
+            vec![Component::TreeApi],
+            vec![
+                Component::HttpApi,
+                Component::StateKeeper,
+                Component::TreeApi,
+            ],
+        ];
+        for components in invalid_components {
+            let err = validate_components(&components).unwrap_err().to_string();
+            assert!(err.contains("tree_api"), "{components:?}: {err}");
+        }
+    }
+
+    #[test]
+    fn component_names_are_parseable() {
+        for component in Component::iter() {
+            let parsed: Components = component.name().parse().unwrap();
+            assert_eq!(parsed.0, [component]);
+        }
+    }
+
+    #[test]
+    fn reporting_main_node_components() {
+        let network_config = NetworkConfig::for_tests();
+        let web3_config = Web3JsonRpcConfig::for_tests();
+        let contracts_config = ContractsConfig::for_tests();
+        let base_config = InternalApiConfig::new(&network_config, &web3_config, &contracts_config);
+
+        let api_config = base_config.clone().with_components(&[
+            Component::HttpApi,
+            Component::StateKeeper,
+            Component::Tree,
+        ]);
+        assert_eq!(
+            api_config.node_components,
+            ["http_api", "state_keeper", "tree"]
+        );
+        assert!(!api_config.consensus_enabled);
+
+        let api_config =
+            base_config.with_components(&[Component::StateKeeper, Component::Consensus]);
+        assert_eq!(api_config.node_components, ["state_keeper", "consensus"]);
+        assert!(api_config.consensus_enabled);
+    }
+}
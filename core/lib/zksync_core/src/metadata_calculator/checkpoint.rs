@@ -0,0 +1,160 @@
+//! Periodic Merkle tree checkpoints allowing to restore the tree without rebuilding it from scratch.
+//!
+//! Checkpoints are stored in a directory next to the tree RocksDB (e.g., `/db/tree.checkpoints` for the tree
+//! at `/db/tree`); each checkpoint is a RocksDB checkpoint in a subdirectory named after the last L1 batch
+//! processed by the tree. Only the latest checkpoint is retained.
+
+use std::{
+    fs, io,
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context as _;
+use zksync_types::L1BatchNumber;
+
+use super::{helpers::AsyncTree, metrics::METRICS};
+
+/// Returns a path next to `db_path` with the specified `suffix` appended to the file name.
+pub(super) fn sibling_path(db_path: &Path, suffix: &str) -> anyhow::Result<PathBuf> {
+    let mut file_name = db_path
+        .file_name()
+        .context("Merkle tree path doesn't have a file name")?
+        .to_owned();
+    file_name.push(".");
+    file_name.push(suffix);
+    Ok(db_path.with_file_name(file_name))
+}
+
+/// Returns the directory with checkpoints for the tree at `db_path`.
+pub(super) fn checkpoints_dir(db_path: &Path) -> anyhow::Result<PathBuf> {
+    sibling_path(db_path, "checkpoints")
+}
+
+/// Returns the L1 batch and the path of the latest checkpoint in `dir`, if any.
+pub(super) fn latest_checkpoint(dir: &Path) -> anyhow::Result<Option<(L1BatchNumber, PathBuf)>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed reading `{}`", dir.display()));
+        }
+    };
+
+    let mut latest = None;
+    for entry in entries {
+        let entry = entry.with_context(|| format!("failed reading `{}`", dir.display()))?;
+        // Skip entries not corresponding to checkpoints, e.g. temporary dirs of incomplete checkpoints.
+        let Some(l1_batch) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse().ok())
+        else {
+            continue;
+        };
+        let l1_batch = L1BatchNumber(l1_batch);
+        if latest
+            .as_ref()
+            .map_or(true, |(number, _)| *number < l1_batch)
+        {
+            latest = Some((l1_batch, entry.path()));
+        }
+    }
+    Ok(latest)
+}
+
+/// Copies a checkpoint from `src` to `dst` so that it can be opened without modifying the checkpoint.
+/// Immutable SST files are hard-linked if possible; other files (e.g., the manifest) are appended to by RocksDB,
+/// so they are copied.
+pub(super) fn copy_checkpoint(src: &Path, dst: &Path) -> io::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let src_path = entry?.path();
+        let dst_path = dst.join(src_path.file_name().unwrap());
+        // ^ `unwrap()` is safe: paths returned by `read_dir()` always have a file name
+        let is_sst = src_path.extension().map_or(false, |ext| ext == "sst");
+        if !is_sst || fs::hard_link(&src_path, &dst_path).is_err() {
+            fs::copy(&src_path, &dst_path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Periodically creates checkpoints of the Merkle tree.
+#[derive(Debug)]
+pub(super) struct TreeCheckpointer {
+    dir: PathBuf,
+    interval: NonZeroU32,
+    last_checkpoint: Option<L1BatchNumber>,
+}
+
+impl TreeCheckpointer {
+    pub fn new(db_path: &Path, interval: NonZeroU32) -> anyhow::Result<Self> {
+        let dir = checkpoints_dir(db_path)?;
+        let last_checkpoint = latest_checkpoint(&dir)?.map(|(l1_batch, _)| l1_batch);
+        if let Some(l1_batch) = last_checkpoint {
+            METRICS.last_checkpoint_l1_batch.set(l1_batch.0.into());
+        }
+        Ok(Self {
+            dir,
+            interval,
+            last_checkpoint,
+        })
+    }
+
+    /// Creates a checkpoint of the tree state flushed to RocksDB if at least `interval` L1 batches were processed
+    /// since the last checkpoint. Errors are logged rather than propagated since checkpoints are an optimization
+    /// and should not stop tree updates.
+    pub async fn checkpoint_if_needed(&mut self, tree: &AsyncTree) {
+        let Some(l1_batch) = tree.next_l1_batch_number().0.checked_sub(1) else {
+            return; // The tree is empty
+        };
+        let l1_batch = L1BatchNumber(l1_batch);
+        let is_needed = self.last_checkpoint.map_or(true, |last_l1_batch| {
+            l1_batch.0 >= last_l1_batch.0 + self.interval.get()
+        });
+        if !is_needed {
+            return;
+        }
+
+        if let Err(err) = self.create_checkpoint(tree, l1_batch).await {
+            tracing::warn!(
+                "Failed creating Merkle tree checkpoint for L1 batch #{l1_batch}: {err:#}"
+            );
+        } else {
+            tracing::info!("Created Merkle tree checkpoint for L1 batch #{l1_batch}");
+            self.last_checkpoint = Some(l1_batch);
+            METRICS.last_checkpoint_l1_batch.set(l1_batch.0.into());
+        }
+    }
+
+    async fn create_checkpoint(
+        &self,
+        tree: &AsyncTree,
+        l1_batch: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let path = self.dir.join(l1_batch.0.to_string());
+        if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            // May be a leftover from a checkpoint created before reverting the tree.
+            tokio::fs::remove_dir_all(&path)
+                .await
+                .with_context(|| format!("failed removing `{}`", path.display()))?;
+        }
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .with_context(|| format!("failed creating `{}`", self.dir.display()))?;
+        tree.create_checkpoint(path.clone()).await?;
+
+        // Remove all other checkpoints.
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let entry_path = entry.path();
+            if entry_path != path && entry.file_type().await?.is_dir() {
+                tokio::fs::remove_dir_all(&entry_path)
+                    .await
+                    .with_context(|| format!("failed removing `{}`", entry_path.display()))?;
+            }
+        }
+        Ok(())
+    }
+}
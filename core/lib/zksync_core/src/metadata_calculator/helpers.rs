@@ -21,10 +21,11 @@ use zksync_merkle_tree::{
     recovery::MerkleTreeRecovery,
     Database, Key, NoVersionError, RocksDBWrapper, TreeEntry, TreeEntryWithProof, TreeInstruction,
 };
-use zksync_storage::{RocksDB, RocksDBOptions, StalledWritesRetries};
+use zksync_storage::{RocksDB, RocksDBMemoryBudget, RocksDBOptions, StalledWritesRetries};
 use zksync_types::{block::L1BatchHeader, L1BatchNumber, StorageKey, H256};
 
 use super::metrics::{LoadChangesStage, TreeUpdateStage, METRICS};
+use crate::sync_layer::SyncState;
 
 /// General information about the Merkle tree.
 #[derive(Debug, Serialize, Deserialize)]
@@ -35,6 +36,34 @@ pub struct MerkleTreeInfo {
     pub leaf_count: u64,
 }
 
+impl MerkleTreeInfo {
+    /// Lag of the tree (in L1 batches) relative to Postgres after which the tree is considered degraded
+    /// since it doesn't keep up with the sealed L1 batches.
+    const MAX_L1_BATCH_LAG: u32 = 50;
+
+    /// Converts this info into tree health given the number of sealed L1 batches in Postgres not yet processed
+    /// by the tree. The lightweight tree only checks that the tree tip keeps up with Postgres; the full tree
+    /// additionally checks that it's ready to serve proofs for recent L1 batches, i.e. that its lag doesn't exceed
+    /// `max_l1_batch_lag_for_proofs`. If the latter is `None`, the proof readiness check is skipped.
+    pub(super) fn health(
+        self,
+        l1_batch_lag: u32,
+        max_l1_batch_lag_for_proofs: Option<u32>,
+    ) -> Health {
+        let exceeds_lag_for_proofs =
+            max_l1_batch_lag_for_proofs.map_or(false, |max_lag| l1_batch_lag > max_lag);
+        let status = match self.mode {
+            MerkleTreeMode::Full if exceeds_lag_for_proofs => HealthStatus::Affected,
+            _ if l1_batch_lag > Self::MAX_L1_BATCH_LAG => HealthStatus::Degraded,
+            _ => HealthStatus::Ready,
+        };
+        Health::from(status).with_details(MerkleTreeHealth::MainLoop {
+            info: self,
+            l1_batch_lag,
+        })
+    }
+}
+
 /// Health details for a Merkle tree.
 #[derive(Debug, Serialize)]
 #[serde(tag = "stage", rename_all = "snake_case")]
@@ -44,18 +73,23 @@ pub(super) enum MerkleTreeHealth {
         chunk_count: u64,
         recovered_chunk_count: u64,
     },
-    MainLoop(MerkleTreeInfo),
+    MainLoop {
+        #[serde(flatten)]
+        info: MerkleTreeInfo,
+        l1_batch_lag: u32,
+    },
 }
 
 impl From<MerkleTreeHealth> for Health {
     fn from(details: MerkleTreeHealth) -> Self {
-        Self::from(HealthStatus::Ready).with_details(details)
-    }
-}
-
-impl From<MerkleTreeInfo> for Health {
-    fn from(info: MerkleTreeInfo) -> Self {
-        Self::from(HealthStatus::Ready).with_details(MerkleTreeHealth::MainLoop(info))
+        let status = match &details {
+            // The tree cannot serve requests until it enters the main loop.
+            MerkleTreeHealth::Initialization | MerkleTreeHealth::Recovery { .. } => {
+                HealthStatus::Initializing
+            }
+            MerkleTreeHealth::MainLoop { .. } => HealthStatus::Ready,
+        };
+        Self::from(status).with_details(details)
     }
 }
 
@@ -64,16 +98,20 @@ pub(super) async fn create_db(
     path: PathBuf,
     block_cache_capacity: usize,
     memtable_capacity: usize,
+    memory_budget: Option<RocksDBMemoryBudget>,
     stalled_writes_timeout: Duration,
     multi_get_chunk_size: usize,
+    collect_statistics: bool,
 ) -> anyhow::Result<RocksDBWrapper> {
     tokio::task::spawn_blocking(move || {
         create_db_sync(
             &path,
             block_cache_capacity,
             memtable_capacity,
+            memory_budget,
             stalled_writes_timeout,
             multi_get_chunk_size,
+            collect_statistics,
         )
     })
     .await
@@ -84,13 +122,16 @@ fn create_db_sync(
     path: &Path,
     block_cache_capacity: usize,
     memtable_capacity: usize,
+    memory_budget: Option<RocksDBMemoryBudget>,
     stalled_writes_timeout: Duration,
     multi_get_chunk_size: usize,
+    collect_statistics: bool,
 ) -> anyhow::Result<RocksDBWrapper> {
     tracing::info!(
         "Initializing Merkle tree database at `{path}` with {multi_get_chunk_size} multi-get chunk size, \
          {block_cache_capacity}B block cache, {memtable_capacity}B memtable capacity, \
-         {stalled_writes_timeout:?} stalled writes timeout",
+         memory budget {memory_budget:?}, {stalled_writes_timeout:?} stalled writes timeout, \
+         collecting statistics: {collect_statistics}",
         path = path.display()
     );
 
@@ -101,6 +142,11 @@ fn create_db_sync(
             large_memtable_capacity: Some(memtable_capacity),
             stalled_writes_retries: StalledWritesRetries::new(stalled_writes_timeout),
             max_open_files: None,
+            write_buffer_size: None,
+            max_background_jobs: None,
+            compaction_style: None,
+            memory_budget,
+            collect_statistics,
         },
     )?;
     if cfg!(test) {
@@ -165,11 +211,19 @@ impl AsyncTree {
         self.as_ref().next_l1_batch_number()
     }
 
-    #[cfg(test)]
     pub fn root_hash(&self) -> H256 {
         self.as_ref().root_hash()
     }
 
+    /// Creates a checkpoint of the tree state flushed to RocksDB at the specified `path`, which must not exist.
+    pub async fn create_checkpoint(&self, path: PathBuf) -> anyhow::Result<()> {
+        let reader = self.as_ref().reader();
+        tokio::task::spawn_blocking(move || reader.create_checkpoint(&path))
+            .await
+            .context("panicked creating Merkle tree checkpoint")?
+            .context("failed creating Merkle tree checkpoint")
+    }
+
     pub async fn process_l1_batch(
         &mut self,
         storage_logs: Vec<TreeInstruction<StorageKey>>,
@@ -211,6 +265,10 @@ pub(crate) struct AsyncTreeReader {
 }
 
 impl AsyncTreeReader {
+    pub fn mode(&self) -> MerkleTreeMode {
+        self.mode
+    }
+
     pub async fn info(self) -> MerkleTreeInfo {
         tokio::task::spawn_blocking(move || MerkleTreeInfo {
             mode: self.mode,
@@ -222,6 +280,12 @@ impl AsyncTreeReader {
         .unwrap()
     }
 
+    pub async fn l1_batch_root_hash(self, l1_batch_number: L1BatchNumber) -> Option<H256> {
+        tokio::task::spawn_blocking(move || self.inner.l1_batch_root_hash(l1_batch_number))
+            .await
+            .unwrap()
+    }
+
     pub async fn entries_with_proofs(
         self,
         l1_batch_number: L1BatchNumber,
@@ -360,6 +424,9 @@ impl GenericAsyncTree {
 #[derive(Debug, Clone)]
 pub(super) struct Delayer {
     delay_interval: Duration,
+    /// If set, the delay is adapted to the node sync lag: while the node is catching up with the main node,
+    /// the tree runs without delays.
+    sync_state: Option<SyncState>,
     // Notifies the tests about the next L1 batch number and tree root hash when the calculator
     // runs out of L1 batches to process. (Since RocksDB is exclusive, we cannot just create
     // another instance to check these params on the test side without stopping the calculation.)
@@ -368,25 +435,51 @@ pub(super) struct Delayer {
 }
 
 impl Delayer {
+    /// Sync lag (in miniblocks) above which the node is considered to be catching up with the main node.
+    const CATCH_UP_LAG_THRESHOLD: u32 = 100;
+
     pub fn new(delay_interval: Duration) -> Self {
         Self {
             delay_interval,
+            sync_state: None,
             #[cfg(test)]
             delay_notifier: mpsc::unbounded_channel().0,
         }
     }
 
+    pub fn with_sync_state(mut self, sync_state: SyncState) -> Self {
+        self.sync_state = Some(sync_state);
+        self
+    }
+
     pub fn delay_interval(&self) -> Duration {
         self.delay_interval
     }
 
+    /// Checks whether the node lags significantly behind the main node. Always returns `false` if the sync state
+    /// is not set (e.g., on the main node).
+    pub fn is_catching_up(&self) -> bool {
+        let lag = self.sync_state.as_ref().and_then(SyncState::lag);
+        lag.map_or(false, |lag| lag > Self::CATCH_UP_LAG_THRESHOLD)
+    }
+
+    /// Returns the delay applied if the tree has made no progress. This is zero if the adaptive delay is enabled
+    /// and the node lags significantly behind the main node; otherwise, it's the configured delay interval.
+    fn effective_delay_interval(&self) -> Duration {
+        if self.is_catching_up() {
+            Duration::ZERO
+        } else {
+            self.delay_interval
+        }
+    }
+
     #[cfg_attr(not(test), allow(unused))] // `tree` is only used in test mode
     pub fn wait(&self, tree: &AsyncTree) -> impl Future<Output = ()> {
         #[cfg(test)]
         self.delay_notifier
             .send((tree.next_l1_batch_number(), tree.root_hash()))
             .ok();
-        tokio::time::sleep(self.delay_interval)
+        tokio::time::sleep(self.effective_delay_interval())
     }
 }
 
@@ -480,7 +573,7 @@ mod tests {
     use tempfile::TempDir;
     use zksync_dal::{ConnectionPool, Core};
     use zksync_prover_interface::inputs::PrepareBasicCircuitsJob;
-    use zksync_types::{StorageKey, StorageLog};
+    use zksync_types::{MiniblockNumber, StorageKey, StorageLog};
 
     use super::*;
     use crate::{
@@ -610,9 +703,11 @@ mod tests {
         let db = create_db(
             temp_dir.path().to_owned(),
             0,
-            16 << 20,       // 16 MiB,
+            16 << 20, // 16 MiB,
+            None,
             Duration::ZERO, // writes should never be stalled in tests
             500,
+            false,
         )
         .await
         .unwrap();
@@ -760,4 +855,26 @@ mod tests {
             assert_log_equivalence(&mut storage, &mut tree, L1BatchNumber(batch_number)).await;
         }
     }
+
+    #[test]
+    fn adaptive_delay_based_on_sync_lag() {
+        let delay_interval = Duration::from_millis(100);
+        let sync_state = SyncState::default();
+        let delayer = Delayer::new(delay_interval).with_sync_state(sync_state.clone());
+        // The lag is unknown yet.
+        assert!(!delayer.is_catching_up());
+        assert_eq!(delayer.effective_delay_interval(), delay_interval);
+
+        sync_state.set_local_block(MiniblockNumber(10));
+        sync_state.set_main_node_block(MiniblockNumber(11 + Delayer::CATCH_UP_LAG_THRESHOLD));
+        assert!(delayer.is_catching_up());
+        assert_eq!(delayer.effective_delay_interval(), Duration::ZERO);
+        // The static delay should be unaffected by the lag.
+        let static_delayer = Delayer::new(delay_interval);
+        assert_eq!(static_delayer.effective_delay_interval(), delay_interval);
+
+        sync_state.set_local_block(MiniblockNumber(11 + Delayer::CATCH_UP_LAG_THRESHOLD));
+        assert!(!delayer.is_catching_up());
+        assert_eq!(delayer.effective_delay_interval(), delay_interval);
+    }
 }
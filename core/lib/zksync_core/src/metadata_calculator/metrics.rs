@@ -3,8 +3,8 @@
 use std::time::{Duration, Instant};
 
 use vise::{
-    Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver, Metrics,
-    Unit,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver,
+    Metrics, Unit,
 };
 use zksync_types::block::L1BatchHeader;
 use zksync_utils::time::seconds_since_epoch;
@@ -85,6 +85,11 @@ pub(super) struct MetadataCalculatorMetrics {
     /// The lag can only be positive if Postgres was restored from a backup truncating some
     /// of the batches already processed by the tree.
     pub backup_lag: Gauge<u64>,
+    /// Last L1 batch for which a Merkle tree checkpoint was created.
+    pub last_checkpoint_l1_batch: Gauge<u64>,
+    /// Number of times the tree had to wait for an L1 batch that was reported as sealed, but wasn't found
+    /// in Postgres (e.g., because of an L1 batch rollback racing the tree).
+    pub waiting_for_l1_batch: Counter,
     /// Number of zero values that need to be checked for L1 batch of the initial write in the process
     /// of updating the Merkle tree.
     #[metrics(buckets = COUNTS_BUCKETS)]
@@ -2,6 +2,9 @@
 //! stores them in the DB.
 
 use std::{
+    io,
+    num::NonZeroU32,
+    path::Path,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -12,17 +15,25 @@ use zksync_config::configs::{
     chain::OperationsManagerConfig,
     database::{MerkleTreeConfig, MerkleTreeMode},
 };
-use zksync_dal::{ConnectionPool, Core};
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_health_check::{HealthUpdater, ReactiveHealthCheck};
+use zksync_merkle_tree::RocksDBWrapper;
 use zksync_object_store::ObjectStore;
+use zksync_storage::RocksDBMemoryBudget;
+use zksync_types::L1BatchNumber;
 
 pub use self::helpers::LazyAsyncTreeReader;
 pub(crate) use self::helpers::{AsyncTreeReader, L1BatchWithLogs, MerkleTreeInfo};
 use self::{
-    helpers::{create_db, Delayer, GenericAsyncTree, MerkleTreeHealth},
+    checkpoint::TreeCheckpointer,
+    helpers::{
+        create_db, AsyncTree, AsyncTreeRecovery, Delayer, GenericAsyncTree, MerkleTreeHealth,
+    },
     updater::TreeUpdater,
 };
+use crate::sync_layer::SyncState;
 
+mod checkpoint;
 mod helpers;
 mod metrics;
 mod recovery;
@@ -41,6 +52,10 @@ pub struct MetadataCalculatorConfig {
     pub delay_interval: Duration,
     /// Maximum number of L1 batches to get from Postgres on a single update iteration.
     pub max_l1_batches_per_iter: usize,
+    /// Lag of the full tree (in L1 batches) relative to Postgres after which the tree health is considered affected
+    /// since the tree cannot serve proofs for recent L1 batches. Not applied while the node is catching up
+    /// with the main node.
+    pub max_l1_batch_lag_for_proofs: u32,
     /// Chunk size for multi-get operations. Can speed up loading data for the Merkle tree on some environments,
     /// but the effects vary wildly depending on the setup (e.g., the filesystem used).
     pub multi_get_chunk_size: usize,
@@ -49,8 +64,17 @@ pub struct MetadataCalculatorConfig {
     /// Capacity of RocksDB memtables. Can be set to a reasonably large value (order of 512 MiB)
     /// to mitigate write stalls.
     pub memtable_capacity: usize,
+    /// Memory budget shared with other RocksDB instances. If set, the block cache from the budget is used
+    /// instead of a dedicated cache with `block_cache_capacity`.
+    pub memory_budget: Option<RocksDBMemoryBudget>,
     /// Timeout to wait for the Merkle tree database to run compaction on stalled writes.
     pub stalled_writes_timeout: Duration,
+    /// Interval in L1 batches between checkpoints of the tree. Checkpoints are stored next to the tree
+    /// and allow to rebuild the tree without processing the entire storage logs history. If not set,
+    /// checkpoints are not created.
+    pub checkpoint_interval: Option<NonZeroU32>,
+    /// Whether to collect RocksDB statistics (e.g., IO metrics) for the tree database.
+    pub collect_rocksdb_statistics: bool,
 }
 
 impl MetadataCalculatorConfig {
@@ -63,14 +87,40 @@ impl MetadataCalculatorConfig {
             mode: merkle_tree_config.mode,
             delay_interval: operation_config.delay_interval(),
             max_l1_batches_per_iter: merkle_tree_config.max_l1_batches_per_iter,
+            max_l1_batch_lag_for_proofs: merkle_tree_config.max_l1_batch_lag_for_proofs,
             multi_get_chunk_size: merkle_tree_config.multi_get_chunk_size,
             block_cache_capacity: merkle_tree_config.block_cache_size(),
             memtable_capacity: merkle_tree_config.memtable_capacity(),
+            memory_budget: None,
             stalled_writes_timeout: merkle_tree_config.stalled_writes_timeout(),
+            checkpoint_interval: None,
+            collect_rocksdb_statistics: merkle_tree_config.statistics_enabled,
         }
     }
 }
 
+/// Source a Merkle tree was rebuilt from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TreeRestoreSource {
+    /// Tree checkpoint for the specified L1 batch.
+    Checkpoint(L1BatchNumber),
+    /// Storage logs in Postgres.
+    StorageLogs,
+}
+
+/// Outcome of restoring a Merkle tree from a checkpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CheckpointRestore {
+    /// The tree was restored from the checkpoint for the specified L1 batch.
+    Restored(L1BatchNumber),
+    /// There is no usable checkpoint; the tree should be built as usual.
+    NoCheckpoint,
+    /// The tree already exists, so it doesn't need to be restored.
+    TreeExists,
+    /// Restoring was interrupted by a stop signal.
+    Interrupted,
+}
+
 #[derive(Debug)]
 pub struct MetadataCalculator {
     config: MetadataCalculatorConfig,
@@ -103,6 +153,14 @@ impl MetadataCalculator {
         })
     }
 
+    /// Enables the adaptive delay between tree updates based on the node sync lag. While the node is catching up
+    /// with the main node, the tree runs without delays; once the node is caught up, the configured delay interval
+    /// is used.
+    pub fn with_sync_state(mut self, sync_state: SyncState) -> Self {
+        self.delayer = self.delayer.with_sync_state(sync_state);
+        self
+    }
+
     /// Returns a health check for this calculator.
     pub fn tree_health_check(&self) -> ReactiveHealthCheck {
         self.health_updater.subscribe()
@@ -118,12 +176,25 @@ impl MetadataCalculator {
             .update(MerkleTreeHealth::Initialization.into());
 
         let started_at = Instant::now();
-        let db = create_db(
-            self.config.db_path.clone().into(),
+        let db = self.create_db(Path::new(&self.config.db_path)).await?;
+        tracing::info!(
+            "Opened Merkle tree RocksDB with configuration {:?} in {:?}",
+            self.config,
+            started_at.elapsed()
+        );
+
+        Ok(GenericAsyncTree::new(db, self.config.mode).await)
+    }
+
+    async fn create_db(&self, path: &Path) -> anyhow::Result<RocksDBWrapper> {
+        create_db(
+            path.to_owned(),
             self.config.block_cache_capacity,
             self.config.memtable_capacity,
+            self.config.memory_budget.clone(),
             self.config.stalled_writes_timeout,
             self.config.multi_get_chunk_size,
+            self.config.collect_rocksdb_statistics,
         )
         .await
         .with_context(|| {
@@ -131,14 +202,317 @@ impl MetadataCalculator {
                 "failed opening Merkle tree RocksDB with configuration {:?}",
                 self.config
             )
-        })?;
+        })
+    }
+
+    /// Rebuilds the Merkle tree from scratch using the storage logs history in Postgres and replaces the tree
+    /// at the configured path with the rebuilt one. The tree is rebuilt for the latest L1 batch with metadata
+    /// in Postgres, and its root hash is checked against the one stored for this batch; later batches are processed
+    /// by the calculator as usual once it's started.
+    ///
+    /// If the tree has a checkpoint (see [`MetadataCalculatorConfig::checkpoint_interval`]) not newer than
+    /// the target L1 batch, the tree is restored from the checkpoint, and only the remaining L1 batches are processed.
+    /// If the checkpoint cannot be used (e.g., it's inconsistent with Postgres), the tree is rebuilt from scratch.
+    ///
+    /// The tree is built in a temporary directory next to the configured path. The existing tree is only removed
+    /// after the rebuilt tree is verified, so an interrupted or failed rebuild leaves it intact.
+    ///
+    /// Returns the L1 batch the tree was rebuilt for, or `None` if rebuilding was interrupted by a stop signal.
+    pub async fn rebuild_tree(
+        self,
+        pool: &ConnectionPool<Core>,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<Option<L1BatchNumber>> {
+        let rebuilt = self.rebuild_tree_inner(pool, stop_receiver).await?;
+        Ok(rebuilt.map(|(l1_batch, _)| l1_batch))
+    }
+
+    async fn rebuild_tree_inner(
+        &self,
+        pool: &ConnectionPool<Core>,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<Option<(L1BatchNumber, TreeRestoreSource)>> {
+        let mut storage = pool.connection_tagged("metadata_calculator").await?;
+        let l1_batch = storage
+            .blocks_dal()
+            .get_last_l1_batch_number_with_metadata()
+            .await
+            .context("failed getting last L1 batch with metadata")?
+            .context(
+                "Postgres doesn't contain L1 batches with metadata; the tree can be built by running the node normally",
+            )?;
+        drop(storage);
+
+        let db_path = Path::new(&self.config.db_path);
+        let rebuild_path = checkpoint::sibling_path(db_path, "rebuild")?;
+        Self::remove_rebuild_leftovers(&rebuild_path).await?;
+
+        let restored = self
+            .restore_tree_from_checkpoint(pool, l1_batch, &rebuild_path, stop_receiver)
+            .await?;
+        let source = match restored {
+            CheckpointRestore::Restored(checkpoint_l1_batch) => {
+                TreeRestoreSource::Checkpoint(checkpoint_l1_batch)
+            }
+            CheckpointRestore::NoCheckpoint | CheckpointRestore::TreeExists => {
+                Self::remove_rebuild_leftovers(&rebuild_path).await?;
+                tracing::info!(
+                    "Rebuilding Merkle tree for L1 batch #{l1_batch} at `{}`",
+                    rebuild_path.display()
+                );
+                let db = self.create_db(&rebuild_path).await?;
+                let tree = AsyncTreeRecovery::new(db, l1_batch.0.into(), self.config.mode);
+                let tree = tree
+                    .rebuild(l1_batch, pool, stop_receiver, &self.health_updater)
+                    .await?;
+                if tree.is_none() {
+                    return Ok(None);
+                }
+                // The rebuilt RocksDB instance is closed when `tree` is dropped here, before moving it.
+                TreeRestoreSource::StorageLogs
+            }
+            CheckpointRestore::Interrupted => return Ok(None),
+        };
+
+        if tokio::fs::try_exists(db_path).await.unwrap_or(false) {
+            tokio::fs::remove_dir_all(db_path)
+                .await
+                .with_context(|| format!("failed removing `{}`", db_path.display()))?;
+        }
+        tokio::fs::rename(&rebuild_path, db_path)
+            .await
+            .with_context(|| {
+                format!(
+                    "failed moving rebuilt tree from `{}` to `{}`",
+                    rebuild_path.display(),
+                    db_path.display()
+                )
+            })?;
         tracing::info!(
-            "Opened Merkle tree RocksDB with configuration {:?} in {:?}",
-            self.config,
-            started_at.elapsed()
+            "Merkle tree for L1 batch #{l1_batch} is rebuilt from {source:?} at `{}`",
+            db_path.display()
         );
+        Ok(Some((l1_batch, source)))
+    }
 
-        Ok(GenericAsyncTree::new(db, self.config.mode).await)
+    async fn remove_rebuild_leftovers(rebuild_path: &Path) -> anyhow::Result<()> {
+        if tokio::fs::try_exists(rebuild_path).await.unwrap_or(false) {
+            tracing::info!(
+                "Removing leftovers of a previous tree rebuild at `{}`",
+                rebuild_path.display()
+            );
+            tokio::fs::remove_dir_all(rebuild_path)
+                .await
+                .with_context(|| format!("failed removing `{}`", rebuild_path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Restores the tree for `l1_batch` at `rebuild_path` from the latest checkpoint not newer than `l1_batch`
+    /// and processes the remaining L1 batches. If the checkpoint cannot be opened or is inconsistent with Postgres,
+    /// it is discarded, and [`CheckpointRestore::NoCheckpoint`] is returned.
+    async fn restore_tree_from_checkpoint(
+        &self,
+        pool: &ConnectionPool<Core>,
+        l1_batch: L1BatchNumber,
+        rebuild_path: &Path,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<CheckpointRestore> {
+        let checkpoints_dir = checkpoint::checkpoints_dir(Path::new(&self.config.db_path))?;
+        let Some((checkpoint_l1_batch, checkpoint_path)) =
+            checkpoint::latest_checkpoint(&checkpoints_dir)?
+        else {
+            return Ok(CheckpointRestore::NoCheckpoint);
+        };
+        if checkpoint_l1_batch > l1_batch {
+            tracing::info!(
+                "Latest Merkle tree checkpoint is for L1 batch #{checkpoint_l1_batch}, which is newer \
+                 than the rebuilt L1 batch #{l1_batch}; ignoring it"
+            );
+            return Ok(CheckpointRestore::NoCheckpoint);
+        }
+
+        tracing::info!(
+            "Restoring Merkle tree from checkpoint for L1 batch #{checkpoint_l1_batch} at `{}`",
+            checkpoint_path.display()
+        );
+        let mut storage = pool.connection_tagged("metadata_calculator").await?;
+        let tree = self
+            .open_checkpoint(
+                &mut storage,
+                &checkpoint_path,
+                checkpoint_l1_batch,
+                rebuild_path,
+            )
+            .await;
+        let mut tree = match tree {
+            Ok(tree) => tree,
+            Err(err) => {
+                tracing::warn!(
+                    "Merkle tree checkpoint for L1 batch #{checkpoint_l1_batch} cannot be used: {err:#}; \
+                     discarding it and rebuilding the tree from scratch"
+                );
+                Self::discard_checkpoint(&checkpoint_path).await;
+                return Ok(CheckpointRestore::NoCheckpoint);
+            }
+        };
+
+        let mut unsaved_l1_batches = 0;
+        for number in (checkpoint_l1_batch.0 + 1)..=l1_batch.0 {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, restoring Merkle tree is shut down");
+                return Ok(CheckpointRestore::Interrupted);
+            }
+            let number = L1BatchNumber(number);
+            let l1_batch_data = L1BatchWithLogs::new(&mut storage, number)
+                .await
+                .with_context(|| format!("L1 batch #{number} is not in Postgres"))?;
+            tree.process_l1_batch(l1_batch_data.storage_logs).await;
+            unsaved_l1_batches += 1;
+            if unsaved_l1_batches >= self.max_l1_batches_per_iter {
+                tree.save().await;
+                unsaved_l1_batches = 0;
+            }
+        }
+        tree.save().await;
+
+        Self::check_restored_tree(&mut storage, &tree, l1_batch)
+            .await
+            .context("restored Merkle tree is inconsistent with Postgres")?;
+        tracing::info!(
+            "Restored Merkle tree from checkpoint for L1 batch #{checkpoint_l1_batch} and processed {} L1 batches",
+            l1_batch.0 - checkpoint_l1_batch.0
+        );
+        Ok(CheckpointRestore::Restored(checkpoint_l1_batch))
+    }
+
+    /// Copies the checkpoint for `checkpoint_l1_batch` at `checkpoint_path` to `db_path`, opens it and checks it
+    /// against Postgres.
+    async fn open_checkpoint(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        checkpoint_path: &Path,
+        checkpoint_l1_batch: L1BatchNumber,
+        db_path: &Path,
+    ) -> anyhow::Result<AsyncTree> {
+        let checkpoint_path = checkpoint_path.to_owned();
+        let db_path_clone = db_path.to_owned();
+        tokio::task::spawn_blocking(move || {
+            checkpoint::copy_checkpoint(&checkpoint_path, &db_path_clone)
+        })
+        .await
+        .context("panicked copying Merkle tree checkpoint")?
+        .context("failed copying Merkle tree checkpoint")?;
+
+        let db = self.create_db(db_path).await?;
+        let tree = AsyncTree::new(db, self.config.mode);
+        Self::check_restored_tree(storage, &tree, checkpoint_l1_batch).await?;
+        Ok(tree)
+    }
+
+    /// Removes an unusable checkpoint so that it's not used again. Errors are logged rather than propagated
+    /// since the checkpoint is ignored anyway.
+    async fn discard_checkpoint(checkpoint_path: &Path) {
+        if let Err(err) = tokio::fs::remove_dir_all(checkpoint_path).await {
+            tracing::warn!(
+                "Failed removing Merkle tree checkpoint at `{}`: {err}",
+                checkpoint_path.display()
+            );
+        }
+    }
+
+    /// Restores the tree from the latest checkpoint if the tree RocksDB is missing, e.g. after it was removed
+    /// because of corruption caused by a crash. If the checkpoint cannot be opened or is inconsistent
+    /// with Postgres, it is discarded, and the tree will be built from scratch as usual.
+    async fn restore_missing_tree_from_checkpoint(
+        &self,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<CheckpointRestore> {
+        let db_path = Path::new(&self.config.db_path);
+        if !Self::is_dir_missing_or_empty(db_path).await? {
+            return Ok(CheckpointRestore::TreeExists);
+        }
+        let checkpoints_dir = checkpoint::checkpoints_dir(db_path)?;
+        let Some((checkpoint_l1_batch, checkpoint_path)) =
+            checkpoint::latest_checkpoint(&checkpoints_dir)?
+        else {
+            return Ok(CheckpointRestore::NoCheckpoint);
+        };
+
+        tracing::info!(
+            "Merkle tree at `{}` is missing; restoring it from checkpoint for L1 batch #{checkpoint_l1_batch} at `{}`",
+            db_path.display(),
+            checkpoint_path.display()
+        );
+        let mut storage = pool.connection_tagged("metadata_calculator").await?;
+        let tree = self
+            .open_checkpoint(&mut storage, &checkpoint_path, checkpoint_l1_batch, db_path)
+            .await;
+        drop(storage);
+        let err = match tree {
+            Ok(tree) => {
+                // Close the RocksDB instance; it will be reopened when creating the tree.
+                drop(tree);
+                tracing::info!(
+                    "Restored Merkle tree from checkpoint for L1 batch #{checkpoint_l1_batch}"
+                );
+                return Ok(CheckpointRestore::Restored(checkpoint_l1_batch));
+            }
+            Err(err) => err,
+        };
+
+        tracing::warn!(
+            "Merkle tree checkpoint for L1 batch #{checkpoint_l1_batch} cannot be used: {err:#}; \
+             discarding it and building the tree from scratch"
+        );
+        Self::discard_checkpoint(&checkpoint_path).await;
+        if tokio::fs::try_exists(db_path).await.unwrap_or(false) {
+            tokio::fs::remove_dir_all(db_path)
+                .await
+                .with_context(|| format!("failed removing `{}`", db_path.display()))?;
+        }
+        Ok(CheckpointRestore::NoCheckpoint)
+    }
+
+    async fn is_dir_missing_or_empty(path: &Path) -> anyhow::Result<bool> {
+        let mut entries = match tokio::fs::read_dir(path).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(true),
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed reading `{}`", path.display()));
+            }
+        };
+        let first_entry = entries
+            .next_entry()
+            .await
+            .with_context(|| format!("failed reading `{}`", path.display()))?;
+        Ok(first_entry.is_none())
+    }
+
+    async fn check_restored_tree(
+        storage: &mut Connection<'_, Core>,
+        tree: &AsyncTree,
+        l1_batch: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let next_l1_batch = tree.next_l1_batch_number();
+        anyhow::ensure!(
+            next_l1_batch == l1_batch + 1,
+            "unexpected next L1 batch for the tree: expected #{}, got #{next_l1_batch}",
+            l1_batch + 1
+        );
+        let expected_root_hash = storage
+            .blocks_dal()
+            .get_l1_batch_state_root(l1_batch)
+            .await
+            .with_context(|| format!("failed getting root hash for L1 batch #{l1_batch}"))?
+            .with_context(|| format!("root hash for L1 batch #{l1_batch} is not in Postgres"))?;
+        let root_hash = tree.root_hash();
+        anyhow::ensure!(
+            root_hash == expected_root_hash,
+            "tree root hash {root_hash:?} for L1 batch #{l1_batch} differs from the one in Postgres: \
+             {expected_root_hash:?}"
+        );
+        Ok(())
     }
 
     pub async fn run(
@@ -146,6 +520,7 @@ impl MetadataCalculator {
         pool: ConnectionPool<Core>,
         stop_receiver: watch::Receiver<bool>,
     ) -> anyhow::Result<()> {
+        self.restore_missing_tree_from_checkpoint(&pool).await?;
         let tree = self.create_tree().await?;
         let tree = tree
             .ensure_ready(&pool, &stop_receiver, &self.health_updater)
@@ -160,7 +535,16 @@ impl MetadataCalculator {
         );
         self.tree_reader.send_replace(Some(tree_reader));
 
-        let updater = TreeUpdater::new(tree, self.max_l1_batches_per_iter, self.object_store);
+        let mut updater = TreeUpdater::new(
+            tree,
+            self.max_l1_batches_per_iter,
+            self.config.max_l1_batch_lag_for_proofs,
+            self.object_store,
+        );
+        if let Some(interval) = self.config.checkpoint_interval {
+            let checkpointer = TreeCheckpointer::new(Path::new(&self.config.db_path), interval)?;
+            updater = updater.with_checkpointer(checkpointer);
+        }
         updater
             .loop_updating_tree(self.delayer, &pool, stop_receiver, self.health_updater)
             .await
@@ -24,6 +24,11 @@
 //! Recovery performs basic sanity checks to ensure that the tree won't end up containing garbage data.
 //! E.g., it's checked that the tree always recovers from the same snapshot; that the tree root hash
 //! after recovery matches one in the Postgres snapshot etc.
+//!
+//! The same logic is used to rebuild the tree from scratch using the full storage logs history in Postgres
+//! (e.g., if the tree RocksDB was lost or corrupted). In this case, the latest values for each key as of
+//! the target L1 batch are loaded instead of a snapshot, and the root hash is checked against the one
+//! stored in Postgres for the batch.
 
 use std::{
     fmt, ops,
@@ -39,7 +44,7 @@ use zksync_health_check::HealthUpdater;
 use zksync_merkle_tree::TreeEntry;
 use zksync_types::{
     snapshots::{uniform_hashed_keys_chunk, SnapshotRecoveryStatus},
-    MiniblockNumber, H256,
+    L1BatchNumber, MiniblockNumber, H256,
 };
 
 use super::{
@@ -108,11 +113,22 @@ impl HandleRecoveryEvent for RecoveryHealthUpdater<'_> {
     }
 }
 
+/// Source of tree entries for recovery.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RecoverySource {
+    /// Postgres snapshot. All entries are stored as storage logs for the snapshot miniblock.
+    Snapshot,
+    /// Full storage logs history in Postgres; used to rebuild the tree for the specified L1 batch.
+    /// For each key, the latest value as of the batch is used.
+    StorageLogs(L1BatchNumber),
+}
+
 #[derive(Debug, Clone, Copy)]
 struct SnapshotParameters {
     miniblock: MiniblockNumber,
     expected_root_hash: H256,
     log_count: u64,
+    source: RecoverySource,
 }
 
 impl SnapshotParameters {
@@ -138,6 +154,39 @@ impl SnapshotParameters {
             miniblock,
             expected_root_hash,
             log_count,
+            source: RecoverySource::Snapshot,
+        })
+    }
+
+    /// Returns parameters to rebuild the tree for the specified L1 batch from the storage logs history.
+    async fn for_l1_batch(
+        pool: &ConnectionPool<Core>,
+        l1_batch: L1BatchNumber,
+    ) -> anyhow::Result<Self> {
+        let mut storage = pool.connection().await?;
+        let (_, miniblock) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch)
+            .await
+            .with_context(|| format!("Failed getting miniblock range for L1 batch #{l1_batch}"))?
+            .with_context(|| format!("L1 batch #{l1_batch} doesn't have miniblocks"))?;
+        let expected_root_hash = storage
+            .blocks_dal()
+            .get_l1_batch_state_root(l1_batch)
+            .await
+            .with_context(|| format!("Failed getting root hash for L1 batch #{l1_batch}"))?
+            .with_context(|| format!("Root hash for L1 batch #{l1_batch} is not in Postgres"))?;
+        let log_count = storage
+            .snapshots_creator_dal()
+            .get_distinct_storage_logs_keys_count(l1_batch)
+            .await
+            .with_context(|| format!("Failed getting number of keys for L1 batch #{l1_batch}"))?;
+
+        Ok(Self {
+            miniblock,
+            expected_root_hash,
+            log_count,
+            source: RecoverySource::StorageLogs(l1_batch),
         })
     }
 
@@ -206,6 +255,26 @@ impl GenericAsyncTree {
 }
 
 impl AsyncTreeRecovery {
+    /// Rebuilds the tree for `l1_batch` from the storage logs history in Postgres. The tree must be empty;
+    /// unlike recovery from a snapshot, rebuilding cannot be resumed after an interruption.
+    pub(super) async fn rebuild(
+        self,
+        l1_batch: L1BatchNumber,
+        pool: &ConnectionPool<Core>,
+        stop_receiver: &watch::Receiver<bool>,
+        health_updater: &HealthUpdater,
+    ) -> anyhow::Result<Option<AsyncTree>> {
+        let snapshot = SnapshotParameters::for_l1_batch(pool, l1_batch).await?;
+        tracing::info!("Rebuilding Merkle tree with parameters {snapshot:?}");
+        let recovery_options = RecoveryOptions {
+            chunk_count: snapshot.chunk_count(),
+            concurrency_limit: pool.max_size() as usize,
+            events: Box::new(RecoveryHealthUpdater::new(health_updater)),
+        };
+        self.recover(snapshot, recovery_options, pool, stop_receiver)
+            .await
+    }
+
     async fn recover(
         mut self,
         snapshot: SnapshotParameters,
@@ -221,11 +290,14 @@ impl AsyncTreeRecovery {
             "Recovering Merkle tree from Postgres snapshot in {chunk_count} concurrent chunks"
         );
 
-        let mut storage = pool.connection().await?;
-        let remaining_chunks = self
-            .filter_chunks(&mut storage, snapshot.miniblock, &chunks)
-            .await?;
-        drop(storage);
+        let remaining_chunks = if snapshot.source == RecoverySource::Snapshot {
+            let mut storage = pool.connection().await?;
+            self.filter_chunks(&mut storage, snapshot.miniblock, &chunks)
+                .await?
+        } else {
+            // The tree is always rebuilt from scratch, so no chunks are recovered yet.
+            chunks
+        };
         options
             .events
             .recovery_started(chunk_count, chunk_count - remaining_chunks.len() as u64);
@@ -242,7 +314,7 @@ impl AsyncTreeRecovery {
                 .await
                 .context("semaphore is never closed")?;
             options.events.chunk_started().await;
-            Self::recover_key_chunk(&tree, snapshot.miniblock, chunk, pool, stop_receiver).await?;
+            Self::recover_key_chunk(&tree, snapshot, chunk, pool, stop_receiver).await?;
             options.events.chunk_recovered().await;
             anyhow::Ok(())
         });
@@ -316,7 +388,7 @@ impl AsyncTreeRecovery {
 
     async fn recover_key_chunk(
         tree: &Mutex<AsyncTreeRecovery>,
-        snapshot_miniblock: MiniblockNumber,
+        snapshot: SnapshotParameters,
         key_chunk: ops::RangeInclusive<H256>,
         pool: &ConnectionPool<Core>,
         stop_receiver: &watch::Receiver<bool>,
@@ -332,13 +404,43 @@ impl AsyncTreeRecovery {
 
         let entries_latency =
             RECOVERY_METRICS.chunk_latency[&ChunkRecoveryStage::LoadEntries].start();
-        let all_entries = storage
-            .storage_logs_dal()
-            .get_tree_entries_for_miniblock(snapshot_miniblock, key_chunk.clone())
-            .await
-            .with_context(|| {
-                format!("Failed getting entries for chunk {key_chunk:?} in snapshot for miniblock #{snapshot_miniblock}")
-            })?;
+        let snapshot_miniblock = snapshot.miniblock;
+        let all_entries = match snapshot.source {
+            RecoverySource::Snapshot => storage
+                .storage_logs_dal()
+                .get_tree_entries_for_miniblock(snapshot_miniblock, key_chunk.clone())
+                .await
+                .with_context(|| {
+                    format!("Failed getting entries for chunk {key_chunk:?} in snapshot for miniblock #{snapshot_miniblock}")
+                })?
+                .into_iter()
+                .map(|entry| TreeEntry {
+                    key: entry.tree_key(),
+                    value: entry.value,
+                    leaf_index: entry.leaf_index,
+                })
+                .collect(),
+            RecoverySource::StorageLogs(l1_batch) => {
+                let logs = storage
+                    .snapshots_creator_dal()
+                    .get_storage_logs_chunk(snapshot_miniblock, l1_batch, key_chunk.clone())
+                    .await
+                    .with_context(|| {
+                        format!("Failed getting entries for chunk {key_chunk:?} as of L1 batch #{l1_batch}")
+                    })?;
+                let mut entries: Vec<_> = logs
+                    .into_iter()
+                    .map(|log| TreeEntry {
+                        key: log.key.hashed_key_u256(),
+                        value: log.value,
+                        leaf_index: log.enumeration_index,
+                    })
+                    .collect();
+                // Unlike snapshot entries, logs are not ordered; sort them so that the sanity check below works.
+                entries.sort_unstable_by_key(|entry| entry.key);
+                entries
+            }
+        };
         drop(storage);
         let entries_latency = entries_latency.observe();
         tracing::debug!(
@@ -363,14 +465,6 @@ impl AsyncTreeRecovery {
             );
         }
 
-        let all_entries = all_entries
-            .into_iter()
-            .map(|entry| TreeEntry {
-                key: entry.tree_key(),
-                value: entry.value,
-                leaf_index: entry.leaf_index,
-            })
-            .collect();
         let lock_tree_latency =
             RECOVERY_METRICS.chunk_latency[&ChunkRecoveryStage::LockTree].start();
         let mut tree = tree.lock().await;
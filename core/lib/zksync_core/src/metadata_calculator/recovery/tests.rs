@@ -1,6 +1,10 @@
 //! Tests for metadata calculator snapshot recovery.
 
-use std::{path::PathBuf, time::Duration};
+use std::{
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use assert_matches::assert_matches;
 use tempfile::TempDir;
@@ -19,12 +23,13 @@ use super::*;
 use crate::{
     genesis::{insert_genesis_batch, GenesisParams},
     metadata_calculator::{
+        checkpoint::{checkpoints_dir, latest_checkpoint},
         helpers::create_db,
         tests::{
-            extend_db_state, extend_db_state_from_l1_batch, gen_storage_logs, run_calculator,
-            setup_calculator,
+            extend_db_state, extend_db_state_from_l1_batch, gen_storage_logs, reset_db_state,
+            run_calculator, setup_calculator,
         },
-        MetadataCalculator, MetadataCalculatorConfig,
+        CheckpointRestore, MetadataCalculator, MetadataCalculatorConfig, TreeRestoreSource,
     },
     utils::testonly::prepare_recovery_snapshot,
 };
@@ -35,6 +40,7 @@ fn calculating_chunk_count() {
         miniblock: MiniblockNumber(1),
         log_count: 160_000_000,
         expected_root_hash: H256::zero(),
+        source: RecoverySource::Snapshot,
     };
     assert_eq!(snapshot.chunk_count(), 800);
 
@@ -49,9 +55,11 @@ async fn create_tree_recovery(path: PathBuf, l1_batch: L1BatchNumber) -> AsyncTr
     let db = create_db(
         path,
         0,
-        16 << 20,       // 16 MiB,
+        16 << 20, // 16 MiB,
+        None,
         Duration::ZERO, // writes should never be stalled in tests
         500,
+        false,
     )
     .await
     .unwrap();
@@ -88,9 +96,270 @@ async fn basic_recovery_workflow() {
             .expect("Tree recovery unexpectedly aborted");
 
         assert_eq!(tree.root_hash(), snapshot_recovery.l1_batch_root_hash);
+        // The tree is still initializing until the metadata calculator enters the main loop.
         let health = health_check.check_health().await;
-        assert_matches!(health.status(), HealthStatus::Ready);
+        assert_matches!(health.status(), HealthStatus::Initializing);
+        let details = serde_json::to_value(&health).unwrap()["details"].clone();
+        assert_eq!(details["stage"], "recovery");
+        assert_eq!(details["recovered_chunk_count"], chunk_count);
+    }
+}
+
+#[tokio::test]
+async fn rebuilding_tree_from_storage_logs() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (calculator, _) = setup_calculator(&temp_dir.path().join("init"), &pool).await;
+    reset_db_state(&pool, 5).await;
+    // Overwrite some of the existing slots, so that the rebuilt tree must use the latest values.
+    let overwritten_logs = gen_storage_logs(0..20, 1)
+        .pop()
+        .unwrap()
+        .into_iter()
+        .map(|log| StorageLog::new_write_log(log.key, H256::repeat_byte(0xff)))
+        .collect();
+    let mut storage = pool.connection().await.unwrap();
+    extend_db_state(&mut storage, [overwritten_logs]).await;
+    drop(storage);
+    let expected_root_hash = run_calculator(calculator, pool.clone()).await;
+
+    // Emulate a corrupted tree at the target path.
+    let tree_path = temp_dir.path().join("rebuilt");
+    std::fs::create_dir_all(&tree_path).unwrap();
+    std::fs::write(tree_path.join("garbage"), b"garbage").unwrap();
+
+    let merkle_tree_config = MerkleTreeConfig {
+        path: tree_path.to_str().unwrap().to_owned(),
+        ..MerkleTreeConfig::default()
+    };
+    let calculator_config = MetadataCalculatorConfig::for_main_node(
+        &merkle_tree_config,
+        &OperationsManagerConfig { delay_interval: 50 },
+    );
+    let calculator = MetadataCalculator::new(calculator_config, None)
+        .await
+        .unwrap();
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let rebuilt_l1_batch = calculator
+        .rebuild_tree(&pool, &stop_receiver)
+        .await
+        .unwrap();
+    assert_eq!(rebuilt_l1_batch, Some(L1BatchNumber(6)));
+    assert!(!tree_path.join("garbage").exists());
+
+    let db = create_db(tree_path, 0, 16 << 20, None, Duration::ZERO, 500, false)
+        .await
+        .unwrap();
+    let tree = AsyncTree::new(db, MerkleTreeMode::Full);
+    assert_eq!(tree.root_hash(), expected_root_hash);
+    assert_eq!(tree.next_l1_batch_number(), L1BatchNumber(7));
+}
+
+fn calculator_config_with_checkpoints(
+    tree_path: &Path,
+    checkpoint_interval: Option<NonZeroU32>,
+) -> MetadataCalculatorConfig {
+    let merkle_tree_config = MerkleTreeConfig {
+        path: tree_path.to_str().unwrap().to_owned(),
+        max_l1_batches_per_iter: 10,
+        ..MerkleTreeConfig::default()
+    };
+    let mut calculator_config = MetadataCalculatorConfig::for_main_node(
+        &merkle_tree_config,
+        &OperationsManagerConfig { delay_interval: 50 },
+    );
+    calculator_config.checkpoint_interval = checkpoint_interval;
+    calculator_config
+}
+
+async fn rebuild_tree_at(
+    pool: &ConnectionPool<Core>,
+    tree_path: &Path,
+) -> (H256, TreeRestoreSource) {
+    let calculator_config = calculator_config_with_checkpoints(tree_path, None);
+    let calculator = MetadataCalculator::new(calculator_config, None)
+        .await
+        .unwrap();
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let (rebuilt_l1_batch, source) = calculator
+        .rebuild_tree_inner(pool, &stop_receiver)
+        .await
+        .unwrap()
+        .expect("rebuilding was interrupted");
+    assert_eq!(rebuilt_l1_batch, L1BatchNumber(7));
+    drop(calculator);
+
+    let db = create_db(
+        tree_path.to_owned(),
+        0,
+        16 << 20,
+        None,
+        Duration::ZERO,
+        500,
+        false,
+    )
+    .await
+    .unwrap();
+    let tree = AsyncTree::new(db, MerkleTreeMode::Full);
+    assert_eq!(tree.next_l1_batch_number(), L1BatchNumber(8));
+    (tree.root_hash(), source)
+}
+
+#[tokio::test]
+async fn rebuilding_tree_from_checkpoint() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let tree_path = temp_dir.path().join("tree");
+    let checkpoint_interval = NonZeroU32::new(3);
+    reset_db_state(&pool, 5).await;
+
+    let calculator_config = calculator_config_with_checkpoints(&tree_path, checkpoint_interval);
+    let calculator = MetadataCalculator::new(calculator_config, None)
+        .await
+        .unwrap();
+    run_calculator(calculator, pool.clone()).await;
+    let checkpoints_dir = checkpoints_dir(&tree_path).unwrap();
+    let (checkpoint_l1_batch, _) = latest_checkpoint(&checkpoints_dir).unwrap().unwrap();
+    assert_eq!(checkpoint_l1_batch, L1BatchNumber(5));
+
+    // Add L1 batches not covered by the checkpoint.
+    let mut storage = pool.connection().await.unwrap();
+    extend_db_state(&mut storage, gen_storage_logs(100..140, 2)).await;
+    drop(storage);
+    let calculator_config = calculator_config_with_checkpoints(&tree_path, checkpoint_interval);
+    let calculator = MetadataCalculator::new(calculator_config, None)
+        .await
+        .unwrap();
+    let expected_root_hash = run_calculator(calculator, pool.clone()).await;
+    let (checkpoint_l1_batch, _) = latest_checkpoint(&checkpoints_dir).unwrap().unwrap();
+    assert_eq!(checkpoint_l1_batch, L1BatchNumber(5));
+
+    // Corrupt the tree so that it cannot be used as is. The rebuild must use the checkpoint + 2 L1 batches.
+    std::fs::remove_dir_all(&tree_path).unwrap();
+    let (root_hash, source) = rebuild_tree_at(&pool, &tree_path).await;
+    assert_eq!(root_hash, expected_root_hash);
+    assert_eq!(source, TreeRestoreSource::Checkpoint(L1BatchNumber(5)));
+
+    // Compare with the tree rebuilt from scratch.
+    let (root_hash, source) = rebuild_tree_at(&pool, &temp_dir.path().join("full")).await;
+    assert_eq!(root_hash, expected_root_hash);
+    assert_eq!(source, TreeRestoreSource::StorageLogs);
+}
+
+#[tokio::test]
+async fn restoring_missing_tree_from_checkpoint_on_startup() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let tree_path = temp_dir.path().join("tree");
+    let checkpoint_interval = NonZeroU32::new(3);
+    reset_db_state(&pool, 5).await;
+
+    let calculator_config = calculator_config_with_checkpoints(&tree_path, checkpoint_interval);
+    let calculator = MetadataCalculator::new(calculator_config, None)
+        .await
+        .unwrap();
+    run_calculator(calculator, pool.clone()).await;
+    let mut storage = pool.connection().await.unwrap();
+    extend_db_state(&mut storage, gen_storage_logs(100..140, 2)).await;
+    drop(storage);
+
+    // Emulate the tree removed after a crash.
+    std::fs::remove_dir_all(&tree_path).unwrap();
+    let calculator_config = calculator_config_with_checkpoints(&tree_path, checkpoint_interval);
+    let calculator = MetadataCalculator::new(calculator_config, None)
+        .await
+        .unwrap();
+    let restored_l1_batch = calculator
+        .restore_missing_tree_from_checkpoint(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        restored_l1_batch,
+        CheckpointRestore::Restored(L1BatchNumber(5))
+    );
+    // The tree is present now, so it shouldn't be restored again.
+    let restored_l1_batch = calculator
+        .restore_missing_tree_from_checkpoint(&pool)
+        .await
+        .unwrap();
+    assert_eq!(restored_l1_batch, CheckpointRestore::TreeExists);
+
+    // The calculator must process the remaining L1 batches on top of the restored tree.
+    let root_hash = run_calculator(calculator, pool.clone()).await;
+    let (expected_root_hash, source) = rebuild_tree_at(&pool, &temp_dir.path().join("full")).await;
+    assert_eq!(source, TreeRestoreSource::StorageLogs);
+    assert_eq!(root_hash, expected_root_hash);
+}
+
+#[tokio::test]
+async fn inconsistent_checkpoint_is_ignored_on_startup() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let tree_path = temp_dir.path().join("tree");
+    let checkpoint_interval = NonZeroU32::new(3);
+    reset_db_state(&pool, 5).await;
+
+    let calculator_config = calculator_config_with_checkpoints(&tree_path, checkpoint_interval);
+    let calculator = MetadataCalculator::new(calculator_config, None)
+        .await
+        .unwrap();
+    run_calculator(calculator, pool.clone()).await;
+
+    // Reset Postgres so that the checkpoint cannot be verified against it.
+    std::fs::remove_dir_all(&tree_path).unwrap();
+    reset_db_state(&pool, 5).await;
+    let calculator_config = calculator_config_with_checkpoints(&tree_path, checkpoint_interval);
+    let calculator = MetadataCalculator::new(calculator_config, None)
+        .await
+        .unwrap();
+    let restored_l1_batch = calculator
+        .restore_missing_tree_from_checkpoint(&pool)
+        .await
+        .unwrap();
+    assert_eq!(restored_l1_batch, CheckpointRestore::NoCheckpoint);
+    assert!(!tree_path.exists());
+    // The checkpoint should be discarded.
+    let checkpoints_dir = checkpoints_dir(&tree_path).unwrap();
+    assert_eq!(latest_checkpoint(&checkpoints_dir).unwrap(), None);
+}
+
+#[tokio::test]
+async fn corrupted_checkpoint_is_discarded_on_startup() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let tree_path = temp_dir.path().join("tree");
+    let checkpoint_interval = NonZeroU32::new(3);
+    reset_db_state(&pool, 5).await;
+
+    let calculator_config = calculator_config_with_checkpoints(&tree_path, checkpoint_interval);
+    let calculator = MetadataCalculator::new(calculator_config, None)
+        .await
+        .unwrap();
+    let expected_root_hash = run_calculator(calculator, pool.clone()).await;
+
+    // Emulate a partially written checkpoint: remove the tree and corrupt all checkpoint files.
+    std::fs::remove_dir_all(&tree_path).unwrap();
+    let checkpoints_dir = checkpoints_dir(&tree_path).unwrap();
+    let (_, checkpoint_path) = latest_checkpoint(&checkpoints_dir).unwrap().unwrap();
+    for entry in std::fs::read_dir(&checkpoint_path).unwrap() {
+        std::fs::write(entry.unwrap().path(), b"garbage").unwrap();
     }
+
+    let calculator_config = calculator_config_with_checkpoints(&tree_path, checkpoint_interval);
+    let calculator = MetadataCalculator::new(calculator_config, None)
+        .await
+        .unwrap();
+    let restored_l1_batch = calculator
+        .restore_missing_tree_from_checkpoint(&pool)
+        .await
+        .unwrap();
+    assert_eq!(restored_l1_batch, CheckpointRestore::NoCheckpoint);
+    assert!(!tree_path.exists());
+    assert!(!checkpoint_path.exists());
+
+    // The tree should be built from scratch as usual.
+    let root_hash = run_calculator(calculator, pool).await;
+    assert_eq!(root_hash, expected_root_hash);
 }
 
 async fn prepare_recovery_snapshot_with_genesis(
@@ -12,7 +12,7 @@ use zksync_config::configs::{
 };
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_health_check::{CheckHealth, HealthStatus};
-use zksync_merkle_tree::domain::ZkSyncTree;
+use zksync_merkle_tree::{domain::ZkSyncTree, RocksDBWrapper, TreeInstruction};
 use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_prover_interface::inputs::PrepareBasicCircuitsJob;
 use zksync_types::{
@@ -21,7 +21,10 @@ use zksync_types::{
 };
 use zksync_utils::u32_to_h256;
 
-use super::{GenericAsyncTree, L1BatchWithLogs, MetadataCalculator, MetadataCalculatorConfig};
+use super::{
+    helpers::AsyncTree, metrics::METRICS, updater::TreeUpdater, GenericAsyncTree, L1BatchWithLogs,
+    LazyAsyncTreeReader, MerkleTreeInfo, MetadataCalculator, MetadataCalculatorConfig,
+};
 use crate::{
     genesis::{insert_genesis_batch, GenesisParams},
     utils::testonly::{create_l1_batch, create_miniblock},
@@ -102,6 +105,95 @@ async fn expected_tree_hash(pool: &ConnectionPool<Core>) -> H256 {
     ZkSyncTree::process_genesis_batch(&all_logs).root_hash
 }
 
+#[test]
+fn tree_health_depends_on_mode() {
+    let tree_health = |mode, l1_batch_lag, max_l1_batch_lag_for_proofs| {
+        let info = MerkleTreeInfo {
+            mode,
+            root_hash: H256::zero(),
+            next_l1_batch_number: L1BatchNumber(1),
+            leaf_count: 0,
+        };
+        info.health(l1_batch_lag, max_l1_batch_lag_for_proofs)
+    };
+
+    for mode in [MerkleTreeMode::Full, MerkleTreeMode::Lightweight] {
+        let health = tree_health(mode, 0, Some(5));
+        assert_matches!(health.status(), HealthStatus::Ready);
+        let details = serde_json::to_value(&health).unwrap()["details"].clone();
+        assert_eq!(details["stage"], "main_loop");
+        assert_eq!(details["l1_batch_lag"], 0);
+    }
+
+    // A moderate lag prevents a full tree from serving proofs for recent L1 batches, but is fine for a lightweight tree.
+    assert_matches!(
+        tree_health(MerkleTreeMode::Full, 10, Some(5)).status(),
+        HealthStatus::Affected
+    );
+    assert_matches!(
+        tree_health(MerkleTreeMode::Lightweight, 10, Some(5)).status(),
+        HealthStatus::Ready
+    );
+
+    // A large lag means that the tree tip doesn't keep up with Postgres.
+    assert_matches!(
+        tree_health(MerkleTreeMode::Full, 100, Some(5)).status(),
+        HealthStatus::Affected
+    );
+    assert_matches!(
+        tree_health(MerkleTreeMode::Lightweight, 100, Some(5)).status(),
+        HealthStatus::Degraded
+    );
+
+    // The proof readiness check can be disabled (e.g., while the node is catching up), or have a custom threshold.
+    assert_matches!(
+        tree_health(MerkleTreeMode::Full, 10, None).status(),
+        HealthStatus::Ready
+    );
+    assert_matches!(
+        tree_health(MerkleTreeMode::Full, 100, None).status(),
+        HealthStatus::Degraded
+    );
+    assert_matches!(
+        tree_health(MerkleTreeMode::Full, 10, Some(20)).status(),
+        HealthStatus::Ready
+    );
+}
+
+#[tokio::test]
+async fn tree_is_initializing_until_main_loop() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (calculator, _) = setup_calculator(temp_dir.path(), &pool).await;
+    let tree_health_check = calculator.tree_health_check();
+
+    let tree = calculator.create_tree().await.unwrap();
+    let health = tree_health_check.check_health().await;
+    assert_matches!(health.status(), HealthStatus::Initializing);
+    let details = serde_json::to_value(&health).unwrap()["details"].clone();
+    assert_eq!(details["stage"], "initialization");
+    drop(tree);
+
+    reset_db_state(&pool, 1).await;
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let calculator_handle = tokio::spawn(calculator.run(pool, stop_receiver));
+    loop {
+        let health = tree_health_check.check_health().await;
+        if health.status() == HealthStatus::Ready {
+            break;
+        }
+        assert_matches!(health.status(), HealthStatus::Initializing);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    stop_sender.send_replace(true);
+    tokio::time::timeout(RUN_TIMEOUT, calculator_handle)
+        .await
+        .expect("timed out waiting for calculator")
+        .unwrap()
+        .unwrap();
+}
+
 #[tokio::test]
 async fn status_receiver_has_correct_states() {
     let pool = ConnectionPool::<Core>::test_pool().await;
@@ -150,6 +242,60 @@ async fn status_receiver_has_correct_states() {
     );
 }
 
+#[tokio::test]
+async fn stalled_tree_is_reflected_in_health() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let mut calculator = setup_lightweight_calculator(temp_dir.path(), &pool).await;
+    let tree_health_check = calculator.tree_health_check();
+    reset_db_state(&pool, 1).await;
+
+    let (stop_sx, stop_rx) = watch::channel(false);
+    let (delay_sx, mut delay_rx) = mpsc::unbounded_channel();
+    calculator.delayer.delay_notifier = delay_sx;
+    let calculator_handle = tokio::spawn(calculator.run(pool.clone(), stop_rx));
+    let (next_l1_batch, _) = tokio::time::timeout(RUN_TIMEOUT, delay_rx.recv())
+        .await
+        .expect("metadata calculator timed out processing initial blocks")
+        .unwrap();
+    assert_eq!(next_l1_batch, L1BatchNumber(2));
+    assert_matches!(
+        tree_health_check.check_health().await.status(),
+        HealthStatus::Ready
+    );
+
+    // Seal an L1 batch with a gap, so that the tree cannot progress while Postgres reports many unprocessed L1 batches.
+    let mut storage = pool.connection().await.unwrap();
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&create_l1_batch(60))
+        .await
+        .unwrap();
+    drop(storage);
+
+    let health = loop {
+        let (next_l1_batch, _) = tokio::time::timeout(RUN_TIMEOUT, delay_rx.recv())
+            .await
+            .expect("metadata calculator shut down prematurely")
+            .unwrap();
+        assert_eq!(next_l1_batch, L1BatchNumber(2));
+        let health = tree_health_check.check_health().await;
+        if !matches!(health.status(), HealthStatus::Ready) {
+            break health;
+        }
+    };
+    assert_matches!(health.status(), HealthStatus::Degraded);
+    let details = serde_json::to_value(&health).unwrap()["details"].clone();
+    assert_eq!(details["l1_batch_lag"], 59);
+
+    stop_sx.send(true).unwrap();
+    tokio::time::timeout(RUN_TIMEOUT, calculator_handle)
+        .await
+        .expect("timed out waiting for calculator")
+        .unwrap()
+        .unwrap();
+}
+
 #[tokio::test]
 async fn multi_l1_batch_workflow() {
     let pool = ConnectionPool::<Core>::test_pool().await;
@@ -236,6 +382,49 @@ async fn running_metadata_calculator_with_additional_blocks() {
     assert_eq!(root_hash_for_full_tree, updated_root_hash);
 }
 
+#[tokio::test]
+async fn tree_waits_for_l1_batch_missing_in_postgres() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let calculator = setup_lightweight_calculator(temp_dir.path(), &pool).await;
+    reset_db_state(&pool, 1).await;
+    run_calculator(calculator, pool.clone()).await;
+
+    let calculator = setup_lightweight_calculator(temp_dir.path(), &pool).await;
+    let tree = calculator.create_tree().await.unwrap();
+    let GenericAsyncTree::Ready(tree) = tree else {
+        panic!("Unexpected tree state: {tree:?}");
+    };
+    assert_eq!(tree.next_l1_batch_number(), L1BatchNumber(2));
+    let mut updater = TreeUpdater::new(tree, 10, 5, None);
+
+    // Simulate the tree requesting L1 batches that are not (yet) present in Postgres.
+    let wait_count = METRICS.waiting_for_l1_batch.get();
+    let mut storage = pool.connection().await.unwrap();
+    let mut next_l1_batch = updater.process_multiple_batches(&mut storage, 2..=3).await;
+    assert_eq!(next_l1_batch, L1BatchNumber(2));
+    assert!(METRICS.waiting_for_l1_batch.get() > wait_count);
+
+    let insert_task = tokio::spawn({
+        let pool = pool.clone();
+        async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let new_logs = gen_storage_logs(100..200, 2);
+            extend_db_state(&mut pool.connection().await.unwrap(), new_logs).await;
+        }
+    });
+    run_with_timeout(RUN_TIMEOUT, async {
+        while next_l1_batch <= L1BatchNumber(3) {
+            next_l1_batch = updater
+                .process_multiple_batches(&mut storage, next_l1_batch.0..=3)
+                .await;
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+    insert_task.await.unwrap();
+}
+
 #[tokio::test]
 async fn shutting_down_calculator() {
     let pool = ConnectionPool::<Core>::test_pool().await;
@@ -558,6 +747,33 @@ async fn insert_initial_writes_for_batch(
         .unwrap();
 }
 
+/// Creates a Merkle tree at `db_path` with L1 batches consisting of the specified storage logs (without touching
+/// Postgres) and returns a reader for the tree together with root hashes of the L1 batches.
+pub(crate) async fn create_tree_reader(
+    db_path: &Path,
+    logs_by_l1_batch: Vec<Vec<StorageLog>>,
+) -> (LazyAsyncTreeReader, Vec<H256>) {
+    let db = RocksDBWrapper::new(db_path).unwrap();
+    let mut tree = AsyncTree::new(db, MerkleTreeMode::Lightweight);
+    let mut next_leaf_index = 1;
+    let mut root_hashes = vec![];
+    for logs in logs_by_l1_batch {
+        let instructions = logs
+            .into_iter()
+            .map(|log| {
+                next_leaf_index += 1;
+                TreeInstruction::write(log.key, next_leaf_index - 1, log.value)
+            })
+            .collect();
+        let metadata = tree.process_l1_batch(instructions).await;
+        root_hashes.push(metadata.root_hash);
+    }
+    tree.save().await;
+
+    let (_, reader_receiver) = watch::channel(Some(tree.reader()));
+    (LazyAsyncTreeReader(reader_receiver), root_hashes)
+}
+
 pub(crate) fn gen_storage_logs(
     indices: ops::Range<u32>,
     num_batches: usize,
@@ -6,7 +6,7 @@ use anyhow::Context as _;
 use futures::{future, FutureExt};
 use tokio::sync::watch;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
-use zksync_health_check::HealthUpdater;
+use zksync_health_check::{Health, HealthUpdater};
 use zksync_merkle_tree::domain::TreeMetadata;
 use zksync_object_store::ObjectStore;
 use zksync_types::{
@@ -16,7 +16,8 @@ use zksync_types::{
 };
 
 use super::{
-    helpers::{AsyncTree, Delayer, L1BatchWithLogs},
+    checkpoint::TreeCheckpointer,
+    helpers::{AsyncTree, Delayer, L1BatchWithLogs, MerkleTreeInfo},
     metrics::{TreeUpdateStage, METRICS},
     MetadataCalculator,
 };
@@ -26,22 +27,33 @@ use crate::utils::wait_for_l1_batch;
 pub(super) struct TreeUpdater {
     tree: AsyncTree,
     max_l1_batches_per_iter: usize,
+    max_l1_batch_lag_for_proofs: u32,
     object_store: Option<Arc<dyn ObjectStore>>,
+    checkpointer: Option<TreeCheckpointer>,
 }
 
 impl TreeUpdater {
     pub fn new(
         tree: AsyncTree,
         max_l1_batches_per_iter: usize,
+        max_l1_batch_lag_for_proofs: u32,
         object_store: Option<Arc<dyn ObjectStore>>,
     ) -> Self {
         Self {
             tree,
             max_l1_batches_per_iter,
+            max_l1_batch_lag_for_proofs,
             object_store,
+            checkpointer: None,
         }
     }
 
+    /// Enables periodic tree checkpoints.
+    pub fn with_checkpointer(mut self, checkpointer: TreeCheckpointer) -> Self {
+        self.checkpointer = Some(checkpointer);
+        self
+    }
+
     async fn process_l1_batch(
         &mut self,
         l1_batch: L1BatchWithLogs,
@@ -76,7 +88,9 @@ impl TreeUpdater {
     /// Processes a range of L1 batches with a single flushing of the tree updates to RocksDB at the end.
     /// This allows to save on RocksDB I/O ops.
     ///
-    /// Returns the number of the next L1 batch to be processed by the tree.
+    /// Returns the number of the next L1 batch to be processed by the tree. If an L1 batch in the range is missing
+    /// in Postgres, the batches preceding it are processed, and the missing batch will be requested again
+    /// on the next iteration.
     ///
     /// # Implementation details
     ///
@@ -84,7 +98,7 @@ impl TreeUpdater {
     /// the first L1 batch data beforehand.) This allows saving some time if we actually process
     /// multiple L1 batches at once (e.g., during the initial tree syncing), and if loading data from Postgres
     /// is slow for whatever reason.
-    async fn process_multiple_batches(
+    pub(super) async fn process_multiple_batches(
         &mut self,
         storage: &mut Connection<'_, Core>,
         l1_batch_numbers: ops::RangeInclusive<u32>,
@@ -97,10 +111,19 @@ impl TreeUpdater {
 
         let mut total_logs = 0;
         let mut updated_headers = vec![];
+        let mut next_l1_batch_number = last_l1_batch_number + 1;
         for l1_batch_number in l1_batch_numbers {
             let l1_batch_number = L1BatchNumber(l1_batch_number);
             let Some(current_l1_batch_data) = l1_batch_data else {
-                return l1_batch_number;
+                // The L1 batch was reported as sealed, but is missing in Postgres. This is a benign race
+                // (e.g., with an L1 batch rollback), so we back off and retry later rather than erroring.
+                tracing::warn!(
+                    "L1 batch #{l1_batch_number} is not present in Postgres although it's not greater than \
+                     the last sealed L1 batch; waiting for it to appear"
+                );
+                METRICS.waiting_for_l1_batch.inc();
+                next_l1_batch_number = l1_batch_number;
+                break;
             };
             total_logs += current_l1_batch_data.storage_logs.len();
 
@@ -157,19 +180,26 @@ impl TreeUpdater {
             l1_batch_data = next_l1_batch_data;
         }
 
+        if updated_headers.is_empty() {
+            return next_l1_batch_number;
+        }
         let save_rocksdb_latency = METRICS.start_stage(TreeUpdateStage::SaveRocksdb);
         self.tree.save().await;
         save_rocksdb_latency.observe();
         MetadataCalculator::update_metrics(&updated_headers, total_logs, start);
+        if let Some(checkpointer) = &mut self.checkpointer {
+            checkpointer.checkpoint_if_needed(&self.tree).await;
+        }
 
-        last_l1_batch_number + 1
+        next_l1_batch_number
     }
 
+    /// Processes the next chunk of L1 batches. Returns the last sealed L1 batch in Postgres, if any.
     async fn step(
         &mut self,
         mut storage: Connection<'_, Core>,
         next_l1_batch_to_seal: &mut L1BatchNumber,
-    ) {
+    ) -> Option<L1BatchNumber> {
         let Some(last_sealed_l1_batch) = storage
             .blocks_dal()
             .get_sealed_l1_batch_number()
@@ -177,7 +207,7 @@ impl TreeUpdater {
             .unwrap()
         else {
             tracing::trace!("No L1 batches to seal: Postgres storage is empty");
-            return;
+            return None;
         };
         let last_requested_l1_batch =
             next_l1_batch_to_seal.0 + self.max_l1_batches_per_iter as u32 - 1;
@@ -193,6 +223,17 @@ impl TreeUpdater {
                 .process_multiple_batches(&mut storage, l1_batch_numbers)
                 .await;
         }
+        Some(last_sealed_l1_batch)
+    }
+
+    /// Returns the number of sealed L1 batches in Postgres not yet processed by the tree.
+    fn l1_batch_lag(
+        last_sealed_l1_batch: Option<L1BatchNumber>,
+        next_l1_batch_to_seal: L1BatchNumber,
+    ) -> u32 {
+        last_sealed_l1_batch.map_or(0, |number| {
+            (number.0 + 1).saturating_sub(next_l1_batch_to_seal.0)
+        })
     }
 
     /// The processing loop for this updater.
@@ -210,6 +251,17 @@ impl TreeUpdater {
         };
         let mut storage = pool.connection_tagged("metadata_calculator").await?;
 
+        // Proofs for recent L1 batches are not expected to be available while the node is catching up
+        // with the main node, so the proof readiness check is skipped in this case.
+        let max_l1_batch_lag_for_proofs = self.max_l1_batch_lag_for_proofs;
+        let health =
+            |tree_info: MerkleTreeInfo, l1_batch_lag: u32, is_catching_up: bool| -> Health {
+                tree_info.health(
+                    l1_batch_lag,
+                    (!is_catching_up).then_some(max_l1_batch_lag_for_proofs),
+                )
+            };
+
         // Ensure genesis creation
         let tree = &mut self.tree;
         if tree.is_empty() {
@@ -240,7 +292,9 @@ impl TreeUpdater {
             max_batches_per_iter = self.max_l1_batches_per_iter
         );
         let tree_info = tree.reader().info().await;
-        health_updater.update(tree_info.into());
+        let mut l1_batch_lag = Self::l1_batch_lag(current_db_batch, next_l1_batch_to_seal);
+        let mut is_catching_up = delayer.is_catching_up();
+        health_updater.update(health(tree_info, l1_batch_lag, is_catching_up));
 
         // It may be the case that we don't have any L1 batches with metadata in Postgres, e.g. after
         // recovering from a snapshot. We cannot wait for such a batch to appear (*this* is the component
@@ -268,7 +322,8 @@ impl TreeUpdater {
                 tracing::info!("Truncated Merkle tree to L1 batch #{next_l1_batch_to_seal}");
 
                 let tree_info = tree.reader().info().await;
-                health_updater.update(tree_info.into());
+                l1_batch_lag = Self::l1_batch_lag(current_db_batch, next_l1_batch_to_seal);
+                health_updater.update(health(tree_info, l1_batch_lag, is_catching_up));
             }
         }
 
@@ -280,16 +335,26 @@ impl TreeUpdater {
             let storage = pool.connection_tagged("metadata_calculator").await?;
 
             let snapshot = *next_l1_batch_to_seal;
-            self.step(storage, &mut next_l1_batch_to_seal).await;
+            let last_sealed_l1_batch = self.step(storage, &mut next_l1_batch_to_seal).await;
+            let prev_l1_batch_lag = l1_batch_lag;
+            l1_batch_lag = Self::l1_batch_lag(last_sealed_l1_batch, next_l1_batch_to_seal);
+            let was_catching_up = is_catching_up;
+            is_catching_up = delayer.is_catching_up();
             let delay = if snapshot == *next_l1_batch_to_seal {
                 tracing::trace!(
                     "Metadata calculator (next L1 batch: #{next_l1_batch_to_seal}) \
                      didn't make any progress; delaying it using {delayer:?}"
                 );
+                // Postgres and the node sync state can advance while the tree is stalled, so the health must be updated
+                // even without progress.
+                if l1_batch_lag != prev_l1_batch_lag || is_catching_up != was_catching_up {
+                    let tree_info = self.tree.reader().info().await;
+                    health_updater.update(health(tree_info, l1_batch_lag, is_catching_up));
+                }
                 delayer.wait(&self.tree).left_future()
             } else {
                 let tree_info = self.tree.reader().info().await;
-                health_updater.update(tree_info.into());
+                health_updater.update(health(tree_info, l1_batch_lag, is_catching_up));
 
                 tracing::trace!(
                     "Metadata calculator (next L1 batch: #{next_l1_batch_to_seal}) made progress from #{snapshot}"
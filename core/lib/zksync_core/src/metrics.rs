@@ -175,6 +175,8 @@ pub(crate) struct ExternalNodeMetrics {
     pub synced: Gauge<u64>,
     /// Current sync lag of the external node.
     pub sync_lag: Gauge<u64>,
+    /// Number of failed attempts to fetch the latest miniblock from the main node in order to determine the sync status.
+    pub main_node_unreachable: Counter,
     /// Number of the last L1 batch checked by the re-org detector or consistency checker.
     pub last_correct_batch: Family<CheckerComponent, Gauge<u64>>,
     /// Number of the last miniblock checked by the re-org detector or consistency checker.
@@ -0,0 +1,45 @@
+//! Metrics for the reorg detector.
+
+use std::time::Duration;
+
+use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, Metrics};
+
+use super::Error;
+
+/// Result of a single reorg check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "result", rename_all = "snake_case")]
+pub(super) enum CheckResult {
+    /// Local storage matches the main node.
+    Match,
+    /// A reorg was detected.
+    Reorg,
+    /// Check has failed because of a transient error; it will be retried.
+    TransientError,
+    /// Check has failed because of a fatal error other than a reorg.
+    FatalError,
+}
+
+impl CheckResult {
+    pub fn new(result: &Result<(), Error>) -> Self {
+        match result {
+            Ok(()) => Self::Match,
+            Err(Error::ReorgDetected(_)) => Self::Reorg,
+            Err(err) if err.is_transient() => Self::TransientError,
+            Err(_) => Self::FatalError,
+        }
+    }
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "external_node_reorg_detector")]
+pub(super) struct ReorgDetectorMetrics {
+    /// Latency of a single reorg check, including localizing a reorg if one is detected.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub check_latency: Family<CheckResult, Histogram<Duration>>,
+    /// Number of performed reorg checks.
+    pub checks: Family<CheckResult, Counter>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<ReorgDetectorMetrics> = vise::Global::new();
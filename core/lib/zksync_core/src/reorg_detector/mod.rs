@@ -1,7 +1,11 @@
-use std::{fmt, time::Duration};
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
+use serde::Serialize;
 use tokio::sync::watch;
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
@@ -12,11 +16,14 @@ use zksync_web3_decl::{
     namespaces::{EthNamespaceClient, ZksNamespaceClient},
 };
 
+use self::metrics::{CheckResult, METRICS};
 use crate::{
     metrics::{CheckerComponent, EN_METRICS},
+    sync_layer::{MainNodeRetryConfig, RetryingMainNodeClient},
     utils::binary_search_with,
 };
 
+mod metrics;
 #[cfg(test)]
 mod tests;
 
@@ -138,6 +145,38 @@ impl MainNodeClient for HttpClient {
     }
 }
 
+#[async_trait]
+impl MainNodeClient for RetryingMainNodeClient<dyn MainNodeClient> {
+    async fn sealed_miniblock_number(&self) -> EnrichedClientResult<MiniblockNumber> {
+        self.retry("sealed_miniblock_number", || {
+            self.inner.sealed_miniblock_number()
+        })
+        .await
+    }
+
+    async fn sealed_l1_batch_number(&self) -> EnrichedClientResult<L1BatchNumber> {
+        self.retry("sealed_l1_batch_number", || {
+            self.inner.sealed_l1_batch_number()
+        })
+        .await
+    }
+
+    async fn miniblock_hash(&self, number: MiniblockNumber) -> EnrichedClientResult<Option<H256>> {
+        self.retry("miniblock_hash", || self.inner.miniblock_hash(number))
+            .await
+    }
+
+    async fn l1_batch_root_hash(
+        &self,
+        number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<H256>> {
+        self.retry("l1_batch_root_hash", || {
+            self.inner.l1_batch_root_hash(number)
+        })
+        .await
+    }
+}
+
 trait HandleReorgDetectorEvent: fmt::Debug + Send + Sync {
     fn initialize(&mut self);
 
@@ -149,13 +188,79 @@ trait HandleReorgDetectorEvent: fmt::Debug + Send + Sync {
 
     fn report_divergence(&mut self, diverged_l1_batch: L1BatchNumber);
 
+    fn report_reorg(
+        &mut self,
+        last_correct_l1_batch: L1BatchNumber,
+        diverged_l1_batch: L1BatchNumber,
+    );
+
+    /// Signals that a consistency check against the main node has started. The check is finished
+    /// with [`Self::report_check()`].
+    fn start_check(&mut self);
+
+    fn report_check(&mut self, result: CheckResult, latency: Duration);
+
     fn start_shutting_down(&mut self);
 }
 
-/// Default implementation of [`HandleReorgDetectorEvent`] that reports values as metrics.
-impl HandleReorgDetectorEvent for HealthUpdater {
+/// Information about the last reorg detected by [`ReorgDetector`].
+#[derive(Debug, Clone, Copy, Serialize)]
+struct ReorgDetails {
+    last_correct_l1_batch: L1BatchNumber,
+    diverged_l1_batch: L1BatchNumber,
+}
+
+/// Default implementation of [`HandleReorgDetectorEvent`] that reports values as metrics and via the health check.
+/// Health details include the last detected reorg (if any), so that it can be observed after the node has rolled back,
+/// and whether the detector is currently comparing the local state against the main node (`checking`).
+#[derive(Debug)]
+struct HealthEventHandler {
+    health_updater: HealthUpdater,
+    last_reorg: Option<ReorgDetails>,
+    is_checking: bool,
+    status: HealthStatus,
+    details: serde_json::Value,
+}
+
+impl HealthEventHandler {
+    fn new(health_updater: HealthUpdater) -> Self {
+        Self {
+            health_updater,
+            last_reorg: None,
+            is_checking: false,
+            status: HealthStatus::NotReady,
+            details: serde_json::json!({}),
+        }
+    }
+
+    fn update_health(&mut self, status: HealthStatus, details: serde_json::Value) {
+        self.status = status;
+        self.details = details;
+        self.publish_health();
+    }
+
+    fn publish_health(&self) {
+        let mut details = self.details.clone();
+        if let Some(last_reorg) = self.last_reorg {
+            details["last_reorg"] = serde_json::json!(last_reorg);
+        }
+        if self.status != HealthStatus::ShuttingDown {
+            details["checking"] = self.is_checking.into();
+        }
+        let mut health = Health::from(self.status);
+        if details
+            .as_object()
+            .is_some_and(|details| !details.is_empty())
+        {
+            health = health.with_details(details);
+        }
+        self.health_updater.update(health);
+    }
+}
+
+impl HandleReorgDetectorEvent for HealthEventHandler {
     fn initialize(&mut self) {
-        self.update(Health::from(HealthStatus::Ready));
+        self.update_health(HealthStatus::Initializing, serde_json::json!({}));
     }
 
     fn update_correct_block(
@@ -182,18 +287,49 @@ impl HandleReorgDetectorEvent for HealthUpdater {
             "last_correct_miniblock": last_correct_miniblock,
             "last_correct_l1_batch": last_correct_l1_batch,
         });
-        self.update(Health::from(HealthStatus::Ready).with_details(health_details));
+        self.update_health(HealthStatus::Ready, health_details);
     }
 
     fn report_divergence(&mut self, diverged_l1_batch: L1BatchNumber) {
         let health_details = serde_json::json!({
             "diverged_l1_batch": diverged_l1_batch,
         });
-        self.update(Health::from(HealthStatus::Affected).with_details(health_details));
+        self.update_health(HealthStatus::Affected, health_details);
+    }
+
+    fn report_reorg(
+        &mut self,
+        last_correct_l1_batch: L1BatchNumber,
+        diverged_l1_batch: L1BatchNumber,
+    ) {
+        self.last_reorg = Some(ReorgDetails {
+            last_correct_l1_batch,
+            diverged_l1_batch,
+        });
+        let health_details = serde_json::json!({
+            "last_correct_l1_batch": last_correct_l1_batch,
+        });
+        self.update_health(HealthStatus::Failed, health_details);
+    }
+
+    fn start_check(&mut self) {
+        self.is_checking = true;
+        self.publish_health();
+    }
+
+    fn report_check(&mut self, result: CheckResult, latency: Duration) {
+        METRICS.check_latency[&result].observe(latency);
+        METRICS.checks[&result].inc();
+        self.is_checking = false;
+        if self.status == HealthStatus::Initializing && result == CheckResult::Match {
+            // The first successful check may not update the last correct block (e.g., if the storage is empty).
+            self.status = HealthStatus::Ready;
+        }
+        self.publish_health();
     }
 
     fn start_shutting_down(&mut self) {
-        self.update(HealthStatus::ShuttingDown.into());
+        self.update_health(HealthStatus::ShuttingDown, serde_json::json!({}));
     }
 }
 
@@ -227,13 +363,29 @@ impl ReorgDetector {
         let (health_check, health_updater) = ReactiveHealthCheck::new("reorg_detector");
         Self {
             client: Box::new(client),
-            event_handler: Box::new(health_updater),
+            event_handler: Box::new(HealthEventHandler::new(health_updater)),
             pool,
             sleep_interval: Self::DEFAULT_SLEEP_INTERVAL,
             health_check,
         }
     }
 
+    /// Retries requests to the main node failed with transient errors according to the specified policy.
+    #[must_use]
+    pub fn with_retries(mut self, config: MainNodeRetryConfig) -> Self {
+        if config.max_retries > 0 {
+            self.client = Box::new(RetryingMainNodeClient::new(self.client, config));
+        }
+        self
+    }
+
+    /// Sets the interval between consistency checks. The default interval is 5 seconds.
+    #[must_use]
+    pub fn with_sleep_interval(mut self, sleep_interval: Duration) -> Self {
+        self.sleep_interval = sleep_interval;
+        self
+    }
+
     pub fn health_check(&self) -> &ReactiveHealthCheck {
         &self.health_check
     }
@@ -305,6 +457,8 @@ impl ReorgDetector {
         tracing::info!("Searching for the first diverged L1 batch");
         let last_correct_l1_batch = self.detect_reorg(first_l1_batch, diverged_l1_batch).await?;
         tracing::info!("Reorg localized: last correct L1 batch is #{last_correct_l1_batch}");
+        self.event_handler
+            .report_reorg(last_correct_l1_batch, last_correct_l1_batch + 1);
         Err(Error::ReorgDetected(last_correct_l1_batch))
     }
 
@@ -390,7 +544,12 @@ impl ReorgDetector {
     pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) -> Result<(), Error> {
         self.event_handler.initialize();
         while !*stop_receiver.borrow() {
-            match self.check_consistency().await {
+            self.event_handler.start_check();
+            let started_at = Instant::now();
+            let result = self.check_consistency().await;
+            self.event_handler
+                .report_check(CheckResult::new(&result), started_at.elapsed());
+            match result {
                 Err(err) if err.is_transient() => {
                     tracing::warn!("Following transient error occurred: {err}");
                     tracing::info!("Trying again after a delay");
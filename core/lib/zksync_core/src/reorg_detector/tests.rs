@@ -9,6 +9,7 @@ use assert_matches::assert_matches;
 use test_casing::{test_casing, Product};
 use tokio::sync::mpsc;
 use zksync_dal::{Connection, CoreDal};
+use zksync_health_check::CheckHealth;
 use zksync_types::{
     block::{MiniblockHasher, MiniblockHeader},
     ProtocolVersion,
@@ -147,6 +148,60 @@ impl HandleReorgDetectorEvent for mpsc::UnboundedSender<(MiniblockNumber, L1Batc
         // Do nothing
     }
 
+    fn report_reorg(
+        &mut self,
+        _last_correct_l1_batch: L1BatchNumber,
+        _diverged_l1_batch: L1BatchNumber,
+    ) {
+        // Do nothing
+    }
+
+    fn start_check(&mut self) {
+        // Do nothing
+    }
+
+    fn report_check(&mut self, _result: CheckResult, _latency: Duration) {
+        // Do nothing
+    }
+
+    fn start_shutting_down(&mut self) {
+        // Do nothing
+    }
+}
+
+/// Event handler recording reported reorg checks.
+#[derive(Debug)]
+struct CheckRecorder(mpsc::UnboundedSender<(CheckResult, Duration)>);
+
+impl HandleReorgDetectorEvent for CheckRecorder {
+    fn initialize(&mut self) {
+        // Do nothing
+    }
+
+    fn update_correct_block(&mut self, _: MiniblockNumber, _: L1BatchNumber) {
+        // Do nothing
+    }
+
+    fn report_divergence(&mut self, _diverged_l1_batch: L1BatchNumber) {
+        // Do nothing
+    }
+
+    fn report_reorg(
+        &mut self,
+        _last_correct_l1_batch: L1BatchNumber,
+        _diverged_l1_batch: L1BatchNumber,
+    ) {
+        // Do nothing
+    }
+
+    fn start_check(&mut self) {
+        // Do nothing
+    }
+
+    fn report_check(&mut self, result: CheckResult, latency: Duration) {
+        self.0.send((result, latency)).ok();
+    }
+
     fn start_shutting_down(&mut self) {
         // Do nothing
     }
@@ -156,7 +211,7 @@ fn create_mock_detector(client: MockMainNodeClient, pool: ConnectionPool<Core>)
     let (health_check, health_updater) = ReactiveHealthCheck::new("reorg_detector");
     ReorgDetector {
         client: Box::new(client),
-        event_handler: Box::new(health_updater),
+        event_handler: Box::new(HealthEventHandler::new(health_updater)),
         pool,
         sleep_interval: Duration::from_millis(10),
         health_check,
@@ -260,8 +315,42 @@ async fn detector_stops_on_fatal_rpc_error() {
 
     let stop = watch::channel(false).1;
     let detector = create_mock_detector(client, pool.clone());
+    let failed_checks_before = METRICS.checks[&CheckResult::FatalError].get();
     // Check that the detector stops when a fatal RPC error is encountered.
     detector.run(stop).await.unwrap_err();
+    assert!(METRICS.checks[&CheckResult::FatalError].get() > failed_checks_before);
+}
+
+#[tokio::test]
+async fn detector_reports_check_latency() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    let genesis_batch = insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    let mut client = MockMainNodeClient::default();
+    client.miniblock_hashes.insert(
+        MiniblockNumber(0),
+        MiniblockHasher::legacy_hash(MiniblockNumber(0)),
+    );
+    client
+        .l1_batch_root_hashes
+        .insert(L1BatchNumber(0), genesis_batch.root_hash);
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let (check_sender, mut check_receiver) = mpsc::unbounded_channel();
+    let detector = ReorgDetector {
+        event_handler: Box::new(CheckRecorder(check_sender)),
+        ..create_mock_detector(client, pool.clone())
+    };
+    let detector_task = tokio::spawn(detector.run(stop_receiver));
+
+    let (result, latency) = check_receiver.recv().await.unwrap();
+    assert_eq!(result, CheckResult::Match);
+    assert!(latency > Duration::ZERO, "{latency:?}");
+
+    stop_sender.send_replace(true);
+    detector_task.await.unwrap().unwrap();
 }
 
 #[tokio::test]
@@ -307,6 +396,69 @@ async fn reorg_is_detected_on_batch_hash_mismatch() {
         detector.check_consistency().await,
         Err(Error::ReorgDetected(L1BatchNumber(1)))
     );
+    let health = detector.health_check().check_health().await;
+    assert_matches!(health.status(), HealthStatus::Failed);
+    let details = serde_json::to_value(&health).unwrap()["details"].clone();
+    assert_eq!(
+        details["last_reorg"],
+        serde_json::json!({
+            "last_correct_l1_batch": 1,
+            "diverged_l1_batch": 2,
+        })
+    );
+
+    // The last reorg should be retained in health details after subsequent successful checks.
+    detector
+        .event_handler
+        .update_correct_block(MiniblockNumber(2), L1BatchNumber(1));
+    let health = detector.health_check().check_health().await;
+    assert_matches!(health.status(), HealthStatus::Ready);
+    let details = serde_json::to_value(&health).unwrap()["details"].clone();
+    assert_eq!(details["last_correct_l1_batch"], 1);
+    assert_eq!(details["last_reorg"]["diverged_l1_batch"], 2);
+}
+
+#[tokio::test]
+async fn health_reports_ongoing_checks() {
+    let (health_check, health_updater) = ReactiveHealthCheck::new("reorg_detector");
+    let mut event_handler = HealthEventHandler::new(health_updater);
+    event_handler.initialize();
+    let health = serde_json::to_value(health_check.check_health().await).unwrap();
+    assert_eq!(health["status"], "initializing");
+    assert_eq!(health["details"]["checking"], false);
+
+    event_handler.start_check();
+    let health = serde_json::to_value(health_check.check_health().await).unwrap();
+    assert_eq!(health["status"], "initializing");
+    assert_eq!(health["details"]["checking"], true);
+
+    event_handler.update_correct_block(MiniblockNumber(3), L1BatchNumber(1));
+    let health = serde_json::to_value(health_check.check_health().await).unwrap();
+    assert_eq!(health["details"]["checking"], true);
+    assert_eq!(health["details"]["last_correct_l1_batch"], 1);
+
+    event_handler.report_check(CheckResult::Match, Duration::from_millis(10));
+    let health = serde_json::to_value(health_check.check_health().await).unwrap();
+    assert_eq!(health["status"], "ready");
+    assert_eq!(health["details"]["checking"], false);
+    assert_eq!(health["details"]["last_correct_l1_batch"], 1);
+}
+
+#[tokio::test]
+async fn detector_is_initializing_until_first_successful_check() {
+    let (health_check, health_updater) = ReactiveHealthCheck::new("reorg_detector");
+    let mut event_handler = HealthEventHandler::new(health_updater);
+    event_handler.initialize();
+    event_handler.start_check();
+    event_handler.report_check(CheckResult::TransientError, Duration::from_millis(10));
+    let health = health_check.check_health().await;
+    assert_matches!(health.status(), HealthStatus::Initializing);
+
+    // A successful check on empty storage doesn't report the last correct block, but must still finish initialization.
+    event_handler.start_check();
+    event_handler.report_check(CheckResult::Match, Duration::from_millis(10));
+    let health = health_check.check_health().await;
+    assert_matches!(health.status(), HealthStatus::Ready);
 }
 
 #[tokio::test]
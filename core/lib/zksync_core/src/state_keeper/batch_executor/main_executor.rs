@@ -27,6 +27,7 @@ use crate::{
         state_keeper_storage::ReadStorageFactory,
         types::ExecutionMetricsForCriteria,
     },
+    utils::is_sampled_for_call_traces,
 };
 
 /// The default implementation of [`BatchExecutor`].
@@ -35,6 +36,7 @@ use crate::{
 pub struct MainBatchExecutor {
     storage_factory: Arc<dyn ReadStorageFactory>,
     save_call_traces: bool,
+    call_traces_sampling_rate: f64,
     optional_bytecode_compression: bool,
 }
 
@@ -47,9 +49,52 @@ impl MainBatchExecutor {
         Self {
             storage_factory,
             save_call_traces,
+            call_traces_sampling_rate: 1.0,
             optional_bytecode_compression,
         }
     }
+
+    /// Sets the fraction of transactions for which call traces are saved. Has no effect if call traces
+    /// are not saved at all. By default, traces are saved for all transactions.
+    ///
+    /// Transactions are sampled deterministically based on their hash, so the same transactions are sampled
+    /// on re-execution and on different nodes with the same sampling rate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is not in the `0.0..=1.0` range.
+    pub fn with_call_traces_sampling_rate(mut self, rate: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&rate),
+            "Call traces sampling rate must be in 0.0..=1.0 range, got {rate}"
+        );
+        self.call_traces_sampling_rate = rate;
+        self
+    }
+
+    /// Starts executing a batch on top of the provided `storage` rather than the storage produced by a factory.
+    /// Call traces are not saved for transactions executed in this way. Can be used to execute transactions
+    /// in a sandbox, e.g. to self-test the VM.
+    pub(crate) fn init_sandboxed_batch<S: ReadStorage + Send + 'static>(
+        storage: S,
+        l1_batch_params: L1BatchEnv,
+        system_env: SystemEnv,
+    ) -> BatchExecutorHandle {
+        let (commands_sender, commands_receiver) = mpsc::channel(1);
+        let executor = CommandReceiver {
+            save_call_traces: false,
+            call_traces_sampling_rate: 1.0,
+            optional_bytecode_compression: false,
+            commands: commands_receiver,
+        };
+        let handle = tokio::task::spawn_blocking(move || {
+            executor.run(storage, l1_batch_params, system_env);
+        });
+        BatchExecutorHandle {
+            handle,
+            commands: commands_sender,
+        }
+    }
 }
 
 #[async_trait]
@@ -65,6 +110,7 @@ impl BatchExecutor for MainBatchExecutor {
         let (commands_sender, commands_receiver) = mpsc::channel(1);
         let executor = CommandReceiver {
             save_call_traces: self.save_call_traces,
+            call_traces_sampling_rate: self.call_traces_sampling_rate,
             optional_bytecode_compression: self.optional_bytecode_compression,
             commands: commands_receiver,
         };
@@ -97,6 +143,7 @@ impl BatchExecutor for MainBatchExecutor {
 #[derive(Debug)]
 struct CommandReceiver {
     save_call_traces: bool,
+    call_traces_sampling_rate: f64,
     optional_bytecode_compression: bool,
     commands: mpsc::Receiver<Command>,
 }
@@ -156,12 +203,14 @@ impl CommandReceiver {
         vm.make_snapshot();
 
         // Execute the transaction.
+        let save_call_trace = self.save_call_traces
+            && is_sampled_for_call_traces(tx.hash(), self.call_traces_sampling_rate);
         let latency = KEEPER_METRICS.tx_execution_time[&TxExecutionStage::Execution].start();
         let (tx_result, compressed_bytecodes, call_tracer_result) =
             if self.optional_bytecode_compression {
-                self.execute_tx_in_vm_with_optional_compression(tx, vm)
+                self.execute_tx_in_vm_with_optional_compression(tx, vm, save_call_trace)
             } else {
-                self.execute_tx_in_vm(tx, vm)
+                self.execute_tx_in_vm(tx, vm, save_call_trace)
             };
         latency.observe();
         APP_METRICS.processed_txs[&TxStage::StateKeeper].inc();
@@ -222,6 +271,7 @@ impl CommandReceiver {
         &self,
         tx: &Transaction,
         vm: &mut VmInstance<S, HistoryEnabled>,
+        save_call_trace: bool,
     ) -> (
         VmExecutionResultAndLogs,
         Vec<CompressedBytecodeInfo>,
@@ -240,7 +290,7 @@ impl CommandReceiver {
         vm.make_snapshot();
 
         let call_tracer_result = Arc::new(OnceCell::default());
-        let tracer = if self.save_call_traces {
+        let tracer = if save_call_trace {
             vec![CallTracer::new(call_tracer_result.clone()).into_tracer_pointer()]
         } else {
             vec![]
@@ -261,7 +311,7 @@ impl CommandReceiver {
         vm.rollback_to_the_latest_snapshot();
 
         let call_tracer_result = Arc::new(OnceCell::default());
-        let tracer = if self.save_call_traces {
+        let tracer = if save_call_trace {
             vec![CallTracer::new(call_tracer_result.clone()).into_tracer_pointer()]
         } else {
             vec![]
@@ -291,13 +341,14 @@ impl CommandReceiver {
         &self,
         tx: &Transaction,
         vm: &mut VmInstance<S, HistoryEnabled>,
+        save_call_trace: bool,
     ) -> (
         VmExecutionResultAndLogs,
         Vec<CompressedBytecodeInfo>,
         Vec<Call>,
     ) {
         let call_tracer_result = Arc::new(OnceCell::default());
-        let tracer = if self.save_call_traces {
+        let tracer = if save_call_trace {
             vec![CallTracer::new(call_tracer_result.clone()).into_tracer_pointer()]
         } else {
             vec![]
@@ -57,6 +57,29 @@ async fn execute_l2_tx(storage_type: StorageType) {
     executor.finish_batch().await;
 }
 
+/// Checks that a batch executes identically with Postgres-only storage and with RocksDB-backed storage.
+#[tokio::test]
+async fn execution_parity_with_and_without_rocksdb() {
+    let alice = Account::random();
+    let mut final_states = vec![];
+    for storage_type in [StorageType::Rocksdb, StorageType::Postgres] {
+        let connection_pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+        let mut alice = alice.clone();
+        let mut tester = Tester::new(connection_pool);
+        tester.genesis().await;
+        tester.fund(&[alice.address()]).await;
+        let executor = tester.create_batch_executor(storage_type).await;
+
+        for _ in 0..3 {
+            let res = executor.execute_tx(alice.execute()).await;
+            assert_executed(&res);
+        }
+        let finished_batch = executor.finish_batch().await;
+        final_states.push(finished_batch.final_execution_state);
+    }
+    assert_eq!(final_states[0], final_states[1]);
+}
+
 #[derive(Debug, Clone, Copy)]
 enum SnapshotRecoveryMutation {
     RemoveNonce,
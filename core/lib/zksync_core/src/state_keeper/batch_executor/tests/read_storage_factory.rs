@@ -4,33 +4,7 @@ use tokio::sync::watch;
 use zksync_dal::{ConnectionPool, Core};
 use zksync_state::RocksdbStorage;
 
-use crate::state_keeper::{
-    state_keeper_storage::{PgOrRocksdbStorage, ReadStorageFactory},
-    AsyncRocksdbCache,
-};
-
-#[derive(Debug, Clone)]
-pub struct PostgresFactory {
-    pool: ConnectionPool<Core>,
-}
-
-#[async_trait]
-impl ReadStorageFactory for PostgresFactory {
-    async fn access_storage(
-        &self,
-        _stop_receiver: &watch::Receiver<bool>,
-    ) -> anyhow::Result<Option<PgOrRocksdbStorage<'_>>> {
-        Ok(Some(
-            AsyncRocksdbCache::access_storage_pg(&self.pool).await?,
-        ))
-    }
-}
-
-impl PostgresFactory {
-    pub fn new(pool: ConnectionPool<Core>) -> Self {
-        Self { pool }
-    }
-}
+use crate::state_keeper::state_keeper_storage::{PgOrRocksdbStorage, ReadStorageFactory};
 
 #[derive(Debug, Clone)]
 pub struct RocksdbFactory {
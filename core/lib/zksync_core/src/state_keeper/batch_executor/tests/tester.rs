@@ -23,17 +23,15 @@ use zksync_types::{
 };
 use zksync_utils::u256_to_h256;
 
-use super::{
-    read_storage_factory::{PostgresFactory, RocksdbFactory},
-    StorageType,
-};
+use super::{read_storage_factory::RocksdbFactory, StorageType};
 use crate::{
     genesis::create_genesis_l1_batch,
     state_keeper::{
         batch_executor::{BatchExecutorHandle, TxExecutionResult},
         state_keeper_storage::ReadStorageFactory,
         tests::{default_l1_batch_env, default_system_env, BASE_SYSTEM_CONTRACTS},
-        AsyncRocksdbCache, BatchExecutor, MainBatchExecutor,
+        AsyncRocksdbCache, BatchExecutor, MainBatchExecutor, PostgresStorageFactory,
+        StateKeeperRocksdbOptions,
     },
     utils::testonly::prepare_recovery_snapshot,
 };
@@ -105,6 +103,7 @@ impl Tester {
                 let (state_keeper_storage, task) = AsyncRocksdbCache::new(
                     self.pool(),
                     self.state_keeper_db_path(),
+                    StateKeeperRocksdbOptions::default(),
                     self.enum_index_migration_chunk_size(),
                 );
                 let handle = tokio::task::spawn(async move {
@@ -133,7 +132,7 @@ impl Tester {
             }
             StorageType::Postgres => {
                 self.create_batch_executor_inner(
-                    Arc::new(PostgresFactory::new(self.pool())),
+                    Arc::new(PostgresStorageFactory::new(self.pool())),
                     l1_batch_env,
                     system_env,
                 )
@@ -164,6 +163,7 @@ impl Tester {
         let (storage_factory, task) = AsyncRocksdbCache::new(
             self.pool(),
             self.state_keeper_db_path(),
+            StateKeeperRocksdbOptions::default(),
             self.enum_index_migration_chunk_size(),
         );
         let (_, stop_receiver) = watch::channel(false);
@@ -193,7 +193,7 @@ impl Tester {
             }
             StorageType::Postgres => {
                 self.recover_batch_executor_inner(
-                    Arc::new(PostgresFactory::new(self.pool())),
+                    Arc::new(PostgresStorageFactory::new(self.pool())),
                     snapshot,
                 )
                 .await
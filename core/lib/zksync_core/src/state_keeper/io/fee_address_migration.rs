@@ -9,6 +9,10 @@ use tokio::sync::watch;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_types::MiniblockNumber;
 
+/// Number of miniblocks migrated in a single chunk. Must be constant, so that each chunk is migrated atomically
+/// and its start can be used to check whether the chunk is migrated.
+const CHUNK_SIZE: u32 = 100_000;
+
 /// Runs the migration for pending miniblocks.
 pub(crate) async fn migrate_pending_miniblocks(
     storage: &mut Connection<'_, Core>,
@@ -38,7 +42,8 @@ pub(crate) async fn migrate_pending_miniblocks(
     Ok(())
 }
 
-/// Runs the migration for non-pending miniblocks. Should be run as a background task.
+/// Runs the migration for non-pending miniblocks. Should be run as a background task; returns
+/// once all miniblocks are migrated (or a stop signal is received).
 pub(crate) async fn migrate_miniblocks(
     pool: ConnectionPool<Core>,
     stop_receiver: watch::Receiver<bool>,
@@ -69,7 +74,7 @@ pub(crate) async fn migrate_miniblocks(
     } = migrate_miniblocks_inner(
         pool,
         last_miniblock,
-        100_000,
+        CHUNK_SIZE,
         Duration::from_secs(1),
         stop_receiver,
     )
@@ -79,6 +84,44 @@ pub(crate) async fn migrate_miniblocks(
     Ok(())
 }
 
+/// Checks that all miniblocks in the storage have their fee address migrated. Should be used instead
+/// of [`migrate_miniblocks()`] if the migration task is disabled.
+pub async fn ensure_miniblocks_migrated(pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+    let mut storage = pool.connection_tagged("state_keeper").await?;
+    #[allow(deprecated)]
+    let l1_batches_have_fee_account_address = storage
+        .blocks_dal()
+        .check_l1_batches_have_fee_account_address()
+        .await
+        .context("failed getting metadata for l1_batches table")?;
+    if !l1_batches_have_fee_account_address {
+        return Ok(());
+    }
+    if storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await?
+        .is_some()
+    {
+        return Ok(());
+    }
+    let Some(last_miniblock) = storage.blocks_dal().get_sealed_miniblock_number().await? else {
+        return Ok(()); // Storage is empty, so there's nothing to migrate
+    };
+
+    let mut chunk_start = MiniblockNumber(0);
+    while chunk_start <= last_miniblock {
+        anyhow::ensure!(
+            is_fee_address_migrated(&mut storage, chunk_start).await?,
+            "`fee_account_address` is not migrated for miniblock #{chunk_start} (last sealed miniblock: \
+             #{last_miniblock}); the fee address migration must be enabled until it completes. \
+             Running the node with an unmigrated storage will lead to incorrect data being served"
+        );
+        chunk_start += CHUNK_SIZE;
+    }
+    Ok(())
+}
+
 #[derive(Debug, Default)]
 struct MigrationOutput {
     miniblocks_affected: u64,
@@ -278,6 +321,24 @@ mod tests {
         assert_eq!(result.miniblocks_affected, 0);
     }
 
+    #[tokio::test]
+    async fn ensuring_migration_completeness() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        prepare_storage(&mut storage).await;
+        drop(storage);
+
+        let err = ensure_miniblocks_migrated(&pool).await.unwrap_err();
+        let err = format!("{err:#}");
+        assert!(err.contains("miniblock #0"), "{err}");
+
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        migrate_miniblocks(pool.clone(), stop_receiver)
+            .await
+            .unwrap();
+        ensure_miniblocks_migrated(&pool).await.unwrap();
+    }
+
     #[test_casing(3, [1, 2, 3])]
     #[tokio::test]
     async fn stopping_and_resuming_migration(chunk_size: u32) {
@@ -1,4 +1,4 @@
-use std::{fmt, time::Duration};
+use std::{fmt, ops, time::Duration};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
@@ -13,14 +13,14 @@ use zksync_types::{
 pub use self::{
     common::IoCursor,
     output_handler::{OutputHandler, StateKeeperOutputHandler},
-    persistence::{MiniblockSealerTask, StateKeeperPersistence},
+    persistence::{MiniblockSealerTask, SealQueueLoad, StateKeeperPersistence},
 };
 use super::seal_criteria::IoSealCriteria;
 
 pub(crate) mod common;
 pub(crate) mod fee_address_migration;
 pub(crate) mod mempool;
-mod output_handler;
+pub(crate) mod output_handler;
 mod persistence;
 pub(crate) mod seal_logic;
 #[cfg(test)]
@@ -148,6 +148,19 @@ pub trait StateKeeperIO: 'static + Send + fmt::Debug + IoSealCriteria {
         &mut self,
         number: L1BatchNumber,
     ) -> anyhow::Result<ProtocolVersionId>;
+    /// Loads protocol versions of L1 batches in the specified range, which are guaranteed to exist in the storage.
+    /// The default implementation calls [`Self::load_batch_version_id()`] for each batch; implementations should
+    /// override it if versions can be loaded in bulk.
+    async fn load_batch_version_ids(
+        &mut self,
+        numbers: ops::RangeInclusive<L1BatchNumber>,
+    ) -> anyhow::Result<Vec<ProtocolVersionId>> {
+        let mut versions = vec![];
+        for number in numbers.start().0..=numbers.end().0 {
+            versions.push(self.load_batch_version_id(L1BatchNumber(number)).await?);
+        }
+        Ok(versions)
+    }
     /// Loads protocol upgrade tx for given version.
     async fn load_upgrade_tx(
         &mut self,
@@ -1,9 +1,10 @@
 //! Handling outputs produced by the state keeper.
 
-use std::fmt;
+use std::{fmt, ops};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
+use zksync_types::{L1BatchNumber, MiniblockNumber};
 
 use crate::state_keeper::{io::IoCursor, updates::UpdatesManager};
 
@@ -25,6 +26,51 @@ pub trait StateKeeperOutputHandler: 'static + Send + fmt::Debug {
     }
 }
 
+/// Summary of a sealed L1 batch. Emitted as a single structured `tracing` event once all output handlers
+/// have processed the batch, so that log consumers get a machine-parseable record of each sealed batch.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct L1BatchSealedEvent {
+    pub l1_batch_number: L1BatchNumber,
+    /// Miniblocks in the batch, including the fictive one.
+    pub miniblocks: ops::RangeInclusive<MiniblockNumber>,
+    pub tx_count: usize,
+    pub gas_used: usize,
+    pub pubdata_published: u32,
+    /// Criterion that has triggered sealing the batch, if known.
+    pub seal_criterion: Option<&'static str>,
+}
+
+impl L1BatchSealedEvent {
+    pub(crate) fn new(updates_manager: &UpdatesManager) -> Self {
+        // At this point, the fictive miniblock is still open, i.e., not included into `l1_batch.miniblock_count`.
+        let last_miniblock = updates_manager.miniblock.number;
+        let first_miniblock = last_miniblock - updates_manager.l1_batch.miniblock_count as u32;
+        let execution_metrics = updates_manager.pending_execution_metrics();
+        Self {
+            l1_batch_number: updates_manager.l1_batch.number,
+            miniblocks: first_miniblock..=last_miniblock,
+            tx_count: updates_manager.pending_executed_transactions_len(),
+            gas_used: execution_metrics.gas_used,
+            pubdata_published: execution_metrics.pubdata_published,
+            seal_criterion: updates_manager.l1_batch.seal_criterion,
+        }
+    }
+
+    fn emit(&self) {
+        tracing::info!(
+            l1_batch_number = self.l1_batch_number.0,
+            first_miniblock = self.miniblocks.start().0,
+            last_miniblock = self.miniblocks.end().0,
+            tx_count = self.tx_count,
+            gas_used = self.gas_used,
+            pubdata_published = self.pubdata_published,
+            seal_criterion = self.seal_criterion.unwrap_or("unknown"),
+            "Sealed L1 batch #{}",
+            self.l1_batch_number
+        );
+    }
+}
+
 /// Compound output handler plugged into the state keeper.
 ///
 /// This handle aggregates one or more [`StateKeeperOutputHandler`]s executing their hooks
@@ -94,6 +140,7 @@ impl OutputHandler {
                     )
                 })?;
         }
+        L1BatchSealedEvent::new(updates_manager).emit();
         Ok(())
     }
 }
@@ -51,6 +51,7 @@ impl StateKeeperPersistence {
         let (commands_sender, commands_receiver) = mpsc::channel(command_capacity);
         let sealer = MiniblockSealerTask {
             pool: pool.clone(),
+            call_traces_pool: None,
             is_sync,
             commands_sender: commands_sender.downgrade(),
             commands_receiver,
@@ -71,6 +72,13 @@ impl StateKeeperPersistence {
         self
     }
 
+    /// Returns a handle allowing to monitor the load of the miniblock seal queue.
+    pub fn seal_queue_load(&self) -> SealQueueLoad {
+        SealQueueLoad {
+            commands_sender: self.commands_sender.downgrade(),
+        }
+    }
+
     /// Submits a new sealing `command` to the sealer that this handle is attached to.
     ///
     /// If there are currently too many unprocessed commands, this method will wait until
@@ -161,10 +169,27 @@ impl StateKeeperOutputHandler for StateKeeperPersistence {
     }
 }
 
+/// Handle allowing to monitor the load of the miniblock seal queue, e.g. to postpone heavy background operations
+/// until the queue is drained.
+#[derive(Debug, Clone)]
+pub struct SealQueueLoad {
+    commands_sender: mpsc::WeakSender<Completable<MiniblockSealCommand>>,
+}
+
+impl SealQueueLoad {
+    /// Returns the number of miniblock sealing commands waiting in the queue.
+    pub fn pending_commands(&self) -> usize {
+        self.commands_sender
+            .upgrade()
+            .map_or(0, |sender| sender.max_capacity() - sender.capacity())
+    }
+}
+
 /// Component responsible for sealing miniblocks (i.e., storing their data to Postgres).
 #[derive(Debug)]
 pub struct MiniblockSealerTask {
     pool: ConnectionPool<Core>,
+    call_traces_pool: Option<ConnectionPool<Core>>,
     is_sync: bool,
     // Weak sender handle to get queue capacity stats.
     commands_sender: mpsc::WeakSender<Completable<MiniblockSealCommand>>,
@@ -172,6 +197,15 @@ pub struct MiniblockSealerTask {
 }
 
 impl MiniblockSealerTask {
+    /// Makes the sealer persist call traces of sealed transactions using a dedicated connection pool,
+    /// rather than in the same DB transaction as other miniblock data. Traces are persisted before the miniblock
+    /// is committed, so a failure persisting them aborts sealing.
+    #[must_use]
+    pub fn with_call_traces_pool(mut self, pool: ConnectionPool<Core>) -> Self {
+        self.call_traces_pool = Some(pool);
+        self
+    }
+
     /// Seals miniblocks as they are received from the [`StateKeeperPersistence`]. This should be run
     /// on a separate Tokio task.
     pub async fn run(mut self) -> anyhow::Result<()> {
@@ -191,7 +225,16 @@ impl MiniblockSealerTask {
         // an earlier one.
         while let Some(completable) = self.next_command().await {
             let mut storage = self.pool.connection_tagged("state_keeper").await?;
-            completable.command.seal(&mut storage).await;
+            if let Some(call_traces_pool) = &self.call_traces_pool {
+                let mut call_traces_storage =
+                    call_traces_pool.connection_tagged("state_keeper").await?;
+                completable
+                    .command
+                    .seal_with_separate_call_traces(&mut storage, &mut call_traces_storage)
+                    .await;
+            } else {
+                completable.command.seal(&mut storage).await;
+            }
             if let Some(delta) = miniblock_seal_delta {
                 MINIBLOCK_METRICS.seal_delta.observe(delta.elapsed());
             }
@@ -233,7 +276,8 @@ mod tests {
     use multivm::zk_evm_latest::ethereum_types::H256;
     use zksync_dal::CoreDal;
     use zksync_types::{
-        block::BlockGasCount, tx::ExecutionMetrics, L1BatchNumber, MiniblockNumber,
+        block::BlockGasCount, fee::TransactionExecutionMetrics, l2::L2Tx, tx::ExecutionMetrics,
+        vm_trace::Call, L1BatchNumber, MiniblockNumber, U256,
     };
 
     use super::*;
@@ -246,6 +290,7 @@ mod tests {
                 default_l1_batch_env, default_system_env, default_vm_block_result,
             },
         },
+        utils::testonly::create_l2_transaction,
     };
 
     async fn test_miniblock_and_l1_batch_processing(
@@ -399,4 +444,144 @@ mod tests {
 
         persistence.wait_for_all_commands().await;
     }
+
+    fn create_updates_with_call_trace(tx: L2Tx) -> UpdatesManager {
+        let l1_batch_env = default_l1_batch_env(1, 1, Address::random());
+        let mut updates = UpdatesManager::new(&l1_batch_env, &default_system_env());
+        let call_trace = Call::new_high_level(0, 0, U256::zero(), vec![], vec![], None, vec![]);
+        updates.extend_from_executed_transaction(
+            tx.into(),
+            create_execution_result(0, []),
+            vec![],
+            BlockGasCount::default(),
+            ExecutionMetrics::default(),
+            vec![call_trace],
+        );
+        updates
+    }
+
+    #[tokio::test]
+    async fn call_traces_are_persisted_via_dedicated_pool() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        // Use a separate database for call traces, so that it's possible to check which pool was used.
+        let call_traces_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+        drop(storage);
+
+        let tx = create_l2_transaction(10, 100);
+        let tx_hash = tx.hash();
+        // Call traces reference transactions, so the transaction must be present in both databases.
+        for pool in [&pool, &call_traces_pool] {
+            pool.connection()
+                .await
+                .unwrap()
+                .transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await
+                .unwrap();
+        }
+
+        let (mut persistence, miniblock_sealer) =
+            StateKeeperPersistence::new(pool.clone(), Address::default(), 1);
+        let miniblock_sealer = miniblock_sealer.with_call_traces_pool(call_traces_pool.clone());
+        tokio::spawn(miniblock_sealer.run());
+
+        let updates = create_updates_with_call_trace(tx);
+        persistence.handle_miniblock(&updates).await.unwrap();
+        persistence.wait_for_all_commands().await;
+
+        let mut storage = pool.connection().await.unwrap();
+        assert_eq!(
+            storage
+                .blocks_dal()
+                .get_sealed_miniblock_number()
+                .await
+                .unwrap(),
+            Some(MiniblockNumber(1))
+        );
+        let trace = storage
+            .transactions_dal()
+            .get_call_trace(tx_hash)
+            .await
+            .unwrap();
+        assert!(trace.is_none(), "{trace:?}");
+
+        let mut call_traces_storage = call_traces_pool.connection().await.unwrap();
+        let trace = call_traces_storage
+            .transactions_dal()
+            .get_call_trace(tx_hash)
+            .await
+            .unwrap()
+            .expect("no call trace in dedicated storage");
+        assert_eq!(trace.calls.len(), 1, "{trace:?}");
+    }
+
+    #[tokio::test]
+    async fn failure_persisting_call_traces_aborts_miniblock_sealing() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let call_traces_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+        let tx = create_l2_transaction(10, 100);
+        let tx_hash = tx.hash();
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        drop(storage);
+
+        // The transaction is missing from the call traces database, so persisting call traces will fail.
+        let (mut persistence, miniblock_sealer) =
+            StateKeeperPersistence::new(pool.clone(), Address::default(), 1);
+        let miniblock_sealer = miniblock_sealer.with_call_traces_pool(call_traces_pool.clone());
+        let miniblock_sealer_task = tokio::spawn(miniblock_sealer.run());
+        let updates = create_updates_with_call_trace(tx.clone());
+        persistence.handle_miniblock(&updates).await.unwrap();
+        let err = miniblock_sealer_task.await.unwrap_err();
+        assert!(err.is_panic(), "{err:?}");
+
+        let mut storage = pool.connection().await.unwrap();
+        let sealed_miniblock_number = storage.blocks_dal().get_sealed_miniblock_number().await;
+        assert_eq!(sealed_miniblock_number.unwrap(), Some(MiniblockNumber(0)));
+        drop(storage);
+
+        // Emulate a sealing attempt that has persisted call traces, but hasn't committed the miniblock.
+        let mut call_traces_storage = call_traces_pool.connection().await.unwrap();
+        call_traces_storage
+            .transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        call_traces_storage
+            .transactions_dal()
+            .insert_call_traces(&updates.miniblock.executed_transactions)
+            .await;
+        drop(call_traces_storage);
+
+        // Sealing the miniblock again should succeed.
+        let (mut persistence, miniblock_sealer) =
+            StateKeeperPersistence::new(pool.clone(), Address::default(), 1);
+        let miniblock_sealer = miniblock_sealer.with_call_traces_pool(call_traces_pool.clone());
+        tokio::spawn(miniblock_sealer.run());
+        persistence.handle_miniblock(&updates).await.unwrap();
+        persistence.wait_for_all_commands().await;
+
+        let mut storage = pool.connection().await.unwrap();
+        let sealed_miniblock_number = storage.blocks_dal().get_sealed_miniblock_number().await;
+        assert_eq!(sealed_miniblock_number.unwrap(), Some(MiniblockNumber(1)));
+        let mut call_traces_storage = call_traces_pool.connection().await.unwrap();
+        let trace = call_traces_storage
+            .transactions_dal()
+            .get_call_trace(tx_hash)
+            .await
+            .unwrap()
+            .expect("no call trace in dedicated storage");
+        assert_eq!(trace.calls.len(), 1, "{trace:?}");
+    }
 }
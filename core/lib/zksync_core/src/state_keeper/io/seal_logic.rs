@@ -60,7 +60,9 @@ impl UpdatesManager {
             l2_erc20_bridge_addr,
             false, // fictive miniblocks don't have txs, so it's fine to pass `false` here.
         );
-        miniblock_command.seal_inner(&mut transaction, true).await;
+        miniblock_command
+            .seal_inner(&mut transaction, true, None)
+            .await;
         progress.observe(None);
 
         let progress = L1_BATCH_METRICS.start(L1BatchSealStage::LogDeduplication);
@@ -252,7 +254,19 @@ impl UpdatesManager {
 
 impl MiniblockSealCommand {
     pub(super) async fn seal(&self, storage: &mut Connection<'_, Core>) {
-        self.seal_inner(storage, false).await;
+        self.seal_inner(storage, false, None).await;
+    }
+
+    /// Seals the miniblock similarly to [`Self::seal()`], but persists call traces of the miniblock transactions
+    /// using `call_traces_storage`. Traces are persisted before the miniblock is committed, and a failure
+    /// to persist them aborts sealing, so a sealed miniblock always has call traces for its transactions.
+    pub(super) async fn seal_with_separate_call_traces(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        call_traces_storage: &mut Connection<'_, Core>,
+    ) {
+        self.seal_inner(storage, false, Some(call_traces_storage))
+            .await;
     }
 
     async fn insert_transactions(&self, transaction: &mut Connection<'_, Core>) {
@@ -299,8 +313,18 @@ impl MiniblockSealCommand {
     /// one for sending fees to the operator).
     ///
     /// `l2_erc20_bridge_addr` is required to extract the information on newly added tokens.
-    async fn seal_inner(&self, storage: &mut Connection<'_, Core>, is_fictive: bool) {
+    ///
+    /// If `call_traces_storage` is provided, call traces of the miniblock transactions are persisted using it
+    /// right before the miniblock is committed. This isn't supported if transactions are inserted during sealing
+    /// (since call traces reference transactions); in this case, call traces are persisted using `storage`.
+    async fn seal_inner(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        is_fictive: bool,
+        call_traces_storage: Option<&mut Connection<'_, Core>>,
+    ) {
         self.assert_valid_miniblock(is_fictive);
+        let call_traces_storage = call_traces_storage.filter(|_| !self.pre_insert_txs);
 
         let mut transaction = storage.start_transaction().await.unwrap();
         if self.pre_insert_txs {
@@ -354,14 +378,24 @@ impl MiniblockSealCommand {
 
         let progress =
             MINIBLOCK_METRICS.start(MiniblockSealStage::MarkTransactionsInMiniblock, is_fictive);
-        transaction
-            .transactions_dal()
-            .mark_txs_as_executed_in_miniblock(
-                miniblock_number,
-                &self.miniblock.executed_transactions,
-                self.base_fee_per_gas.into(),
-            )
-            .await;
+        let mut transactions_dal = transaction.transactions_dal();
+        if call_traces_storage.is_none() {
+            transactions_dal
+                .mark_txs_as_executed_in_miniblock(
+                    miniblock_number,
+                    &self.miniblock.executed_transactions,
+                    self.base_fee_per_gas.into(),
+                )
+                .await;
+        } else {
+            transactions_dal
+                .mark_txs_as_executed_in_miniblock_without_call_traces(
+                    miniblock_number,
+                    &self.miniblock.executed_transactions,
+                    self.base_fee_per_gas.into(),
+                )
+                .await;
+        }
         progress.observe(self.miniblock.executed_transactions.len());
 
         let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::InsertStorageLogs, is_fictive);
@@ -448,6 +482,19 @@ impl MiniblockSealCommand {
             .await;
         progress.observe(user_l2_to_l1_log_count);
 
+        if let Some(call_traces_storage) = call_traces_storage {
+            // Call traces are persisted before committing the miniblock, so that the miniblock isn't committed
+            // if this fails. If the miniblock commit fails instead, the persisted traces are overwritten
+            // once the transactions are sealed again.
+            let progress =
+                MINIBLOCK_METRICS.start(MiniblockSealStage::InsertCallTraces, is_fictive);
+            call_traces_storage
+                .transactions_dal()
+                .insert_call_traces(&self.miniblock.executed_transactions)
+                .await;
+            progress.observe(self.miniblock.executed_transactions.len());
+        }
+
         let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::CommitMiniblock, is_fictive);
         let current_l2_virtual_block_info = transaction
             .storage_web3_dal()
@@ -10,9 +10,9 @@ use multivm::interface::{Halt, L1BatchEnv, SystemEnv};
 use tokio::sync::watch;
 use zksync_dal::{ConnectionPool, Core};
 use zksync_types::{
-    block::MiniblockExecutionData, l2::TransactionType, protocol_upgrade::ProtocolUpgradeTx,
-    protocol_version::ProtocolVersionId, storage_writes_deduplicator::StorageWritesDeduplicator,
-    L1BatchNumber, Transaction,
+    block::MiniblockExecutionData, helpers::unix_timestamp_ms, l2::TransactionType,
+    protocol_upgrade::ProtocolUpgradeTx, protocol_version::ProtocolVersionId,
+    storage_writes_deduplicator::StorageWritesDeduplicator, L1BatchNumber, Transaction,
 };
 
 use super::{
@@ -22,7 +22,7 @@ use super::{
         fee_address_migration, IoCursor, MiniblockParams, OutputHandler, PendingBatchData,
         StateKeeperIO,
     },
-    metrics::{AGGREGATION_METRICS, KEEPER_METRICS, L1_BATCH_METRICS},
+    metrics::{TxExecutionType, AGGREGATION_METRICS, KEEPER_METRICS, L1_BATCH_METRICS},
     seal_criteria::{ConditionalSealer, SealData, SealResolution},
     types::ExecutionMetricsForCriteria,
     updates::UpdatesManager,
@@ -32,6 +32,8 @@ use crate::gas_tracker::gas_count_from_writes;
 /// Amount of time to block on waiting for some resource. The exact value is not really important,
 /// we only need it to not block on waiting indefinitely and be able to process cancellation requests.
 pub(super) const POLL_WAIT_DURATION: Duration = Duration::from_secs(1);
+/// Seal criterion reported for L1 batches sealed as per [`IoSealCriteria`](super::seal_criteria::IoSealCriteria).
+pub(super) const UNCONDITIONAL_SEAL_CRITERION: &str = "unconditional";
 
 /// Structure used to indicate that task cancellation was requested.
 #[derive(thiserror::Error, Debug)]
@@ -86,19 +88,13 @@ impl ZkSyncStateKeeper {
         }
     }
 
-    /// Temporary method to migrate fee addresses from L1 batches to miniblocks.
+    /// Temporary method to migrate fee addresses from L1 batches to miniblocks. The returned future completes
+    /// once there are no more miniblocks to migrate, so it should be run as a task allowed to finish.
     pub fn run_fee_address_migration(
         &self,
         pool: ConnectionPool<Core>,
     ) -> impl Future<Output = anyhow::Result<()>> {
-        let mut stop_receiver = self.stop_receiver.clone();
-        async move {
-            fee_address_migration::migrate_miniblocks(pool, stop_receiver.clone()).await?;
-            // Since this is run as a task, we don't want it to exit on success (this would shut down the node).
-            // We still want for the task to be cancellation-aware, so we just wait until a stop signal is sent.
-            stop_receiver.changed().await.ok();
-            Ok(())
-        }
+        fee_address_migration::migrate_miniblocks(pool, self.stop_receiver.clone())
     }
 
     pub async fn run(mut self) -> anyhow::Result<()> {
@@ -276,8 +272,12 @@ impl ZkSyncStateKeeper {
             protocol_upgrade_tx = None; // The protocol upgrade was already executed
         }
 
-        if protocol_upgrade_tx.is_some() {
+        if let Some(tx) = &protocol_upgrade_tx {
             tracing::info!("There is a new upgrade tx to be executed in batch #{l1_batch_number}");
+            let latency = unix_timestamp_ms().saturating_sub(tx.received_timestamp_ms);
+            KEEPER_METRICS
+                .protocol_upgrade_tx_latency
+                .observe(Duration::from_millis(latency));
         }
         Ok(protocol_upgrade_tx)
     }
@@ -477,6 +477,7 @@ impl ZkSyncStateKeeper {
                     "L1 batch #{} should be sealed unconditionally as per sealing rules",
                     updates_manager.l1_batch.number
                 );
+                updates_manager.l1_batch.seal_criterion = Some(UNCONDITIONAL_SEAL_CRITERION);
                 return Ok(());
             }
 
@@ -538,6 +539,8 @@ impl ZkSyncStateKeeper {
                         l1_gas: tx_l1_gas_this_tx,
                         execution_metrics: tx_execution_metrics,
                     } = *tx_metrics;
+                    let tx_type = TxExecutionType::from_is_l1(tx.is_l1());
+                    KEEPER_METRICS.processed_transactions[&tx_type].inc();
                     updates_manager.extend_from_executed_transaction(
                         tx,
                         *tx_result,
@@ -641,8 +644,9 @@ impl ZkSyncStateKeeper {
     /// 1. The VM entered an incorrect state (e.g. out of gas). In that case, we must revert the transaction and seal
     /// the block.
     /// 2. Seal manager decided that batch is ready to be sealed.
-    /// Note: this method doesn't mutate `updates_manager` in the end. However, reference should be mutable
-    /// because we use `apply_and_rollback` method of `updates_manager.storage_writes_deduplicator`.
+    /// Note: this method doesn't mutate `updates_manager` in the end, except for recording the seal criterion
+    /// if the batch should be sealed. Besides that, reference should be mutable because we use
+    /// `apply_and_rollback` method of `updates_manager.storage_writes_deduplicator`.
     async fn process_one_tx(
         &mut self,
         batch_executor: &BatchExecutorHandle,
@@ -660,7 +664,7 @@ impl ZkSyncStateKeeper {
         // Otherwise, `ExcludeAndSeal` resolution is returned, i.e. batch will be sealed and transaction will be included in the next L1 batch.
 
         let is_first_tx = updates_manager.pending_executed_transactions_len() == 0;
-        let resolution = match &exec_result {
+        let (resolution, criterion) = match &exec_result {
             TxExecutionResult::BootloaderOutOfGasForTx
             | TxExecutionResult::RejectedByVm {
                 reason: Halt::NotEnoughGasProvided,
@@ -678,10 +682,10 @@ impl ZkSyncStateKeeper {
                     SealResolution::ExcludeAndSeal
                 };
                 AGGREGATION_METRICS.inc(error_message, &resolution);
-                resolution
+                (resolution, Some(error_message))
             }
             TxExecutionResult::RejectedByVm { reason } => {
-                SealResolution::Unexecutable(reason.to_string())
+                (SealResolution::Unexecutable(reason.to_string()), None)
             }
             TxExecutionResult::Success {
                 tx_result,
@@ -737,6 +741,7 @@ impl ZkSyncStateKeeper {
                     cumulative_size: encoding_len,
                     writes_metrics: tx_writes_metrics,
                     gas_remaining: *gas_remaining,
+                    miniblock_count: 0,
                 };
                 let block_data = SealData {
                     execution_metrics: tx_data.execution_metrics
@@ -748,6 +753,7 @@ impl ZkSyncStateKeeper {
                         + updates_manager.pending_txs_encoding_size(),
                     writes_metrics: block_writes_metrics,
                     gas_remaining: *gas_remaining,
+                    miniblock_count: updates_manager.pending_miniblocks_count(),
                 };
 
                 self.sealer.should_seal_l1_batch(
@@ -760,6 +766,9 @@ impl ZkSyncStateKeeper {
                 )
             }
         };
+        if resolution.should_seal() {
+            updates_manager.l1_batch.seal_criterion = criterion;
+        }
         (resolution, exec_result)
     }
 }
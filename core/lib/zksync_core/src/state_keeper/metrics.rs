@@ -44,6 +44,10 @@ const INCLUSION_DELAY_BUCKETS: Buckets = Buckets::values(&[
     2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 20.0, 30.0, 60.0, 120.0, 240.0,
 ]);
 
+const PROTOCOL_UPGRADE_TX_LATENCY_BUCKETS: Buckets = Buckets::values(&[
+    0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0, 300.0, 600.0, 1_800.0, 3_600.0, 21_600.0, 86_400.0,
+]);
+
 /// General-purpose state keeper metrics.
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "server_state_keeper")]
@@ -59,6 +63,9 @@ pub(crate) struct StateKeeperMetrics {
     pub get_tx_from_mempool: Histogram<Duration>,
     /// Number of transactions rejected by the state keeper.
     pub rejected_transactions: Counter,
+    /// Number of transactions included into miniblocks by the state keeper, split by the transaction type
+    /// (L1 priority operations vs L2 transactions).
+    pub processed_transactions: Family<TxExecutionType, Counter>,
     /// Time spent waiting for the hash of a previous L1 batch.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub wait_for_prev_hash_time: Histogram<Duration>,
@@ -75,6 +82,36 @@ pub(crate) struct StateKeeperMetrics {
     pub gas_price_too_high: Counter,
     /// Number of times blob base fee was reported as too high.
     pub blob_base_fee_too_high: Counter,
+    /// Latency between the moment a protocol upgrade transaction was received and the moment it was applied
+    /// by the state keeper. On the main node, the transaction is received from L1; on external nodes,
+    /// it is fetched from the main node.
+    #[metrics(buckets = PROTOCOL_UPGRADE_TX_LATENCY_BUCKETS)]
+    pub protocol_upgrade_tx_latency: Histogram<Duration>,
+}
+
+/// Returns the number of observations and their sum in seconds for [`StateKeeperMetrics::protocol_upgrade_tx_latency`].
+#[cfg(test)]
+pub(crate) fn protocol_upgrade_tx_latency_stats() -> (u64, f64) {
+    const METRIC_NAME: &str = "server_state_keeper_protocol_upgrade_tx_latency_seconds";
+
+    let registry = vise::MetricsCollection::default().collect();
+    let mut buffer = String::new();
+    registry
+        .encode(&mut buffer, vise::Format::OpenMetrics)
+        .unwrap();
+    let mut count = 0;
+    let mut sum = 0.0;
+    for line in buffer.lines() {
+        let Some((name, value)) = line.split_once(' ') else {
+            continue;
+        };
+        if name == format!("{METRIC_NAME}_count") {
+            count = value.parse().unwrap();
+        } else if name == format!("{METRIC_NAME}_sum") {
+            sum = value.parse().unwrap();
+        }
+    }
+    (count, sum)
 }
 
 #[vise::register]
@@ -266,6 +303,7 @@ pub(super) enum MiniblockSealStage {
     InsertL2ToL1Logs,
     CommitMiniblock,
     ReportTxMetrics,
+    InsertCallTraces,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet)]
@@ -389,6 +427,33 @@ pub(super) struct ExecutorMetrics {
 #[vise::register]
 pub(super) static EXECUTOR_METRICS: vise::Global<ExecutorMetrics> = vise::Global::new();
 
+/// Outcome of a single iteration of the state keeper RocksDB compaction task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "outcome", rename_all = "snake_case")]
+pub(super) enum CompactionOutcome {
+    /// Compaction was performed.
+    Completed,
+    /// Compaction was skipped because RocksDB is not initialized yet.
+    SkippedUninitialized,
+    /// Compaction was skipped because the miniblock seal queue is backed up.
+    SkippedBusy,
+}
+
+/// Metrics related to manual compaction of the state keeper RocksDB.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "state_keeper_rocksdb_compaction")]
+pub(super) struct RocksdbCompactionMetrics {
+    /// Number of compaction task iterations split by the outcome.
+    pub runs: Family<CompactionOutcome, Counter>,
+    /// Latency of a manual RocksDB compaction.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub latency: Histogram<Duration>,
+}
+
+#[vise::register]
+pub(super) static ROCKSDB_COMPACTION_METRICS: vise::Global<RocksdbCompactionMetrics> =
+    vise::Global::new();
+
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "batch_tip")]
 pub(crate) struct BatchTipMetrics {
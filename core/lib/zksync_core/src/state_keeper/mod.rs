@@ -1,5 +1,7 @@
 use std::sync::Arc;
 
+use anyhow::Context as _;
+use multivm::vm_latest::constants::{BLOCK_GAS_LIMIT, MAX_VM_PUBDATA_PER_BATCH};
 use tokio::sync::watch;
 use zksync_config::{
     configs::chain::{MempoolConfig, NetworkConfig, StateKeeperConfig},
@@ -10,13 +12,18 @@ use zksync_dal::{ConnectionPool, Core};
 pub use self::{
     batch_executor::{main_executor::MainBatchExecutor, BatchExecutor},
     io::{
-        mempool::MempoolIO, MiniblockSealerTask, OutputHandler, StateKeeperIO,
-        StateKeeperOutputHandler, StateKeeperPersistence,
+        fee_address_migration::ensure_miniblocks_migrated, mempool::MempoolIO, MiniblockSealerTask,
+        OutputHandler, SealQueueLoad, StateKeeperIO, StateKeeperOutputHandler,
+        StateKeeperPersistence,
     },
     keeper::ZkSyncStateKeeper,
     mempool_actor::MempoolFetcher,
     seal_criteria::SequencerSealer,
-    state_keeper_storage::{AsyncCatchupTask, AsyncRocksdbCache},
+    self_test::run_vm_self_test,
+    state_keeper_storage::{
+        AsyncCatchupTask, AsyncRocksdbCache, PostgresStorageFactory, ReadStorageFactory,
+        RocksdbCompactionTask, StateKeeperRocksdbOptions,
+    },
     types::MempoolGuard,
 };
 use crate::fee_model::BatchFeeModelInputProvider;
@@ -28,12 +35,29 @@ mod keeper;
 mod mempool_actor;
 pub(crate) mod metrics;
 pub mod seal_criteria;
+mod self_test;
 mod state_keeper_storage;
 #[cfg(test)]
 pub(crate) mod tests;
 pub(crate) mod types;
 pub(crate) mod updates;
 
+/// Checks that batch limits in the state keeper config don't exceed what the VM can handle.
+/// Otherwise, the state keeper could seal batches that would be rejected by the prover or L1.
+pub(crate) fn validate_batch_limits(config: &StateKeeperConfig) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        config.max_gas_per_batch <= BLOCK_GAS_LIMIT.into(),
+        "max_gas_per_batch ({}) exceeds the VM batch gas limit {BLOCK_GAS_LIMIT}",
+        config.max_gas_per_batch
+    );
+    anyhow::ensure!(
+        config.max_pubdata_per_batch <= MAX_VM_PUBDATA_PER_BATCH as u64,
+        "max_pubdata_per_batch ({}) exceeds the VM batch pubdata limit {MAX_VM_PUBDATA_PER_BATCH}",
+        config.max_pubdata_per_batch
+    );
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn create_state_keeper(
     state_keeper_config: StateKeeperConfig,
@@ -45,11 +69,16 @@ pub(crate) async fn create_state_keeper(
     batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     output_handler: OutputHandler,
     stop_receiver: watch::Receiver<bool>,
-) -> (ZkSyncStateKeeper, AsyncCatchupTask) {
+) -> anyhow::Result<(ZkSyncStateKeeper, AsyncCatchupTask)> {
+    validate_batch_limits(&state_keeper_config).context("invalid state keeper config")?;
+    let enum_index_migration_chunk_size = state_keeper_config
+        .enum_index_migration_chunk_size()
+        .context("invalid state keeper config")?;
     let (storage_factory, task) = AsyncRocksdbCache::new(
         pool.clone(),
         db_config.state_keeper_db_path.clone(),
-        state_keeper_config.enum_index_migration_chunk_size(),
+        StateKeeperRocksdbOptions::from_db_config(db_config),
+        enum_index_migration_chunk_size,
     );
     let batch_executor_base = MainBatchExecutor::new(
         Arc::new(storage_factory),
@@ -69,7 +98,7 @@ pub(crate) async fn create_state_keeper(
     .expect("Failed initializing main node I/O for state keeper");
 
     let sealer = SequencerSealer::new(state_keeper_config);
-    (
+    Ok((
         ZkSyncStateKeeper::new(
             stop_receiver,
             Box::new(io),
@@ -78,5 +107,5 @@ pub(crate) async fn create_state_keeper(
             Arc::new(sealer),
         ),
         task,
-    )
+    ))
 }
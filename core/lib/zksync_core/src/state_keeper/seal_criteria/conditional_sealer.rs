@@ -22,7 +22,8 @@ pub trait ConditionalSealer: 'static + fmt::Debug + Send + Sync {
         protocol_version: ProtocolVersionId,
     ) -> Option<&'static str>;
 
-    /// Returns the action that should be taken by the state keeper after executing a transaction.
+    /// Returns the action that should be taken by the state keeper after executing a transaction,
+    /// together with the name of the criterion that has determined this action (if any).
     fn should_seal_l1_batch(
         &self,
         l1_batch_number: u32,
@@ -31,7 +32,7 @@ pub trait ConditionalSealer: 'static + fmt::Debug + Send + Sync {
         block_data: &SealData,
         tx_data: &SealData,
         protocol_version: ProtocolVersionId,
-    ) -> SealResolution;
+    ) -> (SealResolution, Option<&'static str>);
 }
 
 /// Implementation of [`ConditionalSealer`] used by the main node.
@@ -78,7 +79,7 @@ impl ConditionalSealer for SequencerSealer {
         block_data: &SealData,
         tx_data: &SealData,
         protocol_version: ProtocolVersionId,
-    ) -> SealResolution {
+    ) -> (SealResolution, Option<&'static str>) {
         tracing::trace!(
             "Determining seal resolution for L1 batch #{l1_batch_number} with {tx_count} transactions \
              and metrics {:?}",
@@ -86,6 +87,7 @@ impl ConditionalSealer for SequencerSealer {
         );
 
         let mut final_seal_resolution = SealResolution::NoSeal;
+        let mut final_criterion = None;
         for sealer in &self.sealers {
             let seal_resolution = sealer.should_seal(
                 &self.config,
@@ -108,9 +110,13 @@ impl ConditionalSealer for SequencerSealer {
                 SealResolution::NoSeal => { /* Don't do anything */ }
             }
 
-            final_seal_resolution = final_seal_resolution.stricter(seal_resolution);
+            let stricter_resolution = final_seal_resolution.clone().stricter(seal_resolution);
+            if stricter_resolution != final_seal_resolution {
+                final_criterion = Some(sealer.prom_criterion_name());
+            }
+            final_seal_resolution = stricter_resolution;
         }
-        final_seal_resolution
+        (final_seal_resolution, final_criterion)
     }
 }
 
@@ -138,6 +144,7 @@ impl SequencerSealer {
             Box::new(criteria::CircuitsCriterion),
             Box::new(criteria::TxEncodingSizeCriterion),
             Box::new(criteria::GasForBatchTipCriterion),
+            Box::new(criteria::MiniblocksCriterion),
         ]
     }
 }
@@ -165,7 +172,7 @@ impl ConditionalSealer for NoopSealer {
         _block_data: &SealData,
         _tx_data: &SealData,
         _protocol_version: ProtocolVersionId,
-    ) -> SealResolution {
-        SealResolution::NoSeal
+    ) -> (SealResolution, Option<&'static str>) {
+        (SealResolution::NoSeal, None)
     }
 }
@@ -0,0 +1,82 @@
+use zksync_types::ProtocolVersionId;
+
+use crate::state_keeper::seal_criteria::{
+    SealCriterion, SealData, SealResolution, StateKeeperConfig,
+};
+
+/// Safety criterion sealing the batch once it contains too many miniblocks. The limit is expected to be set high
+/// enough so that it's not triggered during normal operation.
+#[derive(Debug)]
+pub struct MiniblocksCriterion;
+
+impl SealCriterion for MiniblocksCriterion {
+    fn should_seal(
+        &self,
+        config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        _tx_count: usize,
+        block_data: &SealData,
+        _tx_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> SealResolution {
+        if block_data.miniblock_count >= config.max_miniblocks_per_batch() {
+            SealResolution::IncludeAndSeal
+        } else {
+            SealResolution::NoSeal
+        }
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        "miniblocks"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_miniblocks_seal_criterion() {
+        let config = StateKeeperConfig {
+            max_miniblocks_per_batch: Some(3),
+            ..Default::default()
+        };
+        let criterion = MiniblocksCriterion;
+
+        let resolution_for_count = |miniblock_count| {
+            let block_data = SealData {
+                miniblock_count,
+                ..SealData::default()
+            };
+            criterion.should_seal(
+                &config,
+                Default::default(),
+                1,
+                &block_data,
+                &SealData::default(),
+                ProtocolVersionId::latest(),
+            )
+        };
+        assert_eq!(resolution_for_count(2), SealResolution::NoSeal);
+        assert_eq!(resolution_for_count(3), SealResolution::IncludeAndSeal);
+        assert_eq!(resolution_for_count(4), SealResolution::IncludeAndSeal);
+    }
+
+    #[test]
+    fn miniblocks_seal_criterion_with_default_limit() {
+        let config = StateKeeperConfig::default();
+        let block_data = SealData {
+            miniblock_count: 1_000,
+            ..SealData::default()
+        };
+        let resolution = MiniblocksCriterion.should_seal(
+            &config,
+            Default::default(),
+            1,
+            &block_data,
+            &SealData::default(),
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(resolution, SealResolution::NoSeal);
+    }
+}
@@ -1,12 +1,14 @@
 mod gas;
 mod gas_for_batch_tip;
 mod geometry_seal_criteria;
+mod miniblocks;
 mod pubdata_bytes;
 mod slots;
 mod tx_encoding_size;
 
 pub(in crate::state_keeper) use self::{
     gas::GasCriterion, gas_for_batch_tip::GasForBatchTipCriterion,
-    geometry_seal_criteria::CircuitsCriterion, pubdata_bytes::PubDataBytesCriterion,
-    slots::SlotsCriterion, tx_encoding_size::TxEncodingSizeCriterion,
+    geometry_seal_criteria::CircuitsCriterion, miniblocks::MiniblocksCriterion,
+    pubdata_bytes::PubDataBytesCriterion, slots::SlotsCriterion,
+    tx_encoding_size::TxEncodingSizeCriterion,
 };
@@ -10,7 +10,7 @@
 //! Maintaining all the criteria in one place has proven itself to be very error-prone,
 //! thus now every criterion is independent of the others.
 
-use std::fmt;
+use std::{fmt, time::Duration};
 
 use multivm::vm_latest::TransactionVmExt;
 use zksync_config::configs::chain::StateKeeperConfig;
@@ -82,6 +82,8 @@ pub struct SealData {
     pub(super) cumulative_size: usize,
     pub(super) writes_metrics: DeduplicatedWritesMetrics,
     pub(super) gas_remaining: u32,
+    /// Number of miniblocks (including the currently open one). Only meaningful for the entire L1 batch.
+    pub(super) miniblock_count: usize,
 }
 
 impl SealData {
@@ -102,6 +104,7 @@ impl SealData {
             cumulative_size: transaction.bootloader_encoding_size(),
             writes_metrics,
             gas_remaining: tx_metrics.gas_remaining,
+            miniblock_count: 0,
         }
     }
 }
@@ -177,13 +180,60 @@ impl IoSealCriteria for TimeoutSealer {
     }
 }
 
+/// Seals an L1 batch once the specified duration has elapsed since the first transaction in the batch, regardless
+/// of the batch fill level. Unlike [`TimeoutSealer`], the deadline is measured from the timestamp of the miniblock
+/// containing the first executed transaction rather than from the batch timestamp, so idle time before
+/// the first transaction isn't counted.
+///
+/// This is a building block for [`IoSealCriteria`] implementations; it isn't used by
+/// [`MempoolIO`](crate::state_keeper::MempoolIO). Doesn't influence miniblock sealing.
+#[derive(Debug, Clone, Copy)]
+pub struct FirstTxTimeoutSealer {
+    timeout: Duration,
+}
+
+impl FirstTxTimeoutSealer {
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+}
+
+impl IoSealCriteria for FirstTxTimeoutSealer {
+    fn should_seal_l1_batch_unconditionally(&mut self, manager: &UpdatesManager) -> bool {
+        const RULE_NAME: &str = "first_tx_timeout";
+
+        let Some(first_tx_timestamp) = manager.first_tx_miniblock_timestamp() else {
+            return false;
+        };
+
+        let should_seal_timeout =
+            u128::from(millis_since(first_tx_timestamp)) >= self.timeout.as_millis();
+        if should_seal_timeout {
+            AGGREGATION_METRICS.inc_criterion(RULE_NAME);
+            tracing::debug!(
+                "Decided to seal L1 batch #{} using rule `{RULE_NAME}`; first tx miniblock timestamp: {}, \
+                 timeout: {:?}",
+                manager.l1_batch.number,
+                extractors::display_timestamp(first_tx_timestamp),
+                self.timeout
+            );
+        }
+        should_seal_timeout
+    }
+
+    fn should_seal_miniblock(&mut self, _manager: &UpdatesManager) -> bool {
+        false
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use zksync_utils::time::seconds_since_epoch;
 
     use super::*;
-    use crate::state_keeper::tests::{
-        create_execution_result, create_transaction, create_updates_manager,
+    use crate::state_keeper::{
+        io::MiniblockParams,
+        tests::{create_execution_result, create_transaction, create_updates_manager},
     };
 
     fn apply_tx_to_manager(manager: &mut UpdatesManager) {
@@ -230,4 +280,44 @@ mod tests {
             "Non-empty miniblock with too recent timestamp shouldn't be sealed"
         );
     }
+
+    #[test]
+    fn first_tx_timeout_sealer() {
+        let mut sealer = FirstTxTimeoutSealer::new(Duration::from_secs(10));
+        let mut manager = create_updates_manager();
+        manager.miniblock.timestamp = seconds_since_epoch() - 20;
+        assert!(
+            !sealer.should_seal_l1_batch_unconditionally(&manager),
+            "Empty batch shouldn't be sealed"
+        );
+
+        // The timeout is measured from the first tx miniblock, so idle time before it doesn't count.
+        // This relies on the test not running for more than 10 seconds.
+        manager.push_miniblock(MiniblockParams {
+            timestamp: seconds_since_epoch(),
+            virtual_blocks: 1,
+        });
+        apply_tx_to_manager(&mut manager);
+        assert!(
+            !sealer.should_seal_l1_batch_unconditionally(&manager),
+            "Batch with recent first tx shouldn't be sealed"
+        );
+
+        manager.miniblock.timestamp = seconds_since_epoch() - 20;
+        assert!(
+            sealer.should_seal_l1_batch_unconditionally(&manager),
+            "Batch with old first tx should be sealed"
+        );
+
+        // The first tx miniblock timestamp should be retained after the miniblock is sealed.
+        manager.push_miniblock(MiniblockParams {
+            timestamp: seconds_since_epoch(),
+            virtual_blocks: 1,
+        });
+        apply_tx_to_manager(&mut manager);
+        assert!(
+            sealer.should_seal_l1_batch_unconditionally(&manager),
+            "Batch with old first tx should be sealed"
+        );
+    }
 }
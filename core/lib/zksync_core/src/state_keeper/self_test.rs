@@ -0,0 +1,184 @@
+//! VM self-test ensuring that base system contracts are consistent and can be used to execute transactions.
+
+use anyhow::Context as _;
+use multivm::{
+    interface::{L1BatchEnv, L2BlockEnv, SystemEnv, TxExecutionMode},
+    vm_latest::constants::BLOCK_GAS_LIMIT,
+};
+use zksync_contracts::{BaseSystemContracts, SystemContractCode};
+use zksync_state::InMemoryStorage;
+use zksync_system_constants::{REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, ZKPORTER_IS_AVAILABLE};
+use zksync_types::{
+    block::MiniblockHasher,
+    fee_model::{BatchFeeInput, PubdataIndependentBatchFeeModelInput},
+    l1::{OpProcessingType, PriorityQueueType},
+    Address, Execute, ExecuteTransactionCommon, L1BatchNumber, L1TxCommonData, L2ChainId,
+    MiniblockNumber, PriorityOpId, ProtocolVersionId, Transaction, H256, U256,
+};
+use zksync_utils::{be_words_to_bytes, bytecode::hash_bytecode};
+
+use super::{batch_executor::TxExecutionResult, MainBatchExecutor};
+
+/// Checks that the provided base system contracts are internally consistent and runs a trivial transaction
+/// through [`MainBatchExecutor`] using them. The transaction is executed on top of an in-memory storage
+/// containing only system contracts, so the check doesn't touch Postgres or RocksDB.
+///
+/// # Errors
+///
+/// Returns an error if bytecode hashes of the contracts are inconsistent, or if the VM fails to execute
+/// the transaction (including the case when the VM panics).
+pub async fn run_vm_self_test(
+    base_system_contracts: BaseSystemContracts,
+    chain_id: L2ChainId,
+) -> anyhow::Result<()> {
+    check_bytecode_hash("bootloader", &base_system_contracts.bootloader)?;
+    check_bytecode_hash("default account", &base_system_contracts.default_aa)?;
+
+    // The VM may panic on malformed contracts, so we run execution in a separate task to catch panics.
+    tokio::spawn(execute_test_transaction(base_system_contracts, chain_id))
+        .await
+        .context("VM panicked executing test transaction")??;
+    tracing::info!("VM self-test passed");
+    Ok(())
+}
+
+fn check_bytecode_hash(name: &str, contract: &SystemContractCode) -> anyhow::Result<()> {
+    let actual_hash = hash_bytecode(&be_words_to_bytes(&contract.code));
+    anyhow::ensure!(
+        actual_hash == contract.hash,
+        "Hash of {name} bytecode ({actual_hash:?}) differs from the expected one ({:?}); \
+         the bytecode is probably corrupted",
+        contract.hash
+    );
+    Ok(())
+}
+
+async fn execute_test_transaction(
+    base_system_contracts: BaseSystemContracts,
+    chain_id: L2ChainId,
+) -> anyhow::Result<()> {
+    let storage = InMemoryStorage::with_system_contracts_and_chain_id(chain_id, hash_bytecode);
+    let system_env = SystemEnv {
+        zk_porter_available: ZKPORTER_IS_AVAILABLE,
+        version: ProtocolVersionId::latest(),
+        base_system_smart_contracts: base_system_contracts,
+        gas_limit: BLOCK_GAS_LIMIT,
+        execution_mode: TxExecutionMode::VerifyExecute,
+        default_validation_computational_gas_limit: BLOCK_GAS_LIMIT,
+        chain_id,
+    };
+    let l1_batch_env = L1BatchEnv {
+        previous_batch_hash: None,
+        number: L1BatchNumber(1),
+        timestamp: 1,
+        fee_account: Address::repeat_byte(0x01),
+        enforced_base_fee: None,
+        first_l2_block: L2BlockEnv {
+            number: 1,
+            timestamp: 1,
+            prev_block_hash: MiniblockHasher::legacy_hash(MiniblockNumber(0)),
+            max_virtual_blocks_to_create: 1,
+        },
+        fee_input: BatchFeeInput::PubdataIndependent(PubdataIndependentBatchFeeModelInput {
+            fair_l2_gas_price: 1,
+            fair_pubdata_price: 1,
+            l1_gas_price: 1,
+        }),
+    };
+
+    let executor = MainBatchExecutor::init_sandboxed_batch(storage, l1_batch_env, system_env);
+    match executor.execute_tx(test_transaction()).await {
+        TxExecutionResult::Success { tx_result, .. } => {
+            anyhow::ensure!(
+                !tx_result.result.is_failed(),
+                "Test transaction failed: {:?}",
+                tx_result.result
+            );
+        }
+        TxExecutionResult::RejectedByVm { reason } => {
+            anyhow::bail!("Test transaction was rejected by VM: {reason}");
+        }
+        TxExecutionResult::BootloaderOutOfGasForTx => {
+            anyhow::bail!("Bootloader is out of gas for test transaction");
+        }
+    }
+
+    let finished_batch = executor.finish_batch().await;
+    let tip_result = &finished_batch.block_tip_execution_result.result;
+    anyhow::ensure!(
+        !tip_result.is_failed(),
+        "Failed sealing batch with test transaction: {tip_result:?}"
+    );
+    Ok(())
+}
+
+/// Creates a priority operation calling an empty account. Priority operations are used since they don't require
+/// signing and funding the sender account.
+fn test_transaction() -> Transaction {
+    let sender = Address::repeat_byte(0x02);
+    let gas_limit = U256::from(20_000_000);
+    let execute = Execute {
+        contract_address: Address::repeat_byte(0x03),
+        calldata: vec![],
+        value: U256::zero(),
+        factory_deps: None,
+    };
+    let common_data = L1TxCommonData {
+        sender,
+        serial_id: PriorityOpId(0),
+        deadline_block: 0,
+        layer_2_tip_fee: U256::zero(),
+        full_fee: U256::zero(),
+        max_fee_per_gas: U256::zero(),
+        gas_limit,
+        gas_per_pubdata_limit: REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE.into(),
+        op_processing_type: OpProcessingType::Common,
+        priority_queue_type: PriorityQueueType::Deque,
+        eth_hash: H256::zero(),
+        eth_block: 0,
+        canonical_tx_hash: H256::from_low_u64_be(1),
+        to_mint: U256::zero(),
+        refund_recipient: sender,
+    };
+    Transaction {
+        common_data: ExecuteTransactionCommon::L1(common_data),
+        execute,
+        received_timestamp_ms: 0,
+        raw_bytes: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn self_test_with_valid_contracts() {
+        run_vm_self_test(BaseSystemContracts::load_from_disk(), L2ChainId::default())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn self_test_with_corrupted_bytecode() {
+        let mut contracts = BaseSystemContracts::load_from_disk();
+        contracts.bootloader.code[1] += U256::one();
+
+        let err = run_vm_self_test(contracts, L2ChainId::default())
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("bootloader"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn self_test_with_mismatched_contracts() {
+        // Bytecode hashes are consistent, but the bootloader cannot execute transactions.
+        let mut contracts = BaseSystemContracts::load_from_disk();
+        contracts.bootloader = contracts.default_aa.clone();
+
+        run_vm_self_test(contracts, L2ChainId::default())
+            .await
+            .unwrap_err();
+    }
+}
@@ -1,16 +1,22 @@
-use std::{fmt::Debug, sync::Arc};
+use std::{fmt::Debug, num::NonZeroU32, sync::Arc, time::Duration};
 
 use anyhow::Context;
 use async_trait::async_trait;
 use once_cell::sync::OnceCell;
 use tokio::{runtime::Handle, sync::watch};
+use zksync_config::configs::database::{DBConfig, RocksdbCompactionStyle};
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_state::{
     PostgresStorage, ReadStorage, RocksdbStorage, RocksdbStorageBuilder, StateKeeperColumnFamily,
 };
-use zksync_storage::RocksDB;
+use zksync_storage::{CompactionStyle, RocksDB, RocksDBMemoryBudget, RocksDBOptions};
 use zksync_types::{L1BatchNumber, MiniblockNumber};
 
+use super::{
+    io::SealQueueLoad,
+    metrics::{CompactionOutcome, ROCKSDB_COMPACTION_METRICS},
+};
+
 /// Factory that can produce a [`ReadStorage`] implementation on demand.
 #[async_trait]
 pub trait ReadStorageFactory: Debug + Send + Sync + 'static {
@@ -70,6 +76,49 @@ impl<'a> From<RocksdbStorage> for PgOrRocksdbStorage<'a> {
     }
 }
 
+/// Tuning options for the state keeper RocksDB. All options are unset by default, meaning that
+/// the default RocksDB configuration is used.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateKeeperRocksdbOptions {
+    /// Byte size of a single write buffer (memtable).
+    pub write_buffer_size: Option<usize>,
+    /// Maximum number of concurrent background jobs (compactions and flushes).
+    pub max_background_jobs: Option<NonZeroU32>,
+    /// Compaction style.
+    pub compaction_style: Option<RocksdbCompactionStyle>,
+    /// Memory budget shared with other RocksDB instances (e.g., the Merkle tree).
+    pub memory_budget: Option<RocksDBMemoryBudget>,
+    /// Whether to collect RocksDB statistics (e.g., IO metrics).
+    pub collect_statistics: bool,
+}
+
+impl StateKeeperRocksdbOptions {
+    /// Extracts options from the main node database config.
+    pub fn from_db_config(config: &DBConfig) -> Self {
+        Self {
+            write_buffer_size: config.state_keeper_db_write_buffer_size(),
+            max_background_jobs: config.state_keeper_db_max_background_jobs,
+            compaction_style: config.state_keeper_db_compaction_style,
+            memory_budget: None,
+            collect_statistics: config.state_keeper_db_statistics_enabled,
+        }
+    }
+
+    fn to_rocksdb_options(self) -> RocksDBOptions {
+        RocksDBOptions {
+            write_buffer_size: self.write_buffer_size,
+            max_background_jobs: self.max_background_jobs,
+            compaction_style: self.compaction_style.map(|style| match style {
+                RocksdbCompactionStyle::Level => CompactionStyle::Level,
+                RocksdbCompactionStyle::Universal => CompactionStyle::Universal,
+            }),
+            memory_budget: self.memory_budget,
+            collect_statistics: self.collect_statistics,
+            ..RocksDBOptions::default()
+        }
+    }
+}
+
 /// A [`ReadStorageFactory`] implementation that can produce short-lived [`ReadStorage`] handles
 /// backed by either Postgres or RocksDB (if it's caught up). Always initialized as a `Postgres`
 /// variant and is then mutated into `Rocksdb` once RocksDB cache is caught up. After which it
@@ -182,17 +231,33 @@ impl AsyncRocksdbCache {
     pub fn new(
         pool: ConnectionPool<Core>,
         state_keeper_db_path: String,
+        state_keeper_db_options: StateKeeperRocksdbOptions,
         enum_index_migration_chunk_size: usize,
     ) -> (Self, AsyncCatchupTask) {
         let rocksdb_cell = Arc::new(OnceCell::new());
         let task = AsyncCatchupTask {
             pool: pool.clone(),
             state_keeper_db_path,
+            state_keeper_db_options,
             enum_index_migration_chunk_size,
             rocksdb_cell: rocksdb_cell.clone(),
         };
         (Self { pool, rocksdb_cell }, task)
     }
+
+    /// Creates a task periodically triggering manual compaction of the underlying RocksDB instance with
+    /// the specified interval. Compaction is skipped if the miniblock seal queue is backed up.
+    pub fn compaction_task(
+        &self,
+        interval: Duration,
+        seal_queue: SealQueueLoad,
+    ) -> RocksdbCompactionTask {
+        RocksdbCompactionTask {
+            rocksdb_cell: self.rocksdb_cell.clone(),
+            interval,
+            seal_queue,
+        }
+    }
 }
 
 #[async_trait]
@@ -205,10 +270,38 @@ impl ReadStorageFactory for AsyncRocksdbCache {
     }
 }
 
+/// A [`ReadStorageFactory`] implementation producing [`ReadStorage`] handles backed solely by Postgres.
+/// This is slower than [`AsyncRocksdbCache`], but doesn't require a RocksDB instance, which simplifies
+/// operating nodes in constrained environments (e.g., ephemeral nodes in CI).
+#[derive(Debug, Clone)]
+pub struct PostgresStorageFactory {
+    pool: ConnectionPool<Core>,
+}
+
+impl PostgresStorageFactory {
+    pub fn new(pool: ConnectionPool<Core>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ReadStorageFactory for PostgresStorageFactory {
+    async fn access_storage(
+        &self,
+        _stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<Option<PgOrRocksdbStorage<'_>>> {
+        let storage = AsyncRocksdbCache::access_storage_pg(&self.pool)
+            .await
+            .context("Failed accessing Postgres storage")?;
+        Ok(Some(storage))
+    }
+}
+
 #[derive(Debug)]
 pub struct AsyncCatchupTask {
     pool: ConnectionPool<Core>,
     state_keeper_db_path: String,
+    state_keeper_db_options: StateKeeperRocksdbOptions,
     enum_index_migration_chunk_size: usize,
     rocksdb_cell: Arc<OnceCell<RocksDB<StateKeeperColumnFamily>>>,
 }
@@ -216,10 +309,12 @@ pub struct AsyncCatchupTask {
 impl AsyncCatchupTask {
     pub async fn run(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         tracing::debug!("Catching up RocksDB asynchronously");
-        let mut rocksdb_builder: RocksdbStorageBuilder =
-            RocksdbStorage::builder(self.state_keeper_db_path.as_ref())
-                .await
-                .context("Failed initializing RocksDB storage")?;
+        let mut rocksdb_builder: RocksdbStorageBuilder = RocksdbStorage::builder_with_options(
+            self.state_keeper_db_path.as_ref(),
+            self.state_keeper_db_options.to_rocksdb_options(),
+        )
+        .await
+        .context("Failed initializing RocksDB storage")?;
         rocksdb_builder.enable_enum_index_migration(self.enum_index_migration_chunk_size);
         let mut connection = self
             .pool
@@ -241,3 +336,141 @@ impl AsyncCatchupTask {
         Ok(())
     }
 }
+
+/// Task periodically triggering manual compaction of the state keeper RocksDB. Compaction removes tombstones
+/// accumulated in the DB, which can degrade read performance over time.
+#[derive(Debug)]
+pub struct RocksdbCompactionTask {
+    rocksdb_cell: Arc<OnceCell<RocksDB<StateKeeperColumnFamily>>>,
+    interval: Duration,
+    seal_queue: SealQueueLoad,
+}
+
+impl RocksdbCompactionTask {
+    async fn try_compact(&self) -> anyhow::Result<CompactionOutcome> {
+        let Some(rocksdb) = self.rocksdb_cell.get() else {
+            tracing::debug!("State keeper RocksDB is not initialized yet; skipping compaction");
+            return Ok(CompactionOutcome::SkippedUninitialized);
+        };
+        let pending_commands = self.seal_queue.pending_commands();
+        if pending_commands > 0 {
+            tracing::info!(
+                "Miniblock seal queue has {pending_commands} pending command(s); skipping state keeper RocksDB compaction"
+            );
+            return Ok(CompactionOutcome::SkippedBusy);
+        }
+
+        tracing::info!("Starting state keeper RocksDB compaction");
+        let rocksdb = rocksdb.clone();
+        let latency = ROCKSDB_COMPACTION_METRICS.latency.start();
+        tokio::task::spawn_blocking(move || rocksdb.compact())
+            .await
+            .context("panicked compacting state keeper RocksDB")?;
+        let elapsed = latency.observe();
+        tracing::info!("Compacted state keeper RocksDB in {elapsed:?}");
+        Ok(CompactionOutcome::Completed)
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::info!(
+            "Starting state keeper RocksDB compaction task with interval {:?}",
+            self.interval
+        );
+        loop {
+            if tokio::time::timeout(self.interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+            let outcome = self.try_compact().await?;
+            ROCKSDB_COMPACTION_METRICS.runs[&outcome].inc();
+        }
+        tracing::info!(
+            "Stop signal received, state keeper RocksDB compaction task is shutting down"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+    use zksync_types::Address;
+
+    use super::*;
+    use crate::{
+        genesis::{insert_genesis_batch, GenesisParams},
+        state_keeper::{
+            io::StateKeeperOutputHandler, tests::create_updates_manager, StateKeeperPersistence,
+        },
+    };
+
+    #[tokio::test]
+    async fn compaction_task_skips_compaction_if_seal_queue_is_backed_up() {
+        let pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().to_str().unwrap().to_owned();
+        let (cache, _catchup_task) = AsyncRocksdbCache::new(
+            pool.clone(),
+            db_path,
+            StateKeeperRocksdbOptions::default(),
+            10,
+        );
+        // The sealer task is not run, so submitted miniblocks will stay in the seal queue.
+        let (mut persistence, _sealer) = StateKeeperPersistence::new(pool, Address::default(), 5);
+        let task = cache.compaction_task(Duration::from_secs(60), persistence.seal_queue_load());
+
+        let outcome = task.try_compact().await.unwrap();
+        assert_eq!(outcome, CompactionOutcome::SkippedUninitialized);
+
+        let rocksdb = RocksDB::new(temp_dir.path()).unwrap();
+        cache.rocksdb_cell.set(rocksdb).unwrap();
+        let outcome = task.try_compact().await.unwrap();
+        assert_eq!(outcome, CompactionOutcome::Completed);
+
+        let updates_manager = create_updates_manager();
+        persistence
+            .handle_miniblock(&updates_manager)
+            .await
+            .unwrap();
+        assert_eq!(persistence.seal_queue_load().pending_commands(), 1);
+        let outcome = task.try_compact().await.unwrap();
+        assert_eq!(outcome, CompactionOutcome::SkippedBusy);
+    }
+
+    #[tokio::test]
+    async fn catchup_task_opens_rocksdb_with_configured_options() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+        drop(storage);
+
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().to_str().unwrap().to_owned();
+        let options = StateKeeperRocksdbOptions {
+            write_buffer_size: Some(16 << 20),
+            max_background_jobs: NonZeroU32::new(2),
+            compaction_style: Some(RocksdbCompactionStyle::Universal),
+            memory_budget: Some(RocksDBMemoryBudget::new(64 << 20, 2)),
+            collect_statistics: true,
+        };
+        // Applying `RocksDBOptions` is tested in the storage crate; here, we only check the options mapping.
+        let rocksdb_options = options.clone().to_rocksdb_options();
+        assert_eq!(rocksdb_options.write_buffer_size, Some(16 << 20));
+        assert_eq!(rocksdb_options.max_background_jobs, NonZeroU32::new(2));
+        assert_eq!(
+            rocksdb_options.compaction_style,
+            Some(CompactionStyle::Universal)
+        );
+        assert!(rocksdb_options.memory_budget.is_some());
+        assert!(rocksdb_options.collect_statistics);
+
+        let (cache, catchup_task) = AsyncRocksdbCache::new(pool, db_path, options, 10);
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        catchup_task.run(stop_receiver).await.unwrap();
+        assert!(cache.rocksdb_cell.get().is_some());
+    }
+}
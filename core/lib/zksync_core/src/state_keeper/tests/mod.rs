@@ -1,20 +1,27 @@
 use std::{
+    collections::HashMap,
+    fmt,
     sync::{
         atomic::{AtomicBool, AtomicU64, Ordering},
-        Arc,
+        Arc, Mutex,
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
 
+use assert_matches::assert_matches;
 use multivm::{
     interface::{
         CurrentExecutionState, ExecutionResult, FinishedL1Batch, L1BatchEnv, L2BlockEnv, Refunds,
         SystemEnv, TxExecutionMode, VmExecutionResultAndLogs, VmExecutionStatistics,
     },
-    vm_latest::{constants::BLOCK_GAS_LIMIT, VmExecutionLogs},
+    vm_latest::{
+        constants::{BLOCK_GAS_LIMIT, MAX_VM_PUBDATA_PER_BATCH},
+        VmExecutionLogs,
+    },
 };
 use once_cell::sync::Lazy;
 use tokio::sync::watch;
+use tracing_subscriber::{layer::SubscriberExt, Layer};
 use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_contracts::BaseSystemContracts;
 use zksync_system_constants::ZKPORTER_IS_AVAILABLE;
@@ -22,6 +29,7 @@ use zksync_types::{
     aggregated_operations::AggregatedActionType,
     block::{BlockGasCount, MiniblockExecutionData, MiniblockHasher},
     fee_model::{BatchFeeInput, PubdataIndependentBatchFeeModelInput},
+    helpers::unix_timestamp_ms,
     tx::tx_execution_info::ExecutionMetrics,
     zk_evm_types::{LogQuery, Timestamp},
     Address, L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId, StorageLogQuery,
@@ -31,22 +39,24 @@ use zksync_types::{
 mod tester;
 
 use self::tester::{
-    pending_batch_data, random_tx, random_upgrade_tx, rejected_exec, successful_exec,
-    successful_exec_with_metrics, TestIO, TestScenario,
+    pending_batch_data, random_l1_tx, random_tx, rejected_exec, successful_exec,
+    successful_exec_with_metrics, IoCall, TestIO, TestScenario,
 };
-pub(crate) use self::tester::{MockBatchExecutor, TestBatchExecutorBuilder};
+pub(crate) use self::tester::{random_upgrade_tx, MockBatchExecutor, TestBatchExecutorBuilder};
 use crate::{
     gas_tracker::l1_batch_base_cost,
     state_keeper::{
-        batch_executor::TxExecutionResult,
-        keeper::POLL_WAIT_DURATION,
+        batch_executor::{BatchExecutor, TxExecutionResult},
+        io::output_handler::L1BatchSealedEvent,
+        keeper::{POLL_WAIT_DURATION, UNCONDITIONAL_SEAL_CRITERION},
+        metrics::{protocol_upgrade_tx_latency_stats, TxExecutionType, KEEPER_METRICS},
         seal_criteria::{
-            criteria::{GasCriterion, SlotsCriterion},
-            SequencerSealer,
+            criteria::{GasCriterion, MiniblocksCriterion, SlotsCriterion},
+            FirstTxTimeoutSealer, IoSealCriteria, SequencerSealer,
         },
         types::ExecutionMetricsForCriteria,
         updates::UpdatesManager,
-        ZkSyncStateKeeper,
+        validate_batch_limits, ZkSyncStateKeeper,
     },
     utils::testonly::create_l2_transaction,
 };
@@ -198,6 +208,31 @@ impl Query {
     }
 }
 
+#[test]
+fn validating_batch_limits() {
+    let mut config = StateKeeperConfig::for_tests();
+    validate_batch_limits(&config).unwrap();
+    config.max_gas_per_batch = BLOCK_GAS_LIMIT.into();
+    config.max_pubdata_per_batch = MAX_VM_PUBDATA_PER_BATCH as u64;
+    validate_batch_limits(&config).unwrap();
+
+    let mut invalid_config = config.clone();
+    invalid_config.max_gas_per_batch += 1;
+    let err = validate_batch_limits(&invalid_config)
+        .unwrap_err()
+        .to_string();
+    assert!(err.starts_with("max_gas_per_batch"), "{err}");
+    assert!(err.contains(&BLOCK_GAS_LIMIT.to_string()), "{err}");
+
+    let mut invalid_config = config;
+    invalid_config.max_pubdata_per_batch += 1;
+    let err = validate_batch_limits(&invalid_config)
+        .unwrap_err()
+        .to_string();
+    assert!(err.starts_with("max_pubdata_per_batch"), "{err}");
+    assert!(err.contains(&MAX_VM_PUBDATA_PER_BATCH.to_string()), "{err}");
+}
+
 #[tokio::test]
 async fn sealed_by_number_of_txs() {
     let config = StateKeeperConfig {
@@ -217,6 +252,146 @@ async fn sealed_by_number_of_txs() {
         .await;
 }
 
+/// Fields of a recorded `tracing` event.
+type EventFields = HashMap<&'static str, String>;
+
+/// `tracing` layer recording all events with a message starting with the specified prefix.
+#[derive(Debug, Clone)]
+struct RecordingLayer {
+    message_prefix: &'static str,
+    events: Arc<Mutex<Vec<EventFields>>>,
+}
+
+impl RecordingLayer {
+    fn new(message_prefix: &'static str) -> Self {
+        Self {
+            message_prefix,
+            events: Arc::default(),
+        }
+    }
+
+    fn take_events(&self) -> Vec<EventFields> {
+        std::mem::take(&mut *self.events.lock().unwrap())
+    }
+}
+
+impl<S: tracing::Subscriber> Layer<S> for RecordingLayer {
+    fn on_event(
+        &self,
+        event: &tracing::Event<'_>,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        struct FieldsVisitor(EventFields);
+
+        impl tracing::field::Visit for FieldsVisitor {
+            fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+                self.0.insert(field.name(), value.to_owned());
+            }
+
+            fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn fmt::Debug) {
+                self.0.insert(field.name(), format!("{value:?}"));
+            }
+        }
+
+        let mut visitor = FieldsVisitor(EventFields::new());
+        event.record(&mut visitor);
+        let is_matching = visitor
+            .0
+            .get("message")
+            .map_or(false, |message| message.starts_with(self.message_prefix));
+        if is_matching {
+            self.events.lock().unwrap().push(visitor.0);
+        }
+    }
+}
+
+#[tokio::test]
+async fn sealed_l1_batch_event_contains_seal_metadata() {
+    let recording_layer = RecordingLayer::new("Sealed L1 batch");
+    let subscriber = tracing_subscriber::registry().with(recording_layer.clone());
+    // The test runtime is single-threaded, so the state keeper task will use this subscriber as well.
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let config = StateKeeperConfig {
+        transaction_slots: 2,
+        ..StateKeeperConfig::default()
+    };
+    let sealer = SequencerSealer::with_sealers(config, vec![Box::new(SlotsCriterion)]);
+    let execution_result = successful_exec_with_metrics(ExecutionMetricsForCriteria {
+        l1_gas: BlockGasCount::default(),
+        execution_metrics: ExecutionMetrics {
+            gas_used: 1_000,
+            pubdata_published: 100,
+            ..ExecutionMetrics::default()
+        },
+    });
+
+    TestScenario::new()
+        .seal_miniblock_when(|updates| updates.miniblock.executed_transactions.len() == 1)
+        .next_tx("First tx", random_tx(1), execution_result.clone())
+        .miniblock_sealed("Miniblock 1")
+        .next_tx("Second tx", random_tx(2), execution_result)
+        .miniblock_sealed("Miniblock 2")
+        .batch_sealed_with("Batch 1", |updates| {
+            let event = L1BatchSealedEvent::new(updates);
+            assert_eq!(
+                event,
+                L1BatchSealedEvent {
+                    l1_batch_number: L1BatchNumber(1),
+                    // Includes the fictive miniblock.
+                    miniblocks: MiniblockNumber(1)..=MiniblockNumber(3),
+                    tx_count: 2,
+                    gas_used: 2_000,
+                    pubdata_published: 200,
+                    seal_criterion: Some("slots"),
+                }
+            );
+        })
+        .run(sealer)
+        .await;
+    let events = recording_layer.take_events();
+    assert_eq!(events.len(), 1, "{events:?}");
+    let event = &events[0];
+    let expected_fields = [
+        ("message", "Sealed L1 batch #1"),
+        ("l1_batch_number", "1"),
+        ("first_miniblock", "1"),
+        ("last_miniblock", "3"),
+        ("tx_count", "2"),
+        ("gas_used", "2000"),
+        ("pubdata_published", "200"),
+        ("seal_criterion", "slots"),
+    ];
+    for (name, expected_value) in expected_fields {
+        assert_eq!(event[name], expected_value, "{event:?}");
+    }
+}
+
+#[tokio::test]
+async fn sealed_by_number_of_miniblocks() {
+    let config = StateKeeperConfig {
+        max_miniblocks_per_batch: Some(2),
+        ..StateKeeperConfig::default()
+    };
+    let sealer = SequencerSealer::with_sealers(config, vec![Box::new(MiniblocksCriterion)]);
+
+    TestScenario::new()
+        .seal_miniblock_when(|updates| updates.miniblock.executed_transactions.len() == 1)
+        .next_tx("First tx", random_tx(1), successful_exec())
+        .miniblock_sealed("Miniblock 1")
+        .next_tx("Second tx", random_tx(2), successful_exec())
+        .miniblock_sealed("Miniblock 2")
+        .batch_sealed_with("Batch 1", |updates| {
+            // Both miniblocks with txs are sealed; the open fictive miniblock is not counted.
+            assert_eq!(updates.l1_batch.miniblock_count, 2);
+            assert_eq!(updates.l1_batch.executed_transactions.len(), 2);
+        })
+        .next_tx("Third tx", random_tx(3), successful_exec())
+        .miniblock_sealed("Miniblock 3")
+        .run(sealer)
+        .await;
+}
+
 #[tokio::test]
 async fn sealed_by_gas() {
     let config = StateKeeperConfig {
@@ -318,13 +493,7 @@ async fn batch_sealed_before_miniblock_does() {
         .seal_miniblock_when(|updates| updates.miniblock.executed_transactions.len() == 3)
         .next_tx("First tx", random_tx(1), successful_exec())
         .next_tx("Second tx", random_tx(2), successful_exec())
-        .miniblock_sealed_with("Miniblock with two txs", |updates| {
-            assert_eq!(
-                updates.miniblock.executed_transactions.len(),
-                2,
-                "The miniblock should have 2 txs"
-            );
-        })
+        .miniblock_sealed_with_tx_count("Miniblock with two txs", 2)
         .batch_sealed("Batch 1")
         .run(sealer)
         .await;
@@ -422,13 +591,7 @@ async fn pending_batch_is_applied() {
         .seal_miniblock_when(|updates| updates.miniblock.executed_transactions.len() == 1)
         .load_pending_batch(pending_batch)
         .next_tx("Final tx of batch", random_tx(3), successful_exec())
-        .miniblock_sealed_with("Miniblock with a single tx", |updates| {
-            assert_eq!(
-                updates.miniblock.executed_transactions.len(),
-                1,
-                "Only one transaction should be in miniblock"
-            );
-        })
+        .miniblock_sealed_with_tx_count("Miniblock with a single tx", 1)
         .batch_sealed_with("Batch sealed with all 3 txs", |updates| {
             assert_eq!(
                 updates.l1_batch.executed_transactions.len(),
@@ -482,6 +645,67 @@ async fn load_upgrade_tx() {
     // we should load the upgrade transaction -- that's the `SetChainIdUpgrade`.
 }
 
+#[tokio::test]
+async fn upgrade_tx_latency_is_reported() {
+    const UPGRADE_TX_AGE: Duration = Duration::from_secs(600);
+
+    let scenario = TestScenario::new();
+    let batch_executor_base = TestBatchExecutorBuilder::new(&scenario);
+    let (stop_sender, stop_receiver) = watch::channel(false);
+
+    let (mut io, output_handler) = TestIO::new(stop_sender, scenario);
+    let mut upgrade_tx = random_upgrade_tx(1);
+    upgrade_tx.received_timestamp_ms = unix_timestamp_ms() - UPGRADE_TX_AGE.as_millis() as u64;
+    io.add_upgrade_tx(ProtocolVersionId::next(), upgrade_tx.clone());
+
+    let mut sk = ZkSyncStateKeeper::new(
+        stop_receiver,
+        Box::new(io),
+        Box::new(batch_executor_base),
+        output_handler,
+        Arc::new(SequencerSealer::default()),
+    );
+    // Other tests may observe the metric concurrently, so we only check that it has grown sufficiently.
+    let (count_before, sum_before) = protocol_upgrade_tx_latency_stats();
+    let loaded_tx = sk
+        .load_protocol_upgrade_tx(&[], ProtocolVersionId::next(), L1BatchNumber(2))
+        .await
+        .unwrap();
+    assert_eq!(loaded_tx, Some(upgrade_tx));
+
+    let (count_after, sum_after) = protocol_upgrade_tx_latency_stats();
+    assert!(count_after > count_before);
+    let latency = sum_after - sum_before;
+    assert!(latency >= UPGRADE_TX_AGE.as_secs_f64(), "{latency}");
+}
+
+#[tokio::test]
+async fn processed_transactions_are_counted_by_type() {
+    let processed_l1_txs = &KEEPER_METRICS.processed_transactions[&TxExecutionType::L1];
+    let processed_l2_txs = &KEEPER_METRICS.processed_transactions[&TxExecutionType::L2];
+    let (l1_txs_before, l2_txs_before) = (processed_l1_txs.get(), processed_l2_txs.get());
+
+    let config = StateKeeperConfig {
+        transaction_slots: 3,
+        ..StateKeeperConfig::default()
+    };
+    let sealer = SequencerSealer::with_sealers(config, vec![Box::new(SlotsCriterion)]);
+
+    TestScenario::new()
+        .seal_miniblock_when(|updates| updates.miniblock.executed_transactions.len() == 3)
+        .next_tx("L1 tx", random_l1_tx(1), successful_exec())
+        .next_tx("First L2 tx", random_tx(2), successful_exec())
+        .next_tx("Second L2 tx", random_tx(3), successful_exec())
+        .miniblock_sealed("Miniblock 1")
+        .batch_sealed("Batch 1")
+        .run(sealer)
+        .await;
+
+    // Other tests may run concurrently and process transactions as well, so we only check the lower bounds.
+    assert!(processed_l1_txs.get() >= l1_txs_before + 1);
+    assert!(processed_l2_txs.get() >= l2_txs_before + 2);
+}
+
 /// Unconditionally seal the batch without triggering specific criteria.
 #[tokio::test]
 async fn unconditional_sealing() {
@@ -514,11 +738,79 @@ async fn unconditional_sealing() {
         .no_txs_until_next_action("We don't give transaction to wait for miniblock to be sealed")
         .miniblock_sealed("Miniblock is sealed with just one tx")
         .no_txs_until_next_action("Still no tx")
-        .batch_sealed("Batch is sealed with just one tx")
+        .batch_sealed_with("Batch is sealed with just one tx", |updates| {
+            assert_eq!(
+                updates.l1_batch.seal_criterion,
+                Some(UNCONDITIONAL_SEAL_CRITERION)
+            );
+        })
+        .run(sealer)
+        .await;
+}
+
+/// Checks that a batch with a single transaction is sealed once the first tx timeout elapses.
+#[tokio::test]
+async fn sealing_by_first_tx_timeout() {
+    // `TestIO` assigns miniblock timestamps starting from 1, so the timeout is elapsed for the very first tx.
+    let mut timeout_sealer = FirstTxTimeoutSealer::new(Duration::from_secs(60));
+
+    let config = StateKeeperConfig {
+        transaction_slots: 2,
+        ..StateKeeperConfig::default()
+    };
+    let sealer = SequencerSealer::with_sealers(config, vec![Box::new(SlotsCriterion)]);
+
+    TestScenario::new()
+        .seal_l1_batch_when(move |manager| {
+            timeout_sealer.should_seal_l1_batch_unconditionally(manager)
+        })
+        .next_tx("The only tx", random_tx(1), successful_exec())
+        .miniblock_sealed("Miniblock is sealed with just one tx")
+        .batch_sealed_with("Batch is sealed with just one tx", |updates| {
+            assert_eq!(updates.l1_batch.executed_transactions.len(), 1);
+            assert_eq!(
+                updates.l1_batch.seal_criterion,
+                Some(UNCONDITIONAL_SEAL_CRITERION)
+            );
+        })
         .run(sealer)
         .await;
 }
 
+#[tokio::test]
+async fn mock_batch_executor_with_configured_results() {
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let rejected_tx = random_tx(1);
+    let out_of_gas_tx = random_tx(2);
+    let mut executor = MockBatchExecutor::default()
+        .with_tx_result(rejected_tx.hash(), rejected_exec())
+        .with_tx_result(
+            out_of_gas_tx.hash(),
+            TxExecutionResult::BootloaderOutOfGasForTx,
+        )
+        .allow_rollbacks();
+
+    let handle = executor
+        .init_batch(
+            default_l1_batch_env(1, 1, Address::random()),
+            default_system_env(),
+            &stop_receiver,
+        )
+        .await
+        .unwrap();
+    let result = handle.execute_tx(rejected_tx.clone()).await;
+    assert_matches!(result, TxExecutionResult::RejectedByVm { .. });
+    let result = handle.execute_tx(out_of_gas_tx).await;
+    assert_matches!(result, TxExecutionResult::BootloaderOutOfGasForTx);
+    handle.rollback_last_tx().await;
+    // Registered results are consumed, so the transaction should succeed on re-execution.
+    let result = handle.execute_tx(rejected_tx).await;
+    assert_matches!(result, TxExecutionResult::Success { .. });
+    let result = handle.execute_tx(random_tx(3)).await;
+    assert_matches!(result, TxExecutionResult::Success { .. });
+    handle.finish_batch().await;
+}
+
 /// Checks the next miniblock sealed after pending batch has a correct timestamp
 #[tokio::test]
 async fn miniblock_timestamp_after_pending_batch() {
@@ -651,3 +943,128 @@ async fn protocol_upgrade() {
         .run(sealer)
         .await;
 }
+
+#[tokio::test]
+async fn protocol_upgrade_with_upgrade_tx() {
+    let config = StateKeeperConfig {
+        transaction_slots: 2,
+        ..StateKeeperConfig::default()
+    };
+    let sealer = SequencerSealer::with_sealers(config, vec![Box::new(SlotsCriterion)]);
+    let upgrade_tx = random_upgrade_tx(10);
+    let upgrade_tx_hash = Transaction::from(upgrade_tx.clone()).hash();
+
+    let calls = TestScenario::new()
+        .seal_miniblock_when(|updates| updates.miniblock.executed_transactions.len() == 1)
+        .next_tx("First tx", random_tx(1), successful_exec())
+        .miniblock_sealed("Miniblock 1")
+        .increment_protocol_version("Increment protocol version")
+        .next_tx("Second tx", random_tx(2), successful_exec())
+        .miniblock_sealed("Miniblock 2")
+        .batch_sealed("Batch 1")
+        .expect_upgrade_tx("Upgrade tx", ProtocolVersionId::next(), upgrade_tx)
+        .miniblock_sealed_with("Miniblock 3", move |updates| {
+            let executed_tx_hashes: Vec<_> = updates
+                .miniblock
+                .executed_transactions
+                .iter()
+                .map(|tx| tx.hash)
+                .collect();
+            assert_eq!(
+                executed_tx_hashes,
+                [upgrade_tx_hash],
+                "Upgrade tx should be the first tx in the batch"
+            );
+        })
+        .next_tx("Third tx", random_tx(3), successful_exec())
+        .miniblock_sealed("Miniblock 4")
+        .batch_sealed_with("Batch 2", |updates| {
+            assert_eq!(updates.protocol_version(), ProtocolVersionId::next());
+        })
+        .next_tx("Fourth tx", random_tx(4), successful_exec())
+        .miniblock_sealed("Miniblock 5")
+        .next_tx("Fifth tx", random_tx(5), successful_exec())
+        .miniblock_sealed("Miniblock 6")
+        .batch_sealed("Batch 3")
+        .run_recording_io_calls(sealer)
+        .await;
+
+    // The upgrade transaction must not be requested for the batches after the upgraded one.
+    let upgrade_tx_calls: Vec<_> = calls
+        .iter()
+        .filter(|call| matches!(call, IoCall::LoadUpgradeTx { .. }))
+        .collect();
+    assert_eq!(
+        upgrade_tx_calls,
+        [&IoCall::LoadUpgradeTx {
+            version_id: ProtocolVersionId::next()
+        }],
+        "Unexpected upgrade tx loads: {calls:#?}"
+    );
+}
+
+#[tokio::test]
+async fn io_calls_around_protocol_upgrade() {
+    let config = StateKeeperConfig {
+        transaction_slots: 2,
+        ..StateKeeperConfig::default()
+    };
+    let sealer = SequencerSealer::with_sealers(config, vec![Box::new(SlotsCriterion)]);
+
+    let calls = TestScenario::new()
+        .seal_miniblock_when(|updates| updates.miniblock.executed_transactions.len() == 1)
+        .next_tx("First tx", random_tx(1), successful_exec())
+        .miniblock_sealed("Miniblock 1")
+        .increment_protocol_version("Increment protocol version")
+        .next_tx("Second tx", random_tx(2), successful_exec())
+        .miniblock_sealed("Miniblock 2")
+        .batch_sealed("Batch 1")
+        .next_tx("Third tx", random_tx(3), successful_exec())
+        .miniblock_sealed("Miniblock 3")
+        .next_tx("Fourth tx", random_tx(4), successful_exec())
+        .miniblock_sealed("Miniblock 4")
+        .batch_sealed("Batch 2")
+        .run_recording_io_calls(sealer)
+        .await;
+
+    assert_eq!(calls[0], IoCall::Initialize);
+    // The upgrade transaction must be loaded only once, for the first batch with the new protocol version.
+    let upgrade_tx_calls: Vec<_> = calls
+        .iter()
+        .enumerate()
+        .filter(|(_, call)| matches!(call, IoCall::LoadUpgradeTx { .. }))
+        .collect();
+    assert_eq!(
+        upgrade_tx_calls.len(),
+        1,
+        "Unexpected upgrade tx loads: {calls:#?}"
+    );
+    let (upgrade_tx_idx, upgrade_tx_call) = upgrade_tx_calls[0];
+    assert_eq!(
+        *upgrade_tx_call,
+        IoCall::LoadUpgradeTx {
+            version_id: ProtocolVersionId::next()
+        }
+    );
+
+    // The upgrade transaction must be loaded after the parameters and system contracts for the new batch are loaded,
+    // but before any transactions are requested for it.
+    assert!(
+        matches!(
+            &calls[upgrade_tx_idx - 2],
+            IoCall::WaitForNewBatchParams { l1_batch, .. } if *l1_batch == L1BatchNumber(2)
+        ),
+        "{calls:#?}"
+    );
+    assert_eq!(
+        calls[upgrade_tx_idx - 1],
+        IoCall::LoadBaseSystemContracts {
+            protocol_version: ProtocolVersionId::next(),
+            l1_batch: L1BatchNumber(2),
+        }
+    );
+    assert!(
+        matches!(calls[upgrade_tx_idx + 1], IoCall::WaitForNextTx { .. }),
+        "{calls:#?}"
+    );
+}
@@ -1,7 +1,7 @@
 use std::{
     collections::{HashMap, HashSet, VecDeque},
     convert::TryInto,
-    fmt, mem,
+    fmt, mem, ops,
     sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
@@ -16,8 +16,9 @@ use multivm::{
 use tokio::sync::{mpsc, watch};
 use zksync_contracts::BaseSystemContracts;
 use zksync_types::{
-    block::MiniblockExecutionData, fee_model::BatchFeeInput, protocol_upgrade::ProtocolUpgradeTx,
-    Address, L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId, Transaction, H256,
+    block::MiniblockExecutionData, fee_model::BatchFeeInput, l1::L1Tx,
+    protocol_upgrade::ProtocolUpgradeTx, Address, L1BatchNumber, L2ChainId, MiniblockNumber,
+    PriorityOpId, ProtocolVersionId, Transaction, H256,
 };
 
 use crate::{
@@ -98,6 +99,24 @@ impl TestScenario {
         self
     }
 
+    /// Expect the state keeper to load a protocol upgrade transaction for the specified protocol version
+    /// and to execute it as the first transaction of the newly opened batch. The upgrade transaction is always
+    /// executed successfully.
+    ///
+    /// This action should be placed after sealing the last batch with the old protocol version (i.e., the batch during
+    /// which [`Self::increment_protocol_version()`] was applied), and should be followed by other actions, so that
+    /// the state keeper is not stopped before executing the transaction.
+    pub(crate) fn expect_upgrade_tx(
+        mut self,
+        description: &'static str,
+        version: ProtocolVersionId,
+        tx: ProtocolUpgradeTx,
+    ) -> Self {
+        self.actions
+            .push_back(ScenarioItem::UpgradeTx(description, version, tx));
+        self
+    }
+
     /// Expect the state keeper to request a transaction from IO.
     /// Adds both a transaction and an outcome of this transaction (that would be returned to the state keeper from the
     /// batch executor).
@@ -153,6 +172,21 @@ impl TestScenario {
         self
     }
 
+    /// Expects the miniblock to be sealed with exactly `tx_count` executed transactions.
+    pub(crate) fn miniblock_sealed_with_tx_count(
+        self,
+        description: &'static str,
+        tx_count: usize,
+    ) -> Self {
+        self.miniblock_sealed_with(description, move |updates| {
+            let actual_tx_count = updates.miniblock.executed_transactions.len();
+            assert_eq!(
+                actual_tx_count, tx_count,
+                "{description}: expected {tx_count} txs in miniblock, got {actual_tx_count}"
+            );
+        })
+    }
+
     /// Expects the batch to be sealed.
     pub(crate) fn batch_sealed(mut self, description: &'static str) -> Self {
         self.actions
@@ -191,6 +225,26 @@ impl TestScenario {
     /// Launches the test.
     /// Provided `SealManager` is expected to be externally configured to adhere the written scenario logic.
     pub(crate) async fn run(self, sealer: SequencerSealer) {
+        self.run_with_io(sealer, |io| Box::new(io)).await;
+    }
+
+    /// Same as [`Self::run()`], but additionally records all calls to the state keeper IO
+    /// and returns them in the order they were made.
+    pub(crate) async fn run_recording_io_calls(self, sealer: SequencerSealer) -> Vec<IoCall> {
+        let call_log = IoCallLog::default();
+        let io_call_log = call_log.clone();
+        self.run_with_io(sealer, move |io| {
+            Box::new(RecordingIO::new(io, io_call_log))
+        })
+        .await;
+        call_log.take()
+    }
+
+    async fn run_with_io(
+        self,
+        sealer: SequencerSealer,
+        wrap_io: impl FnOnce(TestIO) -> Box<dyn StateKeeperIO>,
+    ) {
         assert!(!self.actions.is_empty(), "Test scenario can't be empty");
 
         let batch_executor_base = TestBatchExecutorBuilder::new(&self);
@@ -198,7 +252,7 @@ impl TestScenario {
         let (io, output_handler) = TestIO::new(stop_sender, self);
         let state_keeper = ZkSyncStateKeeper::new(
             stop_receiver,
-            Box::new(io),
+            wrap_io(io),
             Box::new(batch_executor_base),
             output_handler,
             Arc::new(sealer),
@@ -234,6 +288,19 @@ pub(crate) fn random_tx(tx_number: u64) -> Transaction {
     tx.into()
 }
 
+/// Creates a random L1 (priority) transaction. Provided tx number would be used as a transaction hash,
+/// so it's easier to understand which transaction caused test to fail.
+pub(crate) fn random_l1_tx(tx_number: u64) -> Transaction {
+    let mut tx = L1Tx {
+        execute: Default::default(),
+        common_data: Default::default(),
+        received_timestamp_ms: 0,
+    };
+    tx.common_data.serial_id = PriorityOpId(tx_number);
+    tx.common_data.canonical_tx_hash = H256::from_low_u64_be(tx_number);
+    tx.into()
+}
+
 /// Creates a random protocol upgrade transaction. Provided tx number would be used as a transaction hash,
 /// so it's easier to understand which transaction caused test to fail.
 pub(crate) fn random_upgrade_tx(tx_number: u64) -> ProtocolUpgradeTx {
@@ -315,6 +382,8 @@ enum ScenarioItem {
     NoTxsUntilNextAction(&'static str),
     /// Increments protocol version in IO state.
     IncrementProtocolVersion(&'static str),
+    /// Protocol upgrade transaction loaded by the state keeper at the start of a batch.
+    UpgradeTx(&'static str, ProtocolVersionId, ProtocolUpgradeTx),
     Tx(&'static str, Transaction, TxExecutionResult),
     Rollback(&'static str, Transaction),
     Reject(&'static str, Transaction, Option<String>),
@@ -339,6 +408,12 @@ impl fmt::Debug for ScenarioItem {
                 .debug_tuple("IncrementProtocolVersion")
                 .field(descr)
                 .finish(),
+            Self::UpgradeTx(descr, version, tx) => formatter
+                .debug_tuple("UpgradeTx")
+                .field(descr)
+                .field(version)
+                .field(tx)
+                .finish(),
             Self::Tx(descr, tx, result) => formatter
                 .debug_tuple("Tx")
                 .field(descr)
@@ -375,6 +450,8 @@ pub(crate) struct TestBatchExecutorBuilder {
     txs: ExpectedTransactions,
     /// Set of transactions that would be rolled back at least once.
     rollback_set: HashSet<H256>,
+    /// Set of protocol upgrade transactions, which must be executed first in their batches.
+    upgrade_txs: HashSet<H256>,
 }
 
 impl TestBatchExecutorBuilder {
@@ -382,6 +459,7 @@ impl TestBatchExecutorBuilder {
         let mut txs = VecDeque::new();
         let mut batch_txs = HashMap::new();
         let mut rollback_set = HashSet::new();
+        let mut upgrade_txs = HashSet::new();
 
         // Insert data about the pending batch, if it exists.
         // All the txs from the pending batch must succeed.
@@ -410,6 +488,14 @@ impl TestBatchExecutorBuilder {
                             txs
                         });
                 }
+                ScenarioItem::UpgradeTx(_, _, tx) => {
+                    let tx_hash = Transaction::from(tx.clone()).hash();
+                    batch_txs
+                        .entry(tx_hash)
+                        .or_insert_with(VecDeque::new)
+                        .push_back(successful_exec());
+                    upgrade_txs.insert(tx_hash);
+                }
                 ScenarioItem::Rollback(_, tx) => {
                     rollback_set.insert(tx.hash());
                 }
@@ -429,7 +515,11 @@ impl TestBatchExecutorBuilder {
         // for the initialization of the "next-to-last" batch.
         txs.push_back(HashMap::default());
 
-        Self { txs, rollback_set }
+        Self {
+            txs,
+            rollback_set,
+            upgrade_txs,
+        }
     }
 
     /// Adds successful transactions to be executed in a single L1 batch.
@@ -456,6 +546,7 @@ impl BatchExecutor for TestBatchExecutorBuilder {
             commands_receiver,
             self.txs.pop_front().unwrap(),
             self.rollback_set.clone(),
+            self.upgrade_txs.clone(),
         );
         let handle = tokio::task::spawn_blocking(move || executor.run());
 
@@ -471,6 +562,8 @@ pub(super) struct TestBatchExecutor {
     txs: HashMap<H256, VecDeque<TxExecutionResult>>,
     /// Set of transactions that are expected to be rolled back.
     rollback_set: HashSet<H256>,
+    /// Set of protocol upgrade transactions that must be executed first in the batch.
+    upgrade_txs: HashSet<H256>,
     /// Last executed tx hash.
     last_tx: H256,
 }
@@ -480,11 +573,13 @@ impl TestBatchExecutor {
         commands: mpsc::Receiver<Command>,
         txs: HashMap<H256, VecDeque<TxExecutionResult>>,
         rollback_set: HashSet<H256>,
+        upgrade_txs: HashSet<H256>,
     ) -> Self {
         Self {
             commands,
             txs,
             rollback_set,
+            upgrade_txs,
             last_tx: H256::default(), // We don't expect rollbacks until the first tx is executed.
         }
     }
@@ -493,6 +588,14 @@ impl TestBatchExecutor {
         while let Some(cmd) = self.commands.blocking_recv() {
             match cmd {
                 Command::ExecuteTx(tx, resp) => {
+                    if self.upgrade_txs.contains(&tx.hash()) {
+                        assert_eq!(
+                            self.last_tx,
+                            H256::default(),
+                            "Upgrade transaction {:?} is not the first transaction in the batch",
+                            tx.hash()
+                        );
+                    }
                     let result = self
                         .txs
                         .get_mut(&tx.hash())
@@ -526,6 +629,13 @@ impl TestBatchExecutor {
                     // tx in a row, and it's going to cause a panic anyway.
                 }
                 Command::FinishBatch(resp) => {
+                    for tx_hash in &self.upgrade_txs {
+                        let is_pending = self.txs.get(tx_hash).is_some_and(|txs| !txs.is_empty());
+                        assert!(
+                            !is_pending,
+                            "Upgrade transaction {tx_hash:?} was not executed in the batch"
+                        );
+                    }
                     // Blanket result, it doesn't really matter.
                     resp.send(default_vm_block_result()).unwrap();
                     return;
@@ -595,7 +705,10 @@ pub(super) struct TestIO {
     /// requests until some other action happens.
     skipping_txs: bool,
     protocol_version: ProtocolVersionId,
-    previous_batch_protocol_version: ProtocolVersionId, // FIXME: not updated
+    /// Protocol version of the currently open batch.
+    batch_protocol_version: ProtocolVersionId,
+    /// Protocol version of the batch preceding the currently open one.
+    previous_batch_protocol_version: ProtocolVersionId,
     protocol_upgrade_txs: HashMap<ProtocolVersionId, ProtocolUpgradeTx>,
 }
 
@@ -642,6 +755,7 @@ impl TestIO {
             fee_account: FEE_ACCOUNT,
             skipping_txs: false,
             protocol_version: ProtocolVersionId::latest(),
+            batch_protocol_version: ProtocolVersionId::latest(),
             previous_batch_protocol_version: ProtocolVersionId::latest(),
             protocol_upgrade_txs: HashMap::default(),
         };
@@ -732,6 +846,8 @@ impl StateKeeperIO for TestIO {
         self.miniblock_number += 1;
         self.timestamp += 1;
         self.batch_number += 1;
+        self.previous_batch_protocol_version =
+            mem::replace(&mut self.batch_protocol_version, self.protocol_version);
         Ok(Some(params))
     }
 
@@ -823,6 +939,24 @@ impl StateKeeperIO for TestIO {
         &mut self,
         version_id: ProtocolVersionId,
     ) -> anyhow::Result<Option<ProtocolUpgradeTx>> {
+        let expects_upgrade_tx = matches!(
+            self.actions
+                .lock()
+                .expect("scenario queue is poisoned")
+                .front(),
+            Some(ScenarioItem::UpgradeTx(..))
+        );
+        if expects_upgrade_tx {
+            let action = self.pop_next_item("load_upgrade_tx");
+            let ScenarioItem::UpgradeTx(_, expected_version, tx) = action else {
+                unreachable!("checked above");
+            };
+            assert_eq!(
+                version_id, expected_version,
+                "Upgrade transaction was loaded for an unexpected protocol version"
+            );
+            return Ok(Some(tx));
+        }
         Ok(self.protocol_upgrade_txs.get(&version_id).cloned())
     }
 
@@ -834,10 +968,222 @@ impl StateKeeperIO for TestIO {
     }
 }
 
-/// `BatchExecutor` which doesn't check anything at all. Accepts all transactions.
-// FIXME: move to `utils`?
+/// Call to a [`StateKeeperIO`] method recorded by [`RecordingIO`]. Transactions are identified by their hashes,
+/// and cursors by the next miniblock and L1 batch numbers.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum IoCall {
+    Initialize,
+    WaitForNewBatchParams {
+        next_miniblock: MiniblockNumber,
+        l1_batch: L1BatchNumber,
+        max_wait: Duration,
+    },
+    WaitForNewMiniblockParams {
+        next_miniblock: MiniblockNumber,
+        max_wait: Duration,
+    },
+    WaitForNextTx {
+        max_wait: Duration,
+    },
+    Rollback {
+        tx_hash: H256,
+    },
+    Reject {
+        tx_hash: H256,
+        error: String,
+    },
+    LoadBaseSystemContracts {
+        protocol_version: ProtocolVersionId,
+        l1_batch: L1BatchNumber,
+    },
+    LoadBatchVersionId {
+        number: L1BatchNumber,
+    },
+    LoadBatchVersionIds {
+        numbers: ops::RangeInclusive<L1BatchNumber>,
+    },
+    LoadUpgradeTx {
+        version_id: ProtocolVersionId,
+    },
+    LoadBatchStateHash {
+        number: L1BatchNumber,
+    },
+}
+
+/// Shared log of calls recorded by [`RecordingIO`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IoCallLog(Arc<Mutex<Vec<IoCall>>>);
+
+impl IoCallLog {
+    fn push(&self, call: IoCall) {
+        self.0.lock().expect("IO call log is poisoned").push(call);
+    }
+
+    /// Takes all calls recorded so far.
+    pub(crate) fn take(&self) -> Vec<IoCall> {
+        mem::take(&mut *self.0.lock().expect("IO call log is poisoned"))
+    }
+}
+
+/// [`StateKeeperIO`] decorator recording all calls to the IO methods (with their arguments) to a shared [`IoCallLog`]
+/// and delegating them to the wrapped IO. Seal criteria methods are delegated without recording since they are called
+/// after each transaction and aren't a part of the IO interaction protocol.
 #[derive(Debug)]
-pub(crate) struct MockBatchExecutor;
+pub(crate) struct RecordingIO<IO> {
+    inner: IO,
+    call_log: IoCallLog,
+}
+
+impl<IO: StateKeeperIO> RecordingIO<IO> {
+    pub(crate) fn new(inner: IO, call_log: IoCallLog) -> Self {
+        Self { inner, call_log }
+    }
+}
+
+impl<IO: StateKeeperIO> IoSealCriteria for RecordingIO<IO> {
+    fn should_seal_l1_batch_unconditionally(&mut self, manager: &UpdatesManager) -> bool {
+        self.inner.should_seal_l1_batch_unconditionally(manager)
+    }
+
+    fn should_seal_miniblock(&mut self, manager: &UpdatesManager) -> bool {
+        self.inner.should_seal_miniblock(manager)
+    }
+}
+
+#[async_trait]
+impl<IO: StateKeeperIO> StateKeeperIO for RecordingIO<IO> {
+    fn chain_id(&self) -> L2ChainId {
+        self.inner.chain_id()
+    }
+
+    async fn initialize(&mut self) -> anyhow::Result<(IoCursor, Option<PendingBatchData>)> {
+        self.call_log.push(IoCall::Initialize);
+        self.inner.initialize().await
+    }
+
+    async fn wait_for_new_batch_params(
+        &mut self,
+        cursor: &IoCursor,
+        max_wait: Duration,
+    ) -> anyhow::Result<Option<L1BatchParams>> {
+        self.call_log.push(IoCall::WaitForNewBatchParams {
+            next_miniblock: cursor.next_miniblock,
+            l1_batch: cursor.l1_batch,
+            max_wait,
+        });
+        self.inner.wait_for_new_batch_params(cursor, max_wait).await
+    }
+
+    async fn wait_for_new_miniblock_params(
+        &mut self,
+        cursor: &IoCursor,
+        max_wait: Duration,
+    ) -> anyhow::Result<Option<MiniblockParams>> {
+        self.call_log.push(IoCall::WaitForNewMiniblockParams {
+            next_miniblock: cursor.next_miniblock,
+            max_wait,
+        });
+        self.inner
+            .wait_for_new_miniblock_params(cursor, max_wait)
+            .await
+    }
+
+    async fn wait_for_next_tx(
+        &mut self,
+        max_wait: Duration,
+    ) -> anyhow::Result<Option<Transaction>> {
+        self.call_log.push(IoCall::WaitForNextTx { max_wait });
+        self.inner.wait_for_next_tx(max_wait).await
+    }
+
+    async fn rollback(&mut self, tx: Transaction) -> anyhow::Result<()> {
+        self.call_log.push(IoCall::Rollback { tx_hash: tx.hash() });
+        self.inner.rollback(tx).await
+    }
+
+    async fn reject(&mut self, tx: &Transaction, error: &str) -> anyhow::Result<()> {
+        self.call_log.push(IoCall::Reject {
+            tx_hash: tx.hash(),
+            error: error.to_owned(),
+        });
+        self.inner.reject(tx, error).await
+    }
+
+    async fn load_base_system_contracts(
+        &mut self,
+        protocol_version: ProtocolVersionId,
+        cursor: &IoCursor,
+    ) -> anyhow::Result<BaseSystemContracts> {
+        self.call_log.push(IoCall::LoadBaseSystemContracts {
+            protocol_version,
+            l1_batch: cursor.l1_batch,
+        });
+        self.inner
+            .load_base_system_contracts(protocol_version, cursor)
+            .await
+    }
+
+    async fn load_batch_version_id(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> anyhow::Result<ProtocolVersionId> {
+        self.call_log.push(IoCall::LoadBatchVersionId { number });
+        self.inner.load_batch_version_id(number).await
+    }
+
+    async fn load_batch_version_ids(
+        &mut self,
+        numbers: ops::RangeInclusive<L1BatchNumber>,
+    ) -> anyhow::Result<Vec<ProtocolVersionId>> {
+        self.call_log.push(IoCall::LoadBatchVersionIds {
+            numbers: numbers.clone(),
+        });
+        self.inner.load_batch_version_ids(numbers).await
+    }
+
+    async fn load_upgrade_tx(
+        &mut self,
+        version_id: ProtocolVersionId,
+    ) -> anyhow::Result<Option<ProtocolUpgradeTx>> {
+        self.call_log.push(IoCall::LoadUpgradeTx { version_id });
+        self.inner.load_upgrade_tx(version_id).await
+    }
+
+    async fn load_batch_state_hash(&mut self, number: L1BatchNumber) -> anyhow::Result<H256> {
+        self.call_log.push(IoCall::LoadBatchStateHash { number });
+        self.inner.load_batch_state_hash(number).await
+    }
+}
+
+/// `BatchExecutor` which doesn't check anything at all. By default, accepts all transactions and panics on rollbacks.
+/// Unlike `TestBatchExecutor`, can be used without a `TestScenario`.
+// FIXME: move to `utils`?
+#[derive(Debug, Default)]
+pub(crate) struct MockBatchExecutor {
+    /// Mapping tx -> response. Responses for the same transaction are consumed one by one;
+    /// transactions without responses are executed successfully. Shared among all L1 batches.
+    tx_results: Arc<Mutex<HashMap<H256, VecDeque<TxExecutionResult>>>>,
+    allow_rollbacks: bool,
+}
+
+impl MockBatchExecutor {
+    /// Registers the result of the next execution of the specified transaction.
+    pub(crate) fn with_tx_result(self, tx_hash: H256, result: TxExecutionResult) -> Self {
+        self.tx_results
+            .lock()
+            .unwrap()
+            .entry(tx_hash)
+            .or_default()
+            .push_back(result);
+        self
+    }
+
+    /// Allows rolling back transactions.
+    pub(crate) fn allow_rollbacks(mut self) -> Self {
+        self.allow_rollbacks = true;
+        self
+    }
+}
 
 #[async_trait]
 impl BatchExecutor for MockBatchExecutor {
@@ -848,13 +1194,25 @@ impl BatchExecutor for MockBatchExecutor {
         _stop_receiver: &watch::Receiver<bool>,
     ) -> Option<BatchExecutorHandle> {
         let (send, recv) = mpsc::channel(1);
-        let handle = tokio::task::spawn(async {
+        let tx_results = self.tx_results.clone();
+        let allow_rollbacks = self.allow_rollbacks;
+        let handle = tokio::task::spawn(async move {
             let mut recv = recv;
             while let Some(cmd) = recv.recv().await {
                 match cmd {
-                    Command::ExecuteTx(_, resp) => resp.send(successful_exec()).unwrap(),
+                    Command::ExecuteTx(tx, resp) => {
+                        let result = tx_results
+                            .lock()
+                            .unwrap()
+                            .get_mut(&tx.hash())
+                            .and_then(VecDeque::pop_front);
+                        resp.send(result.unwrap_or_else(successful_exec)).unwrap();
+                    }
                     Command::StartNextMiniblock(_, resp) => resp.send(()).unwrap(),
-                    Command::RollbackLastTx(_) => panic!("unexpected rollback"),
+                    Command::RollbackLastTx(resp) => {
+                        assert!(allow_rollbacks, "unexpected rollback");
+                        resp.send(()).unwrap();
+                    }
                     Command::FinishBatch(resp) => {
                         // Blanket result, it doesn't really matter.
                         resp.send(default_vm_block_result()).unwrap();
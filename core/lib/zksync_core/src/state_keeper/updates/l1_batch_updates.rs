@@ -18,6 +18,13 @@ pub struct L1BatchUpdates {
     // how much L1 gas will it take to submit this block?
     pub l1_gas_count: BlockGasCount,
     pub txs_encoding_size: usize,
+    /// Number of miniblocks sealed in this batch so far.
+    pub miniblock_count: usize,
+    /// Timestamp of the first sealed miniblock in this batch that contains executed transactions.
+    pub first_tx_miniblock_timestamp: Option<u64>,
+    /// Name of the criterion that has triggered sealing this batch. Set by the state keeper once it decides
+    /// to seal the batch.
+    pub seal_criterion: Option<&'static str>,
     pub finished: Option<FinishedL1Batch>,
 }
 
@@ -30,11 +37,19 @@ impl L1BatchUpdates {
             block_execution_metrics: Default::default(),
             l1_gas_count: new_block_gas_count(),
             txs_encoding_size: 0,
+            miniblock_count: 0,
+            first_tx_miniblock_timestamp: None,
+            seal_criterion: None,
             finished: None,
         }
     }
 
     pub(crate) fn extend_from_sealed_miniblock(&mut self, miniblock_updates: MiniblockUpdates) {
+        if self.first_tx_miniblock_timestamp.is_none()
+            && !miniblock_updates.executed_transactions.is_empty()
+        {
+            self.first_tx_miniblock_timestamp = Some(miniblock_updates.timestamp);
+        }
         for tx in &miniblock_updates.executed_transactions {
             if let ExecuteTransactionCommon::L1(data) = &tx.transaction.common_data {
                 let onchain_metadata = data.onchain_metadata().onchain_data;
@@ -47,6 +62,7 @@ impl L1BatchUpdates {
         self.l1_gas_count += miniblock_updates.l1_gas_count;
         self.block_execution_metrics += miniblock_updates.block_execution_metrics;
         self.txs_encoding_size += miniblock_updates.txs_encoding_size;
+        self.miniblock_count += 1;
     }
 }
 
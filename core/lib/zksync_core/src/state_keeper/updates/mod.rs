@@ -176,6 +176,19 @@ impl UpdatesManager {
     pub(crate) fn pending_txs_encoding_size(&self) -> usize {
         self.l1_batch.txs_encoding_size + self.miniblock.txs_encoding_size
     }
+
+    /// Returns the timestamp of the first miniblock in the batch (including the currently open one)
+    /// that contains executed transactions.
+    pub(crate) fn first_tx_miniblock_timestamp(&self) -> Option<u64> {
+        self.l1_batch.first_tx_miniblock_timestamp.or_else(|| {
+            (!self.miniblock.executed_transactions.is_empty()).then_some(self.miniblock.timestamp)
+        })
+    }
+
+    /// Returns the number of miniblocks in the batch, including the currently open one.
+    pub(crate) fn pending_miniblocks_count(&self) -> usize {
+        self.l1_batch.miniblock_count + 1
+    }
 }
 
 /// Command to seal a miniblock containing all necessary data for it.
@@ -1,15 +1,22 @@
 //! Component responsible for updating L1 batch status.
 
-use std::{fmt, time::Duration};
+use std::{
+    fmt,
+    num::{NonZeroU32, NonZeroUsize},
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use futures::future;
 use serde::Serialize;
 #[cfg(test)]
 use tokio::sync::mpsc;
 use tokio::sync::watch;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_db_connection::retry::is_connection_error;
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_types::{
     aggregated_operations::AggregatedActionType, api, L1BatchNumber, MiniblockNumber, H256,
@@ -21,7 +28,10 @@ use zksync_web3_decl::{
 };
 
 use super::metrics::{FetchStage, FETCHER_METRICS};
-use crate::{metrics::EN_METRICS, utils::projected_first_l1_batch};
+use crate::{
+    metrics::EN_METRICS,
+    utils::{clamp_polling_interval, projected_first_l1_batch},
+};
 
 #[cfg(test)]
 mod tests;
@@ -155,6 +165,13 @@ impl UpdaterCursor {
         })
     }
 
+    /// Returns the number of L1 batches committed on L1, but not yet executed (i.e., not finalized).
+    fn finality_lag(&self) -> u32 {
+        self.last_committed_l1_batch
+            .0
+            .saturating_sub(self.last_executed_l1_batch.0)
+    }
+
     fn extract_tx_hash_and_timestamp(
         batch_info: &api::BlockDetails,
         stage: AggregatedActionType,
@@ -172,17 +189,20 @@ impl UpdaterCursor {
         }
     }
 
+    /// Updates the cursor with the provided batch details. Batches executed after `finality_cutoff`
+    /// are not marked as executed yet.
     fn update(
         &mut self,
         status_changes: &mut StatusChanges,
         batch_info: &api::BlockDetails,
+        finality_cutoff: DateTime<Utc>,
     ) -> anyhow::Result<()> {
         for stage in [
             AggregatedActionType::Commit,
             AggregatedActionType::PublishProofOnchain,
             AggregatedActionType::Execute,
         ] {
-            self.update_stage(status_changes, batch_info, stage)?;
+            self.update_stage(status_changes, batch_info, stage, finality_cutoff)?;
         }
         Ok(())
     }
@@ -192,6 +212,7 @@ impl UpdaterCursor {
         status_changes: &mut StatusChanges,
         batch_info: &api::BlockDetails,
         stage: AggregatedActionType,
+        finality_cutoff: DateTime<Utc>,
     ) -> anyhow::Result<()> {
         let (l1_tx_hash, happened_at) = Self::extract_tx_hash_and_timestamp(batch_info, stage);
         let (last_l1_batch, changes_to_update) = match stage {
@@ -220,6 +241,14 @@ impl UpdaterCursor {
         let happened_at = happened_at.with_context(|| {
             format!("Malformed API response: batch is {action_str}, but has no relevant timestamp")
         })?;
+        if matches!(stage, AggregatedActionType::Execute) && happened_at > finality_cutoff {
+            tracing::debug!(
+                "Batch {} was executed at {happened_at}, which is after the finality cutoff {finality_cutoff}; \
+                 postponing marking it as executed",
+                batch_info.l1_batch_number
+            );
+            return Ok(());
+        }
         changes_to_update.push(BatchStatusChange {
             number: batch_info.l1_batch_number,
             l1_tx_hash,
@@ -246,6 +275,12 @@ pub struct BatchStatusUpdater {
     pool: ConnectionPool<Core>,
     health_updater: HealthUpdater,
     sleep_interval: Duration,
+    /// Maximum number of L1 batches for which details are fetched concurrently when catching up.
+    backfill_concurrency: NonZeroUsize,
+    /// Additional delay after the execution on L1 before an L1 batch is marked as executed (i.e., finalized).
+    finality_delay: Duration,
+    /// Finality lag (in L1 batches) after which the updater is considered degraded.
+    max_finality_lag: Option<NonZeroU32>,
     /// Test-only sender of status changes each time they are produced and applied to the storage.
     #[cfg(test)]
     changes_sender: mpsc::UnboundedSender<StatusChanges>,
@@ -268,22 +303,82 @@ impl BatchStatusUpdater {
             pool,
             health_updater: ReactiveHealthCheck::new("batch_status_updater").1,
             sleep_interval,
+            backfill_concurrency: NonZeroUsize::MIN,
+            finality_delay: Duration::ZERO,
+            max_finality_lag: None,
             #[cfg(test)]
             changes_sender: mpsc::unbounded_channel().0,
         }
     }
 
+    /// Sets the maximum number of L1 batches for which details are fetched concurrently when the updater
+    /// is far behind the last sealed batch (e.g., when the node starts). Once the updater catches up, it switches
+    /// to following the tail serially. By default, backfill is serial.
+    pub fn with_backfill_concurrency(mut self, concurrency: NonZeroUsize) -> Self {
+        self.backfill_concurrency = concurrency;
+        self
+    }
+
+    /// Sets the additional delay after the execution of an L1 batch on L1 before the batch is reported
+    /// as executed, and thus its miniblocks are reported as finalized by the API server. This provides an extra
+    /// safety margin for clients relying on finality. Statuses of committed and proven batches are not affected.
+    /// By default, there is no delay.
+    pub fn with_finality_delay(mut self, delay: Duration) -> Self {
+        self.finality_delay = delay;
+        self
+    }
+
+    /// Sets the maximum number of L1 batches committed on L1, but not yet marked as executed, after which the updater
+    /// reports [`HealthStatus::Degraded`] health. The updater still operates normally; the degraded health signals
+    /// that finality lags behind, e.g., because batches aren't executed on L1 or because of a large finality delay.
+    /// By default, finality lag doesn't influence health.
+    pub fn with_max_finality_lag(mut self, max_lag: NonZeroU32) -> Self {
+        self.max_finality_lag = Some(max_lag);
+        self
+    }
+
+    /// Ensures that the updater doesn't poll the main node more frequently than `min_interval`.
+    pub fn with_min_polling_interval(mut self, min_interval: Duration) -> Self {
+        self.sleep_interval = clamp_polling_interval(self.sleep_interval, min_interval);
+        self
+    }
+
     pub fn health_check(&self) -> ReactiveHealthCheck {
         self.health_updater.subscribe()
     }
 
-    pub async fn run(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+    fn health(&self, cursor: UpdaterCursor) -> Health {
+        let finality_lag = cursor.finality_lag();
+        let is_degraded = self
+            .max_finality_lag
+            .is_some_and(|max_lag| finality_lag > max_lag.get());
+        let status = if is_degraded {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Ready
+        };
+        Health::from(status).with_details(cursor)
+    }
+
+    /// Returns the latest L1 execution time for batches that can be marked as executed.
+    fn finality_cutoff(&self) -> DateTime<Utc> {
+        let now = Utc::now();
+        chrono::Duration::from_std(self.finality_delay)
+            .ok()
+            .and_then(|delay| now.checked_sub_signed(delay))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC)
+    }
+
+    pub async fn run(self: Arc<Self>, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.health_updater
+            .update(HealthStatus::Initializing.into());
         let mut storage = self.pool.connection_tagged("sync_layer").await?;
         let mut cursor = UpdaterCursor::new(&mut storage).await?;
         drop(storage);
         tracing::info!("Initialized batch status updater cursor: {cursor:?}");
+        // The updater is initializing until it successfully fetches status changes from the main node.
         self.health_updater
-            .update(Health::from(HealthStatus::Ready).with_details(cursor));
+            .update(Health::from(HealthStatus::Initializing).with_details(cursor));
 
         loop {
             if *stop_receiver.borrow() {
@@ -295,21 +390,42 @@ impl BatchStatusUpdater {
             // while requesting the changes, we will be able to process what we already fetched.
             let mut status_changes = StatusChanges::default();
             // Note that we don't update `cursor` here (it is copied), but rather only in `apply_status_changes`.
-            match self.get_status_changes(&mut status_changes, cursor).await {
-                Ok(()) => { /* everything went smoothly */ }
+            let is_fetched = match self.get_status_changes(&mut status_changes, cursor).await {
+                Ok(()) => true,
                 Err(UpdaterError::Web3(err)) => {
                     tracing::warn!("Failed to get status changes from the main node: {err}");
+                    false
+                }
+                Err(UpdaterError::Internal(err)) if is_connection_error(&err) => {
+                    tracing::warn!("Lost Postgres connection getting status changes: {err:#}");
+                    false
                 }
                 Err(UpdaterError::Internal(err)) => return Err(err),
-            }
+            };
 
             if status_changes.is_empty() {
+                if is_fetched {
+                    self.health_updater.update(self.health(cursor));
+                }
                 tokio::time::sleep(self.sleep_interval).await;
             } else {
-                self.apply_status_changes(&mut cursor, status_changes)
-                    .await?;
-                self.health_updater
-                    .update(Health::from(HealthStatus::Ready).with_details(cursor));
+                // `cursor` is only updated if the changes are successfully committed to Postgres.
+                let mut updated_cursor = cursor;
+                match self
+                    .apply_status_changes(&mut updated_cursor, status_changes)
+                    .await
+                {
+                    Ok(()) => {
+                        cursor = updated_cursor;
+                        self.health_updater.update(self.health(cursor));
+                    }
+                    Err(err) if is_connection_error(&err) => {
+                        // The changes weren't committed, so they will be re-fetched on the next iteration.
+                        tracing::warn!("Lost Postgres connection applying status changes: {err:#}");
+                        tokio::time::sleep(self.sleep_interval).await;
+                    }
+                    Err(err) => return Err(err),
+                }
             }
         }
     }
@@ -322,9 +438,20 @@ impl BatchStatusUpdater {
     async fn get_status_changes(
         &self,
         status_changes: &mut StatusChanges,
-        mut cursor: UpdaterCursor,
+        cursor: UpdaterCursor,
     ) -> Result<(), UpdaterError> {
         let total_latency = EN_METRICS.update_batch_statuses.start();
+        // Latency is observed regardless of the exit path (including early returns and errors).
+        let result = self.get_status_changes_inner(status_changes, cursor).await;
+        total_latency.observe();
+        result
+    }
+
+    async fn get_status_changes_inner(
+        &self,
+        status_changes: &mut StatusChanges,
+        mut cursor: UpdaterCursor,
+    ) -> Result<(), UpdaterError> {
         let Some(last_sealed_batch) = self
             .pool
             .connection_tagged("sync_layer")
@@ -336,28 +463,44 @@ impl BatchStatusUpdater {
             return Ok(()); // No L1 batches in the storage yet; do nothing.
         };
 
+        let finality_cutoff = self.finality_cutoff();
         let mut batch = cursor.last_executed_l1_batch.next();
+        let backfill_concurrency = self.backfill_concurrency.get();
+        if backfill_concurrency > 1 {
+            // Backfill mode: while there are more batches left than can be fetched concurrently, fetch details
+            // for chunks of batches in parallel. Details are applied to the cursor in the batch order, so the cursor
+            // (and thus the persisted watermarks) advances monotonically regardless of the order in which responses arrive.
+            while batch <= last_sealed_batch
+                && (last_sealed_batch.0 - batch.0) as usize >= backfill_concurrency
+            {
+                let chunk_end = batch + backfill_concurrency as u32;
+                let batch_infos = future::try_join_all(
+                    (batch.0..chunk_end.0)
+                        .map(|number| self.fetch_batch_info(L1BatchNumber(number))),
+                )
+                .await?;
+                for batch_info in batch_infos {
+                    let Some(batch_info) = batch_info else {
+                        return Ok(());
+                    };
+                    cursor.update(status_changes, &batch_info, finality_cutoff)?;
+                    if batch_info.base.commit_tx_hash.is_none() {
+                        // No committed batches after this one.
+                        return Ok(());
+                    }
+                }
+                batch = chunk_end;
+            }
+        }
+
         // In this loop we try to progress on the batch statuses, utilizing the same request to the node to potentially
         // update all three statuses (e.g. if the node is still syncing), but also skipping the gaps in the statuses
         // (e.g. if the last executed batch is 10, but the last proven is 20, we don't need to check the batches 11-19).
         while batch <= last_sealed_batch {
-            // While we may receive `None` for the `self.current_l1_batch`, it's OK: open batch is guaranteed to not
-            // be sent to L1.
-            let miniblock_number = self.client.resolve_l1_batch_to_miniblock(batch).await?;
-            let Some(miniblock_number) = miniblock_number else {
+            let Some(batch_info) = self.fetch_batch_info(batch).await? else {
                 return Ok(());
             };
-
-            let Some(batch_info) = self.client.block_details(miniblock_number).await? else {
-                // We cannot recover from an external API inconsistency.
-                let err = anyhow::anyhow!(
-                    "Node API is inconsistent: miniblock {miniblock_number} was reported to be a part of {batch} L1 batch, \
-                    but API has no information about this miniblock",
-                );
-                return Err(err.into());
-            };
-
-            cursor.update(status_changes, &batch_info)?;
+            cursor.update(status_changes, &batch_info, finality_cutoff)?;
 
             // Check whether we can skip a part of the range.
             if batch_info.base.commit_tx_hash.is_none() {
@@ -375,11 +518,33 @@ impl BatchStatusUpdater {
                 batch += 1;
             }
         }
-
-        total_latency.observe();
         Ok(())
     }
 
+    /// Fetches details for the specified L1 batch from the main node. Returns `None` if the batch is not sealed
+    /// on the main node yet.
+    async fn fetch_batch_info(
+        &self,
+        batch: L1BatchNumber,
+    ) -> Result<Option<api::BlockDetails>, UpdaterError> {
+        // While we may receive `None` for the `self.current_l1_batch`, it's OK: open batch is guaranteed to not
+        // be sent to L1.
+        let miniblock_number = self.client.resolve_l1_batch_to_miniblock(batch).await?;
+        let Some(miniblock_number) = miniblock_number else {
+            return Ok(None);
+        };
+
+        let Some(batch_info) = self.client.block_details(miniblock_number).await? else {
+            // We cannot recover from an external API inconsistency.
+            let err = anyhow::anyhow!(
+                "Node API is inconsistent: miniblock {miniblock_number} was reported to be a part of {batch} L1 batch, \
+                but API has no information about this miniblock",
+            );
+            return Err(err.into());
+        };
+        Ok(Some(batch_info))
+    }
+
     /// Inserts the provided status changes into the database.
     /// The status changes are applied to the database by inserting bogus confirmed transactions (with
     /// some fields missing/substituted) only to satisfy API needs; this component doesn't expect the updated
@@ -409,6 +574,10 @@ impl BatchStatusUpdater {
                 change.number <= last_sealed_batch,
                 "Incorrect update state: unknown batch marked as committed"
             );
+            anyhow::ensure!(
+                change.number == cursor.last_committed_l1_batch.next(),
+                "Incorrect update state: batches must be marked as committed sequentially"
+            );
 
             transaction
                 .eth_sender_dal()
@@ -433,6 +602,10 @@ impl BatchStatusUpdater {
                 change.number <= cursor.last_committed_l1_batch,
                 "Incorrect update state: proven batch must be committed"
             );
+            anyhow::ensure!(
+                change.number == cursor.last_proven_l1_batch.next(),
+                "Incorrect update state: batches must be marked as proven sequentially"
+            );
 
             transaction
                 .eth_sender_dal()
@@ -457,6 +630,10 @@ impl BatchStatusUpdater {
                 change.number <= cursor.last_proven_l1_batch,
                 "Incorrect update state: executed batch must be proven"
             );
+            anyhow::ensure!(
+                change.number == cursor.last_executed_l1_batch.next(),
+                "Incorrect update state: batches must be marked as executed sequentially"
+            );
 
             transaction
                 .eth_sender_dal()
@@ -1,11 +1,17 @@
 //! Tests for batch status updater.
 
-use std::{future, sync::Arc};
+use std::{
+    future,
+    num::{NonZeroU32, NonZeroUsize},
+    sync::Arc,
+};
 
+use assert_matches::assert_matches;
 use chrono::TimeZone;
 use test_casing::{test_casing, Product};
 use tokio::sync::{watch, Mutex};
 use zksync_contracts::BaseSystemContractsHashes;
+use zksync_health_check::{AppHealthCheck, CheckHealth};
 use zksync_types::{Address, ProtocolVersionId};
 
 use super::*;
@@ -269,6 +275,152 @@ async fn updater_cursor_after_snapshot_recovery() {
     assert_eq!(cursor.last_executed_l1_batch, L1BatchNumber(23));
 }
 
+#[test]
+fn updater_cursor_respects_finality_cutoff() {
+    let mut cursor = UpdaterCursor {
+        last_executed_l1_batch: L1BatchNumber(0),
+        last_proven_l1_batch: L1BatchNumber(0),
+        last_committed_l1_batch: L1BatchNumber(0),
+    };
+    let batch_info = mock_block_details(1, L1BatchStage::Executed);
+    let executed_at = batch_info.base.executed_at.unwrap();
+
+    let mut changes = StatusChanges::default();
+    let cutoff = executed_at - chrono::Duration::seconds(1);
+    cursor.update(&mut changes, &batch_info, cutoff).unwrap();
+    assert_eq!(changes.commit.len(), 1);
+    assert_eq!(changes.prove.len(), 1);
+    assert!(changes.execute.is_empty());
+    assert_eq!(cursor.last_proven_l1_batch, L1BatchNumber(1));
+    assert_eq!(cursor.last_executed_l1_batch, L1BatchNumber(0));
+
+    let mut changes = StatusChanges::default();
+    cursor
+        .update(&mut changes, &batch_info, executed_at)
+        .unwrap();
+    assert!(changes.commit.is_empty() && changes.prove.is_empty());
+    assert_eq!(changes.execute.len(), 1);
+    assert_eq!(cursor.last_executed_l1_batch, L1BatchNumber(1));
+}
+
+#[tokio::test]
+async fn updater_health_reflects_finality_lag() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let (updater, _) = mock_updater(MockMainNodeClient::default(), pool.clone());
+    let mut cursor = UpdaterCursor {
+        last_executed_l1_batch: L1BatchNumber(2),
+        last_proven_l1_batch: L1BatchNumber(4),
+        last_committed_l1_batch: L1BatchNumber(5),
+    };
+    assert_eq!(cursor.finality_lag(), 3);
+    // Finality lag doesn't influence health by default.
+    assert_matches!(updater.health(cursor).status(), HealthStatus::Ready);
+
+    let updater = updater.with_max_finality_lag(NonZeroU32::new(2).unwrap());
+    assert_matches!(updater.health(cursor).status(), HealthStatus::Degraded);
+    cursor.last_executed_l1_batch = L1BatchNumber(3);
+    assert_matches!(updater.health(cursor).status(), HealthStatus::Ready);
+}
+
+#[tokio::test]
+async fn updater_is_initializing_until_first_successful_fetch() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    let target_batch_stages =
+        L1BatchStagesMap::new(L1BatchNumber(1), vec![L1BatchStage::Executed; 2]);
+    for (number, _) in target_batch_stages.iter() {
+        seal_l1_batch(&mut storage, number).await;
+    }
+
+    let client = MockMainNodeClient::from(target_batch_stages.clone());
+    // Block main node responses so that the updater cannot complete its first iteration.
+    let client_guard = client.0.clone().lock_owned().await;
+    let (updater, mut changes_receiver) = mock_updater(client, pool.clone());
+    let app_health = AppHealthCheck::new(None, None);
+    app_health.insert_component(updater.health_check());
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let updater_task = tokio::spawn(Arc::new(updater).run(stop_receiver));
+
+    loop {
+        let health = app_health.check_health().await;
+        if health.is_initializing() {
+            assert!(!health.is_healthy());
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    drop(client_guard);
+
+    let mut observed_batch_stages =
+        L1BatchStagesMap::empty(L1BatchNumber(1), target_batch_stages.stages.len());
+    while observed_batch_stages != target_batch_stages {
+        let changes = changes_receiver.recv().await.unwrap();
+        observed_batch_stages.update(&changes);
+    }
+    loop {
+        let health = app_health.check_health().await;
+        if health.is_healthy() {
+            assert!(!health.is_initializing());
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    stop_sender.send_replace(true);
+    updater_task.await.unwrap().expect("updater failed");
+}
+
+#[tokio::test]
+async fn updater_with_large_finality_lag_is_degraded() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let mut stages = vec![L1BatchStage::Executed; 2];
+    stages.extend([L1BatchStage::Committed; 3]);
+    let target_batch_stages = L1BatchStagesMap::new(L1BatchNumber(1), stages);
+    for (number, _) in target_batch_stages.iter() {
+        seal_l1_batch(&mut storage, number).await;
+    }
+
+    let client = MockMainNodeClient::from(target_batch_stages.clone());
+    let (updater, mut changes_receiver) = mock_updater(client, pool.clone());
+    let updater = updater.with_max_finality_lag(NonZeroU32::new(2).unwrap());
+    let health_check = updater.health_check();
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let updater_task = tokio::spawn(Arc::new(updater).run(stop_receiver));
+
+    let mut observed_batch_stages =
+        L1BatchStagesMap::empty(L1BatchNumber(1), target_batch_stages.stages.len());
+    loop {
+        let changes = changes_receiver.recv().await.unwrap();
+        observed_batch_stages.update(&changes);
+        if observed_batch_stages == target_batch_stages {
+            break;
+        }
+    }
+
+    // Batches #3..=5 are committed, but not executed.
+    let health = loop {
+        let health = health_check.check_health().await;
+        if health.status() == HealthStatus::Degraded {
+            break health;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    };
+    let health = serde_json::to_value(health).unwrap();
+    assert_eq!(health["details"]["last_committed_l1_batch"], 5);
+    assert_eq!(health["details"]["last_executed_l1_batch"], 2);
+
+    stop_sender.send_replace(true);
+    updater_task.await.unwrap().expect("updater failed");
+}
+
 #[test_casing(4, Product(([false, true], [false, true])))]
 #[tokio::test]
 async fn normal_updater_operation(snapshot_recovery: bool, async_batches: bool) {
@@ -310,7 +462,7 @@ async fn normal_updater_operation(snapshot_recovery: bool, async_batches: bool)
     let client = MockMainNodeClient::from(target_batch_stages.clone());
     let (updater, mut changes_receiver) = mock_updater(client, pool.clone());
     let (stop_sender, stop_receiver) = watch::channel(false);
-    let updater_task = tokio::spawn(updater.run(stop_receiver));
+    let updater_task = tokio::spawn(Arc::new(updater).run(stop_receiver));
 
     let batches_task = if async_batches {
         let pool = pool.clone();
@@ -341,6 +493,113 @@ async fn normal_updater_operation(snapshot_recovery: bool, async_batches: bool)
     updater_task.await.unwrap().expect("updater failed");
 }
 
+#[test_casing(3, [2, 3, 7])]
+#[tokio::test]
+async fn updater_with_backfill_concurrency(concurrency: usize) {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let mut stages = vec![L1BatchStage::Executed; 10];
+    stages.extend([L1BatchStage::Proven; 5]);
+    stages.extend([L1BatchStage::Committed; 5]);
+    stages.push(L1BatchStage::Open);
+    let target_batch_stages = L1BatchStagesMap::new(L1BatchNumber(1), stages);
+    for (number, _) in target_batch_stages.iter() {
+        seal_l1_batch(&mut storage, number).await;
+    }
+
+    let client = MockMainNodeClient::from(target_batch_stages.clone());
+    let (updater, mut changes_receiver) = mock_updater(client, pool.clone());
+    let updater = updater.with_backfill_concurrency(NonZeroUsize::new(concurrency).unwrap());
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let updater_task = tokio::spawn(Arc::new(updater).run(stop_receiver));
+
+    let mut observed_batch_stages =
+        L1BatchStagesMap::empty(L1BatchNumber(1), target_batch_stages.stages.len());
+    let mut last_updated_batches = [L1BatchNumber(0); 3];
+    loop {
+        let changes = changes_receiver.recv().await.unwrap();
+        // Check that no batches are skipped for any of the stages.
+        let stage_changes = [&changes.commit, &changes.prove, &changes.execute];
+        for (last_updated_batch, stage_changes) in
+            last_updated_batches.iter_mut().zip(stage_changes)
+        {
+            for change in stage_changes {
+                assert_eq!(change.number, last_updated_batch.next());
+                *last_updated_batch = change.number;
+            }
+        }
+
+        observed_batch_stages.update(&changes);
+        if observed_batch_stages == target_batch_stages {
+            break;
+        }
+    }
+
+    target_batch_stages.assert_storage(&mut storage).await;
+    stop_sender.send_replace(true);
+    updater_task.await.unwrap().expect("updater failed");
+}
+
+#[tokio::test]
+async fn updater_with_finality_delay() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let main_node_batch_stages = L1BatchStagesMap::new(
+        L1BatchNumber(1),
+        vec![
+            L1BatchStage::Executed,
+            L1BatchStage::Executed,
+            L1BatchStage::Proven,
+        ],
+    );
+    for (number, _) in main_node_batch_stages.iter() {
+        seal_l1_batch(&mut storage, number).await;
+    }
+    // Executed batches must be reported only as proven because of the finality delay.
+    let target_batch_stages =
+        L1BatchStagesMap::new(L1BatchNumber(1), vec![L1BatchStage::Proven; 3]);
+
+    let client = MockMainNodeClient::from(main_node_batch_stages);
+    let (updater, mut changes_receiver) = mock_updater(client, pool.clone());
+    // Mock batches are executed shortly after the Unix epoch, so we need a large delay.
+    let updater = updater.with_finality_delay(Duration::from_secs(100 * 365 * 86_400));
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let updater_task = tokio::spawn(Arc::new(updater).run(stop_receiver));
+
+    let mut observed_batch_stages =
+        L1BatchStagesMap::empty(L1BatchNumber(1), target_batch_stages.stages.len());
+    loop {
+        let changes = changes_receiver.recv().await.unwrap();
+        assert!(changes.execute.is_empty(), "{changes:?}");
+        observed_batch_stages.update(&changes);
+        if observed_batch_stages == target_batch_stages {
+            break;
+        }
+    }
+    // Let the updater run several more iterations to check that executed statuses are not reported.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(changes_receiver.try_recv().is_err());
+
+    target_batch_stages.assert_storage(&mut storage).await;
+    let finalized_miniblock = storage
+        .blocks_web3_dal()
+        .resolve_block_id(api::BlockId::Number(api::BlockNumber::Finalized))
+        .await
+        .unwrap();
+    assert_eq!(finalized_miniblock, Some(MiniblockNumber(0)));
+
+    stop_sender.send_replace(true);
+    updater_task.await.unwrap().expect("updater failed");
+}
+
 #[test_casing(2, [false, true])]
 #[tokio::test]
 async fn updater_with_gradual_main_node_updates(snapshot_recovery: bool) {
@@ -396,7 +655,7 @@ async fn updater_with_gradual_main_node_updates(snapshot_recovery: bool) {
 
     let (updater, mut changes_receiver) = mock_updater(client, pool.clone());
     let (stop_sender, stop_receiver) = watch::channel(false);
-    let updater_task = tokio::spawn(updater.run(stop_receiver));
+    let updater_task = tokio::spawn(Arc::new(updater).run(stop_receiver));
 
     loop {
         let changes = changes_receiver.recv().await.unwrap();
@@ -424,7 +683,7 @@ async fn test_resuming_updater(pool: ConnectionPool<Core>, initial_batch_stages:
     let client = MockMainNodeClient::from(target_batch_stages.clone());
     let (updater, mut changes_receiver) = mock_updater(client, pool.clone());
     let (stop_sender, stop_receiver) = watch::channel(false);
-    let updater_task = tokio::spawn(updater.run(stop_receiver));
+    let updater_task = tokio::spawn(Arc::new(updater).run(stop_receiver));
 
     let mut observed_batch_stages = initial_batch_stages;
     loop {
@@ -1,8 +1,11 @@
 //! Client abstractions for syncing between the external node and the main node.
 
-use std::fmt;
+use std::{fmt, future::Future, time::Duration};
 
 use async_trait::async_trait;
+use hyper::client::HttpConnector;
+use rand::Rng;
+use tower::Layer;
 use zksync_config::GenesisConfig;
 use zksync_system_constants::ACCOUNT_CODE_STORAGE_ADDRESS;
 use zksync_types::{
@@ -11,10 +14,15 @@ use zksync_types::{
 };
 use zksync_web3_decl::{
     error::{ClientRpcContext, EnrichedClientError, EnrichedClientResult},
-    jsonrpsee::http_client::{HttpClient, HttpClientBuilder},
+    jsonrpsee::{
+        core::client::ClientT,
+        http_client::{transport::HttpBackend, HttpClient, HttpClientBuilder},
+    },
     namespaces::{EnNamespaceClient, EthNamespaceClient, ZksNamespaceClient},
 };
 
+use super::{compression::DecompressionLayer, metrics::CLIENT_METRICS};
+
 /// Client abstracting connection to the main node.
 #[async_trait]
 pub trait MainNodeClient: 'static + Send + Sync + fmt::Debug {
@@ -46,15 +54,297 @@ pub trait MainNodeClient: 'static + Send + Sync + fmt::Debug {
     async fn fetch_genesis_config(&self) -> EnrichedClientResult<GenesisConfig>;
 }
 
+/// Configuration of the JSON-RPC client connecting to the main node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MainNodeClientConfig {
+    /// Timeout for a single request to the main node. The timeout covers the entire request lifecycle,
+    /// including establishing a TCP connection, so it also bounds the time spent connecting to an unresponsive host.
+    pub request_timeout: Duration,
+    /// Timeout for establishing a TCP connection to the main node.
+    pub connect_timeout: Duration,
+    /// Idle time after which TCP keepalive probes are sent on connections to the main node. Keepalive allows
+    /// detecting connections silently dropped by intermediaries (e.g., load balancers with idle timeouts).
+    /// If `None`, keepalive is disabled.
+    pub tcp_keepalive: Option<Duration>,
+    /// Whether to request gzip-compressed responses from the main node.
+    pub compress_responses: bool,
+    /// Maximum size of a response body in bytes. For compressed responses, the limit applies to the decompressed body.
+    pub max_response_body_size: usize,
+    /// Policy for retrying requests failed with transient errors.
+    pub retries: MainNodeRetryConfig,
+}
+
+impl Default for MainNodeClientConfig {
+    fn default() -> Self {
+        Self {
+            request_timeout: Self::DEFAULT_REQUEST_TIMEOUT,
+            connect_timeout: Self::DEFAULT_CONNECT_TIMEOUT,
+            tcp_keepalive: Some(Self::DEFAULT_TCP_KEEPALIVE),
+            compress_responses: false,
+            max_response_body_size: Self::DEFAULT_MAX_RESPONSE_BODY_SIZE,
+            retries: MainNodeRetryConfig::default(),
+        }
+    }
+}
+
+impl MainNodeClientConfig {
+    /// Default value for [`Self::request_timeout`].
+    pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+    /// Default value for [`Self::connect_timeout`].
+    pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+    /// Default value for [`Self::tcp_keepalive`].
+    pub const DEFAULT_TCP_KEEPALIVE: Duration = Duration::from_secs(30);
+    /// Default value for [`Self::max_response_body_size`]. Mirrors the `jsonrpsee` default.
+    pub const DEFAULT_MAX_RESPONSE_BODY_SIZE: usize = 10 * 1_024 * 1_024;
+
+    fn client_builder(&self) -> HttpClientBuilder {
+        let max_response_size = u32::try_from(self.max_response_body_size).unwrap_or(u32::MAX);
+        HttpClientBuilder::default()
+            .request_timeout(self.request_timeout)
+            .max_response_size(max_response_size)
+    }
+
+    fn http_connector(&self) -> HttpConnector {
+        let mut connector = HttpConnector::new();
+        // Allow HTTPS URLs; TLS is handled by the wrapping connector.
+        connector.enforce_http(false);
+        // Mirror the `jsonrpsee` default.
+        connector.set_nodelay(true);
+        connector.set_connect_timeout(Some(self.connect_timeout));
+        connector.set_keepalive(self.tcp_keepalive);
+        connector
+    }
+
+    fn connection_layer(&self) -> ConnectionLayer {
+        let connector = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_native_roots()
+            .https_or_http()
+            .enable_http1()
+            .wrap_connector(self.http_connector());
+        let client = hyper::Client::builder().build(connector);
+        ConnectionLayer(HttpBackend::Https(client))
+    }
+}
+
+/// Layer replacing the HTTP backend of the `jsonrpsee` client with a backend using a configured TCP connector.
+/// This is necessary because the `jsonrpsee` client builder doesn't allow configuring the connect timeout
+/// or TCP keepalive.
+#[derive(Debug, Clone)]
+struct ConnectionLayer(HttpBackend);
+
+impl<S> Layer<S> for ConnectionLayer {
+    type Service = HttpBackend;
+
+    fn layer(&self, _inner: S) -> Self::Service {
+        self.0.clone()
+    }
+}
+
+/// Policy for retrying main node requests failed with transient errors (e.g., timeouts or transport errors).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MainNodeRetryConfig {
+    /// Maximum number of retries for a single request. If set to 0 (the default), requests are not retried.
+    pub max_retries: usize,
+    /// Delay before the first retry. The delay is doubled for each subsequent retry and randomly jittered.
+    pub base_delay: Duration,
+}
+
+impl Default for MainNodeRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            base_delay: Self::DEFAULT_BASE_DELAY,
+        }
+    }
+}
+
+impl MainNodeRetryConfig {
+    /// Default value for [`Self::base_delay`].
+    pub const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(100);
+
+    /// Upper bound for the delay between retries (before jitter).
+    const MAX_DELAY: Duration = Duration::from_secs(60);
+
+    fn backoff_delay(&self, retry: usize) -> Duration {
+        let multiplier = 2_u32.saturating_pow(u32::try_from(retry).unwrap_or(u32::MAX));
+        let delay = self
+            .base_delay
+            .saturating_mul(multiplier)
+            .min(Self::MAX_DELAY);
+        let jitter = rand::thread_rng().gen_range(0.5..1.5);
+        delay.mul_f64(jitter)
+    }
+
+    /// Performs a request to the main node, retrying it according to this policy if it fails with a transient error.
+    /// The request must be idempotent.
+    pub async fn retry<T, Fut>(
+        &self,
+        method: &'static str,
+        mut request: impl FnMut() -> Fut,
+    ) -> EnrichedClientResult<T>
+    where
+        Fut: Future<Output = EnrichedClientResult<T>>,
+    {
+        let mut retry = 0;
+        loop {
+            match request().await {
+                Err(err) if err.is_transient() && retry < self.max_retries => {
+                    let delay = self.backoff_delay(retry);
+                    retry += 1;
+                    tracing::info!(
+                        "Request `{method}` to main node failed with a transient error: {err}; \
+                         retrying in {delay:?} (retry {retry}/{})",
+                        self.max_retries
+                    );
+                    CLIENT_METRICS.retries[&method].inc();
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Main node client wrapper retrying requests failed with transient errors using exponential backoff with jitter.
+/// All [`MainNodeClient`] methods are idempotent reads, so it's safe to retry them. The wrapper is generic
+/// so that it can be used with other client traits covering idempotent reads (e.g., in the reorg detector).
+#[derive(Debug)]
+pub struct RetryingMainNodeClient<C: ?Sized = dyn MainNodeClient> {
+    pub(crate) inner: Box<C>,
+    pub(crate) config: MainNodeRetryConfig,
+}
+
+impl<C: ?Sized> RetryingMainNodeClient<C> {
+    pub fn new(inner: Box<C>, config: MainNodeRetryConfig) -> Self {
+        Self { inner, config }
+    }
+
+    pub(crate) async fn retry<T, Fut>(
+        &self,
+        method: &'static str,
+        request: impl FnMut() -> Fut,
+    ) -> EnrichedClientResult<T>
+    where
+        Fut: Future<Output = EnrichedClientResult<T>>,
+    {
+        self.config.retry(method, request).await
+    }
+}
+
+#[async_trait]
+impl MainNodeClient for RetryingMainNodeClient {
+    async fn fetch_system_contract_by_hash(
+        &self,
+        hash: H256,
+    ) -> EnrichedClientResult<Option<Vec<u8>>> {
+        self.retry("fetch_system_contract_by_hash", || {
+            self.inner.fetch_system_contract_by_hash(hash)
+        })
+        .await
+    }
+
+    async fn fetch_genesis_contract_bytecode(
+        &self,
+        address: Address,
+    ) -> EnrichedClientResult<Option<Vec<u8>>> {
+        self.retry("fetch_genesis_contract_bytecode", || {
+            self.inner.fetch_genesis_contract_bytecode(address)
+        })
+        .await
+    }
+
+    async fn fetch_protocol_version(
+        &self,
+        protocol_version: ProtocolVersionId,
+    ) -> EnrichedClientResult<Option<api::ProtocolVersion>> {
+        self.retry("fetch_protocol_version", || {
+            self.inner.fetch_protocol_version(protocol_version)
+        })
+        .await
+    }
+
+    async fn fetch_l2_block_number(&self) -> EnrichedClientResult<MiniblockNumber> {
+        self.retry("fetch_l2_block_number", || {
+            self.inner.fetch_l2_block_number()
+        })
+        .await
+    }
+
+    async fn fetch_l2_block(
+        &self,
+        number: MiniblockNumber,
+        with_transactions: bool,
+    ) -> EnrichedClientResult<Option<en::SyncBlock>> {
+        self.retry("fetch_l2_block", || {
+            self.inner.fetch_l2_block(number, with_transactions)
+        })
+        .await
+    }
+
+    async fn fetch_consensus_genesis(&self) -> EnrichedClientResult<Option<en::ConsensusGenesis>> {
+        self.retry("fetch_consensus_genesis", || {
+            self.inner.fetch_consensus_genesis()
+        })
+        .await
+    }
+
+    async fn fetch_genesis_config(&self) -> EnrichedClientResult<GenesisConfig> {
+        self.retry("fetch_genesis_config", || self.inner.fetch_genesis_config())
+            .await
+    }
+}
+
 impl dyn MainNodeClient {
-    /// Creates a client based on JSON-RPC.
+    /// Creates a client based on JSON-RPC with the default configuration.
     pub fn json_rpc(url: &str) -> anyhow::Result<HttpClient> {
-        HttpClientBuilder::default().build(url).map_err(Into::into)
+        Self::json_rpc_http_client(url, &MainNodeClientConfig::default())
+    }
+
+    /// Creates a plain HTTP JSON-RPC client with the timeouts, TCP keepalive and response size limit from the specified
+    /// configuration. Unlike [`Self::json_rpc_with_config()`], response compression and retries are not applied;
+    /// this client is suitable for arbitrary requests to the main node (e.g., proxying transactions).
+    pub fn json_rpc_http_client(
+        url: &str,
+        config: &MainNodeClientConfig,
+    ) -> anyhow::Result<HttpClient> {
+        let middleware = tower::ServiceBuilder::new().layer(config.connection_layer());
+        config
+            .client_builder()
+            .set_http_middleware(middleware)
+            .build(url)
+            .map_err(Into::into)
+    }
+
+    /// Creates a client based on JSON-RPC with the specified configuration. If compression is enabled,
+    /// compressed responses are transparently decompressed by the client. If retries are enabled,
+    /// the client is wrapped in [`RetryingMainNodeClient`].
+    pub fn json_rpc_with_config(
+        url: &str,
+        config: &MainNodeClientConfig,
+    ) -> anyhow::Result<Box<Self>> {
+        let client: Box<Self> = if config.compress_responses {
+            let middleware = tower::ServiceBuilder::new()
+                .layer(DecompressionLayer::new(config.max_response_body_size))
+                .layer(config.connection_layer());
+            let builder = config.client_builder().set_http_middleware(middleware);
+            Box::new(builder.build(url)?)
+        } else {
+            Box::new(Self::json_rpc_http_client(url, config)?)
+        };
+        Ok(if config.retries.max_retries > 0 {
+            Box::new(RetryingMainNodeClient::new(client, config.retries))
+        } else {
+            client
+        })
     }
 }
 
 #[async_trait]
-impl MainNodeClient for HttpClient {
+impl<S> MainNodeClient for HttpClient<S>
+where
+    S: 'static,
+    HttpClient<S>: ClientT + Send + Sync + fmt::Debug,
+{
     async fn fetch_system_contract_by_hash(
         &self,
         hash: H256,
@@ -143,3 +433,253 @@ impl MainNodeClient for HttpClient {
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::SocketAddr,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use futures::future;
+    use test_casing::test_casing;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpSocket, TcpStream},
+    };
+    use zksync_web3_decl::jsonrpsee::core::ClientError;
+
+    use super::*;
+    use crate::consensus::testonly::MockMainNodeClient;
+
+    /// Returns a request failing with the specified error a certain number of times, and the counter of its calls.
+    fn flaky_request(
+        failure_count: usize,
+        is_transient: bool,
+    ) -> (
+        impl FnMut() -> future::Ready<EnrichedClientResult<MiniblockNumber>>,
+        Arc<AtomicUsize>,
+    ) {
+        let call_count = Arc::<AtomicUsize>::default();
+        let request = {
+            let call_count = call_count.clone();
+            move || {
+                let call_count = call_count.fetch_add(1, Ordering::SeqCst);
+                future::ready(if call_count >= failure_count {
+                    Ok(MiniblockNumber(42))
+                } else if is_transient {
+                    Err(EnrichedClientError::new(
+                        ClientError::RequestTimeout,
+                        "get_block_number",
+                    ))
+                } else {
+                    Err(EnrichedClientError::custom("fatal", "get_block_number"))
+                })
+            }
+        };
+        (request, call_count)
+    }
+
+    fn retry_config(max_retries: usize) -> MainNodeRetryConfig {
+        MainNodeRetryConfig {
+            max_retries,
+            base_delay: Duration::from_millis(1),
+        }
+    }
+
+    #[tokio::test]
+    async fn retrying_transient_errors() {
+        let (request, call_count) = flaky_request(2, true);
+        let number = retry_config(3)
+            .retry("get_block_number", request)
+            .await
+            .unwrap();
+        assert_eq!(number, MiniblockNumber(42));
+        assert_eq!(call_count.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn giving_up_after_max_retries() {
+        let (request, call_count) = flaky_request(usize::MAX, true);
+        let err = retry_config(3)
+            .retry("get_block_number", request)
+            .await
+            .unwrap_err();
+        assert!(err.is_transient(), "{err:?}");
+        assert_eq!(call_count.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn not_retrying_non_transient_errors() {
+        let (request, call_count) = flaky_request(1, false);
+        let err = retry_config(3)
+            .retry("get_block_number", request)
+            .await
+            .unwrap_err();
+        assert!(!err.is_transient(), "{err:?}");
+        assert_eq!(call_count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn not_retrying_with_zero_max_retries() {
+        let (request, call_count) = flaky_request(1, true);
+        let err = MainNodeRetryConfig::default()
+            .retry("get_block_number", request)
+            .await
+            .unwrap_err();
+        assert!(err.is_transient(), "{err:?}");
+        assert_eq!(call_count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn retrying_client_delegates_to_inner_client() {
+        let mut inner = MockMainNodeClient::default();
+        let protocol_version = api::ProtocolVersion {
+            version_id: ProtocolVersionId::latest() as u16,
+            ..api::ProtocolVersion::default()
+        };
+        inner.insert_protocol_version(protocol_version);
+        let client: Box<dyn MainNodeClient> = Box::new(inner);
+        let client = RetryingMainNodeClient::new(client, retry_config(3));
+
+        let fetched_version = client
+            .fetch_protocol_version(ProtocolVersionId::latest())
+            .await
+            .unwrap()
+            .expect("no protocol version");
+        assert_eq!(
+            fetched_version.version_id,
+            ProtocolVersionId::latest() as u16
+        );
+        let missing_version = client
+            .fetch_protocol_version(ProtocolVersionId::next())
+            .await
+            .unwrap();
+        assert!(missing_version.is_none(), "{missing_version:?}");
+    }
+
+    /// Spawns a TCP server that accepts connections, but never responds to requests.
+    async fn spawn_unresponsive_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut connections = vec![];
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                connections.push(stream);
+            }
+        });
+        format!("http://{addr}/")
+    }
+
+    /// Spawns an HTTP server responding to each request with a JSON-RPC response of the specified size.
+    async fn spawn_server_with_large_responses(response_size: usize) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let prefix = r#"{"jsonrpc":"2.0","id":0,"result":"0x"#;
+        let suffix = r#""}"#;
+        let padding = "0".repeat(response_size - prefix.len() - suffix.len());
+        let body = format!("{prefix}{padding}{suffix}");
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = [0_u8; 4_096];
+                // The request is small enough to be read at once; its contents don't matter.
+                let _read_result = stream.read(&mut request).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).await.ok();
+            }
+        });
+        format!("http://{addr}/")
+    }
+
+    #[tokio::test]
+    async fn http_client_applies_response_size_limit() {
+        let url = spawn_server_with_large_responses(1_024).await;
+        let config = MainNodeClientConfig {
+            max_response_body_size: 512,
+            ..MainNodeClientConfig::default()
+        };
+        // This client is used for proxying transactions to the main node.
+        let client = <dyn MainNodeClient>::json_rpc_http_client(&url, &config).unwrap();
+
+        let err = client.fetch_l2_block_number().await.unwrap_err();
+        assert!(matches!(err.as_ref(), ClientError::Transport(_)), "{err:?}");
+    }
+
+    #[test_casing(2, [false, true])]
+    #[tokio::test]
+    async fn client_applies_request_timeout(compress_responses: bool) {
+        let url = spawn_unresponsive_server().await;
+        let config = MainNodeClientConfig {
+            request_timeout: Duration::from_millis(100),
+            compress_responses,
+            ..MainNodeClientConfig::default()
+        };
+        let client = <dyn MainNodeClient>::json_rpc_with_config(&url, &config).unwrap();
+
+        let err = tokio::time::timeout(Duration::from_secs(10), client.fetch_l2_block_number())
+            .await
+            .expect("request timeout was not applied")
+            .unwrap_err();
+        assert!(
+            matches!(err.as_ref(), ClientError::RequestTimeout),
+            "{err:?}"
+        );
+    }
+
+    /// Local TCP listener that never accepts connections. Its accept queue is filled up, so that new connection
+    /// attempts hang until they are timed out.
+    struct StalledListener {
+        addr: SocketAddr,
+        _listener: TcpListener,
+        _connections: Vec<TcpStream>,
+    }
+
+    impl StalledListener {
+        async fn new() -> Self {
+            let socket = TcpSocket::new_v4().unwrap();
+            socket.bind(([127, 0, 0, 1], 0).into()).unwrap();
+            let addr = socket.local_addr().unwrap();
+            let listener = socket.listen(0).unwrap();
+
+            let mut connections = vec![];
+            while let Ok(stream) =
+                tokio::time::timeout(Duration::from_millis(100), TcpStream::connect(addr)).await
+            {
+                connections.push(stream.unwrap());
+                assert!(connections.len() < 100, "accept queue was not filled up");
+            }
+            Self {
+                addr,
+                _listener: listener,
+                _connections: connections,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn client_applies_connect_timeout() {
+        let listener = StalledListener::new().await;
+        let url = &format!("http://{}/", listener.addr);
+        let config = MainNodeClientConfig {
+            connect_timeout: Duration::from_millis(100),
+            ..MainNodeClientConfig::default()
+        };
+        assert!(config.request_timeout > Duration::from_secs(10));
+        let client = <dyn MainNodeClient>::json_rpc_http_client(url, &config).unwrap();
+
+        let err = tokio::time::timeout(Duration::from_secs(10), client.fetch_l2_block_number())
+            .await
+            .expect("connect timeout was not applied")
+            .unwrap_err();
+        // The request should fail on connection rather than time out as a whole.
+        assert!(matches!(err.as_ref(), ClientError::Transport(_)), "{err:?}");
+    }
+}
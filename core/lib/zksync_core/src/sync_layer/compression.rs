@@ -0,0 +1,226 @@
+//! HTTP middleware negotiating compressed responses from the main node.
+
+use std::{
+    io::{self, Read},
+    task::{Context, Poll},
+};
+
+use flate2::read::GzDecoder;
+use futures::future::BoxFuture;
+use hyper::{
+    header::{HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH},
+    Body, Request, Response,
+};
+use tower::{Layer, Service};
+use zksync_web3_decl::jsonrpsee::http_client::transport::Error as TransportError;
+
+use super::metrics::CLIENT_METRICS;
+
+/// Layer requesting gzip-compressed responses from the server and transparently decompressing them.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct DecompressionLayer {
+    max_response_size: usize,
+}
+
+impl DecompressionLayer {
+    /// Creates a layer failing responses that exceed `max_response_size` bytes after decompression.
+    pub fn new(max_response_size: usize) -> Self {
+        Self { max_response_size }
+    }
+}
+
+impl<S> Layer<S> for DecompressionLayer {
+    type Service = Decompression<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Decompression {
+            inner,
+            max_response_size: self.max_response_size,
+        }
+    }
+}
+
+/// Service produced by [`DecompressionLayer`].
+#[derive(Debug, Clone)]
+pub(super) struct Decompression<S> {
+    inner: S,
+    max_response_size: usize,
+}
+
+impl<S> Service<Request<Body>> for Decompression<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = TransportError>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = TransportError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<Body>) -> Self::Future {
+        request
+            .headers_mut()
+            .insert(ACCEPT_ENCODING, HeaderValue::from_static("gzip"));
+        let response = self.inner.call(request);
+        let max_response_size = self.max_response_size;
+        Box::pin(async move { decompress_response(response.await?, max_response_size).await })
+    }
+}
+
+async fn decompress_response(
+    response: Response<Body>,
+    max_response_size: usize,
+) -> Result<Response<Body>, TransportError> {
+    let is_gzipped = response
+        .headers()
+        .get(CONTENT_ENCODING)
+        .map_or(false, |encoding| {
+            encoding.as_bytes().eq_ignore_ascii_case(b"gzip")
+        });
+    if !is_gzipped {
+        return Ok(response);
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let compressed = hyper::body::to_bytes(body)
+        .await
+        .map_err(|err| TransportError::Http(Box::new(err)))?;
+    let compressed_len = compressed.len() as u64;
+    // Decompression is CPU-bound, so it's moved off the async runtime.
+    let decompressed =
+        tokio::task::spawn_blocking(move || decompress(&compressed, max_response_size))
+            .await
+            .map_err(|err| TransportError::Http(Box::new(err)))?
+            .map_err(|err| TransportError::Http(Box::new(err)))?;
+
+    let saved_len = (decompressed.len() as u64).saturating_sub(compressed_len);
+    CLIENT_METRICS
+        .compressed_response_bytes
+        .inc_by(compressed_len);
+    CLIENT_METRICS.compression_saved_bytes.inc_by(saved_len);
+
+    // The body is no longer encoded, and its length has changed.
+    parts.headers.remove(CONTENT_ENCODING);
+    parts.headers.remove(CONTENT_LENGTH);
+    Ok(Response::from_parts(parts, decompressed.into()))
+}
+
+/// Decompresses a gzipped body, failing if the decompressed body exceeds `max_size` bytes. The limit is enforced
+/// while decompressing, so that a small malicious response cannot exhaust memory.
+fn decompress(compressed: &[u8], max_size: usize) -> io::Result<Vec<u8>> {
+    let mut decompressed = vec![];
+    GzDecoder::new(compressed)
+        .take(max_size as u64 + 1)
+        .read_to_end(&mut decompressed)?;
+    if decompressed.len() > max_size {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("decompressed response exceeds the limit of {max_size} bytes"),
+        ));
+    }
+    Ok(decompressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    const RESPONSE: &str = r#"{"jsonrpc":"2.0","id":0,"result":"0x2a"}"#;
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    async fn call_service(
+        payload: &'static str,
+        compress: bool,
+        max_response_size: usize,
+    ) -> Result<Response<Body>, TransportError> {
+        let inner = service_fn(move |request: Request<Body>| async move {
+            let accept_encoding = request.headers().get(ACCEPT_ENCODING).unwrap();
+            assert_eq!(accept_encoding, "gzip");
+
+            let response = if compress {
+                Response::builder()
+                    .header(CONTENT_ENCODING, "gzip")
+                    .body(Body::from(gzip(payload.as_bytes())))
+            } else {
+                Response::builder().body(Body::from(payload))
+            };
+            Ok::<_, TransportError>(response.unwrap())
+        });
+        DecompressionLayer::new(max_response_size)
+            .layer(inner)
+            .oneshot(Request::new(Body::empty()))
+            .await
+    }
+
+    #[tokio::test]
+    async fn decoding_compressed_response() {
+        let payload: &'static str = RESPONSE.repeat(100).leak();
+        let saved_bytes_before = CLIENT_METRICS.compression_saved_bytes.get();
+        let response = call_service(payload, true, usize::MAX).await.unwrap();
+        assert!(!response.headers().contains_key(CONTENT_ENCODING));
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, payload.as_bytes());
+
+        let saved_bytes = payload.len() - gzip(payload.as_bytes()).len();
+        assert!(
+            CLIENT_METRICS.compression_saved_bytes.get() >= saved_bytes_before + saved_bytes as u64
+        );
+    }
+
+    #[tokio::test]
+    async fn passing_through_uncompressed_response() {
+        let response = call_service(RESPONSE, false, usize::MAX).await.unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, RESPONSE.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn decompressed_response_size_is_limited() {
+        let payload: &'static str = RESPONSE.repeat(1_000).leak();
+        let compressed_len = gzip(payload.as_bytes()).len();
+        assert!(compressed_len < payload.len() / 10);
+
+        let response = call_service(payload, true, payload.len()).await.unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, payload.as_bytes());
+
+        // The limit must be applied to the decompressed body rather than to the compressed one.
+        let err = call_service(payload, true, payload.len() - 1)
+            .await
+            .unwrap_err();
+        let TransportError::Http(err) = err else {
+            panic!("unexpected error: {err:?}");
+        };
+        assert!(err.to_string().contains("exceeds the limit"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn decompression_error() {
+        let inner = service_fn(|_: Request<Body>| async {
+            let response = Response::builder()
+                .header(CONTENT_ENCODING, "gzip")
+                .body(Body::from("not gzip"))
+                .unwrap();
+            Ok::<_, TransportError>(response)
+        });
+        let err = DecompressionLayer::new(usize::MAX)
+            .layer(inner)
+            .oneshot(Request::new(Body::empty()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TransportError::Http(_)), "{err:?}");
+    }
+}
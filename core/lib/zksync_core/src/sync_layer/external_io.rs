@@ -1,13 +1,19 @@
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::{BTreeMap, HashMap},
+    num::NonZeroUsize,
+    ops,
+    time::Duration,
+};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
+use lru::LruCache;
 use vm_utils::storage::L1BatchParamsProvider;
 use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes, SystemContractCode};
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_types::{
-    protocol_upgrade::ProtocolUpgradeTx, L1BatchNumber, L2ChainId, MiniblockNumber,
-    ProtocolVersionId, Transaction, H256,
+    l2::TransactionType, protocol_upgrade::ProtocolUpgradeTx, L1BatchNumber, L2ChainId,
+    MiniblockNumber, ProtocolVersionId, Transaction, H256,
 };
 use zksync_utils::bytes_to_be_words;
 
@@ -15,18 +21,29 @@ use super::{
     client::MainNodeClient,
     sync_action::{ActionQueue, SyncAction},
 };
-use crate::state_keeper::{
-    io::{
-        common::{load_pending_batch, poll_iters, IoCursor},
-        fee_address_migration, L1BatchParams, MiniblockParams, PendingBatchData, StateKeeperIO,
+use crate::{
+    metadata_calculator::LazyAsyncTreeReader,
+    state_keeper::{
+        io::{
+            common::{load_pending_batch, poll_iters, IoCursor},
+            fee_address_migration, L1BatchParams, MiniblockParams, PendingBatchData, StateKeeperIO,
+        },
+        metrics::KEEPER_METRICS,
+        seal_criteria::IoSealCriteria,
+        updates::UpdatesManager,
     },
-    metrics::KEEPER_METRICS,
-    seal_criteria::IoSealCriteria,
-    updates::UpdatesManager,
 };
 
 /// The interval between the action queue polling attempts for the new actions.
 const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Capacity of the base system contracts cache. The cache is cleared on each protocol upgrade, so it normally holds
+/// contracts for a single version; the extra capacity allows loading contracts for an older version (e.g., when
+/// re-executing a pending L1 batch) without evicting the latest version.
+const BASE_SYSTEM_CONTRACTS_CACHE_CAPACITY: usize = 2;
+/// Default upper bound for the interval between attempts to load an L1 batch state hash.
+const DEFAULT_STATE_HASH_MAX_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Maximum number of L1 batch protocol versions prefetched from Postgres in a single query.
+const BATCH_VERSIONS_PREFETCH_SIZE: u32 = 64;
 
 /// ExternalIO is the IO abstraction for the state keeper that is used in the external node.
 /// It receives a sequence of actions from the fetcher via the action queue and propagates it
@@ -41,6 +58,17 @@ pub struct ExternalIO {
     actions: ActionQueue,
     main_node_client: Box<dyn MainNodeClient>,
     chain_id: L2ChainId,
+    /// Base system contracts keyed by the protocol version. Contracts for a specific version never change,
+    /// so entries cannot become stale; the cache is cleared on a protocol upgrade nevertheless since
+    /// older versions are no longer used.
+    base_system_contracts_cache: LruCache<ProtocolVersionId, BaseSystemContracts>,
+    /// Local Merkle tree used as a fallback source of L1 batch state hashes not yet persisted in Postgres.
+    tree_reader: Option<LazyAsyncTreeReader>,
+    /// Upper bound for the exponential backoff when waiting for an L1 batch state hash.
+    state_hash_max_poll_interval: Duration,
+    /// Protocol versions of sealed L1 batches prefetched in bulk. Versions of sealed batches never change,
+    /// so entries are only removed once the state keeper has moved past them.
+    prefetched_batch_versions: BTreeMap<L1BatchNumber, ProtocolVersionId>,
 }
 
 impl ExternalIO {
@@ -65,9 +93,96 @@ impl ExternalIO {
             actions,
             main_node_client,
             chain_id,
+            base_system_contracts_cache: LruCache::new(
+                NonZeroUsize::new(BASE_SYSTEM_CONTRACTS_CACHE_CAPACITY).unwrap(),
+            ),
+            tree_reader: None,
+            state_hash_max_poll_interval: DEFAULT_STATE_HASH_MAX_POLL_INTERVAL,
+            prefetched_batch_versions: BTreeMap::new(),
         })
     }
 
+    /// Sets the local Merkle tree to load L1 batch state hashes from if they are not yet persisted in Postgres.
+    /// If a hash is available from both sources, the hashes are checked to match.
+    pub fn with_tree_reader(mut self, tree_reader: LazyAsyncTreeReader) -> Self {
+        self.tree_reader = Some(tree_reader);
+        self
+    }
+
+    /// Sets the upper bound for the interval between attempts to load an L1 batch state hash. The interval
+    /// starts from 100ms and doubles after each unsuccessful attempt.
+    pub fn with_state_hash_max_poll_interval(mut self, interval: Duration) -> Self {
+        self.state_hash_max_poll_interval = interval;
+        self
+    }
+
+    /// Loads the state hash of the specified L1 batch from Postgres and, if configured, from the local Merkle tree.
+    /// Returns `None` if the hash is not available from either source.
+    async fn try_load_batch_state_hash(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<H256>> {
+        let mut storage = self.pool.connection_tagged("sync_layer").await?;
+        let postgres_hash = self
+            .l1_batch_params_provider
+            .load_l1_batch_state_hash(&mut storage, l1_batch_number)
+            .await
+            .with_context(|| {
+                format!("failed loading state hash for L1 batch #{l1_batch_number}")
+            })?;
+        drop(storage);
+
+        let Some(tree_reader) = self
+            .tree_reader
+            .as_ref()
+            .and_then(LazyAsyncTreeReader::read)
+        else {
+            return Ok(postgres_hash);
+        };
+        let tree_hash = tree_reader.l1_batch_root_hash(l1_batch_number).await;
+        Ok(match (postgres_hash, tree_hash) {
+            (Some(postgres_hash), Some(tree_hash)) => {
+                anyhow::ensure!(
+                    postgres_hash == tree_hash,
+                    "State hash for L1 batch #{l1_batch_number} in Postgres ({postgres_hash:?}) differs from \
+                     the root hash in the Merkle tree ({tree_hash:?})"
+                );
+                Some(postgres_hash)
+            }
+            (None, Some(tree_hash)) => {
+                tracing::info!(
+                    "State hash for L1 batch #{l1_batch_number} is not yet persisted in Postgres; using root hash \
+                     from the Merkle tree: {tree_hash:?}"
+                );
+                Some(tree_hash)
+            }
+            (postgres_hash, None) => postgres_hash,
+        })
+    }
+
+    /// Prefetches protocol versions for the L1 batches starting from `number` in a single query. Besides `number`
+    /// itself, this covers batches already sealed in Postgres, which is the case when the state keeper catches up
+    /// with the storage, e.g. after a restart.
+    async fn prefetch_batch_versions(&mut self, number: L1BatchNumber) -> anyhow::Result<()> {
+        let mut storage = self.pool.connection_tagged("sync_layer").await?;
+        let last_number = number + (BATCH_VERSIONS_PREFETCH_SIZE - 1);
+        let versions = self
+            .l1_batch_params_provider
+            .load_l1_batch_protocol_versions(&mut storage, number..=last_number)
+            .await
+            .with_context(|| {
+                format!(
+                    "failed prefetching protocol versions for L1 batches {number}..={last_number}"
+                )
+            })?;
+        let prefetched_versions = (number.0..)
+            .map(L1BatchNumber)
+            .zip(versions)
+            .map_while(|(number, version)| Some((number, version?)));
+        self.prefetched_batch_versions.extend(prefetched_versions);
+        Ok(())
+    }
+
     async fn get_base_system_contract(
         &self,
         hash: H256,
@@ -115,6 +230,65 @@ impl ExternalIO {
             }
         })
     }
+
+    async fn load_base_system_contracts_uncached(
+        &self,
+        protocol_version: ProtocolVersionId,
+        cursor: &IoCursor,
+    ) -> anyhow::Result<BaseSystemContracts> {
+        let base_system_contracts = self
+            .pool
+            .connection_tagged("sync_layer")
+            .await?
+            .protocol_versions_dal()
+            .load_base_system_contracts_by_version_id(protocol_version as u16)
+            .await
+            .context("failed loading base system contracts")?;
+
+        if let Some(contracts) = base_system_contracts {
+            return Ok(contracts);
+        }
+        tracing::info!("Fetching protocol version {protocol_version:?} from the main node");
+
+        let protocol_version = self
+            .main_node_client
+            .fetch_protocol_version(protocol_version)
+            .await
+            .context("failed to fetch protocol version from the main node")?
+            .context("protocol version is missing on the main node")?;
+        self.pool
+            .connection_tagged("sync_layer")
+            .await?
+            .protocol_versions_dal()
+            .save_protocol_version(
+                protocol_version
+                    .version_id
+                    .try_into()
+                    .context("cannot convert protocol version")?,
+                protocol_version.timestamp,
+                protocol_version.verification_keys_hashes,
+                protocol_version.base_system_contracts,
+                protocol_version.l2_system_upgrade_tx_hash,
+            )
+            .await;
+
+        let BaseSystemContractsHashes {
+            bootloader,
+            default_aa,
+        } = protocol_version.base_system_contracts;
+        let bootloader = self
+            .get_base_system_contract(bootloader, cursor.next_miniblock)
+            .await
+            .with_context(|| format!("cannot fetch bootloader code for {protocol_version:?}"))?;
+        let default_aa = self
+            .get_base_system_contract(default_aa, cursor.next_miniblock)
+            .await
+            .with_context(|| format!("cannot fetch default AA code for {protocol_version:?}"))?;
+        Ok(BaseSystemContracts {
+            bootloader,
+            default_aa,
+        })
+    }
 }
 
 impl IoSealCriteria for ExternalIO {
@@ -299,7 +473,17 @@ impl StateKeeperIO for ExternalIO {
                     let SyncAction::Tx(tx) = actions.pop_action().unwrap() else {
                         unreachable!()
                     };
-                    return Ok(Some(Transaction::from(*tx)));
+                    let block_received_at = tx.block_received_at();
+                    let tx = Transaction::from(*tx);
+                    // On the external node, upgrade transactions are received from the main node as regular ones.
+                    // The latency is measured from the moment the block with the transaction was received
+                    // to the moment it's handed over to the state keeper for execution.
+                    if tx.tx_format() == TransactionType::ProtocolUpgradeTransaction {
+                        KEEPER_METRICS
+                            .protocol_upgrade_tx_latency
+                            .observe(block_received_at.elapsed());
+                    }
+                    return Ok(Some(tx));
                 }
                 Some(SyncAction::SealMiniblock | SyncAction::SealBatch) => {
                     // No more transactions in the current miniblock; the state keeper should seal it.
@@ -337,70 +521,62 @@ impl StateKeeperIO for ExternalIO {
         protocol_version: ProtocolVersionId,
         cursor: &IoCursor,
     ) -> anyhow::Result<BaseSystemContracts> {
-        let base_system_contracts = self
-            .pool
-            .connection_tagged("sync_layer")
-            .await?
-            .protocol_versions_dal()
-            .load_base_system_contracts_by_version_id(protocol_version as u16)
-            .await
-            .context("failed loading base system contracts")?;
-
-        if let Some(contracts) = base_system_contracts {
-            return Ok(contracts);
+        if let Some(contracts) = self.base_system_contracts_cache.get(&protocol_version) {
+            return Ok(contracts.clone());
         }
-        tracing::info!("Fetching protocol version {protocol_version:?} from the main node");
 
-        let protocol_version = self
-            .main_node_client
-            .fetch_protocol_version(protocol_version)
-            .await
-            .context("failed to fetch protocol version from the main node")?
-            .context("protocol version is missing on the main node")?;
-        self.pool
-            .connection_tagged("sync_layer")
-            .await?
-            .protocol_versions_dal()
-            .save_protocol_version(
-                protocol_version
-                    .version_id
-                    .try_into()
-                    .context("cannot convert protocol version")?,
-                protocol_version.timestamp,
-                protocol_version.verification_keys_hashes,
-                protocol_version.base_system_contracts,
-                protocol_version.l2_system_upgrade_tx_hash,
-            )
-            .await;
-
-        let BaseSystemContractsHashes {
-            bootloader,
-            default_aa,
-        } = protocol_version.base_system_contracts;
-        let bootloader = self
-            .get_base_system_contract(bootloader, cursor.next_miniblock)
-            .await
-            .with_context(|| format!("cannot fetch bootloader code for {protocol_version:?}"))?;
-        let default_aa = self
-            .get_base_system_contract(default_aa, cursor.next_miniblock)
-            .await
-            .with_context(|| format!("cannot fetch default AA code for {protocol_version:?}"))?;
-        Ok(BaseSystemContracts {
-            bootloader,
-            default_aa,
-        })
+        let contracts = self
+            .load_base_system_contracts_uncached(protocol_version, cursor)
+            .await?;
+        let is_upgrade = self
+            .base_system_contracts_cache
+            .iter()
+            .all(|(&cached_version, _)| cached_version < protocol_version);
+        if is_upgrade {
+            tracing::info!(
+                "Protocol version changed to {protocol_version:?}; invalidating base system contracts cache"
+            );
+            self.base_system_contracts_cache.clear();
+        }
+        self.base_system_contracts_cache
+            .put(protocol_version, contracts.clone());
+        Ok(contracts)
     }
 
     async fn load_batch_version_id(
         &mut self,
         number: L1BatchNumber,
     ) -> anyhow::Result<ProtocolVersionId> {
+        // The state keeper requests versions in the increasing batch order, so older entries are no longer needed.
+        self.prefetched_batch_versions = self.prefetched_batch_versions.split_off(&number);
+        if self.prefetched_batch_versions.is_empty() {
+            self.prefetch_batch_versions(number).await?;
+        }
+        self.prefetched_batch_versions
+            .get(&number)
+            .copied()
+            .with_context(|| format!("L1 batch #{number} misses protocol version"))
+    }
+
+    async fn load_batch_version_ids(
+        &mut self,
+        numbers: ops::RangeInclusive<L1BatchNumber>,
+    ) -> anyhow::Result<Vec<ProtocolVersionId>> {
         let mut storage = self.pool.connection_tagged("sync_layer").await?;
-        self.l1_batch_params_provider
-            .load_l1_batch_protocol_version(&mut storage, number)
+        let versions = self
+            .l1_batch_params_provider
+            .load_l1_batch_protocol_versions(&mut storage, numbers.clone())
             .await
-            .with_context(|| format!("failed loading protocol version for L1 batch #{number}"))?
-            .with_context(|| format!("L1 batch #{number} misses protocol version"))
+            .with_context(|| {
+                format!("failed loading protocol versions for L1 batches {numbers:?}")
+            })?;
+        versions
+            .into_iter()
+            .zip(numbers.start().0..)
+            .map(|(version, number)| {
+                version.with_context(|| format!("L1 batch #{number} misses protocol version"))
+            })
+            .collect()
     }
 
     async fn load_upgrade_tx(
@@ -416,13 +592,18 @@ impl StateKeeperIO for ExternalIO {
         l1_batch_number: L1BatchNumber,
     ) -> anyhow::Result<H256> {
         tracing::info!("Getting L1 batch hash for L1 batch #{l1_batch_number}");
-        let mut storage = self.pool.connection_tagged("sync_layer").await?;
         let wait_latency = KEEPER_METRICS.wait_for_prev_hash_time.start();
-        let (hash, _) = self
-            .l1_batch_params_provider
-            .wait_for_l1_batch_params(&mut storage, l1_batch_number)
-            .await
-            .with_context(|| format!("error waiting for params for L1 batch #{l1_batch_number}"))?;
+        let mut poll_interval = POLL_INTERVAL.min(self.state_hash_max_poll_interval);
+        let hash = loop {
+            if let Some(hash) = self.try_load_batch_state_hash(l1_batch_number).await? {
+                break hash;
+            }
+            tracing::debug!(
+                "State hash for L1 batch #{l1_batch_number} is not available yet; retrying in {poll_interval:?}"
+            );
+            tokio::time::sleep(poll_interval).await;
+            poll_interval = (poll_interval * 2).min(self.state_hash_max_poll_interval);
+        };
         wait_latency.observe();
         Ok(hash)
     }
@@ -1,3 +1,5 @@
+use std::{ops, time::Instant};
+
 use anyhow::Context as _;
 use zksync_dal::{Connection, Core, CoreDal};
 use zksync_types::{
@@ -17,7 +19,12 @@ use crate::{
 /// Same as [`zksync_types::Transaction`], just with additional guarantees that the "received at" timestamp was set locally.
 /// We cannot transfer `Transaction`s without these timestamps, because this would break backward compatibility.
 #[derive(Debug, Clone)]
-pub(crate) struct FetchedTransaction(zksync_types::Transaction);
+pub(crate) struct FetchedTransaction {
+    inner: zksync_types::Transaction,
+    /// Moment the block containing the transaction was received from the main node. Unlike the "received at" timestamp,
+    /// it is not persisted, so it's only used to measure sync latency.
+    block_received_at: Instant,
+}
 
 impl FetchedTransaction {
     pub fn new(mut tx: zksync_types::Transaction) -> Self {
@@ -25,17 +32,31 @@ impl FetchedTransaction {
         // with an earlier timestamp are persisted earlier). Without this property, code relying on causal ordering may work incorrectly;
         // e.g., `pendingTransactions` subscriptions notifier can skip transactions.
         tx.received_timestamp_ms = unix_timestamp_ms();
-        Self(tx)
+        Self {
+            inner: tx,
+            block_received_at: Instant::now(),
+        }
+    }
+
+    /// Sets the moment the block containing this transaction was received. If not called, the moment
+    /// this transaction was created is used.
+    pub fn with_block_received_at(mut self, block_received_at: Instant) -> Self {
+        self.block_received_at = block_received_at;
+        self
     }
 
     pub fn hash(&self) -> H256 {
-        self.0.hash()
+        self.inner.hash()
+    }
+
+    pub fn block_received_at(&self) -> Instant {
+        self.block_received_at
     }
 }
 
 impl From<FetchedTransaction> for zksync_types::Transaction {
     fn from(tx: FetchedTransaction) -> Self {
-        tx.0
+        tx.inner
     }
 }
 
@@ -70,6 +91,8 @@ impl TryFrom<SyncBlock> for FetchedBlock {
     type Error = anyhow::Error;
 
     fn try_from(block: SyncBlock) -> anyhow::Result<Self> {
+        // Blocks are converted immediately after being received from the main node.
+        let received_at = Instant::now();
         let Some(transactions) = block.transactions else {
             return Err(anyhow::anyhow!("Transactions are always requested"));
         };
@@ -94,7 +117,7 @@ impl TryFrom<SyncBlock> for FetchedBlock {
             operator_address: block.operator_address,
             transactions: transactions
                 .into_iter()
-                .map(FetchedTransaction::new)
+                .map(|tx| FetchedTransaction::new(tx).with_block_received_at(received_at))
                 .collect(),
         })
     }
@@ -117,6 +140,24 @@ impl IoCursor {
         Ok(this)
     }
 
+    /// Checks whether the `received` miniblock number skips ahead of the next expected miniblock. If so,
+    /// logs and reports the gap, and returns the range of missing miniblocks.
+    pub(crate) fn detect_gap(
+        &self,
+        received: MiniblockNumber,
+    ) -> Option<ops::Range<MiniblockNumber>> {
+        let expected = self.next_miniblock;
+        if received <= expected {
+            return None;
+        }
+        tracing::warn!(
+            "Detected a gap in fetched miniblocks: expected miniblock {expected}, received {received}; \
+             missing miniblocks will be re-fetched"
+        );
+        FETCHER_METRICS.miniblock_gaps.inc();
+        Some(expected..received)
+    }
+
     pub(crate) fn advance(&mut self, block: FetchedBlock) -> Vec<SyncAction> {
         assert_eq!(block.number, self.next_miniblock);
         let local_block_hash = block.compute_hash(self.prev_miniblock_hash);
@@ -1,31 +1,68 @@
 use anyhow::Context as _;
 use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes, SystemContractCode};
-use zksync_dal::{Connection, Core, CoreDal};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_db_connection::retry::{is_connection_error, ConnectionRetryPolicy};
 use zksync_types::{
     block::DeployedContract, system_contracts::get_system_smart_contracts, AccountTreeId, L2ChainId,
 };
+use zksync_web3_decl::error::EnrichedClientError;
 
 use super::client::MainNodeClient;
 use crate::genesis::{ensure_genesis_state, GenesisParams};
 
+/// Performs genesis for the external node if it wasn't performed yet.
+///
+/// Genesis is idempotent: genesis params are fetched from the main node before any data is written,
+/// and all genesis data is written in a single DB transaction that re-checks whether genesis is needed.
+/// Thus, a failure at any point leaves no partially written genesis, and the whole process can be safely restarted.
+/// Transient errors (main node transport errors and Postgres connection errors) are retried a bounded number of times.
 pub async fn perform_genesis_if_needed(
-    storage: &mut Connection<'_, Core>,
+    pool: &ConnectionPool<Core>,
+    zksync_chain_id: L2ChainId,
+    client: &dyn MainNodeClient,
+) -> anyhow::Result<()> {
+    ConnectionRetryPolicy::default()
+        .retry_if("genesis", is_transient_error, || {
+            try_perform_genesis(pool, zksync_chain_id, client)
+        })
+        .await
+}
+
+async fn try_perform_genesis(
+    pool: &ConnectionPool<Core>,
     zksync_chain_id: L2ChainId,
     client: &dyn MainNodeClient,
 ) -> anyhow::Result<()> {
-    let mut transaction = storage.start_transaction().await?;
     // We want to check whether the genesis is needed before we create genesis params to not
     // make the node startup slower.
-    if transaction.blocks_dal().is_genesis_needed().await? {
-        let genesis_params = create_genesis_params(client, zksync_chain_id).await?;
-        ensure_genesis_state(&mut transaction, &genesis_params)
-            .await
-            .context("ensure_genesis_state")?;
+    let mut storage = pool.connection_tagged("sync_layer").await?;
+    if !storage.blocks_dal().is_genesis_needed().await? {
+        return Ok(());
     }
-    transaction.commit().await?;
+    drop(storage);
+
+    // Fetch genesis params before writing anything, so that we don't hold a DB transaction open
+    // while communicating with the main node.
+    let genesis_params = create_genesis_params(client, zksync_chain_id).await?;
+    let mut storage = pool.connection_tagged("sync_layer").await?;
+    // The genesis L1 batch serves as the completion marker; `ensure_genesis_state()` checks it
+    // and writes genesis data atomically.
+    ensure_genesis_state(&mut storage, &genesis_params)
+        .await
+        .context("ensure_genesis_state")?;
     Ok(())
 }
 
+/// Checks whether the genesis error is transient, i.e., genesis can be retried.
+fn is_transient_error(err: &anyhow::Error) -> bool {
+    is_connection_error(err)
+        || err.chain().any(|cause| {
+            cause
+                .downcast_ref::<EnrichedClientError>()
+                .map_or(false, EnrichedClientError::is_transient)
+        })
+}
+
 async fn create_genesis_params(
     client: &dyn MainNodeClient,
     zksync_chain_id: L2ChainId,
@@ -110,3 +147,235 @@ async fn fetch_base_system_contracts(
         },
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        io,
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use zksync_config::GenesisConfig;
+    use zksync_dal::SqlxError;
+    use zksync_types::{api, Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, H256};
+    use zksync_utils::be_words_to_bytes;
+    use zksync_web3_decl::{error::EnrichedClientResult, jsonrpsee::core::ClientError};
+
+    use super::*;
+    use crate::genesis::insert_genesis_batch;
+
+    /// Main node client serving genesis data. The first `failure_count` genesis config requests fail
+    /// with a transient error.
+    #[derive(Debug)]
+    struct MockGenesisClient {
+        genesis_config: GenesisConfig,
+        base_system_contracts: HashMap<H256, Vec<u8>>,
+        system_contracts: HashMap<Address, Vec<u8>>,
+        failure_count: usize,
+        genesis_config_requests: AtomicUsize,
+    }
+
+    impl MockGenesisClient {
+        /// Creates a client for the mock genesis params. Genesis config fields depending on the genesis state
+        /// (e.g., the root hash) are computed by inserting the genesis batch in a transaction, which is then rolled back.
+        async fn new(pool: &ConnectionPool<Core>, failure_count: usize) -> Self {
+            let params = GenesisParams::mock();
+            let mut storage = pool.connection().await.unwrap();
+            let mut transaction = storage.start_transaction().await.unwrap();
+            let batch_params = insert_genesis_batch(&mut transaction, &params)
+                .await
+                .unwrap();
+            drop(transaction);
+
+            let genesis_config = GenesisConfig {
+                genesis_root_hash: batch_params.root_hash,
+                genesis_commitment: batch_params.commitment,
+                rollup_last_leaf_index: batch_params.rollup_last_leaf_index,
+                ..params.config
+            };
+            let base_system_contracts = [
+                &params.base_system_contracts.bootloader,
+                &params.base_system_contracts.default_aa,
+            ];
+            let base_system_contracts = base_system_contracts
+                .into_iter()
+                .map(|contract| (contract.hash, be_words_to_bytes(&contract.code)))
+                .collect();
+            let system_contracts = params
+                .system_contracts
+                .into_iter()
+                .map(|contract| (*contract.account_id.address(), contract.bytecode))
+                .collect();
+            Self {
+                genesis_config,
+                base_system_contracts,
+                system_contracts,
+                failure_count,
+                genesis_config_requests: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl MainNodeClient for MockGenesisClient {
+        async fn fetch_system_contract_by_hash(
+            &self,
+            hash: H256,
+        ) -> EnrichedClientResult<Option<Vec<u8>>> {
+            Ok(self.base_system_contracts.get(&hash).cloned())
+        }
+
+        async fn fetch_genesis_contract_bytecode(
+            &self,
+            address: Address,
+        ) -> EnrichedClientResult<Option<Vec<u8>>> {
+            Ok(self.system_contracts.get(&address).cloned())
+        }
+
+        async fn fetch_protocol_version(
+            &self,
+            _protocol_version: ProtocolVersionId,
+        ) -> EnrichedClientResult<Option<api::ProtocolVersion>> {
+            unreachable!()
+        }
+
+        async fn fetch_l2_block_number(&self) -> EnrichedClientResult<MiniblockNumber> {
+            unreachable!()
+        }
+
+        async fn fetch_l2_block(
+            &self,
+            _number: MiniblockNumber,
+            _with_transactions: bool,
+        ) -> EnrichedClientResult<Option<api::en::SyncBlock>> {
+            unreachable!()
+        }
+
+        async fn fetch_consensus_genesis(
+            &self,
+        ) -> EnrichedClientResult<Option<api::en::ConsensusGenesis>> {
+            unreachable!()
+        }
+
+        async fn fetch_genesis_config(&self) -> EnrichedClientResult<GenesisConfig> {
+            let request_idx = self.genesis_config_requests.fetch_add(1, Ordering::SeqCst);
+            if request_idx < self.failure_count {
+                return Err(EnrichedClientError::new(
+                    ClientError::RequestTimeout,
+                    "genesis_config",
+                ));
+            }
+            Ok(self.genesis_config.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn performing_genesis() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let client = MockGenesisClient::new(&pool, 0).await;
+        let chain_id = client.genesis_config.l2_chain_id;
+
+        perform_genesis_if_needed(&pool, chain_id, &client)
+            .await
+            .unwrap();
+        let mut storage = pool.connection().await.unwrap();
+        assert!(!storage.blocks_dal().is_genesis_needed().await.unwrap());
+        let genesis_root_hash = storage
+            .blocks_dal()
+            .get_l1_batch_state_root(L1BatchNumber(0))
+            .await
+            .unwrap();
+        assert_eq!(
+            genesis_root_hash,
+            Some(client.genesis_config.genesis_root_hash)
+        );
+        drop(storage);
+
+        // Repeated genesis should be a no-op.
+        perform_genesis_if_needed(&pool, chain_id, &client)
+            .await
+            .unwrap();
+        assert_eq!(client.genesis_config_requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn retrying_genesis_after_transient_error() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let client = MockGenesisClient::new(&pool, 1).await;
+        let chain_id = client.genesis_config.l2_chain_id;
+
+        perform_genesis_if_needed(&pool, chain_id, &client)
+            .await
+            .unwrap();
+        assert_eq!(client.genesis_config_requests.load(Ordering::SeqCst), 2);
+
+        let mut storage = pool.connection().await.unwrap();
+        let sealed_l1_batch = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .unwrap();
+        assert_eq!(sealed_l1_batch, Some(L1BatchNumber(0)));
+    }
+
+    #[tokio::test]
+    async fn recovering_from_interrupted_genesis_write() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let client = MockGenesisClient::new(&pool, 0).await;
+        let chain_id = client.genesis_config.l2_chain_id;
+
+        let (pool, client) = (&pool, &client);
+        let attempts = &AtomicUsize::new(0);
+        let policy =
+            ConnectionRetryPolicy::new(3, Duration::from_millis(10), Duration::from_millis(10));
+        policy
+            .retry_if("genesis", is_transient_error, move || async move {
+                if attempts.fetch_add(1, Ordering::SeqCst) > 0 {
+                    return try_perform_genesis(pool, chain_id, client).await;
+                }
+
+                let mut storage = pool.connection().await?;
+                let mut transaction = storage.start_transaction().await?;
+                insert_genesis_batch(&mut transaction, &GenesisParams::mock()).await?;
+                // Simulate losing the DB connection after genesis data is partially written.
+                // The transaction is rolled back once it's dropped.
+                let err = io::Error::new(io::ErrorKind::ConnectionReset, "connection reset");
+                Err(SqlxError::Io(err).into())
+            })
+            .await
+            .unwrap();
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert_eq!(client.genesis_config_requests.load(Ordering::SeqCst), 1);
+
+        let mut storage = pool.connection().await.unwrap();
+        assert!(!storage.blocks_dal().is_genesis_needed().await.unwrap());
+        let genesis_root_hash = storage
+            .blocks_dal()
+            .get_l1_batch_state_root(L1BatchNumber(0))
+            .await
+            .unwrap();
+        assert_eq!(
+            genesis_root_hash,
+            Some(client.genesis_config.genesis_root_hash)
+        );
+    }
+
+    #[tokio::test]
+    async fn non_transient_genesis_errors_are_not_retried() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let client = MockGenesisClient::new(&pool, 0).await;
+        let other_chain_id =
+            L2ChainId::try_from(client.genesis_config.l2_chain_id.as_u64() + 1).unwrap();
+
+        let err = perform_genesis_if_needed(&pool, other_chain_id, &client)
+            .await
+            .unwrap_err();
+        assert!(!is_transient_error(&err), "{err:#}");
+        assert_eq!(client.genesis_config_requests.load(Ordering::SeqCst), 1);
+
+        let mut storage = pool.connection().await.unwrap();
+        assert!(storage.blocks_dal().is_genesis_needed().await.unwrap());
+    }
+}
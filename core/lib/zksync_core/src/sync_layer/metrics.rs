@@ -2,7 +2,10 @@
 
 use std::time::Duration;
 
-use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
+    Metrics, Unit,
+};
 use zksync_types::aggregated_operations::AggregatedActionType;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
@@ -36,15 +39,20 @@ impl From<AggregatedActionType> for L1BatchStage {
 /// Metrics for the fetcher.
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "external_node_fetcher")]
-pub(super) struct FetcherMetrics {
+pub(crate) struct FetcherMetrics {
     #[metrics(buckets = Buckets::LATENCIES)]
     pub requests: Family<FetchStage, Histogram<Duration>>,
     pub l1_batch: Family<L1BatchStage, Gauge<u64>>,
     pub miniblock: Gauge<u64>,
+    /// Number of gaps detected in the stream of fetched miniblocks (i.e., cases when a received miniblock
+    /// skips ahead of the expected next one).
+    pub miniblock_gaps: Counter,
+    /// Current effective number of miniblocks requested from the main node at once.
+    pub request_size: Gauge<usize>,
 }
 
 #[vise::register]
-pub(super) static FETCHER_METRICS: vise::Global<FetcherMetrics> = vise::Global::new();
+pub(crate) static FETCHER_METRICS: vise::Global<FetcherMetrics> = vise::Global::new();
 
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "external_node_action_queue")]
@@ -52,5 +60,24 @@ pub(super) struct ActionQueueMetrics {
     pub action_queue_size: Gauge<usize>,
 }
 
+/// Metrics for the main node JSON-RPC client.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "external_node_main_node_client")]
+pub(super) struct ClientMetrics {
+    /// Total size of compressed responses received from the main node.
+    #[metrics(unit = Unit::Bytes)]
+    pub compressed_response_bytes: Counter,
+    /// Total number of bytes saved by response compression (i.e., the difference between decompressed
+    /// and compressed response sizes).
+    #[metrics(unit = Unit::Bytes)]
+    pub compression_saved_bytes: Counter,
+    /// Number of retries of requests failed with a transient error, labeled by the request method.
+    #[metrics(labels = ["method"])]
+    pub retries: LabeledFamily<&'static str, Counter>,
+}
+
+#[vise::register]
+pub(super) static CLIENT_METRICS: vise::Global<ClientMetrics> = vise::Global::new();
+
 #[vise::register]
 pub(super) static QUEUE_METRICS: vise::Global<ActionQueueMetrics> = vise::Global::new();
@@ -1,16 +1,19 @@
 pub mod batch_status_updater;
 mod client;
+mod compression;
 pub mod external_io;
 pub mod fetcher;
 pub mod genesis;
-mod metrics;
+pub(crate) mod metrics;
 pub(crate) mod sync_action;
 mod sync_state;
 #[cfg(test)]
 mod tests;
 
 pub use self::{
-    client::MainNodeClient, external_io::ExternalIO, sync_action::ActionQueue,
+    client::{MainNodeClient, MainNodeClientConfig, MainNodeRetryConfig, RetryingMainNodeClient},
+    external_io::ExternalIO,
+    sync_action::ActionQueue,
     sync_state::SyncState,
 };
 
@@ -1,10 +1,10 @@
-use std::sync::Arc;
+use std::{num::NonZeroU32, sync::Arc};
 
 use async_trait::async_trait;
 use serde::Serialize;
 use zksync_concurrency::{ctx, sync};
 use zksync_health_check::{CheckHealth, Health, HealthStatus};
-use zksync_types::MiniblockNumber;
+use zksync_types::{api::en, MiniblockNumber};
 
 use crate::{
     metrics::EN_METRICS,
@@ -66,13 +66,53 @@ impl SyncState {
         self.0.send_modify(|inner| inner.set_main_node_block(block));
     }
 
-    fn set_local_block(&self, block: MiniblockNumber) {
+    pub(crate) fn set_local_block(&self, block: MiniblockNumber) {
         self.0.send_modify(|inner| inner.set_local_block(block));
     }
 
+    /// Records a failed attempt to fetch the main node block. Once `max_consecutive_failures` attempts fail in a row,
+    /// the main node is marked as unreachable until the next [`Self::set_main_node_block()`] call. This is reflected
+    /// in the health check, so that the stale main node block doesn't go unnoticed, while isolated failures
+    /// (e.g., a single timed-out request) don't make the health check flap.
+    pub(crate) fn register_main_node_failure(&self, max_consecutive_failures: NonZeroU32) {
+        EN_METRICS.main_node_unreachable.inc();
+        self.0.send_modify(|inner| {
+            inner.consecutive_main_node_failures += 1;
+            inner.main_node_unreachable =
+                inner.consecutive_main_node_failures >= max_consecutive_failures.get();
+        });
+    }
+
+    /// Marks whether the main node is idle, i.e., the node is synced, but the main node head hasn't changed
+    /// for a long time. This is reflected in the health check.
+    pub(crate) fn set_main_node_idle(&self, is_idle: bool) {
+        self.0.send_if_modified(|inner| {
+            let is_modified = inner.main_node_idle != is_idle;
+            inner.main_node_idle = is_idle;
+            is_modified
+        });
+    }
+
     pub(crate) fn is_synced(&self) -> bool {
         self.0.borrow().is_synced().0
     }
+
+    /// Returns the lag of the local node behind the main node in miniblocks, or `None` if it's not known yet.
+    pub(crate) fn lag(&self) -> Option<u32> {
+        self.0.borrow().is_synced().1
+    }
+
+    /// Returns the current sync status as exposed via the web3 API.
+    pub(crate) fn status(&self) -> en::SyncStatus {
+        let inner = self.0.borrow();
+        let (is_synced, lag) = inner.is_synced();
+        en::SyncStatus {
+            local_miniblock: inner.local_block,
+            main_node_miniblock: inner.main_node_block,
+            lag,
+            is_synced,
+        }
+    }
 }
 
 #[async_trait]
@@ -100,6 +140,13 @@ impl StateKeeperOutputHandler for SyncState {
 pub(crate) struct SyncStateInner {
     pub(crate) main_node_block: Option<MiniblockNumber>,
     pub(crate) local_block: Option<MiniblockNumber>,
+    /// Number of consecutive failed attempts to fetch the main node block.
+    pub(crate) consecutive_main_node_failures: u32,
+    /// Whether the main node is considered unreachable, i.e., enough consecutive attempts to fetch the main node block
+    /// have failed.
+    pub(crate) main_node_unreachable: bool,
+    /// Whether the main node head hasn't changed for a long time while the node is synced.
+    pub(crate) main_node_idle: bool,
 }
 
 impl SyncStateInner {
@@ -111,6 +158,8 @@ impl SyncStateInner {
             }
         }
         self.main_node_block = Some(block);
+        self.consecutive_main_node_failures = 0;
+        self.main_node_unreachable = false;
         self.update_sync_metric();
     }
 
@@ -170,13 +219,22 @@ impl From<&SyncStateInner> for Health {
             main_node_block: Option<MiniblockNumber>,
             #[serde(skip_serializing_if = "Option::is_none")]
             local_block: Option<MiniblockNumber>,
+            main_node_unreachable: bool,
+            consecutive_main_node_failures: u32,
+            main_node_idle: bool,
         }
 
         let (is_synced, block_diff) = state.is_synced();
-        let status = if is_synced {
+        let status = if state.main_node_unreachable {
+            // The main node block may be stale, so we cannot trust the sync status.
+            HealthStatus::Affected
+        } else if is_synced && state.main_node_idle {
+            // The network may be genuinely quiet, but it may also indicate that the main node is stuck.
+            HealthStatus::Degraded
+        } else if is_synced {
             HealthStatus::Ready
         } else if block_diff.is_some() {
-            HealthStatus::Affected
+            HealthStatus::Degraded
         } else {
             return HealthStatus::NotReady.into(); // `state` isn't initialized yet
         };
@@ -184,6 +242,9 @@ impl From<&SyncStateInner> for Health {
             is_synced,
             main_node_block: state.main_node_block,
             local_block: state.local_block,
+            main_node_unreachable: state.main_node_unreachable,
+            consecutive_main_node_failures: state.consecutive_main_node_failures,
+            main_node_idle: state.main_node_idle,
         })
     }
 }
@@ -210,7 +271,7 @@ mod tests {
         assert!(!sync_state.is_synced());
 
         let health = sync_state.check_health().await;
-        assert_matches!(health.status(), HealthStatus::Affected);
+        assert_matches!(health.status(), HealthStatus::Degraded);
 
         // Within the threshold, the node is synced.
         sync_state.set_local_block(MiniblockNumber(1));
@@ -228,6 +289,75 @@ mod tests {
         assert!(!sync_state.is_synced());
     }
 
+    #[tokio::test]
+    async fn main_node_is_unreachable_only_after_consecutive_failures() {
+        let max_failures = NonZeroU32::new(3).unwrap();
+        let sync_state = SyncState::default();
+        sync_state.set_local_block(MiniblockNumber(1));
+        sync_state.set_main_node_block(MiniblockNumber(1));
+
+        for _ in 1..max_failures.get() {
+            sync_state.register_main_node_failure(max_failures);
+            let health = sync_state.check_health().await;
+            assert_matches!(health.status(), HealthStatus::Ready);
+        }
+        sync_state.register_main_node_failure(max_failures);
+        let health = sync_state.check_health().await;
+        assert_matches!(health.status(), HealthStatus::Affected);
+        let health = serde_json::to_value(health).unwrap();
+        assert_eq!(health["details"]["main_node_unreachable"], true);
+        assert_eq!(health["details"]["consecutive_main_node_failures"], 3);
+
+        // A successful poll resets the failure counter.
+        sync_state.set_main_node_block(MiniblockNumber(2));
+        let health = sync_state.check_health().await;
+        assert_matches!(health.status(), HealthStatus::Ready);
+        sync_state.register_main_node_failure(max_failures);
+        let health = sync_state.check_health().await;
+        assert_matches!(health.status(), HealthStatus::Ready);
+    }
+
+    #[test]
+    fn test_sync_state_status() {
+        let sync_state = SyncState::default();
+        sync_state.set_local_block(MiniblockNumber(5));
+        // The main node hasn't been observed yet.
+        assert_eq!(
+            sync_state.status(),
+            en::SyncStatus {
+                local_miniblock: Some(MiniblockNumber(5)),
+                main_node_miniblock: None,
+                lag: None,
+                is_synced: false,
+            }
+        );
+
+        sync_state.set_main_node_block(MiniblockNumber(SYNC_MINIBLOCK_DELTA + 10));
+        let status = sync_state.status();
+        assert_eq!(
+            status.main_node_miniblock,
+            Some(MiniblockNumber(SYNC_MINIBLOCK_DELTA + 10))
+        );
+        assert_eq!(status.lag, Some(SYNC_MINIBLOCK_DELTA + 5));
+        assert!(!status.is_synced);
+
+        sync_state.set_local_block(MiniblockNumber(SYNC_MINIBLOCK_DELTA + 10));
+        let status = sync_state.status();
+        assert_eq!(status.lag, Some(0));
+        assert!(status.is_synced);
+
+        let status = serde_json::to_value(status).unwrap();
+        assert_eq!(
+            status,
+            serde_json::json!({
+                "localMiniblock": SYNC_MINIBLOCK_DELTA + 10,
+                "mainNodeMiniblock": SYNC_MINIBLOCK_DELTA + 10,
+                "lag": 0,
+                "isSynced": true,
+            })
+        );
+    }
+
     #[test]
     fn test_sync_state_doesnt_panic_on_local_block() {
         let sync_state = SyncState::default();
@@ -1,9 +1,15 @@
 //! High-level sync layer tests.
 
-use std::{iter, sync::Arc, time::Duration};
+use std::{
+    iter,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
+use tempfile::TempDir;
 use test_casing::test_casing;
 use tokio::{sync::watch, task::JoinHandle};
+use vm_utils::storage::L1BatchParamsProvider;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_types::{
@@ -11,20 +17,25 @@ use zksync_types::{
     block::MiniblockHasher,
     fee_model::{BatchFeeInput, PubdataIndependentBatchFeeModelInput},
     snapshots::SnapshotRecoveryStatus,
-    Address, L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId, Transaction, H256,
+    Address, L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersion, ProtocolVersionId,
+    Transaction, H256,
 };
 
 use super::{fetcher::FetchedTransaction, sync_action::SyncAction, *};
 use crate::{
     consensus::testonly::MockMainNodeClient,
     genesis::{insert_genesis_batch, GenesisParams},
+    metadata_calculator::tests::{create_tree_reader, gen_storage_logs},
     state_keeper::{
-        io::{L1BatchParams, MiniblockParams},
+        io::{common::IoCursor, L1BatchParams, MiniblockParams, StateKeeperIO},
+        metrics::protocol_upgrade_tx_latency_stats,
         seal_criteria::NoopSealer,
-        tests::TestBatchExecutorBuilder,
+        tests::{random_upgrade_tx, TestBatchExecutorBuilder},
         OutputHandler, StateKeeperPersistence, ZkSyncStateKeeper,
     },
-    utils::testonly::{create_l1_batch_metadata, create_l2_transaction, prepare_recovery_snapshot},
+    utils::testonly::{
+        create_l1_batch, create_l1_batch_metadata, create_l2_transaction, prepare_recovery_snapshot,
+    },
 };
 
 const TEST_TIMEOUT: Duration = Duration::from_secs(10);
@@ -309,6 +320,264 @@ async fn external_io_works_without_local_protocol_version(snapshot_recovery: boo
     assert_eq!(miniblock.protocol_version, Some(ProtocolVersionId::next()));
 }
 
+#[tokio::test]
+async fn external_io_caches_base_system_contracts() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    ensure_genesis(&mut storage).await;
+    let cursor = IoCursor::new(&mut storage).await.unwrap();
+
+    let mut client = MockMainNodeClient::default();
+    client.insert_protocol_version(api::ProtocolVersion {
+        version_id: ProtocolVersionId::next() as u16,
+        base_system_contracts: BaseSystemContractsHashes {
+            bootloader: H256::repeat_byte(1),
+            default_aa: H256::repeat_byte(2),
+        },
+        ..api::ProtocolVersion::default()
+    });
+    let (_actions_sender, action_queue) = ActionQueue::new();
+    let mut io = ExternalIO::new(
+        pool.clone(),
+        action_queue,
+        Box::new(client),
+        L2ChainId::default(),
+    )
+    .await
+    .unwrap();
+
+    let version = ProtocolVersionId::latest();
+    let original_contracts = io
+        .load_base_system_contracts(version, &cursor)
+        .await
+        .unwrap();
+    // Tamper with the cached entry to check that it's used for subsequent batches with the same version.
+    let mut cached_contracts = original_contracts.clone();
+    cached_contracts.default_aa = cached_contracts.bootloader.clone();
+    io.base_system_contracts_cache
+        .put(version, cached_contracts.clone());
+    for _ in 0..3 {
+        let contracts = io
+            .load_base_system_contracts(version, &cursor)
+            .await
+            .unwrap();
+        assert_eq!(contracts.default_aa.hash, cached_contracts.default_aa.hash);
+    }
+
+    // A protocol upgrade should invalidate the cache.
+    let next_contracts = io
+        .load_base_system_contracts(ProtocolVersionId::next(), &cursor)
+        .await
+        .unwrap();
+    assert_eq!(next_contracts.bootloader.hash, H256::repeat_byte(1));
+    assert!(!io.base_system_contracts_cache.contains(&version));
+    assert!(io
+        .base_system_contracts_cache
+        .contains(&ProtocolVersionId::next()));
+    let contracts = io
+        .load_base_system_contracts(version, &cursor)
+        .await
+        .unwrap();
+    assert_eq!(
+        contracts.default_aa.hash,
+        original_contracts.default_aa.hash
+    );
+    // Loading contracts for an older version is not an upgrade, so it doesn't evict the latest version.
+    assert!(io.base_system_contracts_cache.contains(&version));
+    assert!(io
+        .base_system_contracts_cache
+        .contains(&ProtocolVersionId::next()));
+}
+
+#[tokio::test]
+async fn external_io_reports_upgrade_tx_latency() {
+    const BLOCK_AGE: Duration = Duration::from_secs(60);
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    ensure_genesis(&mut storage).await;
+    let cursor = IoCursor::new(&mut storage).await.unwrap();
+    drop(storage);
+
+    let (actions_sender, action_queue) = ActionQueue::new();
+    let mut io = ExternalIO::new(
+        pool,
+        action_queue,
+        Box::new(MockMainNodeClient::default()),
+        L2ChainId::default(),
+    )
+    .await
+    .unwrap();
+
+    let upgrade_tx = Transaction::from(random_upgrade_tx(1));
+    let upgrade_tx_hash = upgrade_tx.hash();
+    let upgrade_tx =
+        FetchedTransaction::new(upgrade_tx).with_block_received_at(Instant::now() - BLOCK_AGE);
+    let actions = vec![
+        open_l1_batch(1, 1, 1),
+        upgrade_tx.into(),
+        SyncAction::SealMiniblock,
+    ];
+    actions_sender.push_actions(actions).await;
+
+    io.wait_for_new_batch_params(&cursor, TEST_TIMEOUT)
+        .await
+        .unwrap()
+        .expect("no new L1 batch params");
+    // Other tests may observe the metric concurrently, so we only check that it has grown sufficiently.
+    let (count_before, sum_before) = protocol_upgrade_tx_latency_stats();
+    let tx = io
+        .wait_for_next_tx(TEST_TIMEOUT)
+        .await
+        .unwrap()
+        .expect("no upgrade transaction");
+    assert_eq!(tx.hash(), upgrade_tx_hash);
+
+    let (count_after, sum_after) = protocol_upgrade_tx_latency_stats();
+    assert!(count_after > count_before);
+    let latency = sum_after - sum_before;
+    assert!(latency >= BLOCK_AGE.as_secs_f64(), "{latency}");
+}
+
+#[tokio::test]
+async fn external_io_loads_state_hash_from_tree() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    ensure_genesis(&mut storage).await;
+    // L1 batch #1 is sealed, but its state hash is not persisted in Postgres.
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&create_l1_batch(1))
+        .await
+        .unwrap();
+
+    let temp_dir = TempDir::new().unwrap();
+    let (tree_reader, root_hashes) =
+        create_tree_reader(temp_dir.path(), gen_storage_logs(0..20, 2)).await;
+    let (_actions_sender, action_queue) = ActionQueue::new();
+    let mut io = ExternalIO::new(
+        pool.clone(),
+        action_queue,
+        Box::<MockMainNodeClient>::default(),
+        L2ChainId::default(),
+    )
+    .await
+    .unwrap()
+    .with_tree_reader(tree_reader);
+
+    let hash = tokio::time::timeout(TEST_TIMEOUT, io.load_batch_state_hash(L1BatchNumber(1)))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(hash, root_hashes[1]);
+
+    // If the hash is available in both sources, they must agree.
+    storage
+        .blocks_dal()
+        .set_l1_batch_hash(L1BatchNumber(1), H256::repeat_byte(0xff))
+        .await
+        .unwrap();
+    let err = io
+        .load_batch_state_hash(L1BatchNumber(1))
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("differs"), "{err}");
+
+    storage
+        .blocks_dal()
+        .set_l1_batch_hash(L1BatchNumber(1), root_hashes[1])
+        .await
+        .unwrap();
+    let hash = io.load_batch_state_hash(L1BatchNumber(1)).await.unwrap();
+    assert_eq!(hash, root_hashes[1]);
+}
+
+#[test_casing(2, [false, true])]
+#[tokio::test]
+async fn external_io_bulk_loads_batch_versions(snapshot_recovery: bool) {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    let first_l1_batch = if snapshot_recovery {
+        prepare_recovery_snapshot(&mut storage, L1BatchNumber(23), MiniblockNumber(42), &[])
+            .await
+            .l1_batch_number
+    } else {
+        ensure_genesis(&mut storage).await;
+        L1BatchNumber(0)
+    };
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion {
+            id: ProtocolVersionId::next(),
+            ..ProtocolVersion::default()
+        })
+        .await;
+    for (i, version) in [ProtocolVersionId::latest(), ProtocolVersionId::next()]
+        .into_iter()
+        .cycle()
+        .take(4)
+        .enumerate()
+    {
+        let mut header = create_l1_batch(first_l1_batch.0 + i as u32 + 1);
+        header.protocol_version = Some(version);
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+    }
+
+    let (_actions_sender, action_queue) = ActionQueue::new();
+    let mut io = ExternalIO::new(
+        pool.clone(),
+        action_queue,
+        Box::<MockMainNodeClient>::default(),
+        L2ChainId::default(),
+    )
+    .await
+    .unwrap();
+    let params_provider = L1BatchParamsProvider::new(&mut storage).await.unwrap();
+    let numbers = first_l1_batch..=first_l1_batch + 4;
+    let versions = io.load_batch_version_ids(numbers.clone()).await.unwrap();
+    assert_eq!(versions.len(), 5);
+    for (number, version) in (numbers.start().0..).map(L1BatchNumber).zip(versions) {
+        let individual_version = params_provider
+            .load_l1_batch_protocol_version(&mut storage, number)
+            .await
+            .unwrap();
+        assert_eq!(Some(version), individual_version, "L1 batch #{number}");
+        // Versions are prefetched in bulk on the first call and served from the prefetched data afterwards.
+        let prefetched_version = io.load_batch_version_id(number).await.unwrap();
+        assert_eq!(version, prefetched_version, "L1 batch #{number}");
+    }
+    let versions = io
+        .load_batch_version_ids(first_l1_batch + 2..=first_l1_batch + 3)
+        .await
+        .unwrap();
+    assert_eq!(
+        versions,
+        [ProtocolVersionId::next(), ProtocolVersionId::latest()]
+    );
+
+    let err = io
+        .load_batch_version_ids(first_l1_batch..=first_l1_batch + 5)
+        .await
+        .unwrap_err();
+    assert!(
+        format!("{err:#}").contains("misses protocol version"),
+        "{err:#}"
+    );
+    let err = io
+        .load_batch_version_id(first_l1_batch + 5)
+        .await
+        .unwrap_err();
+    assert!(
+        format!("{err:#}").contains("misses protocol version"),
+        "{err:#}"
+    );
+}
+
 pub(super) async fn run_state_keeper_with_multiple_miniblocks(
     pool: ConnectionPool<Core>,
     snapshot_recovery: bool,
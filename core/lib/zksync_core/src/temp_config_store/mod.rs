@@ -8,8 +8,8 @@ use zksync_config::{
         },
         fri_prover_group::FriProverGroupConfig,
         house_keeper::HouseKeeperConfig,
-        FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig, PrometheusConfig,
-        ProofDataHandlerConfig, WitnessGeneratorConfig,
+        AdminConfig, FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig,
+        PrometheusConfig, ProofDataHandlerConfig, WitnessGeneratorConfig,
     },
     ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
     GasAdjusterConfig, ObjectStoreConfig, PostgresConfig,
@@ -72,12 +72,31 @@ pub struct TempConfigStore {
     pub gas_adjuster_config: Option<GasAdjusterConfig>,
     pub object_store_config: Option<ObjectStoreConfig>,
     pub consensus_config: Option<consensus::Config>,
+    pub admin_config: Option<AdminConfig>,
+}
+
+impl TempConfigStore {
+    /// Checks that the configs are mutually consistent.
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.api_config.is_some() {
+            let has_database_url = self
+                .postgres_config
+                .as_ref()
+                .is_some_and(|config| config.master_url.is_some() || config.replica_url.is_some());
+            anyhow::ensure!(
+                has_database_url,
+                "`api` config is present, but `postgres` config doesn't specify a database URL \
+                 (`master_url` or `replica_url`); API servers cannot function without a database"
+            );
+        }
+        Ok(())
+    }
 }
 
 impl ProtoFmt for TempConfigStore {
     type Proto = proto::TempConfigStore;
     fn read(r: &Self::Proto) -> anyhow::Result<Self> {
-        Ok(Self {
+        let this = Self {
             postgres_config: read_optional_repr(&r.postgres).context("postgres")?,
             health_check_config: read_optional_repr(&r.health_check).context("health_check")?,
             merkle_tree_api_config: read_optional_repr(&r.merkle_tree_api)
@@ -112,7 +131,10 @@ impl ProtoFmt for TempConfigStore {
             gas_adjuster_config: read_optional_repr(&r.gas_adjuster).context("gas_adjuster")?,
             object_store_config: read_optional_repr(&r.object_store).context("object_store")?,
             consensus_config: read_optional(&r.consensus).context("consensus")?,
-        })
+            admin_config: read_optional_repr(&r.admin).context("admin")?,
+        };
+        this.validate()?;
+        Ok(this)
     }
 
     fn build(&self) -> Self::Proto {
@@ -155,6 +177,7 @@ impl ProtoFmt for TempConfigStore {
             gas_adjuster: self.gas_adjuster_config.as_ref().map(ProtoRepr::build),
             object_store: self.object_store_config.as_ref().map(ProtoRepr::build),
             consensus: self.consensus_config.as_ref().map(ProtoFmt::build),
+            admin: self.admin_config.as_ref().map(ProtoRepr::build),
         }
     }
 }
@@ -162,6 +185,8 @@ impl ProtoFmt for TempConfigStore {
 #[derive(Debug)]
 pub struct Secrets {
     pub consensus: Option<consensus::Secrets>,
+    /// Bearer token required to access the admin server.
+    pub admin_token: Option<String>,
 }
 
 impl ProtoFmt for Secrets {
@@ -169,12 +194,14 @@ impl ProtoFmt for Secrets {
     fn read(r: &Self::Proto) -> anyhow::Result<Self> {
         Ok(Self {
             consensus: read_optional(&r.consensus).context("consensus")?,
+            admin_token: r.admin_token.clone(),
         })
     }
 
     fn build(&self) -> Self::Proto {
         Self::Proto {
             consensus: self.consensus.as_ref().map(|x| x.build()),
+            admin_token: self.admin_token.clone(),
         }
     }
 }
@@ -6,8 +6,18 @@ use super::*;
 
 impl Distribution<TempConfigStore> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> TempConfigStore {
+        let api_config: Option<ApiConfig> = self.sample(rng);
+        let mut postgres_config: Option<PostgresConfig> = self.sample(rng);
+        if api_config.is_some() {
+            // The API config requires a database URL to be valid.
+            let postgres_config = postgres_config.get_or_insert_with(|| self.sample(rng));
+            postgres_config
+                .master_url
+                .get_or_insert_with(|| self.sample(rng));
+        }
+
         TempConfigStore {
-            postgres_config: self.sample(rng),
+            postgres_config,
             health_check_config: self.sample(rng),
             merkle_tree_api_config: self.sample(rng),
             web3_json_rpc_config: self.sample(rng),
@@ -24,7 +34,7 @@ impl Distribution<TempConfigStore> for EncodeDist {
             prometheus_config: self.sample(rng),
             proof_data_handler_config: self.sample(rng),
             witness_generator_config: self.sample(rng),
-            api_config: self.sample(rng),
+            api_config,
             contracts_config: self.sample(rng),
             db_config: self.sample(rng),
             eth_client_config: self.sample(rng),
@@ -33,6 +43,7 @@ impl Distribution<TempConfigStore> for EncodeDist {
             gas_adjuster_config: self.sample(rng),
             object_store_config: self.sample(rng),
             consensus_config: self.sample(rng),
+            admin_config: self.sample(rng),
         }
     }
 }
@@ -42,3 +53,28 @@ fn test_encoding() {
     let rng = &mut rand::thread_rng();
     test_encode_all_formats::<FmtConv<TempConfigStore>>(rng);
 }
+
+#[test]
+fn api_config_requires_database_url() {
+    let rng = &mut rand::thread_rng();
+    let dist = EncodeDist {
+        required_only: false,
+        decimal_fractions: false,
+    };
+    let mut config: TempConfigStore = dist.sample(rng);
+    config.api_config = Some(dist.sample(rng));
+    config.postgres_config = None;
+    let err = TempConfigStore::read(&config.build()).unwrap_err();
+    assert!(err.to_string().contains("database URL"), "{err}");
+
+    let mut postgres_config: PostgresConfig = dist.sample(rng);
+    postgres_config.master_url = None;
+    postgres_config.replica_url = None;
+    config.postgres_config = Some(postgres_config.clone());
+    let err = TempConfigStore::read(&config.build()).unwrap_err();
+    assert!(err.to_string().contains("database URL"), "{err}");
+
+    postgres_config.master_url = Some("postgres://postgres@localhost/zksync_local".to_owned());
+    config.postgres_config = Some(postgres_config);
+    TempConfigStore::read(&config.build()).unwrap();
+}
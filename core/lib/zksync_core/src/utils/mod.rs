@@ -15,12 +15,35 @@ use zksync_eth_client::{CallFunctionArgs, Error as EthClientError, EthInterface}
 use zksync_l1_contract_interface::Detokenize;
 use zksync_types::{
     ethabi::{self, Address},
-    L1BatchNumber, ProtocolVersionId,
+    L1BatchNumber, ProtocolVersionId, H256,
 };
 
 #[cfg(test)]
 pub(crate) mod testonly;
 
+/// Clamps the interval used by a polling loop (e.g., one fetching data from the main node) so that it's
+/// not less than `min_interval`. This guards the main node and Postgres against tight loops caused by misconfiguration.
+pub fn clamp_polling_interval(interval: Duration, min_interval: Duration) -> Duration {
+    if interval < min_interval {
+        tracing::debug!("Polling interval {interval:?} is clamped to the floor {min_interval:?}");
+        min_interval
+    } else {
+        interval
+    }
+}
+
+/// Checks whether a transaction with the specified hash should have its call trace saved given the sampling rate
+/// (a fraction of transactions from 0.0 to 1.0). Transactions are sampled deterministically based on their hash,
+/// so the same transactions are sampled on re-execution and on different nodes with the same sampling rate.
+pub(crate) fn is_sampled_for_call_traces(tx_hash: H256, sampling_rate: f64) -> bool {
+    if sampling_rate >= 1.0 {
+        return true;
+    }
+    // Transaction hashes are uniformly distributed, so their prefix can be used as a source of randomness.
+    let hash_prefix = u64::from_be_bytes(tx_hash.as_bytes()[..8].try_into().unwrap());
+    (hash_prefix as f64) < sampling_rate * (u64::MAX as f64)
+}
+
 /// Fallible and async predicate for binary search.
 #[async_trait]
 pub(crate) trait BinarySearchPredicate: Send {
@@ -222,13 +245,59 @@ mod tests {
         Block, ContractCall, ExecutedTxStatus, FailureInfo, RawTransactionBytes,
     };
     use zksync_types::{
-        web3::types::{BlockId, BlockNumber, Filter, Log, Transaction, TransactionReceipt},
+        web3::{
+            signing::keccak256,
+            types::{BlockId, BlockNumber, Filter, Log, Transaction, TransactionReceipt},
+        },
         H160, H256, U256, U64,
     };
 
     use super::*;
     use crate::genesis::{insert_genesis_batch, GenesisParams};
 
+    #[test]
+    fn clamping_polling_interval() {
+        let min_interval = Duration::from_millis(100);
+        assert_eq!(
+            clamp_polling_interval(Duration::ZERO, min_interval),
+            min_interval
+        );
+        assert_eq!(
+            clamp_polling_interval(Duration::from_millis(1), min_interval),
+            min_interval
+        );
+        assert_eq!(
+            clamp_polling_interval(Duration::from_secs(5), min_interval),
+            Duration::from_secs(5)
+        );
+    }
+
+    #[test]
+    fn sampling_transactions_for_call_traces() {
+        let tx_hashes: Vec<_> = (0_u64..1_000)
+            .map(|i| H256(keccak256(&i.to_be_bytes())))
+            .collect();
+        assert!(tx_hashes
+            .iter()
+            .all(|&hash| is_sampled_for_call_traces(hash, 1.0)));
+        assert!(!tx_hashes
+            .iter()
+            .any(|&hash| is_sampled_for_call_traces(hash, 0.0)));
+
+        let sampled_count = tx_hashes
+            .iter()
+            .filter(|&&hash| is_sampled_for_call_traces(hash, 0.1))
+            .count();
+        assert!((50..=150).contains(&sampled_count), "{sampled_count}");
+
+        // Increasing the sampling rate must not unsample transactions.
+        for &hash in &tx_hashes {
+            if is_sampled_for_call_traces(hash, 0.1) {
+                assert!(is_sampled_for_call_traces(hash, 0.5));
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_binary_search() {
         for divergence_point in [1, 50, 51, 100] {
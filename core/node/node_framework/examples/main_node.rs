@@ -23,6 +23,7 @@ use zksync_core::{
         web3::{state::InternalApiConfig, Namespace},
     },
     metadata_calculator::MetadataCalculatorConfig,
+    Component,
 };
 use zksync_env_config::FromEnv;
 use zksync_node_framework::{
@@ -56,14 +57,27 @@ use zksync_node_framework::{
     service::{ZkStackService, ZkStackServiceBuilder, ZkStackServiceError},
 };
 
+/// Web3 server layer that is added to the node once all components are known, so that
+/// it can report them via the API.
+struct PendingWeb3Server {
+    create_layer: fn(u16, InternalApiConfig, Web3ServerOptionalConfig) -> Web3ServerLayer,
+    port: u16,
+    internal_api_config: InternalApiConfig,
+    optional_config: Web3ServerOptionalConfig,
+}
+
 struct MainNodeBuilder {
     node: ZkStackServiceBuilder,
+    components: Vec<Component>,
+    web3_servers: Vec<PendingWeb3Server>,
 }
 
 impl MainNodeBuilder {
     fn new() -> Self {
         Self {
             node: ZkStackServiceBuilder::new(),
+            components: Vec::new(),
+            web3_servers: Vec::new(),
         }
     }
 
@@ -128,6 +142,7 @@ impl MainNodeBuilder {
         );
         self.node
             .add_layer(MetadataCalculatorLayer(metadata_calculator_config));
+        self.components.push(Component::Tree);
         Ok(self)
     }
 
@@ -145,6 +160,7 @@ impl MainNodeBuilder {
             .add_layer(mempool_io_layer)
             .add_layer(main_node_batch_executor_builder_layer)
             .add_layer(state_keeper_layer);
+        self.components.push(Component::StateKeeper);
         Ok(self)
     }
 
@@ -153,6 +169,7 @@ impl MainNodeBuilder {
             ETHWatchConfig::from_env()?,
             ContractsConfig::from_env()?,
         ));
+        self.components.push(Component::EthWatcher);
         Ok(self)
     }
 
@@ -160,6 +177,7 @@ impl MainNodeBuilder {
         self.node.add_layer(ProofDataHandlerLayer::new(
             ProofDataHandlerConfig::from_env()?,
         ));
+        self.components.push(Component::ProofDataHandler);
         Ok(self)
     }
 
@@ -181,16 +199,19 @@ impl MainNodeBuilder {
 
         // On main node we always use master pool sink.
         self.node.add_layer(TxSinkLayer::MasterPoolSink);
-        self.node.add_layer(TxSenderLayer::new(
-            TxSenderConfig::new(
-                &state_keeper_config,
-                &rpc_config,
-                network_config.zksync_network_id,
-            ),
-            postgres_storage_caches_config,
-            rpc_config.vm_concurrency_limit(),
-            ApiContracts::load_from_disk(), // TODO (BFT-138): Allow to dynamically reload API contracts
-        ));
+        self.node.add_layer(
+            TxSenderLayer::new(
+                TxSenderConfig::new(
+                    &state_keeper_config,
+                    &rpc_config,
+                    network_config.zksync_network_id,
+                ),
+                postgres_storage_caches_config,
+                rpc_config.vm_concurrency_limit(),
+                ApiContracts::load_from_disk(), // TODO (BFT-138): Allow to dynamically reload API contracts
+            )
+            .with_trace_call_concurrency_limit(rpc_config.trace_call_concurrency_limit),
+        );
         Ok(self)
     }
 
@@ -198,6 +219,7 @@ impl MainNodeBuilder {
         let rpc_config = ApiConfig::from_env()?.web3_json_rpc;
         self.node
             .add_layer(TreeApiClientLayer::http(rpc_config.tree_api_url));
+        self.components.push(Component::TreeApi);
         Ok(self)
     }
 
@@ -220,13 +242,20 @@ impl MainNodeBuilder {
             subscriptions_limit: Some(rpc_config.subscriptions_limit()),
             batch_request_size_limit: Some(rpc_config.max_batch_request_size()),
             response_body_size_limit: Some(rpc_config.max_response_body_size()),
+            request_body_size_limit: Some(rpc_config.max_request_body_size()),
             ..Default::default()
         };
-        self.node.add_layer(Web3ServerLayer::http(
-            rpc_config.http_port,
-            InternalApiConfig::new(&network_config, &rpc_config, &contracts_config),
+        self.components.push(Component::HttpApi);
+        self.web3_servers.push(PendingWeb3Server {
+            create_layer: Web3ServerLayer::http,
+            port: rpc_config.http_port,
+            internal_api_config: InternalApiConfig::new(
+                &network_config,
+                &rpc_config,
+                &contracts_config,
+            ),
             optional_config,
-        ));
+        });
 
         Ok(self)
     }
@@ -248,19 +277,27 @@ impl MainNodeBuilder {
         let optional_config = Web3ServerOptionalConfig {
             namespaces: Some(namespaces),
             filters_limit: Some(rpc_config.filters_limit()),
+            filters_limit_per_connection: rpc_config.filters_limit_per_connection(),
             subscriptions_limit: Some(rpc_config.subscriptions_limit()),
             batch_request_size_limit: Some(rpc_config.max_batch_request_size()),
             response_body_size_limit: Some(rpc_config.max_response_body_size()),
+            request_body_size_limit: Some(rpc_config.max_request_body_size()),
             websocket_requests_per_minute_limit: Some(
                 rpc_config.websocket_requests_per_minute_limit(),
             ),
             replication_lag_limit_sec: circuit_breaker_config.replication_lag_limit_sec,
         };
-        self.node.add_layer(Web3ServerLayer::ws(
-            rpc_config.ws_port,
-            InternalApiConfig::new(&network_config, &rpc_config, &contracts_config),
+        self.components.push(Component::WsApi);
+        self.web3_servers.push(PendingWeb3Server {
+            create_layer: Web3ServerLayer::ws,
+            port: rpc_config.ws_port,
+            internal_api_config: InternalApiConfig::new(
+                &network_config,
+                &rpc_config,
+                &contracts_config,
+            ),
             optional_config,
-        ));
+        });
 
         Ok(self)
     }
@@ -278,6 +315,8 @@ impl MainNodeBuilder {
             network_config,
             state_keeper_config.l1_batch_commit_data_generator_mode,
         ));
+        self.components
+            .extend([Component::EthTxAggregator, Component::EthTxManager]);
 
         Ok(self)
     }
@@ -296,12 +335,14 @@ impl MainNodeBuilder {
             fri_prover_group_config,
             fri_proof_compressor_config,
         ));
+        self.components.push(Component::Housekeeper);
 
         Ok(self)
     }
 
     fn add_commitment_generator_layer(mut self) -> anyhow::Result<Self> {
         self.node.add_layer(CommitmentGeneratorLayer);
+        self.components.push(Component::CommitmentGenerator);
 
         Ok(self)
     }
@@ -317,10 +358,19 @@ impl MainNodeBuilder {
     fn add_contract_verification_api_layer(mut self) -> anyhow::Result<Self> {
         let config = ApiConfig::from_env()?.contract_verification;
         self.node.add_layer(ContractVerificationApiLayer(config));
+        self.components.push(Component::ContractVerificationApi);
         Ok(self)
     }
 
     fn build(mut self) -> Result<ZkStackService, ZkStackServiceError> {
+        for server in std::mem::take(&mut self.web3_servers) {
+            let internal_api_config = server.internal_api_config.with_components(&self.components);
+            self.node.add_layer((server.create_layer)(
+                server.port,
+                internal_api_config,
+                server.optional_config,
+            ));
+        }
         self.node.build()
     }
 }
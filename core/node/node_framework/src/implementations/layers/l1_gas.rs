@@ -70,7 +70,7 @@ impl WiringLayer for SequencerL1GasLayer {
 
         let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
             gas_adjuster.clone(),
-            FeeModelConfig::from_state_keeper_config(&self.state_keeper_config),
+            FeeModelConfig::from_state_keeper_config(&self.state_keeper_config)?,
         ));
         context.insert_resource(FeeInputResource(batch_fee_input_provider))?;
 
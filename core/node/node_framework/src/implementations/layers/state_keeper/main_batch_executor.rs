@@ -1,7 +1,9 @@
 use std::sync::Arc;
 
 use zksync_config::{configs::chain::StateKeeperConfig, DBConfig};
-use zksync_core::state_keeper::{AsyncCatchupTask, AsyncRocksdbCache, MainBatchExecutor};
+use zksync_core::state_keeper::{
+    AsyncCatchupTask, AsyncRocksdbCache, MainBatchExecutor, StateKeeperRocksdbOptions,
+};
 
 use crate::{
     implementations::resources::{pools::MasterPoolResource, state_keeper::BatchExecutorResource},
@@ -35,10 +37,15 @@ impl WiringLayer for MainBatchExecutorLayer {
     async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
         let master_pool = context.get_resource::<MasterPoolResource>().await?;
 
+        let enum_index_migration_chunk_size = self
+            .state_keeper_config
+            .enum_index_migration_chunk_size()
+            .map_err(|err| WiringError::Configuration(err.to_string()))?;
         let (storage_factory, task) = AsyncRocksdbCache::new(
             master_pool.get_singleton().await?,
-            self.db_config.state_keeper_db_path,
-            self.state_keeper_config.enum_index_migration_chunk_size(),
+            self.db_config.state_keeper_db_path.clone(),
+            StateKeeperRocksdbOptions::from_db_config(&self.db_config),
+            enum_index_migration_chunk_size,
         );
         let builder = MainBatchExecutor::new(
             Arc::new(storage_factory),
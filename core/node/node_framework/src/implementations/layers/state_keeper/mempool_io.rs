@@ -75,7 +75,7 @@ impl WiringLayer for MempoolIOLayer {
         let master_pool = context.get_resource::<MasterPoolResource>().await?;
 
         // Create miniblock sealer task.
-        let (persistence, miniblock_sealer) = StateKeeperPersistence::new(
+        let (persistence, mut miniblock_sealer) = StateKeeperPersistence::new(
             master_pool
                 .get_singleton()
                 .await
@@ -83,6 +83,15 @@ impl WiringLayer for MempoolIOLayer {
             self.contracts_config.l2_erc20_bridge_addr,
             self.state_keeper_config.miniblock_seal_queue_capacity,
         );
+        if self.state_keeper_config.save_call_traces
+            && self.state_keeper_config.separate_call_traces_pool
+        {
+            let call_traces_pool = master_pool
+                .get_singleton()
+                .await
+                .context("Get master pool")?;
+            miniblock_sealer = miniblock_sealer.with_call_traces_pool(call_traces_pool);
+        }
         let output_handler = OutputHandler::new(Box::new(persistence));
         context.insert_resource(OutputHandlerResource(Unique::new(output_handler)))?;
         context.add_task(Box::new(MiniblockSealerTask(miniblock_sealer)));
@@ -22,9 +22,11 @@ use crate::{
 pub struct Web3ServerOptionalConfig {
     pub namespaces: Option<Vec<Namespace>>,
     pub filters_limit: Option<usize>,
+    pub filters_limit_per_connection: Option<usize>,
     pub subscriptions_limit: Option<usize>,
     pub batch_request_size_limit: Option<usize>,
     pub response_body_size_limit: Option<usize>,
+    pub request_body_size_limit: Option<usize>,
     pub websocket_requests_per_minute_limit: Option<NonZeroU32>,
     // used by circuit breaker.
     pub replication_lag_limit_sec: Option<u32>,
@@ -38,6 +40,9 @@ impl Web3ServerOptionalConfig {
         if let Some(filters_limit) = self.filters_limit {
             api_builder = api_builder.with_filter_limit(filters_limit);
         }
+        if let Some(limit) = self.filters_limit_per_connection {
+            api_builder = api_builder.with_filter_limit_per_connection(limit);
+        }
         if let Some(subscriptions_limit) = self.subscriptions_limit {
             api_builder = api_builder.with_subscriptions_limit(subscriptions_limit);
         }
@@ -47,6 +52,9 @@ impl Web3ServerOptionalConfig {
         if let Some(response_body_size_limit) = self.response_body_size_limit {
             api_builder = api_builder.with_response_body_size_limit(response_body_size_limit);
         }
+        if let Some(request_body_size_limit) = self.request_body_size_limit {
+            api_builder = api_builder.with_request_body_size_limit(request_body_size_limit);
+        }
         if let Some(websocket_requests_per_minute_limit) = self.websocket_requests_per_minute_limit
         {
             api_builder = api_builder
@@ -1,4 +1,4 @@
-use std::{fmt, sync::Arc};
+use std::{fmt, num::NonZeroUsize, sync::Arc};
 
 use zksync_core::api_server::{
     execution_sandbox::{VmConcurrencyBarrier, VmConcurrencyLimiter},
@@ -30,6 +30,7 @@ pub struct TxSenderLayer {
     tx_sender_config: TxSenderConfig,
     postgres_storage_caches_config: PostgresStorageCachesConfig,
     max_vm_concurrency: usize,
+    max_trace_call_concurrency: Option<NonZeroUsize>,
     api_contracts: ApiContracts,
 }
 
@@ -44,9 +45,15 @@ impl TxSenderLayer {
             tx_sender_config,
             postgres_storage_caches_config,
             max_vm_concurrency,
+            max_trace_call_concurrency: None,
             api_contracts,
         }
     }
+
+    pub fn with_trace_call_concurrency_limit(mut self, limit: Option<NonZeroUsize>) -> Self {
+        self.max_trace_call_concurrency = limit;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -85,8 +92,11 @@ impl WiringLayer for TxSenderLayer {
         }
 
         // Initialize `VmConcurrencyLimiter`.
-        let (vm_concurrency_limiter, vm_concurrency_barrier) =
+        let (mut vm_concurrency_limiter, vm_concurrency_barrier) =
             VmConcurrencyLimiter::new(self.max_vm_concurrency);
+        if let Some(limit) = self.max_trace_call_concurrency {
+            vm_concurrency_limiter = vm_concurrency_limiter.with_trace_concurrency_limit(limit);
+        }
         context.add_task(Box::new(VmConcurrencyBarrierTask {
             barrier: vm_concurrency_barrier,
         }));